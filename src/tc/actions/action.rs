@@ -11,7 +11,12 @@ use netlink_packet_utils::{
 };
 
 use super::{
-    TcActionMirror, TcActionMirrorOption, TcActionNat, TcActionNatOption,
+    TcActionGact, TcActionGactOption, TcActionMirror, TcActionMirrorOption,
+    TcActionNat, TcActionNatOption, TcActionPedit, TcActionPeditOption,
+    TcActionPolice, TcActionPoliceOption, TcActionSkbedit,
+    TcActionSkbeditOption, TcActionSkbmod, TcActionSkbmodOption,
+    TcActionTunnelKey, TcActionTunnelKeyOption, TcActionVlan,
+    TcActionVlanOption,
 };
 use crate::tc::TcStats2;
 
@@ -178,32 +183,75 @@ impl Nla for TcActionAttribute {
 #[derive(Debug, PartialEq, Eq, Clone)]
 #[non_exhaustive]
 pub enum TcActionOption {
+    Gact(TcActionGactOption),
     Mirror(TcActionMirrorOption),
     Nat(TcActionNatOption),
+    Pedit(TcActionPeditOption),
+    Police(TcActionPoliceOption),
+    Skbedit(TcActionSkbeditOption),
+    Skbmod(TcActionSkbmodOption),
+    TunnelKey(TcActionTunnelKeyOption),
+    Vlan(TcActionVlanOption),
     Other(DefaultNla),
 }
 
 impl Nla for TcActionOption {
     fn value_len(&self) -> usize {
         match self {
+            Self::Gact(nla) => nla.value_len(),
             Self::Mirror(nla) => nla.value_len(),
             Self::Nat(nla) => nla.value_len(),
+            Self::Pedit(nla) => nla.value_len(),
+            Self::Police(nla) => nla.value_len(),
+            Self::Skbedit(nla) => nla.value_len(),
+            Self::Skbmod(nla) => nla.value_len(),
+            Self::TunnelKey(nla) => nla.value_len(),
+            Self::Vlan(nla) => nla.value_len(),
             Self::Other(nla) => nla.value_len(),
         }
     }
 
     fn emit_value(&self, buffer: &mut [u8]) {
         match self {
+            Self::Gact(nla) => nla.emit_value(buffer),
             Self::Mirror(nla) => nla.emit_value(buffer),
             Self::Nat(nla) => nla.emit_value(buffer),
+            Self::Pedit(nla) => nla.emit_value(buffer),
+            Self::Police(nla) => nla.emit_value(buffer),
+            Self::Skbedit(nla) => nla.emit_value(buffer),
+            Self::Skbmod(nla) => nla.emit_value(buffer),
+            Self::TunnelKey(nla) => nla.emit_value(buffer),
+            Self::Vlan(nla) => nla.emit_value(buffer),
             Self::Other(nla) => nla.emit_value(buffer),
         }
     }
 
+    fn is_nested(&self) -> bool {
+        match self {
+            Self::Gact(nla) => nla.is_nested(),
+            Self::Mirror(nla) => nla.is_nested(),
+            Self::Nat(nla) => nla.is_nested(),
+            Self::Pedit(nla) => nla.is_nested(),
+            Self::Police(nla) => nla.is_nested(),
+            Self::Skbedit(nla) => nla.is_nested(),
+            Self::Skbmod(nla) => nla.is_nested(),
+            Self::TunnelKey(nla) => nla.is_nested(),
+            Self::Vlan(nla) => nla.is_nested(),
+            Self::Other(nla) => nla.is_nested(),
+        }
+    }
+
     fn kind(&self) -> u16 {
         match self {
+            Self::Gact(nla) => nla.kind(),
             Self::Mirror(nla) => nla.kind(),
             Self::Nat(nla) => nla.kind(),
+            Self::Pedit(nla) => nla.kind(),
+            Self::Police(nla) => nla.kind(),
+            Self::Skbedit(nla) => nla.kind(),
+            Self::Skbmod(nla) => nla.kind(),
+            Self::TunnelKey(nla) => nla.kind(),
+            Self::Vlan(nla) => nla.kind(),
             Self::Other(nla) => nla.kind(),
         }
     }
@@ -219,6 +267,10 @@ where
         kind: S,
     ) -> Result<Self, DecodeError> {
         Ok(match kind.as_ref() {
+            TcActionGact::KIND => Self::Gact(
+                TcActionGactOption::parse(buf)
+                    .context("failed to parse gact action")?,
+            ),
             TcActionMirror::KIND => Self::Mirror(
                 TcActionMirrorOption::parse(buf)
                     .context("failed to parse mirror action")?,
@@ -227,6 +279,30 @@ where
                 TcActionNatOption::parse(buf)
                     .context("failed to parse nat action")?,
             ),
+            TcActionPedit::KIND => Self::Pedit(
+                TcActionPeditOption::parse(buf)
+                    .context("failed to parse pedit action")?,
+            ),
+            TcActionPolice::KIND => Self::Police(
+                TcActionPoliceOption::parse(buf)
+                    .context("failed to parse police action")?,
+            ),
+            TcActionSkbedit::KIND => Self::Skbedit(
+                TcActionSkbeditOption::parse(buf)
+                    .context("failed to parse skbedit action")?,
+            ),
+            TcActionSkbmod::KIND => Self::Skbmod(
+                TcActionSkbmodOption::parse(buf)
+                    .context("failed to parse skbmod action")?,
+            ),
+            TcActionTunnelKey::KIND => Self::TunnelKey(
+                TcActionTunnelKeyOption::parse(buf)
+                    .context("failed to parse tunnel_key action")?,
+            ),
+            TcActionVlan::KIND => Self::Vlan(
+                TcActionVlanOption::parse(buf)
+                    .context("failed to parse vlan action")?,
+            ),
             _ => Self::Other(
                 DefaultNla::parse(buf)
                     .context("failed to parse action options")?,
@@ -295,6 +371,9 @@ const TC_ACT_QUEUED: i32 = 5;
 const TC_ACT_REPEAT: i32 = 6;
 const TC_ACT_REDIRECT: i32 = 7;
 const TC_ACT_TRAP: i32 = 8;
+const TC_ACT_EXT_VAL_MASK: i32 = (1 << 28) - 1;
+const TC_ACT_JUMP: i32 = 1 << 28;
+const TC_ACT_GOTO_CHAIN: i32 = 2 << 28;
 
 #[derive(Debug, PartialEq, Eq, Clone, Copy, Default)]
 #[non_exhaustive]
@@ -310,6 +389,10 @@ pub enum TcActionType {
     Repeat,
     Redirect,
     Trap,
+    /// Skip the specified number of following actions
+    Jump(u32),
+    /// Continue classification at the specified chain
+    GotoChain(u32),
     Other(i32),
 }
 
@@ -326,6 +409,12 @@ impl From<i32> for TcActionType {
             TC_ACT_REPEAT => Self::Repeat,
             TC_ACT_REDIRECT => Self::Redirect,
             TC_ACT_TRAP => Self::Trap,
+            _ if d & !TC_ACT_EXT_VAL_MASK == TC_ACT_JUMP => {
+                Self::Jump((d & TC_ACT_EXT_VAL_MASK) as u32)
+            }
+            _ if d & !TC_ACT_EXT_VAL_MASK == TC_ACT_GOTO_CHAIN => {
+                Self::GotoChain((d & TC_ACT_EXT_VAL_MASK) as u32)
+            }
             _ => Self::Other(d),
         }
     }
@@ -344,7 +433,60 @@ impl From<TcActionType> for i32 {
             TcActionType::Repeat => TC_ACT_REPEAT,
             TcActionType::Redirect => TC_ACT_REDIRECT,
             TcActionType::Trap => TC_ACT_TRAP,
+            TcActionType::Jump(d) => {
+                TC_ACT_JUMP | (d as i32 & TC_ACT_EXT_VAL_MASK)
+            }
+            TcActionType::GotoChain(d) => {
+                TC_ACT_GOTO_CHAIN | (d as i32 & TC_ACT_EXT_VAL_MASK)
+            }
             TcActionType::Other(d) => d,
         }
     }
 }
+
+const TCF_T_BUF_LEN: usize = 32;
+
+// kernel struct `tcf_t`
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Default)]
+#[non_exhaustive]
+pub struct Tcf {
+    /// Time since the action was installed, in jiffies
+    pub install: u64,
+    /// Time since the action was last used, in jiffies
+    pub lastuse: u64,
+    pub expires: u64,
+    /// Time since the action was first used, in jiffies
+    pub firstuse: u64,
+}
+
+buffer!(TcfBuffer(TCF_T_BUF_LEN) {
+    install: (u64, 0..8),
+    lastuse: (u64, 8..16),
+    expires: (u64, 16..24),
+    firstuse: (u64, 24..TCF_T_BUF_LEN),
+});
+
+impl Emitable for Tcf {
+    fn buffer_len(&self) -> usize {
+        TCF_T_BUF_LEN
+    }
+
+    fn emit(&self, buffer: &mut [u8]) {
+        let mut packet = TcfBuffer::new(buffer);
+        packet.set_install(self.install);
+        packet.set_lastuse(self.lastuse);
+        packet.set_expires(self.expires);
+        packet.set_firstuse(self.firstuse);
+    }
+}
+
+impl<T: AsRef<[u8]>> Parseable<TcfBuffer<T>> for Tcf {
+    fn parse(buf: &TcfBuffer<T>) -> Result<Self, DecodeError> {
+        Ok(Self {
+            install: buf.install(),
+            lastuse: buf.lastuse(),
+            expires: buf.expires(),
+            firstuse: buf.firstuse(),
+        })
+    }
+}
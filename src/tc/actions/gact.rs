@@ -0,0 +1,214 @@
+// SPDX-License-Identifier: MIT
+
+/// Generic action
+///
+/// The gact action applies a fixed verdict (pass, drop, reclassify,
+/// goto chain, etc.) to every packet, or optionally a different verdict
+/// chosen randomly or deterministically for a fraction of packets.
+use anyhow::Context;
+use netlink_packet_utils::{
+    nla::{DefaultNla, Nla, NlaBuffer},
+    traits::{Emitable, Parseable},
+    DecodeError,
+};
+
+use super::{
+    TcActionGeneric, TcActionGenericBuffer, TcActionType, Tcf, TcfBuffer,
+};
+
+#[derive(Debug, PartialEq, Eq, Clone)]
+#[non_exhaustive]
+pub struct TcActionGact {}
+impl TcActionGact {
+    pub const KIND: &'static str = "gact";
+}
+
+const TCA_GACT_TM: u16 = 1;
+const TCA_GACT_PARMS: u16 = 2;
+const TCA_GACT_PROB: u16 = 3;
+
+#[derive(Debug, PartialEq, Eq, Clone)]
+#[non_exhaustive]
+pub enum TcActionGactOption {
+    Tm(Tcf),
+    Parms(TcGact),
+    Prob(TcGactProb),
+    Other(DefaultNla),
+}
+
+impl Nla for TcActionGactOption {
+    fn value_len(&self) -> usize {
+        match self {
+            Self::Tm(v) => v.buffer_len(),
+            Self::Parms(v) => v.buffer_len(),
+            Self::Prob(v) => v.buffer_len(),
+            Self::Other(attr) => attr.value_len(),
+        }
+    }
+
+    fn emit_value(&self, buffer: &mut [u8]) {
+        match self {
+            Self::Tm(v) => v.emit(buffer),
+            Self::Parms(v) => v.emit(buffer),
+            Self::Prob(v) => v.emit(buffer),
+            Self::Other(attr) => attr.emit_value(buffer),
+        }
+    }
+
+    fn kind(&self) -> u16 {
+        match self {
+            Self::Tm(_) => TCA_GACT_TM,
+            Self::Parms(_) => TCA_GACT_PARMS,
+            Self::Prob(_) => TCA_GACT_PROB,
+            Self::Other(nla) => nla.kind(),
+        }
+    }
+}
+
+impl<'a, T: AsRef<[u8]> + ?Sized> Parseable<NlaBuffer<&'a T>>
+    for TcActionGactOption
+{
+    fn parse(buf: &NlaBuffer<&'a T>) -> Result<Self, DecodeError> {
+        let payload = buf.value();
+        Ok(match buf.kind() {
+            TCA_GACT_TM => Self::Tm(
+                Tcf::parse(
+                    &TcfBuffer::new_checked(payload)
+                        .context("invalid TCA_GACT_TM")?,
+                )
+                .context("failed to parse TCA_GACT_TM")?,
+            ),
+            TCA_GACT_PARMS => Self::Parms(
+                TcGact::parse(
+                    &TcGactBuffer::new_checked(payload)
+                        .context("invalid TCA_GACT_PARMS")?,
+                )
+                .context("failed to parse TCA_GACT_PARMS")?,
+            ),
+            TCA_GACT_PROB => Self::Prob(
+                TcGactProb::parse(
+                    &TcGactProbBuffer::new_checked(payload)
+                        .context("invalid TCA_GACT_PROB")?,
+                )
+                .context("failed to parse TCA_GACT_PROB")?,
+            ),
+            _ => Self::Other(
+                DefaultNla::parse(buf).context("failed to parse gact nla")?,
+            ),
+        })
+    }
+}
+
+const TC_GACT_BUF_LEN: usize = TcActionGeneric::BUF_LEN;
+
+// kernel struct `tc_gact`
+#[derive(Debug, PartialEq, Eq, Clone, Default)]
+#[non_exhaustive]
+pub struct TcGact {
+    pub generic: TcActionGeneric,
+}
+
+buffer!(TcGactBuffer(TC_GACT_BUF_LEN) {
+    generic: (slice, 0..TC_GACT_BUF_LEN),
+});
+
+impl Emitable for TcGact {
+    fn buffer_len(&self) -> usize {
+        TC_GACT_BUF_LEN
+    }
+
+    fn emit(&self, buffer: &mut [u8]) {
+        let mut packet = TcGactBuffer::new(buffer);
+        self.generic.emit(packet.generic_mut());
+    }
+}
+
+impl<T: AsRef<[u8]> + ?Sized> Parseable<TcGactBuffer<&T>> for TcGact {
+    fn parse(buf: &TcGactBuffer<&T>) -> Result<Self, DecodeError> {
+        Ok(Self {
+            generic: TcActionGeneric::parse(&TcActionGenericBuffer::new(
+                buf.generic(),
+            ))?,
+        })
+    }
+}
+
+const TC_GACT_P_BUF_LEN: usize = 8;
+
+// kernel struct `tc_gact_p`
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Default)]
+#[non_exhaustive]
+pub struct TcGactProb {
+    pub ptype: TcGactProbType,
+    /// For `Netrand`, the action applies to one packet in `pval` at
+    /// random. For `Determ`, it applies to every `pval`-th packet.
+    pub pval: u16,
+    pub paction: TcActionType,
+}
+
+buffer!(TcGactProbBuffer(TC_GACT_P_BUF_LEN) {
+    ptype: (u16, 0..2),
+    pval: (u16, 2..4),
+    paction: (i32, 4..TC_GACT_P_BUF_LEN),
+});
+
+impl Emitable for TcGactProb {
+    fn buffer_len(&self) -> usize {
+        TC_GACT_P_BUF_LEN
+    }
+
+    fn emit(&self, buffer: &mut [u8]) {
+        let mut packet = TcGactProbBuffer::new(buffer);
+        packet.set_ptype(self.ptype.into());
+        packet.set_pval(self.pval);
+        packet.set_paction(self.paction.into());
+    }
+}
+
+impl<T: AsRef<[u8]>> Parseable<TcGactProbBuffer<T>> for TcGactProb {
+    fn parse(buf: &TcGactProbBuffer<T>) -> Result<Self, DecodeError> {
+        Ok(Self {
+            ptype: buf.ptype().into(),
+            pval: buf.pval(),
+            paction: buf.paction().into(),
+        })
+    }
+}
+
+const PGACT_NONE: u16 = 0;
+const PGACT_NETRAND: u16 = 1;
+const PGACT_DETERM: u16 = 2;
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Default)]
+#[non_exhaustive]
+pub enum TcGactProbType {
+    #[default]
+    None,
+    /// Random
+    Netrand,
+    /// Deterministic
+    Determ,
+    Other(u16),
+}
+
+impl From<u16> for TcGactProbType {
+    fn from(d: u16) -> Self {
+        match d {
+            PGACT_NONE => Self::None,
+            PGACT_NETRAND => Self::Netrand,
+            PGACT_DETERM => Self::Determ,
+            _ => Self::Other(d),
+        }
+    }
+}
+
+impl From<TcGactProbType> for u16 {
+    fn from(v: TcGactProbType) -> u16 {
+        match v {
+            TcGactProbType::None => PGACT_NONE,
+            TcGactProbType::Netrand => PGACT_NETRAND,
+            TcGactProbType::Determ => PGACT_DETERM,
+            TcGactProbType::Other(d) => d,
+        }
+    }
+}
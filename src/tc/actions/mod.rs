@@ -1,13 +1,24 @@
 // SPDX-License-Identifier: MIT
 
 mod action;
+mod gact;
 mod mirror;
 mod nat;
 pub(crate) mod nat_flag;
+mod pedit;
+mod police;
+mod skbedit;
+mod skbmod;
+mod tunnel_key;
+mod vlan;
 
 pub use self::action::{
     TcAction, TcActionAttribute, TcActionGeneric, TcActionGenericBuffer,
-    TcActionOption, TcActionType,
+    TcActionOption, TcActionType, Tcf, TcfBuffer,
+};
+pub use self::gact::{
+    TcActionGact, TcActionGactOption, TcGact, TcGactBuffer, TcGactProb,
+    TcGactProbBuffer, TcGactProbType,
 };
 pub use self::mirror::{
     TcActionMirror, TcActionMirrorOption, TcMirror, TcMirrorActionType,
@@ -15,3 +26,25 @@ pub use self::mirror::{
 };
 pub use self::nat::{TcActionNat, TcActionNatOption, TcNat, TcNatBuffer};
 pub use self::nat_flag::TcNatFlag;
+pub use self::pedit::{
+    TcActionPedit, TcActionPeditOption, TcPedit, TcPeditBuffer, TcPeditCmd,
+    TcPeditEdit, TcPeditHeaderType, TcPeditKey, TcPeditKeyBuffer, TcPeditKeyEx,
+};
+pub use self::police::{
+    TcActionPolice, TcActionPoliceOption, TcPolice, TcPoliceBuffer,
+};
+pub use self::skbedit::{
+    TcActionSkbedit, TcActionSkbeditOption, TcSkbedit, TcSkbeditBuffer,
+    TcSkbeditFlag, TcSkbeditPtype,
+};
+pub use self::skbmod::{
+    TcActionSkbmod, TcActionSkbmodOption, TcSkbmod, TcSkbmodBuffer,
+    TcSkbmodFlag,
+};
+pub use self::tunnel_key::{
+    TcActionTunnelKey, TcActionTunnelKeyOption, TcTunnelKey, TcTunnelKeyAction,
+    TcTunnelKeyBuffer,
+};
+pub use self::vlan::{
+    TcActionVlan, TcActionVlanOption, TcVlan, TcVlanAction, TcVlanBuffer,
+};
@@ -0,0 +1,618 @@
+// SPDX-License-Identifier: MIT
+
+/// Pedit action
+///
+/// The pedit action edits arbitrary packet data, with each key
+/// rewriting (or adding to) a 32 bits word at an offset of the packet.
+/// In extended mode, offsets are relative to the header selected by
+/// `TcPeditKeyEx`.
+use std::net::{Ipv4Addr, Ipv6Addr};
+
+use anyhow::Context;
+use byteorder::{ByteOrder, NativeEndian};
+use netlink_packet_utils::{
+    nla::{DefaultNla, Nla, NlaBuffer, NlasIterator},
+    parsers::parse_u16,
+    traits::{Emitable, Parseable},
+    DecodeError,
+};
+
+use super::{
+    TcActionGeneric, TcActionGenericBuffer, TcActionOption, Tcf, TcfBuffer,
+};
+
+#[derive(Debug, PartialEq, Eq, Clone)]
+#[non_exhaustive]
+pub struct TcActionPedit {}
+impl TcActionPedit {
+    pub const KIND: &'static str = "pedit";
+
+    /// Generate `TCA_ACT_OPTIONS` of an extended pedit action applying
+    /// the `edits` in order, in the same layout as iproute2.
+    pub fn options(
+        generic: TcActionGeneric,
+        edits: &[TcPeditEdit],
+    ) -> Vec<TcActionOption> {
+        vec![
+            TcActionOption::Pedit(TcActionPeditOption::ParmsEx(TcPedit {
+                generic,
+                flags: 0,
+                keys: edits.iter().map(|e| e.key).collect(),
+            })),
+            TcActionOption::Pedit(TcActionPeditOption::KeysEx(
+                edits
+                    .iter()
+                    .map(|e| TcPeditKeyEx {
+                        htype: e.htype,
+                        cmd: e.cmd,
+                    })
+                    .collect(),
+            )),
+        ]
+    }
+}
+
+const TCA_PEDIT_TM: u16 = 1;
+const TCA_PEDIT_PARMS: u16 = 2;
+// const TCA_PEDIT_PAD: u16 = 3;
+const TCA_PEDIT_PARMS_EX: u16 = 4;
+const TCA_PEDIT_KEYS_EX: u16 = 5;
+const TCA_PEDIT_KEY_EX: u16 = 6;
+
+const TCA_PEDIT_KEY_EX_HTYPE: u16 = 1;
+const TCA_PEDIT_KEY_EX_CMD: u16 = 2;
+
+#[derive(Debug, PartialEq, Eq, Clone)]
+#[non_exhaustive]
+pub enum TcActionPeditOption {
+    Tm(Tcf),
+    Parms(TcPedit),
+    /// Same as `Parms`, used along with `KeysEx`
+    ParmsEx(TcPedit),
+    /// Header type and command of each key of `ParmsEx`
+    KeysEx(Vec<TcPeditKeyEx>),
+    Other(DefaultNla),
+}
+
+impl Nla for TcActionPeditOption {
+    fn value_len(&self) -> usize {
+        match self {
+            Self::Tm(v) => v.buffer_len(),
+            Self::Parms(v) | Self::ParmsEx(v) => v.buffer_len(),
+            Self::KeysEx(v) => v.as_slice().buffer_len(),
+            Self::Other(attr) => attr.value_len(),
+        }
+    }
+
+    fn emit_value(&self, buffer: &mut [u8]) {
+        match self {
+            Self::Tm(v) => v.emit(buffer),
+            Self::Parms(v) | Self::ParmsEx(v) => v.emit(buffer),
+            Self::KeysEx(v) => v.as_slice().emit(buffer),
+            Self::Other(attr) => attr.emit_value(buffer),
+        }
+    }
+
+    fn is_nested(&self) -> bool {
+        matches!(self, Self::KeysEx(_))
+    }
+
+    fn kind(&self) -> u16 {
+        match self {
+            Self::Tm(_) => TCA_PEDIT_TM,
+            Self::Parms(_) => TCA_PEDIT_PARMS,
+            Self::ParmsEx(_) => TCA_PEDIT_PARMS_EX,
+            Self::KeysEx(_) => TCA_PEDIT_KEYS_EX,
+            Self::Other(nla) => nla.kind(),
+        }
+    }
+}
+
+impl<'a, T: AsRef<[u8]> + ?Sized> Parseable<NlaBuffer<&'a T>>
+    for TcActionPeditOption
+{
+    fn parse(buf: &NlaBuffer<&'a T>) -> Result<Self, DecodeError> {
+        let payload = buf.value();
+        Ok(match buf.kind() {
+            TCA_PEDIT_TM => Self::Tm(
+                Tcf::parse(
+                    &TcfBuffer::new_checked(payload)
+                        .context("invalid TCA_PEDIT_TM")?,
+                )
+                .context("failed to parse TCA_PEDIT_TM")?,
+            ),
+            TCA_PEDIT_PARMS => Self::Parms(
+                TcPedit::parse(
+                    &TcPeditBuffer::new_checked(payload)
+                        .context("invalid TCA_PEDIT_PARMS")?,
+                )
+                .context("failed to parse TCA_PEDIT_PARMS")?,
+            ),
+            TCA_PEDIT_PARMS_EX => Self::ParmsEx(
+                TcPedit::parse(
+                    &TcPeditBuffer::new_checked(payload)
+                        .context("invalid TCA_PEDIT_PARMS_EX")?,
+                )
+                .context("failed to parse TCA_PEDIT_PARMS_EX")?,
+            ),
+            TCA_PEDIT_KEYS_EX => {
+                let mut keys = vec![];
+                for nla in NlasIterator::new(payload) {
+                    let nla = nla.context("invalid TCA_PEDIT_KEYS_EX")?;
+                    keys.push(
+                        TcPeditKeyEx::parse(&nla)
+                            .context("failed to parse TCA_PEDIT_KEYS_EX")?,
+                    );
+                }
+                Self::KeysEx(keys)
+            }
+            _ => Self::Other(
+                DefaultNla::parse(buf).context("failed to parse pedit nla")?,
+            ),
+        })
+    }
+}
+
+const TC_PEDIT_BUF_LEN: usize = TcActionGeneric::BUF_LEN + 4;
+const TC_PEDIT_KEY_BUF_LEN: usize = 24;
+
+// kernel struct `tc_pedit_sel`
+#[derive(Debug, PartialEq, Eq, Clone, Default)]
+#[non_exhaustive]
+pub struct TcPedit {
+    pub generic: TcActionGeneric,
+    pub flags: u8,
+    pub keys: Vec<TcPeditKey>,
+}
+
+buffer!(TcPeditBuffer(TC_PEDIT_BUF_LEN) {
+    generic: (slice, 0..TcActionGeneric::BUF_LEN),
+    nkeys: (u8, TcActionGeneric::BUF_LEN),
+    flags: (u8, TcActionGeneric::BUF_LEN + 1),
+    keys: (slice, TC_PEDIT_BUF_LEN..),
+});
+
+impl Emitable for TcPedit {
+    fn buffer_len(&self) -> usize {
+        TC_PEDIT_BUF_LEN + self.keys.len() * TC_PEDIT_KEY_BUF_LEN
+    }
+
+    fn emit(&self, buffer: &mut [u8]) {
+        let mut packet = TcPeditBuffer::new(buffer);
+        self.generic.emit(packet.generic_mut());
+        packet.set_nkeys(self.keys.len() as u8);
+        packet.set_flags(self.flags);
+        let key_buf = packet.keys_mut();
+        for (i, k) in self.keys.iter().enumerate() {
+            k.emit(
+                &mut key_buf[(i * TC_PEDIT_KEY_BUF_LEN)
+                    ..((i + 1) * TC_PEDIT_KEY_BUF_LEN)],
+            );
+        }
+    }
+}
+
+impl<T: AsRef<[u8]> + ?Sized> Parseable<TcPeditBuffer<&T>> for TcPedit {
+    fn parse(buf: &TcPeditBuffer<&T>) -> Result<Self, DecodeError> {
+        let nkeys = buf.nkeys() as usize;
+        let key_payload = buf.keys();
+        if key_payload.len() < nkeys * TC_PEDIT_KEY_BUF_LEN {
+            return Err(DecodeError::from(format!(
+                "Invalid pedit keys, expecting {nkeys} keys, but got {} bytes",
+                key_payload.len()
+            )));
+        }
+        let mut keys = Vec::with_capacity(nkeys);
+        for i in 0..nkeys {
+            keys.push(
+                TcPeditKey::parse(&TcPeditKeyBuffer::new(
+                    &key_payload[(i * TC_PEDIT_KEY_BUF_LEN)
+                        ..((i + 1) * TC_PEDIT_KEY_BUF_LEN)],
+                ))
+                .context("failed to parse pedit key")?,
+            );
+        }
+        Ok(Self {
+            generic: TcActionGeneric::parse(&TcActionGenericBuffer::new(
+                buf.generic(),
+            ))?,
+            flags: buf.flags(),
+            keys,
+        })
+    }
+}
+
+// kernel struct `tc_pedit_key`
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Default)]
+#[non_exhaustive]
+pub struct TcPeditKey {
+    /// Bits of the word to keep, in network byte order
+    pub mask: u32,
+    /// Value to set or add, in network byte order
+    pub val: u32,
+    /// Offset of the 32 bits word to edit
+    pub off: u32,
+    /// Offset of the byte used to calculate an additional offset
+    pub at: u32,
+    pub offmask: u32,
+    pub shift: u32,
+}
+
+buffer!(TcPeditKeyBuffer(TC_PEDIT_KEY_BUF_LEN) {
+    mask: (u32, 0..4),
+    val: (u32, 4..8),
+    off: (u32, 8..12),
+    at: (u32, 12..16),
+    offmask: (u32, 16..20),
+    shift: (u32, 20..TC_PEDIT_KEY_BUF_LEN),
+});
+
+impl Emitable for TcPeditKey {
+    fn buffer_len(&self) -> usize {
+        TC_PEDIT_KEY_BUF_LEN
+    }
+
+    fn emit(&self, buffer: &mut [u8]) {
+        let mut packet = TcPeditKeyBuffer::new(buffer);
+        packet.set_mask(self.mask);
+        packet.set_val(self.val);
+        packet.set_off(self.off);
+        packet.set_at(self.at);
+        packet.set_offmask(self.offmask);
+        packet.set_shift(self.shift);
+    }
+}
+
+impl<T: AsRef<[u8]>> Parseable<TcPeditKeyBuffer<T>> for TcPeditKey {
+    fn parse(buf: &TcPeditKeyBuffer<T>) -> Result<Self, DecodeError> {
+        Ok(Self {
+            mask: buf.mask(),
+            val: buf.val(),
+            off: buf.off(),
+            at: buf.at(),
+            offmask: buf.offmask(),
+            shift: buf.shift(),
+        })
+    }
+}
+
+/// Extended attributes of a pedit key, `TCA_PEDIT_KEY_EX`
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Default)]
+#[non_exhaustive]
+pub struct TcPeditKeyEx {
+    pub htype: TcPeditHeaderType,
+    pub cmd: TcPeditCmd,
+}
+
+impl TcPeditKeyEx {
+    fn attributes(&self) -> [TcPeditKeyExAttr; 2] {
+        [
+            TcPeditKeyExAttr::HeaderType(self.htype),
+            TcPeditKeyExAttr::Cmd(self.cmd),
+        ]
+    }
+}
+
+impl Nla for TcPeditKeyEx {
+    fn value_len(&self) -> usize {
+        self.attributes().as_slice().buffer_len()
+    }
+
+    fn emit_value(&self, buffer: &mut [u8]) {
+        self.attributes().as_slice().emit(buffer)
+    }
+
+    fn is_nested(&self) -> bool {
+        true
+    }
+
+    fn kind(&self) -> u16 {
+        TCA_PEDIT_KEY_EX
+    }
+}
+
+impl<'a, T: AsRef<[u8]> + ?Sized> Parseable<NlaBuffer<&'a T>> for TcPeditKeyEx {
+    fn parse(buf: &NlaBuffer<&'a T>) -> Result<Self, DecodeError> {
+        if buf.kind() != TCA_PEDIT_KEY_EX {
+            return Err(DecodeError::from(format!(
+                "Invalid TCA_PEDIT_KEYS_EX entry, expecting \
+                TCA_PEDIT_KEY_EX, but got {}",
+                buf.kind()
+            )));
+        }
+        let mut key_ex = Self::default();
+        for nla in NlasIterator::new(buf.value()) {
+            let nla = nla.context("invalid TCA_PEDIT_KEY_EX")?;
+            let payload = nla.value();
+            match nla.kind() {
+                TCA_PEDIT_KEY_EX_HTYPE => {
+                    key_ex.htype = parse_u16(payload)
+                        .context("failed to parse TCA_PEDIT_KEY_EX_HTYPE")?
+                        .into()
+                }
+                TCA_PEDIT_KEY_EX_CMD => {
+                    key_ex.cmd = parse_u16(payload)
+                        .context("failed to parse TCA_PEDIT_KEY_EX_CMD")?
+                        .into()
+                }
+                _ => (),
+            }
+        }
+        Ok(key_ex)
+    }
+}
+
+enum TcPeditKeyExAttr {
+    HeaderType(TcPeditHeaderType),
+    Cmd(TcPeditCmd),
+}
+
+impl Nla for TcPeditKeyExAttr {
+    fn value_len(&self) -> usize {
+        2
+    }
+
+    fn emit_value(&self, buffer: &mut [u8]) {
+        match self {
+            Self::HeaderType(v) => NativeEndian::write_u16(buffer, (*v).into()),
+            Self::Cmd(v) => NativeEndian::write_u16(buffer, (*v).into()),
+        }
+    }
+
+    fn kind(&self) -> u16 {
+        match self {
+            Self::HeaderType(_) => TCA_PEDIT_KEY_EX_HTYPE,
+            Self::Cmd(_) => TCA_PEDIT_KEY_EX_CMD,
+        }
+    }
+}
+
+const TCA_PEDIT_KEY_EX_HDR_TYPE_NETWORK: u16 = 0;
+const TCA_PEDIT_KEY_EX_HDR_TYPE_ETH: u16 = 1;
+const TCA_PEDIT_KEY_EX_HDR_TYPE_IP4: u16 = 2;
+const TCA_PEDIT_KEY_EX_HDR_TYPE_IP6: u16 = 3;
+const TCA_PEDIT_KEY_EX_HDR_TYPE_TCP: u16 = 4;
+const TCA_PEDIT_KEY_EX_HDR_TYPE_UDP: u16 = 5;
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Default)]
+#[non_exhaustive]
+pub enum TcPeditHeaderType {
+    /// Offset is relative to the network header, legacy behavior
+    #[default]
+    Network,
+    Eth,
+    Ip4,
+    Ip6,
+    Tcp,
+    Udp,
+    Other(u16),
+}
+
+impl From<u16> for TcPeditHeaderType {
+    fn from(d: u16) -> Self {
+        match d {
+            TCA_PEDIT_KEY_EX_HDR_TYPE_NETWORK => Self::Network,
+            TCA_PEDIT_KEY_EX_HDR_TYPE_ETH => Self::Eth,
+            TCA_PEDIT_KEY_EX_HDR_TYPE_IP4 => Self::Ip4,
+            TCA_PEDIT_KEY_EX_HDR_TYPE_IP6 => Self::Ip6,
+            TCA_PEDIT_KEY_EX_HDR_TYPE_TCP => Self::Tcp,
+            TCA_PEDIT_KEY_EX_HDR_TYPE_UDP => Self::Udp,
+            _ => Self::Other(d),
+        }
+    }
+}
+
+impl From<TcPeditHeaderType> for u16 {
+    fn from(v: TcPeditHeaderType) -> u16 {
+        match v {
+            TcPeditHeaderType::Network => TCA_PEDIT_KEY_EX_HDR_TYPE_NETWORK,
+            TcPeditHeaderType::Eth => TCA_PEDIT_KEY_EX_HDR_TYPE_ETH,
+            TcPeditHeaderType::Ip4 => TCA_PEDIT_KEY_EX_HDR_TYPE_IP4,
+            TcPeditHeaderType::Ip6 => TCA_PEDIT_KEY_EX_HDR_TYPE_IP6,
+            TcPeditHeaderType::Tcp => TCA_PEDIT_KEY_EX_HDR_TYPE_TCP,
+            TcPeditHeaderType::Udp => TCA_PEDIT_KEY_EX_HDR_TYPE_UDP,
+            TcPeditHeaderType::Other(d) => d,
+        }
+    }
+}
+
+const TCA_PEDIT_KEY_EX_CMD_SET: u16 = 0;
+const TCA_PEDIT_KEY_EX_CMD_ADD: u16 = 1;
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Default)]
+#[non_exhaustive]
+pub enum TcPeditCmd {
+    #[default]
+    Set,
+    Add,
+    Other(u16),
+}
+
+impl From<u16> for TcPeditCmd {
+    fn from(d: u16) -> Self {
+        match d {
+            TCA_PEDIT_KEY_EX_CMD_SET => Self::Set,
+            TCA_PEDIT_KEY_EX_CMD_ADD => Self::Add,
+            _ => Self::Other(d),
+        }
+    }
+}
+
+impl From<TcPeditCmd> for u16 {
+    fn from(v: TcPeditCmd) -> u16 {
+        match v {
+            TcPeditCmd::Set => TCA_PEDIT_KEY_EX_CMD_SET,
+            TcPeditCmd::Add => TCA_PEDIT_KEY_EX_CMD_ADD,
+            TcPeditCmd::Other(d) => d,
+        }
+    }
+}
+
+/// A pedit key along with its extended attributes, generated by the
+/// helpers below to match `tc action pedit ex munge` of iproute2.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Default)]
+#[non_exhaustive]
+pub struct TcPeditEdit {
+    pub key: TcPeditKey,
+    pub htype: TcPeditHeaderType,
+    pub cmd: TcPeditCmd,
+}
+
+const PEDIT_U16_MASKS: [u32; 3] = [0x0000ffff, 0xff0000ff, 0xffff0000];
+const PEDIT_U8_MASKS: [u32; 4] =
+    [0x00ffffff, 0xff00ffff, 0xffff00ff, 0xffffff00];
+
+impl TcPeditEdit {
+    /// Use `cmd` instead of setting the field, for example
+    /// `TcPeditEdit::ipv4_ttl(0xff).with_cmd(TcPeditCmd::Add)` decreases
+    /// the TTL by one.
+    pub fn with_cmd(mut self, cmd: TcPeditCmd) -> Self {
+        self.cmd = cmd;
+        self
+    }
+
+    /// `munge eth dst set <mac>`
+    pub fn eth_dst(mac: [u8; 6]) -> Vec<Self> {
+        Self::pack_mac(0, mac)
+    }
+
+    /// `munge eth src set <mac>`
+    pub fn eth_src(mac: [u8; 6]) -> Vec<Self> {
+        Self::pack_mac(6, mac)
+    }
+
+    /// `munge eth type set <ethertype>`
+    pub fn eth_type(ethertype: u16) -> Self {
+        Self::pack_u16(TcPeditHeaderType::Eth, 12, ethertype)
+    }
+
+    /// `munge ip tos set <tos>`
+    pub fn ipv4_tos(tos: u8) -> Self {
+        Self::pack_u8(TcPeditHeaderType::Ip4, 1, tos)
+    }
+
+    /// `munge ip ttl set <ttl>`
+    pub fn ipv4_ttl(ttl: u8) -> Self {
+        Self::pack_u8(TcPeditHeaderType::Ip4, 8, ttl)
+    }
+
+    /// `munge ip src set <addr>`
+    pub fn ipv4_src(addr: Ipv4Addr) -> Self {
+        Self::pack_u32(TcPeditHeaderType::Ip4, 12, addr.octets())
+    }
+
+    /// `munge ip dst set <addr>`
+    pub fn ipv4_dst(addr: Ipv4Addr) -> Self {
+        Self::pack_u32(TcPeditHeaderType::Ip4, 16, addr.octets())
+    }
+
+    /// `munge ip6 hoplimit set <hoplimit>`
+    pub fn ipv6_hoplimit(hoplimit: u8) -> Self {
+        Self::pack_u8(TcPeditHeaderType::Ip6, 7, hoplimit)
+    }
+
+    /// `munge ip6 src set <addr>`
+    pub fn ipv6_src(addr: Ipv6Addr) -> Vec<Self> {
+        Self::pack_ipv6(8, addr)
+    }
+
+    /// `munge ip6 dst set <addr>`
+    pub fn ipv6_dst(addr: Ipv6Addr) -> Vec<Self> {
+        Self::pack_ipv6(24, addr)
+    }
+
+    /// `munge tcp sport set <port>`
+    pub fn tcp_sport(port: u16) -> Self {
+        Self::pack_u16(TcPeditHeaderType::Tcp, 0, port)
+    }
+
+    /// `munge tcp dport set <port>`
+    pub fn tcp_dport(port: u16) -> Self {
+        Self::pack_u16(TcPeditHeaderType::Tcp, 2, port)
+    }
+
+    /// `munge udp sport set <port>`
+    pub fn udp_sport(port: u16) -> Self {
+        Self::pack_u16(TcPeditHeaderType::Udp, 0, port)
+    }
+
+    /// `munge udp dport set <port>`
+    pub fn udp_dport(port: u16) -> Self {
+        Self::pack_u16(TcPeditHeaderType::Udp, 2, port)
+    }
+
+    fn new(htype: TcPeditHeaderType, off: u32, mask: u32, val: u32) -> Self {
+        Self {
+            key: TcPeditKey {
+                mask: mask.to_be(),
+                val: val.to_be(),
+                off: off & !3,
+                ..Default::default()
+            },
+            htype,
+            cmd: TcPeditCmd::Set,
+        }
+    }
+
+    // Same as `pack_key32()` of iproute2, `off` should be 4 bytes aligned
+    fn pack_u32(htype: TcPeditHeaderType, off: u32, val: [u8; 4]) -> Self {
+        Self::new(htype, off, 0, u32::from_be_bytes(val))
+    }
+
+    // Same as `pack_key16()` of iproute2, `off` should not cross the
+    // 4 bytes boundary
+    fn pack_u16(htype: TcPeditHeaderType, off: u32, val: u16) -> Self {
+        let index = (off & 3) as usize;
+        let stride = 8 * (2 - index);
+        Self::new(htype, off, PEDIT_U16_MASKS[index], (val as u32) << stride)
+    }
+
+    // Same as `pack_key8()` of iproute2
+    fn pack_u8(htype: TcPeditHeaderType, off: u32, val: u8) -> Self {
+        let index = (off & 3) as usize;
+        let stride = 8 * (3 - index);
+        Self::new(htype, off, PEDIT_U8_MASKS[index], (val as u32) << stride)
+    }
+
+    // Same as `pack_mac()` of iproute2
+    fn pack_mac(off: u32, mac: [u8; 6]) -> Vec<Self> {
+        let htype = TcPeditHeaderType::Eth;
+        if off & 3 == 0 {
+            vec![
+                Self::pack_u32(htype, off, [mac[0], mac[1], mac[2], mac[3]]),
+                Self::pack_u16(
+                    htype,
+                    off + 4,
+                    u16::from_be_bytes([mac[4], mac[5]]),
+                ),
+            ]
+        } else {
+            vec![
+                Self::pack_u16(
+                    htype,
+                    off,
+                    u16::from_be_bytes([mac[0], mac[1]]),
+                ),
+                Self::pack_u32(
+                    htype,
+                    (off & !3) + 4,
+                    [mac[2], mac[3], mac[4], mac[5]],
+                ),
+            ]
+        }
+    }
+
+    fn pack_ipv6(off: u32, addr: Ipv6Addr) -> Vec<Self> {
+        addr.octets()
+            .chunks(4)
+            .enumerate()
+            .map(|(i, v)| {
+                Self::pack_u32(
+                    TcPeditHeaderType::Ip6,
+                    off + i as u32 * 4,
+                    [v[0], v[1], v[2], v[3]],
+                )
+            })
+            .collect()
+    }
+}
@@ -0,0 +1,240 @@
+// SPDX-License-Identifier: MIT
+
+/// Police action
+///
+/// The police action rate limits packets by a token bucket, applying
+/// the conform action to packets within the rate and the exceed action
+/// to others. Legacy classifiers embed it in `TCA_*_POLICE` attributes.
+use anyhow::Context;
+use byteorder::{ByteOrder, NativeEndian};
+use netlink_packet_utils::{
+    nla::{DefaultNla, Nla, NlaBuffer},
+    parsers::{parse_i32, parse_u32, parse_u64},
+    traits::{Emitable, Parseable},
+    DecodeError,
+};
+
+use super::{Tcf, TcfBuffer};
+use crate::tc::{
+    ratespec::{emit_rate_table, parse_rate_table},
+    TcActionType, TcRateSpec, TcRateSpecBuffer,
+};
+
+#[derive(Debug, PartialEq, Eq, Clone)]
+#[non_exhaustive]
+pub struct TcActionPolice {}
+impl TcActionPolice {
+    pub const KIND: &'static str = "police";
+}
+
+const TCA_POLICE_TBF: u16 = 1;
+const TCA_POLICE_RATE: u16 = 2;
+const TCA_POLICE_PEAKRATE: u16 = 3;
+const TCA_POLICE_AVRATE: u16 = 4;
+const TCA_POLICE_RESULT: u16 = 5;
+const TCA_POLICE_TM: u16 = 6;
+// const TCA_POLICE_PAD: u16 = 7;
+const TCA_POLICE_RATE64: u16 = 8;
+const TCA_POLICE_PEAKRATE64: u16 = 9;
+const TCA_POLICE_PKTRATE64: u16 = 10;
+const TCA_POLICE_PKTBURST64: u16 = 11;
+
+#[derive(Debug, PartialEq, Eq, Clone)]
+#[non_exhaustive]
+pub enum TcActionPoliceOption {
+    Tbf(TcPolice),
+    /// Rate table, could be generated by `TcPsched::calc_rtable()`
+    Rate(Vec<u32>),
+    /// Peak rate table, could be generated by `TcPsched::calc_rtable()`
+    PeakRate(Vec<u32>),
+    /// Average rate in bytes per second
+    AvRate(u32),
+    /// Action for packets conforming to the rate
+    Result(TcActionType),
+    Tm(Tcf),
+    /// Rate in bytes per second, used when it does not fit in
+    /// `TcRateSpec::rate`
+    Rate64(u64),
+    /// Peak rate in bytes per second, used when it does not fit in
+    /// `TcRateSpec::rate`
+    PeakRate64(u64),
+    /// Rate in packets per second
+    PktRate64(u64),
+    /// Bucket size for `PktRate64`
+    PktBurst64(u64),
+    Other(DefaultNla),
+}
+
+impl Nla for TcActionPoliceOption {
+    fn value_len(&self) -> usize {
+        match self {
+            Self::Tbf(v) => v.buffer_len(),
+            Self::Rate(v) | Self::PeakRate(v) => v.len() * 4,
+            Self::AvRate(_) | Self::Result(_) => 4,
+            Self::Tm(v) => v.buffer_len(),
+            Self::Rate64(_)
+            | Self::PeakRate64(_)
+            | Self::PktRate64(_)
+            | Self::PktBurst64(_) => 8,
+            Self::Other(attr) => attr.value_len(),
+        }
+    }
+
+    fn emit_value(&self, buffer: &mut [u8]) {
+        match self {
+            Self::Tbf(v) => v.emit(buffer),
+            Self::Rate(v) | Self::PeakRate(v) => emit_rate_table(v, buffer),
+            Self::AvRate(d) => NativeEndian::write_u32(buffer, *d),
+            Self::Result(v) => NativeEndian::write_i32(buffer, (*v).into()),
+            Self::Tm(v) => v.emit(buffer),
+            Self::Rate64(d)
+            | Self::PeakRate64(d)
+            | Self::PktRate64(d)
+            | Self::PktBurst64(d) => NativeEndian::write_u64(buffer, *d),
+            Self::Other(attr) => attr.emit_value(buffer),
+        }
+    }
+
+    fn kind(&self) -> u16 {
+        match self {
+            Self::Tbf(_) => TCA_POLICE_TBF,
+            Self::Rate(_) => TCA_POLICE_RATE,
+            Self::PeakRate(_) => TCA_POLICE_PEAKRATE,
+            Self::AvRate(_) => TCA_POLICE_AVRATE,
+            Self::Result(_) => TCA_POLICE_RESULT,
+            Self::Tm(_) => TCA_POLICE_TM,
+            Self::Rate64(_) => TCA_POLICE_RATE64,
+            Self::PeakRate64(_) => TCA_POLICE_PEAKRATE64,
+            Self::PktRate64(_) => TCA_POLICE_PKTRATE64,
+            Self::PktBurst64(_) => TCA_POLICE_PKTBURST64,
+            Self::Other(attr) => attr.kind(),
+        }
+    }
+}
+
+impl<'a, T: AsRef<[u8]> + ?Sized> Parseable<NlaBuffer<&'a T>>
+    for TcActionPoliceOption
+{
+    fn parse(buf: &NlaBuffer<&'a T>) -> Result<Self, DecodeError> {
+        let payload = buf.value();
+        Ok(match buf.kind() {
+            TCA_POLICE_TBF => Self::Tbf(
+                TcPolice::parse(
+                    &TcPoliceBuffer::new_checked(payload)
+                        .context("invalid TCA_POLICE_TBF")?,
+                )
+                .context("failed to parse TCA_POLICE_TBF")?,
+            ),
+            TCA_POLICE_RATE => Self::Rate(parse_rate_table(payload)),
+            TCA_POLICE_PEAKRATE => Self::PeakRate(parse_rate_table(payload)),
+            TCA_POLICE_AVRATE => Self::AvRate(
+                parse_u32(payload)
+                    .context("failed to parse TCA_POLICE_AVRATE")?,
+            ),
+            TCA_POLICE_RESULT => Self::Result(
+                parse_i32(payload)
+                    .context("failed to parse TCA_POLICE_RESULT")?
+                    .into(),
+            ),
+            TCA_POLICE_TM => Self::Tm(
+                Tcf::parse(
+                    &TcfBuffer::new_checked(payload)
+                        .context("invalid TCA_POLICE_TM")?,
+                )
+                .context("failed to parse TCA_POLICE_TM")?,
+            ),
+            TCA_POLICE_RATE64 => Self::Rate64(
+                parse_u64(payload)
+                    .context("failed to parse TCA_POLICE_RATE64")?,
+            ),
+            TCA_POLICE_PEAKRATE64 => Self::PeakRate64(
+                parse_u64(payload)
+                    .context("failed to parse TCA_POLICE_PEAKRATE64")?,
+            ),
+            TCA_POLICE_PKTRATE64 => Self::PktRate64(
+                parse_u64(payload)
+                    .context("failed to parse TCA_POLICE_PKTRATE64")?,
+            ),
+            TCA_POLICE_PKTBURST64 => Self::PktBurst64(
+                parse_u64(payload)
+                    .context("failed to parse TCA_POLICE_PKTBURST64")?,
+            ),
+            _ => Self::Other(
+                DefaultNla::parse(buf).context("failed to parse police nla")?,
+            ),
+        })
+    }
+}
+
+const TC_POLICE_BUF_LEN: usize = TcRateSpec::BUF_LEN * 2 + 32;
+
+// kernel struct `tc_police`
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Default)]
+#[non_exhaustive]
+pub struct TcPolice {
+    pub index: u32,
+    /// Action for packets exceeding the rate
+    pub action: TcActionType,
+    pub limit: u32,
+    /// Bucket size in scheduler ticks, could be calculated by
+    /// `TcPsched::calc_xmittime()`
+    pub burst: u32,
+    pub mtu: u32,
+    pub rate: TcRateSpec,
+    pub peakrate: TcRateSpec,
+    pub refcnt: i32,
+    pub bindcnt: i32,
+    pub capab: u32,
+}
+
+buffer!(TcPoliceBuffer(TC_POLICE_BUF_LEN) {
+    index: (u32, 0..4),
+    action: (i32, 4..8),
+    limit: (u32, 8..12),
+    burst: (u32, 12..16),
+    mtu: (u32, 16..20),
+    rate: (slice, 20..32),
+    peakrate: (slice, 32..44),
+    refcnt: (i32, 44..48),
+    bindcnt: (i32, 48..52),
+    capab: (u32, 52..TC_POLICE_BUF_LEN),
+});
+
+impl Emitable for TcPolice {
+    fn buffer_len(&self) -> usize {
+        TC_POLICE_BUF_LEN
+    }
+
+    fn emit(&self, buffer: &mut [u8]) {
+        let mut packet = TcPoliceBuffer::new(buffer);
+        packet.set_index(self.index);
+        packet.set_action(self.action.into());
+        packet.set_limit(self.limit);
+        packet.set_burst(self.burst);
+        packet.set_mtu(self.mtu);
+        self.rate.emit(packet.rate_mut());
+        self.peakrate.emit(packet.peakrate_mut());
+        packet.set_refcnt(self.refcnt);
+        packet.set_bindcnt(self.bindcnt);
+        packet.set_capab(self.capab);
+    }
+}
+
+impl<T: AsRef<[u8]> + ?Sized> Parseable<TcPoliceBuffer<&T>> for TcPolice {
+    fn parse(buf: &TcPoliceBuffer<&T>) -> Result<Self, DecodeError> {
+        Ok(Self {
+            index: buf.index(),
+            action: buf.action().into(),
+            limit: buf.limit(),
+            burst: buf.burst(),
+            mtu: buf.mtu(),
+            rate: TcRateSpec::parse(&TcRateSpecBuffer::new(buf.rate()))?,
+            peakrate: TcRateSpec::parse(&TcRateSpecBuffer::new(
+                buf.peakrate(),
+            ))?,
+            refcnt: buf.refcnt(),
+            bindcnt: buf.bindcnt(),
+            capab: buf.capab(),
+        })
+    }
+}
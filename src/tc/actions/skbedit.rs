@@ -0,0 +1,294 @@
+// SPDX-License-Identifier: MIT
+
+/// Skbedit action
+///
+/// The skbedit action modifies the packet metadata (priority, mark,
+/// packet type and transmit queue) instead of the packet data.
+use anyhow::Context;
+use byteorder::{ByteOrder, NativeEndian};
+use netlink_packet_utils::{
+    nla::{DefaultNla, Nla, NlaBuffer},
+    parsers::{parse_u16, parse_u32, parse_u64},
+    traits::{Emitable, Parseable},
+    DecodeError,
+};
+
+use super::{TcActionGeneric, TcActionGenericBuffer, Tcf, TcfBuffer};
+use crate::tc::TcHandle;
+
+#[derive(Debug, PartialEq, Eq, Clone)]
+#[non_exhaustive]
+pub struct TcActionSkbedit {}
+impl TcActionSkbedit {
+    pub const KIND: &'static str = "skbedit";
+}
+
+const TCA_SKBEDIT_TM: u16 = 1;
+const TCA_SKBEDIT_PARMS: u16 = 2;
+const TCA_SKBEDIT_PRIORITY: u16 = 3;
+const TCA_SKBEDIT_QUEUE_MAPPING: u16 = 4;
+const TCA_SKBEDIT_MARK: u16 = 5;
+// const TCA_SKBEDIT_PAD: u16 = 6;
+const TCA_SKBEDIT_PTYPE: u16 = 7;
+const TCA_SKBEDIT_MASK: u16 = 8;
+const TCA_SKBEDIT_FLAGS: u16 = 9;
+const TCA_SKBEDIT_QUEUE_MAPPING_MAX: u16 = 10;
+
+#[derive(Debug, PartialEq, Eq, Clone)]
+#[non_exhaustive]
+pub enum TcActionSkbeditOption {
+    Tm(Tcf),
+    Parms(TcSkbedit),
+    Priority(TcHandle),
+    /// Transmit queue, or the lower bound of the queue range when
+    /// `QueueMappingMax` is set
+    QueueMapping(u16),
+    Mark(u32),
+    Ptype(TcSkbeditPtype),
+    /// Mask applied to `Mark`
+    Mask(u32),
+    Flags(Vec<TcSkbeditFlag>),
+    QueueMappingMax(u16),
+    Other(DefaultNla),
+}
+
+impl Nla for TcActionSkbeditOption {
+    fn value_len(&self) -> usize {
+        match self {
+            Self::Tm(v) => v.buffer_len(),
+            Self::Parms(v) => v.buffer_len(),
+            Self::QueueMapping(_)
+            | Self::Ptype(_)
+            | Self::QueueMappingMax(_) => 2,
+            Self::Priority(_) | Self::Mark(_) | Self::Mask(_) => 4,
+            Self::Flags(_) => 8,
+            Self::Other(attr) => attr.value_len(),
+        }
+    }
+
+    fn emit_value(&self, buffer: &mut [u8]) {
+        match self {
+            Self::Tm(v) => v.emit(buffer),
+            Self::Parms(v) => v.emit(buffer),
+            Self::Priority(v) => NativeEndian::write_u32(buffer, (*v).into()),
+            Self::QueueMapping(d) | Self::QueueMappingMax(d) => {
+                NativeEndian::write_u16(buffer, *d)
+            }
+            Self::Mark(d) | Self::Mask(d) => {
+                NativeEndian::write_u32(buffer, *d)
+            }
+            Self::Ptype(v) => NativeEndian::write_u16(buffer, (*v).into()),
+            Self::Flags(v) => NativeEndian::write_u64(
+                buffer,
+                u64::from(&VecTcSkbeditFlag(v.to_vec())),
+            ),
+            Self::Other(attr) => attr.emit_value(buffer),
+        }
+    }
+
+    fn kind(&self) -> u16 {
+        match self {
+            Self::Tm(_) => TCA_SKBEDIT_TM,
+            Self::Parms(_) => TCA_SKBEDIT_PARMS,
+            Self::Priority(_) => TCA_SKBEDIT_PRIORITY,
+            Self::QueueMapping(_) => TCA_SKBEDIT_QUEUE_MAPPING,
+            Self::Mark(_) => TCA_SKBEDIT_MARK,
+            Self::Ptype(_) => TCA_SKBEDIT_PTYPE,
+            Self::Mask(_) => TCA_SKBEDIT_MASK,
+            Self::Flags(_) => TCA_SKBEDIT_FLAGS,
+            Self::QueueMappingMax(_) => TCA_SKBEDIT_QUEUE_MAPPING_MAX,
+            Self::Other(nla) => nla.kind(),
+        }
+    }
+}
+
+impl<'a, T: AsRef<[u8]> + ?Sized> Parseable<NlaBuffer<&'a T>>
+    for TcActionSkbeditOption
+{
+    fn parse(buf: &NlaBuffer<&'a T>) -> Result<Self, DecodeError> {
+        let payload = buf.value();
+        Ok(match buf.kind() {
+            TCA_SKBEDIT_TM => Self::Tm(
+                Tcf::parse(
+                    &TcfBuffer::new_checked(payload)
+                        .context("invalid TCA_SKBEDIT_TM")?,
+                )
+                .context("failed to parse TCA_SKBEDIT_TM")?,
+            ),
+            TCA_SKBEDIT_PARMS => Self::Parms(
+                TcSkbedit::parse(
+                    &TcSkbeditBuffer::new_checked(payload)
+                        .context("invalid TCA_SKBEDIT_PARMS")?,
+                )
+                .context("failed to parse TCA_SKBEDIT_PARMS")?,
+            ),
+            TCA_SKBEDIT_PRIORITY => Self::Priority(
+                parse_u32(payload)
+                    .context("failed to parse TCA_SKBEDIT_PRIORITY")?
+                    .into(),
+            ),
+            TCA_SKBEDIT_QUEUE_MAPPING => Self::QueueMapping(
+                parse_u16(payload)
+                    .context("failed to parse TCA_SKBEDIT_QUEUE_MAPPING")?,
+            ),
+            TCA_SKBEDIT_MARK => Self::Mark(
+                parse_u32(payload)
+                    .context("failed to parse TCA_SKBEDIT_MARK")?,
+            ),
+            TCA_SKBEDIT_PTYPE => Self::Ptype(
+                parse_u16(payload)
+                    .context("failed to parse TCA_SKBEDIT_PTYPE")?
+                    .into(),
+            ),
+            TCA_SKBEDIT_MASK => Self::Mask(
+                parse_u32(payload)
+                    .context("failed to parse TCA_SKBEDIT_MASK")?,
+            ),
+            TCA_SKBEDIT_FLAGS => Self::Flags(
+                VecTcSkbeditFlag::from(
+                    parse_u64(payload)
+                        .context("failed to parse TCA_SKBEDIT_FLAGS")?,
+                )
+                .0,
+            ),
+            TCA_SKBEDIT_QUEUE_MAPPING_MAX => Self::QueueMappingMax(
+                parse_u16(payload)
+                    .context("failed to parse TCA_SKBEDIT_QUEUE_MAPPING_MAX")?,
+            ),
+            _ => Self::Other(
+                DefaultNla::parse(buf)
+                    .context("failed to parse skbedit nla")?,
+            ),
+        })
+    }
+}
+
+const TC_SKBEDIT_BUF_LEN: usize = TcActionGeneric::BUF_LEN;
+
+// kernel struct `tc_skbedit`
+#[derive(Debug, PartialEq, Eq, Clone, Default)]
+#[non_exhaustive]
+pub struct TcSkbedit {
+    pub generic: TcActionGeneric,
+}
+
+buffer!(TcSkbeditBuffer(TC_SKBEDIT_BUF_LEN) {
+    generic: (slice, 0..TC_SKBEDIT_BUF_LEN),
+});
+
+impl Emitable for TcSkbedit {
+    fn buffer_len(&self) -> usize {
+        TC_SKBEDIT_BUF_LEN
+    }
+
+    fn emit(&self, buffer: &mut [u8]) {
+        let mut packet = TcSkbeditBuffer::new(buffer);
+        self.generic.emit(packet.generic_mut());
+    }
+}
+
+impl<T: AsRef<[u8]> + ?Sized> Parseable<TcSkbeditBuffer<&T>> for TcSkbedit {
+    fn parse(buf: &TcSkbeditBuffer<&T>) -> Result<Self, DecodeError> {
+        Ok(Self {
+            generic: TcActionGeneric::parse(&TcActionGenericBuffer::new(
+                buf.generic(),
+            ))?,
+        })
+    }
+}
+
+const PACKET_HOST: u16 = 0;
+const PACKET_BROADCAST: u16 = 1;
+const PACKET_MULTICAST: u16 = 2;
+const PACKET_OTHERHOST: u16 = 3;
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Default)]
+#[non_exhaustive]
+pub enum TcSkbeditPtype {
+    #[default]
+    Host,
+    Broadcast,
+    Multicast,
+    OtherHost,
+    Other(u16),
+}
+
+impl From<u16> for TcSkbeditPtype {
+    fn from(d: u16) -> Self {
+        match d {
+            PACKET_HOST => Self::Host,
+            PACKET_BROADCAST => Self::Broadcast,
+            PACKET_MULTICAST => Self::Multicast,
+            PACKET_OTHERHOST => Self::OtherHost,
+            _ => Self::Other(d),
+        }
+    }
+}
+
+impl From<TcSkbeditPtype> for u16 {
+    fn from(v: TcSkbeditPtype) -> u16 {
+        match v {
+            TcSkbeditPtype::Host => PACKET_HOST,
+            TcSkbeditPtype::Broadcast => PACKET_BROADCAST,
+            TcSkbeditPtype::Multicast => PACKET_MULTICAST,
+            TcSkbeditPtype::OtherHost => PACKET_OTHERHOST,
+            TcSkbeditPtype::Other(d) => d,
+        }
+    }
+}
+
+const SKBEDIT_F_INHERITDSFIELD: u64 = 0x20;
+const SKBEDIT_F_TXQ_SKBHASH: u64 = 0x40;
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+#[non_exhaustive]
+pub enum TcSkbeditFlag {
+    /// Set the priority from the DS field of IP header
+    InheritDsfield,
+    /// Pick the transmit queue from the queue range by packet hash
+    TxqSkbhash,
+    Other(u64),
+}
+
+impl From<TcSkbeditFlag> for u64 {
+    fn from(v: TcSkbeditFlag) -> u64 {
+        match v {
+            TcSkbeditFlag::InheritDsfield => SKBEDIT_F_INHERITDSFIELD,
+            TcSkbeditFlag::TxqSkbhash => SKBEDIT_F_TXQ_SKBHASH,
+            TcSkbeditFlag::Other(i) => i,
+        }
+    }
+}
+
+const ALL_SKBEDIT_FLAGS: [TcSkbeditFlag; 2] =
+    [TcSkbeditFlag::InheritDsfield, TcSkbeditFlag::TxqSkbhash];
+
+#[derive(Clone, Eq, PartialEq, Debug)]
+struct VecTcSkbeditFlag(Vec<TcSkbeditFlag>);
+
+impl From<u64> for VecTcSkbeditFlag {
+    fn from(d: u64) -> Self {
+        let mut got: u64 = 0;
+        let mut ret = Vec::new();
+        for flag in ALL_SKBEDIT_FLAGS {
+            if (d & (u64::from(flag))) > 0 {
+                ret.push(flag);
+                got += u64::from(flag);
+            }
+        }
+        if got != d {
+            ret.push(TcSkbeditFlag::Other(d - got));
+        }
+        Self(ret)
+    }
+}
+
+impl From<&VecTcSkbeditFlag> for u64 {
+    fn from(v: &VecTcSkbeditFlag) -> u64 {
+        let mut d: u64 = 0;
+        for flag in &v.0 {
+            d += u64::from(*flag);
+        }
+        d
+    }
+}
@@ -0,0 +1,222 @@
+// SPDX-License-Identifier: MIT
+
+/// Skbmod action
+///
+/// The skbmod action rewrites the ethernet header: destination and
+/// source MAC, ether type, swapping the MAC addresses or marking ECN.
+use anyhow::Context;
+use byteorder::{ByteOrder, NativeEndian};
+use netlink_packet_utils::{
+    nla::{DefaultNla, Nla, NlaBuffer},
+    parsers::{parse_mac, parse_u16},
+    traits::{Emitable, Parseable},
+    DecodeError,
+};
+
+use super::{TcActionGeneric, TcActionGenericBuffer, Tcf, TcfBuffer};
+
+#[derive(Debug, PartialEq, Eq, Clone)]
+#[non_exhaustive]
+pub struct TcActionSkbmod {}
+impl TcActionSkbmod {
+    pub const KIND: &'static str = "skbmod";
+}
+
+const TCA_SKBMOD_TM: u16 = 1;
+const TCA_SKBMOD_PARMS: u16 = 2;
+const TCA_SKBMOD_DMAC: u16 = 3;
+const TCA_SKBMOD_SMAC: u16 = 4;
+const TCA_SKBMOD_ETYPE: u16 = 5;
+// const TCA_SKBMOD_PAD: u16 = 6;
+
+#[derive(Debug, PartialEq, Eq, Clone)]
+#[non_exhaustive]
+pub enum TcActionSkbmodOption {
+    Tm(Tcf),
+    Parms(TcSkbmod),
+    Dmac([u8; 6]),
+    Smac([u8; 6]),
+    Etype(u16),
+    Other(DefaultNla),
+}
+
+impl Nla for TcActionSkbmodOption {
+    fn value_len(&self) -> usize {
+        match self {
+            Self::Tm(v) => v.buffer_len(),
+            Self::Parms(v) => v.buffer_len(),
+            Self::Dmac(_) | Self::Smac(_) => 6,
+            Self::Etype(_) => 2,
+            Self::Other(attr) => attr.value_len(),
+        }
+    }
+
+    fn emit_value(&self, buffer: &mut [u8]) {
+        match self {
+            Self::Tm(v) => v.emit(buffer),
+            Self::Parms(v) => v.emit(buffer),
+            Self::Dmac(v) | Self::Smac(v) => buffer.copy_from_slice(v),
+            Self::Etype(d) => NativeEndian::write_u16(buffer, *d),
+            Self::Other(attr) => attr.emit_value(buffer),
+        }
+    }
+
+    fn kind(&self) -> u16 {
+        match self {
+            Self::Tm(_) => TCA_SKBMOD_TM,
+            Self::Parms(_) => TCA_SKBMOD_PARMS,
+            Self::Dmac(_) => TCA_SKBMOD_DMAC,
+            Self::Smac(_) => TCA_SKBMOD_SMAC,
+            Self::Etype(_) => TCA_SKBMOD_ETYPE,
+            Self::Other(nla) => nla.kind(),
+        }
+    }
+}
+
+impl<'a, T: AsRef<[u8]> + ?Sized> Parseable<NlaBuffer<&'a T>>
+    for TcActionSkbmodOption
+{
+    fn parse(buf: &NlaBuffer<&'a T>) -> Result<Self, DecodeError> {
+        let payload = buf.value();
+        Ok(match buf.kind() {
+            TCA_SKBMOD_TM => Self::Tm(
+                Tcf::parse(
+                    &TcfBuffer::new_checked(payload)
+                        .context("invalid TCA_SKBMOD_TM")?,
+                )
+                .context("failed to parse TCA_SKBMOD_TM")?,
+            ),
+            TCA_SKBMOD_PARMS => Self::Parms(
+                TcSkbmod::parse(
+                    &TcSkbmodBuffer::new_checked(payload)
+                        .context("invalid TCA_SKBMOD_PARMS")?,
+                )
+                .context("failed to parse TCA_SKBMOD_PARMS")?,
+            ),
+            TCA_SKBMOD_DMAC => Self::Dmac(
+                parse_mac(payload)
+                    .context("failed to parse TCA_SKBMOD_DMAC")?,
+            ),
+            TCA_SKBMOD_SMAC => Self::Smac(
+                parse_mac(payload)
+                    .context("failed to parse TCA_SKBMOD_SMAC")?,
+            ),
+            TCA_SKBMOD_ETYPE => Self::Etype(
+                parse_u16(payload)
+                    .context("failed to parse TCA_SKBMOD_ETYPE")?,
+            ),
+            _ => Self::Other(
+                DefaultNla::parse(buf).context("failed to parse skbmod nla")?,
+            ),
+        })
+    }
+}
+
+const TC_SKBMOD_BUF_LEN: usize = TcActionGeneric::BUF_LEN + 12;
+
+// kernel struct `tc_skbmod`
+#[derive(Debug, PartialEq, Eq, Clone, Default)]
+#[non_exhaustive]
+pub struct TcSkbmod {
+    pub generic: TcActionGeneric,
+    pub flags: Vec<TcSkbmodFlag>,
+}
+
+// 4 bytes of padding after `generic` to align the 64 bits `flags`
+buffer!(TcSkbmodBuffer(TC_SKBMOD_BUF_LEN) {
+    generic: (slice, 0..TcActionGeneric::BUF_LEN),
+    flags: (u64, 24..TC_SKBMOD_BUF_LEN),
+});
+
+impl Emitable for TcSkbmod {
+    fn buffer_len(&self) -> usize {
+        TC_SKBMOD_BUF_LEN
+    }
+
+    fn emit(&self, buffer: &mut [u8]) {
+        let mut packet = TcSkbmodBuffer::new(buffer);
+        self.generic.emit(packet.generic_mut());
+        packet.set_flags(u64::from(&VecTcSkbmodFlag(self.flags.to_vec())));
+    }
+}
+
+impl<T: AsRef<[u8]> + ?Sized> Parseable<TcSkbmodBuffer<&T>> for TcSkbmod {
+    fn parse(buf: &TcSkbmodBuffer<&T>) -> Result<Self, DecodeError> {
+        Ok(Self {
+            generic: TcActionGeneric::parse(&TcActionGenericBuffer::new(
+                buf.generic(),
+            ))?,
+            flags: VecTcSkbmodFlag::from(buf.flags()).0,
+        })
+    }
+}
+
+const SKBMOD_F_DMAC: u64 = 0x1;
+const SKBMOD_F_SMAC: u64 = 0x2;
+const SKBMOD_F_ETYPE: u64 = 0x4;
+const SKBMOD_F_SWAPMAC: u64 = 0x8;
+const SKBMOD_F_ECN: u64 = 0x10;
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+#[non_exhaustive]
+pub enum TcSkbmodFlag {
+    Dmac,
+    Smac,
+    Etype,
+    /// Swap the destination and source MAC
+    SwapMac,
+    /// Mark ECN CE instead of dropping
+    Ecn,
+    Other(u64),
+}
+
+impl From<TcSkbmodFlag> for u64 {
+    fn from(v: TcSkbmodFlag) -> u64 {
+        match v {
+            TcSkbmodFlag::Dmac => SKBMOD_F_DMAC,
+            TcSkbmodFlag::Smac => SKBMOD_F_SMAC,
+            TcSkbmodFlag::Etype => SKBMOD_F_ETYPE,
+            TcSkbmodFlag::SwapMac => SKBMOD_F_SWAPMAC,
+            TcSkbmodFlag::Ecn => SKBMOD_F_ECN,
+            TcSkbmodFlag::Other(i) => i,
+        }
+    }
+}
+
+const ALL_SKBMOD_FLAGS: [TcSkbmodFlag; 5] = [
+    TcSkbmodFlag::Dmac,
+    TcSkbmodFlag::Smac,
+    TcSkbmodFlag::Etype,
+    TcSkbmodFlag::SwapMac,
+    TcSkbmodFlag::Ecn,
+];
+
+#[derive(Clone, Eq, PartialEq, Debug)]
+struct VecTcSkbmodFlag(Vec<TcSkbmodFlag>);
+
+impl From<u64> for VecTcSkbmodFlag {
+    fn from(d: u64) -> Self {
+        let mut got: u64 = 0;
+        let mut ret = Vec::new();
+        for flag in ALL_SKBMOD_FLAGS {
+            if (d & (u64::from(flag))) > 0 {
+                ret.push(flag);
+                got += u64::from(flag);
+            }
+        }
+        if got != d {
+            ret.push(TcSkbmodFlag::Other(d - got));
+        }
+        Self(ret)
+    }
+}
+
+impl From<&VecTcSkbmodFlag> for u64 {
+    fn from(v: &VecTcSkbmodFlag) -> u64 {
+        let mut d: u64 = 0;
+        for flag in &v.0 {
+            d += u64::from(*flag);
+        }
+        d
+    }
+}
@@ -0,0 +1,280 @@
+// SPDX-License-Identifier: MIT
+
+/// Tunnel key action
+///
+/// The tunnel_key action sets the tunnel metadata used by a later
+/// encapsulation on a collect metadata tunnel device, or releases the
+/// tunnel metadata of decapsulated packets.
+use std::net::{Ipv4Addr, Ipv6Addr};
+
+use anyhow::Context;
+use byteorder::{BigEndian, ByteOrder};
+use netlink_packet_utils::{
+    nla::{DefaultNla, Nla, NlaBuffer, NlasIterator},
+    parsers::{parse_u16_be, parse_u32_be, parse_u8},
+    traits::{Emitable, Parseable},
+    DecodeError,
+};
+
+use super::{TcActionGeneric, TcActionGenericBuffer, Tcf, TcfBuffer};
+use crate::{
+    ip::{parse_ipv4_addr, parse_ipv6_addr},
+    tc::TcFlowerEncOpts,
+};
+
+#[derive(Debug, PartialEq, Eq, Clone)]
+#[non_exhaustive]
+pub struct TcActionTunnelKey {}
+impl TcActionTunnelKey {
+    pub const KIND: &'static str = "tunnel_key";
+}
+
+const TCA_TUNNEL_KEY_TM: u16 = 1;
+const TCA_TUNNEL_KEY_PARMS: u16 = 2;
+const TCA_TUNNEL_KEY_ENC_IPV4_SRC: u16 = 3;
+const TCA_TUNNEL_KEY_ENC_IPV4_DST: u16 = 4;
+const TCA_TUNNEL_KEY_ENC_IPV6_SRC: u16 = 5;
+const TCA_TUNNEL_KEY_ENC_IPV6_DST: u16 = 6;
+const TCA_TUNNEL_KEY_ENC_KEY_ID: u16 = 7;
+// const TCA_TUNNEL_KEY_PAD: u16 = 8;
+const TCA_TUNNEL_KEY_ENC_DST_PORT: u16 = 9;
+const TCA_TUNNEL_KEY_NO_CSUM: u16 = 10;
+const TCA_TUNNEL_KEY_ENC_OPTS: u16 = 11;
+const TCA_TUNNEL_KEY_ENC_TOS: u16 = 12;
+const TCA_TUNNEL_KEY_ENC_TTL: u16 = 13;
+const TCA_TUNNEL_KEY_NO_FRAG: u16 = 14;
+
+#[derive(Debug, PartialEq, Eq, Clone)]
+#[non_exhaustive]
+pub enum TcActionTunnelKeyOption {
+    Tm(Tcf),
+    Parms(TcTunnelKey),
+    EncIpv4Src(Ipv4Addr),
+    EncIpv4Dst(Ipv4Addr),
+    EncIpv6Src(Ipv6Addr),
+    EncIpv6Dst(Ipv6Addr),
+    /// Tunnel ID, e.g. VNI of vxlan and geneve
+    EncKeyId(u32),
+    EncDstPort(u16),
+    /// Do not calculate UDP checksum of the outer header
+    NoCsum(bool),
+    /// Shares the layout of flower `KeyEncOpts`, but `TcFlowerEncOpts::Gtp`
+    /// is not supported by the tunnel_key action
+    EncOpts(Vec<TcFlowerEncOpts>),
+    EncTos(u8),
+    EncTtl(u8),
+    /// Do not fragment the encapsulated packet
+    NoFrag,
+    Other(DefaultNla),
+}
+
+impl Nla for TcActionTunnelKeyOption {
+    fn value_len(&self) -> usize {
+        match self {
+            Self::Tm(v) => v.buffer_len(),
+            Self::Parms(v) => v.buffer_len(),
+            Self::EncIpv4Src(_) | Self::EncIpv4Dst(_) | Self::EncKeyId(_) => 4,
+            Self::EncIpv6Src(_) | Self::EncIpv6Dst(_) => 16,
+            Self::EncDstPort(_) => 2,
+            Self::NoCsum(_) | Self::EncTos(_) | Self::EncTtl(_) => 1,
+            Self::EncOpts(v) => v.as_slice().buffer_len(),
+            Self::NoFrag => 0,
+            Self::Other(attr) => attr.value_len(),
+        }
+    }
+
+    fn emit_value(&self, buffer: &mut [u8]) {
+        match self {
+            Self::Tm(v) => v.emit(buffer),
+            Self::Parms(v) => v.emit(buffer),
+            Self::EncIpv4Src(v) | Self::EncIpv4Dst(v) => {
+                buffer.copy_from_slice(&v.octets())
+            }
+            Self::EncIpv6Src(v) | Self::EncIpv6Dst(v) => {
+                buffer.copy_from_slice(&v.octets())
+            }
+            Self::EncKeyId(d) => BigEndian::write_u32(buffer, *d),
+            Self::EncDstPort(d) => BigEndian::write_u16(buffer, *d),
+            Self::NoCsum(v) => buffer[0] = (*v).into(),
+            Self::EncOpts(v) => v.as_slice().emit(buffer),
+            Self::EncTos(d) | Self::EncTtl(d) => buffer[0] = *d,
+            Self::NoFrag => (),
+            Self::Other(attr) => attr.emit_value(buffer),
+        }
+    }
+
+    fn is_nested(&self) -> bool {
+        match self {
+            // iproute2 flags the options as nested unless they are geneve
+            Self::EncOpts(v) => v.iter().any(|opt| opt.is_nested()),
+            _ => false,
+        }
+    }
+
+    fn kind(&self) -> u16 {
+        match self {
+            Self::Tm(_) => TCA_TUNNEL_KEY_TM,
+            Self::Parms(_) => TCA_TUNNEL_KEY_PARMS,
+            Self::EncIpv4Src(_) => TCA_TUNNEL_KEY_ENC_IPV4_SRC,
+            Self::EncIpv4Dst(_) => TCA_TUNNEL_KEY_ENC_IPV4_DST,
+            Self::EncIpv6Src(_) => TCA_TUNNEL_KEY_ENC_IPV6_SRC,
+            Self::EncIpv6Dst(_) => TCA_TUNNEL_KEY_ENC_IPV6_DST,
+            Self::EncKeyId(_) => TCA_TUNNEL_KEY_ENC_KEY_ID,
+            Self::EncDstPort(_) => TCA_TUNNEL_KEY_ENC_DST_PORT,
+            Self::NoCsum(_) => TCA_TUNNEL_KEY_NO_CSUM,
+            Self::EncOpts(_) => TCA_TUNNEL_KEY_ENC_OPTS,
+            Self::EncTos(_) => TCA_TUNNEL_KEY_ENC_TOS,
+            Self::EncTtl(_) => TCA_TUNNEL_KEY_ENC_TTL,
+            Self::NoFrag => TCA_TUNNEL_KEY_NO_FRAG,
+            Self::Other(nla) => nla.kind(),
+        }
+    }
+}
+
+impl<'a, T: AsRef<[u8]> + ?Sized> Parseable<NlaBuffer<&'a T>>
+    for TcActionTunnelKeyOption
+{
+    fn parse(buf: &NlaBuffer<&'a T>) -> Result<Self, DecodeError> {
+        let payload = buf.value();
+        Ok(match buf.kind() {
+            TCA_TUNNEL_KEY_TM => Self::Tm(
+                Tcf::parse(
+                    &TcfBuffer::new_checked(payload)
+                        .context("invalid TCA_TUNNEL_KEY_TM")?,
+                )
+                .context("failed to parse TCA_TUNNEL_KEY_TM")?,
+            ),
+            TCA_TUNNEL_KEY_PARMS => Self::Parms(
+                TcTunnelKey::parse(
+                    &TcTunnelKeyBuffer::new_checked(payload)
+                        .context("invalid TCA_TUNNEL_KEY_PARMS")?,
+                )
+                .context("failed to parse TCA_TUNNEL_KEY_PARMS")?,
+            ),
+            TCA_TUNNEL_KEY_ENC_IPV4_SRC => Self::EncIpv4Src(
+                parse_ipv4_addr(payload)
+                    .context("failed to parse TCA_TUNNEL_KEY_ENC_IPV4_SRC")?,
+            ),
+            TCA_TUNNEL_KEY_ENC_IPV4_DST => Self::EncIpv4Dst(
+                parse_ipv4_addr(payload)
+                    .context("failed to parse TCA_TUNNEL_KEY_ENC_IPV4_DST")?,
+            ),
+            TCA_TUNNEL_KEY_ENC_IPV6_SRC => Self::EncIpv6Src(
+                parse_ipv6_addr(payload)
+                    .context("failed to parse TCA_TUNNEL_KEY_ENC_IPV6_SRC")?,
+            ),
+            TCA_TUNNEL_KEY_ENC_IPV6_DST => Self::EncIpv6Dst(
+                parse_ipv6_addr(payload)
+                    .context("failed to parse TCA_TUNNEL_KEY_ENC_IPV6_DST")?,
+            ),
+            TCA_TUNNEL_KEY_ENC_KEY_ID => Self::EncKeyId(
+                parse_u32_be(payload)
+                    .context("failed to parse TCA_TUNNEL_KEY_ENC_KEY_ID")?,
+            ),
+            TCA_TUNNEL_KEY_ENC_DST_PORT => Self::EncDstPort(
+                parse_u16_be(payload)
+                    .context("failed to parse TCA_TUNNEL_KEY_ENC_DST_PORT")?,
+            ),
+            TCA_TUNNEL_KEY_NO_CSUM => Self::NoCsum(
+                parse_u8(payload)
+                    .context("failed to parse TCA_TUNNEL_KEY_NO_CSUM")?
+                    > 0,
+            ),
+            TCA_TUNNEL_KEY_ENC_OPTS => {
+                let mut opts = vec![];
+                for opt in NlasIterator::new(payload) {
+                    let opt = opt.context("invalid TCA_TUNNEL_KEY_ENC_OPTS")?;
+                    opts.push(
+                        TcFlowerEncOpts::parse(&opt).context(
+                            "failed to parse TCA_TUNNEL_KEY_ENC_OPTS",
+                        )?,
+                    );
+                }
+                Self::EncOpts(opts)
+            }
+            TCA_TUNNEL_KEY_ENC_TOS => Self::EncTos(
+                parse_u8(payload)
+                    .context("failed to parse TCA_TUNNEL_KEY_ENC_TOS")?,
+            ),
+            TCA_TUNNEL_KEY_ENC_TTL => Self::EncTtl(
+                parse_u8(payload)
+                    .context("failed to parse TCA_TUNNEL_KEY_ENC_TTL")?,
+            ),
+            TCA_TUNNEL_KEY_NO_FRAG => Self::NoFrag,
+            _ => Self::Other(
+                DefaultNla::parse(buf)
+                    .context("failed to parse tunnel_key nla")?,
+            ),
+        })
+    }
+}
+
+const TC_TUNNEL_KEY_BUF_LEN: usize = TcActionGeneric::BUF_LEN + 4;
+
+// kernel struct `tc_tunnel_key`
+#[derive(Debug, PartialEq, Eq, Clone, Default)]
+#[non_exhaustive]
+pub struct TcTunnelKey {
+    pub generic: TcActionGeneric,
+    pub t_action: TcTunnelKeyAction,
+}
+
+buffer!(TcTunnelKeyBuffer(TC_TUNNEL_KEY_BUF_LEN) {
+    generic: (slice, 0..TcActionGeneric::BUF_LEN),
+    t_action: (i32, TcActionGeneric::BUF_LEN..TC_TUNNEL_KEY_BUF_LEN),
+});
+
+impl Emitable for TcTunnelKey {
+    fn buffer_len(&self) -> usize {
+        TC_TUNNEL_KEY_BUF_LEN
+    }
+
+    fn emit(&self, buffer: &mut [u8]) {
+        let mut packet = TcTunnelKeyBuffer::new(buffer);
+        self.generic.emit(packet.generic_mut());
+        packet.set_t_action(self.t_action.into());
+    }
+}
+
+impl<T: AsRef<[u8]> + ?Sized> Parseable<TcTunnelKeyBuffer<&T>> for TcTunnelKey {
+    fn parse(buf: &TcTunnelKeyBuffer<&T>) -> Result<Self, DecodeError> {
+        Ok(Self {
+            generic: TcActionGeneric::parse(&TcActionGenericBuffer::new(
+                buf.generic(),
+            ))?,
+            t_action: buf.t_action().into(),
+        })
+    }
+}
+
+const TCA_TUNNEL_KEY_ACT_SET: i32 = 1;
+const TCA_TUNNEL_KEY_ACT_RELEASE: i32 = 2;
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Default)]
+#[non_exhaustive]
+pub enum TcTunnelKeyAction {
+    #[default]
+    Set,
+    Release,
+    Other(i32),
+}
+
+impl From<i32> for TcTunnelKeyAction {
+    fn from(d: i32) -> Self {
+        match d {
+            TCA_TUNNEL_KEY_ACT_SET => Self::Set,
+            TCA_TUNNEL_KEY_ACT_RELEASE => Self::Release,
+            _ => Self::Other(d),
+        }
+    }
+}
+
+impl From<TcTunnelKeyAction> for i32 {
+    fn from(v: TcTunnelKeyAction) -> i32 {
+        match v {
+            TcTunnelKeyAction::Set => TCA_TUNNEL_KEY_ACT_SET,
+            TcTunnelKeyAction::Release => TCA_TUNNEL_KEY_ACT_RELEASE,
+            TcTunnelKeyAction::Other(d) => d,
+        }
+    }
+}
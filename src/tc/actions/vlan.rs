@@ -0,0 +1,217 @@
+// SPDX-License-Identifier: MIT
+
+/// Vlan action
+///
+/// The vlan action pushes, pops or modifies the VLAN tag of the packet,
+/// or pushes/pops a whole ethernet header.
+use anyhow::Context;
+use byteorder::{BigEndian, ByteOrder, NativeEndian};
+use netlink_packet_utils::{
+    nla::{DefaultNla, Nla, NlaBuffer},
+    parsers::{parse_mac, parse_u16, parse_u16_be, parse_u8},
+    traits::{Emitable, Parseable},
+    DecodeError,
+};
+
+use super::{TcActionGeneric, TcActionGenericBuffer, Tcf, TcfBuffer};
+
+#[derive(Debug, PartialEq, Eq, Clone)]
+#[non_exhaustive]
+pub struct TcActionVlan {}
+impl TcActionVlan {
+    pub const KIND: &'static str = "vlan";
+}
+
+const TCA_VLAN_TM: u16 = 1;
+const TCA_VLAN_PARMS: u16 = 2;
+const TCA_VLAN_PUSH_VLAN_ID: u16 = 3;
+const TCA_VLAN_PUSH_VLAN_PROTOCOL: u16 = 4;
+// const TCA_VLAN_PAD: u16 = 5;
+const TCA_VLAN_PUSH_VLAN_PRIORITY: u16 = 6;
+const TCA_VLAN_PUSH_ETH_DST: u16 = 7;
+const TCA_VLAN_PUSH_ETH_SRC: u16 = 8;
+
+#[derive(Debug, PartialEq, Eq, Clone)]
+#[non_exhaustive]
+pub enum TcActionVlanOption {
+    Tm(Tcf),
+    Parms(TcVlan),
+    PushVlanId(u16),
+    /// Ether type of the pushed tag, e.g. `ETH_P_8021AD` (0x88a8)
+    PushVlanProtocol(u16),
+    PushVlanPriority(u8),
+    PushEthDst([u8; 6]),
+    PushEthSrc([u8; 6]),
+    Other(DefaultNla),
+}
+
+impl Nla for TcActionVlanOption {
+    fn value_len(&self) -> usize {
+        match self {
+            Self::Tm(v) => v.buffer_len(),
+            Self::Parms(v) => v.buffer_len(),
+            Self::PushVlanId(_) | Self::PushVlanProtocol(_) => 2,
+            Self::PushVlanPriority(_) => 1,
+            Self::PushEthDst(_) | Self::PushEthSrc(_) => 6,
+            Self::Other(attr) => attr.value_len(),
+        }
+    }
+
+    fn emit_value(&self, buffer: &mut [u8]) {
+        match self {
+            Self::Tm(v) => v.emit(buffer),
+            Self::Parms(v) => v.emit(buffer),
+            Self::PushVlanId(d) => NativeEndian::write_u16(buffer, *d),
+            Self::PushVlanProtocol(d) => BigEndian::write_u16(buffer, *d),
+            Self::PushVlanPriority(d) => buffer[0] = *d,
+            Self::PushEthDst(v) | Self::PushEthSrc(v) => {
+                buffer.copy_from_slice(v)
+            }
+            Self::Other(attr) => attr.emit_value(buffer),
+        }
+    }
+
+    fn kind(&self) -> u16 {
+        match self {
+            Self::Tm(_) => TCA_VLAN_TM,
+            Self::Parms(_) => TCA_VLAN_PARMS,
+            Self::PushVlanId(_) => TCA_VLAN_PUSH_VLAN_ID,
+            Self::PushVlanProtocol(_) => TCA_VLAN_PUSH_VLAN_PROTOCOL,
+            Self::PushVlanPriority(_) => TCA_VLAN_PUSH_VLAN_PRIORITY,
+            Self::PushEthDst(_) => TCA_VLAN_PUSH_ETH_DST,
+            Self::PushEthSrc(_) => TCA_VLAN_PUSH_ETH_SRC,
+            Self::Other(nla) => nla.kind(),
+        }
+    }
+}
+
+impl<'a, T: AsRef<[u8]> + ?Sized> Parseable<NlaBuffer<&'a T>>
+    for TcActionVlanOption
+{
+    fn parse(buf: &NlaBuffer<&'a T>) -> Result<Self, DecodeError> {
+        let payload = buf.value();
+        Ok(match buf.kind() {
+            TCA_VLAN_TM => Self::Tm(
+                Tcf::parse(
+                    &TcfBuffer::new_checked(payload)
+                        .context("invalid TCA_VLAN_TM")?,
+                )
+                .context("failed to parse TCA_VLAN_TM")?,
+            ),
+            TCA_VLAN_PARMS => Self::Parms(
+                TcVlan::parse(
+                    &TcVlanBuffer::new_checked(payload)
+                        .context("invalid TCA_VLAN_PARMS")?,
+                )
+                .context("failed to parse TCA_VLAN_PARMS")?,
+            ),
+            TCA_VLAN_PUSH_VLAN_ID => Self::PushVlanId(
+                parse_u16(payload)
+                    .context("failed to parse TCA_VLAN_PUSH_VLAN_ID")?,
+            ),
+            TCA_VLAN_PUSH_VLAN_PROTOCOL => Self::PushVlanProtocol(
+                parse_u16_be(payload)
+                    .context("failed to parse TCA_VLAN_PUSH_VLAN_PROTOCOL")?,
+            ),
+            TCA_VLAN_PUSH_VLAN_PRIORITY => Self::PushVlanPriority(
+                parse_u8(payload)
+                    .context("failed to parse TCA_VLAN_PUSH_VLAN_PRIORITY")?,
+            ),
+            TCA_VLAN_PUSH_ETH_DST => Self::PushEthDst(
+                parse_mac(payload)
+                    .context("failed to parse TCA_VLAN_PUSH_ETH_DST")?,
+            ),
+            TCA_VLAN_PUSH_ETH_SRC => Self::PushEthSrc(
+                parse_mac(payload)
+                    .context("failed to parse TCA_VLAN_PUSH_ETH_SRC")?,
+            ),
+            _ => Self::Other(
+                DefaultNla::parse(buf).context("failed to parse vlan nla")?,
+            ),
+        })
+    }
+}
+
+const TC_VLAN_BUF_LEN: usize = TcActionGeneric::BUF_LEN + 4;
+
+// kernel struct `tc_vlan`
+#[derive(Debug, PartialEq, Eq, Clone, Default)]
+#[non_exhaustive]
+pub struct TcVlan {
+    pub generic: TcActionGeneric,
+    pub v_action: TcVlanAction,
+}
+
+buffer!(TcVlanBuffer(TC_VLAN_BUF_LEN) {
+    generic: (slice, 0..TcActionGeneric::BUF_LEN),
+    v_action: (i32, TcActionGeneric::BUF_LEN..TC_VLAN_BUF_LEN),
+});
+
+impl Emitable for TcVlan {
+    fn buffer_len(&self) -> usize {
+        TC_VLAN_BUF_LEN
+    }
+
+    fn emit(&self, buffer: &mut [u8]) {
+        let mut packet = TcVlanBuffer::new(buffer);
+        self.generic.emit(packet.generic_mut());
+        packet.set_v_action(self.v_action.into());
+    }
+}
+
+impl<T: AsRef<[u8]> + ?Sized> Parseable<TcVlanBuffer<&T>> for TcVlan {
+    fn parse(buf: &TcVlanBuffer<&T>) -> Result<Self, DecodeError> {
+        Ok(Self {
+            generic: TcActionGeneric::parse(&TcActionGenericBuffer::new(
+                buf.generic(),
+            ))?,
+            v_action: buf.v_action().into(),
+        })
+    }
+}
+
+const TCA_VLAN_ACT_POP: i32 = 1;
+const TCA_VLAN_ACT_PUSH: i32 = 2;
+const TCA_VLAN_ACT_MODIFY: i32 = 3;
+const TCA_VLAN_ACT_POP_ETH: i32 = 4;
+const TCA_VLAN_ACT_PUSH_ETH: i32 = 5;
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Default)]
+#[non_exhaustive]
+pub enum TcVlanAction {
+    #[default]
+    Pop,
+    Push,
+    Modify,
+    /// Remove the ethernet header
+    PopEth,
+    /// Add an ethernet header using `PushEthDst` and `PushEthSrc`
+    PushEth,
+    Other(i32),
+}
+
+impl From<i32> for TcVlanAction {
+    fn from(d: i32) -> Self {
+        match d {
+            TCA_VLAN_ACT_POP => Self::Pop,
+            TCA_VLAN_ACT_PUSH => Self::Push,
+            TCA_VLAN_ACT_MODIFY => Self::Modify,
+            TCA_VLAN_ACT_POP_ETH => Self::PopEth,
+            TCA_VLAN_ACT_PUSH_ETH => Self::PushEth,
+            _ => Self::Other(d),
+        }
+    }
+}
+
+impl From<TcVlanAction> for i32 {
+    fn from(v: TcVlanAction) -> i32 {
+        match v {
+            TcVlanAction::Pop => TCA_VLAN_ACT_POP,
+            TcVlanAction::Push => TCA_VLAN_ACT_PUSH,
+            TcVlanAction::Modify => TCA_VLAN_ACT_MODIFY,
+            TcVlanAction::PopEth => TCA_VLAN_ACT_POP_ETH,
+            TcVlanAction::PushEth => TCA_VLAN_ACT_PUSH_ETH,
+            TcVlanAction::Other(d) => d,
+        }
+    }
+}
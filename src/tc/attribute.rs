@@ -61,7 +61,7 @@ impl Nla for TcAttribute {
             Self::Stats2(ref v) => v.as_slice().buffer_len(),
             Self::Stats(ref v) => v.buffer_len(),
             Self::Kind(ref string) => string.as_bytes().len() + 1,
-            Self::Options(ref opt) => opt.as_slice().buffer_len(),
+            Self::Options(ref opt) => VecTcOption::value_len(opt),
             Self::DumpInvisible(_) => 0, // The existence of NLA means true
             Self::Other(ref attr) => attr.value_len(),
         }
@@ -82,7 +82,7 @@ impl Nla for TcAttribute {
                     .copy_from_slice(string.as_bytes());
                 buffer[string.as_bytes().len()] = 0;
             }
-            Self::Options(ref opt) => opt.as_slice().emit(buffer),
+            Self::Options(ref opt) => VecTcOption::emit_value(opt, buffer),
             Self::DumpInvisible(_) => (),
             Self::Other(ref attr) => attr.emit_value(buffer),
         }
@@ -104,6 +104,13 @@ impl Nla for TcAttribute {
             Self::Other(ref nla) => nla.kind(),
         }
     }
+
+    fn is_nested(&self) -> bool {
+        match *self {
+            Self::Options(ref opt) => VecTcOption::is_nested(opt),
+            _ => false,
+        }
+    }
 }
 
 impl<'a, T: AsRef<[u8]> + ?Sized> ParseableParametrized<NlaBuffer<&'a T>, &str>
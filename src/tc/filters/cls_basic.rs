@@ -0,0 +1,165 @@
+// SPDX-License-Identifier: MIT
+
+/// Basic filter
+///
+/// Classifies packets by an extended match expression tree, matching all
+/// packets when the tree is empty.
+use anyhow::Context;
+use byteorder::{ByteOrder, NativeEndian};
+use netlink_packet_utils::{
+    nla::{DefaultNla, Nla, NlaBuffer, NlasIterator},
+    parsers::parse_u32,
+    traits::{Emitable, Parseable},
+    DecodeError,
+};
+
+use crate::tc::{TcAction, TcActionPoliceOption, TcEmatchTree, TcHandle};
+
+const TCA_BASIC_CLASSID: u16 = 1;
+const TCA_BASIC_EMATCHES: u16 = 2;
+const TCA_BASIC_ACT: u16 = 3;
+const TCA_BASIC_POLICE: u16 = 4;
+const TCA_BASIC_PCNT: u16 = 5;
+
+const TC_BASIC_PCNT_BUF_LEN: usize = 16;
+
+#[derive(Debug, PartialEq, Eq, Clone)]
+#[non_exhaustive]
+pub struct TcFilterBasic {}
+
+impl TcFilterBasic {
+    pub const KIND: &'static str = "basic";
+}
+
+#[derive(Debug, PartialEq, Eq, Clone)]
+#[non_exhaustive]
+pub enum TcFilterBasicOption {
+    ClassId(TcHandle),
+    Ematches(TcEmatchTree),
+    Action(Vec<TcAction>),
+    Police(Vec<TcActionPoliceOption>),
+    Pnct(TcBasicPcnt),
+    Other(DefaultNla),
+}
+
+impl Nla for TcFilterBasicOption {
+    fn value_len(&self) -> usize {
+        match self {
+            Self::ClassId(_) => 4,
+            Self::Ematches(v) => v.buffer_len(),
+            Self::Action(acts) => acts.as_slice().buffer_len(),
+            Self::Police(p) => p.as_slice().buffer_len(),
+            Self::Pnct(v) => v.buffer_len(),
+            Self::Other(attr) => attr.value_len(),
+        }
+    }
+
+    fn emit_value(&self, buffer: &mut [u8]) {
+        match self {
+            Self::ClassId(i) => NativeEndian::write_u32(buffer, (*i).into()),
+            Self::Ematches(v) => v.emit(buffer),
+            Self::Action(acts) => acts.as_slice().emit(buffer),
+            Self::Police(p) => p.as_slice().emit(buffer),
+            Self::Pnct(v) => v.emit(buffer),
+            Self::Other(attr) => attr.emit_value(buffer),
+        }
+    }
+
+    fn kind(&self) -> u16 {
+        match self {
+            Self::ClassId(_) => TCA_BASIC_CLASSID,
+            Self::Ematches(_) => TCA_BASIC_EMATCHES,
+            Self::Action(_) => TCA_BASIC_ACT,
+            Self::Police(_) => TCA_BASIC_POLICE,
+            Self::Pnct(_) => TCA_BASIC_PCNT,
+            Self::Other(attr) => attr.kind(),
+        }
+    }
+}
+
+impl<'a, T: AsRef<[u8]> + ?Sized> Parseable<NlaBuffer<&'a T>>
+    for TcFilterBasicOption
+{
+    fn parse(buf: &NlaBuffer<&'a T>) -> Result<Self, DecodeError> {
+        let payload = buf.value();
+        Ok(match buf.kind() {
+            TCA_BASIC_CLASSID => Self::ClassId(
+                parse_u32(payload)
+                    .context("failed to parse TCA_BASIC_CLASSID")?
+                    .into(),
+            ),
+            TCA_BASIC_EMATCHES => Self::Ematches(
+                TcEmatchTree::parse(payload)
+                    .context("failed to parse TCA_BASIC_EMATCHES")?,
+            ),
+            TCA_BASIC_ACT => {
+                let mut acts = vec![];
+                for act in NlasIterator::new(payload) {
+                    let act = act.context("invalid TCA_BASIC_ACT")?;
+                    acts.push(
+                        TcAction::parse(&act)
+                            .context("failed to parse TCA_BASIC_ACT")?,
+                    );
+                }
+                Self::Action(acts)
+            }
+            TCA_BASIC_POLICE => {
+                let mut nlas = vec![];
+                for nla in NlasIterator::new(payload) {
+                    let nla = nla.context("invalid TCA_BASIC_POLICE")?;
+                    nlas.push(
+                        TcActionPoliceOption::parse(&nla)
+                            .context("failed to parse TCA_BASIC_POLICE")?,
+                    );
+                }
+                Self::Police(nlas)
+            }
+            TCA_BASIC_PCNT => Self::Pnct(
+                TcBasicPcnt::parse(
+                    &TcBasicPcntBuffer::new_checked(payload)
+                        .context("invalid TCA_BASIC_PCNT")?,
+                )
+                .context("failed to parse TCA_BASIC_PCNT")?,
+            ),
+            _ => Self::Other(
+                DefaultNla::parse(buf).context("failed to parse basic nla")?,
+            ),
+        })
+    }
+}
+
+// kernel struct `tc_basic_pcnt`
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Default)]
+#[non_exhaustive]
+pub struct TcBasicPcnt {
+    /// Number of packets looked up by this filter
+    pub rcnt: u64,
+    /// Number of packets matched by this filter
+    pub rhit: u64,
+}
+
+buffer!(TcBasicPcntBuffer(TC_BASIC_PCNT_BUF_LEN) {
+    rcnt: (u64, 0..8),
+    rhit: (u64, 8..TC_BASIC_PCNT_BUF_LEN),
+});
+
+impl Emitable for TcBasicPcnt {
+    fn buffer_len(&self) -> usize {
+        TC_BASIC_PCNT_BUF_LEN
+    }
+
+    fn emit(&self, buffer: &mut [u8]) {
+        let mut packet = TcBasicPcntBuffer::new(buffer);
+        packet.set_rcnt(self.rcnt);
+        packet.set_rhit(self.rhit);
+    }
+}
+
+impl<T: AsRef<[u8]>> Parseable<TcBasicPcntBuffer<T>> for TcBasicPcnt {
+    fn parse(buf: &TcBasicPcntBuffer<T>) -> Result<Self, DecodeError> {
+        Ok(Self {
+            rcnt: buf.rcnt(),
+            rhit: buf.rhit(),
+        })
+    }
+}
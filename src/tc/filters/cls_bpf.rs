@@ -0,0 +1,331 @@
+// SPDX-License-Identifier: MIT
+
+/// BPF filter
+///
+/// Classifies packets by a classic BPF bytecode or an eBPF program
+/// loaded into kernel. With direct-action flag, the eBPF program return
+/// code is used as the action verdict.
+use anyhow::Context;
+use byteorder::{ByteOrder, NativeEndian};
+use netlink_packet_utils::{
+    nla::{DefaultNla, Nla, NlaBuffer, NlasIterator},
+    parsers::{parse_string, parse_u16, parse_u32},
+    traits::{Emitable, Parseable},
+    DecodeError,
+};
+
+use super::u32_flags::VecTcU32OptionFlag;
+use crate::tc::{TcAction, TcActionPoliceOption, TcHandle, TcU32OptionFlag};
+
+const TCA_BPF_ACT: u16 = 1;
+const TCA_BPF_POLICE: u16 = 2;
+const TCA_BPF_CLASSID: u16 = 3;
+const TCA_BPF_OPS_LEN: u16 = 4;
+const TCA_BPF_OPS: u16 = 5;
+const TCA_BPF_FD: u16 = 6;
+const TCA_BPF_NAME: u16 = 7;
+const TCA_BPF_FLAGS: u16 = 8;
+const TCA_BPF_FLAGS_GEN: u16 = 9;
+const TCA_BPF_TAG: u16 = 10;
+const TCA_BPF_ID: u16 = 11;
+
+const BPF_TAG_SIZE: usize = 8;
+
+#[derive(Debug, PartialEq, Eq, Clone)]
+#[non_exhaustive]
+pub struct TcFilterBpf {}
+
+impl TcFilterBpf {
+    pub const KIND: &'static str = "bpf";
+}
+
+#[derive(Debug, PartialEq, Eq, Clone)]
+#[non_exhaustive]
+pub enum TcFilterBpfOption {
+    Action(Vec<TcAction>),
+    Police(Vec<TcActionPoliceOption>),
+    ClassId(TcHandle),
+    /// Number of classic BPF instructions in `Ops`
+    OpsLen(u16),
+    /// Classic BPF instructions
+    Ops(Vec<TcBpfSockFilter>),
+    /// File descriptor of the eBPF program to attach
+    Fd(u32),
+    /// Name of the eBPF program, usually the object file name and section
+    Name(String),
+    Flags(Vec<TcBpfFlag>),
+    /// Classifier flags, e.g. `SkipHw` and `SkipSw`
+    FlagsGen(Vec<TcU32OptionFlag>),
+    /// Tag (SHA-1 prefix of instructions) of the attached eBPF program
+    Tag([u8; BPF_TAG_SIZE]),
+    /// ID of the attached eBPF program
+    Id(u32),
+    Other(DefaultNla),
+}
+
+impl Nla for TcFilterBpfOption {
+    fn value_len(&self) -> usize {
+        match self {
+            Self::Action(acts) => acts.as_slice().buffer_len(),
+            Self::Police(p) => p.as_slice().buffer_len(),
+            Self::OpsLen(_) => 2,
+            Self::Ops(v) => v.len() * TC_BPF_SOCK_FILTER_BUF_LEN,
+            Self::Name(s) => s.len() + 1,
+            Self::ClassId(_)
+            | Self::Fd(_)
+            | Self::Flags(_)
+            | Self::FlagsGen(_)
+            | Self::Id(_) => 4,
+            Self::Tag(_) => BPF_TAG_SIZE,
+            Self::Other(attr) => attr.value_len(),
+        }
+    }
+
+    fn emit_value(&self, buffer: &mut [u8]) {
+        match self {
+            Self::Action(acts) => acts.as_slice().emit(buffer),
+            Self::Police(p) => p.as_slice().emit(buffer),
+            Self::ClassId(i) => NativeEndian::write_u32(buffer, (*i).into()),
+            Self::OpsLen(d) => NativeEndian::write_u16(buffer, *d),
+            Self::Ops(v) => {
+                for (i, op) in v.iter().enumerate() {
+                    op.emit(
+                        &mut buffer[(i * TC_BPF_SOCK_FILTER_BUF_LEN)
+                            ..((i + 1) * TC_BPF_SOCK_FILTER_BUF_LEN)],
+                    );
+                }
+            }
+            Self::Fd(d) | Self::Id(d) => NativeEndian::write_u32(buffer, *d),
+            Self::Name(s) => {
+                buffer[..s.len()].copy_from_slice(s.as_bytes());
+                buffer[s.len()] = 0;
+            }
+            Self::Flags(v) => NativeEndian::write_u32(
+                buffer,
+                u32::from(&VecTcBpfFlag(v.to_vec())),
+            ),
+            Self::FlagsGen(v) => NativeEndian::write_u32(
+                buffer,
+                u32::from(&VecTcU32OptionFlag(v.to_vec())),
+            ),
+            Self::Tag(v) => buffer.copy_from_slice(v),
+            Self::Other(attr) => attr.emit_value(buffer),
+        }
+    }
+
+    fn kind(&self) -> u16 {
+        match self {
+            Self::Action(_) => TCA_BPF_ACT,
+            Self::Police(_) => TCA_BPF_POLICE,
+            Self::ClassId(_) => TCA_BPF_CLASSID,
+            Self::OpsLen(_) => TCA_BPF_OPS_LEN,
+            Self::Ops(_) => TCA_BPF_OPS,
+            Self::Fd(_) => TCA_BPF_FD,
+            Self::Name(_) => TCA_BPF_NAME,
+            Self::Flags(_) => TCA_BPF_FLAGS,
+            Self::FlagsGen(_) => TCA_BPF_FLAGS_GEN,
+            Self::Tag(_) => TCA_BPF_TAG,
+            Self::Id(_) => TCA_BPF_ID,
+            Self::Other(attr) => attr.kind(),
+        }
+    }
+}
+
+impl<'a, T: AsRef<[u8]> + ?Sized> Parseable<NlaBuffer<&'a T>>
+    for TcFilterBpfOption
+{
+    fn parse(buf: &NlaBuffer<&'a T>) -> Result<Self, DecodeError> {
+        let payload = buf.value();
+        Ok(match buf.kind() {
+            TCA_BPF_ACT => {
+                let mut acts = vec![];
+                for act in NlasIterator::new(payload) {
+                    let act = act.context("invalid TCA_BPF_ACT")?;
+                    acts.push(
+                        TcAction::parse(&act)
+                            .context("failed to parse TCA_BPF_ACT")?,
+                    );
+                }
+                Self::Action(acts)
+            }
+            TCA_BPF_POLICE => {
+                let mut nlas = vec![];
+                for nla in NlasIterator::new(payload) {
+                    let nla = nla.context("invalid TCA_BPF_POLICE")?;
+                    nlas.push(
+                        TcActionPoliceOption::parse(&nla)
+                            .context("failed to parse TCA_BPF_POLICE")?,
+                    );
+                }
+                Self::Police(nlas)
+            }
+            TCA_BPF_CLASSID => Self::ClassId(
+                parse_u32(payload)
+                    .context("failed to parse TCA_BPF_CLASSID")?
+                    .into(),
+            ),
+            TCA_BPF_OPS_LEN => Self::OpsLen(
+                parse_u16(payload)
+                    .context("failed to parse TCA_BPF_OPS_LEN")?,
+            ),
+            TCA_BPF_OPS => {
+                if !payload.len().is_multiple_of(TC_BPF_SOCK_FILTER_BUF_LEN) {
+                    return Err(DecodeError::from(format!(
+                        "Invalid TCA_BPF_OPS length {}, expecting \
+                        multiple of {TC_BPF_SOCK_FILTER_BUF_LEN}",
+                        payload.len()
+                    )));
+                }
+                let mut ops = vec![];
+                for chunk in payload.chunks(TC_BPF_SOCK_FILTER_BUF_LEN) {
+                    ops.push(
+                        TcBpfSockFilter::parse(
+                            &TcBpfSockFilterBuffer::new_checked(chunk)
+                                .context("invalid TCA_BPF_OPS")?,
+                        )
+                        .context("failed to parse TCA_BPF_OPS")?,
+                    );
+                }
+                Self::Ops(ops)
+            }
+            TCA_BPF_FD => Self::Fd(
+                parse_u32(payload).context("failed to parse TCA_BPF_FD")?,
+            ),
+            TCA_BPF_NAME => Self::Name(
+                parse_string(payload)
+                    .context("failed to parse TCA_BPF_NAME")?,
+            ),
+            TCA_BPF_FLAGS => Self::Flags(
+                VecTcBpfFlag::from(
+                    parse_u32(payload)
+                        .context("failed to parse TCA_BPF_FLAGS")?,
+                )
+                .0,
+            ),
+            TCA_BPF_FLAGS_GEN => Self::FlagsGen(
+                VecTcU32OptionFlag::from(
+                    parse_u32(payload)
+                        .context("failed to parse TCA_BPF_FLAGS_GEN")?,
+                )
+                .0,
+            ),
+            TCA_BPF_TAG => {
+                if payload.len() != BPF_TAG_SIZE {
+                    return Err(DecodeError::from(format!(
+                        "Invalid TCA_BPF_TAG length {}, expecting \
+                        {BPF_TAG_SIZE}",
+                        payload.len()
+                    )));
+                }
+                let mut tag = [0u8; BPF_TAG_SIZE];
+                tag.copy_from_slice(payload);
+                Self::Tag(tag)
+            }
+            TCA_BPF_ID => Self::Id(
+                parse_u32(payload).context("failed to parse TCA_BPF_ID")?,
+            ),
+            _ => Self::Other(
+                DefaultNla::parse(buf).context("failed to parse bpf nla")?,
+            ),
+        })
+    }
+}
+
+const TC_BPF_SOCK_FILTER_BUF_LEN: usize = 8;
+
+// kernel struct `sock_filter`
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Default)]
+#[non_exhaustive]
+pub struct TcBpfSockFilter {
+    /// Opcode of the instruction
+    pub code: u16,
+    /// Jump offset if true
+    pub jt: u8,
+    /// Jump offset if false
+    pub jf: u8,
+    /// Generic multiuse field
+    pub k: u32,
+}
+
+buffer!(TcBpfSockFilterBuffer(TC_BPF_SOCK_FILTER_BUF_LEN) {
+    code: (u16, 0..2),
+    jt: (u8, 2),
+    jf: (u8, 3),
+    k: (u32, 4..TC_BPF_SOCK_FILTER_BUF_LEN),
+});
+
+impl Emitable for TcBpfSockFilter {
+    fn buffer_len(&self) -> usize {
+        TC_BPF_SOCK_FILTER_BUF_LEN
+    }
+
+    fn emit(&self, buffer: &mut [u8]) {
+        let mut packet = TcBpfSockFilterBuffer::new(buffer);
+        packet.set_code(self.code);
+        packet.set_jt(self.jt);
+        packet.set_jf(self.jf);
+        packet.set_k(self.k);
+    }
+}
+
+impl<T: AsRef<[u8]>> Parseable<TcBpfSockFilterBuffer<T>> for TcBpfSockFilter {
+    fn parse(buf: &TcBpfSockFilterBuffer<T>) -> Result<Self, DecodeError> {
+        Ok(Self {
+            code: buf.code(),
+            jt: buf.jt(),
+            jf: buf.jf(),
+            k: buf.k(),
+        })
+    }
+}
+
+const TCA_BPF_FLAG_ACT_DIRECT: u32 = 1 << 0;
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+#[non_exhaustive]
+pub enum TcBpfFlag {
+    /// Use the return code of eBPF program as action verdict
+    ActDirect,
+    Other(u32),
+}
+
+impl From<TcBpfFlag> for u32 {
+    fn from(v: TcBpfFlag) -> u32 {
+        match v {
+            TcBpfFlag::ActDirect => TCA_BPF_FLAG_ACT_DIRECT,
+            TcBpfFlag::Other(i) => i,
+        }
+    }
+}
+
+const ALL_BPF_FLAGS: [TcBpfFlag; 1] = [TcBpfFlag::ActDirect];
+
+#[derive(Clone, Eq, PartialEq, Debug)]
+struct VecTcBpfFlag(Vec<TcBpfFlag>);
+
+impl From<u32> for VecTcBpfFlag {
+    fn from(d: u32) -> Self {
+        let mut got: u32 = 0;
+        let mut ret = Vec::new();
+        for flag in ALL_BPF_FLAGS {
+            if (d & (u32::from(flag))) > 0 {
+                ret.push(flag);
+                got += u32::from(flag);
+            }
+        }
+        if got != d {
+            ret.push(TcBpfFlag::Other(d - got));
+        }
+        Self(ret)
+    }
+}
+
+impl From<&VecTcBpfFlag> for u32 {
+    fn from(v: &VecTcBpfFlag) -> u32 {
+        let mut d: u32 = 0;
+        for flag in &v.0 {
+            d += u32::from(*flag);
+        }
+        d
+    }
+}
@@ -0,0 +1,350 @@
+// SPDX-License-Identifier: MIT
+
+/// Flow filter
+///
+/// Classifies packets by a set of flow keys, either by hashing the keys
+/// into a number of classes, or by mapping a single key into a class id
+/// through a series of arithmetic operations.
+use anyhow::Context;
+use byteorder::{ByteOrder, NativeEndian};
+use netlink_packet_utils::{
+    nla::{DefaultNla, Nla, NlaBuffer, NlasIterator},
+    parsers::parse_u32,
+    traits::{Emitable, Parseable},
+    DecodeError,
+};
+
+use crate::tc::{TcAction, TcActionPoliceOption, TcHandle};
+
+const TCA_FLOW_KEYS: u16 = 1;
+const TCA_FLOW_MODE: u16 = 2;
+const TCA_FLOW_BASECLASS: u16 = 3;
+const TCA_FLOW_RSHIFT: u16 = 4;
+const TCA_FLOW_ADDEND: u16 = 5;
+const TCA_FLOW_MASK: u16 = 6;
+const TCA_FLOW_XOR: u16 = 7;
+const TCA_FLOW_DIVISOR: u16 = 8;
+const TCA_FLOW_ACT: u16 = 9;
+const TCA_FLOW_POLICE: u16 = 10;
+const TCA_FLOW_EMATCHES: u16 = 11;
+const TCA_FLOW_PERTURB: u16 = 12;
+
+#[derive(Debug, PartialEq, Eq, Clone)]
+#[non_exhaustive]
+pub struct TcFilterFlow {}
+
+impl TcFilterFlow {
+    pub const KIND: &'static str = "flow";
+}
+
+#[derive(Debug, PartialEq, Eq, Clone)]
+#[non_exhaustive]
+pub enum TcFilterFlowOption {
+    Keys(Vec<TcFlowKey>),
+    Mode(TcFlowMode),
+    BaseClass(TcHandle),
+    /// Right shift applied to the key in map mode
+    Rshift(u32),
+    /// Value added to the key in map mode
+    Addend(u32),
+    /// Mask applied to the key in map mode
+    Mask(u32),
+    /// Value XORed with the key in map mode
+    Xor(u32),
+    /// Number of classes the keys are hashed or mapped into
+    Divisor(u32),
+    Action(Vec<TcAction>),
+    Police(Vec<TcActionPoliceOption>),
+    Ematches(Vec<u8>),
+    /// Hash perturbation period in seconds
+    Perturb(u32),
+    Other(DefaultNla),
+}
+
+impl Nla for TcFilterFlowOption {
+    fn value_len(&self) -> usize {
+        match self {
+            Self::Keys(_)
+            | Self::Mode(_)
+            | Self::BaseClass(_)
+            | Self::Rshift(_)
+            | Self::Addend(_)
+            | Self::Mask(_)
+            | Self::Xor(_)
+            | Self::Divisor(_)
+            | Self::Perturb(_) => 4,
+            Self::Action(acts) => acts.as_slice().buffer_len(),
+            Self::Police(p) => p.as_slice().buffer_len(),
+            Self::Ematches(b) => b.len(),
+            Self::Other(attr) => attr.value_len(),
+        }
+    }
+
+    fn emit_value(&self, buffer: &mut [u8]) {
+        match self {
+            Self::Keys(v) => NativeEndian::write_u32(
+                buffer,
+                u32::from(&VecTcFlowKey(v.to_vec())),
+            ),
+            Self::Mode(v) => NativeEndian::write_u32(buffer, (*v).into()),
+            Self::BaseClass(i) => NativeEndian::write_u32(buffer, (*i).into()),
+            Self::Rshift(d)
+            | Self::Addend(d)
+            | Self::Mask(d)
+            | Self::Xor(d)
+            | Self::Divisor(d)
+            | Self::Perturb(d) => NativeEndian::write_u32(buffer, *d),
+            Self::Action(acts) => acts.as_slice().emit(buffer),
+            Self::Police(p) => p.as_slice().emit(buffer),
+            Self::Ematches(b) => buffer.copy_from_slice(b.as_slice()),
+            Self::Other(attr) => attr.emit_value(buffer),
+        }
+    }
+
+    fn kind(&self) -> u16 {
+        match self {
+            Self::Keys(_) => TCA_FLOW_KEYS,
+            Self::Mode(_) => TCA_FLOW_MODE,
+            Self::BaseClass(_) => TCA_FLOW_BASECLASS,
+            Self::Rshift(_) => TCA_FLOW_RSHIFT,
+            Self::Addend(_) => TCA_FLOW_ADDEND,
+            Self::Mask(_) => TCA_FLOW_MASK,
+            Self::Xor(_) => TCA_FLOW_XOR,
+            Self::Divisor(_) => TCA_FLOW_DIVISOR,
+            Self::Action(_) => TCA_FLOW_ACT,
+            Self::Police(_) => TCA_FLOW_POLICE,
+            Self::Ematches(_) => TCA_FLOW_EMATCHES,
+            Self::Perturb(_) => TCA_FLOW_PERTURB,
+            Self::Other(attr) => attr.kind(),
+        }
+    }
+}
+
+impl<'a, T: AsRef<[u8]> + ?Sized> Parseable<NlaBuffer<&'a T>>
+    for TcFilterFlowOption
+{
+    fn parse(buf: &NlaBuffer<&'a T>) -> Result<Self, DecodeError> {
+        let payload = buf.value();
+        Ok(match buf.kind() {
+            TCA_FLOW_KEYS => Self::Keys(
+                VecTcFlowKey::from(
+                    parse_u32(payload)
+                        .context("failed to parse TCA_FLOW_KEYS")?,
+                )
+                .0,
+            ),
+            TCA_FLOW_MODE => Self::Mode(
+                parse_u32(payload)
+                    .context("failed to parse TCA_FLOW_MODE")?
+                    .into(),
+            ),
+            TCA_FLOW_BASECLASS => Self::BaseClass(
+                parse_u32(payload)
+                    .context("failed to parse TCA_FLOW_BASECLASS")?
+                    .into(),
+            ),
+            TCA_FLOW_RSHIFT => Self::Rshift(
+                parse_u32(payload)
+                    .context("failed to parse TCA_FLOW_RSHIFT")?,
+            ),
+            TCA_FLOW_ADDEND => Self::Addend(
+                parse_u32(payload)
+                    .context("failed to parse TCA_FLOW_ADDEND")?,
+            ),
+            TCA_FLOW_MASK => Self::Mask(
+                parse_u32(payload).context("failed to parse TCA_FLOW_MASK")?,
+            ),
+            TCA_FLOW_XOR => Self::Xor(
+                parse_u32(payload).context("failed to parse TCA_FLOW_XOR")?,
+            ),
+            TCA_FLOW_DIVISOR => Self::Divisor(
+                parse_u32(payload)
+                    .context("failed to parse TCA_FLOW_DIVISOR")?,
+            ),
+            TCA_FLOW_ACT => {
+                let mut acts = vec![];
+                for act in NlasIterator::new(payload) {
+                    let act = act.context("invalid TCA_FLOW_ACT")?;
+                    acts.push(
+                        TcAction::parse(&act)
+                            .context("failed to parse TCA_FLOW_ACT")?,
+                    );
+                }
+                Self::Action(acts)
+            }
+            TCA_FLOW_POLICE => {
+                let mut nlas = vec![];
+                for nla in NlasIterator::new(payload) {
+                    let nla = nla.context("invalid TCA_FLOW_POLICE")?;
+                    nlas.push(
+                        TcActionPoliceOption::parse(&nla)
+                            .context("failed to parse TCA_FLOW_POLICE")?,
+                    );
+                }
+                Self::Police(nlas)
+            }
+            TCA_FLOW_EMATCHES => Self::Ematches(payload.to_vec()),
+            TCA_FLOW_PERTURB => Self::Perturb(
+                parse_u32(payload)
+                    .context("failed to parse TCA_FLOW_PERTURB")?,
+            ),
+            _ => Self::Other(
+                DefaultNla::parse(buf).context("failed to parse flow nla")?,
+            ),
+        })
+    }
+}
+
+const FLOW_MODE_MAP: u32 = 0;
+const FLOW_MODE_HASH: u32 = 1;
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Default)]
+#[non_exhaustive]
+pub enum TcFlowMode {
+    /// Map a single key into a class id
+    #[default]
+    Map,
+    /// Hash all keys into a class id
+    Hash,
+    Other(u32),
+}
+
+impl From<u32> for TcFlowMode {
+    fn from(d: u32) -> Self {
+        match d {
+            FLOW_MODE_MAP => Self::Map,
+            FLOW_MODE_HASH => Self::Hash,
+            _ => Self::Other(d),
+        }
+    }
+}
+
+impl From<TcFlowMode> for u32 {
+    fn from(v: TcFlowMode) -> u32 {
+        match v {
+            TcFlowMode::Map => FLOW_MODE_MAP,
+            TcFlowMode::Hash => FLOW_MODE_HASH,
+            TcFlowMode::Other(d) => d,
+        }
+    }
+}
+
+const FLOW_KEY_SRC: u32 = 1 << 0;
+const FLOW_KEY_DST: u32 = 1 << 1;
+const FLOW_KEY_PROTO: u32 = 1 << 2;
+const FLOW_KEY_PROTO_SRC: u32 = 1 << 3;
+const FLOW_KEY_PROTO_DST: u32 = 1 << 4;
+const FLOW_KEY_IIF: u32 = 1 << 5;
+const FLOW_KEY_PRIORITY: u32 = 1 << 6;
+const FLOW_KEY_MARK: u32 = 1 << 7;
+const FLOW_KEY_NFCT: u32 = 1 << 8;
+const FLOW_KEY_NFCT_SRC: u32 = 1 << 9;
+const FLOW_KEY_NFCT_DST: u32 = 1 << 10;
+const FLOW_KEY_NFCT_PROTO_SRC: u32 = 1 << 11;
+const FLOW_KEY_NFCT_PROTO_DST: u32 = 1 << 12;
+const FLOW_KEY_RTCLASSID: u32 = 1 << 13;
+const FLOW_KEY_SKUID: u32 = 1 << 14;
+const FLOW_KEY_SKGID: u32 = 1 << 15;
+const FLOW_KEY_VLAN_TAG: u32 = 1 << 16;
+const FLOW_KEY_RXHASH: u32 = 1 << 17;
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+#[non_exhaustive]
+pub enum TcFlowKey {
+    Src,
+    Dst,
+    Proto,
+    ProtoSrc,
+    ProtoDst,
+    Iif,
+    Priority,
+    Mark,
+    Nfct,
+    NfctSrc,
+    NfctDst,
+    NfctProtoSrc,
+    NfctProtoDst,
+    RtClassId,
+    SkUid,
+    SkGid,
+    VlanTag,
+    RxHash,
+    Other(u32),
+}
+
+impl From<TcFlowKey> for u32 {
+    fn from(v: TcFlowKey) -> u32 {
+        match v {
+            TcFlowKey::Src => FLOW_KEY_SRC,
+            TcFlowKey::Dst => FLOW_KEY_DST,
+            TcFlowKey::Proto => FLOW_KEY_PROTO,
+            TcFlowKey::ProtoSrc => FLOW_KEY_PROTO_SRC,
+            TcFlowKey::ProtoDst => FLOW_KEY_PROTO_DST,
+            TcFlowKey::Iif => FLOW_KEY_IIF,
+            TcFlowKey::Priority => FLOW_KEY_PRIORITY,
+            TcFlowKey::Mark => FLOW_KEY_MARK,
+            TcFlowKey::Nfct => FLOW_KEY_NFCT,
+            TcFlowKey::NfctSrc => FLOW_KEY_NFCT_SRC,
+            TcFlowKey::NfctDst => FLOW_KEY_NFCT_DST,
+            TcFlowKey::NfctProtoSrc => FLOW_KEY_NFCT_PROTO_SRC,
+            TcFlowKey::NfctProtoDst => FLOW_KEY_NFCT_PROTO_DST,
+            TcFlowKey::RtClassId => FLOW_KEY_RTCLASSID,
+            TcFlowKey::SkUid => FLOW_KEY_SKUID,
+            TcFlowKey::SkGid => FLOW_KEY_SKGID,
+            TcFlowKey::VlanTag => FLOW_KEY_VLAN_TAG,
+            TcFlowKey::RxHash => FLOW_KEY_RXHASH,
+            TcFlowKey::Other(i) => i,
+        }
+    }
+}
+
+const ALL_FLOW_KEYS: [TcFlowKey; 18] = [
+    TcFlowKey::Src,
+    TcFlowKey::Dst,
+    TcFlowKey::Proto,
+    TcFlowKey::ProtoSrc,
+    TcFlowKey::ProtoDst,
+    TcFlowKey::Iif,
+    TcFlowKey::Priority,
+    TcFlowKey::Mark,
+    TcFlowKey::Nfct,
+    TcFlowKey::NfctSrc,
+    TcFlowKey::NfctDst,
+    TcFlowKey::NfctProtoSrc,
+    TcFlowKey::NfctProtoDst,
+    TcFlowKey::RtClassId,
+    TcFlowKey::SkUid,
+    TcFlowKey::SkGid,
+    TcFlowKey::VlanTag,
+    TcFlowKey::RxHash,
+];
+
+#[derive(Clone, Eq, PartialEq, Debug)]
+struct VecTcFlowKey(Vec<TcFlowKey>);
+
+impl From<u32> for VecTcFlowKey {
+    fn from(d: u32) -> Self {
+        let mut got: u32 = 0;
+        let mut ret = Vec::new();
+        for flag in ALL_FLOW_KEYS {
+            if (d & (u32::from(flag))) > 0 {
+                ret.push(flag);
+                got += u32::from(flag);
+            }
+        }
+        if got != d {
+            ret.push(TcFlowKey::Other(d - got));
+        }
+        Self(ret)
+    }
+}
+
+impl From<&VecTcFlowKey> for u32 {
+    fn from(v: &VecTcFlowKey) -> u32 {
+        let mut d: u32 = 0;
+        for flag in &v.0 {
+            d += u32::from(*flag);
+        }
+        d
+    }
+}
@@ -0,0 +1,128 @@
+// SPDX-License-Identifier: MIT
+
+/// Firewall mark filter
+///
+/// Classifies packets by the firewall mark (`skb->mark`) set by netfilter
+/// or other subsystems, optionally masked before being compared with the
+/// filter handle.
+use anyhow::Context;
+use byteorder::{ByteOrder, NativeEndian};
+use netlink_packet_utils::{
+    nla::{DefaultNla, Nla, NlaBuffer, NlasIterator},
+    parsers::{parse_string, parse_u32},
+    traits::{Emitable, Parseable},
+    DecodeError,
+};
+
+use crate::tc::{TcAction, TcActionPoliceOption, TcHandle};
+
+const TCA_FW_CLASSID: u16 = 1;
+const TCA_FW_POLICE: u16 = 2;
+const TCA_FW_INDEV: u16 = 3;
+const TCA_FW_ACT: u16 = 4;
+const TCA_FW_MASK: u16 = 5;
+
+#[derive(Debug, PartialEq, Eq, Clone)]
+#[non_exhaustive]
+pub struct TcFilterFw {}
+
+impl TcFilterFw {
+    pub const KIND: &'static str = "fw";
+}
+
+#[derive(Debug, PartialEq, Eq, Clone)]
+#[non_exhaustive]
+pub enum TcFilterFwOption {
+    ClassId(TcHandle),
+    Police(Vec<TcActionPoliceOption>),
+    /// Name of the input interface
+    Indev(String),
+    Action(Vec<TcAction>),
+    /// Mask applied to the firewall mark before comparing it with handle
+    Mask(u32),
+    Other(DefaultNla),
+}
+
+impl Nla for TcFilterFwOption {
+    fn value_len(&self) -> usize {
+        match self {
+            Self::ClassId(_) | Self::Mask(_) => 4,
+            Self::Police(p) => p.as_slice().buffer_len(),
+            Self::Indev(s) => s.len() + 1,
+            Self::Action(acts) => acts.as_slice().buffer_len(),
+            Self::Other(attr) => attr.value_len(),
+        }
+    }
+
+    fn emit_value(&self, buffer: &mut [u8]) {
+        match self {
+            Self::ClassId(i) => NativeEndian::write_u32(buffer, (*i).into()),
+            Self::Police(p) => p.as_slice().emit(buffer),
+            Self::Indev(s) => {
+                buffer[..s.len()].copy_from_slice(s.as_bytes());
+                buffer[s.len()] = 0;
+            }
+            Self::Action(acts) => acts.as_slice().emit(buffer),
+            Self::Mask(d) => NativeEndian::write_u32(buffer, *d),
+            Self::Other(attr) => attr.emit_value(buffer),
+        }
+    }
+
+    fn kind(&self) -> u16 {
+        match self {
+            Self::ClassId(_) => TCA_FW_CLASSID,
+            Self::Police(_) => TCA_FW_POLICE,
+            Self::Indev(_) => TCA_FW_INDEV,
+            Self::Action(_) => TCA_FW_ACT,
+            Self::Mask(_) => TCA_FW_MASK,
+            Self::Other(attr) => attr.kind(),
+        }
+    }
+}
+
+impl<'a, T: AsRef<[u8]> + ?Sized> Parseable<NlaBuffer<&'a T>>
+    for TcFilterFwOption
+{
+    fn parse(buf: &NlaBuffer<&'a T>) -> Result<Self, DecodeError> {
+        let payload = buf.value();
+        Ok(match buf.kind() {
+            TCA_FW_CLASSID => Self::ClassId(
+                parse_u32(payload)
+                    .context("failed to parse TCA_FW_CLASSID")?
+                    .into(),
+            ),
+            TCA_FW_POLICE => {
+                let mut nlas = vec![];
+                for nla in NlasIterator::new(payload) {
+                    let nla = nla.context("invalid TCA_FW_POLICE")?;
+                    nlas.push(
+                        TcActionPoliceOption::parse(&nla)
+                            .context("failed to parse TCA_FW_POLICE")?,
+                    );
+                }
+                Self::Police(nlas)
+            }
+            TCA_FW_INDEV => Self::Indev(
+                parse_string(payload)
+                    .context("failed to parse TCA_FW_INDEV")?,
+            ),
+            TCA_FW_ACT => {
+                let mut acts = vec![];
+                for act in NlasIterator::new(payload) {
+                    let act = act.context("invalid TCA_FW_ACT")?;
+                    acts.push(
+                        TcAction::parse(&act)
+                            .context("failed to parse TCA_FW_ACT")?,
+                    );
+                }
+                Self::Action(acts)
+            }
+            TCA_FW_MASK => Self::Mask(
+                parse_u32(payload).context("failed to parse TCA_FW_MASK")?,
+            ),
+            _ => Self::Other(
+                DefaultNla::parse(buf).context("failed to parse fw nla")?,
+            ),
+        })
+    }
+}
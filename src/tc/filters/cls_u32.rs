@@ -11,16 +11,21 @@ use anyhow::Context;
 use byteorder::{ByteOrder, NativeEndian};
 use netlink_packet_utils::{
     nla::{DefaultNla, Nla, NlaBuffer, NlasIterator},
-    parsers::parse_u32,
+    parsers::{parse_string, parse_u32, parse_u64},
     traits::{Emitable, Parseable},
     DecodeError,
 };
 
 use super::u32_flags::{VecTcU32OptionFlag, VecTcU32SelectorFlag};
-use crate::tc::{TcAction, TcHandle, TcU32OptionFlag, TcU32SelectorFlag};
+use crate::tc::{
+    TcAction, TcActionPoliceOption, TcHandle, TcU32OptionFlag,
+    TcU32SelectorFlag,
+};
 
 const TC_U32_SEL_BUF_LEN: usize = 16;
 const TC_U32_KEY_BUF_LEN: usize = 16;
+const TC_U32_MARK_BUF_LEN: usize = 12;
+const TC_U32_PCNT_BUF_LEN: usize = 16;
 
 const TCA_U32_CLASSID: u16 = 1;
 const TCA_U32_HASH: u16 = 2;
@@ -50,11 +55,12 @@ pub enum TcFilterU32Option {
     Link(u32),
     Divisor(u32),
     Selector(TcU32Selector),
-    Police(Vec<u8>),
+    Police(Vec<TcActionPoliceOption>),
     Action(Vec<TcAction>),
-    Indev(Vec<u8>),
-    Pnct(Vec<u8>),
-    Mark(Vec<u8>),
+    /// Input interface name
+    Indev(String),
+    Pnct(TcU32Pcnt),
+    Mark(TcU32Mark),
     Flags(Vec<TcU32OptionFlag>),
     Other(DefaultNla),
 }
@@ -62,10 +68,10 @@ pub enum TcFilterU32Option {
 impl Nla for TcFilterU32Option {
     fn value_len(&self) -> usize {
         match self {
-            Self::Police(b)
-            | Self::Indev(b)
-            | Self::Pnct(b)
-            | Self::Mark(b) => b.len(),
+            Self::Police(p) => p.as_slice().buffer_len(),
+            Self::Indev(s) => s.len() + 1,
+            Self::Pnct(p) => p.buffer_len(),
+            Self::Mark(m) => m.buffer_len(),
             Self::Hash(_)
             | Self::Link(_)
             | Self::Divisor(_)
@@ -79,10 +85,13 @@ impl Nla for TcFilterU32Option {
 
     fn emit_value(&self, buffer: &mut [u8]) {
         match self {
-            Self::Police(b)
-            | Self::Indev(b)
-            | Self::Pnct(b)
-            | Self::Mark(b) => buffer.copy_from_slice(b.as_slice()),
+            Self::Police(p) => p.as_slice().emit(buffer),
+            Self::Indev(s) => {
+                buffer[..s.len()].copy_from_slice(s.as_bytes());
+                buffer[s.len()] = 0;
+            }
+            Self::Pnct(p) => p.emit(buffer),
+            Self::Mark(m) => m.emit(buffer),
             Self::Hash(i) | Self::Link(i) | Self::Divisor(i) => {
                 NativeEndian::write_u32(buffer, *i)
             }
@@ -141,7 +150,17 @@ impl<'a, T: AsRef<[u8]> + ?Sized> Parseable<NlaBuffer<&'a T>>
                 )
                 .context("failed to parse TCA_U32_SEL")?,
             ),
-            TCA_U32_POLICE => Self::Police(payload.to_vec()),
+            TCA_U32_POLICE => {
+                let mut nlas = vec![];
+                for nla in NlasIterator::new(payload) {
+                    let nla = nla.context("invalid TCA_U32_POLICE")?;
+                    nlas.push(
+                        TcActionPoliceOption::parse(&nla)
+                            .context("failed to parse TCA_U32_POLICE")?,
+                    );
+                }
+                Self::Police(nlas)
+            }
             TCA_U32_ACT => {
                 let mut acts = vec![];
                 for act in NlasIterator::new(payload) {
@@ -153,9 +172,24 @@ impl<'a, T: AsRef<[u8]> + ?Sized> Parseable<NlaBuffer<&'a T>>
                 }
                 Self::Action(acts)
             }
-            TCA_U32_INDEV => Self::Indev(payload.to_vec()),
-            TCA_U32_PCNT => Self::Pnct(payload.to_vec()),
-            TCA_U32_MARK => Self::Mark(payload.to_vec()),
+            TCA_U32_INDEV => Self::Indev(
+                parse_string(payload)
+                    .context("failed to parse TCA_U32_INDEV")?,
+            ),
+            TCA_U32_PCNT => Self::Pnct(
+                TcU32Pcnt::parse(
+                    &TcU32PcntBuffer::new_checked(payload)
+                        .context("invalid TCA_U32_PCNT")?,
+                )
+                .context("failed to parse TCA_U32_PCNT")?,
+            ),
+            TCA_U32_MARK => Self::Mark(
+                TcU32Mark::parse(
+                    &TcU32MarkBuffer::new_checked(payload)
+                        .context("invalid TCA_U32_MARK")?,
+                )
+                .context("failed to parse TCA_U32_MARK")?,
+            ),
             TCA_U32_FLAGS => Self::Flags(
                 VecTcU32OptionFlag::from(
                     parse_u32(payload)
@@ -295,3 +329,94 @@ impl<T: AsRef<[u8]>> Parseable<TcU32KeyBuffer<T>> for TcU32Key {
         })
     }
 }
+
+// kernel struct `tc_u32_mark`
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Default)]
+#[non_exhaustive]
+pub struct TcU32Mark {
+    pub val: u32,
+    pub mask: u32,
+    /// Number of packets matched the mark, only set by kernel
+    pub success: u32,
+}
+
+buffer!(TcU32MarkBuffer(TC_U32_MARK_BUF_LEN) {
+    val: (u32, 0..4),
+    mask: (u32, 4..8),
+    success: (u32, 8..TC_U32_MARK_BUF_LEN),
+});
+
+impl Emitable for TcU32Mark {
+    fn buffer_len(&self) -> usize {
+        TC_U32_MARK_BUF_LEN
+    }
+
+    fn emit(&self, buffer: &mut [u8]) {
+        let mut packet = TcU32MarkBuffer::new(buffer);
+        packet.set_val(self.val);
+        packet.set_mask(self.mask);
+        packet.set_success(self.success);
+    }
+}
+
+impl<T: AsRef<[u8]>> Parseable<TcU32MarkBuffer<T>> for TcU32Mark {
+    fn parse(buf: &TcU32MarkBuffer<T>) -> Result<Self, DecodeError> {
+        Ok(Self {
+            val: buf.val(),
+            mask: buf.mask(),
+            success: buf.success(),
+        })
+    }
+}
+
+// kernel struct `tc_u32_pcnt`
+#[derive(Debug, PartialEq, Eq, Clone, Default)]
+#[non_exhaustive]
+pub struct TcU32Pcnt {
+    /// Number of packets looked up by this filter
+    pub rcnt: u64,
+    /// Number of packets matched by this filter
+    pub rhit: u64,
+    /// Number of packets matched by each key of the selector
+    pub kcnts: Vec<u64>,
+}
+
+buffer!(TcU32PcntBuffer(TC_U32_PCNT_BUF_LEN) {
+    rcnt: (u64, 0..8),
+    rhit: (u64, 8..TC_U32_PCNT_BUF_LEN),
+    kcnts: (slice, TC_U32_PCNT_BUF_LEN..),
+});
+
+impl Emitable for TcU32Pcnt {
+    fn buffer_len(&self) -> usize {
+        TC_U32_PCNT_BUF_LEN + self.kcnts.len() * 8
+    }
+
+    fn emit(&self, buffer: &mut [u8]) {
+        let mut packet = TcU32PcntBuffer::new(buffer);
+        packet.set_rcnt(self.rcnt);
+        packet.set_rhit(self.rhit);
+        for (i, kcnt) in self.kcnts.iter().enumerate() {
+            NativeEndian::write_u64(
+                &mut packet.kcnts_mut()[(i * 8)..((i + 1) * 8)],
+                *kcnt,
+            );
+        }
+    }
+}
+
+impl<T: AsRef<[u8]> + ?Sized> Parseable<TcU32PcntBuffer<&T>> for TcU32Pcnt {
+    fn parse(buf: &TcU32PcntBuffer<&T>) -> Result<Self, DecodeError> {
+        let mut kcnts = vec![];
+        for kcnt in buf.kcnts().chunks(8) {
+            kcnts.push(
+                parse_u64(kcnt).context("failed to parse u32 key counter")?,
+            );
+        }
+        Ok(Self {
+            rcnt: buf.rcnt(),
+            rhit: buf.rhit(),
+            kcnts,
+        })
+    }
+}
@@ -0,0 +1,539 @@
+// SPDX-License-Identifier: MIT
+
+/// Extended match
+///
+/// Extended matches (ematches) are small classifiers which can be chained
+/// by logical relations and grouped into sub-expressions, forming an
+/// expression tree. The tree is used by the `basic`, `flow` and `cgroup`
+/// filters.
+use anyhow::Context;
+use byteorder::{ByteOrder, NativeEndian};
+use netlink_packet_utils::{
+    nla::{Nla, NlaBuffer, NlasIterator},
+    parsers::parse_u32,
+    traits::{Emitable, Parseable, ParseableParametrized},
+    DecodeError,
+};
+
+use super::{
+    cls_u32::TcU32KeyBuffer, ematch_cmp::TcEmatchCmpBuffer,
+    ematch_ipset::TcEmatchIpsetBuffer, ematch_nbyte::TcEmatchNbyteBuffer,
+};
+use crate::tc::{
+    TcEmatchCmp, TcEmatchIpset, TcEmatchMetaOption, TcEmatchNbyte, TcU32Key,
+};
+
+const TCA_EMATCH_TREE_HDR: u16 = 1;
+const TCA_EMATCH_TREE_LIST: u16 = 2;
+
+const TCF_EM_REL_END: u16 = 0;
+const TCF_EM_REL_AND: u16 = 1 << 0;
+const TCF_EM_REL_OR: u16 = 1 << 1;
+const TCF_EM_INVERT: u16 = 1 << 2;
+const TCF_EM_REL_MASK: u16 = 3;
+const TCF_EM_REL_FLAGS: u16 = TCF_EM_REL_MASK | TCF_EM_INVERT;
+
+// Default of kernel CONFIG_NET_EMATCH_STACK, deeper trees cannot be matched
+const TCF_EM_MAX_DEPTH: usize = 32;
+
+const TCF_EM_CONTAINER: u16 = 0;
+const TCF_EM_CMP: u16 = 1;
+const TCF_EM_NBYTE: u16 = 2;
+const TCF_EM_U32: u16 = 3;
+const TCF_EM_META: u16 = 4;
+const TCF_EM_IPSET: u16 = 8;
+
+/// The top level sequence of an ematch expression tree. Each term is
+/// evaluated and combined with the next one by its relation until a term
+/// with [TcEmatchRelation::End] is reached.
+#[derive(Debug, PartialEq, Eq, Clone, Default)]
+#[non_exhaustive]
+pub struct TcEmatchTree {
+    pub progid: u16,
+    pub terms: Vec<TcEmatchTerm>,
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Default)]
+#[non_exhaustive]
+pub struct TcEmatchTerm {
+    pub matchid: u16,
+    /// Relation to the next term of the same sequence
+    pub relation: TcEmatchRelation,
+    /// Invert the result of this term
+    pub invert: bool,
+    /// Remaining bits of the ematch header flags besides the relation and
+    /// `invert`, e.g. `TCF_EM_SIMPLE` (0x8)
+    pub flags: u16,
+    pub expr: TcEmatchExpr,
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Default)]
+#[non_exhaustive]
+pub enum TcEmatchRelation {
+    /// Last term of the sequence
+    #[default]
+    End,
+    And,
+    Or,
+}
+
+impl TryFrom<u16> for TcEmatchRelation {
+    type Error = DecodeError;
+
+    fn try_from(d: u16) -> Result<Self, Self::Error> {
+        match d & TCF_EM_REL_MASK {
+            TCF_EM_REL_END => Ok(Self::End),
+            TCF_EM_REL_AND => Ok(Self::And),
+            TCF_EM_REL_OR => Ok(Self::Or),
+            _ => Err(DecodeError::from(format!(
+                "Invalid ematch relation in flags {d:#x}"
+            ))),
+        }
+    }
+}
+
+impl From<TcEmatchRelation> for u16 {
+    fn from(v: TcEmatchRelation) -> u16 {
+        match v {
+            TcEmatchRelation::End => TCF_EM_REL_END,
+            TcEmatchRelation::And => TCF_EM_REL_AND,
+            TcEmatchRelation::Or => TCF_EM_REL_OR,
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Clone)]
+#[non_exhaustive]
+pub enum TcEmatchExpr {
+    /// Nested sequence of terms evaluated as a single term, stored in
+    /// kernel as a container ematch referencing the nested sequence.
+    Group(Vec<TcEmatchTerm>),
+    Cmp(TcEmatchCmp),
+    Nbyte(TcEmatchNbyte),
+    U32(TcU32Key),
+    Meta(Vec<TcEmatchMetaOption>),
+    Ipset(TcEmatchIpset),
+    Other(TcEmatchOther),
+}
+
+impl Default for TcEmatchExpr {
+    fn default() -> Self {
+        Self::Group(Vec::new())
+    }
+}
+
+/// Ematch not supported by this crate yet, holding the raw kind and data.
+#[derive(Debug, PartialEq, Eq, Clone, Default)]
+#[non_exhaustive]
+pub struct TcEmatchOther {
+    pub kind: u16,
+    pub data: Vec<u8>,
+}
+
+impl TcEmatchExpr {
+    fn kind(&self) -> u16 {
+        match self {
+            Self::Group(_) => TCF_EM_CONTAINER,
+            Self::Cmp(_) => TCF_EM_CMP,
+            Self::Nbyte(_) => TCF_EM_NBYTE,
+            Self::U32(_) => TCF_EM_U32,
+            Self::Meta(_) => TCF_EM_META,
+            Self::Ipset(_) => TCF_EM_IPSET,
+            Self::Other(v) => v.kind,
+        }
+    }
+
+    // The data of `Group` depends on its position in the flattened list,
+    // hence it is filled by `flatten_terms()` instead.
+    fn data(&self) -> Vec<u8> {
+        fn emit_to_vec<E: Emitable + ?Sized>(v: &E) -> Vec<u8> {
+            let mut buffer = vec![0u8; v.buffer_len()];
+            v.emit(&mut buffer);
+            buffer
+        }
+        match self {
+            Self::Group(_) => Vec::new(),
+            Self::Cmp(v) => emit_to_vec(v),
+            Self::Nbyte(v) => emit_to_vec(v),
+            Self::U32(v) => emit_to_vec(v),
+            Self::Meta(v) => emit_to_vec(&v.as_slice()),
+            Self::Ipset(v) => emit_to_vec(v),
+            Self::Other(v) => v.data.clone(),
+        }
+    }
+}
+
+impl ParseableParametrized<[u8], u16> for TcEmatchExpr {
+    fn parse_with_param(buf: &[u8], kind: u16) -> Result<Self, DecodeError> {
+        Ok(match kind {
+            TCF_EM_CMP => Self::Cmp(
+                TcEmatchCmp::parse(
+                    &TcEmatchCmpBuffer::new_checked(buf)
+                        .context("invalid cmp ematch")?,
+                )
+                .context("failed to parse cmp ematch")?,
+            ),
+            TCF_EM_NBYTE => Self::Nbyte(
+                TcEmatchNbyte::parse(
+                    &TcEmatchNbyteBuffer::new_checked(buf)
+                        .context("invalid nbyte ematch")?,
+                )
+                .context("failed to parse nbyte ematch")?,
+            ),
+            TCF_EM_U32 => Self::U32(
+                TcU32Key::parse(
+                    &TcU32KeyBuffer::new_checked(buf)
+                        .context("invalid u32 ematch")?,
+                )
+                .context("failed to parse u32 ematch")?,
+            ),
+            TCF_EM_META => {
+                let mut nlas = vec![];
+                for nla in NlasIterator::new(buf) {
+                    let nla = nla.context("invalid meta ematch")?;
+                    nlas.push(
+                        TcEmatchMetaOption::parse(&nla)
+                            .context("failed to parse meta ematch")?,
+                    );
+                }
+                Self::Meta(nlas)
+            }
+            TCF_EM_IPSET => Self::Ipset(
+                TcEmatchIpset::parse(
+                    &TcEmatchIpsetBuffer::new_checked(buf)
+                        .context("invalid ipset ematch")?,
+                )
+                .context("failed to parse ipset ematch")?,
+            ),
+            _ => Self::Other(TcEmatchOther {
+                kind,
+                data: buf.to_vec(),
+            }),
+        })
+    }
+}
+
+const TC_EMATCH_TREE_HDR_BUF_LEN: usize = 4;
+
+buffer!(TcEmatchTreeHeaderBuffer(TC_EMATCH_TREE_HDR_BUF_LEN) {
+    nmatches: (u16, 0..2),
+    progid: (u16, 2..TC_EMATCH_TREE_HDR_BUF_LEN),
+});
+
+const TC_EMATCH_HDR_BUF_LEN: usize = 8;
+
+buffer!(TcEmatchHeaderBuffer(TC_EMATCH_HDR_BUF_LEN) {
+    matchid: (u16, 0..2),
+    kind: (u16, 2..4),
+    flags: (u16, 4..6),
+    pad: (u16, 6..TC_EMATCH_HDR_BUF_LEN),
+    data: (slice, TC_EMATCH_HDR_BUF_LEN..),
+});
+
+// One entry of `TCA_EMATCH_TREE_LIST`, the kernel `tcf_ematch_hdr`
+// followed by the ematch specific data.
+#[derive(Debug, PartialEq, Eq, Clone)]
+struct TcEmatchEntry {
+    index: u16,
+    matchid: u16,
+    kind: u16,
+    flags: u16,
+    data: Vec<u8>,
+}
+
+impl Nla for TcEmatchEntry {
+    fn value_len(&self) -> usize {
+        TC_EMATCH_HDR_BUF_LEN + self.data.len()
+    }
+
+    fn emit_value(&self, buffer: &mut [u8]) {
+        let mut packet = TcEmatchHeaderBuffer::new(buffer);
+        packet.set_matchid(self.matchid);
+        packet.set_kind(self.kind);
+        packet.set_flags(self.flags);
+        packet.set_pad(0);
+        packet.data_mut().copy_from_slice(self.data.as_slice());
+    }
+
+    fn kind(&self) -> u16 {
+        self.index + 1
+    }
+}
+
+impl<'a, T: AsRef<[u8]> + ?Sized> Parseable<NlaBuffer<&'a T>>
+    for TcEmatchEntry
+{
+    fn parse(buf: &NlaBuffer<&'a T>) -> Result<Self, DecodeError> {
+        let hdr = TcEmatchHeaderBuffer::new_checked(buf.value())
+            .context("invalid tcf_ematch_hdr")?;
+        Ok(Self {
+            index: buf.kind().saturating_sub(1),
+            matchid: hdr.matchid(),
+            kind: hdr.kind(),
+            flags: hdr.flags(),
+            data: hdr.data().to_vec(),
+        })
+    }
+}
+
+// Flatten the terms into the list layout generated by iproute2: the
+// terms of a sequence are placed next to each other, followed by the
+// nested sequences in the order they are referenced.
+fn flatten_terms(terms: &[TcEmatchTerm], entries: &mut Vec<TcEmatchEntry>) {
+    let start = entries.len();
+    for term in terms {
+        let mut flags =
+            u16::from(term.relation) | (term.flags & !TCF_EM_REL_FLAGS);
+        if term.invert {
+            flags |= TCF_EM_INVERT;
+        }
+        entries.push(TcEmatchEntry {
+            index: entries.len() as u16,
+            matchid: term.matchid,
+            kind: term.expr.kind(),
+            flags,
+            data: term.expr.data(),
+        });
+    }
+    for (i, term) in terms.iter().enumerate() {
+        if let TcEmatchExpr::Group(nested) = &term.expr {
+            let mut data = vec![0u8; 4];
+            NativeEndian::write_u32(&mut data, entries.len() as u32);
+            entries[start + i].data = data;
+            flatten_terms(nested, entries);
+        }
+    }
+}
+
+// The kernel allows several containers to reference the same sequence,
+// which cannot be represented by `TcEmatchTree`, hence every entry is only
+// allowed to be parsed once.
+fn parse_terms(
+    entries: &[TcEmatchEntry],
+    start: usize,
+    parsed: &mut [bool],
+    depth: usize,
+) -> Result<Vec<TcEmatchTerm>, DecodeError> {
+    if depth > TCF_EM_MAX_DEPTH {
+        return Err(DecodeError::from(format!(
+            "Ematch tree deeper than {TCF_EM_MAX_DEPTH} at {start}"
+        )));
+    }
+    let mut terms = Vec::new();
+    for (i, entry) in entries.iter().enumerate().skip(start) {
+        if parsed[i] {
+            return Err(DecodeError::from(format!(
+                "Ematch {i} is referenced more than once"
+            )));
+        }
+        parsed[i] = true;
+        let relation = TcEmatchRelation::try_from(entry.flags)?;
+        let expr = if entry.kind == TCF_EM_CONTAINER {
+            let nested = parse_u32(&entry.data)
+                .context("failed to parse container ematch")?
+                as usize;
+            // Like kernel, only forward references are allowed to avoid
+            // loops.
+            if nested <= i {
+                return Err(DecodeError::from(format!(
+                    "Invalid container ematch reference {nested} at {i}"
+                )));
+            }
+            TcEmatchExpr::Group(parse_terms(
+                entries,
+                nested,
+                parsed,
+                depth + 1,
+            )?)
+        } else {
+            TcEmatchExpr::parse_with_param(entry.data.as_slice(), entry.kind)?
+        };
+        terms.push(TcEmatchTerm {
+            matchid: entry.matchid,
+            relation,
+            invert: entry.flags & TCF_EM_INVERT > 0,
+            flags: entry.flags & !TCF_EM_REL_FLAGS,
+            expr,
+        });
+        if relation == TcEmatchRelation::End {
+            return Ok(terms);
+        }
+    }
+    Err(DecodeError::from(format!(
+        "Unterminated ematch sequence starting at {start}"
+    )))
+}
+
+#[derive(Debug, PartialEq, Eq, Clone)]
+enum TcEmatchTreeAttr {
+    Header { nmatches: u16, progid: u16 },
+    List(Vec<TcEmatchEntry>),
+}
+
+impl Nla for TcEmatchTreeAttr {
+    fn value_len(&self) -> usize {
+        match self {
+            Self::Header { .. } => TC_EMATCH_TREE_HDR_BUF_LEN,
+            Self::List(entries) => entries.as_slice().buffer_len(),
+        }
+    }
+
+    fn emit_value(&self, buffer: &mut [u8]) {
+        match self {
+            Self::Header { nmatches, progid } => {
+                let mut packet = TcEmatchTreeHeaderBuffer::new(buffer);
+                packet.set_nmatches(*nmatches);
+                packet.set_progid(*progid);
+            }
+            Self::List(entries) => entries.as_slice().emit(buffer),
+        }
+    }
+
+    fn kind(&self) -> u16 {
+        match self {
+            Self::Header { .. } => TCA_EMATCH_TREE_HDR,
+            Self::List(_) => TCA_EMATCH_TREE_LIST,
+        }
+    }
+}
+
+impl TcEmatchTree {
+    fn attributes(&self) -> [TcEmatchTreeAttr; 2] {
+        let mut entries = Vec::new();
+        flatten_terms(self.terms.as_slice(), &mut entries);
+        [
+            TcEmatchTreeAttr::Header {
+                nmatches: entries.len() as u16,
+                progid: self.progid,
+            },
+            TcEmatchTreeAttr::List(entries),
+        ]
+    }
+}
+
+impl Emitable for TcEmatchTree {
+    fn buffer_len(&self) -> usize {
+        self.attributes().as_slice().buffer_len()
+    }
+
+    fn emit(&self, buffer: &mut [u8]) {
+        self.attributes().as_slice().emit(buffer)
+    }
+}
+
+impl<T: AsRef<[u8]> + ?Sized> Parseable<T> for TcEmatchTree {
+    fn parse(buf: &T) -> Result<Self, DecodeError> {
+        let mut progid = 0;
+        let mut entries = Vec::new();
+        for nla in NlasIterator::new(buf.as_ref()) {
+            let nla = nla.context("invalid ematch tree")?;
+            match nla.kind() {
+                TCA_EMATCH_TREE_HDR => {
+                    progid = TcEmatchTreeHeaderBuffer::new_checked(nla.value())
+                        .context("invalid TCA_EMATCH_TREE_HDR")?
+                        .progid();
+                }
+                TCA_EMATCH_TREE_LIST => {
+                    for entry in NlasIterator::new(nla.value()) {
+                        let entry =
+                            entry.context("invalid TCA_EMATCH_TREE_LIST")?;
+                        entries.push(
+                            TcEmatchEntry::parse(&entry).context(
+                                "failed to parse TCA_EMATCH_TREE_LIST",
+                            )?,
+                        );
+                    }
+                }
+                kind => {
+                    return Err(DecodeError::from(format!(
+                        "Unknown ematch tree attribute {kind}"
+                    )))
+                }
+            }
+        }
+        let terms = if entries.is_empty() {
+            Vec::new()
+        } else {
+            parse_terms(
+                entries.as_slice(),
+                0,
+                &mut vec![false; entries.len()],
+                0,
+            )?
+        };
+        Ok(Self { progid, terms })
+    }
+}
+
+const TCF_LAYER_LINK: u8 = 0;
+const TCF_LAYER_NETWORK: u8 = 1;
+const TCF_LAYER_TRANSPORT: u8 = 2;
+
+/// Header the offset of ematch is relative to
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Default)]
+#[non_exhaustive]
+pub enum TcEmatchLayer {
+    #[default]
+    Link,
+    Network,
+    Transport,
+    Other(u8),
+}
+
+impl From<u8> for TcEmatchLayer {
+    fn from(d: u8) -> Self {
+        match d {
+            TCF_LAYER_LINK => Self::Link,
+            TCF_LAYER_NETWORK => Self::Network,
+            TCF_LAYER_TRANSPORT => Self::Transport,
+            _ => Self::Other(d),
+        }
+    }
+}
+
+impl From<TcEmatchLayer> for u8 {
+    fn from(v: TcEmatchLayer) -> u8 {
+        match v {
+            TcEmatchLayer::Link => TCF_LAYER_LINK,
+            TcEmatchLayer::Network => TCF_LAYER_NETWORK,
+            TcEmatchLayer::Transport => TCF_LAYER_TRANSPORT,
+            TcEmatchLayer::Other(d) => d,
+        }
+    }
+}
+
+const TCF_EM_OPND_EQ: u8 = 0;
+const TCF_EM_OPND_GT: u8 = 1;
+const TCF_EM_OPND_LT: u8 = 2;
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Default)]
+#[non_exhaustive]
+pub enum TcEmatchOperand {
+    #[default]
+    Eq,
+    Gt,
+    Lt,
+    Other(u8),
+}
+
+impl From<u8> for TcEmatchOperand {
+    fn from(d: u8) -> Self {
+        match d {
+            TCF_EM_OPND_EQ => Self::Eq,
+            TCF_EM_OPND_GT => Self::Gt,
+            TCF_EM_OPND_LT => Self::Lt,
+            _ => Self::Other(d),
+        }
+    }
+}
+
+impl From<TcEmatchOperand> for u8 {
+    fn from(v: TcEmatchOperand) -> u8 {
+        match v {
+            TcEmatchOperand::Eq => TCF_EM_OPND_EQ,
+            TcEmatchOperand::Gt => TCF_EM_OPND_GT,
+            TcEmatchOperand::Lt => TCF_EM_OPND_LT,
+            TcEmatchOperand::Other(d) => d,
+        }
+    }
+}
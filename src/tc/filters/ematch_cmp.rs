@@ -0,0 +1,108 @@
+// SPDX-License-Identifier: MIT
+
+/// Compare ematch
+///
+/// Compares a 8, 16 or 32 bits value at an offset of packet header with
+/// the specified value.
+use netlink_packet_utils::{
+    traits::{Emitable, Parseable},
+    DecodeError,
+};
+
+use crate::tc::{TcEmatchLayer, TcEmatchOperand};
+
+const TC_EMATCH_CMP_BUF_LEN: usize = 12;
+
+// kernel struct `tcf_em_cmp`
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Default)]
+#[non_exhaustive]
+pub struct TcEmatchCmp {
+    pub val: u32,
+    pub mask: u32,
+    pub off: u16,
+    pub align: TcEmatchCmpAlign,
+    /// `TCF_EM_CMP_TRANS` (1) converts the packet data to host byte
+    /// order before comparing
+    pub flags: u8,
+    pub layer: TcEmatchLayer,
+    pub opnd: TcEmatchOperand,
+}
+
+buffer!(TcEmatchCmpBuffer(TC_EMATCH_CMP_BUF_LEN) {
+    val: (u32, 0..4),
+    mask: (u32, 4..8),
+    off: (u16, 8..10),
+    // align:4 and flags:4 bitfields
+    align_flags: (u8, 10),
+    // layer:4 and opnd:4 bitfields
+    layer_opnd: (u8, 11),
+});
+
+impl Emitable for TcEmatchCmp {
+    fn buffer_len(&self) -> usize {
+        TC_EMATCH_CMP_BUF_LEN
+    }
+
+    fn emit(&self, buffer: &mut [u8]) {
+        let mut packet = TcEmatchCmpBuffer::new(buffer);
+        packet.set_val(self.val);
+        packet.set_mask(self.mask);
+        packet.set_off(self.off);
+        packet
+            .set_align_flags((u8::from(self.align) & 0xf) | (self.flags << 4));
+        packet.set_layer_opnd(
+            (u8::from(self.layer) & 0xf) | (u8::from(self.opnd) << 4),
+        );
+    }
+}
+
+impl<T: AsRef<[u8]>> Parseable<TcEmatchCmpBuffer<T>> for TcEmatchCmp {
+    fn parse(buf: &TcEmatchCmpBuffer<T>) -> Result<Self, DecodeError> {
+        Ok(Self {
+            val: buf.val(),
+            mask: buf.mask(),
+            off: buf.off(),
+            align: (buf.align_flags() & 0xf).into(),
+            flags: buf.align_flags() >> 4,
+            layer: (buf.layer_opnd() & 0xf).into(),
+            opnd: (buf.layer_opnd() >> 4).into(),
+        })
+    }
+}
+
+const TCF_EM_ALIGN_U8: u8 = 1;
+const TCF_EM_ALIGN_U16: u8 = 2;
+const TCF_EM_ALIGN_U32: u8 = 4;
+
+/// Size of the value to compare
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Default)]
+#[non_exhaustive]
+pub enum TcEmatchCmpAlign {
+    #[default]
+    U8,
+    U16,
+    U32,
+    Other(u8),
+}
+
+impl From<u8> for TcEmatchCmpAlign {
+    fn from(d: u8) -> Self {
+        match d {
+            TCF_EM_ALIGN_U8 => Self::U8,
+            TCF_EM_ALIGN_U16 => Self::U16,
+            TCF_EM_ALIGN_U32 => Self::U32,
+            _ => Self::Other(d),
+        }
+    }
+}
+
+impl From<TcEmatchCmpAlign> for u8 {
+    fn from(v: TcEmatchCmpAlign) -> u8 {
+        match v {
+            TcEmatchCmpAlign::U8 => TCF_EM_ALIGN_U8,
+            TcEmatchCmpAlign::U16 => TCF_EM_ALIGN_U16,
+            TcEmatchCmpAlign::U32 => TCF_EM_ALIGN_U32,
+            TcEmatchCmpAlign::Other(d) => d,
+        }
+    }
+}
@@ -0,0 +1,53 @@
+// SPDX-License-Identifier: MIT
+
+/// IP set ematch
+///
+/// Matches packets against an IP set of netfilter.
+use netlink_packet_utils::{
+    traits::{Emitable, Parseable},
+    DecodeError,
+};
+
+const TC_EMATCH_IPSET_BUF_LEN: usize = 4;
+
+// kernel struct `xt_set_info`
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Default)]
+#[non_exhaustive]
+pub struct TcEmatchIpset {
+    /// Index of the IP set
+    pub index: u16,
+    /// Number of dimensions to match
+    pub dim: u8,
+    /// Bit N set means matching dimension N by source address or port,
+    /// otherwise by destination. Bit 0 inverts the match.
+    pub flags: u8,
+}
+
+buffer!(TcEmatchIpsetBuffer(TC_EMATCH_IPSET_BUF_LEN) {
+    index: (u16, 0..2),
+    dim: (u8, 2),
+    flags: (u8, 3),
+});
+
+impl Emitable for TcEmatchIpset {
+    fn buffer_len(&self) -> usize {
+        TC_EMATCH_IPSET_BUF_LEN
+    }
+
+    fn emit(&self, buffer: &mut [u8]) {
+        let mut packet = TcEmatchIpsetBuffer::new(buffer);
+        packet.set_index(self.index);
+        packet.set_dim(self.dim);
+        packet.set_flags(self.flags);
+    }
+}
+
+impl<T: AsRef<[u8]>> Parseable<TcEmatchIpsetBuffer<T>> for TcEmatchIpset {
+    fn parse(buf: &TcEmatchIpsetBuffer<T>) -> Result<Self, DecodeError> {
+        Ok(Self {
+            index: buf.index(),
+            dim: buf.dim(),
+            flags: buf.flags(),
+        })
+    }
+}
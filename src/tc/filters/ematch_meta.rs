@@ -0,0 +1,419 @@
+// SPDX-License-Identifier: MIT
+
+/// Metadata ematch
+///
+/// Compares metadata of packet or its socket, for example firewall mark,
+/// priority or input interface, with a value or another metadata.
+use anyhow::Context;
+use netlink_packet_utils::{
+    nla::{DefaultNla, Nla, NlaBuffer},
+    traits::{Emitable, Parseable},
+    DecodeError,
+};
+
+use crate::tc::TcEmatchOperand;
+
+const TCA_EM_META_HDR: u16 = 1;
+const TCA_EM_META_LVALUE: u16 = 2;
+const TCA_EM_META_RVALUE: u16 = 3;
+
+#[derive(Debug, PartialEq, Eq, Clone)]
+#[non_exhaustive]
+pub enum TcEmatchMetaOption {
+    Header(TcEmatchMetaHeader),
+    /// Value of the left operand, only used when its id is
+    /// [TcEmatchMetaId::Value]. Native endian u32 for integer type.
+    Lvalue(Vec<u8>),
+    /// Value of the right operand, only used when its id is
+    /// [TcEmatchMetaId::Value]. Native endian u32 for integer type.
+    Rvalue(Vec<u8>),
+    Other(DefaultNla),
+}
+
+impl Nla for TcEmatchMetaOption {
+    fn value_len(&self) -> usize {
+        match self {
+            Self::Header(_) => TC_EMATCH_META_HDR_BUF_LEN,
+            Self::Lvalue(v) | Self::Rvalue(v) => v.len(),
+            Self::Other(attr) => attr.value_len(),
+        }
+    }
+
+    fn emit_value(&self, buffer: &mut [u8]) {
+        match self {
+            Self::Header(v) => v.emit(buffer),
+            Self::Lvalue(v) | Self::Rvalue(v) => {
+                buffer.copy_from_slice(v.as_slice())
+            }
+            Self::Other(attr) => attr.emit_value(buffer),
+        }
+    }
+
+    fn kind(&self) -> u16 {
+        match self {
+            Self::Header(_) => TCA_EM_META_HDR,
+            Self::Lvalue(_) => TCA_EM_META_LVALUE,
+            Self::Rvalue(_) => TCA_EM_META_RVALUE,
+            Self::Other(attr) => attr.kind(),
+        }
+    }
+}
+
+impl<'a, T: AsRef<[u8]> + ?Sized> Parseable<NlaBuffer<&'a T>>
+    for TcEmatchMetaOption
+{
+    fn parse(buf: &NlaBuffer<&'a T>) -> Result<Self, DecodeError> {
+        let payload = buf.value();
+        Ok(match buf.kind() {
+            TCA_EM_META_HDR => Self::Header(
+                TcEmatchMetaHeader::parse(
+                    &TcEmatchMetaHeaderBuffer::new_checked(payload)
+                        .context("invalid TCA_EM_META_HDR")?,
+                )
+                .context("failed to parse TCA_EM_META_HDR")?,
+            ),
+            TCA_EM_META_LVALUE => Self::Lvalue(payload.to_vec()),
+            TCA_EM_META_RVALUE => Self::Rvalue(payload.to_vec()),
+            _ => Self::Other(
+                DefaultNla::parse(buf).context("failed to parse meta nla")?,
+            ),
+        })
+    }
+}
+
+const TC_EMATCH_META_HDR_BUF_LEN: usize = 8;
+
+// kernel struct `tcf_meta_hdr`
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Default)]
+#[non_exhaustive]
+pub struct TcEmatchMetaHeader {
+    pub left: TcEmatchMetaValue,
+    pub right: TcEmatchMetaValue,
+}
+
+buffer!(TcEmatchMetaHeaderBuffer(TC_EMATCH_META_HDR_BUF_LEN) {
+    left_kind: (u16, 0..2),
+    left_shift: (u8, 2),
+    left_op: (u8, 3),
+    right_kind: (u16, 4..6),
+    right_shift: (u8, 6),
+    right_op: (u8, 7),
+});
+
+impl Emitable for TcEmatchMetaHeader {
+    fn buffer_len(&self) -> usize {
+        TC_EMATCH_META_HDR_BUF_LEN
+    }
+
+    fn emit(&self, buffer: &mut [u8]) {
+        let mut packet = TcEmatchMetaHeaderBuffer::new(buffer);
+        packet.set_left_kind(self.left.kind());
+        packet.set_left_shift(self.left.shift);
+        packet.set_left_op(self.left.op.into());
+        packet.set_right_kind(self.right.kind());
+        packet.set_right_shift(self.right.shift);
+        packet.set_right_op(self.right.op.into());
+    }
+}
+
+impl<T: AsRef<[u8]>> Parseable<TcEmatchMetaHeaderBuffer<T>>
+    for TcEmatchMetaHeader
+{
+    fn parse(buf: &TcEmatchMetaHeaderBuffer<T>) -> Result<Self, DecodeError> {
+        Ok(Self {
+            left: TcEmatchMetaValue::new(
+                buf.left_kind(),
+                buf.left_shift(),
+                buf.left_op(),
+            ),
+            right: TcEmatchMetaValue::new(
+                buf.right_kind(),
+                buf.right_shift(),
+                buf.right_op(),
+            ),
+        })
+    }
+}
+
+const TCF_META_TYPE_SHIFT: u16 = 12;
+const TCF_META_ID_MASK: u16 = 0x7ff;
+
+// kernel struct `tcf_meta_val`
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Default)]
+#[non_exhaustive]
+pub struct TcEmatchMetaValue {
+    pub value_type: TcEmatchMetaType,
+    pub id: TcEmatchMetaId,
+    /// Right shift applied to the value before comparing
+    pub shift: u8,
+    /// Operand, only used by the left value
+    pub op: TcEmatchOperand,
+}
+
+impl TcEmatchMetaValue {
+    fn new(kind: u16, shift: u8, op: u8) -> Self {
+        Self {
+            value_type: ((kind >> TCF_META_TYPE_SHIFT) as u8).into(),
+            id: (kind & TCF_META_ID_MASK).into(),
+            shift,
+            op: op.into(),
+        }
+    }
+
+    fn kind(&self) -> u16 {
+        (u16::from(u8::from(self.value_type)) << TCF_META_TYPE_SHIFT)
+            | (u16::from(self.id) & TCF_META_ID_MASK)
+    }
+}
+
+const TCF_META_TYPE_VAR: u8 = 0;
+const TCF_META_TYPE_INT: u8 = 1;
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Default)]
+#[non_exhaustive]
+pub enum TcEmatchMetaType {
+    /// Variable length value, e.g. interface name
+    #[default]
+    Var,
+    Int,
+    Other(u8),
+}
+
+impl From<u8> for TcEmatchMetaType {
+    fn from(d: u8) -> Self {
+        match d {
+            TCF_META_TYPE_VAR => Self::Var,
+            TCF_META_TYPE_INT => Self::Int,
+            _ => Self::Other(d),
+        }
+    }
+}
+
+impl From<TcEmatchMetaType> for u8 {
+    fn from(v: TcEmatchMetaType) -> u8 {
+        match v {
+            TcEmatchMetaType::Var => TCF_META_TYPE_VAR,
+            TcEmatchMetaType::Int => TCF_META_TYPE_INT,
+            TcEmatchMetaType::Other(d) => d,
+        }
+    }
+}
+
+const TCF_META_ID_VALUE: u16 = 0;
+const TCF_META_ID_RANDOM: u16 = 1;
+const TCF_META_ID_LOADAVG_0: u16 = 2;
+const TCF_META_ID_LOADAVG_1: u16 = 3;
+const TCF_META_ID_LOADAVG_2: u16 = 4;
+const TCF_META_ID_DEV: u16 = 5;
+const TCF_META_ID_PRIORITY: u16 = 6;
+const TCF_META_ID_PROTOCOL: u16 = 7;
+const TCF_META_ID_PKTTYPE: u16 = 8;
+const TCF_META_ID_PKTLEN: u16 = 9;
+const TCF_META_ID_DATALEN: u16 = 10;
+const TCF_META_ID_MACLEN: u16 = 11;
+const TCF_META_ID_NFMARK: u16 = 12;
+const TCF_META_ID_TCINDEX: u16 = 13;
+const TCF_META_ID_RTCLASSID: u16 = 14;
+const TCF_META_ID_RTIIF: u16 = 15;
+const TCF_META_ID_SK_FAMILY: u16 = 16;
+const TCF_META_ID_SK_STATE: u16 = 17;
+const TCF_META_ID_SK_REUSE: u16 = 18;
+const TCF_META_ID_SK_BOUND_IF: u16 = 19;
+const TCF_META_ID_SK_REFCNT: u16 = 20;
+const TCF_META_ID_SK_SHUTDOWN: u16 = 21;
+const TCF_META_ID_SK_PROTO: u16 = 22;
+const TCF_META_ID_SK_TYPE: u16 = 23;
+const TCF_META_ID_SK_RCVBUF: u16 = 24;
+const TCF_META_ID_SK_RMEM_ALLOC: u16 = 25;
+const TCF_META_ID_SK_WMEM_ALLOC: u16 = 26;
+const TCF_META_ID_SK_OMEM_ALLOC: u16 = 27;
+const TCF_META_ID_SK_WMEM_QUEUED: u16 = 28;
+const TCF_META_ID_SK_RCV_QLEN: u16 = 29;
+const TCF_META_ID_SK_SND_QLEN: u16 = 30;
+const TCF_META_ID_SK_ERR_QLEN: u16 = 31;
+const TCF_META_ID_SK_FORWARD_ALLOCS: u16 = 32;
+const TCF_META_ID_SK_SNDBUF: u16 = 33;
+const TCF_META_ID_SK_ALLOCS: u16 = 34;
+// `__TCF_META_ID_SK_ROUTE_CAPS` in kernel
+const TCF_META_ID_SK_ROUTE_CAPS: u16 = 35;
+const TCF_META_ID_SK_HASH: u16 = 36;
+const TCF_META_ID_SK_LINGERTIME: u16 = 37;
+const TCF_META_ID_SK_ACK_BACKLOG: u16 = 38;
+const TCF_META_ID_SK_MAX_ACK_BACKLOG: u16 = 39;
+const TCF_META_ID_SK_PRIO: u16 = 40;
+const TCF_META_ID_SK_RCVLOWAT: u16 = 41;
+const TCF_META_ID_SK_RCVTIMEO: u16 = 42;
+const TCF_META_ID_SK_SNDTIMEO: u16 = 43;
+const TCF_META_ID_SK_SENDMSG_OFF: u16 = 44;
+const TCF_META_ID_SK_WRITE_PENDING: u16 = 45;
+const TCF_META_ID_VLAN_TAG: u16 = 46;
+const TCF_META_ID_RXHASH: u16 = 47;
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Default)]
+#[non_exhaustive]
+pub enum TcEmatchMetaId {
+    /// Value specified by `Lvalue` or `Rvalue`
+    #[default]
+    Value,
+    Random,
+    LoadAvg0,
+    LoadAvg1,
+    LoadAvg2,
+    Dev,
+    Priority,
+    Protocol,
+    PktType,
+    PktLen,
+    DataLen,
+    MacLen,
+    NfMark,
+    TcIndex,
+    RtClassId,
+    RtIif,
+    SkFamily,
+    SkState,
+    SkReuse,
+    SkBoundIf,
+    SkRefCnt,
+    SkShutdown,
+    SkProto,
+    SkType,
+    SkRcvBuf,
+    SkRmemAlloc,
+    SkWmemAlloc,
+    SkOmemAlloc,
+    SkWmemQueued,
+    SkRcvQlen,
+    SkSndQlen,
+    SkErrQlen,
+    SkForwardAllocs,
+    SkSndBuf,
+    SkAllocs,
+    /// Reserved in kernel ABI but never implemented
+    SkRouteCaps,
+    SkHash,
+    SkLingerTime,
+    SkAckBacklog,
+    SkMaxAckBacklog,
+    SkPrio,
+    SkRcvLowat,
+    SkRcvTimeo,
+    SkSndTimeo,
+    SkSendmsgOff,
+    SkWritePending,
+    VlanTag,
+    RxHash,
+    Other(u16),
+}
+
+impl From<u16> for TcEmatchMetaId {
+    fn from(d: u16) -> Self {
+        match d {
+            TCF_META_ID_VALUE => Self::Value,
+            TCF_META_ID_RANDOM => Self::Random,
+            TCF_META_ID_LOADAVG_0 => Self::LoadAvg0,
+            TCF_META_ID_LOADAVG_1 => Self::LoadAvg1,
+            TCF_META_ID_LOADAVG_2 => Self::LoadAvg2,
+            TCF_META_ID_DEV => Self::Dev,
+            TCF_META_ID_PRIORITY => Self::Priority,
+            TCF_META_ID_PROTOCOL => Self::Protocol,
+            TCF_META_ID_PKTTYPE => Self::PktType,
+            TCF_META_ID_PKTLEN => Self::PktLen,
+            TCF_META_ID_DATALEN => Self::DataLen,
+            TCF_META_ID_MACLEN => Self::MacLen,
+            TCF_META_ID_NFMARK => Self::NfMark,
+            TCF_META_ID_TCINDEX => Self::TcIndex,
+            TCF_META_ID_RTCLASSID => Self::RtClassId,
+            TCF_META_ID_RTIIF => Self::RtIif,
+            TCF_META_ID_SK_FAMILY => Self::SkFamily,
+            TCF_META_ID_SK_STATE => Self::SkState,
+            TCF_META_ID_SK_REUSE => Self::SkReuse,
+            TCF_META_ID_SK_BOUND_IF => Self::SkBoundIf,
+            TCF_META_ID_SK_REFCNT => Self::SkRefCnt,
+            TCF_META_ID_SK_SHUTDOWN => Self::SkShutdown,
+            TCF_META_ID_SK_PROTO => Self::SkProto,
+            TCF_META_ID_SK_TYPE => Self::SkType,
+            TCF_META_ID_SK_RCVBUF => Self::SkRcvBuf,
+            TCF_META_ID_SK_RMEM_ALLOC => Self::SkRmemAlloc,
+            TCF_META_ID_SK_WMEM_ALLOC => Self::SkWmemAlloc,
+            TCF_META_ID_SK_OMEM_ALLOC => Self::SkOmemAlloc,
+            TCF_META_ID_SK_WMEM_QUEUED => Self::SkWmemQueued,
+            TCF_META_ID_SK_RCV_QLEN => Self::SkRcvQlen,
+            TCF_META_ID_SK_SND_QLEN => Self::SkSndQlen,
+            TCF_META_ID_SK_ERR_QLEN => Self::SkErrQlen,
+            TCF_META_ID_SK_FORWARD_ALLOCS => Self::SkForwardAllocs,
+            TCF_META_ID_SK_SNDBUF => Self::SkSndBuf,
+            TCF_META_ID_SK_ALLOCS => Self::SkAllocs,
+            TCF_META_ID_SK_ROUTE_CAPS => Self::SkRouteCaps,
+            TCF_META_ID_SK_HASH => Self::SkHash,
+            TCF_META_ID_SK_LINGERTIME => Self::SkLingerTime,
+            TCF_META_ID_SK_ACK_BACKLOG => Self::SkAckBacklog,
+            TCF_META_ID_SK_MAX_ACK_BACKLOG => Self::SkMaxAckBacklog,
+            TCF_META_ID_SK_PRIO => Self::SkPrio,
+            TCF_META_ID_SK_RCVLOWAT => Self::SkRcvLowat,
+            TCF_META_ID_SK_RCVTIMEO => Self::SkRcvTimeo,
+            TCF_META_ID_SK_SNDTIMEO => Self::SkSndTimeo,
+            TCF_META_ID_SK_SENDMSG_OFF => Self::SkSendmsgOff,
+            TCF_META_ID_SK_WRITE_PENDING => Self::SkWritePending,
+            TCF_META_ID_VLAN_TAG => Self::VlanTag,
+            TCF_META_ID_RXHASH => Self::RxHash,
+            _ => Self::Other(d),
+        }
+    }
+}
+
+impl From<TcEmatchMetaId> for u16 {
+    fn from(v: TcEmatchMetaId) -> u16 {
+        match v {
+            TcEmatchMetaId::Value => TCF_META_ID_VALUE,
+            TcEmatchMetaId::Random => TCF_META_ID_RANDOM,
+            TcEmatchMetaId::LoadAvg0 => TCF_META_ID_LOADAVG_0,
+            TcEmatchMetaId::LoadAvg1 => TCF_META_ID_LOADAVG_1,
+            TcEmatchMetaId::LoadAvg2 => TCF_META_ID_LOADAVG_2,
+            TcEmatchMetaId::Dev => TCF_META_ID_DEV,
+            TcEmatchMetaId::Priority => TCF_META_ID_PRIORITY,
+            TcEmatchMetaId::Protocol => TCF_META_ID_PROTOCOL,
+            TcEmatchMetaId::PktType => TCF_META_ID_PKTTYPE,
+            TcEmatchMetaId::PktLen => TCF_META_ID_PKTLEN,
+            TcEmatchMetaId::DataLen => TCF_META_ID_DATALEN,
+            TcEmatchMetaId::MacLen => TCF_META_ID_MACLEN,
+            TcEmatchMetaId::NfMark => TCF_META_ID_NFMARK,
+            TcEmatchMetaId::TcIndex => TCF_META_ID_TCINDEX,
+            TcEmatchMetaId::RtClassId => TCF_META_ID_RTCLASSID,
+            TcEmatchMetaId::RtIif => TCF_META_ID_RTIIF,
+            TcEmatchMetaId::SkFamily => TCF_META_ID_SK_FAMILY,
+            TcEmatchMetaId::SkState => TCF_META_ID_SK_STATE,
+            TcEmatchMetaId::SkReuse => TCF_META_ID_SK_REUSE,
+            TcEmatchMetaId::SkBoundIf => TCF_META_ID_SK_BOUND_IF,
+            TcEmatchMetaId::SkRefCnt => TCF_META_ID_SK_REFCNT,
+            TcEmatchMetaId::SkShutdown => TCF_META_ID_SK_SHUTDOWN,
+            TcEmatchMetaId::SkProto => TCF_META_ID_SK_PROTO,
+            TcEmatchMetaId::SkType => TCF_META_ID_SK_TYPE,
+            TcEmatchMetaId::SkRcvBuf => TCF_META_ID_SK_RCVBUF,
+            TcEmatchMetaId::SkRmemAlloc => TCF_META_ID_SK_RMEM_ALLOC,
+            TcEmatchMetaId::SkWmemAlloc => TCF_META_ID_SK_WMEM_ALLOC,
+            TcEmatchMetaId::SkOmemAlloc => TCF_META_ID_SK_OMEM_ALLOC,
+            TcEmatchMetaId::SkWmemQueued => TCF_META_ID_SK_WMEM_QUEUED,
+            TcEmatchMetaId::SkRcvQlen => TCF_META_ID_SK_RCV_QLEN,
+            TcEmatchMetaId::SkSndQlen => TCF_META_ID_SK_SND_QLEN,
+            TcEmatchMetaId::SkErrQlen => TCF_META_ID_SK_ERR_QLEN,
+            TcEmatchMetaId::SkForwardAllocs => TCF_META_ID_SK_FORWARD_ALLOCS,
+            TcEmatchMetaId::SkSndBuf => TCF_META_ID_SK_SNDBUF,
+            TcEmatchMetaId::SkAllocs => TCF_META_ID_SK_ALLOCS,
+            TcEmatchMetaId::SkRouteCaps => TCF_META_ID_SK_ROUTE_CAPS,
+            TcEmatchMetaId::SkHash => TCF_META_ID_SK_HASH,
+            TcEmatchMetaId::SkLingerTime => TCF_META_ID_SK_LINGERTIME,
+            TcEmatchMetaId::SkAckBacklog => TCF_META_ID_SK_ACK_BACKLOG,
+            TcEmatchMetaId::SkMaxAckBacklog => TCF_META_ID_SK_MAX_ACK_BACKLOG,
+            TcEmatchMetaId::SkPrio => TCF_META_ID_SK_PRIO,
+            TcEmatchMetaId::SkRcvLowat => TCF_META_ID_SK_RCVLOWAT,
+            TcEmatchMetaId::SkRcvTimeo => TCF_META_ID_SK_RCVTIMEO,
+            TcEmatchMetaId::SkSndTimeo => TCF_META_ID_SK_SNDTIMEO,
+            TcEmatchMetaId::SkSendmsgOff => TCF_META_ID_SK_SENDMSG_OFF,
+            TcEmatchMetaId::SkWritePending => TCF_META_ID_SK_WRITE_PENDING,
+            TcEmatchMetaId::VlanTag => TCF_META_ID_VLAN_TAG,
+            TcEmatchMetaId::RxHash => TCF_META_ID_RXHASH,
+            TcEmatchMetaId::Other(d) => d,
+        }
+    }
+}
@@ -0,0 +1,69 @@
+// SPDX-License-Identifier: MIT
+
+/// N-byte ematch
+///
+/// Compares a sequence of bytes at an offset of packet header with the
+/// specified pattern.
+use netlink_packet_utils::{
+    traits::{Emitable, Parseable},
+    DecodeError,
+};
+
+use crate::tc::TcEmatchLayer;
+
+const TC_EMATCH_NBYTE_BUF_LEN: usize = 4;
+
+// The `len` field is 12 bits wide
+const TC_EMATCH_NBYTE_LEN_MASK: u16 = 0xfff;
+
+// kernel struct `tcf_em_nbyte` followed by the pattern
+#[derive(Debug, PartialEq, Eq, Clone, Default)]
+#[non_exhaustive]
+pub struct TcEmatchNbyte {
+    pub off: u16,
+    pub layer: TcEmatchLayer,
+    pub needle: Vec<u8>,
+}
+
+buffer!(TcEmatchNbyteBuffer(TC_EMATCH_NBYTE_BUF_LEN) {
+    off: (u16, 0..2),
+    // len:12 and layer:4 bitfields
+    len_layer: (u16, 2..TC_EMATCH_NBYTE_BUF_LEN),
+    needle: (slice, TC_EMATCH_NBYTE_BUF_LEN..),
+});
+
+impl Emitable for TcEmatchNbyte {
+    fn buffer_len(&self) -> usize {
+        TC_EMATCH_NBYTE_BUF_LEN + self.needle.len()
+    }
+
+    fn emit(&self, buffer: &mut [u8]) {
+        let mut packet = TcEmatchNbyteBuffer::new(buffer);
+        packet.set_off(self.off);
+        packet.set_len_layer(
+            (self.needle.len() as u16 & TC_EMATCH_NBYTE_LEN_MASK)
+                | (u16::from(u8::from(self.layer)) << 12),
+        );
+        packet.needle_mut().copy_from_slice(self.needle.as_slice());
+    }
+}
+
+impl<T: AsRef<[u8]> + ?Sized> Parseable<TcEmatchNbyteBuffer<&T>>
+    for TcEmatchNbyte
+{
+    fn parse(buf: &TcEmatchNbyteBuffer<&T>) -> Result<Self, DecodeError> {
+        let len = (buf.len_layer() & TC_EMATCH_NBYTE_LEN_MASK) as usize;
+        let needle = buf.needle();
+        if needle.len() < len {
+            return Err(DecodeError::from(format!(
+                "Invalid nbyte ematch pattern length {}, expecting {len}",
+                needle.len()
+            )));
+        }
+        Ok(Self {
+            off: buf.off(),
+            layer: ((buf.len_layer() >> 12) as u8).into(),
+            needle: needle[..len].to_vec(),
+        })
+    }
+}
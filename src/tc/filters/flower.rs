@@ -0,0 +1,1276 @@
+// SPDX-License-Identifier: MIT
+
+/// Flower filter
+///
+/// Matches packets by the flow keys dissected from packet headers,
+/// including tunnel metadata and connection tracking state. Each key
+/// could be combined with a mask of the same size.
+use std::net::{Ipv4Addr, Ipv6Addr};
+
+use anyhow::Context;
+use byteorder::{BigEndian, ByteOrder, NativeEndian};
+use netlink_packet_utils::{
+    nla::{DefaultNla, Nla, NlaBuffer, NlasIterator},
+    parsers::{
+        parse_mac, parse_string, parse_u16, parse_u16_be, parse_u32,
+        parse_u32_be, parse_u8,
+    },
+    traits::{Emitable, Parseable},
+    DecodeError,
+};
+
+use super::{
+    flower_flags::{VecTcFlowerCtFlag, VecTcFlowerKeyFlag},
+    u32_flags::VecTcU32OptionFlag,
+};
+use crate::{
+    ip::{parse_ipv4_addr, parse_ipv6_addr},
+    tc::{
+        TcAction, TcFlowerCtFlag, TcFlowerEncOpts, TcFlowerKeyFlag, TcHandle,
+        TcU32OptionFlag,
+    },
+    IpProtocol,
+};
+
+const TCA_FLOWER_CLASSID: u16 = 1;
+const TCA_FLOWER_INDEV: u16 = 2;
+const TCA_FLOWER_ACT: u16 = 3;
+const TCA_FLOWER_KEY_ETH_DST: u16 = 4;
+const TCA_FLOWER_KEY_ETH_DST_MASK: u16 = 5;
+const TCA_FLOWER_KEY_ETH_SRC: u16 = 6;
+const TCA_FLOWER_KEY_ETH_SRC_MASK: u16 = 7;
+const TCA_FLOWER_KEY_ETH_TYPE: u16 = 8;
+const TCA_FLOWER_KEY_IP_PROTO: u16 = 9;
+const TCA_FLOWER_KEY_IPV4_SRC: u16 = 10;
+const TCA_FLOWER_KEY_IPV4_SRC_MASK: u16 = 11;
+const TCA_FLOWER_KEY_IPV4_DST: u16 = 12;
+const TCA_FLOWER_KEY_IPV4_DST_MASK: u16 = 13;
+const TCA_FLOWER_KEY_IPV6_SRC: u16 = 14;
+const TCA_FLOWER_KEY_IPV6_SRC_MASK: u16 = 15;
+const TCA_FLOWER_KEY_IPV6_DST: u16 = 16;
+const TCA_FLOWER_KEY_IPV6_DST_MASK: u16 = 17;
+const TCA_FLOWER_KEY_TCP_SRC: u16 = 18;
+const TCA_FLOWER_KEY_TCP_DST: u16 = 19;
+const TCA_FLOWER_KEY_UDP_SRC: u16 = 20;
+const TCA_FLOWER_KEY_UDP_DST: u16 = 21;
+const TCA_FLOWER_FLAGS: u16 = 22;
+const TCA_FLOWER_KEY_VLAN_ID: u16 = 23;
+const TCA_FLOWER_KEY_VLAN_PRIO: u16 = 24;
+const TCA_FLOWER_KEY_VLAN_ETH_TYPE: u16 = 25;
+const TCA_FLOWER_KEY_ENC_KEY_ID: u16 = 26;
+const TCA_FLOWER_KEY_ENC_IPV4_SRC: u16 = 27;
+const TCA_FLOWER_KEY_ENC_IPV4_SRC_MASK: u16 = 28;
+const TCA_FLOWER_KEY_ENC_IPV4_DST: u16 = 29;
+const TCA_FLOWER_KEY_ENC_IPV4_DST_MASK: u16 = 30;
+const TCA_FLOWER_KEY_ENC_IPV6_SRC: u16 = 31;
+const TCA_FLOWER_KEY_ENC_IPV6_SRC_MASK: u16 = 32;
+const TCA_FLOWER_KEY_ENC_IPV6_DST: u16 = 33;
+const TCA_FLOWER_KEY_ENC_IPV6_DST_MASK: u16 = 34;
+const TCA_FLOWER_KEY_TCP_SRC_MASK: u16 = 35;
+const TCA_FLOWER_KEY_TCP_DST_MASK: u16 = 36;
+const TCA_FLOWER_KEY_UDP_SRC_MASK: u16 = 37;
+const TCA_FLOWER_KEY_UDP_DST_MASK: u16 = 38;
+const TCA_FLOWER_KEY_SCTP_SRC_MASK: u16 = 39;
+const TCA_FLOWER_KEY_SCTP_DST_MASK: u16 = 40;
+const TCA_FLOWER_KEY_SCTP_SRC: u16 = 41;
+const TCA_FLOWER_KEY_SCTP_DST: u16 = 42;
+const TCA_FLOWER_KEY_ENC_UDP_SRC_PORT: u16 = 43;
+const TCA_FLOWER_KEY_ENC_UDP_SRC_PORT_MASK: u16 = 44;
+const TCA_FLOWER_KEY_ENC_UDP_DST_PORT: u16 = 45;
+const TCA_FLOWER_KEY_ENC_UDP_DST_PORT_MASK: u16 = 46;
+const TCA_FLOWER_KEY_FLAGS: u16 = 47;
+const TCA_FLOWER_KEY_FLAGS_MASK: u16 = 48;
+const TCA_FLOWER_KEY_ICMPV4_CODE: u16 = 49;
+const TCA_FLOWER_KEY_ICMPV4_CODE_MASK: u16 = 50;
+const TCA_FLOWER_KEY_ICMPV4_TYPE: u16 = 51;
+const TCA_FLOWER_KEY_ICMPV4_TYPE_MASK: u16 = 52;
+const TCA_FLOWER_KEY_ICMPV6_CODE: u16 = 53;
+const TCA_FLOWER_KEY_ICMPV6_CODE_MASK: u16 = 54;
+const TCA_FLOWER_KEY_ICMPV6_TYPE: u16 = 55;
+const TCA_FLOWER_KEY_ICMPV6_TYPE_MASK: u16 = 56;
+const TCA_FLOWER_KEY_ARP_SIP: u16 = 57;
+const TCA_FLOWER_KEY_ARP_SIP_MASK: u16 = 58;
+const TCA_FLOWER_KEY_ARP_TIP: u16 = 59;
+const TCA_FLOWER_KEY_ARP_TIP_MASK: u16 = 60;
+const TCA_FLOWER_KEY_ARP_OP: u16 = 61;
+const TCA_FLOWER_KEY_ARP_OP_MASK: u16 = 62;
+const TCA_FLOWER_KEY_ARP_SHA: u16 = 63;
+const TCA_FLOWER_KEY_ARP_SHA_MASK: u16 = 64;
+const TCA_FLOWER_KEY_ARP_THA: u16 = 65;
+const TCA_FLOWER_KEY_ARP_THA_MASK: u16 = 66;
+const TCA_FLOWER_KEY_MPLS_TTL: u16 = 67;
+const TCA_FLOWER_KEY_MPLS_BOS: u16 = 68;
+const TCA_FLOWER_KEY_MPLS_TC: u16 = 69;
+const TCA_FLOWER_KEY_MPLS_LABEL: u16 = 70;
+const TCA_FLOWER_KEY_TCP_FLAGS: u16 = 71;
+const TCA_FLOWER_KEY_TCP_FLAGS_MASK: u16 = 72;
+const TCA_FLOWER_KEY_IP_TOS: u16 = 73;
+const TCA_FLOWER_KEY_IP_TOS_MASK: u16 = 74;
+const TCA_FLOWER_KEY_IP_TTL: u16 = 75;
+const TCA_FLOWER_KEY_IP_TTL_MASK: u16 = 76;
+const TCA_FLOWER_KEY_CVLAN_ID: u16 = 77;
+const TCA_FLOWER_KEY_CVLAN_PRIO: u16 = 78;
+const TCA_FLOWER_KEY_CVLAN_ETH_TYPE: u16 = 79;
+const TCA_FLOWER_KEY_ENC_IP_TOS: u16 = 80;
+const TCA_FLOWER_KEY_ENC_IP_TOS_MASK: u16 = 81;
+const TCA_FLOWER_KEY_ENC_IP_TTL: u16 = 82;
+const TCA_FLOWER_KEY_ENC_IP_TTL_MASK: u16 = 83;
+const TCA_FLOWER_KEY_ENC_OPTS: u16 = 84;
+const TCA_FLOWER_KEY_ENC_OPTS_MASK: u16 = 85;
+const TCA_FLOWER_IN_HW_COUNT: u16 = 86;
+const TCA_FLOWER_KEY_PORT_SRC_MIN: u16 = 87;
+const TCA_FLOWER_KEY_PORT_SRC_MAX: u16 = 88;
+const TCA_FLOWER_KEY_PORT_DST_MIN: u16 = 89;
+const TCA_FLOWER_KEY_PORT_DST_MAX: u16 = 90;
+const TCA_FLOWER_KEY_CT_STATE: u16 = 91;
+const TCA_FLOWER_KEY_CT_STATE_MASK: u16 = 92;
+const TCA_FLOWER_KEY_CT_ZONE: u16 = 93;
+const TCA_FLOWER_KEY_CT_ZONE_MASK: u16 = 94;
+const TCA_FLOWER_KEY_CT_MARK: u16 = 95;
+const TCA_FLOWER_KEY_CT_MARK_MASK: u16 = 96;
+const TCA_FLOWER_KEY_CT_LABELS: u16 = 97;
+const TCA_FLOWER_KEY_CT_LABELS_MASK: u16 = 98;
+const TCA_FLOWER_KEY_MPLS_OPTS: u16 = 99;
+const TCA_FLOWER_KEY_HASH: u16 = 100;
+const TCA_FLOWER_KEY_HASH_MASK: u16 = 101;
+const TCA_FLOWER_KEY_NUM_OF_VLANS: u16 = 102;
+const TCA_FLOWER_KEY_PPPOE_SID: u16 = 103;
+const TCA_FLOWER_KEY_PPP_PROTO: u16 = 104;
+const TCA_FLOWER_KEY_L2TPV3_SID: u16 = 105;
+
+#[derive(Debug, PartialEq, Eq, Clone)]
+#[non_exhaustive]
+pub struct TcFilterFlower {}
+
+impl TcFilterFlower {
+    pub const KIND: &'static str = "flower";
+}
+
+#[derive(Debug, PartialEq, Eq, Clone)]
+#[non_exhaustive]
+pub enum TcFilterFlowerOption {
+    ClassId(TcHandle),
+    /// Only match packets from this input interface name
+    Indev(String),
+    Action(Vec<TcAction>),
+    KeyEthDst([u8; 6]),
+    KeyEthDstMask([u8; 6]),
+    KeyEthSrc([u8; 6]),
+    KeyEthSrcMask([u8; 6]),
+    /// Ethernet protocol, e.g. `0x0800` for IPv4
+    KeyEthType(u16),
+    KeyIpProto(IpProtocol),
+    KeyIpv4Src(Ipv4Addr),
+    KeyIpv4SrcMask(Ipv4Addr),
+    KeyIpv4Dst(Ipv4Addr),
+    KeyIpv4DstMask(Ipv4Addr),
+    KeyIpv6Src(Ipv6Addr),
+    KeyIpv6SrcMask(Ipv6Addr),
+    KeyIpv6Dst(Ipv6Addr),
+    KeyIpv6DstMask(Ipv6Addr),
+    KeyTcpSrc(u16),
+    KeyTcpDst(u16),
+    KeyUdpSrc(u16),
+    KeyUdpDst(u16),
+    /// Classifier flags, e.g. `SkipHw` and `SkipSw`
+    Flags(Vec<TcU32OptionFlag>),
+    KeyVlanId(u16),
+    KeyVlanPrio(u8),
+    KeyVlanEthType(u16),
+    /// Tunnel key ID, e.g. VNI of VXLAN
+    KeyEncKeyId(u32),
+    KeyEncIpv4Src(Ipv4Addr),
+    KeyEncIpv4SrcMask(Ipv4Addr),
+    KeyEncIpv4Dst(Ipv4Addr),
+    KeyEncIpv4DstMask(Ipv4Addr),
+    KeyEncIpv6Src(Ipv6Addr),
+    KeyEncIpv6SrcMask(Ipv6Addr),
+    KeyEncIpv6Dst(Ipv6Addr),
+    KeyEncIpv6DstMask(Ipv6Addr),
+    KeyTcpSrcMask(u16),
+    KeyTcpDstMask(u16),
+    KeyUdpSrcMask(u16),
+    KeyUdpDstMask(u16),
+    KeySctpSrcMask(u16),
+    KeySctpDstMask(u16),
+    KeySctpSrc(u16),
+    KeySctpDst(u16),
+    KeyEncUdpSrcPort(u16),
+    KeyEncUdpSrcPortMask(u16),
+    KeyEncUdpDstPort(u16),
+    KeyEncUdpDstPortMask(u16),
+    KeyFlags(Vec<TcFlowerKeyFlag>),
+    KeyFlagsMask(Vec<TcFlowerKeyFlag>),
+    KeyIcmpv4Code(u8),
+    KeyIcmpv4CodeMask(u8),
+    KeyIcmpv4Type(u8),
+    KeyIcmpv4TypeMask(u8),
+    KeyIcmpv6Code(u8),
+    KeyIcmpv6CodeMask(u8),
+    KeyIcmpv6Type(u8),
+    KeyIcmpv6TypeMask(u8),
+    KeyArpSip(Ipv4Addr),
+    KeyArpSipMask(Ipv4Addr),
+    KeyArpTip(Ipv4Addr),
+    KeyArpTipMask(Ipv4Addr),
+    KeyArpOp(u8),
+    KeyArpOpMask(u8),
+    KeyArpSha([u8; 6]),
+    KeyArpShaMask([u8; 6]),
+    KeyArpTha([u8; 6]),
+    KeyArpThaMask([u8; 6]),
+    /// TTL of the outermost MPLS label stack entry
+    KeyMplsTtl(u8),
+    KeyMplsBos(u8),
+    KeyMplsTc(u8),
+    KeyMplsLabel(u32),
+    /// TCP flags in the lower 12 bits
+    KeyTcpFlags(u16),
+    KeyTcpFlagsMask(u16),
+    KeyIpTos(u8),
+    KeyIpTosMask(u8),
+    KeyIpTtl(u8),
+    KeyIpTtlMask(u8),
+    /// VLAN ID of the inner (customer) VLAN tag
+    KeyCvlanId(u16),
+    KeyCvlanPrio(u8),
+    KeyCvlanEthType(u16),
+    KeyEncIpTos(u8),
+    KeyEncIpTosMask(u8),
+    KeyEncIpTtl(u8),
+    KeyEncIpTtlMask(u8),
+    KeyEncOpts(Vec<TcFlowerEncOpts>),
+    KeyEncOptsMask(Vec<TcFlowerEncOpts>),
+    /// Number of hardware devices the filter is offloaded to
+    InHwCount(u32),
+    /// Port range match of TCP, UDP or SCTP
+    KeyPortSrcMin(u16),
+    KeyPortSrcMax(u16),
+    KeyPortDstMin(u16),
+    KeyPortDstMax(u16),
+    KeyCtState(Vec<TcFlowerCtFlag>),
+    KeyCtStateMask(Vec<TcFlowerCtFlag>),
+    KeyCtZone(u16),
+    KeyCtZoneMask(u16),
+    KeyCtMark(u32),
+    KeyCtMarkMask(u32),
+    /// Conntrack labels of 128 bits
+    KeyCtLabels([u8; 16]),
+    KeyCtLabelsMask([u8; 16]),
+    /// MPLS label stack entries, each is a list of `TcFlowerMplsLseOpt`
+    KeyMplsOpts(Vec<Vec<TcFlowerMplsLseOpt>>),
+    KeyHash(u32),
+    KeyHashMask(u32),
+    KeyNumOfVlans(u8),
+    KeyPppoeSid(u16),
+    KeyPppProto(u16),
+    KeyL2tpv3Sid(u32),
+    Other(DefaultNla),
+}
+
+impl Nla for TcFilterFlowerOption {
+    fn value_len(&self) -> usize {
+        match self {
+            Self::Indev(s) => s.len() + 1,
+            Self::Action(acts) => acts.as_slice().buffer_len(),
+            Self::ClassId(_)
+            | Self::KeyIpv4Src(_)
+            | Self::KeyIpv4SrcMask(_)
+            | Self::KeyIpv4Dst(_)
+            | Self::KeyIpv4DstMask(_)
+            | Self::Flags(_)
+            | Self::KeyEncKeyId(_)
+            | Self::KeyEncIpv4Src(_)
+            | Self::KeyEncIpv4SrcMask(_)
+            | Self::KeyEncIpv4Dst(_)
+            | Self::KeyEncIpv4DstMask(_)
+            | Self::KeyFlags(_)
+            | Self::KeyFlagsMask(_)
+            | Self::KeyArpSip(_)
+            | Self::KeyArpSipMask(_)
+            | Self::KeyArpTip(_)
+            | Self::KeyArpTipMask(_)
+            | Self::KeyMplsLabel(_)
+            | Self::InHwCount(_)
+            | Self::KeyCtMark(_)
+            | Self::KeyCtMarkMask(_)
+            | Self::KeyHash(_)
+            | Self::KeyHashMask(_)
+            | Self::KeyL2tpv3Sid(_) => 4,
+            Self::KeyEthType(_)
+            | Self::KeyTcpSrc(_)
+            | Self::KeyTcpDst(_)
+            | Self::KeyUdpSrc(_)
+            | Self::KeyUdpDst(_)
+            | Self::KeyVlanId(_)
+            | Self::KeyVlanEthType(_)
+            | Self::KeyTcpSrcMask(_)
+            | Self::KeyTcpDstMask(_)
+            | Self::KeyUdpSrcMask(_)
+            | Self::KeyUdpDstMask(_)
+            | Self::KeySctpSrcMask(_)
+            | Self::KeySctpDstMask(_)
+            | Self::KeySctpSrc(_)
+            | Self::KeySctpDst(_)
+            | Self::KeyEncUdpSrcPort(_)
+            | Self::KeyEncUdpSrcPortMask(_)
+            | Self::KeyEncUdpDstPort(_)
+            | Self::KeyEncUdpDstPortMask(_)
+            | Self::KeyTcpFlags(_)
+            | Self::KeyTcpFlagsMask(_)
+            | Self::KeyCvlanId(_)
+            | Self::KeyCvlanEthType(_)
+            | Self::KeyPortSrcMin(_)
+            | Self::KeyPortSrcMax(_)
+            | Self::KeyPortDstMin(_)
+            | Self::KeyPortDstMax(_)
+            | Self::KeyCtState(_)
+            | Self::KeyCtStateMask(_)
+            | Self::KeyCtZone(_)
+            | Self::KeyCtZoneMask(_)
+            | Self::KeyPppoeSid(_)
+            | Self::KeyPppProto(_) => 2,
+            Self::KeyIpProto(_)
+            | Self::KeyVlanPrio(_)
+            | Self::KeyIcmpv4Code(_)
+            | Self::KeyIcmpv4CodeMask(_)
+            | Self::KeyIcmpv4Type(_)
+            | Self::KeyIcmpv4TypeMask(_)
+            | Self::KeyIcmpv6Code(_)
+            | Self::KeyIcmpv6CodeMask(_)
+            | Self::KeyIcmpv6Type(_)
+            | Self::KeyIcmpv6TypeMask(_)
+            | Self::KeyArpOp(_)
+            | Self::KeyArpOpMask(_)
+            | Self::KeyMplsTtl(_)
+            | Self::KeyMplsBos(_)
+            | Self::KeyMplsTc(_)
+            | Self::KeyIpTos(_)
+            | Self::KeyIpTosMask(_)
+            | Self::KeyIpTtl(_)
+            | Self::KeyIpTtlMask(_)
+            | Self::KeyCvlanPrio(_)
+            | Self::KeyEncIpTos(_)
+            | Self::KeyEncIpTosMask(_)
+            | Self::KeyEncIpTtl(_)
+            | Self::KeyEncIpTtlMask(_)
+            | Self::KeyNumOfVlans(_) => 1,
+            Self::KeyEthDst(_)
+            | Self::KeyEthDstMask(_)
+            | Self::KeyEthSrc(_)
+            | Self::KeyEthSrcMask(_)
+            | Self::KeyArpSha(_)
+            | Self::KeyArpShaMask(_)
+            | Self::KeyArpTha(_)
+            | Self::KeyArpThaMask(_) => 6,
+            Self::KeyIpv6Src(_)
+            | Self::KeyIpv6SrcMask(_)
+            | Self::KeyIpv6Dst(_)
+            | Self::KeyIpv6DstMask(_)
+            | Self::KeyEncIpv6Src(_)
+            | Self::KeyEncIpv6SrcMask(_)
+            | Self::KeyEncIpv6Dst(_)
+            | Self::KeyEncIpv6DstMask(_)
+            | Self::KeyCtLabels(_)
+            | Self::KeyCtLabelsMask(_) => 16,
+            Self::KeyEncOpts(v) | Self::KeyEncOptsMask(v) => {
+                v.as_slice().buffer_len()
+            }
+            Self::KeyMplsOpts(v) => mpls_lse_entries(v).as_slice().buffer_len(),
+            Self::Other(attr) => attr.value_len(),
+        }
+    }
+
+    fn emit_value(&self, buffer: &mut [u8]) {
+        match self {
+            Self::ClassId(v) => NativeEndian::write_u32(buffer, (*v).into()),
+            Self::Indev(s) => {
+                buffer[..s.len()].copy_from_slice(s.as_bytes());
+                buffer[s.len()] = 0;
+            }
+            Self::Action(acts) => acts.as_slice().emit(buffer),
+            Self::KeyEthDst(v)
+            | Self::KeyEthDstMask(v)
+            | Self::KeyEthSrc(v)
+            | Self::KeyEthSrcMask(v)
+            | Self::KeyArpSha(v)
+            | Self::KeyArpShaMask(v)
+            | Self::KeyArpTha(v)
+            | Self::KeyArpThaMask(v) => buffer.copy_from_slice(v),
+            Self::KeyCtLabels(v) | Self::KeyCtLabelsMask(v) => {
+                buffer.copy_from_slice(v)
+            }
+            Self::KeyEthType(d)
+            | Self::KeyTcpSrc(d)
+            | Self::KeyTcpDst(d)
+            | Self::KeyUdpSrc(d)
+            | Self::KeyUdpDst(d)
+            | Self::KeyVlanEthType(d)
+            | Self::KeyTcpSrcMask(d)
+            | Self::KeyTcpDstMask(d)
+            | Self::KeyUdpSrcMask(d)
+            | Self::KeyUdpDstMask(d)
+            | Self::KeySctpSrcMask(d)
+            | Self::KeySctpDstMask(d)
+            | Self::KeySctpSrc(d)
+            | Self::KeySctpDst(d)
+            | Self::KeyEncUdpSrcPort(d)
+            | Self::KeyEncUdpSrcPortMask(d)
+            | Self::KeyEncUdpDstPort(d)
+            | Self::KeyEncUdpDstPortMask(d)
+            | Self::KeyTcpFlags(d)
+            | Self::KeyTcpFlagsMask(d)
+            | Self::KeyCvlanEthType(d)
+            | Self::KeyPortSrcMin(d)
+            | Self::KeyPortSrcMax(d)
+            | Self::KeyPortDstMin(d)
+            | Self::KeyPortDstMax(d)
+            | Self::KeyPppoeSid(d)
+            | Self::KeyPppProto(d) => BigEndian::write_u16(buffer, *d),
+            Self::KeyVlanId(d)
+            | Self::KeyCvlanId(d)
+            | Self::KeyCtZone(d)
+            | Self::KeyCtZoneMask(d) => NativeEndian::write_u16(buffer, *d),
+            Self::KeyVlanPrio(d)
+            | Self::KeyIcmpv4Code(d)
+            | Self::KeyIcmpv4CodeMask(d)
+            | Self::KeyIcmpv4Type(d)
+            | Self::KeyIcmpv4TypeMask(d)
+            | Self::KeyIcmpv6Code(d)
+            | Self::KeyIcmpv6CodeMask(d)
+            | Self::KeyIcmpv6Type(d)
+            | Self::KeyIcmpv6TypeMask(d)
+            | Self::KeyArpOp(d)
+            | Self::KeyArpOpMask(d)
+            | Self::KeyMplsTtl(d)
+            | Self::KeyMplsBos(d)
+            | Self::KeyMplsTc(d)
+            | Self::KeyIpTos(d)
+            | Self::KeyIpTosMask(d)
+            | Self::KeyIpTtl(d)
+            | Self::KeyIpTtlMask(d)
+            | Self::KeyCvlanPrio(d)
+            | Self::KeyEncIpTos(d)
+            | Self::KeyEncIpTosMask(d)
+            | Self::KeyEncIpTtl(d)
+            | Self::KeyEncIpTtlMask(d)
+            | Self::KeyNumOfVlans(d) => buffer[0] = *d,
+            Self::KeyIpProto(v) => buffer[0] = i32::from(*v) as u8,
+            Self::KeyIpv4Src(v)
+            | Self::KeyIpv4SrcMask(v)
+            | Self::KeyIpv4Dst(v)
+            | Self::KeyIpv4DstMask(v)
+            | Self::KeyEncIpv4Src(v)
+            | Self::KeyEncIpv4SrcMask(v)
+            | Self::KeyEncIpv4Dst(v)
+            | Self::KeyEncIpv4DstMask(v)
+            | Self::KeyArpSip(v)
+            | Self::KeyArpSipMask(v)
+            | Self::KeyArpTip(v)
+            | Self::KeyArpTipMask(v) => buffer.copy_from_slice(&v.octets()),
+            Self::KeyIpv6Src(v)
+            | Self::KeyIpv6SrcMask(v)
+            | Self::KeyIpv6Dst(v)
+            | Self::KeyIpv6DstMask(v)
+            | Self::KeyEncIpv6Src(v)
+            | Self::KeyEncIpv6SrcMask(v)
+            | Self::KeyEncIpv6Dst(v)
+            | Self::KeyEncIpv6DstMask(v) => buffer.copy_from_slice(&v.octets()),
+            Self::KeyMplsLabel(d)
+            | Self::InHwCount(d)
+            | Self::KeyCtMark(d)
+            | Self::KeyCtMarkMask(d)
+            | Self::KeyHash(d)
+            | Self::KeyHashMask(d) => NativeEndian::write_u32(buffer, *d),
+            Self::KeyEncKeyId(d) | Self::KeyL2tpv3Sid(d) => {
+                BigEndian::write_u32(buffer, *d)
+            }
+            Self::Flags(v) => NativeEndian::write_u32(
+                buffer,
+                u32::from(&VecTcU32OptionFlag(v.to_vec())),
+            ),
+            Self::KeyFlags(v) | Self::KeyFlagsMask(v) => BigEndian::write_u32(
+                buffer,
+                u32::from(&VecTcFlowerKeyFlag(v.to_vec())),
+            ),
+            Self::KeyCtState(v) | Self::KeyCtStateMask(v) => {
+                NativeEndian::write_u16(
+                    buffer,
+                    u16::from(&VecTcFlowerCtFlag(v.to_vec())),
+                )
+            }
+            Self::KeyEncOpts(v) | Self::KeyEncOptsMask(v) => {
+                v.as_slice().emit(buffer)
+            }
+            Self::KeyMplsOpts(v) => mpls_lse_entries(v).as_slice().emit(buffer),
+            Self::Other(attr) => attr.emit_value(buffer),
+        }
+    }
+
+    fn is_nested(&self) -> bool {
+        match self {
+            Self::KeyMplsOpts(_) => true,
+            // iproute2 flags the options as nested unless they are geneve
+            Self::KeyEncOpts(v) | Self::KeyEncOptsMask(v) => {
+                v.iter().any(|opt| opt.is_nested())
+            }
+            _ => false,
+        }
+    }
+
+    fn kind(&self) -> u16 {
+        match self {
+            Self::ClassId(_) => TCA_FLOWER_CLASSID,
+            Self::Indev(_) => TCA_FLOWER_INDEV,
+            Self::Action(_) => TCA_FLOWER_ACT,
+            Self::KeyEthDst(_) => TCA_FLOWER_KEY_ETH_DST,
+            Self::KeyEthDstMask(_) => TCA_FLOWER_KEY_ETH_DST_MASK,
+            Self::KeyEthSrc(_) => TCA_FLOWER_KEY_ETH_SRC,
+            Self::KeyEthSrcMask(_) => TCA_FLOWER_KEY_ETH_SRC_MASK,
+            Self::KeyEthType(_) => TCA_FLOWER_KEY_ETH_TYPE,
+            Self::KeyIpProto(_) => TCA_FLOWER_KEY_IP_PROTO,
+            Self::KeyIpv4Src(_) => TCA_FLOWER_KEY_IPV4_SRC,
+            Self::KeyIpv4SrcMask(_) => TCA_FLOWER_KEY_IPV4_SRC_MASK,
+            Self::KeyIpv4Dst(_) => TCA_FLOWER_KEY_IPV4_DST,
+            Self::KeyIpv4DstMask(_) => TCA_FLOWER_KEY_IPV4_DST_MASK,
+            Self::KeyIpv6Src(_) => TCA_FLOWER_KEY_IPV6_SRC,
+            Self::KeyIpv6SrcMask(_) => TCA_FLOWER_KEY_IPV6_SRC_MASK,
+            Self::KeyIpv6Dst(_) => TCA_FLOWER_KEY_IPV6_DST,
+            Self::KeyIpv6DstMask(_) => TCA_FLOWER_KEY_IPV6_DST_MASK,
+            Self::KeyTcpSrc(_) => TCA_FLOWER_KEY_TCP_SRC,
+            Self::KeyTcpDst(_) => TCA_FLOWER_KEY_TCP_DST,
+            Self::KeyUdpSrc(_) => TCA_FLOWER_KEY_UDP_SRC,
+            Self::KeyUdpDst(_) => TCA_FLOWER_KEY_UDP_DST,
+            Self::Flags(_) => TCA_FLOWER_FLAGS,
+            Self::KeyVlanId(_) => TCA_FLOWER_KEY_VLAN_ID,
+            Self::KeyVlanPrio(_) => TCA_FLOWER_KEY_VLAN_PRIO,
+            Self::KeyVlanEthType(_) => TCA_FLOWER_KEY_VLAN_ETH_TYPE,
+            Self::KeyEncKeyId(_) => TCA_FLOWER_KEY_ENC_KEY_ID,
+            Self::KeyEncIpv4Src(_) => TCA_FLOWER_KEY_ENC_IPV4_SRC,
+            Self::KeyEncIpv4SrcMask(_) => TCA_FLOWER_KEY_ENC_IPV4_SRC_MASK,
+            Self::KeyEncIpv4Dst(_) => TCA_FLOWER_KEY_ENC_IPV4_DST,
+            Self::KeyEncIpv4DstMask(_) => TCA_FLOWER_KEY_ENC_IPV4_DST_MASK,
+            Self::KeyEncIpv6Src(_) => TCA_FLOWER_KEY_ENC_IPV6_SRC,
+            Self::KeyEncIpv6SrcMask(_) => TCA_FLOWER_KEY_ENC_IPV6_SRC_MASK,
+            Self::KeyEncIpv6Dst(_) => TCA_FLOWER_KEY_ENC_IPV6_DST,
+            Self::KeyEncIpv6DstMask(_) => TCA_FLOWER_KEY_ENC_IPV6_DST_MASK,
+            Self::KeyTcpSrcMask(_) => TCA_FLOWER_KEY_TCP_SRC_MASK,
+            Self::KeyTcpDstMask(_) => TCA_FLOWER_KEY_TCP_DST_MASK,
+            Self::KeyUdpSrcMask(_) => TCA_FLOWER_KEY_UDP_SRC_MASK,
+            Self::KeyUdpDstMask(_) => TCA_FLOWER_KEY_UDP_DST_MASK,
+            Self::KeySctpSrcMask(_) => TCA_FLOWER_KEY_SCTP_SRC_MASK,
+            Self::KeySctpDstMask(_) => TCA_FLOWER_KEY_SCTP_DST_MASK,
+            Self::KeySctpSrc(_) => TCA_FLOWER_KEY_SCTP_SRC,
+            Self::KeySctpDst(_) => TCA_FLOWER_KEY_SCTP_DST,
+            Self::KeyEncUdpSrcPort(_) => TCA_FLOWER_KEY_ENC_UDP_SRC_PORT,
+            Self::KeyEncUdpSrcPortMask(_) => {
+                TCA_FLOWER_KEY_ENC_UDP_SRC_PORT_MASK
+            }
+            Self::KeyEncUdpDstPort(_) => TCA_FLOWER_KEY_ENC_UDP_DST_PORT,
+            Self::KeyEncUdpDstPortMask(_) => {
+                TCA_FLOWER_KEY_ENC_UDP_DST_PORT_MASK
+            }
+            Self::KeyFlags(_) => TCA_FLOWER_KEY_FLAGS,
+            Self::KeyFlagsMask(_) => TCA_FLOWER_KEY_FLAGS_MASK,
+            Self::KeyIcmpv4Code(_) => TCA_FLOWER_KEY_ICMPV4_CODE,
+            Self::KeyIcmpv4CodeMask(_) => TCA_FLOWER_KEY_ICMPV4_CODE_MASK,
+            Self::KeyIcmpv4Type(_) => TCA_FLOWER_KEY_ICMPV4_TYPE,
+            Self::KeyIcmpv4TypeMask(_) => TCA_FLOWER_KEY_ICMPV4_TYPE_MASK,
+            Self::KeyIcmpv6Code(_) => TCA_FLOWER_KEY_ICMPV6_CODE,
+            Self::KeyIcmpv6CodeMask(_) => TCA_FLOWER_KEY_ICMPV6_CODE_MASK,
+            Self::KeyIcmpv6Type(_) => TCA_FLOWER_KEY_ICMPV6_TYPE,
+            Self::KeyIcmpv6TypeMask(_) => TCA_FLOWER_KEY_ICMPV6_TYPE_MASK,
+            Self::KeyArpSip(_) => TCA_FLOWER_KEY_ARP_SIP,
+            Self::KeyArpSipMask(_) => TCA_FLOWER_KEY_ARP_SIP_MASK,
+            Self::KeyArpTip(_) => TCA_FLOWER_KEY_ARP_TIP,
+            Self::KeyArpTipMask(_) => TCA_FLOWER_KEY_ARP_TIP_MASK,
+            Self::KeyArpOp(_) => TCA_FLOWER_KEY_ARP_OP,
+            Self::KeyArpOpMask(_) => TCA_FLOWER_KEY_ARP_OP_MASK,
+            Self::KeyArpSha(_) => TCA_FLOWER_KEY_ARP_SHA,
+            Self::KeyArpShaMask(_) => TCA_FLOWER_KEY_ARP_SHA_MASK,
+            Self::KeyArpTha(_) => TCA_FLOWER_KEY_ARP_THA,
+            Self::KeyArpThaMask(_) => TCA_FLOWER_KEY_ARP_THA_MASK,
+            Self::KeyMplsTtl(_) => TCA_FLOWER_KEY_MPLS_TTL,
+            Self::KeyMplsBos(_) => TCA_FLOWER_KEY_MPLS_BOS,
+            Self::KeyMplsTc(_) => TCA_FLOWER_KEY_MPLS_TC,
+            Self::KeyMplsLabel(_) => TCA_FLOWER_KEY_MPLS_LABEL,
+            Self::KeyTcpFlags(_) => TCA_FLOWER_KEY_TCP_FLAGS,
+            Self::KeyTcpFlagsMask(_) => TCA_FLOWER_KEY_TCP_FLAGS_MASK,
+            Self::KeyIpTos(_) => TCA_FLOWER_KEY_IP_TOS,
+            Self::KeyIpTosMask(_) => TCA_FLOWER_KEY_IP_TOS_MASK,
+            Self::KeyIpTtl(_) => TCA_FLOWER_KEY_IP_TTL,
+            Self::KeyIpTtlMask(_) => TCA_FLOWER_KEY_IP_TTL_MASK,
+            Self::KeyCvlanId(_) => TCA_FLOWER_KEY_CVLAN_ID,
+            Self::KeyCvlanPrio(_) => TCA_FLOWER_KEY_CVLAN_PRIO,
+            Self::KeyCvlanEthType(_) => TCA_FLOWER_KEY_CVLAN_ETH_TYPE,
+            Self::KeyEncIpTos(_) => TCA_FLOWER_KEY_ENC_IP_TOS,
+            Self::KeyEncIpTosMask(_) => TCA_FLOWER_KEY_ENC_IP_TOS_MASK,
+            Self::KeyEncIpTtl(_) => TCA_FLOWER_KEY_ENC_IP_TTL,
+            Self::KeyEncIpTtlMask(_) => TCA_FLOWER_KEY_ENC_IP_TTL_MASK,
+            Self::KeyEncOpts(_) => TCA_FLOWER_KEY_ENC_OPTS,
+            Self::KeyEncOptsMask(_) => TCA_FLOWER_KEY_ENC_OPTS_MASK,
+            Self::InHwCount(_) => TCA_FLOWER_IN_HW_COUNT,
+            Self::KeyPortSrcMin(_) => TCA_FLOWER_KEY_PORT_SRC_MIN,
+            Self::KeyPortSrcMax(_) => TCA_FLOWER_KEY_PORT_SRC_MAX,
+            Self::KeyPortDstMin(_) => TCA_FLOWER_KEY_PORT_DST_MIN,
+            Self::KeyPortDstMax(_) => TCA_FLOWER_KEY_PORT_DST_MAX,
+            Self::KeyCtState(_) => TCA_FLOWER_KEY_CT_STATE,
+            Self::KeyCtStateMask(_) => TCA_FLOWER_KEY_CT_STATE_MASK,
+            Self::KeyCtZone(_) => TCA_FLOWER_KEY_CT_ZONE,
+            Self::KeyCtZoneMask(_) => TCA_FLOWER_KEY_CT_ZONE_MASK,
+            Self::KeyCtMark(_) => TCA_FLOWER_KEY_CT_MARK,
+            Self::KeyCtMarkMask(_) => TCA_FLOWER_KEY_CT_MARK_MASK,
+            Self::KeyCtLabels(_) => TCA_FLOWER_KEY_CT_LABELS,
+            Self::KeyCtLabelsMask(_) => TCA_FLOWER_KEY_CT_LABELS_MASK,
+            Self::KeyMplsOpts(_) => TCA_FLOWER_KEY_MPLS_OPTS,
+            Self::KeyHash(_) => TCA_FLOWER_KEY_HASH,
+            Self::KeyHashMask(_) => TCA_FLOWER_KEY_HASH_MASK,
+            Self::KeyNumOfVlans(_) => TCA_FLOWER_KEY_NUM_OF_VLANS,
+            Self::KeyPppoeSid(_) => TCA_FLOWER_KEY_PPPOE_SID,
+            Self::KeyPppProto(_) => TCA_FLOWER_KEY_PPP_PROTO,
+            Self::KeyL2tpv3Sid(_) => TCA_FLOWER_KEY_L2TPV3_SID,
+            Self::Other(attr) => attr.kind(),
+        }
+    }
+}
+
+impl<'a, T: AsRef<[u8]> + ?Sized> Parseable<NlaBuffer<&'a T>>
+    for TcFilterFlowerOption
+{
+    fn parse(buf: &NlaBuffer<&'a T>) -> Result<Self, DecodeError> {
+        let payload = buf.value();
+        Ok(match buf.kind() {
+            TCA_FLOWER_CLASSID => Self::ClassId(
+                parse_u32(payload)
+                    .context("failed to parse TCA_FLOWER_CLASSID")?
+                    .into(),
+            ),
+            TCA_FLOWER_INDEV => Self::Indev(
+                parse_string(payload)
+                    .context("failed to parse TCA_FLOWER_INDEV")?,
+            ),
+            TCA_FLOWER_ACT => {
+                let mut acts = vec![];
+                for act in NlasIterator::new(payload) {
+                    let act = act.context("invalid TCA_FLOWER_ACT")?;
+                    acts.push(
+                        TcAction::parse(&act)
+                            .context("failed to parse TCA_FLOWER_ACT")?,
+                    );
+                }
+                Self::Action(acts)
+            }
+            TCA_FLOWER_KEY_ETH_DST => Self::KeyEthDst(
+                parse_mac(payload)
+                    .context("failed to parse TCA_FLOWER_KEY_ETH_DST")?,
+            ),
+            TCA_FLOWER_KEY_ETH_DST_MASK => Self::KeyEthDstMask(
+                parse_mac(payload)
+                    .context("failed to parse TCA_FLOWER_KEY_ETH_DST_MASK")?,
+            ),
+            TCA_FLOWER_KEY_ETH_SRC => Self::KeyEthSrc(
+                parse_mac(payload)
+                    .context("failed to parse TCA_FLOWER_KEY_ETH_SRC")?,
+            ),
+            TCA_FLOWER_KEY_ETH_SRC_MASK => Self::KeyEthSrcMask(
+                parse_mac(payload)
+                    .context("failed to parse TCA_FLOWER_KEY_ETH_SRC_MASK")?,
+            ),
+            TCA_FLOWER_KEY_ETH_TYPE => Self::KeyEthType(
+                parse_u16_be(payload)
+                    .context("failed to parse TCA_FLOWER_KEY_ETH_TYPE")?,
+            ),
+            TCA_FLOWER_KEY_IP_PROTO => Self::KeyIpProto(IpProtocol::from(
+                parse_u8(payload)
+                    .context("failed to parse TCA_FLOWER_KEY_IP_PROTO")?
+                    as i32,
+            )),
+            TCA_FLOWER_KEY_IPV4_SRC => Self::KeyIpv4Src(
+                parse_ipv4_addr(payload)
+                    .context("failed to parse TCA_FLOWER_KEY_IPV4_SRC")?,
+            ),
+            TCA_FLOWER_KEY_IPV4_SRC_MASK => Self::KeyIpv4SrcMask(
+                parse_ipv4_addr(payload)
+                    .context("failed to parse TCA_FLOWER_KEY_IPV4_SRC_MASK")?,
+            ),
+            TCA_FLOWER_KEY_IPV4_DST => Self::KeyIpv4Dst(
+                parse_ipv4_addr(payload)
+                    .context("failed to parse TCA_FLOWER_KEY_IPV4_DST")?,
+            ),
+            TCA_FLOWER_KEY_IPV4_DST_MASK => Self::KeyIpv4DstMask(
+                parse_ipv4_addr(payload)
+                    .context("failed to parse TCA_FLOWER_KEY_IPV4_DST_MASK")?,
+            ),
+            TCA_FLOWER_KEY_IPV6_SRC => Self::KeyIpv6Src(
+                parse_ipv6_addr(payload)
+                    .context("failed to parse TCA_FLOWER_KEY_IPV6_SRC")?,
+            ),
+            TCA_FLOWER_KEY_IPV6_SRC_MASK => Self::KeyIpv6SrcMask(
+                parse_ipv6_addr(payload)
+                    .context("failed to parse TCA_FLOWER_KEY_IPV6_SRC_MASK")?,
+            ),
+            TCA_FLOWER_KEY_IPV6_DST => Self::KeyIpv6Dst(
+                parse_ipv6_addr(payload)
+                    .context("failed to parse TCA_FLOWER_KEY_IPV6_DST")?,
+            ),
+            TCA_FLOWER_KEY_IPV6_DST_MASK => Self::KeyIpv6DstMask(
+                parse_ipv6_addr(payload)
+                    .context("failed to parse TCA_FLOWER_KEY_IPV6_DST_MASK")?,
+            ),
+            TCA_FLOWER_KEY_TCP_SRC => Self::KeyTcpSrc(
+                parse_u16_be(payload)
+                    .context("failed to parse TCA_FLOWER_KEY_TCP_SRC")?,
+            ),
+            TCA_FLOWER_KEY_TCP_DST => Self::KeyTcpDst(
+                parse_u16_be(payload)
+                    .context("failed to parse TCA_FLOWER_KEY_TCP_DST")?,
+            ),
+            TCA_FLOWER_KEY_UDP_SRC => Self::KeyUdpSrc(
+                parse_u16_be(payload)
+                    .context("failed to parse TCA_FLOWER_KEY_UDP_SRC")?,
+            ),
+            TCA_FLOWER_KEY_UDP_DST => Self::KeyUdpDst(
+                parse_u16_be(payload)
+                    .context("failed to parse TCA_FLOWER_KEY_UDP_DST")?,
+            ),
+            TCA_FLOWER_FLAGS => Self::Flags(
+                VecTcU32OptionFlag::from(
+                    parse_u32(payload)
+                        .context("failed to parse TCA_FLOWER_FLAGS")?,
+                )
+                .0,
+            ),
+            TCA_FLOWER_KEY_VLAN_ID => Self::KeyVlanId(
+                parse_u16(payload)
+                    .context("failed to parse TCA_FLOWER_KEY_VLAN_ID")?,
+            ),
+            TCA_FLOWER_KEY_VLAN_PRIO => Self::KeyVlanPrio(
+                parse_u8(payload)
+                    .context("failed to parse TCA_FLOWER_KEY_VLAN_PRIO")?,
+            ),
+            TCA_FLOWER_KEY_VLAN_ETH_TYPE => Self::KeyVlanEthType(
+                parse_u16_be(payload)
+                    .context("failed to parse TCA_FLOWER_KEY_VLAN_ETH_TYPE")?,
+            ),
+            TCA_FLOWER_KEY_ENC_KEY_ID => Self::KeyEncKeyId(
+                parse_u32_be(payload)
+                    .context("failed to parse TCA_FLOWER_KEY_ENC_KEY_ID")?,
+            ),
+            TCA_FLOWER_KEY_ENC_IPV4_SRC => Self::KeyEncIpv4Src(
+                parse_ipv4_addr(payload)
+                    .context("failed to parse TCA_FLOWER_KEY_ENC_IPV4_SRC")?,
+            ),
+            TCA_FLOWER_KEY_ENC_IPV4_SRC_MASK => {
+                Self::KeyEncIpv4SrcMask(parse_ipv4_addr(payload).context(
+                    "failed to parse TCA_FLOWER_KEY_ENC_IPV4_SRC_MASK",
+                )?)
+            }
+            TCA_FLOWER_KEY_ENC_IPV4_DST => Self::KeyEncIpv4Dst(
+                parse_ipv4_addr(payload)
+                    .context("failed to parse TCA_FLOWER_KEY_ENC_IPV4_DST")?,
+            ),
+            TCA_FLOWER_KEY_ENC_IPV4_DST_MASK => {
+                Self::KeyEncIpv4DstMask(parse_ipv4_addr(payload).context(
+                    "failed to parse TCA_FLOWER_KEY_ENC_IPV4_DST_MASK",
+                )?)
+            }
+            TCA_FLOWER_KEY_ENC_IPV6_SRC => Self::KeyEncIpv6Src(
+                parse_ipv6_addr(payload)
+                    .context("failed to parse TCA_FLOWER_KEY_ENC_IPV6_SRC")?,
+            ),
+            TCA_FLOWER_KEY_ENC_IPV6_SRC_MASK => {
+                Self::KeyEncIpv6SrcMask(parse_ipv6_addr(payload).context(
+                    "failed to parse TCA_FLOWER_KEY_ENC_IPV6_SRC_MASK",
+                )?)
+            }
+            TCA_FLOWER_KEY_ENC_IPV6_DST => Self::KeyEncIpv6Dst(
+                parse_ipv6_addr(payload)
+                    .context("failed to parse TCA_FLOWER_KEY_ENC_IPV6_DST")?,
+            ),
+            TCA_FLOWER_KEY_ENC_IPV6_DST_MASK => {
+                Self::KeyEncIpv6DstMask(parse_ipv6_addr(payload).context(
+                    "failed to parse TCA_FLOWER_KEY_ENC_IPV6_DST_MASK",
+                )?)
+            }
+            TCA_FLOWER_KEY_TCP_SRC_MASK => Self::KeyTcpSrcMask(
+                parse_u16_be(payload)
+                    .context("failed to parse TCA_FLOWER_KEY_TCP_SRC_MASK")?,
+            ),
+            TCA_FLOWER_KEY_TCP_DST_MASK => Self::KeyTcpDstMask(
+                parse_u16_be(payload)
+                    .context("failed to parse TCA_FLOWER_KEY_TCP_DST_MASK")?,
+            ),
+            TCA_FLOWER_KEY_UDP_SRC_MASK => Self::KeyUdpSrcMask(
+                parse_u16_be(payload)
+                    .context("failed to parse TCA_FLOWER_KEY_UDP_SRC_MASK")?,
+            ),
+            TCA_FLOWER_KEY_UDP_DST_MASK => Self::KeyUdpDstMask(
+                parse_u16_be(payload)
+                    .context("failed to parse TCA_FLOWER_KEY_UDP_DST_MASK")?,
+            ),
+            TCA_FLOWER_KEY_SCTP_SRC_MASK => Self::KeySctpSrcMask(
+                parse_u16_be(payload)
+                    .context("failed to parse TCA_FLOWER_KEY_SCTP_SRC_MASK")?,
+            ),
+            TCA_FLOWER_KEY_SCTP_DST_MASK => Self::KeySctpDstMask(
+                parse_u16_be(payload)
+                    .context("failed to parse TCA_FLOWER_KEY_SCTP_DST_MASK")?,
+            ),
+            TCA_FLOWER_KEY_SCTP_SRC => Self::KeySctpSrc(
+                parse_u16_be(payload)
+                    .context("failed to parse TCA_FLOWER_KEY_SCTP_SRC")?,
+            ),
+            TCA_FLOWER_KEY_SCTP_DST => Self::KeySctpDst(
+                parse_u16_be(payload)
+                    .context("failed to parse TCA_FLOWER_KEY_SCTP_DST")?,
+            ),
+            TCA_FLOWER_KEY_ENC_UDP_SRC_PORT => {
+                Self::KeyEncUdpSrcPort(parse_u16_be(payload).context(
+                    "failed to parse TCA_FLOWER_KEY_ENC_UDP_SRC_PORT",
+                )?)
+            }
+            TCA_FLOWER_KEY_ENC_UDP_SRC_PORT_MASK => {
+                Self::KeyEncUdpSrcPortMask(parse_u16_be(payload).context(
+                    "failed to parse TCA_FLOWER_KEY_ENC_UDP_SRC_PORT_MASK",
+                )?)
+            }
+            TCA_FLOWER_KEY_ENC_UDP_DST_PORT => {
+                Self::KeyEncUdpDstPort(parse_u16_be(payload).context(
+                    "failed to parse TCA_FLOWER_KEY_ENC_UDP_DST_PORT",
+                )?)
+            }
+            TCA_FLOWER_KEY_ENC_UDP_DST_PORT_MASK => {
+                Self::KeyEncUdpDstPortMask(parse_u16_be(payload).context(
+                    "failed to parse TCA_FLOWER_KEY_ENC_UDP_DST_PORT_MASK",
+                )?)
+            }
+            TCA_FLOWER_KEY_FLAGS => Self::KeyFlags(
+                VecTcFlowerKeyFlag::from(
+                    parse_u32_be(payload)
+                        .context("failed to parse TCA_FLOWER_KEY_FLAGS")?,
+                )
+                .0,
+            ),
+            TCA_FLOWER_KEY_FLAGS_MASK => Self::KeyFlagsMask(
+                VecTcFlowerKeyFlag::from(
+                    parse_u32_be(payload)
+                        .context("failed to parse TCA_FLOWER_KEY_FLAGS_MASK")?,
+                )
+                .0,
+            ),
+            TCA_FLOWER_KEY_ICMPV4_CODE => Self::KeyIcmpv4Code(
+                parse_u8(payload)
+                    .context("failed to parse TCA_FLOWER_KEY_ICMPV4_CODE")?,
+            ),
+            TCA_FLOWER_KEY_ICMPV4_CODE_MASK => {
+                Self::KeyIcmpv4CodeMask(parse_u8(payload).context(
+                    "failed to parse TCA_FLOWER_KEY_ICMPV4_CODE_MASK",
+                )?)
+            }
+            TCA_FLOWER_KEY_ICMPV4_TYPE => Self::KeyIcmpv4Type(
+                parse_u8(payload)
+                    .context("failed to parse TCA_FLOWER_KEY_ICMPV4_TYPE")?,
+            ),
+            TCA_FLOWER_KEY_ICMPV4_TYPE_MASK => {
+                Self::KeyIcmpv4TypeMask(parse_u8(payload).context(
+                    "failed to parse TCA_FLOWER_KEY_ICMPV4_TYPE_MASK",
+                )?)
+            }
+            TCA_FLOWER_KEY_ICMPV6_CODE => Self::KeyIcmpv6Code(
+                parse_u8(payload)
+                    .context("failed to parse TCA_FLOWER_KEY_ICMPV6_CODE")?,
+            ),
+            TCA_FLOWER_KEY_ICMPV6_CODE_MASK => {
+                Self::KeyIcmpv6CodeMask(parse_u8(payload).context(
+                    "failed to parse TCA_FLOWER_KEY_ICMPV6_CODE_MASK",
+                )?)
+            }
+            TCA_FLOWER_KEY_ICMPV6_TYPE => Self::KeyIcmpv6Type(
+                parse_u8(payload)
+                    .context("failed to parse TCA_FLOWER_KEY_ICMPV6_TYPE")?,
+            ),
+            TCA_FLOWER_KEY_ICMPV6_TYPE_MASK => {
+                Self::KeyIcmpv6TypeMask(parse_u8(payload).context(
+                    "failed to parse TCA_FLOWER_KEY_ICMPV6_TYPE_MASK",
+                )?)
+            }
+            TCA_FLOWER_KEY_ARP_SIP => Self::KeyArpSip(
+                parse_ipv4_addr(payload)
+                    .context("failed to parse TCA_FLOWER_KEY_ARP_SIP")?,
+            ),
+            TCA_FLOWER_KEY_ARP_SIP_MASK => Self::KeyArpSipMask(
+                parse_ipv4_addr(payload)
+                    .context("failed to parse TCA_FLOWER_KEY_ARP_SIP_MASK")?,
+            ),
+            TCA_FLOWER_KEY_ARP_TIP => Self::KeyArpTip(
+                parse_ipv4_addr(payload)
+                    .context("failed to parse TCA_FLOWER_KEY_ARP_TIP")?,
+            ),
+            TCA_FLOWER_KEY_ARP_TIP_MASK => Self::KeyArpTipMask(
+                parse_ipv4_addr(payload)
+                    .context("failed to parse TCA_FLOWER_KEY_ARP_TIP_MASK")?,
+            ),
+            TCA_FLOWER_KEY_ARP_OP => Self::KeyArpOp(
+                parse_u8(payload)
+                    .context("failed to parse TCA_FLOWER_KEY_ARP_OP")?,
+            ),
+            TCA_FLOWER_KEY_ARP_OP_MASK => Self::KeyArpOpMask(
+                parse_u8(payload)
+                    .context("failed to parse TCA_FLOWER_KEY_ARP_OP_MASK")?,
+            ),
+            TCA_FLOWER_KEY_ARP_SHA => Self::KeyArpSha(
+                parse_mac(payload)
+                    .context("failed to parse TCA_FLOWER_KEY_ARP_SHA")?,
+            ),
+            TCA_FLOWER_KEY_ARP_SHA_MASK => Self::KeyArpShaMask(
+                parse_mac(payload)
+                    .context("failed to parse TCA_FLOWER_KEY_ARP_SHA_MASK")?,
+            ),
+            TCA_FLOWER_KEY_ARP_THA => Self::KeyArpTha(
+                parse_mac(payload)
+                    .context("failed to parse TCA_FLOWER_KEY_ARP_THA")?,
+            ),
+            TCA_FLOWER_KEY_ARP_THA_MASK => Self::KeyArpThaMask(
+                parse_mac(payload)
+                    .context("failed to parse TCA_FLOWER_KEY_ARP_THA_MASK")?,
+            ),
+            TCA_FLOWER_KEY_MPLS_TTL => Self::KeyMplsTtl(
+                parse_u8(payload)
+                    .context("failed to parse TCA_FLOWER_KEY_MPLS_TTL")?,
+            ),
+            TCA_FLOWER_KEY_MPLS_BOS => Self::KeyMplsBos(
+                parse_u8(payload)
+                    .context("failed to parse TCA_FLOWER_KEY_MPLS_BOS")?,
+            ),
+            TCA_FLOWER_KEY_MPLS_TC => Self::KeyMplsTc(
+                parse_u8(payload)
+                    .context("failed to parse TCA_FLOWER_KEY_MPLS_TC")?,
+            ),
+            TCA_FLOWER_KEY_MPLS_LABEL => Self::KeyMplsLabel(
+                parse_u32(payload)
+                    .context("failed to parse TCA_FLOWER_KEY_MPLS_LABEL")?,
+            ),
+            TCA_FLOWER_KEY_TCP_FLAGS => Self::KeyTcpFlags(
+                parse_u16_be(payload)
+                    .context("failed to parse TCA_FLOWER_KEY_TCP_FLAGS")?,
+            ),
+            TCA_FLOWER_KEY_TCP_FLAGS_MASK => Self::KeyTcpFlagsMask(
+                parse_u16_be(payload)
+                    .context("failed to parse TCA_FLOWER_KEY_TCP_FLAGS_MASK")?,
+            ),
+            TCA_FLOWER_KEY_IP_TOS => Self::KeyIpTos(
+                parse_u8(payload)
+                    .context("failed to parse TCA_FLOWER_KEY_IP_TOS")?,
+            ),
+            TCA_FLOWER_KEY_IP_TOS_MASK => Self::KeyIpTosMask(
+                parse_u8(payload)
+                    .context("failed to parse TCA_FLOWER_KEY_IP_TOS_MASK")?,
+            ),
+            TCA_FLOWER_KEY_IP_TTL => Self::KeyIpTtl(
+                parse_u8(payload)
+                    .context("failed to parse TCA_FLOWER_KEY_IP_TTL")?,
+            ),
+            TCA_FLOWER_KEY_IP_TTL_MASK => Self::KeyIpTtlMask(
+                parse_u8(payload)
+                    .context("failed to parse TCA_FLOWER_KEY_IP_TTL_MASK")?,
+            ),
+            TCA_FLOWER_KEY_CVLAN_ID => Self::KeyCvlanId(
+                parse_u16(payload)
+                    .context("failed to parse TCA_FLOWER_KEY_CVLAN_ID")?,
+            ),
+            TCA_FLOWER_KEY_CVLAN_PRIO => Self::KeyCvlanPrio(
+                parse_u8(payload)
+                    .context("failed to parse TCA_FLOWER_KEY_CVLAN_PRIO")?,
+            ),
+            TCA_FLOWER_KEY_CVLAN_ETH_TYPE => Self::KeyCvlanEthType(
+                parse_u16_be(payload)
+                    .context("failed to parse TCA_FLOWER_KEY_CVLAN_ETH_TYPE")?,
+            ),
+            TCA_FLOWER_KEY_ENC_IP_TOS => Self::KeyEncIpTos(
+                parse_u8(payload)
+                    .context("failed to parse TCA_FLOWER_KEY_ENC_IP_TOS")?,
+            ),
+            TCA_FLOWER_KEY_ENC_IP_TOS_MASK => {
+                Self::KeyEncIpTosMask(parse_u8(payload).context(
+                    "failed to parse TCA_FLOWER_KEY_ENC_IP_TOS_MASK",
+                )?)
+            }
+            TCA_FLOWER_KEY_ENC_IP_TTL => Self::KeyEncIpTtl(
+                parse_u8(payload)
+                    .context("failed to parse TCA_FLOWER_KEY_ENC_IP_TTL")?,
+            ),
+            TCA_FLOWER_KEY_ENC_IP_TTL_MASK => {
+                Self::KeyEncIpTtlMask(parse_u8(payload).context(
+                    "failed to parse TCA_FLOWER_KEY_ENC_IP_TTL_MASK",
+                )?)
+            }
+            TCA_FLOWER_KEY_ENC_OPTS => {
+                let mut opts = vec![];
+                for opt in NlasIterator::new(payload) {
+                    let opt = opt.context("invalid TCA_FLOWER_KEY_ENC_OPTS")?;
+                    opts.push(
+                        TcFlowerEncOpts::parse(&opt).context(
+                            "failed to parse TCA_FLOWER_KEY_ENC_OPTS",
+                        )?,
+                    );
+                }
+                Self::KeyEncOpts(opts)
+            }
+            TCA_FLOWER_KEY_ENC_OPTS_MASK => {
+                let mut opts = vec![];
+                for opt in NlasIterator::new(payload) {
+                    let opt =
+                        opt.context("invalid TCA_FLOWER_KEY_ENC_OPTS_MASK")?;
+                    opts.push(TcFlowerEncOpts::parse(&opt).context(
+                        "failed to parse TCA_FLOWER_KEY_ENC_OPTS_MASK",
+                    )?);
+                }
+                Self::KeyEncOptsMask(opts)
+            }
+            TCA_FLOWER_IN_HW_COUNT => Self::InHwCount(
+                parse_u32(payload)
+                    .context("failed to parse TCA_FLOWER_IN_HW_COUNT")?,
+            ),
+            TCA_FLOWER_KEY_PORT_SRC_MIN => Self::KeyPortSrcMin(
+                parse_u16_be(payload)
+                    .context("failed to parse TCA_FLOWER_KEY_PORT_SRC_MIN")?,
+            ),
+            TCA_FLOWER_KEY_PORT_SRC_MAX => Self::KeyPortSrcMax(
+                parse_u16_be(payload)
+                    .context("failed to parse TCA_FLOWER_KEY_PORT_SRC_MAX")?,
+            ),
+            TCA_FLOWER_KEY_PORT_DST_MIN => Self::KeyPortDstMin(
+                parse_u16_be(payload)
+                    .context("failed to parse TCA_FLOWER_KEY_PORT_DST_MIN")?,
+            ),
+            TCA_FLOWER_KEY_PORT_DST_MAX => Self::KeyPortDstMax(
+                parse_u16_be(payload)
+                    .context("failed to parse TCA_FLOWER_KEY_PORT_DST_MAX")?,
+            ),
+            TCA_FLOWER_KEY_CT_STATE => Self::KeyCtState(
+                VecTcFlowerCtFlag::from(
+                    parse_u16(payload)
+                        .context("failed to parse TCA_FLOWER_KEY_CT_STATE")?,
+                )
+                .0,
+            ),
+            TCA_FLOWER_KEY_CT_STATE_MASK => {
+                Self::KeyCtStateMask(
+                    VecTcFlowerCtFlag::from(parse_u16(payload).context(
+                        "failed to parse TCA_FLOWER_KEY_CT_STATE_MASK",
+                    )?)
+                    .0,
+                )
+            }
+            TCA_FLOWER_KEY_CT_ZONE => Self::KeyCtZone(
+                parse_u16(payload)
+                    .context("failed to parse TCA_FLOWER_KEY_CT_ZONE")?,
+            ),
+            TCA_FLOWER_KEY_CT_ZONE_MASK => Self::KeyCtZoneMask(
+                parse_u16(payload)
+                    .context("failed to parse TCA_FLOWER_KEY_CT_ZONE_MASK")?,
+            ),
+            TCA_FLOWER_KEY_CT_MARK => Self::KeyCtMark(
+                parse_u32(payload)
+                    .context("failed to parse TCA_FLOWER_KEY_CT_MARK")?,
+            ),
+            TCA_FLOWER_KEY_CT_MARK_MASK => Self::KeyCtMarkMask(
+                parse_u32(payload)
+                    .context("failed to parse TCA_FLOWER_KEY_CT_MARK_MASK")?,
+            ),
+            TCA_FLOWER_KEY_CT_LABELS => Self::KeyCtLabels(
+                parse_ct_labels(payload)
+                    .context("failed to parse TCA_FLOWER_KEY_CT_LABELS")?,
+            ),
+            TCA_FLOWER_KEY_CT_LABELS_MASK => Self::KeyCtLabelsMask(
+                parse_ct_labels(payload)
+                    .context("failed to parse TCA_FLOWER_KEY_CT_LABELS_MASK")?,
+            ),
+            TCA_FLOWER_KEY_MPLS_OPTS => {
+                let mut entries = vec![];
+                for entry in NlasIterator::new(payload) {
+                    let entry =
+                        entry.context("invalid TCA_FLOWER_KEY_MPLS_OPTS")?;
+                    let mut nlas = vec![];
+                    for nla in NlasIterator::new(entry.value()) {
+                        let nla = nla
+                            .context("invalid TCA_FLOWER_KEY_MPLS_OPTS_LSE")?;
+                        nlas.push(TcFlowerMplsLseOpt::parse(&nla).context(
+                            "failed to parse TCA_FLOWER_KEY_MPLS_OPTS_LSE",
+                        )?);
+                    }
+                    entries.push(nlas);
+                }
+                Self::KeyMplsOpts(entries)
+            }
+            TCA_FLOWER_KEY_HASH => Self::KeyHash(
+                parse_u32(payload)
+                    .context("failed to parse TCA_FLOWER_KEY_HASH")?,
+            ),
+            TCA_FLOWER_KEY_HASH_MASK => Self::KeyHashMask(
+                parse_u32(payload)
+                    .context("failed to parse TCA_FLOWER_KEY_HASH_MASK")?,
+            ),
+            TCA_FLOWER_KEY_NUM_OF_VLANS => Self::KeyNumOfVlans(
+                parse_u8(payload)
+                    .context("failed to parse TCA_FLOWER_KEY_NUM_OF_VLANS")?,
+            ),
+            TCA_FLOWER_KEY_PPPOE_SID => Self::KeyPppoeSid(
+                parse_u16_be(payload)
+                    .context("failed to parse TCA_FLOWER_KEY_PPPOE_SID")?,
+            ),
+            TCA_FLOWER_KEY_PPP_PROTO => Self::KeyPppProto(
+                parse_u16_be(payload)
+                    .context("failed to parse TCA_FLOWER_KEY_PPP_PROTO")?,
+            ),
+            TCA_FLOWER_KEY_L2TPV3_SID => Self::KeyL2tpv3Sid(
+                parse_u32_be(payload)
+                    .context("failed to parse TCA_FLOWER_KEY_L2TPV3_SID")?,
+            ),
+            _ => Self::Other(
+                DefaultNla::parse(buf).context("failed to parse flower nla")?,
+            ),
+        })
+    }
+}
+
+const CT_LABELS_LEN: usize = 16;
+
+fn parse_ct_labels(payload: &[u8]) -> Result<[u8; CT_LABELS_LEN], DecodeError> {
+    if payload.len() == CT_LABELS_LEN {
+        let mut labels = [0u8; CT_LABELS_LEN];
+        labels.copy_from_slice(payload);
+        Ok(labels)
+    } else {
+        Err(DecodeError::from(format!(
+            "Invalid u8 array length {}, expecting {CT_LABELS_LEN} \
+            for conntrack labels, got {:?}",
+            payload.len(),
+            payload,
+        )))
+    }
+}
+
+const TCA_FLOWER_KEY_MPLS_OPTS_LSE: u16 = 1;
+
+// Each label stack entry is stored in a TCA_FLOWER_KEY_MPLS_OPTS_LSE.
+struct MplsLseEntry<'a>(&'a [TcFlowerMplsLseOpt]);
+
+fn mpls_lse_entries(
+    entries: &[Vec<TcFlowerMplsLseOpt>],
+) -> Vec<MplsLseEntry<'_>> {
+    entries
+        .iter()
+        .map(|entry| MplsLseEntry(entry.as_slice()))
+        .collect()
+}
+
+impl Nla for MplsLseEntry<'_> {
+    fn value_len(&self) -> usize {
+        self.0.buffer_len()
+    }
+
+    fn emit_value(&self, buffer: &mut [u8]) {
+        self.0.emit(buffer)
+    }
+
+    fn is_nested(&self) -> bool {
+        true
+    }
+
+    fn kind(&self) -> u16 {
+        TCA_FLOWER_KEY_MPLS_OPTS_LSE
+    }
+}
+
+const TCA_FLOWER_KEY_MPLS_OPT_LSE_DEPTH: u16 = 1;
+const TCA_FLOWER_KEY_MPLS_OPT_LSE_TTL: u16 = 2;
+const TCA_FLOWER_KEY_MPLS_OPT_LSE_BOS: u16 = 3;
+const TCA_FLOWER_KEY_MPLS_OPT_LSE_TC: u16 = 4;
+const TCA_FLOWER_KEY_MPLS_OPT_LSE_LABEL: u16 = 5;
+
+#[derive(Debug, PartialEq, Eq, Clone)]
+#[non_exhaustive]
+pub enum TcFlowerMplsLseOpt {
+    /// Position of the entry in the label stack, starting from 1 as the
+    /// outermost
+    Depth(u8),
+    Ttl(u8),
+    /// Bottom of stack bit
+    Bos(u8),
+    /// Traffic class of 3 bits
+    Tc(u8),
+    /// Label of 20 bits
+    Label(u32),
+    Other(DefaultNla),
+}
+
+impl Nla for TcFlowerMplsLseOpt {
+    fn value_len(&self) -> usize {
+        match self {
+            Self::Depth(_) | Self::Ttl(_) | Self::Bos(_) | Self::Tc(_) => 1,
+            Self::Label(_) => 4,
+            Self::Other(attr) => attr.value_len(),
+        }
+    }
+
+    fn emit_value(&self, buffer: &mut [u8]) {
+        match self {
+            Self::Depth(d) | Self::Ttl(d) | Self::Bos(d) | Self::Tc(d) => {
+                buffer[0] = *d
+            }
+            Self::Label(d) => NativeEndian::write_u32(buffer, *d),
+            Self::Other(attr) => attr.emit_value(buffer),
+        }
+    }
+
+    fn kind(&self) -> u16 {
+        match self {
+            Self::Depth(_) => TCA_FLOWER_KEY_MPLS_OPT_LSE_DEPTH,
+            Self::Ttl(_) => TCA_FLOWER_KEY_MPLS_OPT_LSE_TTL,
+            Self::Bos(_) => TCA_FLOWER_KEY_MPLS_OPT_LSE_BOS,
+            Self::Tc(_) => TCA_FLOWER_KEY_MPLS_OPT_LSE_TC,
+            Self::Label(_) => TCA_FLOWER_KEY_MPLS_OPT_LSE_LABEL,
+            Self::Other(attr) => attr.kind(),
+        }
+    }
+}
+
+impl<'a, T: AsRef<[u8]> + ?Sized> Parseable<NlaBuffer<&'a T>>
+    for TcFlowerMplsLseOpt
+{
+    fn parse(buf: &NlaBuffer<&'a T>) -> Result<Self, DecodeError> {
+        let payload = buf.value();
+        Ok(match buf.kind() {
+            TCA_FLOWER_KEY_MPLS_OPT_LSE_DEPTH => {
+                Self::Depth(parse_u8(payload).context(
+                    "failed to parse TCA_FLOWER_KEY_MPLS_OPT_LSE_DEPTH",
+                )?)
+            }
+            TCA_FLOWER_KEY_MPLS_OPT_LSE_TTL => {
+                Self::Ttl(parse_u8(payload).context(
+                    "failed to parse TCA_FLOWER_KEY_MPLS_OPT_LSE_TTL",
+                )?)
+            }
+            TCA_FLOWER_KEY_MPLS_OPT_LSE_BOS => {
+                Self::Bos(parse_u8(payload).context(
+                    "failed to parse TCA_FLOWER_KEY_MPLS_OPT_LSE_BOS",
+                )?)
+            }
+            TCA_FLOWER_KEY_MPLS_OPT_LSE_TC => {
+                Self::Tc(parse_u8(payload).context(
+                    "failed to parse TCA_FLOWER_KEY_MPLS_OPT_LSE_TC",
+                )?)
+            }
+            TCA_FLOWER_KEY_MPLS_OPT_LSE_LABEL => {
+                Self::Label(parse_u32(payload).context(
+                    "failed to parse TCA_FLOWER_KEY_MPLS_OPT_LSE_LABEL",
+                )?)
+            }
+            _ => Self::Other(
+                DefaultNla::parse(buf)
+                    .context("failed to parse flower mpls lse nla")?,
+            ),
+        })
+    }
+}
@@ -0,0 +1,391 @@
+// SPDX-License-Identifier: MIT
+
+use anyhow::Context;
+use byteorder::{BigEndian, ByteOrder, NativeEndian};
+use netlink_packet_utils::{
+    nla::{DefaultNla, Nla, NlaBuffer, NlasIterator},
+    parsers::{parse_u16_be, parse_u32, parse_u32_be, parse_u8},
+    traits::{Emitable, Parseable},
+    DecodeError,
+};
+
+const TCA_FLOWER_KEY_ENC_OPTS_GENEVE: u16 = 1;
+const TCA_FLOWER_KEY_ENC_OPTS_VXLAN: u16 = 2;
+const TCA_FLOWER_KEY_ENC_OPTS_ERSPAN: u16 = 3;
+const TCA_FLOWER_KEY_ENC_OPTS_GTP: u16 = 4;
+
+/// Tunnel metadata options of `TcFilterFlowerOption::KeyEncOpts` and
+/// `TcFilterFlowerOption::KeyEncOptsMask`.
+/// The mask should hold the same options in the same order as the key.
+/// Also used by `TcActionTunnelKeyOption::EncOpts`.
+#[derive(Debug, PartialEq, Eq, Clone)]
+#[non_exhaustive]
+pub enum TcFlowerEncOpts {
+    /// A single geneve option, multiple geneve options could be matched
+    /// by placing multiple `Geneve` in the list
+    Geneve(Vec<TcFlowerGeneveOpt>),
+    Vxlan(Vec<TcFlowerVxlanOpt>),
+    Erspan(Vec<TcFlowerErspanOpt>),
+    Gtp(Vec<TcFlowerGtpOpt>),
+    Other(DefaultNla),
+}
+
+impl Nla for TcFlowerEncOpts {
+    fn value_len(&self) -> usize {
+        match self {
+            Self::Geneve(v) => v.as_slice().buffer_len(),
+            Self::Vxlan(v) => v.as_slice().buffer_len(),
+            Self::Erspan(v) => v.as_slice().buffer_len(),
+            Self::Gtp(v) => v.as_slice().buffer_len(),
+            Self::Other(attr) => attr.value_len(),
+        }
+    }
+
+    fn emit_value(&self, buffer: &mut [u8]) {
+        match self {
+            Self::Geneve(v) => v.as_slice().emit(buffer),
+            Self::Vxlan(v) => v.as_slice().emit(buffer),
+            Self::Erspan(v) => v.as_slice().emit(buffer),
+            Self::Gtp(v) => v.as_slice().emit(buffer),
+            Self::Other(attr) => attr.emit_value(buffer),
+        }
+    }
+
+    // Like iproute2, only the geneve options are not flagged as nested
+    fn is_nested(&self) -> bool {
+        match self {
+            Self::Geneve(_) => false,
+            Self::Other(attr) => attr.is_nested(),
+            _ => true,
+        }
+    }
+
+    fn kind(&self) -> u16 {
+        match self {
+            Self::Geneve(_) => TCA_FLOWER_KEY_ENC_OPTS_GENEVE,
+            Self::Vxlan(_) => TCA_FLOWER_KEY_ENC_OPTS_VXLAN,
+            Self::Erspan(_) => TCA_FLOWER_KEY_ENC_OPTS_ERSPAN,
+            Self::Gtp(_) => TCA_FLOWER_KEY_ENC_OPTS_GTP,
+            Self::Other(attr) => attr.kind(),
+        }
+    }
+}
+
+impl<'a, T: AsRef<[u8]> + ?Sized> Parseable<NlaBuffer<&'a T>>
+    for TcFlowerEncOpts
+{
+    fn parse(buf: &NlaBuffer<&'a T>) -> Result<Self, DecodeError> {
+        let payload = buf.value();
+        Ok(match buf.kind() {
+            TCA_FLOWER_KEY_ENC_OPTS_GENEVE => {
+                let mut nlas = vec![];
+                for nla in NlasIterator::new(payload) {
+                    let nla =
+                        nla.context("invalid TCA_FLOWER_KEY_ENC_OPTS_GENEVE")?;
+                    nlas.push(TcFlowerGeneveOpt::parse(&nla).context(
+                        "failed to parse TCA_FLOWER_KEY_ENC_OPTS_GENEVE",
+                    )?);
+                }
+                Self::Geneve(nlas)
+            }
+            TCA_FLOWER_KEY_ENC_OPTS_VXLAN => {
+                let mut nlas = vec![];
+                for nla in NlasIterator::new(payload) {
+                    let nla =
+                        nla.context("invalid TCA_FLOWER_KEY_ENC_OPTS_VXLAN")?;
+                    nlas.push(TcFlowerVxlanOpt::parse(&nla).context(
+                        "failed to parse TCA_FLOWER_KEY_ENC_OPTS_VXLAN",
+                    )?);
+                }
+                Self::Vxlan(nlas)
+            }
+            TCA_FLOWER_KEY_ENC_OPTS_ERSPAN => {
+                let mut nlas = vec![];
+                for nla in NlasIterator::new(payload) {
+                    let nla =
+                        nla.context("invalid TCA_FLOWER_KEY_ENC_OPTS_ERSPAN")?;
+                    nlas.push(TcFlowerErspanOpt::parse(&nla).context(
+                        "failed to parse TCA_FLOWER_KEY_ENC_OPTS_ERSPAN",
+                    )?);
+                }
+                Self::Erspan(nlas)
+            }
+            TCA_FLOWER_KEY_ENC_OPTS_GTP => {
+                let mut nlas = vec![];
+                for nla in NlasIterator::new(payload) {
+                    let nla =
+                        nla.context("invalid TCA_FLOWER_KEY_ENC_OPTS_GTP")?;
+                    nlas.push(TcFlowerGtpOpt::parse(&nla).context(
+                        "failed to parse TCA_FLOWER_KEY_ENC_OPTS_GTP",
+                    )?);
+                }
+                Self::Gtp(nlas)
+            }
+            _ => Self::Other(
+                DefaultNla::parse(buf)
+                    .context("failed to parse flower enc opts nla")?,
+            ),
+        })
+    }
+}
+
+const TCA_FLOWER_KEY_ENC_OPT_GENEVE_CLASS: u16 = 1;
+const TCA_FLOWER_KEY_ENC_OPT_GENEVE_TYPE: u16 = 2;
+const TCA_FLOWER_KEY_ENC_OPT_GENEVE_DATA: u16 = 3;
+
+#[derive(Debug, PartialEq, Eq, Clone)]
+#[non_exhaustive]
+pub enum TcFlowerGeneveOpt {
+    Class(u16),
+    Type(u8),
+    /// Option data of 4 to 128 bytes in multiple of 4
+    Data(Vec<u8>),
+    Other(DefaultNla),
+}
+
+impl Nla for TcFlowerGeneveOpt {
+    fn value_len(&self) -> usize {
+        match self {
+            Self::Class(_) => 2,
+            Self::Type(_) => 1,
+            Self::Data(v) => v.len(),
+            Self::Other(attr) => attr.value_len(),
+        }
+    }
+
+    fn emit_value(&self, buffer: &mut [u8]) {
+        match self {
+            Self::Class(d) => BigEndian::write_u16(buffer, *d),
+            Self::Type(d) => buffer[0] = *d,
+            Self::Data(v) => buffer.copy_from_slice(v.as_slice()),
+            Self::Other(attr) => attr.emit_value(buffer),
+        }
+    }
+
+    fn kind(&self) -> u16 {
+        match self {
+            Self::Class(_) => TCA_FLOWER_KEY_ENC_OPT_GENEVE_CLASS,
+            Self::Type(_) => TCA_FLOWER_KEY_ENC_OPT_GENEVE_TYPE,
+            Self::Data(_) => TCA_FLOWER_KEY_ENC_OPT_GENEVE_DATA,
+            Self::Other(attr) => attr.kind(),
+        }
+    }
+}
+
+impl<'a, T: AsRef<[u8]> + ?Sized> Parseable<NlaBuffer<&'a T>>
+    for TcFlowerGeneveOpt
+{
+    fn parse(buf: &NlaBuffer<&'a T>) -> Result<Self, DecodeError> {
+        let payload = buf.value();
+        Ok(match buf.kind() {
+            TCA_FLOWER_KEY_ENC_OPT_GENEVE_CLASS => {
+                Self::Class(parse_u16_be(payload).context(
+                    "failed to parse TCA_FLOWER_KEY_ENC_OPT_GENEVE_CLASS",
+                )?)
+            }
+            TCA_FLOWER_KEY_ENC_OPT_GENEVE_TYPE => {
+                Self::Type(parse_u8(payload).context(
+                    "failed to parse TCA_FLOWER_KEY_ENC_OPT_GENEVE_TYPE",
+                )?)
+            }
+            TCA_FLOWER_KEY_ENC_OPT_GENEVE_DATA => Self::Data(payload.to_vec()),
+            _ => Self::Other(
+                DefaultNla::parse(buf)
+                    .context("failed to parse flower geneve opt nla")?,
+            ),
+        })
+    }
+}
+
+const TCA_FLOWER_KEY_ENC_OPT_VXLAN_GBP: u16 = 1;
+
+#[derive(Debug, PartialEq, Eq, Clone)]
+#[non_exhaustive]
+pub enum TcFlowerVxlanOpt {
+    /// Group Based Policy ID
+    Gbp(u32),
+    Other(DefaultNla),
+}
+
+impl Nla for TcFlowerVxlanOpt {
+    fn value_len(&self) -> usize {
+        match self {
+            Self::Gbp(_) => 4,
+            Self::Other(attr) => attr.value_len(),
+        }
+    }
+
+    fn emit_value(&self, buffer: &mut [u8]) {
+        match self {
+            Self::Gbp(d) => NativeEndian::write_u32(buffer, *d),
+            Self::Other(attr) => attr.emit_value(buffer),
+        }
+    }
+
+    fn kind(&self) -> u16 {
+        match self {
+            Self::Gbp(_) => TCA_FLOWER_KEY_ENC_OPT_VXLAN_GBP,
+            Self::Other(attr) => attr.kind(),
+        }
+    }
+}
+
+impl<'a, T: AsRef<[u8]> + ?Sized> Parseable<NlaBuffer<&'a T>>
+    for TcFlowerVxlanOpt
+{
+    fn parse(buf: &NlaBuffer<&'a T>) -> Result<Self, DecodeError> {
+        let payload = buf.value();
+        Ok(match buf.kind() {
+            TCA_FLOWER_KEY_ENC_OPT_VXLAN_GBP => {
+                Self::Gbp(parse_u32(payload).context(
+                    "failed to parse TCA_FLOWER_KEY_ENC_OPT_VXLAN_GBP",
+                )?)
+            }
+            _ => Self::Other(
+                DefaultNla::parse(buf)
+                    .context("failed to parse flower vxlan opt nla")?,
+            ),
+        })
+    }
+}
+
+const TCA_FLOWER_KEY_ENC_OPT_ERSPAN_VER: u16 = 1;
+const TCA_FLOWER_KEY_ENC_OPT_ERSPAN_INDEX: u16 = 2;
+const TCA_FLOWER_KEY_ENC_OPT_ERSPAN_DIR: u16 = 3;
+const TCA_FLOWER_KEY_ENC_OPT_ERSPAN_HWID: u16 = 4;
+
+#[derive(Debug, PartialEq, Eq, Clone)]
+#[non_exhaustive]
+pub enum TcFlowerErspanOpt {
+    /// ERSPAN version, 1 or 2
+    Ver(u8),
+    /// Session index, only for version 1
+    Index(u32),
+    /// Mirrored traffic direction, only for version 2
+    Dir(u8),
+    /// Hardware ID, only for version 2
+    Hwid(u8),
+    Other(DefaultNla),
+}
+
+impl Nla for TcFlowerErspanOpt {
+    fn value_len(&self) -> usize {
+        match self {
+            Self::Ver(_) | Self::Dir(_) | Self::Hwid(_) => 1,
+            Self::Index(_) => 4,
+            Self::Other(attr) => attr.value_len(),
+        }
+    }
+
+    fn emit_value(&self, buffer: &mut [u8]) {
+        match self {
+            Self::Ver(d) | Self::Dir(d) | Self::Hwid(d) => buffer[0] = *d,
+            Self::Index(d) => BigEndian::write_u32(buffer, *d),
+            Self::Other(attr) => attr.emit_value(buffer),
+        }
+    }
+
+    fn kind(&self) -> u16 {
+        match self {
+            Self::Ver(_) => TCA_FLOWER_KEY_ENC_OPT_ERSPAN_VER,
+            Self::Index(_) => TCA_FLOWER_KEY_ENC_OPT_ERSPAN_INDEX,
+            Self::Dir(_) => TCA_FLOWER_KEY_ENC_OPT_ERSPAN_DIR,
+            Self::Hwid(_) => TCA_FLOWER_KEY_ENC_OPT_ERSPAN_HWID,
+            Self::Other(attr) => attr.kind(),
+        }
+    }
+}
+
+impl<'a, T: AsRef<[u8]> + ?Sized> Parseable<NlaBuffer<&'a T>>
+    for TcFlowerErspanOpt
+{
+    fn parse(buf: &NlaBuffer<&'a T>) -> Result<Self, DecodeError> {
+        let payload = buf.value();
+        Ok(match buf.kind() {
+            TCA_FLOWER_KEY_ENC_OPT_ERSPAN_VER => {
+                Self::Ver(parse_u8(payload).context(
+                    "failed to parse TCA_FLOWER_KEY_ENC_OPT_ERSPAN_VER",
+                )?)
+            }
+            TCA_FLOWER_KEY_ENC_OPT_ERSPAN_INDEX => {
+                Self::Index(parse_u32_be(payload).context(
+                    "failed to parse TCA_FLOWER_KEY_ENC_OPT_ERSPAN_INDEX",
+                )?)
+            }
+            TCA_FLOWER_KEY_ENC_OPT_ERSPAN_DIR => {
+                Self::Dir(parse_u8(payload).context(
+                    "failed to parse TCA_FLOWER_KEY_ENC_OPT_ERSPAN_DIR",
+                )?)
+            }
+            TCA_FLOWER_KEY_ENC_OPT_ERSPAN_HWID => {
+                Self::Hwid(parse_u8(payload).context(
+                    "failed to parse TCA_FLOWER_KEY_ENC_OPT_ERSPAN_HWID",
+                )?)
+            }
+            _ => Self::Other(
+                DefaultNla::parse(buf)
+                    .context("failed to parse flower erspan opt nla")?,
+            ),
+        })
+    }
+}
+
+const TCA_FLOWER_KEY_ENC_OPT_GTP_PDU_TYPE: u16 = 1;
+const TCA_FLOWER_KEY_ENC_OPT_GTP_QFI: u16 = 2;
+
+#[derive(Debug, PartialEq, Eq, Clone)]
+#[non_exhaustive]
+pub enum TcFlowerGtpOpt {
+    PduType(u8),
+    /// QoS Flow Identifier
+    Qfi(u8),
+    Other(DefaultNla),
+}
+
+impl Nla for TcFlowerGtpOpt {
+    fn value_len(&self) -> usize {
+        match self {
+            Self::PduType(_) | Self::Qfi(_) => 1,
+            Self::Other(attr) => attr.value_len(),
+        }
+    }
+
+    fn emit_value(&self, buffer: &mut [u8]) {
+        match self {
+            Self::PduType(d) | Self::Qfi(d) => buffer[0] = *d,
+            Self::Other(attr) => attr.emit_value(buffer),
+        }
+    }
+
+    fn kind(&self) -> u16 {
+        match self {
+            Self::PduType(_) => TCA_FLOWER_KEY_ENC_OPT_GTP_PDU_TYPE,
+            Self::Qfi(_) => TCA_FLOWER_KEY_ENC_OPT_GTP_QFI,
+            Self::Other(attr) => attr.kind(),
+        }
+    }
+}
+
+impl<'a, T: AsRef<[u8]> + ?Sized> Parseable<NlaBuffer<&'a T>>
+    for TcFlowerGtpOpt
+{
+    fn parse(buf: &NlaBuffer<&'a T>) -> Result<Self, DecodeError> {
+        let payload = buf.value();
+        Ok(match buf.kind() {
+            TCA_FLOWER_KEY_ENC_OPT_GTP_PDU_TYPE => {
+                Self::PduType(parse_u8(payload).context(
+                    "failed to parse TCA_FLOWER_KEY_ENC_OPT_GTP_PDU_TYPE",
+                )?)
+            }
+            TCA_FLOWER_KEY_ENC_OPT_GTP_QFI => {
+                Self::Qfi(parse_u8(payload).context(
+                    "failed to parse TCA_FLOWER_KEY_ENC_OPT_GTP_QFI",
+                )?)
+            }
+            _ => Self::Other(
+                DefaultNla::parse(buf)
+                    .context("failed to parse flower gtp opt nla")?,
+            ),
+        })
+    }
+}
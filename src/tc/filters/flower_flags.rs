@@ -0,0 +1,137 @@
+// SPDX-License-Identifier: MIT
+
+const TCA_FLOWER_KEY_FLAGS_IS_FRAGMENT: u32 = 1 << 0;
+const TCA_FLOWER_KEY_FLAGS_FRAG_IS_FIRST: u32 = 1 << 1;
+
+/// Flags of `TcFilterFlowerOption::KeyFlags` and
+/// `TcFilterFlowerOption::KeyFlagsMask`
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+#[non_exhaustive]
+pub enum TcFlowerKeyFlag {
+    IsFragment,
+    FragIsFirst,
+    Other(u32),
+}
+
+impl From<TcFlowerKeyFlag> for u32 {
+    fn from(v: TcFlowerKeyFlag) -> u32 {
+        match v {
+            TcFlowerKeyFlag::IsFragment => TCA_FLOWER_KEY_FLAGS_IS_FRAGMENT,
+            TcFlowerKeyFlag::FragIsFirst => TCA_FLOWER_KEY_FLAGS_FRAG_IS_FIRST,
+            TcFlowerKeyFlag::Other(i) => i,
+        }
+    }
+}
+
+const ALL_KEY_FLAGS: [TcFlowerKeyFlag; 2] =
+    [TcFlowerKeyFlag::IsFragment, TcFlowerKeyFlag::FragIsFirst];
+
+#[derive(Clone, Eq, PartialEq, Debug)]
+pub(crate) struct VecTcFlowerKeyFlag(pub(crate) Vec<TcFlowerKeyFlag>);
+
+impl From<u32> for VecTcFlowerKeyFlag {
+    fn from(d: u32) -> Self {
+        let mut got: u32 = 0;
+        let mut ret = Vec::new();
+        for flag in ALL_KEY_FLAGS {
+            if (d & (u32::from(flag))) > 0 {
+                ret.push(flag);
+                got += u32::from(flag);
+            }
+        }
+        if got != d {
+            ret.push(TcFlowerKeyFlag::Other(d - got));
+        }
+        Self(ret)
+    }
+}
+
+impl From<&VecTcFlowerKeyFlag> for u32 {
+    fn from(v: &VecTcFlowerKeyFlag) -> u32 {
+        let mut d: u32 = 0;
+        for flag in &v.0 {
+            d += u32::from(*flag);
+        }
+        d
+    }
+}
+
+const TCA_FLOWER_KEY_CT_FLAGS_NEW: u16 = 1 << 0;
+const TCA_FLOWER_KEY_CT_FLAGS_ESTABLISHED: u16 = 1 << 1;
+const TCA_FLOWER_KEY_CT_FLAGS_RELATED: u16 = 1 << 2;
+const TCA_FLOWER_KEY_CT_FLAGS_TRACKED: u16 = 1 << 3;
+const TCA_FLOWER_KEY_CT_FLAGS_INVALID: u16 = 1 << 4;
+const TCA_FLOWER_KEY_CT_FLAGS_REPLY: u16 = 1 << 5;
+
+/// Connection tracking state of `TcFilterFlowerOption::KeyCtState` and
+/// `TcFilterFlowerOption::KeyCtStateMask`
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+#[non_exhaustive]
+pub enum TcFlowerCtFlag {
+    /// Beginning of a new connection
+    New,
+    /// Part of an existing connection
+    Established,
+    /// Related to an established connection
+    Related,
+    /// Conntrack has occurred
+    Tracked,
+    /// Conntrack is invalid
+    Invalid,
+    /// Packet is in the reply direction
+    Reply,
+    Other(u16),
+}
+
+impl From<TcFlowerCtFlag> for u16 {
+    fn from(v: TcFlowerCtFlag) -> u16 {
+        match v {
+            TcFlowerCtFlag::New => TCA_FLOWER_KEY_CT_FLAGS_NEW,
+            TcFlowerCtFlag::Established => TCA_FLOWER_KEY_CT_FLAGS_ESTABLISHED,
+            TcFlowerCtFlag::Related => TCA_FLOWER_KEY_CT_FLAGS_RELATED,
+            TcFlowerCtFlag::Tracked => TCA_FLOWER_KEY_CT_FLAGS_TRACKED,
+            TcFlowerCtFlag::Invalid => TCA_FLOWER_KEY_CT_FLAGS_INVALID,
+            TcFlowerCtFlag::Reply => TCA_FLOWER_KEY_CT_FLAGS_REPLY,
+            TcFlowerCtFlag::Other(i) => i,
+        }
+    }
+}
+
+const ALL_CT_FLAGS: [TcFlowerCtFlag; 6] = [
+    TcFlowerCtFlag::New,
+    TcFlowerCtFlag::Established,
+    TcFlowerCtFlag::Related,
+    TcFlowerCtFlag::Tracked,
+    TcFlowerCtFlag::Invalid,
+    TcFlowerCtFlag::Reply,
+];
+
+#[derive(Clone, Eq, PartialEq, Debug)]
+pub(crate) struct VecTcFlowerCtFlag(pub(crate) Vec<TcFlowerCtFlag>);
+
+impl From<u16> for VecTcFlowerCtFlag {
+    fn from(d: u16) -> Self {
+        let mut got: u16 = 0;
+        let mut ret = Vec::new();
+        for flag in ALL_CT_FLAGS {
+            if (d & (u16::from(flag))) > 0 {
+                ret.push(flag);
+                got += u16::from(flag);
+            }
+        }
+        if got != d {
+            ret.push(TcFlowerCtFlag::Other(d - got));
+        }
+        Self(ret)
+    }
+}
+
+impl From<&VecTcFlowerCtFlag> for u16 {
+    fn from(v: &VecTcFlowerCtFlag) -> u16 {
+        let mut d: u16 = 0;
+        for flag in &v.0 {
+            d += u16::from(*flag);
+        }
+        d
+    }
+}
@@ -19,6 +19,8 @@ const TCA_MATCHALL_ACT: u16 = 2;
 const TCA_MATCHALL_FLAGS: u16 = 3;
 const TCA_MATCHALL_PCNT: u16 = 4;
 
+const TC_MATCHALL_PCNT_BUF_LEN: usize = 8;
+
 #[derive(Debug, PartialEq, Eq, Clone)]
 #[non_exhaustive]
 pub struct TcFilterMatchAll {}
@@ -31,7 +33,7 @@ impl TcFilterMatchAll {
 pub enum TcFilterMatchAllOption {
     ClassId(TcHandle),
     Action(Vec<TcAction>),
-    Pnct(Vec<u8>),
+    Pnct(TcMatchAllPcnt),
     Flags(u32),
     Other(DefaultNla),
 }
@@ -39,7 +41,7 @@ pub enum TcFilterMatchAllOption {
 impl Nla for TcFilterMatchAllOption {
     fn value_len(&self) -> usize {
         match self {
-            Self::Pnct(b) => b.len(),
+            Self::Pnct(p) => p.buffer_len(),
             Self::ClassId(_) => 4,
             Self::Flags(_) => 4,
             Self::Action(acts) => acts.as_slice().buffer_len(),
@@ -49,7 +51,7 @@ impl Nla for TcFilterMatchAllOption {
 
     fn emit_value(&self, buffer: &mut [u8]) {
         match self {
-            Self::Pnct(b) => buffer.copy_from_slice(b.as_slice()),
+            Self::Pnct(p) => p.emit(buffer),
             Self::ClassId(i) => NativeEndian::write_u32(buffer, (*i).into()),
             Self::Flags(i) => NativeEndian::write_u32(buffer, *i),
             Self::Action(acts) => acts.as_slice().emit(buffer),
@@ -90,7 +92,13 @@ impl<'a, T: AsRef<[u8]> + ?Sized> Parseable<NlaBuffer<&'a T>>
                 }
                 Self::Action(acts)
             }
-            TCA_MATCHALL_PCNT => Self::Pnct(payload.to_vec()),
+            TCA_MATCHALL_PCNT => Self::Pnct(
+                TcMatchAllPcnt::parse(
+                    &TcMatchAllPcntBuffer::new_checked(payload)
+                        .context("invalid TCA_MATCHALL_PCNT")?,
+                )
+                .context("failed to parse TCA_MATCHALL_PCNT")?,
+            ),
             TCA_MATCHALL_FLAGS => Self::Flags(
                 parse_u32(payload)
                     .context("failed to parse TCA_MATCHALL_FLAGS")?,
@@ -101,3 +109,32 @@ impl<'a, T: AsRef<[u8]> + ?Sized> Parseable<NlaBuffer<&'a T>>
         })
     }
 }
+
+// kernel struct `tc_matchall_pcnt`
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Default)]
+#[non_exhaustive]
+pub struct TcMatchAllPcnt {
+    /// Number of packets matched by this filter in software
+    pub rhit: u64,
+}
+
+buffer!(TcMatchAllPcntBuffer(TC_MATCHALL_PCNT_BUF_LEN) {
+    rhit: (u64, 0..TC_MATCHALL_PCNT_BUF_LEN),
+});
+
+impl Emitable for TcMatchAllPcnt {
+    fn buffer_len(&self) -> usize {
+        TC_MATCHALL_PCNT_BUF_LEN
+    }
+
+    fn emit(&self, buffer: &mut [u8]) {
+        let mut packet = TcMatchAllPcntBuffer::new(buffer);
+        packet.set_rhit(self.rhit);
+    }
+}
+
+impl<T: AsRef<[u8]>> Parseable<TcMatchAllPcntBuffer<T>> for TcMatchAllPcnt {
+    fn parse(buf: &TcMatchAllPcntBuffer<T>) -> Result<Self, DecodeError> {
+        Ok(Self { rhit: buf.rhit() })
+    }
+}
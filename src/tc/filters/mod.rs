@@ -1,11 +1,57 @@
 // SPDX-License-Identifier: MIT
 
+mod cls_basic;
+mod cls_bpf;
+mod cls_cgroup;
+mod cls_flow;
+mod cls_fw;
+mod cls_route;
 mod cls_u32;
+mod ematch;
+mod ematch_cmp;
+mod ematch_ipset;
+mod ematch_meta;
+mod ematch_nbyte;
+mod flower;
+mod flower_enc_opts;
+pub(crate) mod flower_flags;
 mod matchall;
 pub(crate) mod u32_flags;
 
+pub use self::cls_basic::{TcBasicPcnt, TcFilterBasic, TcFilterBasicOption};
+pub use self::cls_bpf::{
+    TcBpfFlag, TcBpfSockFilter, TcFilterBpf, TcFilterBpfOption,
+};
+pub use self::cls_cgroup::{TcFilterCgroup, TcFilterCgroupOption};
+pub use self::cls_flow::{
+    TcFilterFlow, TcFilterFlowOption, TcFlowKey, TcFlowMode,
+};
+pub use self::cls_fw::{TcFilterFw, TcFilterFwOption};
+pub use self::cls_route::{TcFilterRoute, TcFilterRouteOption};
 pub use self::cls_u32::{
-    TcFilterU32, TcFilterU32Option, TcU32Key, TcU32Selector,
+    TcFilterU32, TcFilterU32Option, TcU32Key, TcU32Mark, TcU32Pcnt,
+    TcU32Selector,
+};
+pub use self::ematch::{
+    TcEmatchExpr, TcEmatchLayer, TcEmatchOperand, TcEmatchOther,
+    TcEmatchRelation, TcEmatchTerm, TcEmatchTree,
+};
+pub use self::ematch_cmp::{TcEmatchCmp, TcEmatchCmpAlign};
+pub use self::ematch_ipset::TcEmatchIpset;
+pub use self::ematch_meta::{
+    TcEmatchMetaHeader, TcEmatchMetaId, TcEmatchMetaOption, TcEmatchMetaType,
+    TcEmatchMetaValue,
+};
+pub use self::ematch_nbyte::TcEmatchNbyte;
+pub use self::flower::{
+    TcFilterFlower, TcFilterFlowerOption, TcFlowerMplsLseOpt,
+};
+pub use self::flower_enc_opts::{
+    TcFlowerEncOpts, TcFlowerErspanOpt, TcFlowerGeneveOpt, TcFlowerGtpOpt,
+    TcFlowerVxlanOpt,
+};
+pub use self::flower_flags::{TcFlowerCtFlag, TcFlowerKeyFlag};
+pub use self::matchall::{
+    TcFilterMatchAll, TcFilterMatchAllOption, TcMatchAllPcnt,
 };
-pub use self::matchall::{TcFilterMatchAll, TcFilterMatchAllOption};
 pub use self::u32_flags::{TcU32OptionFlag, TcU32SelectorFlag};
@@ -79,21 +79,34 @@ pub struct TcHandle {
 }
 
 impl TcHandle {
+    /// `TC_H_UNSPEC`
     pub const UNSPEC: Self = Self { major: 0, minor: 0 };
+    /// `TC_H_ROOT`, the parent of root qdisc
     pub const ROOT: Self = Self {
         major: u16::MAX,
         minor: u16::MAX,
     };
+    /// `TC_H_INGRESS`, the parent of `ingress` and `clsact` qdiscs
     pub const INGRESS: Self = Self {
         major: u16::MAX,
         minor: 0xfff1,
     };
 
+    /// `TC_H_CLSACT`, identical to `TC_H_INGRESS`
     pub const CLSACT: Self = Self::INGRESS;
 
     pub const MIN_PRIORITY: u16 = 0xFFE0;
     pub const MIN_INGRESS: u16 = 0xFFF2;
     pub const MIN_EGRESS: u16 = 0xFFF3;
+
+    /// Parent of filters on ingress hook of `clsact` qdisc, `ffff:fff2`
+    pub const CLSACT_INGRESS: Self = Self::new(u16::MAX, Self::MIN_INGRESS);
+    /// Parent of filters on egress hook of `clsact` qdisc, `ffff:fff3`
+    pub const CLSACT_EGRESS: Self = Self::new(u16::MAX, Self::MIN_EGRESS);
+
+    pub const fn new(major: u16, minor: u16) -> Self {
+        Self { major, minor }
+    }
 }
 
 impl From<u32> for TcHandle {
@@ -6,28 +6,87 @@ mod filters;
 mod header;
 mod message;
 mod options;
+mod psched;
 mod qdiscs;
+mod ratespec;
 mod stats;
 
 pub use self::actions::{
-    TcAction, TcActionAttribute, TcActionGeneric, TcActionGenericBuffer,
-    TcActionMirror, TcActionMirrorOption, TcActionNat, TcActionNatOption,
-    TcActionOption, TcActionType, TcMirror, TcMirrorActionType, TcMirrorBuffer,
-    TcNat, TcNatBuffer, TcNatFlag,
+    TcAction, TcActionAttribute, TcActionGact, TcActionGactOption,
+    TcActionGeneric, TcActionGenericBuffer, TcActionMirror,
+    TcActionMirrorOption, TcActionNat, TcActionNatOption, TcActionOption,
+    TcActionPedit, TcActionPeditOption, TcActionPolice, TcActionPoliceOption,
+    TcActionSkbedit, TcActionSkbeditOption, TcActionSkbmod,
+    TcActionSkbmodOption, TcActionTunnelKey, TcActionTunnelKeyOption,
+    TcActionType, TcActionVlan, TcActionVlanOption, TcGact, TcGactBuffer,
+    TcGactProb, TcGactProbBuffer, TcGactProbType, TcMirror, TcMirrorActionType,
+    TcMirrorBuffer, TcNat, TcNatBuffer, TcNatFlag, TcPedit, TcPeditBuffer,
+    TcPeditCmd, TcPeditEdit, TcPeditHeaderType, TcPeditKey, TcPeditKeyBuffer,
+    TcPeditKeyEx, TcPolice, TcPoliceBuffer, TcSkbedit, TcSkbeditBuffer,
+    TcSkbeditFlag, TcSkbeditPtype, TcSkbmod, TcSkbmodBuffer, TcSkbmodFlag,
+    TcTunnelKey, TcTunnelKeyAction, TcTunnelKeyBuffer, TcVlan, TcVlanAction,
+    TcVlanBuffer, Tcf, TcfBuffer,
 };
 pub use self::attribute::TcAttribute;
 pub use self::filters::{
-    TcFilterMatchAll, TcFilterMatchAllOption, TcFilterU32, TcFilterU32Option,
-    TcU32Key, TcU32OptionFlag, TcU32Selector, TcU32SelectorFlag,
+    TcBasicPcnt, TcBpfFlag, TcBpfSockFilter, TcEmatchCmp, TcEmatchCmpAlign,
+    TcEmatchExpr, TcEmatchIpset, TcEmatchLayer, TcEmatchMetaHeader,
+    TcEmatchMetaId, TcEmatchMetaOption, TcEmatchMetaType, TcEmatchMetaValue,
+    TcEmatchNbyte, TcEmatchOperand, TcEmatchOther, TcEmatchRelation,
+    TcEmatchTerm, TcEmatchTree, TcFilterBasic, TcFilterBasicOption,
+    TcFilterBpf, TcFilterBpfOption, TcFilterCgroup, TcFilterCgroupOption,
+    TcFilterFlow, TcFilterFlowOption, TcFilterFlower, TcFilterFlowerOption,
+    TcFilterFw, TcFilterFwOption, TcFilterMatchAll, TcFilterMatchAllOption,
+    TcFilterRoute, TcFilterRouteOption, TcFilterU32, TcFilterU32Option,
+    TcFlowKey, TcFlowMode, TcFlowerCtFlag, TcFlowerEncOpts, TcFlowerErspanOpt,
+    TcFlowerGeneveOpt, TcFlowerGtpOpt, TcFlowerKeyFlag, TcFlowerMplsLseOpt,
+    TcFlowerVxlanOpt, TcMatchAllPcnt, TcU32Key, TcU32Mark, TcU32OptionFlag,
+    TcU32Pcnt, TcU32Selector, TcU32SelectorFlag,
 };
 pub use self::header::{TcHandle, TcHeader, TcMessageBuffer};
 pub use self::message::TcMessage;
 pub use self::options::TcOption;
+pub use self::psched::TcPsched;
 pub use self::qdiscs::{
-    TcFqCodelClStats, TcFqCodelClStatsBuffer, TcFqCodelQdStats,
-    TcFqCodelQdStatsBuffer, TcFqCodelXstats, TcQdiscFqCodel,
-    TcQdiscFqCodelOption, TcQdiscIngress, TcQdiscIngressOption,
+    TcCakeAckFilter, TcCakeAtmMode, TcCakeDiffservMode, TcCakeFlowMode,
+    TcCakeTinStats, TcCakeXstats, TcCbsQopt, TcCbsQoptBuffer, TcChokeXstats,
+    TcChokeXstatsBuffer, TcCodelXstats, TcCodelXstatsBuffer, TcDrrStats,
+    TcDrrStatsBuffer, TcEtfFlag, TcEtfQopt, TcEtfQoptBuffer, TcFifoQopt,
+    TcFifoQoptBuffer, TcFpAdminStatus, TcFqCodelClStats,
+    TcFqCodelClStatsBuffer, TcFqCodelQdStats, TcFqCodelQdStatsBuffer,
+    TcFqCodelXstats, TcFqPieXstats, TcFqPieXstatsBuffer, TcFqQdStats,
+    TcFqQdStatsBuffer, TcGredQopt, TcGredQoptBuffer, TcGredSopt,
+    TcGredSoptBuffer, TcGredVqEntry, TcHfscQopt, TcHfscQoptBuffer, TcHfscStats,
+    TcHfscStatsBuffer, TcHtbGlob, TcHtbGlobBuffer, TcHtbOpt, TcHtbOptBuffer,
+    TcHtbXstats, TcHtbXstatsBuffer, TcMqprioMode, TcMqprioQopt,
+    TcMqprioQoptBuffer, TcMqprioShaper, TcMqprioTcEntry, TcMultiqQopt,
+    TcMultiqQoptBuffer, TcNetemCorr, TcNetemCorrBuffer, TcNetemCorrupt,
+    TcNetemCorruptBuffer, TcNetemGeModel, TcNetemGeModelBuffer, TcNetemGiModel,
+    TcNetemGiModelBuffer, TcNetemLoss, TcNetemQopt, TcNetemQoptBuffer,
+    TcNetemRate, TcNetemRateBuffer, TcNetemReorder, TcNetemReorderBuffer,
+    TcNetemSlot, TcNetemSlotBuffer, TcPieXstats, TcPieXstatsBuffer, TcPrioQopt,
+    TcPrioQoptBuffer, TcQdiscBfifo, TcQdiscCake, TcQdiscCakeOption, TcQdiscCbs,
+    TcQdiscCbsOption, TcQdiscChoke, TcQdiscChokeOption, TcQdiscClsact,
+    TcQdiscClsactOption, TcQdiscCodel, TcQdiscCodelOption, TcQdiscDrr,
+    TcQdiscDrrOption, TcQdiscEtf, TcQdiscEtfOption, TcQdiscEts,
+    TcQdiscEtsOption, TcQdiscFifoOption, TcQdiscFq, TcQdiscFqCodel,
+    TcQdiscFqCodelOption, TcQdiscFqOption, TcQdiscFqPie, TcQdiscFqPieOption,
+    TcQdiscGred, TcQdiscGredOption, TcQdiscHfsc, TcQdiscHfscOption, TcQdiscHtb,
+    TcQdiscHtbOption, TcQdiscIngress, TcQdiscIngressOption, TcQdiscMqprio,
+    TcQdiscMqprioOption, TcQdiscMultiq, TcQdiscMultiqOption, TcQdiscNetem,
+    TcQdiscNetemOption, TcQdiscPfifo, TcQdiscPfifoFast, TcQdiscPie,
+    TcQdiscPieOption, TcQdiscPrio, TcQdiscPrioOption, TcQdiscQfq,
+    TcQdiscQfqOption, TcQdiscRed, TcQdiscRedOption, TcQdiscSfq,
+    TcQdiscSfqOption, TcQdiscTaprio, TcQdiscTaprioOption, TcQdiscTbf,
+    TcQdiscTbfOption, TcQfqStats, TcQfqStatsBuffer, TcRedFlag, TcRedFlags,
+    TcRedFlagsBuffer, TcRedQopt, TcRedQoptBuffer, TcRedXstats,
+    TcRedXstatsBuffer, TcServiceCurve, TcServiceCurveBuffer, TcSfqQopt,
+    TcSfqQoptBuffer, TcSfqQoptV1, TcSfqQoptV1Buffer, TcSfqRedStats,
+    TcSfqRedStatsBuffer, TcSfqXstats, TcSfqXstatsBuffer, TcTaprioCmd,
+    TcTaprioFlag, TcTaprioSchedEntry, TcTaprioTcEntry, TcTaprioXstats,
+    TcTbfQopt, TcTbfQoptBuffer,
 };
+pub use self::ratespec::{TcLinkLayer, TcRateSpec, TcRateSpecBuffer};
 pub use self::stats::{
     TcStats, TcStats2, TcStatsBasic, TcStatsBasicBuffer, TcStatsBuffer,
     TcStatsQueue, TcStatsQueueBuffer, TcXstats,
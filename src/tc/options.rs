@@ -2,14 +2,35 @@
 
 use anyhow::Context;
 use netlink_packet_utils::{
-    nla::{DefaultNla, Nla, NlaBuffer, NlasIterator},
-    traits::{Parseable, ParseableParametrized},
+    nla::{DefaultNla, Nla, NlaBuffer, NlasIterator, NLA_ALIGNTO},
+    traits::{Emitable, Parseable, ParseableParametrized},
     DecodeError,
 };
 
 use super::{
-    TcFilterMatchAll, TcFilterMatchAllOption, TcFilterU32, TcFilterU32Option,
-    TcQdiscFqCodel, TcQdiscFqCodelOption, TcQdiscIngress, TcQdiscIngressOption,
+    TcFilterBasic, TcFilterBasicOption, TcFilterBpf, TcFilterBpfOption,
+    TcFilterCgroup, TcFilterCgroupOption, TcFilterFlow, TcFilterFlowOption,
+    TcFilterFlower, TcFilterFlowerOption, TcFilterFw, TcFilterFwOption,
+    TcFilterMatchAll, TcFilterMatchAllOption, TcFilterRoute,
+    TcFilterRouteOption, TcFilterU32, TcFilterU32Option, TcQdiscBfifo,
+    TcQdiscCake, TcQdiscCakeOption, TcQdiscCbs, TcQdiscCbsOption, TcQdiscChoke,
+    TcQdiscChokeOption, TcQdiscClsact, TcQdiscClsactOption, TcQdiscCodel,
+    TcQdiscCodelOption, TcQdiscDrr, TcQdiscDrrOption, TcQdiscEtf,
+    TcQdiscEtfOption, TcQdiscEts, TcQdiscEtsOption, TcQdiscFifoOption,
+    TcQdiscFq, TcQdiscFqCodel, TcQdiscFqCodelOption, TcQdiscFqOption,
+    TcQdiscFqPie, TcQdiscFqPieOption, TcQdiscGred, TcQdiscGredOption,
+    TcQdiscHfsc, TcQdiscHfscOption, TcQdiscHtb, TcQdiscHtbOption,
+    TcQdiscIngress, TcQdiscIngressOption, TcQdiscMqprio, TcQdiscMqprioOption,
+    TcQdiscMultiq, TcQdiscMultiqOption, TcQdiscNetem, TcQdiscNetemOption,
+    TcQdiscPfifo, TcQdiscPfifoFast, TcQdiscPie, TcQdiscPieOption, TcQdiscPrio,
+    TcQdiscPrioOption, TcQdiscQfq, TcQdiscQfqOption, TcQdiscRed,
+    TcQdiscRedOption, TcQdiscSfq, TcQdiscSfqOption, TcQdiscTaprio,
+    TcQdiscTaprioOption, TcQdiscTbf, TcQdiscTbfOption,
+};
+use crate::tc::qdiscs::{
+    parse_fifo_options, parse_hfsc_options, parse_mqprio_options,
+    parse_multiq_options, parse_netem_options, parse_prio_options,
+    parse_sfq_options,
 };
 
 #[derive(Debug, PartialEq, Eq, Clone)]
@@ -18,10 +39,43 @@ pub enum TcOption {
     FqCodel(TcQdiscFqCodelOption),
     // Qdisc specific options
     Ingress(TcQdiscIngressOption),
+    Clsact(TcQdiscClsactOption),
+    Htb(TcQdiscHtbOption),
+    Netem(TcQdiscNetemOption),
+    Cake(TcQdiscCakeOption),
+    Tbf(TcQdiscTbfOption),
+    Fq(TcQdiscFqOption),
+    Prio(TcQdiscPrioOption),
+    PfifoFast(TcQdiscPrioOption),
+    Multiq(TcQdiscMultiqOption),
+    Pfifo(TcQdiscFifoOption),
+    Bfifo(TcQdiscFifoOption),
+    Mqprio(TcQdiscMqprioOption),
+    Taprio(TcQdiscTaprioOption),
+    Sfq(TcQdiscSfqOption),
+    Hfsc(TcQdiscHfscOption),
+    Ets(TcQdiscEtsOption),
+    Drr(TcQdiscDrrOption),
+    Qfq(TcQdiscQfqOption),
+    Codel(TcQdiscCodelOption),
+    Pie(TcQdiscPieOption),
+    FqPie(TcQdiscFqPieOption),
+    Cbs(TcQdiscCbsOption),
+    Etf(TcQdiscEtfOption),
+    Red(TcQdiscRedOption),
+    Gred(TcQdiscGredOption),
+    Choke(TcQdiscChokeOption),
     // Filter specific options
     U32(TcFilterU32Option),
     // matchall options
     MatchAll(TcFilterMatchAllOption),
+    Flower(TcFilterFlowerOption),
+    Basic(TcFilterBasicOption),
+    Bpf(TcFilterBpfOption),
+    Fw(TcFilterFwOption),
+    Route(TcFilterRouteOption),
+    Flow(TcFilterFlowOption),
+    Cgroup(TcFilterCgroupOption),
     // Other options
     Other(DefaultNla),
 }
@@ -31,8 +85,41 @@ impl Nla for TcOption {
         match self {
             Self::FqCodel(u) => u.value_len(),
             Self::Ingress(u) => u.value_len(),
+            Self::Clsact(u) => u.value_len(),
+            Self::Htb(u) => u.value_len(),
+            Self::Netem(u) => u.value_len(),
+            Self::Cake(u) => u.value_len(),
+            Self::Tbf(u) => u.value_len(),
+            Self::Fq(u) => u.value_len(),
+            Self::Prio(u) => u.value_len(),
+            Self::PfifoFast(u) => u.value_len(),
+            Self::Multiq(u) => u.value_len(),
+            Self::Pfifo(u) => u.value_len(),
+            Self::Bfifo(u) => u.value_len(),
+            Self::Mqprio(u) => u.value_len(),
+            Self::Taprio(u) => u.value_len(),
+            Self::Sfq(u) => u.value_len(),
+            Self::Hfsc(u) => u.value_len(),
+            Self::Ets(u) => u.value_len(),
+            Self::Drr(u) => u.value_len(),
+            Self::Qfq(u) => u.value_len(),
+            Self::Codel(u) => u.value_len(),
+            Self::Pie(u) => u.value_len(),
+            Self::FqPie(u) => u.value_len(),
+            Self::Cbs(u) => u.value_len(),
+            Self::Etf(u) => u.value_len(),
+            Self::Red(u) => u.value_len(),
+            Self::Gred(u) => u.value_len(),
+            Self::Choke(u) => u.value_len(),
             Self::U32(u) => u.value_len(),
             Self::MatchAll(m) => m.value_len(),
+            Self::Flower(u) => u.value_len(),
+            Self::Basic(u) => u.value_len(),
+            Self::Bpf(u) => u.value_len(),
+            Self::Fw(u) => u.value_len(),
+            Self::Route(u) => u.value_len(),
+            Self::Flow(u) => u.value_len(),
+            Self::Cgroup(u) => u.value_len(),
             Self::Other(o) => o.value_len(),
         }
     }
@@ -41,8 +128,41 @@ impl Nla for TcOption {
         match self {
             Self::FqCodel(u) => u.emit_value(buffer),
             Self::Ingress(u) => u.emit_value(buffer),
+            Self::Clsact(u) => u.emit_value(buffer),
+            Self::Htb(u) => u.emit_value(buffer),
+            Self::Netem(u) => u.emit_value(buffer),
+            Self::Cake(u) => u.emit_value(buffer),
+            Self::Tbf(u) => u.emit_value(buffer),
+            Self::Fq(u) => u.emit_value(buffer),
+            Self::Prio(u) => u.emit_value(buffer),
+            Self::PfifoFast(u) => u.emit_value(buffer),
+            Self::Multiq(u) => u.emit_value(buffer),
+            Self::Pfifo(u) => u.emit_value(buffer),
+            Self::Bfifo(u) => u.emit_value(buffer),
+            Self::Mqprio(u) => u.emit_value(buffer),
+            Self::Taprio(u) => u.emit_value(buffer),
+            Self::Sfq(u) => u.emit_value(buffer),
+            Self::Hfsc(u) => u.emit_value(buffer),
+            Self::Ets(u) => u.emit_value(buffer),
+            Self::Drr(u) => u.emit_value(buffer),
+            Self::Qfq(u) => u.emit_value(buffer),
+            Self::Codel(u) => u.emit_value(buffer),
+            Self::Pie(u) => u.emit_value(buffer),
+            Self::FqPie(u) => u.emit_value(buffer),
+            Self::Cbs(u) => u.emit_value(buffer),
+            Self::Etf(u) => u.emit_value(buffer),
+            Self::Red(u) => u.emit_value(buffer),
+            Self::Gred(u) => u.emit_value(buffer),
+            Self::Choke(u) => u.emit_value(buffer),
             Self::U32(u) => u.emit_value(buffer),
             Self::MatchAll(m) => m.emit_value(buffer),
+            Self::Flower(u) => u.emit_value(buffer),
+            Self::Basic(u) => u.emit_value(buffer),
+            Self::Bpf(u) => u.emit_value(buffer),
+            Self::Fw(u) => u.emit_value(buffer),
+            Self::Route(u) => u.emit_value(buffer),
+            Self::Flow(u) => u.emit_value(buffer),
+            Self::Cgroup(u) => u.emit_value(buffer),
             Self::Other(o) => o.emit_value(buffer),
         }
     }
@@ -51,11 +171,119 @@ impl Nla for TcOption {
         match self {
             Self::FqCodel(u) => u.kind(),
             Self::Ingress(u) => u.kind(),
+            Self::Clsact(u) => u.kind(),
+            Self::Htb(u) => u.kind(),
+            Self::Netem(u) => u.kind(),
+            Self::Cake(u) => u.kind(),
+            Self::Tbf(u) => u.kind(),
+            Self::Fq(u) => u.kind(),
+            Self::Prio(u) => u.kind(),
+            Self::PfifoFast(u) => u.kind(),
+            Self::Multiq(u) => u.kind(),
+            Self::Pfifo(u) => u.kind(),
+            Self::Bfifo(u) => u.kind(),
+            Self::Mqprio(u) => u.kind(),
+            Self::Taprio(u) => u.kind(),
+            Self::Sfq(u) => u.kind(),
+            Self::Hfsc(u) => u.kind(),
+            Self::Ets(u) => u.kind(),
+            Self::Drr(u) => u.kind(),
+            Self::Qfq(u) => u.kind(),
+            Self::Codel(u) => u.kind(),
+            Self::Pie(u) => u.kind(),
+            Self::FqPie(u) => u.kind(),
+            Self::Cbs(u) => u.kind(),
+            Self::Etf(u) => u.kind(),
+            Self::Red(u) => u.kind(),
+            Self::Gred(u) => u.kind(),
+            Self::Choke(u) => u.kind(),
             Self::U32(u) => u.kind(),
             Self::MatchAll(m) => m.kind(),
+            Self::Flower(u) => u.kind(),
+            Self::Basic(u) => u.kind(),
+            Self::Bpf(u) => u.kind(),
+            Self::Fw(u) => u.kind(),
+            Self::Route(u) => u.kind(),
+            Self::Flow(u) => u.kind(),
+            Self::Cgroup(u) => u.kind(),
             Self::Other(o) => o.kind(),
         }
     }
+
+    fn is_nested(&self) -> bool {
+        match self {
+            Self::FqCodel(u) => u.is_nested(),
+            Self::Ingress(u) => u.is_nested(),
+            Self::Clsact(u) => u.is_nested(),
+            Self::Htb(u) => u.is_nested(),
+            Self::Netem(u) => u.is_nested(),
+            Self::Cake(u) => u.is_nested(),
+            Self::Tbf(u) => u.is_nested(),
+            Self::Fq(u) => u.is_nested(),
+            Self::Prio(u) => u.is_nested(),
+            Self::PfifoFast(u) => u.is_nested(),
+            Self::Multiq(u) => u.is_nested(),
+            Self::Pfifo(u) => u.is_nested(),
+            Self::Bfifo(u) => u.is_nested(),
+            Self::Mqprio(u) => u.is_nested(),
+            Self::Taprio(u) => u.is_nested(),
+            Self::Sfq(u) => u.is_nested(),
+            Self::Hfsc(u) => u.is_nested(),
+            Self::Ets(u) => u.is_nested(),
+            Self::Drr(u) => u.is_nested(),
+            Self::Qfq(u) => u.is_nested(),
+            Self::Codel(u) => u.is_nested(),
+            Self::Pie(u) => u.is_nested(),
+            Self::FqPie(u) => u.is_nested(),
+            Self::Cbs(u) => u.is_nested(),
+            Self::Etf(u) => u.is_nested(),
+            Self::Red(u) => u.is_nested(),
+            Self::Gred(u) => u.is_nested(),
+            Self::Choke(u) => u.is_nested(),
+            Self::U32(u) => u.is_nested(),
+            Self::MatchAll(m) => m.is_nested(),
+            Self::Flower(u) => u.is_nested(),
+            Self::Basic(u) => u.is_nested(),
+            Self::Bpf(u) => u.is_nested(),
+            Self::Fw(u) => u.is_nested(),
+            Self::Route(u) => u.is_nested(),
+            Self::Flow(u) => u.is_nested(),
+            Self::Cgroup(u) => u.is_nested(),
+            Self::Other(o) => o.is_nested(),
+        }
+    }
+}
+
+impl TcOption {
+    // Some qdiscs place a C struct at the beginning of TCA_OPTIONS instead
+    // of wrapping it into a NLA, hence it should be emitted without NLA
+    // header.
+    fn is_struct(&self) -> bool {
+        matches!(
+            self,
+            Self::Netem(TcQdiscNetemOption::Qopt(_))
+                | Self::Prio(TcQdiscPrioOption::Qopt(_))
+                | Self::PfifoFast(TcQdiscPrioOption::Qopt(_))
+                | Self::Multiq(TcQdiscMultiqOption::Qopt(_))
+                | Self::Pfifo(TcQdiscFifoOption::Qopt(_))
+                | Self::Bfifo(TcQdiscFifoOption::Qopt(_))
+                | Self::Mqprio(TcQdiscMqprioOption::Qopt(_))
+                | Self::Sfq(
+                    TcQdiscSfqOption::Qopt(_) | TcQdiscSfqOption::QoptV1(_)
+                )
+                | Self::Hfsc(TcQdiscHfscOption::Qopt(_))
+        )
+    }
+
+    // Length of the C struct in TCA_OPTIONS. The 2 bytes `tc_hfsc_qopt` is
+    // placed by kernel and iproute2 without padding, the padding is added
+    // by the TCA_OPTIONS NLA instead.
+    fn struct_len(&self) -> usize {
+        match self {
+            Self::Hfsc(TcQdiscHfscOption::Qopt(_)) => self.value_len(),
+            _ => nla_align!(self.value_len()),
+        }
+    }
 }
 
 impl<'a, T> ParseableParametrized<NlaBuffer<&'a T>, &str> for TcOption
@@ -72,11 +300,83 @@ where
                     "failed to parse ingress TCA_OPTIONS attributes",
                 )?)
             }
+            TcQdiscClsact::KIND => Self::Clsact(
+                TcQdiscClsactOption::parse(buf)
+                    .context("failed to parse clsact TCA_OPTIONS attributes")?,
+            ),
             TcQdiscFqCodel::KIND => {
                 Self::FqCodel(TcQdiscFqCodelOption::parse(buf).context(
                     "failed to parse fq_codel TCA_OPTIONS attributes",
                 )?)
             }
+            TcQdiscHtb::KIND => Self::Htb(
+                TcQdiscHtbOption::parse(buf)
+                    .context("failed to parse htb TCA_OPTIONS attributes")?,
+            ),
+            TcQdiscNetem::KIND => Self::Netem(
+                TcQdiscNetemOption::parse(buf)
+                    .context("failed to parse netem TCA_OPTIONS attributes")?,
+            ),
+            TcQdiscCake::KIND => Self::Cake(
+                TcQdiscCakeOption::parse(buf)
+                    .context("failed to parse cake TCA_OPTIONS attributes")?,
+            ),
+            TcQdiscTbf::KIND => Self::Tbf(
+                TcQdiscTbfOption::parse(buf)
+                    .context("failed to parse tbf TCA_OPTIONS attributes")?,
+            ),
+            TcQdiscFq::KIND => Self::Fq(
+                TcQdiscFqOption::parse(buf)
+                    .context("failed to parse fq TCA_OPTIONS attributes")?,
+            ),
+            TcQdiscTaprio::KIND => Self::Taprio(
+                TcQdiscTaprioOption::parse(buf)
+                    .context("failed to parse taprio TCA_OPTIONS attributes")?,
+            ),
+            TcQdiscRed::KIND => Self::Red(
+                TcQdiscRedOption::parse(buf)
+                    .context("failed to parse red TCA_OPTIONS attributes")?,
+            ),
+            TcQdiscGred::KIND => Self::Gred(
+                TcQdiscGredOption::parse(buf)
+                    .context("failed to parse gred TCA_OPTIONS attributes")?,
+            ),
+            TcQdiscChoke::KIND => Self::Choke(
+                TcQdiscChokeOption::parse(buf)
+                    .context("failed to parse choke TCA_OPTIONS attributes")?,
+            ),
+            TcQdiscEts::KIND => Self::Ets(
+                TcQdiscEtsOption::parse(buf)
+                    .context("failed to parse ets TCA_OPTIONS attributes")?,
+            ),
+            TcQdiscDrr::KIND => Self::Drr(
+                TcQdiscDrrOption::parse(buf)
+                    .context("failed to parse drr TCA_OPTIONS attributes")?,
+            ),
+            TcQdiscQfq::KIND => Self::Qfq(
+                TcQdiscQfqOption::parse(buf)
+                    .context("failed to parse qfq TCA_OPTIONS attributes")?,
+            ),
+            TcQdiscCodel::KIND => Self::Codel(
+                TcQdiscCodelOption::parse(buf)
+                    .context("failed to parse codel TCA_OPTIONS attributes")?,
+            ),
+            TcQdiscPie::KIND => Self::Pie(
+                TcQdiscPieOption::parse(buf)
+                    .context("failed to parse pie TCA_OPTIONS attributes")?,
+            ),
+            TcQdiscFqPie::KIND => Self::FqPie(
+                TcQdiscFqPieOption::parse(buf)
+                    .context("failed to parse fq_pie TCA_OPTIONS attributes")?,
+            ),
+            TcQdiscCbs::KIND => Self::Cbs(
+                TcQdiscCbsOption::parse(buf)
+                    .context("failed to parse cbs TCA_OPTIONS attributes")?,
+            ),
+            TcQdiscEtf::KIND => Self::Etf(
+                TcQdiscEtfOption::parse(buf)
+                    .context("failed to parse etf TCA_OPTIONS attributes")?,
+            ),
             TcFilterU32::KIND => Self::U32(
                 TcFilterU32Option::parse(buf)
                     .context("failed to parse u32 TCA_OPTIONS attributes")?,
@@ -86,6 +386,34 @@ where
                     "failed to parse matchall TCA_OPTIONS attributes",
                 )?)
             }
+            TcFilterFlower::KIND => Self::Flower(
+                TcFilterFlowerOption::parse(buf)
+                    .context("failed to parse flower TCA_OPTIONS attributes")?,
+            ),
+            TcFilterBasic::KIND => Self::Basic(
+                TcFilterBasicOption::parse(buf)
+                    .context("failed to parse basic TCA_OPTIONS attributes")?,
+            ),
+            TcFilterBpf::KIND => Self::Bpf(
+                TcFilterBpfOption::parse(buf)
+                    .context("failed to parse bpf TCA_OPTIONS attributes")?,
+            ),
+            TcFilterFw::KIND => Self::Fw(
+                TcFilterFwOption::parse(buf)
+                    .context("failed to parse fw TCA_OPTIONS attributes")?,
+            ),
+            TcFilterRoute::KIND => Self::Route(
+                TcFilterRouteOption::parse(buf)
+                    .context("failed to parse route TCA_OPTIONS attributes")?,
+            ),
+            TcFilterFlow::KIND => Self::Flow(
+                TcFilterFlowOption::parse(buf)
+                    .context("failed to parse flow TCA_OPTIONS attributes")?,
+            ),
+            TcFilterCgroup::KIND => Self::Cgroup(
+                TcFilterCgroupOption::parse(buf)
+                    .context("failed to parse cgroup TCA_OPTIONS attributes")?,
+            ),
             _ => Self::Other(DefaultNla::parse(buf)?),
         })
     }
@@ -93,6 +421,41 @@ where
 
 pub(crate) struct VecTcOption(pub(crate) Vec<TcOption>);
 
+impl VecTcOption {
+    // The `ets` and `fq_pie` qdiscs require NLA_F_NESTED flag on TCA_OPTIONS
+    pub(crate) fn is_nested(options: &[TcOption]) -> bool {
+        options
+            .iter()
+            .any(|opt| matches!(opt, TcOption::Ets(_) | TcOption::FqPie(_)))
+    }
+
+    pub(crate) fn value_len(options: &[TcOption]) -> usize {
+        options
+            .iter()
+            .map(|opt| {
+                if opt.is_struct() {
+                    opt.struct_len()
+                } else {
+                    opt.buffer_len()
+                }
+            })
+            .sum()
+    }
+
+    pub(crate) fn emit_value(options: &[TcOption], buffer: &mut [u8]) {
+        let mut offset = 0;
+        for opt in options {
+            if opt.is_struct() {
+                opt.emit_value(&mut buffer[offset..]);
+                offset += opt.struct_len();
+            } else {
+                opt.emit(&mut buffer[offset..]);
+                offset += opt.buffer_len();
+            }
+        }
+    }
+}
+
 impl<'a, T> ParseableParametrized<NlaBuffer<&'a T>, &str> for VecTcOption
 where
     T: AsRef<[u8]> + ?Sized,
@@ -104,8 +467,32 @@ where
         Ok(match kind {
             TcFilterU32::KIND
             | TcFilterMatchAll::KIND
+            | TcFilterFlower::KIND
+            | TcFilterBasic::KIND
+            | TcFilterBpf::KIND
+            | TcFilterFw::KIND
+            | TcFilterRoute::KIND
+            | TcFilterFlow::KIND
+            | TcFilterCgroup::KIND
             | TcQdiscIngress::KIND
-            | TcQdiscFqCodel::KIND => {
+            | TcQdiscClsact::KIND
+            | TcQdiscFqCodel::KIND
+            | TcQdiscHtb::KIND
+            | TcQdiscCake::KIND
+            | TcQdiscTbf::KIND
+            | TcQdiscFq::KIND
+            | TcQdiscTaprio::KIND
+            | TcQdiscRed::KIND
+            | TcQdiscGred::KIND
+            | TcQdiscChoke::KIND
+            | TcQdiscEts::KIND
+            | TcQdiscDrr::KIND
+            | TcQdiscQfq::KIND
+            | TcQdiscCodel::KIND
+            | TcQdiscPie::KIND
+            | TcQdiscFqPie::KIND
+            | TcQdiscCbs::KIND
+            | TcQdiscEtf::KIND => {
                 let mut nlas = vec![];
                 for nla in NlasIterator::new(buf.value()) {
                     let nla = nla.context(format!(
@@ -121,8 +508,73 @@ where
                 }
                 Self(nlas)
             }
+            TcQdiscNetem::KIND => Self(
+                parse_netem_options(buf.value())
+                    .context("Failed to parse TCA_OPTIONS for kind: netem")?
+                    .into_iter()
+                    .map(TcOption::Netem)
+                    .collect(),
+            ),
+            TcQdiscPrio::KIND => Self(
+                parse_prio_options(buf.value())
+                    .context("Failed to parse TCA_OPTIONS for kind: prio")?
+                    .into_iter()
+                    .map(TcOption::Prio)
+                    .collect(),
+            ),
+            TcQdiscPfifoFast::KIND => Self(
+                parse_prio_options(buf.value())
+                    .context(
+                        "Failed to parse TCA_OPTIONS for kind: pfifo_fast",
+                    )?
+                    .into_iter()
+                    .map(TcOption::PfifoFast)
+                    .collect(),
+            ),
+            TcQdiscMultiq::KIND => Self(
+                parse_multiq_options(buf.value())
+                    .context("Failed to parse TCA_OPTIONS for kind: multiq")?
+                    .into_iter()
+                    .map(TcOption::Multiq)
+                    .collect(),
+            ),
+            TcQdiscPfifo::KIND => Self(
+                parse_fifo_options(buf.value())
+                    .context("Failed to parse TCA_OPTIONS for kind: pfifo")?
+                    .into_iter()
+                    .map(TcOption::Pfifo)
+                    .collect(),
+            ),
+            TcQdiscBfifo::KIND => Self(
+                parse_fifo_options(buf.value())
+                    .context("Failed to parse TCA_OPTIONS for kind: bfifo")?
+                    .into_iter()
+                    .map(TcOption::Bfifo)
+                    .collect(),
+            ),
+            TcQdiscMqprio::KIND => Self(
+                parse_mqprio_options(buf.value())
+                    .context("Failed to parse TCA_OPTIONS for kind: mqprio")?
+                    .into_iter()
+                    .map(TcOption::Mqprio)
+                    .collect(),
+            ),
+            TcQdiscSfq::KIND => Self(
+                parse_sfq_options(buf.value())
+                    .context("Failed to parse TCA_OPTIONS for kind: sfq")?
+                    .into_iter()
+                    .map(TcOption::Sfq)
+                    .collect(),
+            ),
+            TcQdiscHfsc::KIND => Self(
+                parse_hfsc_options(buf.value())
+                    .context("Failed to parse TCA_OPTIONS for kind: hfsc")?
+                    .into_iter()
+                    .map(TcOption::Hfsc)
+                    .collect(),
+            ),
             // Kernel has no guide line or code indicate the scheduler
-            // should place a nla_nest here. The `sfq` qdisc kernel code is
+            // should place a nla_nest here. The `sfq` like qdiscs are
             // using single NLA instead nested ones. Hence we are storing
             // unknown Nla as Vec with single item.
             _ => Self(vec![TcOption::Other(DefaultNla::parse(buf)?)]),
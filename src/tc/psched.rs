@@ -0,0 +1,193 @@
+// SPDX-License-Identifier: MIT
+
+use std::str::FromStr;
+
+use netlink_packet_utils::DecodeError;
+
+use crate::tc::{TcLinkLayer, TcRateSpec};
+
+const TIME_UNITS_PER_SEC: f64 = 1000000.0;
+const ATM_CELL_SIZE: u32 = 53;
+const ATM_CELL_PAYLOAD: u32 = 48;
+const TC_RTAB_CELLS: usize = 256;
+const RED_STAB_SIZE: usize = 256;
+const RED_STAB_MAX: u8 = 31;
+
+/// Packet scheduler clock parameters exposed by kernel in
+/// `/proc/net/psched`, used to convert time and sizes into the scheduler
+/// ticks expected by `tc_tbf_qopt`, `tc_htb_opt` and rate tables.
+///
+/// The conversions are identical to iproute2 `tc_core.c`.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+#[non_exhaustive]
+pub struct TcPsched {
+    pub t2us: u32,
+    pub us2t: u32,
+    pub clock_res: u32,
+}
+
+impl Default for TcPsched {
+    // Linux kernel has been using these fixed values since 2.6.31
+    fn default() -> Self {
+        Self {
+            t2us: 1000,
+            us2t: 64,
+            clock_res: 1000000,
+        }
+    }
+}
+
+impl FromStr for TcPsched {
+    type Err = DecodeError;
+
+    /// Parse the content of `/proc/net/psched`
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut values = s.split_whitespace().map(|v| {
+            u32::from_str_radix(v, 16).map_err(|e| {
+                DecodeError::from(format!(
+                    "Invalid value {v} in /proc/net/psched: {e}"
+                ))
+            })
+        });
+        let mut next = || {
+            values.next().unwrap_or_else(|| {
+                Err(DecodeError::from(format!(
+                    "Invalid /proc/net/psched content: {s:?}"
+                )))
+            })
+        };
+        Ok(Self {
+            t2us: next()?,
+            us2t: next()?,
+            clock_res: next()?,
+        })
+    }
+}
+
+impl TcPsched {
+    fn clock_factor(&self) -> f64 {
+        self.clock_res as f64 / TIME_UNITS_PER_SEC
+    }
+
+    fn tick_in_usec(&self) -> f64 {
+        // Kernel advertises a tick multiplier of 1000 for nanosecond
+        // resolution to stay compatible with old iproute2, which really
+        // is 1.
+        let t2us = if self.clock_res == 1000000000 {
+            self.us2t
+        } else {
+            self.t2us
+        };
+        t2us as f64 / self.us2t as f64 * self.clock_factor()
+    }
+
+    /// Convert microseconds to scheduler ticks
+    pub fn time2tick(&self, time: u32) -> u32 {
+        (time as f64 * self.tick_in_usec()) as u32
+    }
+
+    /// Convert scheduler ticks to microseconds
+    pub fn tick2time(&self, tick: u32) -> u32 {
+        (tick as f64 / self.tick_in_usec()) as u32
+    }
+
+    /// Convert microseconds to kernel clock units
+    pub fn time2ktime(&self, time: u32) -> u32 {
+        (time as f64 * self.clock_factor()) as u32
+    }
+
+    /// Convert kernel clock units to microseconds
+    pub fn ktime2time(&self, ktime: u32) -> u32 {
+        (ktime as f64 / self.clock_factor()) as u32
+    }
+
+    /// Scheduler ticks needed to transmit `size` bytes at `rate` bytes per
+    /// second, identical to iproute2 `tc_calc_xmittime()`.
+    pub fn calc_xmittime(&self, rate: u64, size: u32) -> u32 {
+        self.time2tick(
+            (TIME_UNITS_PER_SEC * (size as f64 / rate as f64)) as u32,
+        )
+    }
+
+    /// Bytes could be transmitted at `rate` bytes per second in `ticks`
+    /// scheduler ticks, identical to iproute2 `tc_calc_xmitsize()`.
+    pub fn calc_xmitsize(&self, rate: u64, ticks: u32) -> u32 {
+        (rate as f64 * self.tick2time(ticks) as f64 / TIME_UNITS_PER_SEC) as u32
+    }
+
+    /// Generate the 256 cells rate table for `TCA_*_RTAB` and `TCA_*_PTAB`,
+    /// identical to iproute2 `tc_calc_rtable()`.
+    ///
+    /// The `rate`, `mpu` and `linklayer` of specified `TcRateSpec` are
+    /// used as input while its `cell_log` and `cell_align` are updated.
+    /// When `cell_log` is `None`, the smallest cell log covering `mtu`
+    /// is chosen. The `mtu` of 0 means 2047.
+    pub fn calc_rtable(
+        &self,
+        rate: &mut TcRateSpec,
+        cell_log: Option<u8>,
+        mtu: u32,
+    ) -> Vec<u32> {
+        let mtu = if mtu == 0 { 2047 } else { mtu };
+        let cell_log = cell_log.unwrap_or_else(|| {
+            let mut cell_log = 0;
+            while (mtu >> cell_log) > 255 {
+                cell_log += 1;
+            }
+            cell_log
+        });
+
+        let rtab = (0..TC_RTAB_CELLS as u32)
+            .map(|i| {
+                let size =
+                    adjust_size((i + 1) << cell_log, rate.mpu, rate.linklayer);
+                self.calc_xmittime(rate.rate.into(), size)
+            })
+            .collect();
+
+        rate.cell_align = -1;
+        rate.cell_log = cell_log;
+        rtab
+    }
+
+    /// Generate the 256 cells idle damping table for `TCA_RED_STAB`,
+    /// `TCA_GRED_STAB` and `TCA_CHOKE_STAB`, identical to iproute2
+    /// `tc_red_eval_idle_damping()`.
+    ///
+    /// The `avpkt` is average packet size in bytes and `rate` is the link
+    /// bandwidth in bytes per second. Return the `scell_log` for
+    /// `tc_red_qopt` along with the table, or `None` when `wlog` is not
+    /// below 32 or idle time could not be covered by any cell log below 32.
+    pub fn calc_red_stab(
+        &self,
+        wlog: u8,
+        avpkt: u32,
+        rate: u64,
+    ) -> Option<(u8, Vec<u8>)> {
+        // Kernel `red_check_params()` refuses `wlog` of 32 and above
+        let w = 1u32.checked_shl(wlog.into())?;
+        let xmit_time = self.calc_xmittime(rate, avpkt) as f64;
+        let lw = -(1.0 - 1.0 / f64::from(w)).ln() / xmit_time;
+        let max_time = RED_STAB_MAX as f64 / lw;
+        let cell_log = (0..32u8)
+            .find(|cell_log| max_time / f64::from(1u32 << cell_log) < 512.0)?;
+
+        let mut stab: Vec<u8> = (0..RED_STAB_SIZE)
+            .map(|i| {
+                ((i as f64 * f64::from(1u32 << cell_log) * lw) as u8)
+                    .min(RED_STAB_MAX)
+            })
+            .collect();
+        stab[RED_STAB_SIZE - 1] = RED_STAB_MAX;
+        Some((cell_log, stab))
+    }
+}
+
+fn adjust_size(size: u32, mpu: u16, linklayer: TcLinkLayer) -> u32 {
+    let size = size.max(mpu.into());
+    match linklayer {
+        // Use full cell size to add ATM tax
+        TcLinkLayer::Atm => size.div_ceil(ATM_CELL_PAYLOAD) * ATM_CELL_SIZE,
+        _ => size,
+    }
+}
@@ -0,0 +1,920 @@
+// SPDX-License-Identifier: MIT
+
+/// Common Applications Kept Enhanced
+///
+/// CAKE combines a deficit round robin flow queueing scheduler using
+/// COBALT AQM with a shaper, DiffServ tins and ACK filtering.
+use anyhow::Context;
+use byteorder::{ByteOrder, NativeEndian};
+use netlink_packet_utils::{
+    nla::{DefaultNla, Nla, NlaBuffer, NlasIterator},
+    parsers::{parse_i32, parse_u32, parse_u64},
+    traits::{Emitable, Parseable},
+    DecodeError,
+};
+
+#[derive(Debug, PartialEq, Eq, Clone)]
+#[non_exhaustive]
+pub struct TcQdiscCake {}
+
+impl TcQdiscCake {
+    pub(crate) const KIND: &'static str = "cake";
+}
+
+// const TCA_CAKE_PAD: u16 = 1;
+const TCA_CAKE_BASE_RATE64: u16 = 2;
+const TCA_CAKE_DIFFSERV_MODE: u16 = 3;
+const TCA_CAKE_ATM: u16 = 4;
+const TCA_CAKE_FLOW_MODE: u16 = 5;
+const TCA_CAKE_OVERHEAD: u16 = 6;
+const TCA_CAKE_RTT: u16 = 7;
+const TCA_CAKE_TARGET: u16 = 8;
+const TCA_CAKE_AUTORATE: u16 = 9;
+const TCA_CAKE_MEMORY: u16 = 10;
+const TCA_CAKE_NAT: u16 = 11;
+const TCA_CAKE_RAW: u16 = 12;
+const TCA_CAKE_WASH: u16 = 13;
+const TCA_CAKE_MPU: u16 = 14;
+const TCA_CAKE_INGRESS: u16 = 15;
+const TCA_CAKE_ACK_FILTER: u16 = 16;
+const TCA_CAKE_SPLIT_GSO: u16 = 17;
+const TCA_CAKE_FWMARK: u16 = 18;
+
+#[derive(Debug, PartialEq, Eq, Clone)]
+#[non_exhaustive]
+pub enum TcQdiscCakeOption {
+    /// Shaper bandwidth in bytes per second, 0 means unlimited
+    BaseRate64(u64),
+    DiffservMode(TcCakeDiffservMode),
+    Atm(TcCakeAtmMode),
+    FlowMode(TcCakeFlowMode),
+    /// Per packet overhead in bytes
+    Overhead(i32),
+    /// Round trip time in microseconds
+    Rtt(u32),
+    /// Target delay in microseconds
+    Target(u32),
+    AutoRate(u32),
+    /// Memory limit in bytes
+    Memory(u32),
+    Nat(u32),
+    /// The existence of this attribute disables overhead compensation,
+    /// the kernel ignores its value.
+    Raw(u32),
+    Wash(u32),
+    /// Minimum packet size in bytes
+    Mpu(u32),
+    Ingress(u32),
+    AckFilter(TcCakeAckFilter),
+    SplitGso(u32),
+    /// Mask applied to the firewall mark for tin selection
+    FwMark(u32),
+    Other(DefaultNla),
+}
+
+impl Nla for TcQdiscCakeOption {
+    fn value_len(&self) -> usize {
+        match self {
+            Self::BaseRate64(_) => 8,
+            Self::DiffservMode(_)
+            | Self::Atm(_)
+            | Self::FlowMode(_)
+            | Self::Overhead(_)
+            | Self::Rtt(_)
+            | Self::Target(_)
+            | Self::AutoRate(_)
+            | Self::Memory(_)
+            | Self::Nat(_)
+            | Self::Raw(_)
+            | Self::Wash(_)
+            | Self::Mpu(_)
+            | Self::Ingress(_)
+            | Self::AckFilter(_)
+            | Self::SplitGso(_)
+            | Self::FwMark(_) => 4,
+            Self::Other(attr) => attr.value_len(),
+        }
+    }
+
+    fn emit_value(&self, buffer: &mut [u8]) {
+        match self {
+            Self::BaseRate64(d) => NativeEndian::write_u64(buffer, *d),
+            Self::DiffservMode(d) => {
+                NativeEndian::write_u32(buffer, (*d).into())
+            }
+            Self::Atm(d) => NativeEndian::write_u32(buffer, (*d).into()),
+            Self::FlowMode(d) => NativeEndian::write_u32(buffer, (*d).into()),
+            Self::AckFilter(d) => NativeEndian::write_u32(buffer, (*d).into()),
+            Self::Overhead(d) => NativeEndian::write_i32(buffer, *d),
+            Self::Rtt(d)
+            | Self::Target(d)
+            | Self::AutoRate(d)
+            | Self::Memory(d)
+            | Self::Nat(d)
+            | Self::Raw(d)
+            | Self::Wash(d)
+            | Self::Mpu(d)
+            | Self::Ingress(d)
+            | Self::SplitGso(d)
+            | Self::FwMark(d) => NativeEndian::write_u32(buffer, *d),
+            Self::Other(attr) => attr.emit_value(buffer),
+        }
+    }
+
+    fn kind(&self) -> u16 {
+        match self {
+            Self::BaseRate64(_) => TCA_CAKE_BASE_RATE64,
+            Self::DiffservMode(_) => TCA_CAKE_DIFFSERV_MODE,
+            Self::Atm(_) => TCA_CAKE_ATM,
+            Self::FlowMode(_) => TCA_CAKE_FLOW_MODE,
+            Self::Overhead(_) => TCA_CAKE_OVERHEAD,
+            Self::Rtt(_) => TCA_CAKE_RTT,
+            Self::Target(_) => TCA_CAKE_TARGET,
+            Self::AutoRate(_) => TCA_CAKE_AUTORATE,
+            Self::Memory(_) => TCA_CAKE_MEMORY,
+            Self::Nat(_) => TCA_CAKE_NAT,
+            Self::Raw(_) => TCA_CAKE_RAW,
+            Self::Wash(_) => TCA_CAKE_WASH,
+            Self::Mpu(_) => TCA_CAKE_MPU,
+            Self::Ingress(_) => TCA_CAKE_INGRESS,
+            Self::AckFilter(_) => TCA_CAKE_ACK_FILTER,
+            Self::SplitGso(_) => TCA_CAKE_SPLIT_GSO,
+            Self::FwMark(_) => TCA_CAKE_FWMARK,
+            Self::Other(attr) => attr.kind(),
+        }
+    }
+}
+
+impl<'a, T: AsRef<[u8]> + ?Sized> Parseable<NlaBuffer<&'a T>>
+    for TcQdiscCakeOption
+{
+    fn parse(buf: &NlaBuffer<&'a T>) -> Result<Self, DecodeError> {
+        let payload = buf.value();
+        Ok(match buf.kind() {
+            TCA_CAKE_BASE_RATE64 => Self::BaseRate64(
+                parse_u64(payload)
+                    .context("failed to parse TCA_CAKE_BASE_RATE64")?,
+            ),
+            TCA_CAKE_DIFFSERV_MODE => Self::DiffservMode(
+                parse_u32(payload)
+                    .context("failed to parse TCA_CAKE_DIFFSERV_MODE")?
+                    .into(),
+            ),
+            TCA_CAKE_ATM => Self::Atm(
+                parse_u32(payload)
+                    .context("failed to parse TCA_CAKE_ATM")?
+                    .into(),
+            ),
+            TCA_CAKE_FLOW_MODE => Self::FlowMode(
+                parse_u32(payload)
+                    .context("failed to parse TCA_CAKE_FLOW_MODE")?
+                    .into(),
+            ),
+            TCA_CAKE_OVERHEAD => Self::Overhead(
+                parse_i32(payload)
+                    .context("failed to parse TCA_CAKE_OVERHEAD")?,
+            ),
+            TCA_CAKE_RTT => Self::Rtt(
+                parse_u32(payload).context("failed to parse TCA_CAKE_RTT")?,
+            ),
+            TCA_CAKE_TARGET => Self::Target(
+                parse_u32(payload)
+                    .context("failed to parse TCA_CAKE_TARGET")?,
+            ),
+            TCA_CAKE_AUTORATE => Self::AutoRate(
+                parse_u32(payload)
+                    .context("failed to parse TCA_CAKE_AUTORATE")?,
+            ),
+            TCA_CAKE_MEMORY => Self::Memory(
+                parse_u32(payload)
+                    .context("failed to parse TCA_CAKE_MEMORY")?,
+            ),
+            TCA_CAKE_NAT => Self::Nat(
+                parse_u32(payload).context("failed to parse TCA_CAKE_NAT")?,
+            ),
+            TCA_CAKE_RAW => Self::Raw(
+                parse_u32(payload).context("failed to parse TCA_CAKE_RAW")?,
+            ),
+            TCA_CAKE_WASH => Self::Wash(
+                parse_u32(payload).context("failed to parse TCA_CAKE_WASH")?,
+            ),
+            TCA_CAKE_MPU => Self::Mpu(
+                parse_u32(payload).context("failed to parse TCA_CAKE_MPU")?,
+            ),
+            TCA_CAKE_INGRESS => Self::Ingress(
+                parse_u32(payload)
+                    .context("failed to parse TCA_CAKE_INGRESS")?,
+            ),
+            TCA_CAKE_ACK_FILTER => Self::AckFilter(
+                parse_u32(payload)
+                    .context("failed to parse TCA_CAKE_ACK_FILTER")?
+                    .into(),
+            ),
+            TCA_CAKE_SPLIT_GSO => Self::SplitGso(
+                parse_u32(payload)
+                    .context("failed to parse TCA_CAKE_SPLIT_GSO")?,
+            ),
+            TCA_CAKE_FWMARK => Self::FwMark(
+                parse_u32(payload)
+                    .context("failed to parse TCA_CAKE_FWMARK")?,
+            ),
+            _ => Self::Other(
+                DefaultNla::parse(buf).context("failed to parse cake nla")?,
+            ),
+        })
+    }
+}
+
+const CAKE_DIFFSERV_DIFFSERV3: u32 = 0;
+const CAKE_DIFFSERV_DIFFSERV4: u32 = 1;
+const CAKE_DIFFSERV_DIFFSERV8: u32 = 2;
+const CAKE_DIFFSERV_BESTEFFORT: u32 = 3;
+const CAKE_DIFFSERV_PRECEDENCE: u32 = 4;
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Default)]
+#[non_exhaustive]
+pub enum TcCakeDiffservMode {
+    #[default]
+    Diffserv3,
+    Diffserv4,
+    Diffserv8,
+    BestEffort,
+    Precedence,
+    Other(u32),
+}
+
+impl From<u32> for TcCakeDiffservMode {
+    fn from(d: u32) -> Self {
+        match d {
+            CAKE_DIFFSERV_DIFFSERV3 => Self::Diffserv3,
+            CAKE_DIFFSERV_DIFFSERV4 => Self::Diffserv4,
+            CAKE_DIFFSERV_DIFFSERV8 => Self::Diffserv8,
+            CAKE_DIFFSERV_BESTEFFORT => Self::BestEffort,
+            CAKE_DIFFSERV_PRECEDENCE => Self::Precedence,
+            _ => Self::Other(d),
+        }
+    }
+}
+
+impl From<TcCakeDiffservMode> for u32 {
+    fn from(v: TcCakeDiffservMode) -> u32 {
+        match v {
+            TcCakeDiffservMode::Diffserv3 => CAKE_DIFFSERV_DIFFSERV3,
+            TcCakeDiffservMode::Diffserv4 => CAKE_DIFFSERV_DIFFSERV4,
+            TcCakeDiffservMode::Diffserv8 => CAKE_DIFFSERV_DIFFSERV8,
+            TcCakeDiffservMode::BestEffort => CAKE_DIFFSERV_BESTEFFORT,
+            TcCakeDiffservMode::Precedence => CAKE_DIFFSERV_PRECEDENCE,
+            TcCakeDiffservMode::Other(d) => d,
+        }
+    }
+}
+
+const CAKE_ATM_NONE: u32 = 0;
+const CAKE_ATM_ATM: u32 = 1;
+const CAKE_ATM_PTM: u32 = 2;
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Default)]
+#[non_exhaustive]
+pub enum TcCakeAtmMode {
+    #[default]
+    None,
+    /// ATM cell framing
+    Atm,
+    /// PTM 64b/65b encoding
+    Ptm,
+    Other(u32),
+}
+
+impl From<u32> for TcCakeAtmMode {
+    fn from(d: u32) -> Self {
+        match d {
+            CAKE_ATM_NONE => Self::None,
+            CAKE_ATM_ATM => Self::Atm,
+            CAKE_ATM_PTM => Self::Ptm,
+            _ => Self::Other(d),
+        }
+    }
+}
+
+impl From<TcCakeAtmMode> for u32 {
+    fn from(v: TcCakeAtmMode) -> u32 {
+        match v {
+            TcCakeAtmMode::None => CAKE_ATM_NONE,
+            TcCakeAtmMode::Atm => CAKE_ATM_ATM,
+            TcCakeAtmMode::Ptm => CAKE_ATM_PTM,
+            TcCakeAtmMode::Other(d) => d,
+        }
+    }
+}
+
+const CAKE_FLOW_NONE: u32 = 0;
+const CAKE_FLOW_SRC_IP: u32 = 1;
+const CAKE_FLOW_DST_IP: u32 = 2;
+const CAKE_FLOW_HOSTS: u32 = 3;
+const CAKE_FLOW_FLOWS: u32 = 4;
+const CAKE_FLOW_DUAL_SRC: u32 = 5;
+const CAKE_FLOW_DUAL_DST: u32 = 6;
+const CAKE_FLOW_TRIPLE: u32 = 7;
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Default)]
+#[non_exhaustive]
+pub enum TcCakeFlowMode {
+    None,
+    SrcIp,
+    DstIp,
+    Hosts,
+    Flows,
+    DualSrc,
+    DualDst,
+    #[default]
+    Triple,
+    Other(u32),
+}
+
+impl From<u32> for TcCakeFlowMode {
+    fn from(d: u32) -> Self {
+        match d {
+            CAKE_FLOW_NONE => Self::None,
+            CAKE_FLOW_SRC_IP => Self::SrcIp,
+            CAKE_FLOW_DST_IP => Self::DstIp,
+            CAKE_FLOW_HOSTS => Self::Hosts,
+            CAKE_FLOW_FLOWS => Self::Flows,
+            CAKE_FLOW_DUAL_SRC => Self::DualSrc,
+            CAKE_FLOW_DUAL_DST => Self::DualDst,
+            CAKE_FLOW_TRIPLE => Self::Triple,
+            _ => Self::Other(d),
+        }
+    }
+}
+
+impl From<TcCakeFlowMode> for u32 {
+    fn from(v: TcCakeFlowMode) -> u32 {
+        match v {
+            TcCakeFlowMode::None => CAKE_FLOW_NONE,
+            TcCakeFlowMode::SrcIp => CAKE_FLOW_SRC_IP,
+            TcCakeFlowMode::DstIp => CAKE_FLOW_DST_IP,
+            TcCakeFlowMode::Hosts => CAKE_FLOW_HOSTS,
+            TcCakeFlowMode::Flows => CAKE_FLOW_FLOWS,
+            TcCakeFlowMode::DualSrc => CAKE_FLOW_DUAL_SRC,
+            TcCakeFlowMode::DualDst => CAKE_FLOW_DUAL_DST,
+            TcCakeFlowMode::Triple => CAKE_FLOW_TRIPLE,
+            TcCakeFlowMode::Other(d) => d,
+        }
+    }
+}
+
+const CAKE_ACK_NONE: u32 = 0;
+const CAKE_ACK_FILTER: u32 = 1;
+const CAKE_ACK_AGGRESSIVE: u32 = 2;
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Default)]
+#[non_exhaustive]
+pub enum TcCakeAckFilter {
+    #[default]
+    None,
+    Filter,
+    Aggressive,
+    Other(u32),
+}
+
+impl From<u32> for TcCakeAckFilter {
+    fn from(d: u32) -> Self {
+        match d {
+            CAKE_ACK_NONE => Self::None,
+            CAKE_ACK_FILTER => Self::Filter,
+            CAKE_ACK_AGGRESSIVE => Self::Aggressive,
+            _ => Self::Other(d),
+        }
+    }
+}
+
+impl From<TcCakeAckFilter> for u32 {
+    fn from(v: TcCakeAckFilter) -> u32 {
+        match v {
+            TcCakeAckFilter::None => CAKE_ACK_NONE,
+            TcCakeAckFilter::Filter => CAKE_ACK_FILTER,
+            TcCakeAckFilter::Aggressive => CAKE_ACK_AGGRESSIVE,
+            TcCakeAckFilter::Other(d) => d,
+        }
+    }
+}
+
+// const TCA_CAKE_STATS_PAD: u16 = 1;
+const TCA_CAKE_STATS_CAPACITY_ESTIMATE64: u16 = 2;
+const TCA_CAKE_STATS_MEMORY_LIMIT: u16 = 3;
+const TCA_CAKE_STATS_MEMORY_USED: u16 = 4;
+const TCA_CAKE_STATS_AVG_NETOFF: u16 = 5;
+const TCA_CAKE_STATS_MIN_NETLEN: u16 = 6;
+const TCA_CAKE_STATS_MAX_NETLEN: u16 = 7;
+const TCA_CAKE_STATS_MIN_ADJLEN: u16 = 8;
+const TCA_CAKE_STATS_MAX_ADJLEN: u16 = 9;
+const TCA_CAKE_STATS_TIN_STATS: u16 = 10;
+const TCA_CAKE_STATS_DEFICIT: u16 = 11;
+const TCA_CAKE_STATS_COBALT_COUNT: u16 = 12;
+const TCA_CAKE_STATS_DROPPING: u16 = 13;
+const TCA_CAKE_STATS_DROP_NEXT_US: u16 = 14;
+const TCA_CAKE_STATS_P_DROP: u16 = 15;
+const TCA_CAKE_STATS_BLUE_TIMER_US: u16 = 16;
+
+/// Extended statistics of cake qdisc and class, the qdisc statistics carry
+/// the capacity, memory, packet length and per tin statistics, while the
+/// class statistics carry the state of a single flow.
+#[derive(Debug, PartialEq, Eq, Clone)]
+#[non_exhaustive]
+pub enum TcCakeXstats {
+    /// Bytes per second
+    CapacityEstimate64(u64),
+    MemoryLimit(u32),
+    MemoryUsed(u32),
+    AvgNetoff(u32),
+    MinNetlen(u32),
+    MaxNetlen(u32),
+    MinAdjlen(u32),
+    MaxAdjlen(u32),
+    /// Statistics of each tin, the Nth item is the statistics of tin N.
+    TinStats(Vec<Vec<TcCakeTinStats>>),
+    Deficit(i32),
+    CobaltCount(u32),
+    Dropping(u32),
+    DropNextUs(i32),
+    PDrop(u32),
+    BlueTimerUs(i32),
+    Other(DefaultNla),
+}
+
+impl Nla for TcCakeXstats {
+    fn value_len(&self) -> usize {
+        match self {
+            Self::CapacityEstimate64(_) => 8,
+            Self::MemoryLimit(_)
+            | Self::MemoryUsed(_)
+            | Self::AvgNetoff(_)
+            | Self::MinNetlen(_)
+            | Self::MaxNetlen(_)
+            | Self::MinAdjlen(_)
+            | Self::MaxAdjlen(_)
+            | Self::Deficit(_)
+            | Self::CobaltCount(_)
+            | Self::Dropping(_)
+            | Self::DropNextUs(_)
+            | Self::PDrop(_)
+            | Self::BlueTimerUs(_) => 4,
+            Self::TinStats(v) => cake_tins(v).as_slice().buffer_len(),
+            Self::Other(attr) => attr.value_len(),
+        }
+    }
+
+    fn emit_value(&self, buffer: &mut [u8]) {
+        match self {
+            Self::CapacityEstimate64(d) => NativeEndian::write_u64(buffer, *d),
+            Self::MemoryLimit(d)
+            | Self::MemoryUsed(d)
+            | Self::AvgNetoff(d)
+            | Self::MinNetlen(d)
+            | Self::MaxNetlen(d)
+            | Self::MinAdjlen(d)
+            | Self::MaxAdjlen(d)
+            | Self::CobaltCount(d)
+            | Self::Dropping(d)
+            | Self::PDrop(d) => NativeEndian::write_u32(buffer, *d),
+            Self::Deficit(d) | Self::DropNextUs(d) | Self::BlueTimerUs(d) => {
+                NativeEndian::write_i32(buffer, *d)
+            }
+            Self::TinStats(v) => cake_tins(v).as_slice().emit(buffer),
+            Self::Other(attr) => attr.emit_value(buffer),
+        }
+    }
+
+    fn kind(&self) -> u16 {
+        match self {
+            Self::CapacityEstimate64(_) => TCA_CAKE_STATS_CAPACITY_ESTIMATE64,
+            Self::MemoryLimit(_) => TCA_CAKE_STATS_MEMORY_LIMIT,
+            Self::MemoryUsed(_) => TCA_CAKE_STATS_MEMORY_USED,
+            Self::AvgNetoff(_) => TCA_CAKE_STATS_AVG_NETOFF,
+            Self::MinNetlen(_) => TCA_CAKE_STATS_MIN_NETLEN,
+            Self::MaxNetlen(_) => TCA_CAKE_STATS_MAX_NETLEN,
+            Self::MinAdjlen(_) => TCA_CAKE_STATS_MIN_ADJLEN,
+            Self::MaxAdjlen(_) => TCA_CAKE_STATS_MAX_ADJLEN,
+            Self::TinStats(_) => TCA_CAKE_STATS_TIN_STATS,
+            Self::Deficit(_) => TCA_CAKE_STATS_DEFICIT,
+            Self::CobaltCount(_) => TCA_CAKE_STATS_COBALT_COUNT,
+            Self::Dropping(_) => TCA_CAKE_STATS_DROPPING,
+            Self::DropNextUs(_) => TCA_CAKE_STATS_DROP_NEXT_US,
+            Self::PDrop(_) => TCA_CAKE_STATS_P_DROP,
+            Self::BlueTimerUs(_) => TCA_CAKE_STATS_BLUE_TIMER_US,
+            Self::Other(attr) => attr.kind(),
+        }
+    }
+}
+
+impl<'a, T: AsRef<[u8]> + ?Sized> Parseable<NlaBuffer<&'a T>> for TcCakeXstats {
+    fn parse(buf: &NlaBuffer<&'a T>) -> Result<Self, DecodeError> {
+        let payload = buf.value();
+        Ok(match buf.kind() {
+            TCA_CAKE_STATS_CAPACITY_ESTIMATE64 => {
+                Self::CapacityEstimate64(parse_u64(payload).context(
+                    "failed to parse TCA_CAKE_STATS_CAPACITY_ESTIMATE64",
+                )?)
+            }
+            TCA_CAKE_STATS_MEMORY_LIMIT => Self::MemoryLimit(
+                parse_u32(payload)
+                    .context("failed to parse TCA_CAKE_STATS_MEMORY_LIMIT")?,
+            ),
+            TCA_CAKE_STATS_MEMORY_USED => Self::MemoryUsed(
+                parse_u32(payload)
+                    .context("failed to parse TCA_CAKE_STATS_MEMORY_USED")?,
+            ),
+            TCA_CAKE_STATS_AVG_NETOFF => Self::AvgNetoff(
+                parse_u32(payload)
+                    .context("failed to parse TCA_CAKE_STATS_AVG_NETOFF")?,
+            ),
+            TCA_CAKE_STATS_MIN_NETLEN => Self::MinNetlen(
+                parse_u32(payload)
+                    .context("failed to parse TCA_CAKE_STATS_MIN_NETLEN")?,
+            ),
+            TCA_CAKE_STATS_MAX_NETLEN => Self::MaxNetlen(
+                parse_u32(payload)
+                    .context("failed to parse TCA_CAKE_STATS_MAX_NETLEN")?,
+            ),
+            TCA_CAKE_STATS_MIN_ADJLEN => Self::MinAdjlen(
+                parse_u32(payload)
+                    .context("failed to parse TCA_CAKE_STATS_MIN_ADJLEN")?,
+            ),
+            TCA_CAKE_STATS_MAX_ADJLEN => Self::MaxAdjlen(
+                parse_u32(payload)
+                    .context("failed to parse TCA_CAKE_STATS_MAX_ADJLEN")?,
+            ),
+            TCA_CAKE_STATS_TIN_STATS => {
+                let mut tins = vec![];
+                for tin in NlasIterator::new(payload) {
+                    let tin =
+                        tin.context("invalid TCA_CAKE_STATS_TIN_STATS")?;
+                    let mut stats = vec![];
+                    for nla in NlasIterator::new(tin.value()) {
+                        let nla =
+                            nla.context("invalid TCA_CAKE_STATS_TIN_STATS")?;
+                        stats.push(TcCakeTinStats::parse(&nla).context(
+                            "failed to parse TCA_CAKE_STATS_TIN_STATS",
+                        )?);
+                    }
+                    tins.push(stats);
+                }
+                Self::TinStats(tins)
+            }
+            TCA_CAKE_STATS_DEFICIT => Self::Deficit(
+                parse_i32(payload)
+                    .context("failed to parse TCA_CAKE_STATS_DEFICIT")?,
+            ),
+            TCA_CAKE_STATS_COBALT_COUNT => Self::CobaltCount(
+                parse_u32(payload)
+                    .context("failed to parse TCA_CAKE_STATS_COBALT_COUNT")?,
+            ),
+            TCA_CAKE_STATS_DROPPING => Self::Dropping(
+                parse_u32(payload)
+                    .context("failed to parse TCA_CAKE_STATS_DROPPING")?,
+            ),
+            TCA_CAKE_STATS_DROP_NEXT_US => Self::DropNextUs(
+                parse_i32(payload)
+                    .context("failed to parse TCA_CAKE_STATS_DROP_NEXT_US")?,
+            ),
+            TCA_CAKE_STATS_P_DROP => Self::PDrop(
+                parse_u32(payload)
+                    .context("failed to parse TCA_CAKE_STATS_P_DROP")?,
+            ),
+            TCA_CAKE_STATS_BLUE_TIMER_US => Self::BlueTimerUs(
+                parse_i32(payload)
+                    .context("failed to parse TCA_CAKE_STATS_BLUE_TIMER_US")?,
+            ),
+            _ => Self::Other(
+                DefaultNla::parse(buf)
+                    .context("failed to parse cake xstats nla")?,
+            ),
+        })
+    }
+}
+
+pub(crate) fn parse_cake_xstats(
+    payload: &[u8],
+) -> Result<Vec<TcCakeXstats>, DecodeError> {
+    let mut stats = vec![];
+    for nla in NlasIterator::new(payload) {
+        let nla = nla.context("invalid cake xstats")?;
+        stats.push(
+            TcCakeXstats::parse(&nla).context("failed to parse cake xstats")?,
+        );
+    }
+    Ok(stats)
+}
+
+// Each tin is stored in a NLA using the tin index plus one as its type.
+struct CakeTin<'a> {
+    index: u16,
+    stats: &'a [TcCakeTinStats],
+}
+
+fn cake_tins(tins: &[Vec<TcCakeTinStats>]) -> Vec<CakeTin<'_>> {
+    tins.iter()
+        .enumerate()
+        .map(|(i, stats)| CakeTin {
+            index: i as u16 + 1,
+            stats: stats.as_slice(),
+        })
+        .collect()
+}
+
+impl Nla for CakeTin<'_> {
+    fn value_len(&self) -> usize {
+        self.stats.buffer_len()
+    }
+
+    fn emit_value(&self, buffer: &mut [u8]) {
+        self.stats.emit(buffer)
+    }
+
+    fn kind(&self) -> u16 {
+        self.index
+    }
+}
+
+// const TCA_CAKE_TIN_STATS_PAD: u16 = 1;
+const TCA_CAKE_TIN_STATS_SENT_PACKETS: u16 = 2;
+const TCA_CAKE_TIN_STATS_SENT_BYTES64: u16 = 3;
+const TCA_CAKE_TIN_STATS_DROPPED_PACKETS: u16 = 4;
+const TCA_CAKE_TIN_STATS_DROPPED_BYTES64: u16 = 5;
+const TCA_CAKE_TIN_STATS_ACKS_DROPPED_PACKETS: u16 = 6;
+const TCA_CAKE_TIN_STATS_ACKS_DROPPED_BYTES64: u16 = 7;
+const TCA_CAKE_TIN_STATS_ECN_MARKED_PACKETS: u16 = 8;
+const TCA_CAKE_TIN_STATS_ECN_MARKED_BYTES64: u16 = 9;
+const TCA_CAKE_TIN_STATS_BACKLOG_PACKETS: u16 = 10;
+const TCA_CAKE_TIN_STATS_BACKLOG_BYTES: u16 = 11;
+const TCA_CAKE_TIN_STATS_THRESHOLD_RATE64: u16 = 12;
+const TCA_CAKE_TIN_STATS_TARGET_US: u16 = 13;
+const TCA_CAKE_TIN_STATS_INTERVAL_US: u16 = 14;
+const TCA_CAKE_TIN_STATS_WAY_INDIRECT_HITS: u16 = 15;
+const TCA_CAKE_TIN_STATS_WAY_MISSES: u16 = 16;
+const TCA_CAKE_TIN_STATS_WAY_COLLISIONS: u16 = 17;
+const TCA_CAKE_TIN_STATS_PEAK_DELAY_US: u16 = 18;
+const TCA_CAKE_TIN_STATS_AVG_DELAY_US: u16 = 19;
+const TCA_CAKE_TIN_STATS_BASE_DELAY_US: u16 = 20;
+const TCA_CAKE_TIN_STATS_SPARSE_FLOWS: u16 = 21;
+const TCA_CAKE_TIN_STATS_BULK_FLOWS: u16 = 22;
+const TCA_CAKE_TIN_STATS_UNRESPONSIVE_FLOWS: u16 = 23;
+const TCA_CAKE_TIN_STATS_MAX_SKBLEN: u16 = 24;
+const TCA_CAKE_TIN_STATS_FLOW_QUANTUM: u16 = 25;
+
+#[derive(Debug, PartialEq, Eq, Clone)]
+#[non_exhaustive]
+pub enum TcCakeTinStats {
+    SentPackets(u32),
+    SentBytes64(u64),
+    DroppedPackets(u32),
+    DroppedBytes64(u64),
+    AcksDroppedPackets(u32),
+    AcksDroppedBytes64(u64),
+    EcnMarkedPackets(u32),
+    EcnMarkedBytes64(u64),
+    BacklogPackets(u32),
+    BacklogBytes(u32),
+    /// Bytes per second
+    ThresholdRate64(u64),
+    TargetUs(u32),
+    IntervalUs(u32),
+    WayIndirectHits(u32),
+    WayMisses(u32),
+    WayCollisions(u32),
+    PeakDelayUs(u32),
+    AvgDelayUs(u32),
+    BaseDelayUs(u32),
+    SparseFlows(u32),
+    BulkFlows(u32),
+    UnresponsiveFlows(u32),
+    MaxSkblen(u32),
+    FlowQuantum(u32),
+    Other(DefaultNla),
+}
+
+impl Nla for TcCakeTinStats {
+    fn value_len(&self) -> usize {
+        match self {
+            Self::SentBytes64(_)
+            | Self::DroppedBytes64(_)
+            | Self::AcksDroppedBytes64(_)
+            | Self::EcnMarkedBytes64(_)
+            | Self::ThresholdRate64(_) => 8,
+            Self::SentPackets(_)
+            | Self::DroppedPackets(_)
+            | Self::AcksDroppedPackets(_)
+            | Self::EcnMarkedPackets(_)
+            | Self::BacklogPackets(_)
+            | Self::BacklogBytes(_)
+            | Self::TargetUs(_)
+            | Self::IntervalUs(_)
+            | Self::WayIndirectHits(_)
+            | Self::WayMisses(_)
+            | Self::WayCollisions(_)
+            | Self::PeakDelayUs(_)
+            | Self::AvgDelayUs(_)
+            | Self::BaseDelayUs(_)
+            | Self::SparseFlows(_)
+            | Self::BulkFlows(_)
+            | Self::UnresponsiveFlows(_)
+            | Self::MaxSkblen(_)
+            | Self::FlowQuantum(_) => 4,
+            Self::Other(attr) => attr.value_len(),
+        }
+    }
+
+    fn emit_value(&self, buffer: &mut [u8]) {
+        match self {
+            Self::SentBytes64(d)
+            | Self::DroppedBytes64(d)
+            | Self::AcksDroppedBytes64(d)
+            | Self::EcnMarkedBytes64(d)
+            | Self::ThresholdRate64(d) => NativeEndian::write_u64(buffer, *d),
+            Self::SentPackets(d)
+            | Self::DroppedPackets(d)
+            | Self::AcksDroppedPackets(d)
+            | Self::EcnMarkedPackets(d)
+            | Self::BacklogPackets(d)
+            | Self::BacklogBytes(d)
+            | Self::TargetUs(d)
+            | Self::IntervalUs(d)
+            | Self::WayIndirectHits(d)
+            | Self::WayMisses(d)
+            | Self::WayCollisions(d)
+            | Self::PeakDelayUs(d)
+            | Self::AvgDelayUs(d)
+            | Self::BaseDelayUs(d)
+            | Self::SparseFlows(d)
+            | Self::BulkFlows(d)
+            | Self::UnresponsiveFlows(d)
+            | Self::MaxSkblen(d)
+            | Self::FlowQuantum(d) => NativeEndian::write_u32(buffer, *d),
+            Self::Other(attr) => attr.emit_value(buffer),
+        }
+    }
+
+    fn kind(&self) -> u16 {
+        match self {
+            Self::SentPackets(_) => TCA_CAKE_TIN_STATS_SENT_PACKETS,
+            Self::SentBytes64(_) => TCA_CAKE_TIN_STATS_SENT_BYTES64,
+            Self::DroppedPackets(_) => TCA_CAKE_TIN_STATS_DROPPED_PACKETS,
+            Self::DroppedBytes64(_) => TCA_CAKE_TIN_STATS_DROPPED_BYTES64,
+            Self::AcksDroppedPackets(_) => {
+                TCA_CAKE_TIN_STATS_ACKS_DROPPED_PACKETS
+            }
+            Self::AcksDroppedBytes64(_) => {
+                TCA_CAKE_TIN_STATS_ACKS_DROPPED_BYTES64
+            }
+            Self::EcnMarkedPackets(_) => TCA_CAKE_TIN_STATS_ECN_MARKED_PACKETS,
+            Self::EcnMarkedBytes64(_) => TCA_CAKE_TIN_STATS_ECN_MARKED_BYTES64,
+            Self::BacklogPackets(_) => TCA_CAKE_TIN_STATS_BACKLOG_PACKETS,
+            Self::BacklogBytes(_) => TCA_CAKE_TIN_STATS_BACKLOG_BYTES,
+            Self::ThresholdRate64(_) => TCA_CAKE_TIN_STATS_THRESHOLD_RATE64,
+            Self::TargetUs(_) => TCA_CAKE_TIN_STATS_TARGET_US,
+            Self::IntervalUs(_) => TCA_CAKE_TIN_STATS_INTERVAL_US,
+            Self::WayIndirectHits(_) => TCA_CAKE_TIN_STATS_WAY_INDIRECT_HITS,
+            Self::WayMisses(_) => TCA_CAKE_TIN_STATS_WAY_MISSES,
+            Self::WayCollisions(_) => TCA_CAKE_TIN_STATS_WAY_COLLISIONS,
+            Self::PeakDelayUs(_) => TCA_CAKE_TIN_STATS_PEAK_DELAY_US,
+            Self::AvgDelayUs(_) => TCA_CAKE_TIN_STATS_AVG_DELAY_US,
+            Self::BaseDelayUs(_) => TCA_CAKE_TIN_STATS_BASE_DELAY_US,
+            Self::SparseFlows(_) => TCA_CAKE_TIN_STATS_SPARSE_FLOWS,
+            Self::BulkFlows(_) => TCA_CAKE_TIN_STATS_BULK_FLOWS,
+            Self::UnresponsiveFlows(_) => TCA_CAKE_TIN_STATS_UNRESPONSIVE_FLOWS,
+            Self::MaxSkblen(_) => TCA_CAKE_TIN_STATS_MAX_SKBLEN,
+            Self::FlowQuantum(_) => TCA_CAKE_TIN_STATS_FLOW_QUANTUM,
+            Self::Other(attr) => attr.kind(),
+        }
+    }
+}
+
+impl<'a, T: AsRef<[u8]> + ?Sized> Parseable<NlaBuffer<&'a T>>
+    for TcCakeTinStats
+{
+    fn parse(buf: &NlaBuffer<&'a T>) -> Result<Self, DecodeError> {
+        let payload = buf.value();
+        Ok(match buf.kind() {
+            TCA_CAKE_TIN_STATS_SENT_PACKETS => {
+                Self::SentPackets(parse_u32(payload).context(
+                    "failed to parse TCA_CAKE_TIN_STATS_SENT_PACKETS",
+                )?)
+            }
+            TCA_CAKE_TIN_STATS_SENT_BYTES64 => {
+                Self::SentBytes64(parse_u64(payload).context(
+                    "failed to parse TCA_CAKE_TIN_STATS_SENT_BYTES64",
+                )?)
+            }
+            TCA_CAKE_TIN_STATS_DROPPED_PACKETS => {
+                Self::DroppedPackets(parse_u32(payload).context(
+                    "failed to parse TCA_CAKE_TIN_STATS_DROPPED_PACKETS",
+                )?)
+            }
+            TCA_CAKE_TIN_STATS_DROPPED_BYTES64 => {
+                Self::DroppedBytes64(parse_u64(payload).context(
+                    "failed to parse TCA_CAKE_TIN_STATS_DROPPED_BYTES64",
+                )?)
+            }
+            TCA_CAKE_TIN_STATS_ACKS_DROPPED_PACKETS => {
+                Self::AcksDroppedPackets(parse_u32(payload).context(
+                    "failed to parse TCA_CAKE_TIN_STATS_ACKS_DROPPED_PACKETS",
+                )?)
+            }
+            TCA_CAKE_TIN_STATS_ACKS_DROPPED_BYTES64 => {
+                Self::AcksDroppedBytes64(parse_u64(payload).context(
+                    "failed to parse TCA_CAKE_TIN_STATS_ACKS_DROPPED_BYTES64",
+                )?)
+            }
+            TCA_CAKE_TIN_STATS_ECN_MARKED_PACKETS => {
+                Self::EcnMarkedPackets(parse_u32(payload).context(
+                    "failed to parse TCA_CAKE_TIN_STATS_ECN_MARKED_PACKETS",
+                )?)
+            }
+            TCA_CAKE_TIN_STATS_ECN_MARKED_BYTES64 => {
+                Self::EcnMarkedBytes64(parse_u64(payload).context(
+                    "failed to parse TCA_CAKE_TIN_STATS_ECN_MARKED_BYTES64",
+                )?)
+            }
+            TCA_CAKE_TIN_STATS_BACKLOG_PACKETS => {
+                Self::BacklogPackets(parse_u32(payload).context(
+                    "failed to parse TCA_CAKE_TIN_STATS_BACKLOG_PACKETS",
+                )?)
+            }
+            TCA_CAKE_TIN_STATS_BACKLOG_BYTES => {
+                Self::BacklogBytes(parse_u32(payload).context(
+                    "failed to parse TCA_CAKE_TIN_STATS_BACKLOG_BYTES",
+                )?)
+            }
+            TCA_CAKE_TIN_STATS_THRESHOLD_RATE64 => {
+                Self::ThresholdRate64(parse_u64(payload).context(
+                    "failed to parse TCA_CAKE_TIN_STATS_THRESHOLD_RATE64",
+                )?)
+            }
+            TCA_CAKE_TIN_STATS_TARGET_US => Self::TargetUs(
+                parse_u32(payload)
+                    .context("failed to parse TCA_CAKE_TIN_STATS_TARGET_US")?,
+            ),
+            TCA_CAKE_TIN_STATS_INTERVAL_US => {
+                Self::IntervalUs(parse_u32(payload).context(
+                    "failed to parse TCA_CAKE_TIN_STATS_INTERVAL_US",
+                )?)
+            }
+            TCA_CAKE_TIN_STATS_WAY_INDIRECT_HITS => {
+                Self::WayIndirectHits(parse_u32(payload).context(
+                    "failed to parse TCA_CAKE_TIN_STATS_WAY_INDIRECT_HITS",
+                )?)
+            }
+            TCA_CAKE_TIN_STATS_WAY_MISSES => Self::WayMisses(
+                parse_u32(payload)
+                    .context("failed to parse TCA_CAKE_TIN_STATS_WAY_MISSES")?,
+            ),
+            TCA_CAKE_TIN_STATS_WAY_COLLISIONS => {
+                Self::WayCollisions(parse_u32(payload).context(
+                    "failed to parse TCA_CAKE_TIN_STATS_WAY_COLLISIONS",
+                )?)
+            }
+            TCA_CAKE_TIN_STATS_PEAK_DELAY_US => {
+                Self::PeakDelayUs(parse_u32(payload).context(
+                    "failed to parse TCA_CAKE_TIN_STATS_PEAK_DELAY_US",
+                )?)
+            }
+            TCA_CAKE_TIN_STATS_AVG_DELAY_US => {
+                Self::AvgDelayUs(parse_u32(payload).context(
+                    "failed to parse TCA_CAKE_TIN_STATS_AVG_DELAY_US",
+                )?)
+            }
+            TCA_CAKE_TIN_STATS_BASE_DELAY_US => {
+                Self::BaseDelayUs(parse_u32(payload).context(
+                    "failed to parse TCA_CAKE_TIN_STATS_BASE_DELAY_US",
+                )?)
+            }
+            TCA_CAKE_TIN_STATS_SPARSE_FLOWS => {
+                Self::SparseFlows(parse_u32(payload).context(
+                    "failed to parse TCA_CAKE_TIN_STATS_SPARSE_FLOWS",
+                )?)
+            }
+            TCA_CAKE_TIN_STATS_BULK_FLOWS => Self::BulkFlows(
+                parse_u32(payload)
+                    .context("failed to parse TCA_CAKE_TIN_STATS_BULK_FLOWS")?,
+            ),
+            TCA_CAKE_TIN_STATS_UNRESPONSIVE_FLOWS => {
+                Self::UnresponsiveFlows(parse_u32(payload).context(
+                    "failed to parse TCA_CAKE_TIN_STATS_UNRESPONSIVE_FLOWS",
+                )?)
+            }
+            TCA_CAKE_TIN_STATS_MAX_SKBLEN => Self::MaxSkblen(
+                parse_u32(payload)
+                    .context("failed to parse TCA_CAKE_TIN_STATS_MAX_SKBLEN")?,
+            ),
+            TCA_CAKE_TIN_STATS_FLOW_QUANTUM => {
+                Self::FlowQuantum(parse_u32(payload).context(
+                    "failed to parse TCA_CAKE_TIN_STATS_FLOW_QUANTUM",
+                )?)
+            }
+            _ => Self::Other(
+                DefaultNla::parse(buf)
+                    .context("failed to parse cake tin stats nla")?,
+            ),
+        })
+    }
+}
@@ -0,0 +1,128 @@
+// SPDX-License-Identifier: MIT
+
+/// Credit Based Shaper
+///
+/// The `cbs` qdisc implements the IEEE 802.1Q credit based shaper, usually
+/// offloaded to the network card as the child of an `mqprio` class.
+use anyhow::Context;
+use netlink_packet_utils::{
+    nla::{DefaultNla, Nla, NlaBuffer},
+    traits::{Emitable, Parseable},
+    DecodeError,
+};
+
+#[derive(Debug, PartialEq, Eq, Clone)]
+#[non_exhaustive]
+pub struct TcQdiscCbs {}
+
+impl TcQdiscCbs {
+    pub(crate) const KIND: &'static str = "cbs";
+}
+
+const TCA_CBS_PARMS: u16 = 1;
+
+#[derive(Debug, PartialEq, Eq, Clone)]
+#[non_exhaustive]
+pub enum TcQdiscCbsOption {
+    Parms(TcCbsQopt),
+    Other(DefaultNla),
+}
+
+impl Nla for TcQdiscCbsOption {
+    fn value_len(&self) -> usize {
+        match self {
+            Self::Parms(v) => v.buffer_len(),
+            Self::Other(attr) => attr.value_len(),
+        }
+    }
+
+    fn emit_value(&self, buffer: &mut [u8]) {
+        match self {
+            Self::Parms(v) => v.emit(buffer),
+            Self::Other(attr) => attr.emit_value(buffer),
+        }
+    }
+
+    fn kind(&self) -> u16 {
+        match self {
+            Self::Parms(_) => TCA_CBS_PARMS,
+            Self::Other(attr) => attr.kind(),
+        }
+    }
+}
+
+impl<'a, T: AsRef<[u8]> + ?Sized> Parseable<NlaBuffer<&'a T>>
+    for TcQdiscCbsOption
+{
+    fn parse(buf: &NlaBuffer<&'a T>) -> Result<Self, DecodeError> {
+        let payload = buf.value();
+        Ok(match buf.kind() {
+            TCA_CBS_PARMS => Self::Parms(
+                TcCbsQopt::parse(
+                    &TcCbsQoptBuffer::new_checked(payload)
+                        .context("invalid TCA_CBS_PARMS")?,
+                )
+                .context("failed to parse TCA_CBS_PARMS")?,
+            ),
+            _ => Self::Other(
+                DefaultNla::parse(buf).context("failed to parse cbs nla")?,
+            ),
+        })
+    }
+}
+
+const TC_CBS_QOPT_BUF_LEN: usize = 20;
+
+// kernel struct `tc_cbs_qopt`
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Default)]
+#[non_exhaustive]
+pub struct TcCbsQopt {
+    /// Set to 1 to offload the shaper to hardware
+    pub offload: u8,
+    /// Maximum credit in bytes
+    pub hicredit: i32,
+    /// Minimum credit in bytes, usually negative
+    pub locredit: i32,
+    /// Rate of credit increase in kilobits per second
+    pub idleslope: i32,
+    /// Rate of credit decrease in kilobits per second, should be
+    /// `idleslope` minus port rate
+    pub sendslope: i32,
+}
+
+buffer!(TcCbsQoptBuffer(TC_CBS_QOPT_BUF_LEN) {
+    offload: (u8, 0),
+    pad: (slice, 1..4),
+    hicredit: (i32, 4..8),
+    locredit: (i32, 8..12),
+    idleslope: (i32, 12..16),
+    sendslope: (i32, 16..TC_CBS_QOPT_BUF_LEN),
+});
+
+impl Emitable for TcCbsQopt {
+    fn buffer_len(&self) -> usize {
+        TC_CBS_QOPT_BUF_LEN
+    }
+
+    fn emit(&self, buffer: &mut [u8]) {
+        let mut packet = TcCbsQoptBuffer::new(buffer);
+        packet.set_offload(self.offload);
+        packet.pad_mut().fill(0);
+        packet.set_hicredit(self.hicredit);
+        packet.set_locredit(self.locredit);
+        packet.set_idleslope(self.idleslope);
+        packet.set_sendslope(self.sendslope);
+    }
+}
+
+impl<T: AsRef<[u8]>> Parseable<TcCbsQoptBuffer<T>> for TcCbsQopt {
+    fn parse(buf: &TcCbsQoptBuffer<T>) -> Result<Self, DecodeError> {
+        Ok(Self {
+            offload: buf.offload(),
+            hicredit: buf.hicredit(),
+            locredit: buf.locredit(),
+            idleslope: buf.idleslope(),
+            sendslope: buf.sendslope(),
+        })
+    }
+}
@@ -0,0 +1,150 @@
+// SPDX-License-Identifier: MIT
+
+/// CHOose and Keep for responsive flows, CHOose and Kill for unresponsive
+/// flows
+///
+/// The `choke` qdisc is RED with additional dropping of packets belonging
+/// to the same flow as a randomly picked packet in the queue.
+use anyhow::Context;
+use byteorder::{ByteOrder, NativeEndian};
+use netlink_packet_utils::{
+    nla::{DefaultNla, Nla, NlaBuffer},
+    parsers::parse_u32,
+    traits::{Emitable, Parseable},
+    DecodeError,
+};
+
+use crate::tc::{TcRedQopt, TcRedQoptBuffer};
+
+#[derive(Debug, PartialEq, Eq, Clone)]
+#[non_exhaustive]
+pub struct TcQdiscChoke {}
+
+impl TcQdiscChoke {
+    pub(crate) const KIND: &'static str = "choke";
+}
+
+const TCA_CHOKE_PARMS: u16 = 1;
+const TCA_CHOKE_STAB: u16 = 2;
+const TCA_CHOKE_MAX_P: u16 = 3;
+
+#[derive(Debug, PartialEq, Eq, Clone)]
+#[non_exhaustive]
+pub enum TcQdiscChokeOption {
+    /// The kernel struct `tc_choke_qopt` is identical to `tc_red_qopt`
+    /// with `limit`, `qth_min` and `qth_max` in packets.
+    Parms(TcRedQopt),
+    /// Idle damping lookup table of 256 cells, could be generated by
+    /// `TcPsched::calc_red_stab()`
+    Stab(Vec<u8>),
+    /// Maximum drop probability in fixed point of 32 bits fraction
+    MaxP(u32),
+    Other(DefaultNla),
+}
+
+impl Nla for TcQdiscChokeOption {
+    fn value_len(&self) -> usize {
+        match self {
+            Self::Parms(v) => v.buffer_len(),
+            Self::Stab(v) => v.len(),
+            Self::MaxP(_) => 4,
+            Self::Other(attr) => attr.value_len(),
+        }
+    }
+
+    fn emit_value(&self, buffer: &mut [u8]) {
+        match self {
+            Self::Parms(v) => v.emit(buffer),
+            Self::Stab(v) => buffer.copy_from_slice(v.as_slice()),
+            Self::MaxP(d) => NativeEndian::write_u32(buffer, *d),
+            Self::Other(attr) => attr.emit_value(buffer),
+        }
+    }
+
+    fn kind(&self) -> u16 {
+        match self {
+            Self::Parms(_) => TCA_CHOKE_PARMS,
+            Self::Stab(_) => TCA_CHOKE_STAB,
+            Self::MaxP(_) => TCA_CHOKE_MAX_P,
+            Self::Other(attr) => attr.kind(),
+        }
+    }
+}
+
+impl<'a, T: AsRef<[u8]> + ?Sized> Parseable<NlaBuffer<&'a T>>
+    for TcQdiscChokeOption
+{
+    fn parse(buf: &NlaBuffer<&'a T>) -> Result<Self, DecodeError> {
+        let payload = buf.value();
+        Ok(match buf.kind() {
+            TCA_CHOKE_PARMS => Self::Parms(
+                TcRedQopt::parse(
+                    &TcRedQoptBuffer::new_checked(payload)
+                        .context("invalid TCA_CHOKE_PARMS")?,
+                )
+                .context("failed to parse TCA_CHOKE_PARMS")?,
+            ),
+            TCA_CHOKE_STAB => Self::Stab(payload.to_vec()),
+            TCA_CHOKE_MAX_P => Self::MaxP(
+                parse_u32(payload)
+                    .context("failed to parse TCA_CHOKE_MAX_P")?,
+            ),
+            _ => Self::Other(
+                DefaultNla::parse(buf).context("failed to parse choke nla")?,
+            ),
+        })
+    }
+}
+
+const TC_CHOKE_XSTATS_BUF_LEN: usize = 20;
+
+// kernel struct `tc_choke_xstats`
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Default)]
+#[non_exhaustive]
+pub struct TcChokeXstats {
+    /// Early drops
+    pub early: u32,
+    /// Drops due to queue limits
+    pub pdrop: u32,
+    /// Drops due to drop() calls
+    pub other: u32,
+    /// Marked packets
+    pub marked: u32,
+    /// Drops due to flow match
+    pub matched: u32,
+}
+
+buffer!(TcChokeXstatsBuffer(TC_CHOKE_XSTATS_BUF_LEN) {
+    early: (u32, 0..4),
+    pdrop: (u32, 4..8),
+    other: (u32, 8..12),
+    marked: (u32, 12..16),
+    matched: (u32, 16..TC_CHOKE_XSTATS_BUF_LEN),
+});
+
+impl Emitable for TcChokeXstats {
+    fn buffer_len(&self) -> usize {
+        TC_CHOKE_XSTATS_BUF_LEN
+    }
+
+    fn emit(&self, buffer: &mut [u8]) {
+        let mut packet = TcChokeXstatsBuffer::new(buffer);
+        packet.set_early(self.early);
+        packet.set_pdrop(self.pdrop);
+        packet.set_other(self.other);
+        packet.set_marked(self.marked);
+        packet.set_matched(self.matched);
+    }
+}
+
+impl<T: AsRef<[u8]>> Parseable<TcChokeXstatsBuffer<T>> for TcChokeXstats {
+    fn parse(buf: &TcChokeXstatsBuffer<T>) -> Result<Self, DecodeError> {
+        Ok(Self {
+            early: buf.early(),
+            pdrop: buf.pdrop(),
+            other: buf.other(),
+            marked: buf.marked(),
+            matched: buf.matched(),
+        })
+    }
+}
@@ -0,0 +1,185 @@
+// SPDX-License-Identifier: MIT
+
+/// Controlled Delay
+///
+/// The `codel` qdisc drops packets when the minimum queueing delay
+/// stays above `target` for at least `interval`.
+use anyhow::Context;
+use byteorder::{ByteOrder, NativeEndian};
+use netlink_packet_utils::{
+    nla::{DefaultNla, Nla, NlaBuffer},
+    parsers::parse_u32,
+    traits::{Emitable, Parseable},
+    DecodeError,
+};
+
+#[derive(Debug, PartialEq, Eq, Clone)]
+#[non_exhaustive]
+pub struct TcQdiscCodel {}
+
+impl TcQdiscCodel {
+    pub(crate) const KIND: &'static str = "codel";
+}
+
+const TCA_CODEL_TARGET: u16 = 1;
+const TCA_CODEL_LIMIT: u16 = 2;
+const TCA_CODEL_INTERVAL: u16 = 3;
+const TCA_CODEL_ECN: u16 = 4;
+const TCA_CODEL_CE_THRESHOLD: u16 = 5;
+
+#[derive(Debug, PartialEq, Eq, Clone)]
+#[non_exhaustive]
+pub enum TcQdiscCodelOption {
+    /// Acceptable minimum queueing delay in microseconds
+    Target(u32),
+    /// Hard limit on queue length in packets
+    Limit(u32),
+    /// Width of moving time window in microseconds
+    Interval(u32),
+    /// Mark packets with ECN instead of dropping when set to 1
+    Ecn(u32),
+    /// Queueing delay in microseconds above which packets are
+    /// marked with ECN CE
+    CeThreshold(u32),
+    Other(DefaultNla),
+}
+
+impl Nla for TcQdiscCodelOption {
+    fn value_len(&self) -> usize {
+        match self {
+            Self::Target(_)
+            | Self::Limit(_)
+            | Self::Interval(_)
+            | Self::Ecn(_)
+            | Self::CeThreshold(_) => 4,
+            Self::Other(attr) => attr.value_len(),
+        }
+    }
+
+    fn emit_value(&self, buffer: &mut [u8]) {
+        match self {
+            Self::Target(d)
+            | Self::Limit(d)
+            | Self::Interval(d)
+            | Self::Ecn(d)
+            | Self::CeThreshold(d) => NativeEndian::write_u32(buffer, *d),
+            Self::Other(attr) => attr.emit_value(buffer),
+        }
+    }
+
+    fn kind(&self) -> u16 {
+        match self {
+            Self::Target(_) => TCA_CODEL_TARGET,
+            Self::Limit(_) => TCA_CODEL_LIMIT,
+            Self::Interval(_) => TCA_CODEL_INTERVAL,
+            Self::Ecn(_) => TCA_CODEL_ECN,
+            Self::CeThreshold(_) => TCA_CODEL_CE_THRESHOLD,
+            Self::Other(attr) => attr.kind(),
+        }
+    }
+}
+
+impl<'a, T: AsRef<[u8]> + ?Sized> Parseable<NlaBuffer<&'a T>>
+    for TcQdiscCodelOption
+{
+    fn parse(buf: &NlaBuffer<&'a T>) -> Result<Self, DecodeError> {
+        let payload = buf.value();
+        Ok(match buf.kind() {
+            TCA_CODEL_TARGET => Self::Target(
+                parse_u32(payload)
+                    .context("failed to parse TCA_CODEL_TARGET")?,
+            ),
+            TCA_CODEL_LIMIT => Self::Limit(
+                parse_u32(payload)
+                    .context("failed to parse TCA_CODEL_LIMIT")?,
+            ),
+            TCA_CODEL_INTERVAL => Self::Interval(
+                parse_u32(payload)
+                    .context("failed to parse TCA_CODEL_INTERVAL")?,
+            ),
+            TCA_CODEL_ECN => Self::Ecn(
+                parse_u32(payload).context("failed to parse TCA_CODEL_ECN")?,
+            ),
+            TCA_CODEL_CE_THRESHOLD => Self::CeThreshold(
+                parse_u32(payload)
+                    .context("failed to parse TCA_CODEL_CE_THRESHOLD")?,
+            ),
+            _ => Self::Other(
+                DefaultNla::parse(buf).context("failed to parse codel nla")?,
+            ),
+        })
+    }
+}
+
+const TC_CODEL_XSTATS_BUF_LEN: usize = 36;
+
+// kernel struct `tc_codel_xstats`
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Default)]
+#[non_exhaustive]
+pub struct TcCodelXstats {
+    /// Largest packet seen so far
+    pub maxpacket: u32,
+    /// Drops since entering dropping state
+    pub count: u32,
+    /// Count at entry to dropping state
+    pub lastcount: u32,
+    /// In-queue delay of most recently dequeued packet
+    pub ldelay: u32,
+    /// Time to drop next packet
+    pub drop_next: i32,
+    /// Times the packet limit of qdisc was hit
+    pub drop_overlimit: u32,
+    /// Packets ECN marked instead of dropped
+    pub ecn_mark: u32,
+    /// Whether in dropping state
+    pub dropping: u32,
+    /// Packets CE marked because of `ce_threshold`
+    pub ce_mark: u32,
+}
+
+buffer!(TcCodelXstatsBuffer(TC_CODEL_XSTATS_BUF_LEN) {
+    maxpacket: (u32, 0..4),
+    count: (u32, 4..8),
+    lastcount: (u32, 8..12),
+    ldelay: (u32, 12..16),
+    drop_next: (i32, 16..20),
+    drop_overlimit: (u32, 20..24),
+    ecn_mark: (u32, 24..28),
+    dropping: (u32, 28..32),
+    ce_mark: (u32, 32..TC_CODEL_XSTATS_BUF_LEN),
+});
+
+impl Emitable for TcCodelXstats {
+    fn buffer_len(&self) -> usize {
+        TC_CODEL_XSTATS_BUF_LEN
+    }
+
+    fn emit(&self, buffer: &mut [u8]) {
+        let mut packet = TcCodelXstatsBuffer::new(buffer);
+        packet.set_maxpacket(self.maxpacket);
+        packet.set_count(self.count);
+        packet.set_lastcount(self.lastcount);
+        packet.set_ldelay(self.ldelay);
+        packet.set_drop_next(self.drop_next);
+        packet.set_drop_overlimit(self.drop_overlimit);
+        packet.set_ecn_mark(self.ecn_mark);
+        packet.set_dropping(self.dropping);
+        packet.set_ce_mark(self.ce_mark);
+    }
+}
+
+impl<T: AsRef<[u8]>> Parseable<TcCodelXstatsBuffer<T>> for TcCodelXstats {
+    fn parse(buf: &TcCodelXstatsBuffer<T>) -> Result<Self, DecodeError> {
+        Ok(Self {
+            maxpacket: buf.maxpacket(),
+            count: buf.count(),
+            lastcount: buf.lastcount(),
+            ldelay: buf.ldelay(),
+            drop_next: buf.drop_next(),
+            drop_overlimit: buf.drop_overlimit(),
+            ecn_mark: buf.ecn_mark(),
+            dropping: buf.dropping(),
+            ce_mark: buf.ce_mark(),
+        })
+    }
+}
@@ -0,0 +1,105 @@
+// SPDX-License-Identifier: MIT
+
+/// Deficit Round Robin
+///
+/// The `drr` qdisc has no options, each of its classes is served in turn
+/// for up to `quantum` bytes.
+use anyhow::Context;
+use byteorder::{ByteOrder, NativeEndian};
+use netlink_packet_utils::{
+    nla::{DefaultNla, Nla, NlaBuffer},
+    parsers::parse_u32,
+    traits::{Emitable, Parseable},
+    DecodeError,
+};
+
+#[derive(Debug, PartialEq, Eq, Clone)]
+#[non_exhaustive]
+pub struct TcQdiscDrr {}
+
+impl TcQdiscDrr {
+    pub(crate) const KIND: &'static str = "drr";
+}
+
+const TCA_DRR_QUANTUM: u16 = 1;
+
+#[derive(Debug, PartialEq, Eq, Clone)]
+#[non_exhaustive]
+pub enum TcQdiscDrrOption {
+    /// Bytes the class may dequeue in each round
+    Quantum(u32),
+    Other(DefaultNla),
+}
+
+impl Nla for TcQdiscDrrOption {
+    fn value_len(&self) -> usize {
+        match self {
+            Self::Quantum(_) => 4,
+            Self::Other(attr) => attr.value_len(),
+        }
+    }
+
+    fn emit_value(&self, buffer: &mut [u8]) {
+        match self {
+            Self::Quantum(d) => NativeEndian::write_u32(buffer, *d),
+            Self::Other(attr) => attr.emit_value(buffer),
+        }
+    }
+
+    fn kind(&self) -> u16 {
+        match self {
+            Self::Quantum(_) => TCA_DRR_QUANTUM,
+            Self::Other(attr) => attr.kind(),
+        }
+    }
+}
+
+impl<'a, T: AsRef<[u8]> + ?Sized> Parseable<NlaBuffer<&'a T>>
+    for TcQdiscDrrOption
+{
+    fn parse(buf: &NlaBuffer<&'a T>) -> Result<Self, DecodeError> {
+        let payload = buf.value();
+        Ok(match buf.kind() {
+            TCA_DRR_QUANTUM => Self::Quantum(
+                parse_u32(payload)
+                    .context("failed to parse TCA_DRR_QUANTUM")?,
+            ),
+            _ => Self::Other(
+                DefaultNla::parse(buf).context("failed to parse drr nla")?,
+            ),
+        })
+    }
+}
+
+const TC_DRR_STATS_BUF_LEN: usize = 4;
+
+// kernel struct `tc_drr_stats`
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Default)]
+#[non_exhaustive]
+pub struct TcDrrStats {
+    /// Bytes left for the class in current round
+    pub deficit: u32,
+}
+
+buffer!(TcDrrStatsBuffer(TC_DRR_STATS_BUF_LEN) {
+    deficit: (u32, 0..TC_DRR_STATS_BUF_LEN),
+});
+
+impl Emitable for TcDrrStats {
+    fn buffer_len(&self) -> usize {
+        TC_DRR_STATS_BUF_LEN
+    }
+
+    fn emit(&self, buffer: &mut [u8]) {
+        let mut packet = TcDrrStatsBuffer::new(buffer);
+        packet.set_deficit(self.deficit);
+    }
+}
+
+impl<T: AsRef<[u8]>> Parseable<TcDrrStatsBuffer<T>> for TcDrrStats {
+    fn parse(buf: &TcDrrStatsBuffer<T>) -> Result<Self, DecodeError> {
+        Ok(Self {
+            deficit: buf.deficit(),
+        })
+    }
+}
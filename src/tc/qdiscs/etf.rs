@@ -0,0 +1,178 @@
+// SPDX-License-Identifier: MIT
+
+/// Earliest TxTime First
+///
+/// The `etf` qdisc sorts packets by the `SO_TXTIME` transmit time set by
+/// applications and dequeues them `delta` nanoseconds ahead of it.
+use anyhow::Context;
+use netlink_packet_utils::{
+    nla::{DefaultNla, Nla, NlaBuffer},
+    traits::{Emitable, Parseable},
+    DecodeError,
+};
+
+#[derive(Debug, PartialEq, Eq, Clone)]
+#[non_exhaustive]
+pub struct TcQdiscEtf {}
+
+impl TcQdiscEtf {
+    pub(crate) const KIND: &'static str = "etf";
+}
+
+const TCA_ETF_PARMS: u16 = 1;
+
+#[derive(Debug, PartialEq, Eq, Clone)]
+#[non_exhaustive]
+pub enum TcQdiscEtfOption {
+    Parms(TcEtfQopt),
+    Other(DefaultNla),
+}
+
+impl Nla for TcQdiscEtfOption {
+    fn value_len(&self) -> usize {
+        match self {
+            Self::Parms(v) => v.buffer_len(),
+            Self::Other(attr) => attr.value_len(),
+        }
+    }
+
+    fn emit_value(&self, buffer: &mut [u8]) {
+        match self {
+            Self::Parms(v) => v.emit(buffer),
+            Self::Other(attr) => attr.emit_value(buffer),
+        }
+    }
+
+    fn kind(&self) -> u16 {
+        match self {
+            Self::Parms(_) => TCA_ETF_PARMS,
+            Self::Other(attr) => attr.kind(),
+        }
+    }
+}
+
+impl<'a, T: AsRef<[u8]> + ?Sized> Parseable<NlaBuffer<&'a T>>
+    for TcQdiscEtfOption
+{
+    fn parse(buf: &NlaBuffer<&'a T>) -> Result<Self, DecodeError> {
+        let payload = buf.value();
+        Ok(match buf.kind() {
+            TCA_ETF_PARMS => Self::Parms(
+                TcEtfQopt::parse(
+                    &TcEtfQoptBuffer::new_checked(payload)
+                        .context("invalid TCA_ETF_PARMS")?,
+                )
+                .context("failed to parse TCA_ETF_PARMS")?,
+            ),
+            _ => Self::Other(
+                DefaultNla::parse(buf).context("failed to parse etf nla")?,
+            ),
+        })
+    }
+}
+
+const TC_ETF_QOPT_BUF_LEN: usize = 12;
+
+// kernel struct `tc_etf_qopt`
+#[derive(Debug, PartialEq, Eq, Clone, Default)]
+#[non_exhaustive]
+pub struct TcEtfQopt {
+    /// Nanoseconds before transmit time to dequeue packets
+    pub delta: i32,
+    /// Reference clock of transmit time, `CLOCK_TAI` is 11
+    pub clockid: i32,
+    pub flags: Vec<TcEtfFlag>,
+}
+
+buffer!(TcEtfQoptBuffer(TC_ETF_QOPT_BUF_LEN) {
+    delta: (i32, 0..4),
+    clockid: (i32, 4..8),
+    flags: (u32, 8..TC_ETF_QOPT_BUF_LEN),
+});
+
+impl Emitable for TcEtfQopt {
+    fn buffer_len(&self) -> usize {
+        TC_ETF_QOPT_BUF_LEN
+    }
+
+    fn emit(&self, buffer: &mut [u8]) {
+        let mut packet = TcEtfQoptBuffer::new(buffer);
+        packet.set_delta(self.delta);
+        packet.set_clockid(self.clockid);
+        packet.set_flags(u32::from(&VecTcEtfFlag(self.flags.to_vec())));
+    }
+}
+
+impl<T: AsRef<[u8]>> Parseable<TcEtfQoptBuffer<T>> for TcEtfQopt {
+    fn parse(buf: &TcEtfQoptBuffer<T>) -> Result<Self, DecodeError> {
+        Ok(Self {
+            delta: buf.delta(),
+            clockid: buf.clockid(),
+            flags: VecTcEtfFlag::from(buf.flags()).0,
+        })
+    }
+}
+
+const TC_ETF_DEADLINE_MODE_ON: u32 = 1 << 0;
+const TC_ETF_OFFLOAD_ON: u32 = 1 << 1;
+const TC_ETF_SKIP_SOCK_CHECK: u32 = 1 << 2;
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+#[non_exhaustive]
+pub enum TcEtfFlag {
+    /// Dequeue packets at `delta` before their deadline instead of
+    /// their exact transmit time
+    DeadlineMode,
+    /// Offload the launch time to hardware
+    Offload,
+    /// Accept packets from sockets without `SO_TXTIME`
+    SkipSockCheck,
+    Other(u32),
+}
+
+impl From<TcEtfFlag> for u32 {
+    fn from(v: TcEtfFlag) -> u32 {
+        match v {
+            TcEtfFlag::DeadlineMode => TC_ETF_DEADLINE_MODE_ON,
+            TcEtfFlag::Offload => TC_ETF_OFFLOAD_ON,
+            TcEtfFlag::SkipSockCheck => TC_ETF_SKIP_SOCK_CHECK,
+            TcEtfFlag::Other(i) => i,
+        }
+    }
+}
+
+const ALL_ETF_FLAGS: [TcEtfFlag; 3] = [
+    TcEtfFlag::DeadlineMode,
+    TcEtfFlag::Offload,
+    TcEtfFlag::SkipSockCheck,
+];
+
+#[derive(Clone, Eq, PartialEq, Debug)]
+struct VecTcEtfFlag(Vec<TcEtfFlag>);
+
+impl From<u32> for VecTcEtfFlag {
+    fn from(d: u32) -> Self {
+        let mut got: u32 = 0;
+        let mut ret = Vec::new();
+        for flag in ALL_ETF_FLAGS {
+            if (d & (u32::from(flag))) > 0 {
+                ret.push(flag);
+                got += u32::from(flag);
+            }
+        }
+        if got != d {
+            ret.push(TcEtfFlag::Other(d - got));
+        }
+        Self(ret)
+    }
+}
+
+impl From<&VecTcEtfFlag> for u32 {
+    fn from(v: &VecTcEtfFlag) -> u32 {
+        let mut d: u32 = 0;
+        for flag in &v.0 {
+            d += u32::from(*flag);
+        }
+        d
+    }
+}
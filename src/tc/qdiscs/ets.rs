@@ -0,0 +1,170 @@
+// SPDX-License-Identifier: MIT
+
+/// Enhanced Transmission Selection
+///
+/// The `ets` qdisc has strict priority bands followed by bands sharing
+/// bandwidth by deficit round robin with their `quantum`, as defined in
+/// IEEE 802.1Qaz.
+use anyhow::Context;
+use byteorder::{ByteOrder, NativeEndian};
+use netlink_packet_utils::{
+    nla::{DefaultNla, Nla, NlaBuffer, NlasIterator},
+    parsers::{parse_u32, parse_u8},
+    traits::{Emitable, Parseable},
+    DecodeError,
+};
+
+#[derive(Debug, PartialEq, Eq, Clone)]
+#[non_exhaustive]
+pub struct TcQdiscEts {}
+
+impl TcQdiscEts {
+    pub(crate) const KIND: &'static str = "ets";
+}
+
+const TCA_ETS_NBANDS: u16 = 1;
+const TCA_ETS_NSTRICT: u16 = 2;
+const TCA_ETS_QUANTA: u16 = 3;
+const TCA_ETS_QUANTA_BAND: u16 = 4;
+const TCA_ETS_PRIOMAP: u16 = 5;
+const TCA_ETS_PRIOMAP_BAND: u16 = 6;
+
+#[derive(Debug, PartialEq, Eq, Clone)]
+#[non_exhaustive]
+pub enum TcQdiscEtsOption {
+    /// Number of bands
+    Nbands(u8),
+    /// Number of strict priority bands
+    Nstrict(u8),
+    /// Quantum in bytes of each non-strict band
+    Quanta(Vec<u32>),
+    /// Quantum in bytes of the band, used by class
+    QuantaBand(u32),
+    /// Map from logical priority to band
+    Priomap(Vec<u8>),
+    Other(DefaultNla),
+}
+
+impl Nla for TcQdiscEtsOption {
+    fn value_len(&self) -> usize {
+        match self {
+            Self::Nbands(_) | Self::Nstrict(_) => 1,
+            Self::Quanta(v) => ets_quanta(v).as_slice().buffer_len(),
+            Self::QuantaBand(_) => 4,
+            Self::Priomap(v) => ets_priomap(v).as_slice().buffer_len(),
+            Self::Other(attr) => attr.value_len(),
+        }
+    }
+
+    fn emit_value(&self, buffer: &mut [u8]) {
+        match self {
+            Self::Nbands(d) | Self::Nstrict(d) => buffer[0] = *d,
+            Self::Quanta(v) => ets_quanta(v).as_slice().emit(buffer),
+            Self::QuantaBand(d) => NativeEndian::write_u32(buffer, *d),
+            Self::Priomap(v) => ets_priomap(v).as_slice().emit(buffer),
+            Self::Other(attr) => attr.emit_value(buffer),
+        }
+    }
+
+    fn kind(&self) -> u16 {
+        match self {
+            Self::Nbands(_) => TCA_ETS_NBANDS,
+            Self::Nstrict(_) => TCA_ETS_NSTRICT,
+            Self::Quanta(_) => TCA_ETS_QUANTA,
+            Self::QuantaBand(_) => TCA_ETS_QUANTA_BAND,
+            Self::Priomap(_) => TCA_ETS_PRIOMAP,
+            Self::Other(attr) => attr.kind(),
+        }
+    }
+
+    fn is_nested(&self) -> bool {
+        matches!(self, Self::Quanta(_) | Self::Priomap(_))
+    }
+}
+
+impl<'a, T: AsRef<[u8]> + ?Sized> Parseable<NlaBuffer<&'a T>>
+    for TcQdiscEtsOption
+{
+    fn parse(buf: &NlaBuffer<&'a T>) -> Result<Self, DecodeError> {
+        let payload = buf.value();
+        Ok(match buf.kind() {
+            TCA_ETS_NBANDS => Self::Nbands(
+                parse_u8(payload).context("failed to parse TCA_ETS_NBANDS")?,
+            ),
+            TCA_ETS_NSTRICT => Self::Nstrict(
+                parse_u8(payload).context("failed to parse TCA_ETS_NSTRICT")?,
+            ),
+            TCA_ETS_QUANTA => {
+                let mut quanta = vec![];
+                for nla in NlasIterator::new(payload) {
+                    let nla = nla.context("invalid TCA_ETS_QUANTA")?;
+                    quanta.push(
+                        parse_u32(nla.value())
+                            .context("failed to parse TCA_ETS_QUANTA_BAND")?,
+                    );
+                }
+                Self::Quanta(quanta)
+            }
+            TCA_ETS_QUANTA_BAND => Self::QuantaBand(
+                parse_u32(payload)
+                    .context("failed to parse TCA_ETS_QUANTA_BAND")?,
+            ),
+            TCA_ETS_PRIOMAP => {
+                let mut priomap = vec![];
+                for nla in NlasIterator::new(payload) {
+                    let nla = nla.context("invalid TCA_ETS_PRIOMAP")?;
+                    priomap.push(
+                        parse_u8(nla.value())
+                            .context("failed to parse TCA_ETS_PRIOMAP_BAND")?,
+                    );
+                }
+                Self::Priomap(priomap)
+            }
+            _ => Self::Other(
+                DefaultNla::parse(buf).context("failed to parse ets nla")?,
+            ),
+        })
+    }
+}
+
+// Each quantum is stored in its own TCA_ETS_QUANTA_BAND
+struct EtsQuantum(u32);
+
+fn ets_quanta(quanta: &[u32]) -> Vec<EtsQuantum> {
+    quanta.iter().map(|quantum| EtsQuantum(*quantum)).collect()
+}
+
+impl Nla for EtsQuantum {
+    fn value_len(&self) -> usize {
+        4
+    }
+
+    fn emit_value(&self, buffer: &mut [u8]) {
+        NativeEndian::write_u32(buffer, self.0)
+    }
+
+    fn kind(&self) -> u16 {
+        TCA_ETS_QUANTA_BAND
+    }
+}
+
+// Each band of priority map is stored in its own TCA_ETS_PRIOMAP_BAND
+struct EtsPriomapBand(u8);
+
+fn ets_priomap(priomap: &[u8]) -> Vec<EtsPriomapBand> {
+    priomap.iter().map(|band| EtsPriomapBand(*band)).collect()
+}
+
+impl Nla for EtsPriomapBand {
+    fn value_len(&self) -> usize {
+        1
+    }
+
+    fn emit_value(&self, buffer: &mut [u8]) {
+        buffer[0] = self.0
+    }
+
+    fn kind(&self) -> u16 {
+        TCA_ETS_PRIOMAP_BAND
+    }
+}
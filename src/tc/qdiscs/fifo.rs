@@ -0,0 +1,98 @@
+// SPDX-License-Identifier: MIT
+
+/// Packet and byte limited First In, First Out queues
+use anyhow::Context;
+use netlink_packet_utils::{
+    nla::Nla,
+    traits::{Emitable, Parseable},
+    DecodeError,
+};
+
+/// The `pfifo` qdisc is limited by number of packets.
+#[derive(Debug, PartialEq, Eq, Clone)]
+#[non_exhaustive]
+pub struct TcQdiscPfifo {}
+
+impl TcQdiscPfifo {
+    pub(crate) const KIND: &'static str = "pfifo";
+}
+
+/// The `bfifo` qdisc is limited by number of bytes.
+#[derive(Debug, PartialEq, Eq, Clone)]
+#[non_exhaustive]
+pub struct TcQdiscBfifo {}
+
+impl TcQdiscBfifo {
+    pub(crate) const KIND: &'static str = "bfifo";
+}
+
+#[derive(Debug, PartialEq, Eq, Clone)]
+#[non_exhaustive]
+pub enum TcQdiscFifoOption {
+    /// The `tc_fifo_qopt` is the whole TCA_OPTIONS without NLA header.
+    Qopt(TcFifoQopt),
+}
+
+impl Nla for TcQdiscFifoOption {
+    fn value_len(&self) -> usize {
+        match self {
+            Self::Qopt(v) => v.buffer_len(),
+        }
+    }
+
+    fn emit_value(&self, buffer: &mut [u8]) {
+        match self {
+            Self::Qopt(v) => v.emit(buffer),
+        }
+    }
+
+    fn kind(&self) -> u16 {
+        match self {
+            // Emitted without NLA header, see `TcQdiscFifoOption::Qopt`
+            Self::Qopt(_) => 0,
+        }
+    }
+}
+
+pub(crate) fn parse_fifo_options(
+    payload: &[u8],
+) -> Result<Vec<TcQdiscFifoOption>, DecodeError> {
+    Ok(vec![TcQdiscFifoOption::Qopt(
+        TcFifoQopt::parse(
+            &TcFifoQoptBuffer::new_checked(payload)
+                .context("invalid tc_fifo_qopt")?,
+        )
+        .context("failed to parse tc_fifo_qopt")?,
+    )])
+}
+
+const TC_FIFO_QOPT_BUF_LEN: usize = 4;
+
+// kernel struct `tc_fifo_qopt`
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Default)]
+#[non_exhaustive]
+pub struct TcFifoQopt {
+    /// Queue length in packets for `pfifo` or in bytes for `bfifo`
+    pub limit: u32,
+}
+
+buffer!(TcFifoQoptBuffer(TC_FIFO_QOPT_BUF_LEN) {
+    limit: (u32, 0..TC_FIFO_QOPT_BUF_LEN),
+});
+
+impl Emitable for TcFifoQopt {
+    fn buffer_len(&self) -> usize {
+        TC_FIFO_QOPT_BUF_LEN
+    }
+
+    fn emit(&self, buffer: &mut [u8]) {
+        let mut packet = TcFifoQoptBuffer::new(buffer);
+        packet.set_limit(self.limit);
+    }
+}
+
+impl<T: AsRef<[u8]>> Parseable<TcFifoQoptBuffer<T>> for TcFifoQopt {
+    fn parse(buf: &TcFifoQoptBuffer<T>) -> Result<Self, DecodeError> {
+        Ok(Self { limit: buf.limit() })
+    }
+}
@@ -0,0 +1,375 @@
+// SPDX-License-Identifier: MIT
+
+/// Fair Queue
+///
+/// FQ is a flow based scheduler designed for pacing of locally generated
+/// traffic.
+use anyhow::Context;
+use byteorder::{ByteOrder, NativeEndian};
+use netlink_packet_utils::{
+    nla::{DefaultNla, Nla, NlaBuffer},
+    parsers::{parse_u32, parse_u8},
+    traits::{Emitable, Parseable},
+    DecodeError,
+};
+
+use crate::tc::{TcPrioQopt, TcPrioQoptBuffer};
+
+#[derive(Debug, PartialEq, Eq, Clone)]
+#[non_exhaustive]
+pub struct TcQdiscFq {}
+
+impl TcQdiscFq {
+    pub(crate) const KIND: &'static str = "fq";
+}
+
+const TCA_FQ_PLIMIT: u16 = 1;
+const TCA_FQ_FLOW_PLIMIT: u16 = 2;
+const TCA_FQ_QUANTUM: u16 = 3;
+const TCA_FQ_INITIAL_QUANTUM: u16 = 4;
+const TCA_FQ_RATE_ENABLE: u16 = 5;
+// const TCA_FQ_FLOW_DEFAULT_RATE: u16 = 6; // obsolete
+const TCA_FQ_FLOW_MAX_RATE: u16 = 7;
+const TCA_FQ_BUCKETS_LOG: u16 = 8;
+const TCA_FQ_FLOW_REFILL_DELAY: u16 = 9;
+const TCA_FQ_ORPHAN_MASK: u16 = 10;
+const TCA_FQ_LOW_RATE_THRESHOLD: u16 = 11;
+const TCA_FQ_CE_THRESHOLD: u16 = 12;
+const TCA_FQ_TIMER_SLACK: u16 = 13;
+const TCA_FQ_HORIZON: u16 = 14;
+const TCA_FQ_HORIZON_DROP: u16 = 15;
+const TCA_FQ_PRIOMAP: u16 = 16;
+const TCA_FQ_WEIGHTS: u16 = 17;
+
+#[derive(Debug, PartialEq, Eq, Clone)]
+#[non_exhaustive]
+pub enum TcQdiscFqOption {
+    /// Limit of total number of packets in queue
+    Plimit(u32),
+    /// Limit of packets per flow
+    FlowPlimit(u32),
+    /// Round robin quantum in bytes
+    Quantum(u32),
+    /// Round robin quantum in bytes for new flow
+    InitialQuantum(u32),
+    RateEnable(u32),
+    /// Per flow max rate in bytes per second
+    FlowMaxRate(u32),
+    /// Log2 of number of buckets
+    BucketsLog(u32),
+    /// Flow credit refill delay in microseconds
+    FlowRefillDelay(u32),
+    /// Mask applied to orphaned skb hashes
+    OrphanMask(u32),
+    /// Per packet delay under this rate in bytes per second
+    LowRateThreshold(u32),
+    /// DCTCP-like CE-marking threshold in microseconds
+    CeThreshold(u32),
+    /// Timer slack in nanoseconds
+    TimerSlack(u32),
+    /// Time horizon in microseconds
+    Horizon(u32),
+    /// Drop packets beyond horizon, or cap their EDT
+    HorizonDrop(u8),
+    /// Map from priority to band, the `bands` should be 3.
+    Priomap(TcPrioQopt),
+    /// Weights of each band
+    Weights(Vec<i32>),
+    Other(DefaultNla),
+}
+
+impl Nla for TcQdiscFqOption {
+    fn value_len(&self) -> usize {
+        match self {
+            Self::Plimit(_)
+            | Self::FlowPlimit(_)
+            | Self::Quantum(_)
+            | Self::InitialQuantum(_)
+            | Self::RateEnable(_)
+            | Self::FlowMaxRate(_)
+            | Self::BucketsLog(_)
+            | Self::FlowRefillDelay(_)
+            | Self::OrphanMask(_)
+            | Self::LowRateThreshold(_)
+            | Self::CeThreshold(_)
+            | Self::TimerSlack(_)
+            | Self::Horizon(_) => 4,
+            Self::HorizonDrop(_) => 1,
+            Self::Priomap(v) => v.buffer_len(),
+            Self::Weights(v) => v.len() * 4,
+            Self::Other(attr) => attr.value_len(),
+        }
+    }
+
+    fn emit_value(&self, buffer: &mut [u8]) {
+        match self {
+            Self::Plimit(d)
+            | Self::FlowPlimit(d)
+            | Self::Quantum(d)
+            | Self::InitialQuantum(d)
+            | Self::RateEnable(d)
+            | Self::FlowMaxRate(d)
+            | Self::BucketsLog(d)
+            | Self::FlowRefillDelay(d)
+            | Self::OrphanMask(d)
+            | Self::LowRateThreshold(d)
+            | Self::CeThreshold(d)
+            | Self::TimerSlack(d)
+            | Self::Horizon(d) => NativeEndian::write_u32(buffer, *d),
+            Self::HorizonDrop(d) => buffer[0] = *d,
+            Self::Priomap(v) => v.emit(buffer),
+            Self::Weights(v) => {
+                for (d, chunk) in v.iter().zip(buffer.chunks_exact_mut(4)) {
+                    NativeEndian::write_i32(chunk, *d);
+                }
+            }
+            Self::Other(attr) => attr.emit_value(buffer),
+        }
+    }
+
+    fn kind(&self) -> u16 {
+        match self {
+            Self::Plimit(_) => TCA_FQ_PLIMIT,
+            Self::FlowPlimit(_) => TCA_FQ_FLOW_PLIMIT,
+            Self::Quantum(_) => TCA_FQ_QUANTUM,
+            Self::InitialQuantum(_) => TCA_FQ_INITIAL_QUANTUM,
+            Self::RateEnable(_) => TCA_FQ_RATE_ENABLE,
+            Self::FlowMaxRate(_) => TCA_FQ_FLOW_MAX_RATE,
+            Self::BucketsLog(_) => TCA_FQ_BUCKETS_LOG,
+            Self::FlowRefillDelay(_) => TCA_FQ_FLOW_REFILL_DELAY,
+            Self::OrphanMask(_) => TCA_FQ_ORPHAN_MASK,
+            Self::LowRateThreshold(_) => TCA_FQ_LOW_RATE_THRESHOLD,
+            Self::CeThreshold(_) => TCA_FQ_CE_THRESHOLD,
+            Self::TimerSlack(_) => TCA_FQ_TIMER_SLACK,
+            Self::Horizon(_) => TCA_FQ_HORIZON,
+            Self::HorizonDrop(_) => TCA_FQ_HORIZON_DROP,
+            Self::Priomap(_) => TCA_FQ_PRIOMAP,
+            Self::Weights(_) => TCA_FQ_WEIGHTS,
+            Self::Other(attr) => attr.kind(),
+        }
+    }
+}
+
+impl<'a, T: AsRef<[u8]> + ?Sized> Parseable<NlaBuffer<&'a T>>
+    for TcQdiscFqOption
+{
+    fn parse(buf: &NlaBuffer<&'a T>) -> Result<Self, DecodeError> {
+        let payload = buf.value();
+        Ok(match buf.kind() {
+            TCA_FQ_PLIMIT => Self::Plimit(
+                parse_u32(payload).context("failed to parse TCA_FQ_PLIMIT")?,
+            ),
+            TCA_FQ_FLOW_PLIMIT => Self::FlowPlimit(
+                parse_u32(payload)
+                    .context("failed to parse TCA_FQ_FLOW_PLIMIT")?,
+            ),
+            TCA_FQ_QUANTUM => Self::Quantum(
+                parse_u32(payload).context("failed to parse TCA_FQ_QUANTUM")?,
+            ),
+            TCA_FQ_INITIAL_QUANTUM => Self::InitialQuantum(
+                parse_u32(payload)
+                    .context("failed to parse TCA_FQ_INITIAL_QUANTUM")?,
+            ),
+            TCA_FQ_RATE_ENABLE => Self::RateEnable(
+                parse_u32(payload)
+                    .context("failed to parse TCA_FQ_RATE_ENABLE")?,
+            ),
+            TCA_FQ_FLOW_MAX_RATE => Self::FlowMaxRate(
+                parse_u32(payload)
+                    .context("failed to parse TCA_FQ_FLOW_MAX_RATE")?,
+            ),
+            TCA_FQ_BUCKETS_LOG => Self::BucketsLog(
+                parse_u32(payload)
+                    .context("failed to parse TCA_FQ_BUCKETS_LOG")?,
+            ),
+            TCA_FQ_FLOW_REFILL_DELAY => Self::FlowRefillDelay(
+                parse_u32(payload)
+                    .context("failed to parse TCA_FQ_FLOW_REFILL_DELAY")?,
+            ),
+            TCA_FQ_ORPHAN_MASK => Self::OrphanMask(
+                parse_u32(payload)
+                    .context("failed to parse TCA_FQ_ORPHAN_MASK")?,
+            ),
+            TCA_FQ_LOW_RATE_THRESHOLD => Self::LowRateThreshold(
+                parse_u32(payload)
+                    .context("failed to parse TCA_FQ_LOW_RATE_THRESHOLD")?,
+            ),
+            TCA_FQ_CE_THRESHOLD => Self::CeThreshold(
+                parse_u32(payload)
+                    .context("failed to parse TCA_FQ_CE_THRESHOLD")?,
+            ),
+            TCA_FQ_TIMER_SLACK => Self::TimerSlack(
+                parse_u32(payload)
+                    .context("failed to parse TCA_FQ_TIMER_SLACK")?,
+            ),
+            TCA_FQ_HORIZON => Self::Horizon(
+                parse_u32(payload).context("failed to parse TCA_FQ_HORIZON")?,
+            ),
+            TCA_FQ_HORIZON_DROP => Self::HorizonDrop(
+                parse_u8(payload)
+                    .context("failed to parse TCA_FQ_HORIZON_DROP")?,
+            ),
+            TCA_FQ_PRIOMAP => Self::Priomap(
+                TcPrioQopt::parse(
+                    &TcPrioQoptBuffer::new_checked(payload)
+                        .context("invalid TCA_FQ_PRIOMAP")?,
+                )
+                .context("failed to parse TCA_FQ_PRIOMAP")?,
+            ),
+            TCA_FQ_WEIGHTS => Self::Weights(
+                payload
+                    .chunks_exact(4)
+                    .map(NativeEndian::read_i32)
+                    .collect(),
+            ),
+            _ => Self::Other(
+                DefaultNla::parse(buf).context("failed to parse fq nla")?,
+            ),
+        })
+    }
+}
+
+const FQ_BANDS: usize = 3;
+const TC_FQ_QD_STATS_BUF_LEN: usize = 152;
+
+// kernel struct `tc_fq_qd_stats`
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Default)]
+#[non_exhaustive]
+pub struct TcFqQdStats {
+    pub gc_flows: u64,
+    /// Obsolete
+    pub highprio_packets: u64,
+    /// Obsolete
+    pub tcp_retrans: u64,
+    pub throttled: u64,
+    pub flows_plimit: u64,
+    pub pkts_too_long: u64,
+    pub allocation_errors: u64,
+    pub time_next_delayed_flow: i64,
+    pub flows: u32,
+    pub inactive_flows: u32,
+    pub throttled_flows: u32,
+    pub unthrottle_latency_ns: u32,
+    /// Packets above `ce_threshold`
+    pub ce_mark: u64,
+    pub horizon_drops: u64,
+    pub horizon_caps: u64,
+    pub fastpath_packets: u64,
+    pub band_drops: [u64; FQ_BANDS],
+    pub band_pkt_count: [u32; FQ_BANDS],
+}
+
+buffer!(TcFqQdStatsBuffer(TC_FQ_QD_STATS_BUF_LEN) {
+    gc_flows: (u64, 0..8),
+    highprio_packets: (u64, 8..16),
+    tcp_retrans: (u64, 16..24),
+    throttled: (u64, 24..32),
+    flows_plimit: (u64, 32..40),
+    pkts_too_long: (u64, 40..48),
+    allocation_errors: (u64, 48..56),
+    time_next_delayed_flow: (i64, 56..64),
+    flows: (u32, 64..68),
+    inactive_flows: (u32, 68..72),
+    throttled_flows: (u32, 72..76),
+    unthrottle_latency_ns: (u32, 76..80),
+    ce_mark: (u64, 80..88),
+    horizon_drops: (u64, 88..96),
+    horizon_caps: (u64, 96..104),
+    fastpath_packets: (u64, 104..112),
+    band_drops: (slice, 112..136),
+    band_pkt_count: (slice, 136..148),
+    pad: (u32, 148..TC_FQ_QD_STATS_BUF_LEN),
+});
+
+impl TcFqQdStats {
+    // Kernel before 6.7 does not have `fastpath_packets`, `band_drops` and
+    // `band_pkt_count`, and kernel before 5.7 does not have
+    // `horizon_drops` and `horizon_caps`. Treat missing counters as 0.
+    pub(crate) fn parse_with_padding(
+        payload: &[u8],
+    ) -> Result<Self, DecodeError> {
+        let mut buf = [0u8; TC_FQ_QD_STATS_BUF_LEN];
+        let len = payload.len().min(TC_FQ_QD_STATS_BUF_LEN);
+        buf[..len].copy_from_slice(&payload[..len]);
+        Self::parse(&TcFqQdStatsBuffer::new(&buf))
+    }
+}
+
+impl Emitable for TcFqQdStats {
+    fn buffer_len(&self) -> usize {
+        TC_FQ_QD_STATS_BUF_LEN
+    }
+
+    fn emit(&self, buffer: &mut [u8]) {
+        let mut packet = TcFqQdStatsBuffer::new(buffer);
+        packet.set_gc_flows(self.gc_flows);
+        packet.set_highprio_packets(self.highprio_packets);
+        packet.set_tcp_retrans(self.tcp_retrans);
+        packet.set_throttled(self.throttled);
+        packet.set_flows_plimit(self.flows_plimit);
+        packet.set_pkts_too_long(self.pkts_too_long);
+        packet.set_allocation_errors(self.allocation_errors);
+        packet.set_time_next_delayed_flow(self.time_next_delayed_flow);
+        packet.set_flows(self.flows);
+        packet.set_inactive_flows(self.inactive_flows);
+        packet.set_throttled_flows(self.throttled_flows);
+        packet.set_unthrottle_latency_ns(self.unthrottle_latency_ns);
+        packet.set_ce_mark(self.ce_mark);
+        packet.set_horizon_drops(self.horizon_drops);
+        packet.set_horizon_caps(self.horizon_caps);
+        packet.set_fastpath_packets(self.fastpath_packets);
+        for (d, chunk) in self
+            .band_drops
+            .iter()
+            .zip(packet.band_drops_mut().chunks_exact_mut(8))
+        {
+            NativeEndian::write_u64(chunk, *d);
+        }
+        for (d, chunk) in self
+            .band_pkt_count
+            .iter()
+            .zip(packet.band_pkt_count_mut().chunks_exact_mut(4))
+        {
+            NativeEndian::write_u32(chunk, *d);
+        }
+        packet.set_pad(0);
+    }
+}
+
+impl<T: AsRef<[u8]> + ?Sized> Parseable<TcFqQdStatsBuffer<&T>> for TcFqQdStats {
+    fn parse(buf: &TcFqQdStatsBuffer<&T>) -> Result<Self, DecodeError> {
+        let mut band_drops = [0u64; FQ_BANDS];
+        for (d, chunk) in
+            band_drops.iter_mut().zip(buf.band_drops().chunks_exact(8))
+        {
+            *d = NativeEndian::read_u64(chunk);
+        }
+        let mut band_pkt_count = [0u32; FQ_BANDS];
+        for (d, chunk) in band_pkt_count
+            .iter_mut()
+            .zip(buf.band_pkt_count().chunks_exact(4))
+        {
+            *d = NativeEndian::read_u32(chunk);
+        }
+        Ok(Self {
+            gc_flows: buf.gc_flows(),
+            highprio_packets: buf.highprio_packets(),
+            tcp_retrans: buf.tcp_retrans(),
+            throttled: buf.throttled(),
+            flows_plimit: buf.flows_plimit(),
+            pkts_too_long: buf.pkts_too_long(),
+            allocation_errors: buf.allocation_errors(),
+            time_next_delayed_flow: buf.time_next_delayed_flow(),
+            flows: buf.flows(),
+            inactive_flows: buf.inactive_flows(),
+            throttled_flows: buf.throttled_flows(),
+            unthrottle_latency_ns: buf.unthrottle_latency_ns(),
+            ce_mark: buf.ce_mark(),
+            horizon_drops: buf.horizon_drops(),
+            horizon_caps: buf.horizon_caps(),
+            fastpath_packets: buf.fastpath_packets(),
+            band_drops,
+            band_pkt_count,
+        })
+    }
+}
@@ -0,0 +1,500 @@
+// SPDX-License-Identifier: MIT
+
+/// Generalized Random Early Detection
+///
+/// The `gred` qdisc holds up to 16 virtual queues (DP) each running its own
+/// RED instance, the virtual queue is selected by `tc_index` of packet.
+/// Kernel provides no xstats for `gred`, the statistics of each virtual
+/// queue are included in `TCA_GRED_PARMS` and `TCA_GRED_VQ_LIST` instead.
+use anyhow::Context;
+use byteorder::{ByteOrder, NativeEndian};
+use netlink_packet_utils::{
+    nla::{DefaultNla, Nla, NlaBuffer, NlasIterator},
+    parsers::{parse_u32, parse_u64},
+    traits::{Emitable, Parseable},
+    DecodeError,
+};
+
+use crate::tc::{qdiscs::red::VecTcRedFlag, TcRedFlag};
+
+#[derive(Debug, PartialEq, Eq, Clone)]
+#[non_exhaustive]
+pub struct TcQdiscGred {}
+
+impl TcQdiscGred {
+    pub(crate) const KIND: &'static str = "gred";
+}
+
+const TCA_GRED_PARMS: u16 = 1;
+const TCA_GRED_STAB: u16 = 2;
+const TCA_GRED_DPS: u16 = 3;
+const TCA_GRED_MAX_P: u16 = 4;
+const TCA_GRED_LIMIT: u16 = 5;
+const TCA_GRED_VQ_LIST: u16 = 6;
+
+#[derive(Debug, PartialEq, Eq, Clone)]
+#[non_exhaustive]
+pub enum TcQdiscGredOption {
+    /// Single virtual queue to change when sending to kernel. Kernel
+    /// replies with all 16 virtual queues, the `dp` of unused ones is set
+    /// to 16 plus their index.
+    Parms(Vec<TcGredQopt>),
+    /// Idle damping lookup table of 256 cells, could be generated by
+    /// `TcPsched::calc_red_stab()`
+    Stab(Vec<u8>),
+    /// Setup of the virtual queues table
+    Dps(TcGredSopt),
+    /// Maximum drop probability in fixed point of 32 bits fraction.
+    /// Single value when sending to kernel, one for each of the 16 virtual
+    /// queues in kernel reply.
+    MaxP(Vec<u32>),
+    /// Hard limit of the whole qdisc in bytes
+    Limit(u32),
+    /// Nested without NLA_F_NESTED flag by both kernel and iproute2
+    VqList(Vec<Vec<TcGredVqEntry>>),
+    Other(DefaultNla),
+}
+
+impl Nla for TcQdiscGredOption {
+    fn value_len(&self) -> usize {
+        match self {
+            Self::Parms(v) => v.len() * TC_GRED_QOPT_BUF_LEN,
+            Self::Stab(v) => v.len(),
+            Self::Dps(v) => v.buffer_len(),
+            Self::MaxP(v) => v.len() * 4,
+            Self::Limit(_) => 4,
+            Self::VqList(v) => gred_vq_entries(v).as_slice().buffer_len(),
+            Self::Other(attr) => attr.value_len(),
+        }
+    }
+
+    fn emit_value(&self, buffer: &mut [u8]) {
+        match self {
+            Self::Parms(v) => {
+                for (qopt, chunk) in
+                    v.iter().zip(buffer.chunks_exact_mut(TC_GRED_QOPT_BUF_LEN))
+                {
+                    qopt.emit(chunk);
+                }
+            }
+            Self::Stab(v) => buffer.copy_from_slice(v.as_slice()),
+            Self::Dps(v) => v.emit(buffer),
+            Self::MaxP(v) => {
+                for (d, chunk) in v.iter().zip(buffer.chunks_exact_mut(4)) {
+                    NativeEndian::write_u32(chunk, *d);
+                }
+            }
+            Self::Limit(d) => NativeEndian::write_u32(buffer, *d),
+            Self::VqList(v) => gred_vq_entries(v).as_slice().emit(buffer),
+            Self::Other(attr) => attr.emit_value(buffer),
+        }
+    }
+
+    fn kind(&self) -> u16 {
+        match self {
+            Self::Parms(_) => TCA_GRED_PARMS,
+            Self::Stab(_) => TCA_GRED_STAB,
+            Self::Dps(_) => TCA_GRED_DPS,
+            Self::MaxP(_) => TCA_GRED_MAX_P,
+            Self::Limit(_) => TCA_GRED_LIMIT,
+            Self::VqList(_) => TCA_GRED_VQ_LIST,
+            Self::Other(attr) => attr.kind(),
+        }
+    }
+}
+
+impl<'a, T: AsRef<[u8]> + ?Sized> Parseable<NlaBuffer<&'a T>>
+    for TcQdiscGredOption
+{
+    fn parse(buf: &NlaBuffer<&'a T>) -> Result<Self, DecodeError> {
+        let payload = buf.value();
+        Ok(match buf.kind() {
+            TCA_GRED_PARMS => {
+                if !payload.len().is_multiple_of(TC_GRED_QOPT_BUF_LEN) {
+                    return Err(DecodeError::from(format!(
+                        "invalid TCA_GRED_PARMS length {}",
+                        payload.len()
+                    )));
+                }
+                let mut qopts = vec![];
+                for chunk in payload.chunks_exact(TC_GRED_QOPT_BUF_LEN) {
+                    qopts.push(
+                        TcGredQopt::parse(&TcGredQoptBuffer::new(chunk))
+                            .context("failed to parse TCA_GRED_PARMS")?,
+                    );
+                }
+                Self::Parms(qopts)
+            }
+            TCA_GRED_STAB => Self::Stab(payload.to_vec()),
+            TCA_GRED_DPS => Self::Dps(
+                TcGredSopt::parse(
+                    &TcGredSoptBuffer::new_checked(payload)
+                        .context("invalid TCA_GRED_DPS")?,
+                )
+                .context("failed to parse TCA_GRED_DPS")?,
+            ),
+            TCA_GRED_MAX_P => {
+                let mut max_p = vec![];
+                for chunk in payload.chunks(4) {
+                    max_p.push(
+                        parse_u32(chunk)
+                            .context("failed to parse TCA_GRED_MAX_P")?,
+                    );
+                }
+                Self::MaxP(max_p)
+            }
+            TCA_GRED_LIMIT => Self::Limit(
+                parse_u32(payload).context("failed to parse TCA_GRED_LIMIT")?,
+            ),
+            TCA_GRED_VQ_LIST => {
+                let mut entries = vec![];
+                for entry in NlasIterator::new(payload) {
+                    let entry = entry.context("invalid TCA_GRED_VQ_LIST")?;
+                    let mut nlas = vec![];
+                    for nla in NlasIterator::new(entry.value()) {
+                        let nla = nla.context("invalid TCA_GRED_VQ_ENTRY")?;
+                        nlas.push(
+                            TcGredVqEntry::parse(&nla)
+                                .context("failed to parse TCA_GRED_VQ_ENTRY")?,
+                        );
+                    }
+                    entries.push(nlas);
+                }
+                Self::VqList(entries)
+            }
+            _ => Self::Other(
+                DefaultNla::parse(buf).context("failed to parse gred nla")?,
+            ),
+        })
+    }
+}
+
+const TCA_GRED_VQ_ENTRY: u16 = 1;
+
+// Each virtual queue is stored in a TCA_GRED_VQ_ENTRY.
+struct GredVqEntry<'a>(&'a [TcGredVqEntry]);
+
+fn gred_vq_entries(entries: &[Vec<TcGredVqEntry>]) -> Vec<GredVqEntry<'_>> {
+    entries
+        .iter()
+        .map(|entry| GredVqEntry(entry.as_slice()))
+        .collect()
+}
+
+impl Nla for GredVqEntry<'_> {
+    fn value_len(&self) -> usize {
+        self.0.buffer_len()
+    }
+
+    fn emit_value(&self, buffer: &mut [u8]) {
+        self.0.emit(buffer)
+    }
+
+    fn kind(&self) -> u16 {
+        TCA_GRED_VQ_ENTRY
+    }
+}
+
+// const TCA_GRED_VQ_PAD: u16 = 1;
+const TCA_GRED_VQ_DP: u16 = 2;
+const TCA_GRED_VQ_STAT_BYTES: u16 = 3;
+const TCA_GRED_VQ_STAT_PACKETS: u16 = 4;
+const TCA_GRED_VQ_STAT_BACKLOG: u16 = 5;
+const TCA_GRED_VQ_STAT_PROB_DROP: u16 = 6;
+const TCA_GRED_VQ_STAT_PROB_MARK: u16 = 7;
+const TCA_GRED_VQ_STAT_FORCED_DROP: u16 = 8;
+const TCA_GRED_VQ_STAT_FORCED_MARK: u16 = 9;
+const TCA_GRED_VQ_STAT_PDROP: u16 = 10;
+const TCA_GRED_VQ_STAT_OTHER: u16 = 11;
+const TCA_GRED_VQ_FLAGS: u16 = 12;
+
+#[derive(Debug, PartialEq, Eq, Clone)]
+#[non_exhaustive]
+pub enum TcGredVqEntry {
+    /// Index of the virtual queue
+    Dp(u32),
+    StatBytes(u64),
+    StatPackets(u32),
+    StatBacklog(u32),
+    StatProbDrop(u32),
+    StatProbMark(u32),
+    StatForcedDrop(u32),
+    StatForcedMark(u32),
+    StatPdrop(u32),
+    StatOther(u32),
+    /// Per virtual queue RED flags, only `Ecn`, `HardDrop` and `NoDrop`
+    /// are supported by kernel
+    Flags(Vec<TcRedFlag>),
+    Other(DefaultNla),
+}
+
+impl Nla for TcGredVqEntry {
+    fn value_len(&self) -> usize {
+        match self {
+            Self::StatBytes(_) => 8,
+            Self::Dp(_)
+            | Self::StatPackets(_)
+            | Self::StatBacklog(_)
+            | Self::StatProbDrop(_)
+            | Self::StatProbMark(_)
+            | Self::StatForcedDrop(_)
+            | Self::StatForcedMark(_)
+            | Self::StatPdrop(_)
+            | Self::StatOther(_)
+            | Self::Flags(_) => 4,
+            Self::Other(attr) => attr.value_len(),
+        }
+    }
+
+    fn emit_value(&self, buffer: &mut [u8]) {
+        match self {
+            Self::StatBytes(d) => NativeEndian::write_u64(buffer, *d),
+            Self::Dp(d)
+            | Self::StatPackets(d)
+            | Self::StatBacklog(d)
+            | Self::StatProbDrop(d)
+            | Self::StatProbMark(d)
+            | Self::StatForcedDrop(d)
+            | Self::StatForcedMark(d)
+            | Self::StatPdrop(d)
+            | Self::StatOther(d) => NativeEndian::write_u32(buffer, *d),
+            Self::Flags(v) => NativeEndian::write_u32(
+                buffer,
+                u32::from(&VecTcRedFlag(v.to_vec())),
+            ),
+            Self::Other(attr) => attr.emit_value(buffer),
+        }
+    }
+
+    fn kind(&self) -> u16 {
+        match self {
+            Self::Dp(_) => TCA_GRED_VQ_DP,
+            Self::StatBytes(_) => TCA_GRED_VQ_STAT_BYTES,
+            Self::StatPackets(_) => TCA_GRED_VQ_STAT_PACKETS,
+            Self::StatBacklog(_) => TCA_GRED_VQ_STAT_BACKLOG,
+            Self::StatProbDrop(_) => TCA_GRED_VQ_STAT_PROB_DROP,
+            Self::StatProbMark(_) => TCA_GRED_VQ_STAT_PROB_MARK,
+            Self::StatForcedDrop(_) => TCA_GRED_VQ_STAT_FORCED_DROP,
+            Self::StatForcedMark(_) => TCA_GRED_VQ_STAT_FORCED_MARK,
+            Self::StatPdrop(_) => TCA_GRED_VQ_STAT_PDROP,
+            Self::StatOther(_) => TCA_GRED_VQ_STAT_OTHER,
+            Self::Flags(_) => TCA_GRED_VQ_FLAGS,
+            Self::Other(attr) => attr.kind(),
+        }
+    }
+}
+
+impl<'a, T: AsRef<[u8]> + ?Sized> Parseable<NlaBuffer<&'a T>>
+    for TcGredVqEntry
+{
+    fn parse(buf: &NlaBuffer<&'a T>) -> Result<Self, DecodeError> {
+        let payload = buf.value();
+        Ok(match buf.kind() {
+            TCA_GRED_VQ_DP => Self::Dp(
+                parse_u32(payload).context("failed to parse TCA_GRED_VQ_DP")?,
+            ),
+            TCA_GRED_VQ_STAT_BYTES => Self::StatBytes(
+                parse_u64(payload)
+                    .context("failed to parse TCA_GRED_VQ_STAT_BYTES")?,
+            ),
+            TCA_GRED_VQ_STAT_PACKETS => Self::StatPackets(
+                parse_u32(payload)
+                    .context("failed to parse TCA_GRED_VQ_STAT_PACKETS")?,
+            ),
+            TCA_GRED_VQ_STAT_BACKLOG => Self::StatBacklog(
+                parse_u32(payload)
+                    .context("failed to parse TCA_GRED_VQ_STAT_BACKLOG")?,
+            ),
+            TCA_GRED_VQ_STAT_PROB_DROP => Self::StatProbDrop(
+                parse_u32(payload)
+                    .context("failed to parse TCA_GRED_VQ_STAT_PROB_DROP")?,
+            ),
+            TCA_GRED_VQ_STAT_PROB_MARK => Self::StatProbMark(
+                parse_u32(payload)
+                    .context("failed to parse TCA_GRED_VQ_STAT_PROB_MARK")?,
+            ),
+            TCA_GRED_VQ_STAT_FORCED_DROP => Self::StatForcedDrop(
+                parse_u32(payload)
+                    .context("failed to parse TCA_GRED_VQ_STAT_FORCED_DROP")?,
+            ),
+            TCA_GRED_VQ_STAT_FORCED_MARK => Self::StatForcedMark(
+                parse_u32(payload)
+                    .context("failed to parse TCA_GRED_VQ_STAT_FORCED_MARK")?,
+            ),
+            TCA_GRED_VQ_STAT_PDROP => Self::StatPdrop(
+                parse_u32(payload)
+                    .context("failed to parse TCA_GRED_VQ_STAT_PDROP")?,
+            ),
+            TCA_GRED_VQ_STAT_OTHER => Self::StatOther(
+                parse_u32(payload)
+                    .context("failed to parse TCA_GRED_VQ_STAT_OTHER")?,
+            ),
+            TCA_GRED_VQ_FLAGS => Self::Flags(
+                VecTcRedFlag::from(
+                    parse_u32(payload)
+                        .context("failed to parse TCA_GRED_VQ_FLAGS")?,
+                )
+                .0,
+            ),
+            _ => Self::Other(
+                DefaultNla::parse(buf)
+                    .context("failed to parse gred vq entry nla")?,
+            ),
+        })
+    }
+}
+
+const TC_GRED_QOPT_BUF_LEN: usize = 52;
+
+// kernel struct `tc_gred_qopt`
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Default)]
+#[non_exhaustive]
+pub struct TcGredQopt {
+    /// Hard limit on queue length in bytes
+    pub limit: u32,
+    /// Min average length threshold, scaled by `wlog`
+    pub qth_min: u32,
+    /// Max average length threshold, scaled by `wlog`
+    pub qth_max: u32,
+    /// Index of the virtual queue
+    pub dp: u32,
+    pub backlog: u32,
+    pub qave: u32,
+    pub forced: u32,
+    pub early: u32,
+    pub other: u32,
+    pub pdrop: u32,
+    /// log(W)
+    pub wlog: u8,
+    /// log(P_max/(qth_max-qth_min))
+    pub plog: u8,
+    /// Cell size for idle damping
+    pub scell_log: u8,
+    /// Priority of this virtual queue in `grio` mode
+    pub prio: u8,
+    pub packets: u32,
+    pub bytesin: u32,
+}
+
+buffer!(TcGredQoptBuffer(TC_GRED_QOPT_BUF_LEN) {
+    limit: (u32, 0..4),
+    qth_min: (u32, 4..8),
+    qth_max: (u32, 8..12),
+    dp: (u32, 12..16),
+    backlog: (u32, 16..20),
+    qave: (u32, 20..24),
+    forced: (u32, 24..28),
+    early: (u32, 28..32),
+    other: (u32, 32..36),
+    pdrop: (u32, 36..40),
+    wlog: (u8, 40),
+    plog: (u8, 41),
+    scell_log: (u8, 42),
+    prio: (u8, 43),
+    packets: (u32, 44..48),
+    bytesin: (u32, 48..TC_GRED_QOPT_BUF_LEN),
+});
+
+impl Emitable for TcGredQopt {
+    fn buffer_len(&self) -> usize {
+        TC_GRED_QOPT_BUF_LEN
+    }
+
+    fn emit(&self, buffer: &mut [u8]) {
+        let mut packet = TcGredQoptBuffer::new(buffer);
+        packet.set_limit(self.limit);
+        packet.set_qth_min(self.qth_min);
+        packet.set_qth_max(self.qth_max);
+        packet.set_dp(self.dp);
+        packet.set_backlog(self.backlog);
+        packet.set_qave(self.qave);
+        packet.set_forced(self.forced);
+        packet.set_early(self.early);
+        packet.set_other(self.other);
+        packet.set_pdrop(self.pdrop);
+        packet.set_wlog(self.wlog);
+        packet.set_plog(self.plog);
+        packet.set_scell_log(self.scell_log);
+        packet.set_prio(self.prio);
+        packet.set_packets(self.packets);
+        packet.set_bytesin(self.bytesin);
+    }
+}
+
+impl<T: AsRef<[u8]>> Parseable<TcGredQoptBuffer<T>> for TcGredQopt {
+    fn parse(buf: &TcGredQoptBuffer<T>) -> Result<Self, DecodeError> {
+        Ok(Self {
+            limit: buf.limit(),
+            qth_min: buf.qth_min(),
+            qth_max: buf.qth_max(),
+            dp: buf.dp(),
+            backlog: buf.backlog(),
+            qave: buf.qave(),
+            forced: buf.forced(),
+            early: buf.early(),
+            other: buf.other(),
+            pdrop: buf.pdrop(),
+            wlog: buf.wlog(),
+            plog: buf.plog(),
+            scell_log: buf.scell_log(),
+            prio: buf.prio(),
+            packets: buf.packets(),
+            bytesin: buf.bytesin(),
+        })
+    }
+}
+
+const TC_GRED_SOPT_BUF_LEN: usize = 12;
+
+// kernel struct `tc_gred_sopt`
+#[derive(Debug, PartialEq, Eq, Clone, Default)]
+#[non_exhaustive]
+pub struct TcGredSopt {
+    /// Number of virtual queues
+    pub dps: u32,
+    /// Default virtual queue for packets with invalid `tc_index`
+    pub def_dp: u32,
+    /// Whether virtual queues are sharing the average queue length by
+    /// priority (RIO-like mode)
+    pub grio: u8,
+    /// RED flags applying to all virtual queues
+    pub flags: Vec<TcRedFlag>,
+}
+
+buffer!(TcGredSoptBuffer(TC_GRED_SOPT_BUF_LEN) {
+    dps: (u32, 0..4),
+    def_dp: (u32, 4..8),
+    grio: (u8, 8),
+    flags: (u8, 9),
+    pad: (u16, 10..TC_GRED_SOPT_BUF_LEN),
+});
+
+impl Emitable for TcGredSopt {
+    fn buffer_len(&self) -> usize {
+        TC_GRED_SOPT_BUF_LEN
+    }
+
+    fn emit(&self, buffer: &mut [u8]) {
+        let mut packet = TcGredSoptBuffer::new(buffer);
+        packet.set_dps(self.dps);
+        packet.set_def_dp(self.def_dp);
+        packet.set_grio(self.grio);
+        // Only the historic flags fit into `tc_gred_sopt`
+        packet.set_flags(
+            (u32::from(&VecTcRedFlag(self.flags.to_vec())) & u32::from(u8::MAX))
+                as u8,
+        );
+        packet.set_pad(0);
+    }
+}
+
+impl<T: AsRef<[u8]>> Parseable<TcGredSoptBuffer<T>> for TcGredSopt {
+    fn parse(buf: &TcGredSoptBuffer<T>) -> Result<Self, DecodeError> {
+        Ok(Self {
+            dps: buf.dps(),
+            def_dp: buf.def_dp(),
+            grio: buf.grio(),
+            flags: VecTcRedFlag::from(u32::from(buf.flags())).0,
+        })
+    }
+}
@@ -0,0 +1,259 @@
+// SPDX-License-Identifier: MIT
+
+/// Hierarchical Fair Service Curve
+///
+/// HFSC schedules a tree of classes by service curves, providing real-time
+/// guarantees through the real-time curve and sharing excess bandwidth by
+/// the link-sharing curve limited by the upper-limit curve.
+///
+/// The `hfsc` qdisc places the `tc_hfsc_qopt` struct as the whole
+/// TCA_OPTIONS without any NLA, while `hfsc` classes are using NLAs.
+use anyhow::Context;
+use netlink_packet_utils::{
+    nla::{DefaultNla, Nla, NlaBuffer, NlasIterator},
+    traits::{Emitable, Parseable},
+    DecodeError,
+};
+
+#[derive(Debug, PartialEq, Eq, Clone)]
+#[non_exhaustive]
+pub struct TcQdiscHfsc {}
+
+impl TcQdiscHfsc {
+    pub(crate) const KIND: &'static str = "hfsc";
+}
+
+const TCA_HFSC_RSC: u16 = 1;
+const TCA_HFSC_FSC: u16 = 2;
+const TCA_HFSC_USC: u16 = 3;
+
+#[derive(Debug, PartialEq, Eq, Clone)]
+#[non_exhaustive]
+pub enum TcQdiscHfscOption {
+    /// The `tc_hfsc_qopt` of qdisc without NLA header
+    Qopt(TcHfscQopt),
+    /// Real-time service curve of class
+    Rsc(TcServiceCurve),
+    /// Link-sharing (fair) service curve of class
+    Fsc(TcServiceCurve),
+    /// Upper-limit service curve of class
+    Usc(TcServiceCurve),
+    Other(DefaultNla),
+}
+
+impl Nla for TcQdiscHfscOption {
+    fn value_len(&self) -> usize {
+        match self {
+            Self::Qopt(v) => v.buffer_len(),
+            Self::Rsc(v) | Self::Fsc(v) | Self::Usc(v) => v.buffer_len(),
+            Self::Other(attr) => attr.value_len(),
+        }
+    }
+
+    fn emit_value(&self, buffer: &mut [u8]) {
+        match self {
+            Self::Qopt(v) => v.emit(buffer),
+            Self::Rsc(v) | Self::Fsc(v) | Self::Usc(v) => v.emit(buffer),
+            Self::Other(attr) => attr.emit_value(buffer),
+        }
+    }
+
+    fn kind(&self) -> u16 {
+        match self {
+            // Emitted without NLA header, see `TcQdiscHfscOption::Qopt`
+            Self::Qopt(_) => 0,
+            Self::Rsc(_) => TCA_HFSC_RSC,
+            Self::Fsc(_) => TCA_HFSC_FSC,
+            Self::Usc(_) => TCA_HFSC_USC,
+            Self::Other(attr) => attr.kind(),
+        }
+    }
+}
+
+impl<'a, T: AsRef<[u8]> + ?Sized> Parseable<NlaBuffer<&'a T>>
+    for TcQdiscHfscOption
+{
+    fn parse(buf: &NlaBuffer<&'a T>) -> Result<Self, DecodeError> {
+        let payload = buf.value();
+        Ok(match buf.kind() {
+            TCA_HFSC_RSC => Self::Rsc(
+                TcServiceCurve::parse(
+                    &TcServiceCurveBuffer::new_checked(payload)
+                        .context("invalid TCA_HFSC_RSC")?,
+                )
+                .context("failed to parse TCA_HFSC_RSC")?,
+            ),
+            TCA_HFSC_FSC => Self::Fsc(
+                TcServiceCurve::parse(
+                    &TcServiceCurveBuffer::new_checked(payload)
+                        .context("invalid TCA_HFSC_FSC")?,
+                )
+                .context("failed to parse TCA_HFSC_FSC")?,
+            ),
+            TCA_HFSC_USC => Self::Usc(
+                TcServiceCurve::parse(
+                    &TcServiceCurveBuffer::new_checked(payload)
+                        .context("invalid TCA_HFSC_USC")?,
+                )
+                .context("failed to parse TCA_HFSC_USC")?,
+            ),
+            _ => Self::Other(
+                DefaultNla::parse(buf).context("failed to parse hfsc nla")?,
+            ),
+        })
+    }
+}
+
+pub(crate) fn parse_hfsc_options(
+    payload: &[u8],
+) -> Result<Vec<TcQdiscHfscOption>, DecodeError> {
+    // Qdisc and class are sharing the same kind, only the 2 bytes
+    // `tc_hfsc_qopt` of qdisc is too small to hold a NLA.
+    // Classes without any service curve (e.g. the root class) are dumped
+    // with an empty TCA_OPTIONS.
+    if payload.is_empty() {
+        return Ok(vec![]);
+    }
+    if payload.len() == TC_HFSC_QOPT_BUF_LEN {
+        return Ok(vec![TcQdiscHfscOption::Qopt(
+            TcHfscQopt::parse(
+                &TcHfscQoptBuffer::new_checked(payload)
+                    .context("invalid tc_hfsc_qopt")?,
+            )
+            .context("failed to parse tc_hfsc_qopt")?,
+        )]);
+    }
+    let mut options = vec![];
+    for nla in NlasIterator::new(payload) {
+        let nla = nla.context("invalid hfsc TCA_OPTIONS")?;
+        options.push(
+            TcQdiscHfscOption::parse(&nla)
+                .context("failed to parse hfsc TCA_OPTIONS")?,
+        );
+    }
+    Ok(options)
+}
+
+const TC_HFSC_QOPT_BUF_LEN: usize = 2;
+
+// kernel struct `tc_hfsc_qopt`
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Default)]
+#[non_exhaustive]
+pub struct TcHfscQopt {
+    /// Minor number of the default class for unclassified packets
+    pub defcls: u16,
+}
+
+buffer!(TcHfscQoptBuffer(TC_HFSC_QOPT_BUF_LEN) {
+    defcls: (u16, 0..TC_HFSC_QOPT_BUF_LEN),
+});
+
+impl Emitable for TcHfscQopt {
+    fn buffer_len(&self) -> usize {
+        TC_HFSC_QOPT_BUF_LEN
+    }
+
+    fn emit(&self, buffer: &mut [u8]) {
+        let mut packet = TcHfscQoptBuffer::new(buffer);
+        packet.set_defcls(self.defcls);
+    }
+}
+
+impl<T: AsRef<[u8]>> Parseable<TcHfscQoptBuffer<T>> for TcHfscQopt {
+    fn parse(buf: &TcHfscQoptBuffer<T>) -> Result<Self, DecodeError> {
+        Ok(Self {
+            defcls: buf.defcls(),
+        })
+    }
+}
+
+const TC_SERVICE_CURVE_BUF_LEN: usize = 12;
+
+// kernel struct `tc_service_curve`
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Default)]
+#[non_exhaustive]
+pub struct TcServiceCurve {
+    /// Slope of the first segment in bytes per second
+    pub m1: u32,
+    /// X-projection of the first segment in microseconds
+    pub d: u32,
+    /// Slope of the second segment in bytes per second
+    pub m2: u32,
+}
+
+buffer!(TcServiceCurveBuffer(TC_SERVICE_CURVE_BUF_LEN) {
+    m1: (u32, 0..4),
+    d: (u32, 4..8),
+    m2: (u32, 8..TC_SERVICE_CURVE_BUF_LEN),
+});
+
+impl Emitable for TcServiceCurve {
+    fn buffer_len(&self) -> usize {
+        TC_SERVICE_CURVE_BUF_LEN
+    }
+
+    fn emit(&self, buffer: &mut [u8]) {
+        let mut packet = TcServiceCurveBuffer::new(buffer);
+        packet.set_m1(self.m1);
+        packet.set_d(self.d);
+        packet.set_m2(self.m2);
+    }
+}
+
+impl<T: AsRef<[u8]>> Parseable<TcServiceCurveBuffer<T>> for TcServiceCurve {
+    fn parse(buf: &TcServiceCurveBuffer<T>) -> Result<Self, DecodeError> {
+        Ok(Self {
+            m1: buf.m1(),
+            d: buf.d(),
+            m2: buf.m2(),
+        })
+    }
+}
+
+const TC_HFSC_STATS_BUF_LEN: usize = 24;
+
+// kernel struct `tc_hfsc_stats`
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Default)]
+#[non_exhaustive]
+pub struct TcHfscStats {
+    /// Total work done in bytes
+    pub work: u64,
+    /// Work done by real-time criteria in bytes
+    pub rtwork: u64,
+    /// Current period
+    pub period: u32,
+    /// Class level in hierarchy
+    pub level: u32,
+}
+
+buffer!(TcHfscStatsBuffer(TC_HFSC_STATS_BUF_LEN) {
+    work: (u64, 0..8),
+    rtwork: (u64, 8..16),
+    period: (u32, 16..20),
+    level: (u32, 20..TC_HFSC_STATS_BUF_LEN),
+});
+
+impl Emitable for TcHfscStats {
+    fn buffer_len(&self) -> usize {
+        TC_HFSC_STATS_BUF_LEN
+    }
+
+    fn emit(&self, buffer: &mut [u8]) {
+        let mut packet = TcHfscStatsBuffer::new(buffer);
+        packet.set_work(self.work);
+        packet.set_rtwork(self.rtwork);
+        packet.set_period(self.period);
+        packet.set_level(self.level);
+    }
+}
+
+impl<T: AsRef<[u8]>> Parseable<TcHfscStatsBuffer<T>> for TcHfscStats {
+    fn parse(buf: &TcHfscStatsBuffer<T>) -> Result<Self, DecodeError> {
+        Ok(Self {
+            work: buf.work(),
+            rtwork: buf.rtwork(),
+            period: buf.period(),
+            level: buf.level(),
+        })
+    }
+}
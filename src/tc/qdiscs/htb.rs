@@ -0,0 +1,300 @@
+// SPDX-License-Identifier: MIT
+
+/// Hierarchy Token Bucket
+///
+/// HTB shapes traffic with a tree of classes, each guaranteed its `rate`
+/// and allowed to borrow unused bandwidth from its parent up to `ceil`.
+use anyhow::Context;
+use byteorder::{ByteOrder, NativeEndian};
+use netlink_packet_utils::{
+    nla::{DefaultNla, Nla, NlaBuffer},
+    parsers::{parse_u32, parse_u64},
+    traits::{Emitable, Parseable},
+    DecodeError,
+};
+
+use crate::tc::{
+    ratespec::{emit_rate_table, parse_rate_table},
+    TcRateSpec, TcRateSpecBuffer,
+};
+
+#[derive(Debug, PartialEq, Eq, Clone)]
+#[non_exhaustive]
+pub struct TcQdiscHtb {}
+
+impl TcQdiscHtb {
+    pub(crate) const KIND: &'static str = "htb";
+}
+
+const TCA_HTB_PARMS: u16 = 1;
+const TCA_HTB_INIT: u16 = 2;
+const TCA_HTB_CTAB: u16 = 3;
+const TCA_HTB_RTAB: u16 = 4;
+const TCA_HTB_DIRECT_QLEN: u16 = 5;
+const TCA_HTB_RATE64: u16 = 6;
+const TCA_HTB_CEIL64: u16 = 7;
+// const TCA_HTB_PAD: u16 = 8;
+const TCA_HTB_OFFLOAD: u16 = 9;
+
+#[derive(Debug, PartialEq, Eq, Clone)]
+#[non_exhaustive]
+pub enum TcQdiscHtbOption {
+    /// Class parameters
+    Parms(TcHtbOpt),
+    /// Qdisc parameters
+    Init(TcHtbGlob),
+    /// Ceil rate table
+    Ctab(Vec<u32>),
+    /// Rate table
+    Rtab(Vec<u32>),
+    DirectQlen(u32),
+    /// Rate in bytes per second when it does not fit into
+    /// `TcRateSpec::rate`
+    Rate64(u64),
+    /// Ceil in bytes per second when it does not fit into
+    /// `TcRateSpec::rate`
+    Ceil64(u64),
+    Offload(bool),
+    Other(DefaultNla),
+}
+
+impl Nla for TcQdiscHtbOption {
+    fn value_len(&self) -> usize {
+        match self {
+            Self::Parms(v) => v.buffer_len(),
+            Self::Init(v) => v.buffer_len(),
+            Self::Ctab(v) | Self::Rtab(v) => v.len() * 4,
+            Self::DirectQlen(_) => 4,
+            Self::Rate64(_) | Self::Ceil64(_) => 8,
+            Self::Offload(_) => 0, // The existence of NLA means true
+            Self::Other(attr) => attr.value_len(),
+        }
+    }
+
+    fn emit_value(&self, buffer: &mut [u8]) {
+        match self {
+            Self::Parms(v) => v.emit(buffer),
+            Self::Init(v) => v.emit(buffer),
+            Self::Ctab(v) | Self::Rtab(v) => emit_rate_table(v, buffer),
+            Self::DirectQlen(d) => NativeEndian::write_u32(buffer, *d),
+            Self::Rate64(d) | Self::Ceil64(d) => {
+                NativeEndian::write_u64(buffer, *d)
+            }
+            Self::Offload(_) => (),
+            Self::Other(attr) => attr.emit_value(buffer),
+        }
+    }
+
+    fn kind(&self) -> u16 {
+        match self {
+            Self::Parms(_) => TCA_HTB_PARMS,
+            Self::Init(_) => TCA_HTB_INIT,
+            Self::Ctab(_) => TCA_HTB_CTAB,
+            Self::Rtab(_) => TCA_HTB_RTAB,
+            Self::DirectQlen(_) => TCA_HTB_DIRECT_QLEN,
+            Self::Rate64(_) => TCA_HTB_RATE64,
+            Self::Ceil64(_) => TCA_HTB_CEIL64,
+            Self::Offload(_) => TCA_HTB_OFFLOAD,
+            Self::Other(attr) => attr.kind(),
+        }
+    }
+}
+
+impl<'a, T: AsRef<[u8]> + ?Sized> Parseable<NlaBuffer<&'a T>>
+    for TcQdiscHtbOption
+{
+    fn parse(buf: &NlaBuffer<&'a T>) -> Result<Self, DecodeError> {
+        let payload = buf.value();
+        Ok(match buf.kind() {
+            TCA_HTB_PARMS => Self::Parms(
+                TcHtbOpt::parse(
+                    &TcHtbOptBuffer::new_checked(payload)
+                        .context("invalid TCA_HTB_PARMS")?,
+                )
+                .context("failed to parse TCA_HTB_PARMS")?,
+            ),
+            TCA_HTB_INIT => Self::Init(
+                TcHtbGlob::parse(
+                    &TcHtbGlobBuffer::new_checked(payload)
+                        .context("invalid TCA_HTB_INIT")?,
+                )
+                .context("failed to parse TCA_HTB_INIT")?,
+            ),
+            TCA_HTB_CTAB => Self::Ctab(parse_rate_table(payload)),
+            TCA_HTB_RTAB => Self::Rtab(parse_rate_table(payload)),
+            TCA_HTB_DIRECT_QLEN => Self::DirectQlen(
+                parse_u32(payload)
+                    .context("failed to parse TCA_HTB_DIRECT_QLEN")?,
+            ),
+            TCA_HTB_RATE64 => Self::Rate64(
+                parse_u64(payload).context("failed to parse TCA_HTB_RATE64")?,
+            ),
+            TCA_HTB_CEIL64 => Self::Ceil64(
+                parse_u64(payload).context("failed to parse TCA_HTB_CEIL64")?,
+            ),
+            TCA_HTB_OFFLOAD => Self::Offload(true),
+            _ => Self::Other(
+                DefaultNla::parse(buf).context("failed to parse htb nla")?,
+            ),
+        })
+    }
+}
+
+const TC_HTB_OPT_BUF_LEN: usize = TcRateSpec::BUF_LEN * 2 + 20;
+
+// kernel struct `tc_htb_opt`
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Default)]
+#[non_exhaustive]
+pub struct TcHtbOpt {
+    pub rate: TcRateSpec,
+    pub ceil: TcRateSpec,
+    pub buffer: u32,
+    pub cbuffer: u32,
+    pub quantum: u32,
+    /// Read only
+    pub level: u32,
+    pub prio: u32,
+}
+
+buffer!(TcHtbOptBuffer(TC_HTB_OPT_BUF_LEN) {
+    rate: (slice, 0..12),
+    ceil: (slice, 12..24),
+    buffer: (u32, 24..28),
+    cbuffer: (u32, 28..32),
+    quantum: (u32, 32..36),
+    level: (u32, 36..40),
+    prio: (u32, 40..TC_HTB_OPT_BUF_LEN),
+});
+
+impl Emitable for TcHtbOpt {
+    fn buffer_len(&self) -> usize {
+        TC_HTB_OPT_BUF_LEN
+    }
+
+    fn emit(&self, buffer: &mut [u8]) {
+        let mut packet = TcHtbOptBuffer::new(buffer);
+        self.rate.emit(packet.rate_mut());
+        self.ceil.emit(packet.ceil_mut());
+        packet.set_buffer(self.buffer);
+        packet.set_cbuffer(self.cbuffer);
+        packet.set_quantum(self.quantum);
+        packet.set_level(self.level);
+        packet.set_prio(self.prio);
+    }
+}
+
+impl<T: AsRef<[u8]> + ?Sized> Parseable<TcHtbOptBuffer<&T>> for TcHtbOpt {
+    fn parse(buf: &TcHtbOptBuffer<&T>) -> Result<Self, DecodeError> {
+        Ok(Self {
+            rate: TcRateSpec::parse(&TcRateSpecBuffer::new(buf.rate()))?,
+            ceil: TcRateSpec::parse(&TcRateSpecBuffer::new(buf.ceil()))?,
+            buffer: buf.buffer(),
+            cbuffer: buf.cbuffer(),
+            quantum: buf.quantum(),
+            level: buf.level(),
+            prio: buf.prio(),
+        })
+    }
+}
+
+const TC_HTB_GLOB_BUF_LEN: usize = 20;
+
+// kernel struct `tc_htb_glob`
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Default)]
+#[non_exhaustive]
+pub struct TcHtbGlob {
+    /// To match HTB/TC protocol version
+    pub version: u32,
+    /// bps->quantum divisor
+    pub rate2quantum: u32,
+    /// Default class number
+    pub defcls: u32,
+    /// Debug flags
+    pub debug: u32,
+    /// Count of non shaped packets
+    pub direct_pkts: u32,
+}
+
+buffer!(TcHtbGlobBuffer(TC_HTB_GLOB_BUF_LEN) {
+    version: (u32, 0..4),
+    rate2quantum: (u32, 4..8),
+    defcls: (u32, 8..12),
+    debug: (u32, 12..16),
+    direct_pkts: (u32, 16..TC_HTB_GLOB_BUF_LEN),
+});
+
+impl Emitable for TcHtbGlob {
+    fn buffer_len(&self) -> usize {
+        TC_HTB_GLOB_BUF_LEN
+    }
+
+    fn emit(&self, buffer: &mut [u8]) {
+        let mut packet = TcHtbGlobBuffer::new(buffer);
+        packet.set_version(self.version);
+        packet.set_rate2quantum(self.rate2quantum);
+        packet.set_defcls(self.defcls);
+        packet.set_debug(self.debug);
+        packet.set_direct_pkts(self.direct_pkts);
+    }
+}
+
+impl<T: AsRef<[u8]>> Parseable<TcHtbGlobBuffer<T>> for TcHtbGlob {
+    fn parse(buf: &TcHtbGlobBuffer<T>) -> Result<Self, DecodeError> {
+        Ok(Self {
+            version: buf.version(),
+            rate2quantum: buf.rate2quantum(),
+            defcls: buf.defcls(),
+            debug: buf.debug(),
+            direct_pkts: buf.direct_pkts(),
+        })
+    }
+}
+
+const TC_HTB_XSTATS_BUF_LEN: usize = 20;
+
+// kernel struct `tc_htb_xstats`
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Default)]
+#[non_exhaustive]
+pub struct TcHtbXstats {
+    pub lends: u32,
+    pub borrows: u32,
+    /// Unused since kernel 3.4
+    pub giants: u32,
+    pub tokens: i32,
+    pub ctokens: i32,
+}
+
+buffer!(TcHtbXstatsBuffer(TC_HTB_XSTATS_BUF_LEN) {
+    lends: (u32, 0..4),
+    borrows: (u32, 4..8),
+    giants: (u32, 8..12),
+    tokens: (i32, 12..16),
+    ctokens: (i32, 16..TC_HTB_XSTATS_BUF_LEN),
+});
+
+impl Emitable for TcHtbXstats {
+    fn buffer_len(&self) -> usize {
+        TC_HTB_XSTATS_BUF_LEN
+    }
+
+    fn emit(&self, buffer: &mut [u8]) {
+        let mut packet = TcHtbXstatsBuffer::new(buffer);
+        packet.set_lends(self.lends);
+        packet.set_borrows(self.borrows);
+        packet.set_giants(self.giants);
+        packet.set_tokens(self.tokens);
+        packet.set_ctokens(self.ctokens);
+    }
+}
+
+impl<T: AsRef<[u8]>> Parseable<TcHtbXstatsBuffer<T>> for TcHtbXstats {
+    fn parse(buf: &TcHtbXstatsBuffer<T>) -> Result<Self, DecodeError> {
+        Ok(Self {
+            lends: buf.lends(),
+            borrows: buf.borrows(),
+            giants: buf.giants(),
+            tokens: buf.tokens(),
+            ctokens: buf.ctokens(),
+        })
+    }
+}
@@ -1,11 +1,123 @@
 // SPDX-License-Identifier: MIT
 
+mod cake;
+mod cbs;
+mod choke;
+mod clsact;
+mod codel;
+mod drr;
+mod etf;
+mod ets;
+mod fifo;
+mod fq;
 mod fq_codel;
+mod fq_pie;
+mod gred;
+mod hfsc;
+mod htb;
 mod ingress;
+mod mqprio;
+mod multiq;
+mod netem;
+mod pie;
+mod prio;
+mod qfq;
+mod red;
+mod sfq;
+mod taprio;
+mod tbf;
 
+pub use self::cake::{
+    TcCakeAckFilter, TcCakeAtmMode, TcCakeDiffservMode, TcCakeFlowMode,
+    TcCakeTinStats, TcCakeXstats, TcQdiscCake, TcQdiscCakeOption,
+};
+pub use self::cbs::{TcCbsQopt, TcCbsQoptBuffer, TcQdiscCbs, TcQdiscCbsOption};
+pub use self::choke::{
+    TcChokeXstats, TcChokeXstatsBuffer, TcQdiscChoke, TcQdiscChokeOption,
+};
+pub use self::clsact::{TcQdiscClsact, TcQdiscClsactOption};
+pub use self::codel::{
+    TcCodelXstats, TcCodelXstatsBuffer, TcQdiscCodel, TcQdiscCodelOption,
+};
+pub use self::drr::{
+    TcDrrStats, TcDrrStatsBuffer, TcQdiscDrr, TcQdiscDrrOption,
+};
+pub use self::etf::{
+    TcEtfFlag, TcEtfQopt, TcEtfQoptBuffer, TcQdiscEtf, TcQdiscEtfOption,
+};
+pub use self::ets::{TcQdiscEts, TcQdiscEtsOption};
+pub use self::fifo::{
+    TcFifoQopt, TcFifoQoptBuffer, TcQdiscBfifo, TcQdiscFifoOption, TcQdiscPfifo,
+};
+pub use self::fq::{
+    TcFqQdStats, TcFqQdStatsBuffer, TcQdiscFq, TcQdiscFqOption,
+};
 pub use self::fq_codel::{
     TcFqCodelClStats, TcFqCodelClStatsBuffer, TcFqCodelQdStats,
     TcFqCodelQdStatsBuffer, TcFqCodelXstats, TcQdiscFqCodel,
     TcQdiscFqCodelOption,
 };
+pub use self::fq_pie::{
+    TcFqPieXstats, TcFqPieXstatsBuffer, TcQdiscFqPie, TcQdiscFqPieOption,
+};
+pub use self::gred::{
+    TcGredQopt, TcGredQoptBuffer, TcGredSopt, TcGredSoptBuffer, TcGredVqEntry,
+    TcQdiscGred, TcQdiscGredOption,
+};
+pub use self::hfsc::{
+    TcHfscQopt, TcHfscQoptBuffer, TcHfscStats, TcHfscStatsBuffer, TcQdiscHfsc,
+    TcQdiscHfscOption, TcServiceCurve, TcServiceCurveBuffer,
+};
+pub use self::htb::{
+    TcHtbGlob, TcHtbGlobBuffer, TcHtbOpt, TcHtbOptBuffer, TcHtbXstats,
+    TcHtbXstatsBuffer, TcQdiscHtb, TcQdiscHtbOption,
+};
 pub use self::ingress::{TcQdiscIngress, TcQdiscIngressOption};
+pub use self::mqprio::{
+    TcFpAdminStatus, TcMqprioMode, TcMqprioQopt, TcMqprioQoptBuffer,
+    TcMqprioShaper, TcMqprioTcEntry, TcQdiscMqprio, TcQdiscMqprioOption,
+};
+pub use self::multiq::{
+    TcMultiqQopt, TcMultiqQoptBuffer, TcQdiscMultiq, TcQdiscMultiqOption,
+};
+pub use self::netem::{
+    TcNetemCorr, TcNetemCorrBuffer, TcNetemCorrupt, TcNetemCorruptBuffer,
+    TcNetemGeModel, TcNetemGeModelBuffer, TcNetemGiModel, TcNetemGiModelBuffer,
+    TcNetemLoss, TcNetemQopt, TcNetemQoptBuffer, TcNetemRate,
+    TcNetemRateBuffer, TcNetemReorder, TcNetemReorderBuffer, TcNetemSlot,
+    TcNetemSlotBuffer, TcQdiscNetem, TcQdiscNetemOption,
+};
+pub use self::pie::{
+    TcPieXstats, TcPieXstatsBuffer, TcQdiscPie, TcQdiscPieOption,
+};
+pub use self::prio::{
+    TcPrioQopt, TcPrioQoptBuffer, TcQdiscPfifoFast, TcQdiscPrio,
+    TcQdiscPrioOption,
+};
+pub use self::qfq::{
+    TcQdiscQfq, TcQdiscQfqOption, TcQfqStats, TcQfqStatsBuffer,
+};
+pub use self::red::{
+    TcQdiscRed, TcQdiscRedOption, TcRedFlag, TcRedFlags, TcRedFlagsBuffer,
+    TcRedQopt, TcRedQoptBuffer, TcRedXstats, TcRedXstatsBuffer,
+};
+pub use self::sfq::{
+    TcQdiscSfq, TcQdiscSfqOption, TcSfqQopt, TcSfqQoptBuffer, TcSfqQoptV1,
+    TcSfqQoptV1Buffer, TcSfqRedStats, TcSfqRedStatsBuffer, TcSfqXstats,
+    TcSfqXstatsBuffer,
+};
+pub use self::taprio::{
+    TcQdiscTaprio, TcQdiscTaprioOption, TcTaprioCmd, TcTaprioFlag,
+    TcTaprioSchedEntry, TcTaprioTcEntry, TcTaprioXstats,
+};
+pub use self::tbf::{TcQdiscTbf, TcQdiscTbfOption, TcTbfQopt, TcTbfQoptBuffer};
+
+pub(crate) use self::cake::parse_cake_xstats;
+pub(crate) use self::fifo::parse_fifo_options;
+pub(crate) use self::hfsc::parse_hfsc_options;
+pub(crate) use self::mqprio::parse_mqprio_options;
+pub(crate) use self::multiq::parse_multiq_options;
+pub(crate) use self::netem::parse_netem_options;
+pub(crate) use self::prio::parse_prio_options;
+pub(crate) use self::sfq::parse_sfq_options;
+pub(crate) use self::taprio::parse_taprio_xstats;
@@ -0,0 +1,410 @@
+// SPDX-License-Identifier: MIT
+
+/// Multiqueue Priority
+///
+/// The `mqprio` qdisc maps traffic classes to ranges of hardware transmit
+/// queues of the device, optionally offloaded to the NIC.
+use anyhow::Context;
+use byteorder::{ByteOrder, NativeEndian};
+use netlink_packet_utils::{
+    nla::{DefaultNla, Nla, NlaBuffer, NlasIterator, NLA_ALIGNTO},
+    parsers::{parse_u16, parse_u32, parse_u64},
+    traits::{Emitable, Parseable},
+    DecodeError,
+};
+
+#[derive(Debug, PartialEq, Eq, Clone)]
+#[non_exhaustive]
+pub struct TcQdiscMqprio {}
+
+impl TcQdiscMqprio {
+    pub(crate) const KIND: &'static str = "mqprio";
+}
+
+const TCA_MQPRIO_MODE: u16 = 1;
+const TCA_MQPRIO_SHAPER: u16 = 2;
+const TCA_MQPRIO_MIN_RATE64: u16 = 3;
+const TCA_MQPRIO_MAX_RATE64: u16 = 4;
+const TCA_MQPRIO_TC_ENTRY: u16 = 5;
+
+#[derive(Debug, PartialEq, Eq, Clone)]
+#[non_exhaustive]
+pub enum TcQdiscMqprioOption {
+    /// The `tc_mqprio_qopt` placed at the beginning of TCA_OPTIONS without
+    /// NLA header. Should be the first option when emitting.
+    Qopt(TcMqprioQopt),
+    Mode(TcMqprioMode),
+    Shaper(TcMqprioShaper),
+    /// Minimum rate in bytes per second of each traffic class
+    MinRate64(Vec<u64>),
+    /// Maximum rate in bytes per second of each traffic class
+    MaxRate64(Vec<u64>),
+    TcEntry(Vec<TcMqprioTcEntry>),
+    Other(DefaultNla),
+}
+
+impl Nla for TcQdiscMqprioOption {
+    fn value_len(&self) -> usize {
+        match self {
+            Self::Qopt(v) => v.buffer_len(),
+            Self::Mode(_) | Self::Shaper(_) => 2,
+            Self::MinRate64(v) | Self::MaxRate64(v) => {
+                mqprio_rates(self.kind(), v).as_slice().buffer_len()
+            }
+            Self::TcEntry(v) => v.as_slice().buffer_len(),
+            Self::Other(attr) => attr.value_len(),
+        }
+    }
+
+    fn emit_value(&self, buffer: &mut [u8]) {
+        match self {
+            Self::Qopt(v) => v.emit(buffer),
+            Self::Mode(v) => NativeEndian::write_u16(buffer, (*v).into()),
+            Self::Shaper(v) => NativeEndian::write_u16(buffer, (*v).into()),
+            Self::MinRate64(v) | Self::MaxRate64(v) => {
+                mqprio_rates(self.kind(), v).as_slice().emit(buffer)
+            }
+            Self::TcEntry(v) => v.as_slice().emit(buffer),
+            Self::Other(attr) => attr.emit_value(buffer),
+        }
+    }
+
+    fn kind(&self) -> u16 {
+        match self {
+            // Emitted without NLA header, see `TcQdiscMqprioOption::Qopt`
+            Self::Qopt(_) => 0,
+            Self::Mode(_) => TCA_MQPRIO_MODE,
+            Self::Shaper(_) => TCA_MQPRIO_SHAPER,
+            Self::MinRate64(_) => TCA_MQPRIO_MIN_RATE64,
+            Self::MaxRate64(_) => TCA_MQPRIO_MAX_RATE64,
+            Self::TcEntry(_) => TCA_MQPRIO_TC_ENTRY,
+            Self::Other(attr) => attr.kind(),
+        }
+    }
+
+    fn is_nested(&self) -> bool {
+        matches!(self, Self::TcEntry(_))
+    }
+}
+
+impl<'a, T: AsRef<[u8]> + ?Sized> Parseable<NlaBuffer<&'a T>>
+    for TcQdiscMqprioOption
+{
+    fn parse(buf: &NlaBuffer<&'a T>) -> Result<Self, DecodeError> {
+        let payload = buf.value();
+        Ok(match buf.kind() {
+            TCA_MQPRIO_MODE => Self::Mode(
+                parse_u16(payload)
+                    .context("failed to parse TCA_MQPRIO_MODE")?
+                    .into(),
+            ),
+            TCA_MQPRIO_SHAPER => Self::Shaper(
+                parse_u16(payload)
+                    .context("failed to parse TCA_MQPRIO_SHAPER")?
+                    .into(),
+            ),
+            TCA_MQPRIO_MIN_RATE64 => Self::MinRate64(
+                parse_mqprio_rates(payload)
+                    .context("failed to parse TCA_MQPRIO_MIN_RATE64")?,
+            ),
+            TCA_MQPRIO_MAX_RATE64 => Self::MaxRate64(
+                parse_mqprio_rates(payload)
+                    .context("failed to parse TCA_MQPRIO_MAX_RATE64")?,
+            ),
+            TCA_MQPRIO_TC_ENTRY => {
+                let mut nlas = vec![];
+                for nla in NlasIterator::new(payload) {
+                    let nla = nla.context("invalid TCA_MQPRIO_TC_ENTRY")?;
+                    nlas.push(
+                        TcMqprioTcEntry::parse(&nla)
+                            .context("failed to parse TCA_MQPRIO_TC_ENTRY")?,
+                    );
+                }
+                Self::TcEntry(nlas)
+            }
+            _ => Self::Other(
+                DefaultNla::parse(buf).context("failed to parse mqprio nla")?,
+            ),
+        })
+    }
+}
+
+pub(crate) fn parse_mqprio_options(
+    payload: &[u8],
+) -> Result<Vec<TcQdiscMqprioOption>, DecodeError> {
+    let mut options = vec![TcQdiscMqprioOption::Qopt(
+        TcMqprioQopt::parse(
+            &TcMqprioQoptBuffer::new_checked(payload)
+                .context("invalid tc_mqprio_qopt")?,
+        )
+        .context("failed to parse tc_mqprio_qopt")?,
+    )];
+    // The kernel skips NLA_ALIGN(sizeof(struct tc_mqprio_qopt)) bytes
+    let nlas = payload
+        .get(nla_align!(TC_MQPRIO_QOPT_BUF_LEN)..)
+        .unwrap_or_default();
+    for nla in NlasIterator::new(nlas) {
+        let nla = nla.context("invalid mqprio TCA_OPTIONS")?;
+        options.push(
+            TcQdiscMqprioOption::parse(&nla)
+                .context("failed to parse mqprio TCA_OPTIONS")?,
+        );
+    }
+    Ok(options)
+}
+
+// Each rate is stored in its own NLA using the type of the outer NLA
+struct MqprioRate {
+    kind: u16,
+    rate: u64,
+}
+
+fn mqprio_rates(kind: u16, rates: &[u64]) -> Vec<MqprioRate> {
+    rates
+        .iter()
+        .map(|rate| MqprioRate { kind, rate: *rate })
+        .collect()
+}
+
+impl Nla for MqprioRate {
+    fn value_len(&self) -> usize {
+        8
+    }
+
+    fn emit_value(&self, buffer: &mut [u8]) {
+        NativeEndian::write_u64(buffer, self.rate)
+    }
+
+    fn kind(&self) -> u16 {
+        self.kind
+    }
+}
+
+fn parse_mqprio_rates(payload: &[u8]) -> Result<Vec<u64>, DecodeError> {
+    let mut rates = vec![];
+    for nla in NlasIterator::new(payload) {
+        let nla = nla?;
+        rates.push(parse_u64(nla.value())?);
+    }
+    Ok(rates)
+}
+
+const TC_QOPT_MAX_QUEUE: usize = 16;
+const TC_QOPT_BITMASK: usize = 15;
+const TC_MQPRIO_QOPT_BUF_LEN: usize = 82;
+
+// kernel struct `tc_mqprio_qopt`
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Default)]
+#[non_exhaustive]
+pub struct TcMqprioQopt {
+    /// Number of traffic classes
+    pub num_tc: u8,
+    /// Map from priority to traffic class
+    pub prio_tc_map: [u8; TC_QOPT_BITMASK + 1],
+    /// Hardware offload mode, 0 for software only
+    pub hw: u8,
+    /// Number of queues of each traffic class
+    pub count: [u16; TC_QOPT_MAX_QUEUE],
+    /// First queue of each traffic class
+    pub offset: [u16; TC_QOPT_MAX_QUEUE],
+}
+
+buffer!(TcMqprioQoptBuffer(TC_MQPRIO_QOPT_BUF_LEN) {
+    num_tc: (u8, 0),
+    prio_tc_map: (slice, 1..17),
+    hw: (u8, 17),
+    count: (slice, 18..50),
+    offset: (slice, 50..TC_MQPRIO_QOPT_BUF_LEN),
+});
+
+impl Emitable for TcMqprioQopt {
+    fn buffer_len(&self) -> usize {
+        TC_MQPRIO_QOPT_BUF_LEN
+    }
+
+    fn emit(&self, buffer: &mut [u8]) {
+        let mut packet = TcMqprioQoptBuffer::new(buffer);
+        packet.set_num_tc(self.num_tc);
+        packet.prio_tc_map_mut().copy_from_slice(&self.prio_tc_map);
+        packet.set_hw(self.hw);
+        NativeEndian::write_u16_into(&self.count, packet.count_mut());
+        NativeEndian::write_u16_into(&self.offset, packet.offset_mut());
+    }
+}
+
+impl<T: AsRef<[u8]> + ?Sized> Parseable<TcMqprioQoptBuffer<&T>>
+    for TcMqprioQopt
+{
+    fn parse(buf: &TcMqprioQoptBuffer<&T>) -> Result<Self, DecodeError> {
+        let mut ret = Self {
+            num_tc: buf.num_tc(),
+            hw: buf.hw(),
+            ..Default::default()
+        };
+        ret.prio_tc_map.copy_from_slice(buf.prio_tc_map());
+        NativeEndian::read_u16_into(buf.count(), &mut ret.count);
+        NativeEndian::read_u16_into(buf.offset(), &mut ret.offset);
+        Ok(ret)
+    }
+}
+
+const TC_MQPRIO_MODE_DCB: u16 = 0;
+const TC_MQPRIO_MODE_CHANNEL: u16 = 1;
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Default)]
+#[non_exhaustive]
+pub enum TcMqprioMode {
+    #[default]
+    Dcb,
+    Channel,
+    Other(u16),
+}
+
+impl From<u16> for TcMqprioMode {
+    fn from(d: u16) -> Self {
+        match d {
+            TC_MQPRIO_MODE_DCB => Self::Dcb,
+            TC_MQPRIO_MODE_CHANNEL => Self::Channel,
+            _ => Self::Other(d),
+        }
+    }
+}
+
+impl From<TcMqprioMode> for u16 {
+    fn from(v: TcMqprioMode) -> u16 {
+        match v {
+            TcMqprioMode::Dcb => TC_MQPRIO_MODE_DCB,
+            TcMqprioMode::Channel => TC_MQPRIO_MODE_CHANNEL,
+            TcMqprioMode::Other(d) => d,
+        }
+    }
+}
+
+const TC_MQPRIO_SHAPER_DCB: u16 = 0;
+const TC_MQPRIO_SHAPER_BW_RATE: u16 = 1;
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Default)]
+#[non_exhaustive]
+pub enum TcMqprioShaper {
+    #[default]
+    Dcb,
+    /// Shaping by `MinRate64` and `MaxRate64`
+    BwRate,
+    Other(u16),
+}
+
+impl From<u16> for TcMqprioShaper {
+    fn from(d: u16) -> Self {
+        match d {
+            TC_MQPRIO_SHAPER_DCB => Self::Dcb,
+            TC_MQPRIO_SHAPER_BW_RATE => Self::BwRate,
+            _ => Self::Other(d),
+        }
+    }
+}
+
+impl From<TcMqprioShaper> for u16 {
+    fn from(v: TcMqprioShaper) -> u16 {
+        match v {
+            TcMqprioShaper::Dcb => TC_MQPRIO_SHAPER_DCB,
+            TcMqprioShaper::BwRate => TC_MQPRIO_SHAPER_BW_RATE,
+            TcMqprioShaper::Other(d) => d,
+        }
+    }
+}
+
+const TCA_MQPRIO_TC_ENTRY_INDEX: u16 = 1;
+const TCA_MQPRIO_TC_ENTRY_FP: u16 = 2;
+
+/// Per traffic class attributes nested in `TCA_MQPRIO_TC_ENTRY`
+#[derive(Debug, PartialEq, Eq, Clone)]
+#[non_exhaustive]
+pub enum TcMqprioTcEntry {
+    /// Index of traffic class
+    Index(u32),
+    /// Frame preemption status
+    Fp(TcFpAdminStatus),
+    Other(DefaultNla),
+}
+
+impl Nla for TcMqprioTcEntry {
+    fn value_len(&self) -> usize {
+        match self {
+            Self::Index(_) | Self::Fp(_) => 4,
+            Self::Other(attr) => attr.value_len(),
+        }
+    }
+
+    fn emit_value(&self, buffer: &mut [u8]) {
+        match self {
+            Self::Index(d) => NativeEndian::write_u32(buffer, *d),
+            Self::Fp(v) => NativeEndian::write_u32(buffer, (*v).into()),
+            Self::Other(attr) => attr.emit_value(buffer),
+        }
+    }
+
+    fn kind(&self) -> u16 {
+        match self {
+            Self::Index(_) => TCA_MQPRIO_TC_ENTRY_INDEX,
+            Self::Fp(_) => TCA_MQPRIO_TC_ENTRY_FP,
+            Self::Other(attr) => attr.kind(),
+        }
+    }
+}
+
+impl<'a, T: AsRef<[u8]> + ?Sized> Parseable<NlaBuffer<&'a T>>
+    for TcMqprioTcEntry
+{
+    fn parse(buf: &NlaBuffer<&'a T>) -> Result<Self, DecodeError> {
+        let payload = buf.value();
+        Ok(match buf.kind() {
+            TCA_MQPRIO_TC_ENTRY_INDEX => Self::Index(
+                parse_u32(payload)
+                    .context("failed to parse TCA_MQPRIO_TC_ENTRY_INDEX")?,
+            ),
+            TCA_MQPRIO_TC_ENTRY_FP => Self::Fp(
+                parse_u32(payload)
+                    .context("failed to parse TCA_MQPRIO_TC_ENTRY_FP")?
+                    .into(),
+            ),
+            _ => Self::Other(
+                DefaultNla::parse(buf)
+                    .context("failed to parse mqprio tc entry nla")?,
+            ),
+        })
+    }
+}
+
+const TC_FP_EXPRESS: u32 = 1;
+const TC_FP_PREEMPTIBLE: u32 = 2;
+
+/// Frame preemption (IEEE 802.1Q-2018 clause 6.7.2) status of a traffic
+/// class
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Default)]
+#[non_exhaustive]
+pub enum TcFpAdminStatus {
+    #[default]
+    Express,
+    Preemptible,
+    Other(u32),
+}
+
+impl From<u32> for TcFpAdminStatus {
+    fn from(d: u32) -> Self {
+        match d {
+            TC_FP_EXPRESS => Self::Express,
+            TC_FP_PREEMPTIBLE => Self::Preemptible,
+            _ => Self::Other(d),
+        }
+    }
+}
+
+impl From<TcFpAdminStatus> for u32 {
+    fn from(v: TcFpAdminStatus) -> u32 {
+        match v {
+            TcFpAdminStatus::Express => TC_FP_EXPRESS,
+            TcFpAdminStatus::Preemptible => TC_FP_PREEMPTIBLE,
+            TcFpAdminStatus::Other(d) => d,
+        }
+    }
+}
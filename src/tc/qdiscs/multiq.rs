@@ -0,0 +1,99 @@
+// SPDX-License-Identifier: MIT
+
+/// Multiqueue
+///
+/// The `multiq` qdisc has one band for each hardware transmit queue of the
+/// device.
+use anyhow::Context;
+use netlink_packet_utils::{
+    nla::Nla,
+    traits::{Emitable, Parseable},
+    DecodeError,
+};
+
+#[derive(Debug, PartialEq, Eq, Clone)]
+#[non_exhaustive]
+pub struct TcQdiscMultiq {}
+
+impl TcQdiscMultiq {
+    pub(crate) const KIND: &'static str = "multiq";
+}
+
+#[derive(Debug, PartialEq, Eq, Clone)]
+#[non_exhaustive]
+pub enum TcQdiscMultiqOption {
+    /// The `tc_multiq_qopt` is the whole TCA_OPTIONS without NLA header.
+    Qopt(TcMultiqQopt),
+}
+
+impl Nla for TcQdiscMultiqOption {
+    fn value_len(&self) -> usize {
+        match self {
+            Self::Qopt(v) => v.buffer_len(),
+        }
+    }
+
+    fn emit_value(&self, buffer: &mut [u8]) {
+        match self {
+            Self::Qopt(v) => v.emit(buffer),
+        }
+    }
+
+    fn kind(&self) -> u16 {
+        match self {
+            // Emitted without NLA header, see `TcQdiscMultiqOption::Qopt`
+            Self::Qopt(_) => 0,
+        }
+    }
+}
+
+pub(crate) fn parse_multiq_options(
+    payload: &[u8],
+) -> Result<Vec<TcQdiscMultiqOption>, DecodeError> {
+    Ok(vec![TcQdiscMultiqOption::Qopt(
+        TcMultiqQopt::parse(
+            &TcMultiqQoptBuffer::new_checked(payload)
+                .context("invalid tc_multiq_qopt")?,
+        )
+        .context("failed to parse tc_multiq_qopt")?,
+    )])
+}
+
+const TC_MULTIQ_QOPT_BUF_LEN: usize = 4;
+
+// kernel struct `tc_multiq_qopt`
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Default)]
+#[non_exhaustive]
+pub struct TcMultiqQopt {
+    /// Number of bands, read only as kernel uses number of transmit queues
+    /// of the device
+    pub bands: u16,
+    /// Maximum number of queues
+    pub max_bands: u16,
+}
+
+buffer!(TcMultiqQoptBuffer(TC_MULTIQ_QOPT_BUF_LEN) {
+    bands: (u16, 0..2),
+    max_bands: (u16, 2..TC_MULTIQ_QOPT_BUF_LEN),
+});
+
+impl Emitable for TcMultiqQopt {
+    fn buffer_len(&self) -> usize {
+        TC_MULTIQ_QOPT_BUF_LEN
+    }
+
+    fn emit(&self, buffer: &mut [u8]) {
+        let mut packet = TcMultiqQoptBuffer::new(buffer);
+        packet.set_bands(self.bands);
+        packet.set_max_bands(self.max_bands);
+    }
+}
+
+impl<T: AsRef<[u8]>> Parseable<TcMultiqQoptBuffer<T>> for TcMultiqQopt {
+    fn parse(buf: &TcMultiqQoptBuffer<T>) -> Result<Self, DecodeError> {
+        Ok(Self {
+            bands: buf.bands(),
+            max_bands: buf.max_bands(),
+        })
+    }
+}
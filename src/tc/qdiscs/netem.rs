@@ -0,0 +1,667 @@
+// SPDX-License-Identifier: MIT
+
+/// Network emulator
+///
+/// The netem qdisc adds delay, packet loss, duplication, corruption and
+/// re-ordering to outgoing packets. Unlike most qdiscs, its TCA_OPTIONS
+/// starts with a `tc_netem_qopt` struct followed by the `TCA_NETEM_*`
+/// attributes.
+use anyhow::Context;
+use byteorder::{ByteOrder, NativeEndian};
+use netlink_packet_utils::{
+    nla::{DefaultNla, Nla, NlaBuffer, NlasIterator},
+    parsers::{parse_u32, parse_u64},
+    traits::{Emitable, Parseable},
+    DecodeError,
+};
+
+#[derive(Debug, PartialEq, Eq, Clone)]
+#[non_exhaustive]
+pub struct TcQdiscNetem {}
+
+impl TcQdiscNetem {
+    pub(crate) const KIND: &'static str = "netem";
+}
+
+// const TCA_NETEM_UNSPEC: u16 = 0;
+const TCA_NETEM_CORR: u16 = 1;
+const TCA_NETEM_DELAY_DIST: u16 = 2;
+const TCA_NETEM_REORDER: u16 = 3;
+const TCA_NETEM_CORRUPT: u16 = 4;
+const TCA_NETEM_LOSS: u16 = 5;
+const TCA_NETEM_RATE: u16 = 6;
+const TCA_NETEM_ECN: u16 = 7;
+const TCA_NETEM_RATE64: u16 = 8;
+// const TCA_NETEM_PAD: u16 = 9;
+const TCA_NETEM_LATENCY64: u16 = 10;
+const TCA_NETEM_JITTER64: u16 = 11;
+const TCA_NETEM_SLOT: u16 = 12;
+const TCA_NETEM_SLOT_DIST: u16 = 13;
+
+#[derive(Debug, PartialEq, Eq, Clone)]
+#[non_exhaustive]
+pub enum TcQdiscNetemOption {
+    /// The `tc_netem_qopt` placed at the beginning of TCA_OPTIONS without
+    /// NLA header. Should be the first option when emitting.
+    Qopt(TcNetemQopt),
+    Corr(TcNetemCorr),
+    /// Delay distribution table
+    DelayDist(Vec<i16>),
+    Reorder(TcNetemReorder),
+    Corrupt(TcNetemCorrupt),
+    Loss(Vec<TcNetemLoss>),
+    Rate(TcNetemRate),
+    Ecn(u32),
+    /// Rate in bytes per second when it does not fit into
+    /// `TcNetemRate::rate`
+    Rate64(u64),
+    /// Added delay in nanoseconds
+    Latency64(i64),
+    /// Random jitter in latency in nanoseconds
+    Jitter64(i64),
+    Slot(TcNetemSlot),
+    /// Slot delay distribution table
+    SlotDist(Vec<i16>),
+    Other(DefaultNla),
+}
+
+impl Nla for TcQdiscNetemOption {
+    fn value_len(&self) -> usize {
+        match self {
+            Self::Qopt(v) => v.buffer_len(),
+            Self::Corr(v) => v.buffer_len(),
+            Self::DelayDist(v) | Self::SlotDist(v) => v.len() * 2,
+            Self::Reorder(v) => v.buffer_len(),
+            Self::Corrupt(v) => v.buffer_len(),
+            Self::Loss(v) => v.as_slice().buffer_len(),
+            Self::Rate(v) => v.buffer_len(),
+            Self::Ecn(_) => 4,
+            Self::Rate64(_) | Self::Latency64(_) | Self::Jitter64(_) => 8,
+            Self::Slot(v) => v.buffer_len(),
+            Self::Other(attr) => attr.value_len(),
+        }
+    }
+
+    fn emit_value(&self, buffer: &mut [u8]) {
+        match self {
+            Self::Qopt(v) => v.emit(buffer),
+            Self::Corr(v) => v.emit(buffer),
+            Self::DelayDist(v) | Self::SlotDist(v) => {
+                for (d, chunk) in v.iter().zip(buffer.chunks_exact_mut(2)) {
+                    NativeEndian::write_i16(chunk, *d);
+                }
+            }
+            Self::Reorder(v) => v.emit(buffer),
+            Self::Corrupt(v) => v.emit(buffer),
+            Self::Loss(v) => v.as_slice().emit(buffer),
+            Self::Rate(v) => v.emit(buffer),
+            Self::Ecn(d) => NativeEndian::write_u32(buffer, *d),
+            Self::Rate64(d) => NativeEndian::write_u64(buffer, *d),
+            Self::Latency64(d) | Self::Jitter64(d) => {
+                NativeEndian::write_i64(buffer, *d)
+            }
+            Self::Slot(v) => v.emit(buffer),
+            Self::Other(attr) => attr.emit_value(buffer),
+        }
+    }
+
+    fn kind(&self) -> u16 {
+        match self {
+            // Emitted without NLA header, see `TcQdiscNetemOption::Qopt`
+            Self::Qopt(_) => 0,
+            Self::Corr(_) => TCA_NETEM_CORR,
+            Self::DelayDist(_) => TCA_NETEM_DELAY_DIST,
+            Self::Reorder(_) => TCA_NETEM_REORDER,
+            Self::Corrupt(_) => TCA_NETEM_CORRUPT,
+            Self::Loss(_) => TCA_NETEM_LOSS,
+            Self::Rate(_) => TCA_NETEM_RATE,
+            Self::Ecn(_) => TCA_NETEM_ECN,
+            Self::Rate64(_) => TCA_NETEM_RATE64,
+            Self::Latency64(_) => TCA_NETEM_LATENCY64,
+            Self::Jitter64(_) => TCA_NETEM_JITTER64,
+            Self::Slot(_) => TCA_NETEM_SLOT,
+            Self::SlotDist(_) => TCA_NETEM_SLOT_DIST,
+            Self::Other(attr) => attr.kind(),
+        }
+    }
+
+    fn is_nested(&self) -> bool {
+        matches!(self, Self::Loss(_))
+    }
+}
+
+impl<'a, T: AsRef<[u8]> + ?Sized> Parseable<NlaBuffer<&'a T>>
+    for TcQdiscNetemOption
+{
+    fn parse(buf: &NlaBuffer<&'a T>) -> Result<Self, DecodeError> {
+        let payload = buf.value();
+        Ok(match buf.kind() {
+            TCA_NETEM_CORR => Self::Corr(
+                TcNetemCorr::parse(
+                    &TcNetemCorrBuffer::new_checked(payload)
+                        .context("invalid TCA_NETEM_CORR")?,
+                )
+                .context("failed to parse TCA_NETEM_CORR")?,
+            ),
+            TCA_NETEM_DELAY_DIST => Self::DelayDist(parse_dist_table(payload)),
+            TCA_NETEM_REORDER => Self::Reorder(
+                TcNetemReorder::parse(
+                    &TcNetemReorderBuffer::new_checked(payload)
+                        .context("invalid TCA_NETEM_REORDER")?,
+                )
+                .context("failed to parse TCA_NETEM_REORDER")?,
+            ),
+            TCA_NETEM_CORRUPT => Self::Corrupt(
+                TcNetemCorrupt::parse(
+                    &TcNetemCorruptBuffer::new_checked(payload)
+                        .context("invalid TCA_NETEM_CORRUPT")?,
+                )
+                .context("failed to parse TCA_NETEM_CORRUPT")?,
+            ),
+            TCA_NETEM_LOSS => {
+                let mut nlas = vec![];
+                for nla in NlasIterator::new(payload) {
+                    let nla = nla.context("invalid TCA_NETEM_LOSS")?;
+                    nlas.push(
+                        TcNetemLoss::parse(&nla)
+                            .context("failed to parse TCA_NETEM_LOSS")?,
+                    );
+                }
+                Self::Loss(nlas)
+            }
+            TCA_NETEM_RATE => Self::Rate(
+                TcNetemRate::parse(
+                    &TcNetemRateBuffer::new_checked(payload)
+                        .context("invalid TCA_NETEM_RATE")?,
+                )
+                .context("failed to parse TCA_NETEM_RATE")?,
+            ),
+            TCA_NETEM_ECN => Self::Ecn(
+                parse_u32(payload).context("failed to parse TCA_NETEM_ECN")?,
+            ),
+            TCA_NETEM_RATE64 => Self::Rate64(
+                parse_u64(payload)
+                    .context("failed to parse TCA_NETEM_RATE64")?,
+            ),
+            TCA_NETEM_LATENCY64 => Self::Latency64(
+                parse_u64(payload)
+                    .context("failed to parse TCA_NETEM_LATENCY64")?
+                    as i64,
+            ),
+            TCA_NETEM_JITTER64 => Self::Jitter64(
+                parse_u64(payload)
+                    .context("failed to parse TCA_NETEM_JITTER64")?
+                    as i64,
+            ),
+            TCA_NETEM_SLOT => Self::Slot(
+                TcNetemSlot::parse(
+                    &TcNetemSlotBuffer::new_checked(payload)
+                        .context("invalid TCA_NETEM_SLOT")?,
+                )
+                .context("failed to parse TCA_NETEM_SLOT")?,
+            ),
+            TCA_NETEM_SLOT_DIST => Self::SlotDist(parse_dist_table(payload)),
+            _ => Self::Other(
+                DefaultNla::parse(buf).context("failed to parse netem nla")?,
+            ),
+        })
+    }
+}
+
+fn parse_dist_table(payload: &[u8]) -> Vec<i16> {
+    payload
+        .chunks_exact(2)
+        .map(NativeEndian::read_i16)
+        .collect()
+}
+
+// The kernel places `tc_netem_qopt` ahead of the nested attributes.
+pub(crate) fn parse_netem_options(
+    payload: &[u8],
+) -> Result<Vec<TcQdiscNetemOption>, DecodeError> {
+    let mut options = vec![TcQdiscNetemOption::Qopt(
+        TcNetemQopt::parse(
+            &TcNetemQoptBuffer::new_checked(payload)
+                .context("invalid tc_netem_qopt")?,
+        )
+        .context("failed to parse tc_netem_qopt")?,
+    )];
+    for nla in NlasIterator::new(&payload[TC_NETEM_QOPT_BUF_LEN..]) {
+        let nla = nla.context("invalid netem TCA_OPTIONS")?;
+        options.push(
+            TcQdiscNetemOption::parse(&nla)
+                .context("failed to parse netem TCA_OPTIONS")?,
+        );
+    }
+    Ok(options)
+}
+
+const TC_NETEM_QOPT_BUF_LEN: usize = 24;
+
+// kernel struct `tc_netem_qopt`
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Default)]
+#[non_exhaustive]
+pub struct TcNetemQopt {
+    /// Added delay in scheduler ticks
+    pub latency: u32,
+    /// FIFO limit in packets
+    pub limit: u32,
+    /// Random packet loss (0=none ~0=100%)
+    pub loss: u32,
+    /// Re-ordering gap (0 for none)
+    pub gap: u32,
+    /// Random packet duplication (0=none ~0=100%)
+    pub duplicate: u32,
+    /// Random jitter in latency in scheduler ticks
+    pub jitter: u32,
+}
+
+buffer!(TcNetemQoptBuffer(TC_NETEM_QOPT_BUF_LEN) {
+    latency: (u32, 0..4),
+    limit: (u32, 4..8),
+    loss: (u32, 8..12),
+    gap: (u32, 12..16),
+    duplicate: (u32, 16..20),
+    jitter: (u32, 20..TC_NETEM_QOPT_BUF_LEN),
+});
+
+impl Emitable for TcNetemQopt {
+    fn buffer_len(&self) -> usize {
+        TC_NETEM_QOPT_BUF_LEN
+    }
+
+    fn emit(&self, buffer: &mut [u8]) {
+        let mut packet = TcNetemQoptBuffer::new(buffer);
+        packet.set_latency(self.latency);
+        packet.set_limit(self.limit);
+        packet.set_loss(self.loss);
+        packet.set_gap(self.gap);
+        packet.set_duplicate(self.duplicate);
+        packet.set_jitter(self.jitter);
+    }
+}
+
+impl<T: AsRef<[u8]>> Parseable<TcNetemQoptBuffer<T>> for TcNetemQopt {
+    fn parse(buf: &TcNetemQoptBuffer<T>) -> Result<Self, DecodeError> {
+        Ok(Self {
+            latency: buf.latency(),
+            limit: buf.limit(),
+            loss: buf.loss(),
+            gap: buf.gap(),
+            duplicate: buf.duplicate(),
+            jitter: buf.jitter(),
+        })
+    }
+}
+
+const TC_NETEM_CORR_BUF_LEN: usize = 12;
+
+// kernel struct `tc_netem_corr`
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Default)]
+#[non_exhaustive]
+pub struct TcNetemCorr {
+    pub delay_corr: u32,
+    pub loss_corr: u32,
+    pub dup_corr: u32,
+}
+
+buffer!(TcNetemCorrBuffer(TC_NETEM_CORR_BUF_LEN) {
+    delay_corr: (u32, 0..4),
+    loss_corr: (u32, 4..8),
+    dup_corr: (u32, 8..TC_NETEM_CORR_BUF_LEN),
+});
+
+impl Emitable for TcNetemCorr {
+    fn buffer_len(&self) -> usize {
+        TC_NETEM_CORR_BUF_LEN
+    }
+
+    fn emit(&self, buffer: &mut [u8]) {
+        let mut packet = TcNetemCorrBuffer::new(buffer);
+        packet.set_delay_corr(self.delay_corr);
+        packet.set_loss_corr(self.loss_corr);
+        packet.set_dup_corr(self.dup_corr);
+    }
+}
+
+impl<T: AsRef<[u8]>> Parseable<TcNetemCorrBuffer<T>> for TcNetemCorr {
+    fn parse(buf: &TcNetemCorrBuffer<T>) -> Result<Self, DecodeError> {
+        Ok(Self {
+            delay_corr: buf.delay_corr(),
+            loss_corr: buf.loss_corr(),
+            dup_corr: buf.dup_corr(),
+        })
+    }
+}
+
+const TC_NETEM_REORDER_BUF_LEN: usize = 8;
+
+// kernel struct `tc_netem_reorder`
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Default)]
+#[non_exhaustive]
+pub struct TcNetemReorder {
+    pub probability: u32,
+    pub correlation: u32,
+}
+
+buffer!(TcNetemReorderBuffer(TC_NETEM_REORDER_BUF_LEN) {
+    probability: (u32, 0..4),
+    correlation: (u32, 4..TC_NETEM_REORDER_BUF_LEN),
+});
+
+impl Emitable for TcNetemReorder {
+    fn buffer_len(&self) -> usize {
+        TC_NETEM_REORDER_BUF_LEN
+    }
+
+    fn emit(&self, buffer: &mut [u8]) {
+        let mut packet = TcNetemReorderBuffer::new(buffer);
+        packet.set_probability(self.probability);
+        packet.set_correlation(self.correlation);
+    }
+}
+
+impl<T: AsRef<[u8]>> Parseable<TcNetemReorderBuffer<T>> for TcNetemReorder {
+    fn parse(buf: &TcNetemReorderBuffer<T>) -> Result<Self, DecodeError> {
+        Ok(Self {
+            probability: buf.probability(),
+            correlation: buf.correlation(),
+        })
+    }
+}
+
+const TC_NETEM_CORRUPT_BUF_LEN: usize = 8;
+
+// kernel struct `tc_netem_corrupt`
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Default)]
+#[non_exhaustive]
+pub struct TcNetemCorrupt {
+    pub probability: u32,
+    pub correlation: u32,
+}
+
+buffer!(TcNetemCorruptBuffer(TC_NETEM_CORRUPT_BUF_LEN) {
+    probability: (u32, 0..4),
+    correlation: (u32, 4..TC_NETEM_CORRUPT_BUF_LEN),
+});
+
+impl Emitable for TcNetemCorrupt {
+    fn buffer_len(&self) -> usize {
+        TC_NETEM_CORRUPT_BUF_LEN
+    }
+
+    fn emit(&self, buffer: &mut [u8]) {
+        let mut packet = TcNetemCorruptBuffer::new(buffer);
+        packet.set_probability(self.probability);
+        packet.set_correlation(self.correlation);
+    }
+}
+
+impl<T: AsRef<[u8]>> Parseable<TcNetemCorruptBuffer<T>> for TcNetemCorrupt {
+    fn parse(buf: &TcNetemCorruptBuffer<T>) -> Result<Self, DecodeError> {
+        Ok(Self {
+            probability: buf.probability(),
+            correlation: buf.correlation(),
+        })
+    }
+}
+
+const TC_NETEM_RATE_BUF_LEN: usize = 16;
+
+// kernel struct `tc_netem_rate`
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Default)]
+#[non_exhaustive]
+pub struct TcNetemRate {
+    /// Rate in bytes per second
+    pub rate: u32,
+    pub packet_overhead: i32,
+    pub cell_size: u32,
+    pub cell_overhead: i32,
+}
+
+buffer!(TcNetemRateBuffer(TC_NETEM_RATE_BUF_LEN) {
+    rate: (u32, 0..4),
+    packet_overhead: (i32, 4..8),
+    cell_size: (u32, 8..12),
+    cell_overhead: (i32, 12..TC_NETEM_RATE_BUF_LEN),
+});
+
+impl Emitable for TcNetemRate {
+    fn buffer_len(&self) -> usize {
+        TC_NETEM_RATE_BUF_LEN
+    }
+
+    fn emit(&self, buffer: &mut [u8]) {
+        let mut packet = TcNetemRateBuffer::new(buffer);
+        packet.set_rate(self.rate);
+        packet.set_packet_overhead(self.packet_overhead);
+        packet.set_cell_size(self.cell_size);
+        packet.set_cell_overhead(self.cell_overhead);
+    }
+}
+
+impl<T: AsRef<[u8]>> Parseable<TcNetemRateBuffer<T>> for TcNetemRate {
+    fn parse(buf: &TcNetemRateBuffer<T>) -> Result<Self, DecodeError> {
+        Ok(Self {
+            rate: buf.rate(),
+            packet_overhead: buf.packet_overhead(),
+            cell_size: buf.cell_size(),
+            cell_overhead: buf.cell_overhead(),
+        })
+    }
+}
+
+const TC_NETEM_SLOT_BUF_LEN: usize = 40;
+
+// kernel struct `tc_netem_slot`
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Default)]
+#[non_exhaustive]
+pub struct TcNetemSlot {
+    /// Minimum delay in nanoseconds
+    pub min_delay: i64,
+    /// Maximum delay in nanoseconds
+    pub max_delay: i64,
+    pub max_packets: i32,
+    pub max_bytes: i32,
+    /// Distribution delay in nanoseconds
+    pub dist_delay: i64,
+    /// Distribution jitter in nanoseconds
+    pub dist_jitter: i64,
+}
+
+buffer!(TcNetemSlotBuffer(TC_NETEM_SLOT_BUF_LEN) {
+    min_delay: (i64, 0..8),
+    max_delay: (i64, 8..16),
+    max_packets: (i32, 16..20),
+    max_bytes: (i32, 20..24),
+    dist_delay: (i64, 24..32),
+    dist_jitter: (i64, 32..TC_NETEM_SLOT_BUF_LEN),
+});
+
+impl Emitable for TcNetemSlot {
+    fn buffer_len(&self) -> usize {
+        TC_NETEM_SLOT_BUF_LEN
+    }
+
+    fn emit(&self, buffer: &mut [u8]) {
+        let mut packet = TcNetemSlotBuffer::new(buffer);
+        packet.set_min_delay(self.min_delay);
+        packet.set_max_delay(self.max_delay);
+        packet.set_max_packets(self.max_packets);
+        packet.set_max_bytes(self.max_bytes);
+        packet.set_dist_delay(self.dist_delay);
+        packet.set_dist_jitter(self.dist_jitter);
+    }
+}
+
+impl<T: AsRef<[u8]>> Parseable<TcNetemSlotBuffer<T>> for TcNetemSlot {
+    fn parse(buf: &TcNetemSlotBuffer<T>) -> Result<Self, DecodeError> {
+        Ok(Self {
+            min_delay: buf.min_delay(),
+            max_delay: buf.max_delay(),
+            max_packets: buf.max_packets(),
+            max_bytes: buf.max_bytes(),
+            dist_delay: buf.dist_delay(),
+            dist_jitter: buf.dist_jitter(),
+        })
+    }
+}
+
+const NETEM_LOSS_GI: u16 = 1;
+const NETEM_LOSS_GE: u16 = 2;
+
+/// Loss models nested in `TCA_NETEM_LOSS`
+#[derive(Debug, PartialEq, Eq, Clone)]
+#[non_exhaustive]
+pub enum TcNetemLoss {
+    /// General Intuitive - 4 state model
+    Gi(TcNetemGiModel),
+    /// Gilbert Elliot model
+    Ge(TcNetemGeModel),
+    Other(DefaultNla),
+}
+
+impl Nla for TcNetemLoss {
+    fn value_len(&self) -> usize {
+        match self {
+            Self::Gi(v) => v.buffer_len(),
+            Self::Ge(v) => v.buffer_len(),
+            Self::Other(attr) => attr.value_len(),
+        }
+    }
+
+    fn emit_value(&self, buffer: &mut [u8]) {
+        match self {
+            Self::Gi(v) => v.emit(buffer),
+            Self::Ge(v) => v.emit(buffer),
+            Self::Other(attr) => attr.emit_value(buffer),
+        }
+    }
+
+    fn kind(&self) -> u16 {
+        match self {
+            Self::Gi(_) => NETEM_LOSS_GI,
+            Self::Ge(_) => NETEM_LOSS_GE,
+            Self::Other(attr) => attr.kind(),
+        }
+    }
+}
+
+impl<'a, T: AsRef<[u8]> + ?Sized> Parseable<NlaBuffer<&'a T>> for TcNetemLoss {
+    fn parse(buf: &NlaBuffer<&'a T>) -> Result<Self, DecodeError> {
+        let payload = buf.value();
+        Ok(match buf.kind() {
+            NETEM_LOSS_GI => Self::Gi(
+                TcNetemGiModel::parse(
+                    &TcNetemGiModelBuffer::new_checked(payload)
+                        .context("invalid NETEM_LOSS_GI")?,
+                )
+                .context("failed to parse NETEM_LOSS_GI")?,
+            ),
+            NETEM_LOSS_GE => Self::Ge(
+                TcNetemGeModel::parse(
+                    &TcNetemGeModelBuffer::new_checked(payload)
+                        .context("invalid NETEM_LOSS_GE")?,
+                )
+                .context("failed to parse NETEM_LOSS_GE")?,
+            ),
+            _ => Self::Other(
+                DefaultNla::parse(buf)
+                    .context("failed to parse netem loss nla")?,
+            ),
+        })
+    }
+}
+
+const TC_NETEM_GIMODEL_BUF_LEN: usize = 20;
+
+/// State transition probabilities for 4 state model, kernel struct
+/// `tc_netem_gimodel`
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Default)]
+#[non_exhaustive]
+pub struct TcNetemGiModel {
+    pub p13: u32,
+    pub p31: u32,
+    pub p32: u32,
+    pub p14: u32,
+    pub p23: u32,
+}
+
+buffer!(TcNetemGiModelBuffer(TC_NETEM_GIMODEL_BUF_LEN) {
+    p13: (u32, 0..4),
+    p31: (u32, 4..8),
+    p32: (u32, 8..12),
+    p14: (u32, 12..16),
+    p23: (u32, 16..TC_NETEM_GIMODEL_BUF_LEN),
+});
+
+impl Emitable for TcNetemGiModel {
+    fn buffer_len(&self) -> usize {
+        TC_NETEM_GIMODEL_BUF_LEN
+    }
+
+    fn emit(&self, buffer: &mut [u8]) {
+        let mut packet = TcNetemGiModelBuffer::new(buffer);
+        packet.set_p13(self.p13);
+        packet.set_p31(self.p31);
+        packet.set_p32(self.p32);
+        packet.set_p14(self.p14);
+        packet.set_p23(self.p23);
+    }
+}
+
+impl<T: AsRef<[u8]>> Parseable<TcNetemGiModelBuffer<T>> for TcNetemGiModel {
+    fn parse(buf: &TcNetemGiModelBuffer<T>) -> Result<Self, DecodeError> {
+        Ok(Self {
+            p13: buf.p13(),
+            p31: buf.p31(),
+            p32: buf.p32(),
+            p14: buf.p14(),
+            p23: buf.p23(),
+        })
+    }
+}
+
+const TC_NETEM_GEMODEL_BUF_LEN: usize = 16;
+
+/// Gilbert-Elliot model, kernel struct `tc_netem_gemodel`
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Default)]
+#[non_exhaustive]
+pub struct TcNetemGeModel {
+    pub p: u32,
+    pub r: u32,
+    pub h: u32,
+    pub k1: u32,
+}
+
+buffer!(TcNetemGeModelBuffer(TC_NETEM_GEMODEL_BUF_LEN) {
+    p: (u32, 0..4),
+    r: (u32, 4..8),
+    h: (u32, 8..12),
+    k1: (u32, 12..TC_NETEM_GEMODEL_BUF_LEN),
+});
+
+impl Emitable for TcNetemGeModel {
+    fn buffer_len(&self) -> usize {
+        TC_NETEM_GEMODEL_BUF_LEN
+    }
+
+    fn emit(&self, buffer: &mut [u8]) {
+        let mut packet = TcNetemGeModelBuffer::new(buffer);
+        packet.set_p(self.p);
+        packet.set_r(self.r);
+        packet.set_h(self.h);
+        packet.set_k1(self.k1);
+    }
+}
+
+impl<T: AsRef<[u8]>> Parseable<TcNetemGeModelBuffer<T>> for TcNetemGeModel {
+    fn parse(buf: &TcNetemGeModelBuffer<T>) -> Result<Self, DecodeError> {
+        Ok(Self {
+            p: buf.p(),
+            r: buf.r(),
+            h: buf.h(),
+            k1: buf.k1(),
+        })
+    }
+}
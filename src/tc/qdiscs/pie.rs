@@ -0,0 +1,211 @@
+// SPDX-License-Identifier: MIT
+
+/// Proportional Integral controller Enhanced
+///
+/// The `pie` qdisc drops packets randomly with probability updated every
+/// `tupdate` to keep the queueing delay around `target`.
+use anyhow::Context;
+use byteorder::{ByteOrder, NativeEndian};
+use netlink_packet_utils::{
+    nla::{DefaultNla, Nla, NlaBuffer},
+    parsers::parse_u32,
+    traits::{Emitable, Parseable},
+    DecodeError,
+};
+
+#[derive(Debug, PartialEq, Eq, Clone)]
+#[non_exhaustive]
+pub struct TcQdiscPie {}
+
+impl TcQdiscPie {
+    pub(crate) const KIND: &'static str = "pie";
+}
+
+const TCA_PIE_TARGET: u16 = 1;
+const TCA_PIE_LIMIT: u16 = 2;
+const TCA_PIE_TUPDATE: u16 = 3;
+const TCA_PIE_ALPHA: u16 = 4;
+const TCA_PIE_BETA: u16 = 5;
+const TCA_PIE_ECN: u16 = 6;
+const TCA_PIE_BYTEMODE: u16 = 7;
+const TCA_PIE_DQ_RATE_ESTIMATOR: u16 = 8;
+
+#[derive(Debug, PartialEq, Eq, Clone)]
+#[non_exhaustive]
+pub enum TcQdiscPieOption {
+    /// Target queueing delay in microseconds
+    Target(u32),
+    /// Hard limit on queue length in packets
+    Limit(u32),
+    /// Interval in microseconds of drop probability update
+    Tupdate(u32),
+    /// Weight of current delay deviation, 0 to 32
+    Alpha(u32),
+    /// Weight of delay trend, 0 to 32
+    Beta(u32),
+    /// Mark packets with ECN instead of dropping when set to 1
+    Ecn(u32),
+    /// Scale drop probability by packet size when set to 1
+    Bytemode(u32),
+    /// Use dequeue rate to calculate queueing delay when set
+    /// to 1
+    DqRateEstimator(u32),
+    Other(DefaultNla),
+}
+
+impl Nla for TcQdiscPieOption {
+    fn value_len(&self) -> usize {
+        match self {
+            Self::Target(_)
+            | Self::Limit(_)
+            | Self::Tupdate(_)
+            | Self::Alpha(_)
+            | Self::Beta(_)
+            | Self::Ecn(_)
+            | Self::Bytemode(_)
+            | Self::DqRateEstimator(_) => 4,
+            Self::Other(attr) => attr.value_len(),
+        }
+    }
+
+    fn emit_value(&self, buffer: &mut [u8]) {
+        match self {
+            Self::Target(d)
+            | Self::Limit(d)
+            | Self::Tupdate(d)
+            | Self::Alpha(d)
+            | Self::Beta(d)
+            | Self::Ecn(d)
+            | Self::Bytemode(d)
+            | Self::DqRateEstimator(d) => NativeEndian::write_u32(buffer, *d),
+            Self::Other(attr) => attr.emit_value(buffer),
+        }
+    }
+
+    fn kind(&self) -> u16 {
+        match self {
+            Self::Target(_) => TCA_PIE_TARGET,
+            Self::Limit(_) => TCA_PIE_LIMIT,
+            Self::Tupdate(_) => TCA_PIE_TUPDATE,
+            Self::Alpha(_) => TCA_PIE_ALPHA,
+            Self::Beta(_) => TCA_PIE_BETA,
+            Self::Ecn(_) => TCA_PIE_ECN,
+            Self::Bytemode(_) => TCA_PIE_BYTEMODE,
+            Self::DqRateEstimator(_) => TCA_PIE_DQ_RATE_ESTIMATOR,
+            Self::Other(attr) => attr.kind(),
+        }
+    }
+}
+
+impl<'a, T: AsRef<[u8]> + ?Sized> Parseable<NlaBuffer<&'a T>>
+    for TcQdiscPieOption
+{
+    fn parse(buf: &NlaBuffer<&'a T>) -> Result<Self, DecodeError> {
+        let payload = buf.value();
+        Ok(match buf.kind() {
+            TCA_PIE_TARGET => Self::Target(
+                parse_u32(payload).context("failed to parse TCA_PIE_TARGET")?,
+            ),
+            TCA_PIE_LIMIT => Self::Limit(
+                parse_u32(payload).context("failed to parse TCA_PIE_LIMIT")?,
+            ),
+            TCA_PIE_TUPDATE => Self::Tupdate(
+                parse_u32(payload)
+                    .context("failed to parse TCA_PIE_TUPDATE")?,
+            ),
+            TCA_PIE_ALPHA => Self::Alpha(
+                parse_u32(payload).context("failed to parse TCA_PIE_ALPHA")?,
+            ),
+            TCA_PIE_BETA => Self::Beta(
+                parse_u32(payload).context("failed to parse TCA_PIE_BETA")?,
+            ),
+            TCA_PIE_ECN => Self::Ecn(
+                parse_u32(payload).context("failed to parse TCA_PIE_ECN")?,
+            ),
+            TCA_PIE_BYTEMODE => Self::Bytemode(
+                parse_u32(payload)
+                    .context("failed to parse TCA_PIE_BYTEMODE")?,
+            ),
+            TCA_PIE_DQ_RATE_ESTIMATOR => Self::DqRateEstimator(
+                parse_u32(payload)
+                    .context("failed to parse TCA_PIE_DQ_RATE_ESTIMATOR")?,
+            ),
+            _ => Self::Other(
+                DefaultNla::parse(buf).context("failed to parse pie nla")?,
+            ),
+        })
+    }
+}
+
+const TC_PIE_XSTATS_BUF_LEN: usize = 40;
+
+// kernel struct `tc_pie_xstats`
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Default)]
+#[non_exhaustive]
+pub struct TcPieXstats {
+    /// Current drop probability
+    pub prob: u64,
+    /// Current delay in milliseconds
+    pub delay: u32,
+    /// Current average dequeue rate in bits per PIE time
+    pub avg_dq_rate: u32,
+    /// Whether `avg_dq_rate` is being calculated
+    pub dq_rate_estimating: u32,
+    /// Total packets enqueued
+    pub packets_in: u32,
+    /// Packets dropped by PIE
+    pub dropped: u32,
+    /// Packets dropped due to lack of space in queue
+    pub overlimit: u32,
+    /// Maximum queue size
+    pub maxq: u32,
+    /// Packets marked with ECN
+    pub ecn_mark: u32,
+}
+
+buffer!(TcPieXstatsBuffer(TC_PIE_XSTATS_BUF_LEN) {
+    prob: (u64, 0..8),
+    delay: (u32, 8..12),
+    avg_dq_rate: (u32, 12..16),
+    dq_rate_estimating: (u32, 16..20),
+    packets_in: (u32, 20..24),
+    dropped: (u32, 24..28),
+    overlimit: (u32, 28..32),
+    maxq: (u32, 32..36),
+    ecn_mark: (u32, 36..TC_PIE_XSTATS_BUF_LEN),
+});
+
+impl Emitable for TcPieXstats {
+    fn buffer_len(&self) -> usize {
+        TC_PIE_XSTATS_BUF_LEN
+    }
+
+    fn emit(&self, buffer: &mut [u8]) {
+        let mut packet = TcPieXstatsBuffer::new(buffer);
+        packet.set_prob(self.prob);
+        packet.set_delay(self.delay);
+        packet.set_avg_dq_rate(self.avg_dq_rate);
+        packet.set_dq_rate_estimating(self.dq_rate_estimating);
+        packet.set_packets_in(self.packets_in);
+        packet.set_dropped(self.dropped);
+        packet.set_overlimit(self.overlimit);
+        packet.set_maxq(self.maxq);
+        packet.set_ecn_mark(self.ecn_mark);
+    }
+}
+
+impl<T: AsRef<[u8]>> Parseable<TcPieXstatsBuffer<T>> for TcPieXstats {
+    fn parse(buf: &TcPieXstatsBuffer<T>) -> Result<Self, DecodeError> {
+        Ok(Self {
+            prob: buf.prob(),
+            delay: buf.delay(),
+            avg_dq_rate: buf.avg_dq_rate(),
+            dq_rate_estimating: buf.dq_rate_estimating(),
+            packets_in: buf.packets_in(),
+            dropped: buf.dropped(),
+            overlimit: buf.overlimit(),
+            maxq: buf.maxq(),
+            ecn_mark: buf.ecn_mark(),
+        })
+    }
+}
@@ -0,0 +1,136 @@
+// SPDX-License-Identifier: MIT
+
+/// Priority qdiscs
+///
+/// The `prio` qdisc dequeues from the lowest numbered band first, packets
+/// are placed into bands by the `priomap` of their priority.
+/// The `pfifo_fast` qdisc is the fixed 3 bands version of it.
+use anyhow::Context;
+use netlink_packet_utils::{
+    nla::{DefaultNla, Nla, NlasIterator},
+    traits::{Emitable, Parseable},
+    DecodeError,
+};
+
+#[derive(Debug, PartialEq, Eq, Clone)]
+#[non_exhaustive]
+pub struct TcQdiscPrio {}
+
+impl TcQdiscPrio {
+    pub(crate) const KIND: &'static str = "prio";
+}
+
+/// The `pfifo_fast` qdisc is using `TcQdiscPrioOption` as options.
+#[derive(Debug, PartialEq, Eq, Clone)]
+#[non_exhaustive]
+pub struct TcQdiscPfifoFast {}
+
+impl TcQdiscPfifoFast {
+    pub(crate) const KIND: &'static str = "pfifo_fast";
+}
+
+#[derive(Debug, PartialEq, Eq, Clone)]
+#[non_exhaustive]
+pub enum TcQdiscPrioOption {
+    /// The `tc_prio_qopt` placed at the beginning of TCA_OPTIONS without
+    /// NLA header. Should be the first option when emitting.
+    Qopt(TcPrioQopt),
+    /// NLAs following `tc_prio_qopt`, iproute2 places an empty nested
+    /// TCA_OPTIONS here.
+    Other(DefaultNla),
+}
+
+impl Nla for TcQdiscPrioOption {
+    fn value_len(&self) -> usize {
+        match self {
+            Self::Qopt(v) => v.buffer_len(),
+            Self::Other(attr) => attr.value_len(),
+        }
+    }
+
+    fn emit_value(&self, buffer: &mut [u8]) {
+        match self {
+            Self::Qopt(v) => v.emit(buffer),
+            Self::Other(attr) => attr.emit_value(buffer),
+        }
+    }
+
+    fn kind(&self) -> u16 {
+        match self {
+            // Emitted without NLA header, see `TcQdiscPrioOption::Qopt`
+            Self::Qopt(_) => 0,
+            Self::Other(attr) => attr.kind(),
+        }
+    }
+}
+
+pub(crate) fn parse_prio_options(
+    payload: &[u8],
+) -> Result<Vec<TcQdiscPrioOption>, DecodeError> {
+    let mut options = vec![TcQdiscPrioOption::Qopt(
+        TcPrioQopt::parse(
+            &TcPrioQoptBuffer::new_checked(payload)
+                .context("invalid tc_prio_qopt")?,
+        )
+        .context("failed to parse tc_prio_qopt")?,
+    )];
+    for nla in NlasIterator::new(&payload[TC_PRIO_QOPT_BUF_LEN..]) {
+        let nla = nla.context("invalid prio TCA_OPTIONS")?;
+        options.push(TcQdiscPrioOption::Other(
+            DefaultNla::parse(&nla)
+                .context("failed to parse prio TCA_OPTIONS")?,
+        ));
+    }
+    Ok(options)
+}
+
+const TC_PRIO_MAX: usize = 15;
+const TC_PRIO_QOPT_BUF_LEN: usize = 4 + TC_PRIO_MAX + 1;
+
+// kernel struct `tc_prio_qopt`
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+#[non_exhaustive]
+pub struct TcPrioQopt {
+    /// Number of bands
+    pub bands: i32,
+    /// Map from logical priority to band
+    pub priomap: [u8; TC_PRIO_MAX + 1],
+}
+
+impl Default for TcPrioQopt {
+    // Kernel default `sch_default_prio2band` with 3 bands
+    fn default() -> Self {
+        Self {
+            bands: 3,
+            priomap: [1, 2, 2, 2, 1, 2, 0, 0, 1, 1, 1, 1, 1, 1, 1, 1],
+        }
+    }
+}
+
+buffer!(TcPrioQoptBuffer(TC_PRIO_QOPT_BUF_LEN) {
+    bands: (i32, 0..4),
+    priomap: (slice, 4..TC_PRIO_QOPT_BUF_LEN),
+});
+
+impl Emitable for TcPrioQopt {
+    fn buffer_len(&self) -> usize {
+        TC_PRIO_QOPT_BUF_LEN
+    }
+
+    fn emit(&self, buffer: &mut [u8]) {
+        let mut packet = TcPrioQoptBuffer::new(buffer);
+        packet.set_bands(self.bands);
+        packet.priomap_mut().copy_from_slice(&self.priomap);
+    }
+}
+
+impl<T: AsRef<[u8]> + ?Sized> Parseable<TcPrioQoptBuffer<&T>> for TcPrioQopt {
+    fn parse(buf: &TcPrioQoptBuffer<&T>) -> Result<Self, DecodeError> {
+        let mut priomap = [0u8; TC_PRIO_MAX + 1];
+        priomap.copy_from_slice(buf.priomap());
+        Ok(Self {
+            bands: buf.bands(),
+            priomap,
+        })
+    }
+}
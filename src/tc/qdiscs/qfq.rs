@@ -0,0 +1,116 @@
+// SPDX-License-Identifier: MIT
+
+/// Quick Fair Queueing
+///
+/// The `qfq` qdisc has no options, its classes share bandwidth in
+/// proportion to their `weight` with `lmax` as maximum packet size.
+use anyhow::Context;
+use byteorder::{ByteOrder, NativeEndian};
+use netlink_packet_utils::{
+    nla::{DefaultNla, Nla, NlaBuffer},
+    parsers::parse_u32,
+    traits::{Emitable, Parseable},
+    DecodeError,
+};
+
+#[derive(Debug, PartialEq, Eq, Clone)]
+#[non_exhaustive]
+pub struct TcQdiscQfq {}
+
+impl TcQdiscQfq {
+    pub(crate) const KIND: &'static str = "qfq";
+}
+
+const TCA_QFQ_WEIGHT: u16 = 1;
+const TCA_QFQ_LMAX: u16 = 2;
+
+#[derive(Debug, PartialEq, Eq, Clone)]
+#[non_exhaustive]
+pub enum TcQdiscQfqOption {
+    /// Weight of the class
+    Weight(u32),
+    /// Maximum packet size in bytes of the class
+    Lmax(u32),
+    Other(DefaultNla),
+}
+
+impl Nla for TcQdiscQfqOption {
+    fn value_len(&self) -> usize {
+        match self {
+            Self::Weight(_) | Self::Lmax(_) => 4,
+            Self::Other(attr) => attr.value_len(),
+        }
+    }
+
+    fn emit_value(&self, buffer: &mut [u8]) {
+        match self {
+            Self::Weight(d) | Self::Lmax(d) => {
+                NativeEndian::write_u32(buffer, *d)
+            }
+            Self::Other(attr) => attr.emit_value(buffer),
+        }
+    }
+
+    fn kind(&self) -> u16 {
+        match self {
+            Self::Weight(_) => TCA_QFQ_WEIGHT,
+            Self::Lmax(_) => TCA_QFQ_LMAX,
+            Self::Other(attr) => attr.kind(),
+        }
+    }
+}
+
+impl<'a, T: AsRef<[u8]> + ?Sized> Parseable<NlaBuffer<&'a T>>
+    for TcQdiscQfqOption
+{
+    fn parse(buf: &NlaBuffer<&'a T>) -> Result<Self, DecodeError> {
+        let payload = buf.value();
+        Ok(match buf.kind() {
+            TCA_QFQ_WEIGHT => Self::Weight(
+                parse_u32(payload).context("failed to parse TCA_QFQ_WEIGHT")?,
+            ),
+            TCA_QFQ_LMAX => Self::Lmax(
+                parse_u32(payload).context("failed to parse TCA_QFQ_LMAX")?,
+            ),
+            _ => Self::Other(
+                DefaultNla::parse(buf).context("failed to parse qfq nla")?,
+            ),
+        })
+    }
+}
+
+const TC_QFQ_STATS_BUF_LEN: usize = 8;
+
+// kernel struct `tc_qfq_stats`
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Default)]
+#[non_exhaustive]
+pub struct TcQfqStats {
+    pub weight: u32,
+    pub lmax: u32,
+}
+
+buffer!(TcQfqStatsBuffer(TC_QFQ_STATS_BUF_LEN) {
+    weight: (u32, 0..4),
+    lmax: (u32, 4..TC_QFQ_STATS_BUF_LEN),
+});
+
+impl Emitable for TcQfqStats {
+    fn buffer_len(&self) -> usize {
+        TC_QFQ_STATS_BUF_LEN
+    }
+
+    fn emit(&self, buffer: &mut [u8]) {
+        let mut packet = TcQfqStatsBuffer::new(buffer);
+        packet.set_weight(self.weight);
+        packet.set_lmax(self.lmax);
+    }
+}
+
+impl<T: AsRef<[u8]>> Parseable<TcQfqStatsBuffer<T>> for TcQfqStats {
+    fn parse(buf: &TcQfqStatsBuffer<T>) -> Result<Self, DecodeError> {
+        Ok(Self {
+            weight: buf.weight(),
+            lmax: buf.lmax(),
+        })
+    }
+}
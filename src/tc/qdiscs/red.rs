@@ -0,0 +1,342 @@
+// SPDX-License-Identifier: MIT
+
+/// Random Early Detection
+///
+/// RED drops or marks packets with probability growing with the average
+/// queue length between `qth_min` and `qth_max`.
+use anyhow::Context;
+use byteorder::{ByteOrder, NativeEndian};
+use netlink_packet_utils::{
+    nla::{DefaultNla, Nla, NlaBuffer},
+    parsers::parse_u32,
+    traits::{Emitable, Parseable},
+    DecodeError,
+};
+
+#[derive(Debug, PartialEq, Eq, Clone)]
+#[non_exhaustive]
+pub struct TcQdiscRed {}
+
+impl TcQdiscRed {
+    pub(crate) const KIND: &'static str = "red";
+}
+
+const TCA_RED_PARMS: u16 = 1;
+const TCA_RED_STAB: u16 = 2;
+const TCA_RED_MAX_P: u16 = 3;
+const TCA_RED_FLAGS: u16 = 4;
+const TCA_RED_EARLY_DROP_BLOCK: u16 = 5;
+const TCA_RED_MARK_BLOCK: u16 = 6;
+
+#[derive(Debug, PartialEq, Eq, Clone)]
+#[non_exhaustive]
+pub enum TcQdiscRedOption {
+    Parms(TcRedQopt),
+    /// Idle damping lookup table of 256 cells, could be generated by
+    /// `TcPsched::calc_red_stab()`
+    Stab(Vec<u8>),
+    /// Maximum drop probability in fixed point of 32 bits fraction
+    MaxP(u32),
+    Flags(TcRedFlags),
+    /// Shared block index to send early dropped packets to
+    EarlyDropBlock(u32),
+    /// Shared block index to send ECN marked packets to
+    MarkBlock(u32),
+    Other(DefaultNla),
+}
+
+impl Nla for TcQdiscRedOption {
+    fn value_len(&self) -> usize {
+        match self {
+            Self::Parms(v) => v.buffer_len(),
+            Self::Stab(v) => v.len(),
+            Self::Flags(v) => v.buffer_len(),
+            Self::MaxP(_) | Self::EarlyDropBlock(_) | Self::MarkBlock(_) => 4,
+            Self::Other(attr) => attr.value_len(),
+        }
+    }
+
+    fn emit_value(&self, buffer: &mut [u8]) {
+        match self {
+            Self::Parms(v) => v.emit(buffer),
+            Self::Stab(v) => buffer.copy_from_slice(v.as_slice()),
+            Self::Flags(v) => v.emit(buffer),
+            Self::MaxP(d) | Self::EarlyDropBlock(d) | Self::MarkBlock(d) => {
+                NativeEndian::write_u32(buffer, *d)
+            }
+            Self::Other(attr) => attr.emit_value(buffer),
+        }
+    }
+
+    fn kind(&self) -> u16 {
+        match self {
+            Self::Parms(_) => TCA_RED_PARMS,
+            Self::Stab(_) => TCA_RED_STAB,
+            Self::MaxP(_) => TCA_RED_MAX_P,
+            Self::Flags(_) => TCA_RED_FLAGS,
+            Self::EarlyDropBlock(_) => TCA_RED_EARLY_DROP_BLOCK,
+            Self::MarkBlock(_) => TCA_RED_MARK_BLOCK,
+            Self::Other(attr) => attr.kind(),
+        }
+    }
+}
+
+impl<'a, T: AsRef<[u8]> + ?Sized> Parseable<NlaBuffer<&'a T>>
+    for TcQdiscRedOption
+{
+    fn parse(buf: &NlaBuffer<&'a T>) -> Result<Self, DecodeError> {
+        let payload = buf.value();
+        Ok(match buf.kind() {
+            TCA_RED_PARMS => Self::Parms(
+                TcRedQopt::parse(
+                    &TcRedQoptBuffer::new_checked(payload)
+                        .context("invalid TCA_RED_PARMS")?,
+                )
+                .context("failed to parse TCA_RED_PARMS")?,
+            ),
+            TCA_RED_STAB => Self::Stab(payload.to_vec()),
+            TCA_RED_MAX_P => Self::MaxP(
+                parse_u32(payload).context("failed to parse TCA_RED_MAX_P")?,
+            ),
+            TCA_RED_FLAGS => Self::Flags(
+                TcRedFlags::parse(
+                    &TcRedFlagsBuffer::new_checked(payload)
+                        .context("invalid TCA_RED_FLAGS")?,
+                )
+                .context("failed to parse TCA_RED_FLAGS")?,
+            ),
+            TCA_RED_EARLY_DROP_BLOCK => Self::EarlyDropBlock(
+                parse_u32(payload)
+                    .context("failed to parse TCA_RED_EARLY_DROP_BLOCK")?,
+            ),
+            TCA_RED_MARK_BLOCK => Self::MarkBlock(
+                parse_u32(payload)
+                    .context("failed to parse TCA_RED_MARK_BLOCK")?,
+            ),
+            _ => Self::Other(
+                DefaultNla::parse(buf).context("failed to parse red nla")?,
+            ),
+        })
+    }
+}
+
+const TC_RED_QOPT_BUF_LEN: usize = 16;
+
+// kernel struct `tc_red_qopt`
+#[derive(Debug, PartialEq, Eq, Clone, Default)]
+#[non_exhaustive]
+pub struct TcRedQopt {
+    /// Hard limit on queue length in bytes
+    pub limit: u32,
+    /// Min average length threshold, scaled by `wlog`
+    pub qth_min: u32,
+    /// Max average length threshold, scaled by `wlog`
+    pub qth_max: u32,
+    /// log(W)
+    pub wlog: u8,
+    /// log(P_max/(qth_max-qth_min))
+    pub plog: u8,
+    /// Cell size for idle damping
+    pub scell_log: u8,
+    pub flags: Vec<TcRedFlag>,
+}
+
+buffer!(TcRedQoptBuffer(TC_RED_QOPT_BUF_LEN) {
+    limit: (u32, 0..4),
+    qth_min: (u32, 4..8),
+    qth_max: (u32, 8..12),
+    wlog: (u8, 12),
+    plog: (u8, 13),
+    scell_log: (u8, 14),
+    flags: (u8, 15),
+});
+
+impl Emitable for TcRedQopt {
+    fn buffer_len(&self) -> usize {
+        TC_RED_QOPT_BUF_LEN
+    }
+
+    fn emit(&self, buffer: &mut [u8]) {
+        let mut packet = TcRedQoptBuffer::new(buffer);
+        packet.set_limit(self.limit);
+        packet.set_qth_min(self.qth_min);
+        packet.set_qth_max(self.qth_max);
+        packet.set_wlog(self.wlog);
+        packet.set_plog(self.plog);
+        packet.set_scell_log(self.scell_log);
+        // Only the historic flags fit into `tc_red_qopt`, the others are
+        // carried by `TcQdiscRedOption::Flags`
+        packet.set_flags(
+            (u32::from(&VecTcRedFlag(self.flags.to_vec())) & u32::from(u8::MAX))
+                as u8,
+        );
+    }
+}
+
+impl<T: AsRef<[u8]>> Parseable<TcRedQoptBuffer<T>> for TcRedQopt {
+    fn parse(buf: &TcRedQoptBuffer<T>) -> Result<Self, DecodeError> {
+        Ok(Self {
+            limit: buf.limit(),
+            qth_min: buf.qth_min(),
+            qth_max: buf.qth_max(),
+            wlog: buf.wlog(),
+            plog: buf.plog(),
+            scell_log: buf.scell_log(),
+            flags: VecTcRedFlag::from(u32::from(buf.flags())).0,
+        })
+    }
+}
+
+const TC_RED_ECN: u32 = 1;
+const TC_RED_HARDDROP: u32 = 2;
+const TC_RED_ADAPTATIVE: u32 = 4;
+const TC_RED_NODROP: u32 = 8;
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+#[non_exhaustive]
+pub enum TcRedFlag {
+    /// Mark packets with ECN instead of dropping
+    Ecn,
+    /// Drop packets above `qth_max` even when ECN is enabled
+    HardDrop,
+    /// Adaptive RED adjusting `max_P` automatically
+    Adaptative,
+    /// Never drop ECN capable packets
+    NoDrop,
+    Other(u32),
+}
+
+impl From<TcRedFlag> for u32 {
+    fn from(v: TcRedFlag) -> u32 {
+        match v {
+            TcRedFlag::Ecn => TC_RED_ECN,
+            TcRedFlag::HardDrop => TC_RED_HARDDROP,
+            TcRedFlag::Adaptative => TC_RED_ADAPTATIVE,
+            TcRedFlag::NoDrop => TC_RED_NODROP,
+            TcRedFlag::Other(i) => i,
+        }
+    }
+}
+
+const ALL_RED_FLAGS: [TcRedFlag; 4] = [
+    TcRedFlag::Ecn,
+    TcRedFlag::HardDrop,
+    TcRedFlag::Adaptative,
+    TcRedFlag::NoDrop,
+];
+
+#[derive(Clone, Eq, PartialEq, Debug)]
+pub(crate) struct VecTcRedFlag(pub(crate) Vec<TcRedFlag>);
+
+impl From<u32> for VecTcRedFlag {
+    fn from(d: u32) -> Self {
+        let mut got: u32 = 0;
+        let mut ret = Vec::new();
+        for flag in ALL_RED_FLAGS {
+            if (d & (u32::from(flag))) > 0 {
+                ret.push(flag);
+                got += u32::from(flag);
+            }
+        }
+        if got != d {
+            ret.push(TcRedFlag::Other(d - got));
+        }
+        Self(ret)
+    }
+}
+
+impl From<&VecTcRedFlag> for u32 {
+    fn from(v: &VecTcRedFlag) -> u32 {
+        let mut d: u32 = 0;
+        for flag in &v.0 {
+            d += u32::from(*flag);
+        }
+        d
+    }
+}
+
+const TC_RED_FLAGS_BUF_LEN: usize = 8;
+
+// kernel struct `nla_bitfield32` of TCA_RED_FLAGS
+#[derive(Debug, PartialEq, Eq, Clone, Default)]
+#[non_exhaustive]
+pub struct TcRedFlags {
+    /// Flags to set
+    pub value: Vec<TcRedFlag>,
+    /// Flags to change, flags not selected are left untouched
+    pub selector: Vec<TcRedFlag>,
+}
+
+buffer!(TcRedFlagsBuffer(TC_RED_FLAGS_BUF_LEN) {
+    value: (u32, 0..4),
+    selector: (u32, 4..TC_RED_FLAGS_BUF_LEN),
+});
+
+impl Emitable for TcRedFlags {
+    fn buffer_len(&self) -> usize {
+        TC_RED_FLAGS_BUF_LEN
+    }
+
+    fn emit(&self, buffer: &mut [u8]) {
+        let mut packet = TcRedFlagsBuffer::new(buffer);
+        packet.set_value(u32::from(&VecTcRedFlag(self.value.to_vec())));
+        packet.set_selector(u32::from(&VecTcRedFlag(self.selector.to_vec())));
+    }
+}
+
+impl<T: AsRef<[u8]>> Parseable<TcRedFlagsBuffer<T>> for TcRedFlags {
+    fn parse(buf: &TcRedFlagsBuffer<T>) -> Result<Self, DecodeError> {
+        Ok(Self {
+            value: VecTcRedFlag::from(buf.value()).0,
+            selector: VecTcRedFlag::from(buf.selector()).0,
+        })
+    }
+}
+
+const TC_RED_XSTATS_BUF_LEN: usize = 16;
+
+// kernel struct `tc_red_xstats`
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Default)]
+#[non_exhaustive]
+pub struct TcRedXstats {
+    /// Early drops
+    pub early: u32,
+    /// Drops due to queue limits
+    pub pdrop: u32,
+    /// Drops due to drop() calls
+    pub other: u32,
+    /// Marked packets
+    pub marked: u32,
+}
+
+buffer!(TcRedXstatsBuffer(TC_RED_XSTATS_BUF_LEN) {
+    early: (u32, 0..4),
+    pdrop: (u32, 4..8),
+    other: (u32, 8..12),
+    marked: (u32, 12..TC_RED_XSTATS_BUF_LEN),
+});
+
+impl Emitable for TcRedXstats {
+    fn buffer_len(&self) -> usize {
+        TC_RED_XSTATS_BUF_LEN
+    }
+
+    fn emit(&self, buffer: &mut [u8]) {
+        let mut packet = TcRedXstatsBuffer::new(buffer);
+        packet.set_early(self.early);
+        packet.set_pdrop(self.pdrop);
+        packet.set_other(self.other);
+        packet.set_marked(self.marked);
+    }
+}
+
+impl<T: AsRef<[u8]>> Parseable<TcRedXstatsBuffer<T>> for TcRedXstats {
+    fn parse(buf: &TcRedXstatsBuffer<T>) -> Result<Self, DecodeError> {
+        Ok(Self {
+            early: buf.early(),
+            pdrop: buf.pdrop(),
+            other: buf.other(),
+            marked: buf.marked(),
+        })
+    }
+}
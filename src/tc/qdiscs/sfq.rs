@@ -0,0 +1,279 @@
+// SPDX-License-Identifier: MIT
+
+/// Stochastic Fairness Queueing
+///
+/// The `sfq` qdisc places the `tc_sfq_qopt` or `tc_sfq_qopt_v1` struct
+/// as the whole TCA_OPTIONS without any NLA.
+use anyhow::Context;
+use netlink_packet_utils::{
+    nla::Nla,
+    traits::{Emitable, Parseable},
+    DecodeError,
+};
+
+use crate::tc::{TcRedQopt, TcRedQoptBuffer};
+
+#[derive(Debug, PartialEq, Eq, Clone)]
+#[non_exhaustive]
+pub struct TcQdiscSfq {}
+
+impl TcQdiscSfq {
+    pub(crate) const KIND: &'static str = "sfq";
+}
+
+#[derive(Debug, PartialEq, Eq, Clone)]
+#[non_exhaustive]
+pub enum TcQdiscSfqOption {
+    /// The original `tc_sfq_qopt` without NLA header
+    Qopt(TcSfqQopt),
+    /// The `tc_sfq_qopt_v1` without NLA header, kernel is always replying
+    /// with this version.
+    QoptV1(TcSfqQoptV1),
+}
+
+impl Nla for TcQdiscSfqOption {
+    fn value_len(&self) -> usize {
+        match self {
+            Self::Qopt(v) => v.buffer_len(),
+            Self::QoptV1(v) => v.buffer_len(),
+        }
+    }
+
+    fn emit_value(&self, buffer: &mut [u8]) {
+        match self {
+            Self::Qopt(v) => v.emit(buffer),
+            Self::QoptV1(v) => v.emit(buffer),
+        }
+    }
+
+    fn kind(&self) -> u16 {
+        match self {
+            // Emitted without NLA header, see `TcQdiscSfqOption::Qopt`
+            Self::Qopt(_) | Self::QoptV1(_) => 0,
+        }
+    }
+}
+
+pub(crate) fn parse_sfq_options(
+    payload: &[u8],
+) -> Result<Vec<TcQdiscSfqOption>, DecodeError> {
+    // Like kernel, use `tc_sfq_qopt_v1` when payload is large enough
+    Ok(vec![if payload.len() >= TC_SFQ_QOPT_V1_BUF_LEN {
+        TcQdiscSfqOption::QoptV1(
+            TcSfqQoptV1::parse(
+                &TcSfqQoptV1Buffer::new_checked(payload)
+                    .context("invalid tc_sfq_qopt_v1")?,
+            )
+            .context("failed to parse tc_sfq_qopt_v1")?,
+        )
+    } else {
+        TcQdiscSfqOption::Qopt(
+            TcSfqQopt::parse(
+                &TcSfqQoptBuffer::new_checked(payload)
+                    .context("invalid tc_sfq_qopt")?,
+            )
+            .context("failed to parse tc_sfq_qopt")?,
+        )
+    }])
+}
+
+const TC_SFQ_QOPT_BUF_LEN: usize = 20;
+
+// kernel struct `tc_sfq_qopt`
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Default)]
+#[non_exhaustive]
+pub struct TcSfqQopt {
+    /// Bytes per round allocated to flow
+    pub quantum: u32,
+    /// Period of hash perturbation in seconds
+    pub perturb_period: i32,
+    /// Maximal packets in queue
+    pub limit: u32,
+    /// Hash divisor
+    pub divisor: u32,
+    /// Maximal number of flows
+    pub flows: u32,
+}
+
+buffer!(TcSfqQoptBuffer(TC_SFQ_QOPT_BUF_LEN) {
+    quantum: (u32, 0..4),
+    perturb_period: (i32, 4..8),
+    limit: (u32, 8..12),
+    divisor: (u32, 12..16),
+    flows: (u32, 16..TC_SFQ_QOPT_BUF_LEN),
+});
+
+impl Emitable for TcSfqQopt {
+    fn buffer_len(&self) -> usize {
+        TC_SFQ_QOPT_BUF_LEN
+    }
+
+    fn emit(&self, buffer: &mut [u8]) {
+        let mut packet = TcSfqQoptBuffer::new(buffer);
+        packet.set_quantum(self.quantum);
+        packet.set_perturb_period(self.perturb_period);
+        packet.set_limit(self.limit);
+        packet.set_divisor(self.divisor);
+        packet.set_flows(self.flows);
+    }
+}
+
+impl<T: AsRef<[u8]>> Parseable<TcSfqQoptBuffer<T>> for TcSfqQopt {
+    fn parse(buf: &TcSfqQoptBuffer<T>) -> Result<Self, DecodeError> {
+        Ok(Self {
+            quantum: buf.quantum(),
+            perturb_period: buf.perturb_period(),
+            limit: buf.limit(),
+            divisor: buf.divisor(),
+            flows: buf.flows(),
+        })
+    }
+}
+
+const TC_SFQ_QOPT_V1_BUF_LEN: usize = 72;
+
+// kernel struct `tc_sfq_qopt_v1`
+#[derive(Debug, PartialEq, Eq, Clone, Default)]
+#[non_exhaustive]
+pub struct TcSfqQoptV1 {
+    pub v0: TcSfqQopt,
+    /// Maximum number of packets per flow
+    pub depth: u32,
+    /// Drop packets from head of queue instead of tail when not zero
+    pub headdrop: u32,
+    /// RED parameters of SFQRED, the `limit` is the hard maximal flow
+    /// queue length in bytes
+    pub red: TcRedQopt,
+    /// Probability in high resolution
+    pub max_p: u32,
+    pub stats: TcSfqRedStats,
+}
+
+buffer!(TcSfqQoptV1Buffer(TC_SFQ_QOPT_V1_BUF_LEN) {
+    v0: (slice, 0..TC_SFQ_QOPT_BUF_LEN),
+    depth: (u32, 20..24),
+    headdrop: (u32, 24..28),
+    red: (slice, 28..44),
+    max_p: (u32, 44..48),
+    stats: (slice, 48..TC_SFQ_QOPT_V1_BUF_LEN),
+});
+
+impl Emitable for TcSfqQoptV1 {
+    fn buffer_len(&self) -> usize {
+        TC_SFQ_QOPT_V1_BUF_LEN
+    }
+
+    fn emit(&self, buffer: &mut [u8]) {
+        let mut packet = TcSfqQoptV1Buffer::new(buffer);
+        self.v0.emit(packet.v0_mut());
+        packet.set_depth(self.depth);
+        packet.set_headdrop(self.headdrop);
+        self.red.emit(packet.red_mut());
+        packet.set_max_p(self.max_p);
+        self.stats.emit(packet.stats_mut());
+    }
+}
+
+impl<T: AsRef<[u8]> + ?Sized> Parseable<TcSfqQoptV1Buffer<&T>> for TcSfqQoptV1 {
+    fn parse(buf: &TcSfqQoptV1Buffer<&T>) -> Result<Self, DecodeError> {
+        Ok(Self {
+            v0: TcSfqQopt::parse(&TcSfqQoptBuffer::new(buf.v0()))?,
+            depth: buf.depth(),
+            headdrop: buf.headdrop(),
+            red: TcRedQopt::parse(&TcRedQoptBuffer::new(buf.red()))?,
+            max_p: buf.max_p(),
+            stats: TcSfqRedStats::parse(&TcSfqRedStatsBuffer::new(
+                buf.stats(),
+            ))?,
+        })
+    }
+}
+
+const TC_SFQRED_STATS_BUF_LEN: usize = 24;
+
+// kernel struct `tc_sfqred_stats`
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Default)]
+#[non_exhaustive]
+pub struct TcSfqRedStats {
+    /// Early drops, below max threshold
+    pub prob_drop: u32,
+    /// Early drops, after max threshold
+    pub forced_drop: u32,
+    /// Marked packets, below max threshold
+    pub prob_mark: u32,
+    /// Marked packets, after max threshold
+    pub forced_mark: u32,
+    /// Marked packets, below max threshold
+    pub prob_mark_head: u32,
+    /// Marked packets, after max threshold
+    pub forced_mark_head: u32,
+}
+
+buffer!(TcSfqRedStatsBuffer(TC_SFQRED_STATS_BUF_LEN) {
+    prob_drop: (u32, 0..4),
+    forced_drop: (u32, 4..8),
+    prob_mark: (u32, 8..12),
+    forced_mark: (u32, 12..16),
+    prob_mark_head: (u32, 16..20),
+    forced_mark_head: (u32, 20..TC_SFQRED_STATS_BUF_LEN),
+});
+
+impl Emitable for TcSfqRedStats {
+    fn buffer_len(&self) -> usize {
+        TC_SFQRED_STATS_BUF_LEN
+    }
+
+    fn emit(&self, buffer: &mut [u8]) {
+        let mut packet = TcSfqRedStatsBuffer::new(buffer);
+        packet.set_prob_drop(self.prob_drop);
+        packet.set_forced_drop(self.forced_drop);
+        packet.set_prob_mark(self.prob_mark);
+        packet.set_forced_mark(self.forced_mark);
+        packet.set_prob_mark_head(self.prob_mark_head);
+        packet.set_forced_mark_head(self.forced_mark_head);
+    }
+}
+
+impl<T: AsRef<[u8]>> Parseable<TcSfqRedStatsBuffer<T>> for TcSfqRedStats {
+    fn parse(buf: &TcSfqRedStatsBuffer<T>) -> Result<Self, DecodeError> {
+        Ok(Self {
+            prob_drop: buf.prob_drop(),
+            forced_drop: buf.forced_drop(),
+            prob_mark: buf.prob_mark(),
+            forced_mark: buf.forced_mark(),
+            prob_mark_head: buf.prob_mark_head(),
+            forced_mark_head: buf.forced_mark_head(),
+        })
+    }
+}
+
+const TC_SFQ_XSTATS_BUF_LEN: usize = 4;
+
+// kernel struct `tc_sfq_xstats`
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Default)]
+#[non_exhaustive]
+pub struct TcSfqXstats {
+    /// Remaining bytes allowed to send in current round of the flow
+    pub allot: i32,
+}
+
+buffer!(TcSfqXstatsBuffer(TC_SFQ_XSTATS_BUF_LEN) {
+    allot: (i32, 0..TC_SFQ_XSTATS_BUF_LEN),
+});
+
+impl Emitable for TcSfqXstats {
+    fn buffer_len(&self) -> usize {
+        TC_SFQ_XSTATS_BUF_LEN
+    }
+
+    fn emit(&self, buffer: &mut [u8]) {
+        let mut packet = TcSfqXstatsBuffer::new(buffer);
+        packet.set_allot(self.allot);
+    }
+}
+
+impl<T: AsRef<[u8]>> Parseable<TcSfqXstatsBuffer<T>> for TcSfqXstats {
+    fn parse(buf: &TcSfqXstatsBuffer<T>) -> Result<Self, DecodeError> {
+        Ok(Self { allot: buf.allot() })
+    }
+}
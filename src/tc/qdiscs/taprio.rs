@@ -0,0 +1,566 @@
+// SPDX-License-Identifier: MIT
+
+/// Time Aware Priority Shaper
+///
+/// The `taprio` qdisc implements the IEEE 802.1Q-2018 Scheduled Traffic
+/// (Qbv), opening and closing the gates of traffic classes following a
+/// cyclic schedule.
+use anyhow::Context;
+use byteorder::{ByteOrder, NativeEndian};
+use netlink_packet_utils::{
+    nla::{DefaultNla, Nla, NlaBuffer, NlasIterator},
+    parsers::{parse_i32, parse_u32, parse_u64, parse_u8},
+    traits::{Emitable, Parseable},
+    DecodeError,
+};
+
+use crate::tc::{TcFpAdminStatus, TcMqprioQopt, TcMqprioQoptBuffer};
+
+#[derive(Debug, PartialEq, Eq, Clone)]
+#[non_exhaustive]
+pub struct TcQdiscTaprio {}
+
+impl TcQdiscTaprio {
+    pub(crate) const KIND: &'static str = "taprio";
+}
+
+const TCA_TAPRIO_ATTR_PRIOMAP: u16 = 1;
+const TCA_TAPRIO_ATTR_SCHED_ENTRY_LIST: u16 = 2;
+const TCA_TAPRIO_ATTR_SCHED_BASE_TIME: u16 = 3;
+// const TCA_TAPRIO_ATTR_SCHED_SINGLE_ENTRY: u16 = 4; // Rejected by kernel
+const TCA_TAPRIO_ATTR_SCHED_CLOCKID: u16 = 5;
+// const TCA_TAPRIO_PAD: u16 = 6;
+const TCA_TAPRIO_ATTR_ADMIN_SCHED: u16 = 7;
+const TCA_TAPRIO_ATTR_SCHED_CYCLE_TIME: u16 = 8;
+const TCA_TAPRIO_ATTR_SCHED_CYCLE_TIME_EXTENSION: u16 = 9;
+const TCA_TAPRIO_ATTR_FLAGS: u16 = 10;
+const TCA_TAPRIO_ATTR_TXTIME_DELAY: u16 = 11;
+const TCA_TAPRIO_ATTR_TC_ENTRY: u16 = 12;
+
+#[derive(Debug, PartialEq, Eq, Clone)]
+#[non_exhaustive]
+pub enum TcQdiscTaprioOption {
+    /// Traffic class to queue mapping
+    Priomap(TcMqprioQopt),
+    /// Gate control list, each item holds the attributes of a
+    /// `TCA_TAPRIO_SCHED_ENTRY`.
+    SchedEntryList(Vec<Vec<TcTaprioSchedEntry>>),
+    /// Start of the schedule in nanoseconds of `SchedClockid`
+    SchedBaseTime(i64),
+    SchedClockid(i32),
+    /// The pending schedule, only used in dump
+    AdminSched(Vec<TcQdiscTaprioOption>),
+    /// Schedule cycle time in nanoseconds
+    SchedCycleTime(i64),
+    /// Cycle time extension in nanoseconds
+    SchedCycleTimeExtension(i64),
+    Flags(Vec<TcTaprioFlag>),
+    /// Transmit time delay in nanoseconds for `TcTaprioFlag::TxtimeAssist`
+    TxtimeDelay(u32),
+    TcEntry(Vec<TcTaprioTcEntry>),
+    Other(DefaultNla),
+}
+
+impl Nla for TcQdiscTaprioOption {
+    fn value_len(&self) -> usize {
+        match self {
+            Self::Priomap(v) => v.buffer_len(),
+            Self::SchedEntryList(v) => {
+                taprio_sched_entries(v).as_slice().buffer_len()
+            }
+            Self::SchedClockid(_) | Self::Flags(_) | Self::TxtimeDelay(_) => 4,
+            Self::SchedBaseTime(_)
+            | Self::SchedCycleTime(_)
+            | Self::SchedCycleTimeExtension(_) => 8,
+            Self::AdminSched(v) => v.as_slice().buffer_len(),
+            Self::TcEntry(v) => v.as_slice().buffer_len(),
+            Self::Other(attr) => attr.value_len(),
+        }
+    }
+
+    fn emit_value(&self, buffer: &mut [u8]) {
+        match self {
+            Self::Priomap(v) => v.emit(buffer),
+            Self::SchedEntryList(v) => {
+                taprio_sched_entries(v).as_slice().emit(buffer)
+            }
+            Self::SchedBaseTime(d)
+            | Self::SchedCycleTime(d)
+            | Self::SchedCycleTimeExtension(d) => {
+                NativeEndian::write_i64(buffer, *d)
+            }
+            Self::SchedClockid(d) => NativeEndian::write_i32(buffer, *d),
+            Self::Flags(v) => NativeEndian::write_u32(
+                buffer,
+                u32::from(&VecTcTaprioFlag(v.to_vec())),
+            ),
+            Self::TxtimeDelay(d) => NativeEndian::write_u32(buffer, *d),
+            Self::AdminSched(v) => v.as_slice().emit(buffer),
+            Self::TcEntry(v) => v.as_slice().emit(buffer),
+            Self::Other(attr) => attr.emit_value(buffer),
+        }
+    }
+
+    fn kind(&self) -> u16 {
+        match self {
+            Self::Priomap(_) => TCA_TAPRIO_ATTR_PRIOMAP,
+            Self::SchedEntryList(_) => TCA_TAPRIO_ATTR_SCHED_ENTRY_LIST,
+            Self::SchedBaseTime(_) => TCA_TAPRIO_ATTR_SCHED_BASE_TIME,
+            Self::SchedClockid(_) => TCA_TAPRIO_ATTR_SCHED_CLOCKID,
+            Self::AdminSched(_) => TCA_TAPRIO_ATTR_ADMIN_SCHED,
+            Self::SchedCycleTime(_) => TCA_TAPRIO_ATTR_SCHED_CYCLE_TIME,
+            Self::SchedCycleTimeExtension(_) => {
+                TCA_TAPRIO_ATTR_SCHED_CYCLE_TIME_EXTENSION
+            }
+            Self::Flags(_) => TCA_TAPRIO_ATTR_FLAGS,
+            Self::TxtimeDelay(_) => TCA_TAPRIO_ATTR_TXTIME_DELAY,
+            Self::TcEntry(_) => TCA_TAPRIO_ATTR_TC_ENTRY,
+            Self::Other(attr) => attr.kind(),
+        }
+    }
+
+    fn is_nested(&self) -> bool {
+        matches!(self, Self::SchedEntryList(_) | Self::TcEntry(_))
+    }
+}
+
+impl<'a, T: AsRef<[u8]> + ?Sized> Parseable<NlaBuffer<&'a T>>
+    for TcQdiscTaprioOption
+{
+    fn parse(buf: &NlaBuffer<&'a T>) -> Result<Self, DecodeError> {
+        let payload = buf.value();
+        Ok(match buf.kind() {
+            TCA_TAPRIO_ATTR_PRIOMAP => Self::Priomap(
+                TcMqprioQopt::parse(
+                    &TcMqprioQoptBuffer::new_checked(payload)
+                        .context("invalid TCA_TAPRIO_ATTR_PRIOMAP")?,
+                )
+                .context("failed to parse TCA_TAPRIO_ATTR_PRIOMAP")?,
+            ),
+            TCA_TAPRIO_ATTR_SCHED_ENTRY_LIST => {
+                let mut entries = vec![];
+                for entry in NlasIterator::new(payload) {
+                    let entry = entry
+                        .context("invalid TCA_TAPRIO_ATTR_SCHED_ENTRY_LIST")?;
+                    let mut nlas = vec![];
+                    for nla in NlasIterator::new(entry.value()) {
+                        let nla =
+                            nla.context("invalid TCA_TAPRIO_SCHED_ENTRY")?;
+                        nlas.push(TcTaprioSchedEntry::parse(&nla).context(
+                            "failed to parse TCA_TAPRIO_SCHED_ENTRY",
+                        )?);
+                    }
+                    entries.push(nlas);
+                }
+                Self::SchedEntryList(entries)
+            }
+            TCA_TAPRIO_ATTR_SCHED_BASE_TIME => {
+                Self::SchedBaseTime(parse_u64(payload).context(
+                    "failed to parse TCA_TAPRIO_ATTR_SCHED_BASE_TIME",
+                )? as i64)
+            }
+            TCA_TAPRIO_ATTR_SCHED_CLOCKID => Self::SchedClockid(
+                parse_i32(payload)
+                    .context("failed to parse TCA_TAPRIO_ATTR_SCHED_CLOCKID")?,
+            ),
+            TCA_TAPRIO_ATTR_ADMIN_SCHED => {
+                let mut nlas = vec![];
+                for nla in NlasIterator::new(payload) {
+                    let nla =
+                        nla.context("invalid TCA_TAPRIO_ATTR_ADMIN_SCHED")?;
+                    nlas.push(Self::parse(&nla).context(
+                        "failed to parse TCA_TAPRIO_ATTR_ADMIN_SCHED",
+                    )?);
+                }
+                Self::AdminSched(nlas)
+            }
+            TCA_TAPRIO_ATTR_SCHED_CYCLE_TIME => {
+                Self::SchedCycleTime(parse_u64(payload).context(
+                    "failed to parse TCA_TAPRIO_ATTR_SCHED_CYCLE_TIME",
+                )? as i64)
+            }
+            TCA_TAPRIO_ATTR_SCHED_CYCLE_TIME_EXTENSION => {
+                Self::SchedCycleTimeExtension(parse_u64(payload).context(
+                    "failed to parse \
+                     TCA_TAPRIO_ATTR_SCHED_CYCLE_TIME_EXTENSION",
+                )? as i64)
+            }
+            TCA_TAPRIO_ATTR_FLAGS => Self::Flags(
+                VecTcTaprioFlag::from(
+                    parse_u32(payload)
+                        .context("failed to parse TCA_TAPRIO_ATTR_FLAGS")?,
+                )
+                .0,
+            ),
+            TCA_TAPRIO_ATTR_TXTIME_DELAY => Self::TxtimeDelay(
+                parse_u32(payload)
+                    .context("failed to parse TCA_TAPRIO_ATTR_TXTIME_DELAY")?,
+            ),
+            TCA_TAPRIO_ATTR_TC_ENTRY => {
+                let mut nlas = vec![];
+                for nla in NlasIterator::new(payload) {
+                    let nla =
+                        nla.context("invalid TCA_TAPRIO_ATTR_TC_ENTRY")?;
+                    nlas.push(
+                        TcTaprioTcEntry::parse(&nla).context(
+                            "failed to parse TCA_TAPRIO_ATTR_TC_ENTRY",
+                        )?,
+                    );
+                }
+                Self::TcEntry(nlas)
+            }
+            _ => Self::Other(
+                DefaultNla::parse(buf).context("failed to parse taprio nla")?,
+            ),
+        })
+    }
+}
+
+const TCA_TAPRIO_SCHED_ENTRY: u16 = 1;
+
+// Each gate control list entry is stored in a TCA_TAPRIO_SCHED_ENTRY.
+struct TaprioSchedEntry<'a>(&'a [TcTaprioSchedEntry]);
+
+fn taprio_sched_entries(
+    entries: &[Vec<TcTaprioSchedEntry>],
+) -> Vec<TaprioSchedEntry<'_>> {
+    entries
+        .iter()
+        .map(|entry| TaprioSchedEntry(entry.as_slice()))
+        .collect()
+}
+
+impl Nla for TaprioSchedEntry<'_> {
+    fn value_len(&self) -> usize {
+        self.0.buffer_len()
+    }
+
+    fn emit_value(&self, buffer: &mut [u8]) {
+        self.0.emit(buffer)
+    }
+
+    fn kind(&self) -> u16 {
+        TCA_TAPRIO_SCHED_ENTRY
+    }
+}
+
+const TCA_TAPRIO_SCHED_ENTRY_INDEX: u16 = 1;
+const TCA_TAPRIO_SCHED_ENTRY_CMD: u16 = 2;
+const TCA_TAPRIO_SCHED_ENTRY_GATE_MASK: u16 = 3;
+const TCA_TAPRIO_SCHED_ENTRY_INTERVAL: u16 = 4;
+
+#[derive(Debug, PartialEq, Eq, Clone)]
+#[non_exhaustive]
+pub enum TcTaprioSchedEntry {
+    Index(u32),
+    Cmd(TcTaprioCmd),
+    /// Bitmap of traffic classes with open gate
+    GateMask(u32),
+    /// Duration of this entry in nanoseconds
+    Interval(u32),
+    Other(DefaultNla),
+}
+
+impl Nla for TcTaprioSchedEntry {
+    fn value_len(&self) -> usize {
+        match self {
+            Self::Cmd(_) => 1,
+            Self::Index(_) | Self::GateMask(_) | Self::Interval(_) => 4,
+            Self::Other(attr) => attr.value_len(),
+        }
+    }
+
+    fn emit_value(&self, buffer: &mut [u8]) {
+        match self {
+            Self::Cmd(v) => buffer[0] = (*v).into(),
+            Self::Index(d) | Self::GateMask(d) | Self::Interval(d) => {
+                NativeEndian::write_u32(buffer, *d)
+            }
+            Self::Other(attr) => attr.emit_value(buffer),
+        }
+    }
+
+    fn kind(&self) -> u16 {
+        match self {
+            Self::Index(_) => TCA_TAPRIO_SCHED_ENTRY_INDEX,
+            Self::Cmd(_) => TCA_TAPRIO_SCHED_ENTRY_CMD,
+            Self::GateMask(_) => TCA_TAPRIO_SCHED_ENTRY_GATE_MASK,
+            Self::Interval(_) => TCA_TAPRIO_SCHED_ENTRY_INTERVAL,
+            Self::Other(attr) => attr.kind(),
+        }
+    }
+}
+
+impl<'a, T: AsRef<[u8]> + ?Sized> Parseable<NlaBuffer<&'a T>>
+    for TcTaprioSchedEntry
+{
+    fn parse(buf: &NlaBuffer<&'a T>) -> Result<Self, DecodeError> {
+        let payload = buf.value();
+        Ok(match buf.kind() {
+            TCA_TAPRIO_SCHED_ENTRY_INDEX => Self::Index(
+                parse_u32(payload)
+                    .context("failed to parse TCA_TAPRIO_SCHED_ENTRY_INDEX")?,
+            ),
+            TCA_TAPRIO_SCHED_ENTRY_CMD => Self::Cmd(
+                parse_u8(payload)
+                    .context("failed to parse TCA_TAPRIO_SCHED_ENTRY_CMD")?
+                    .into(),
+            ),
+            TCA_TAPRIO_SCHED_ENTRY_GATE_MASK => {
+                Self::GateMask(parse_u32(payload).context(
+                    "failed to parse TCA_TAPRIO_SCHED_ENTRY_GATE_MASK",
+                )?)
+            }
+            TCA_TAPRIO_SCHED_ENTRY_INTERVAL => {
+                Self::Interval(parse_u32(payload).context(
+                    "failed to parse TCA_TAPRIO_SCHED_ENTRY_INTERVAL",
+                )?)
+            }
+            _ => Self::Other(
+                DefaultNla::parse(buf)
+                    .context("failed to parse taprio sched entry nla")?,
+            ),
+        })
+    }
+}
+
+const TC_TAPRIO_CMD_SET_GATES: u8 = 0;
+const TC_TAPRIO_CMD_SET_AND_HOLD: u8 = 1;
+const TC_TAPRIO_CMD_SET_AND_RELEASE: u8 = 2;
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Default)]
+#[non_exhaustive]
+pub enum TcTaprioCmd {
+    #[default]
+    SetGates,
+    SetAndHold,
+    SetAndRelease,
+    Other(u8),
+}
+
+impl From<u8> for TcTaprioCmd {
+    fn from(d: u8) -> Self {
+        match d {
+            TC_TAPRIO_CMD_SET_GATES => Self::SetGates,
+            TC_TAPRIO_CMD_SET_AND_HOLD => Self::SetAndHold,
+            TC_TAPRIO_CMD_SET_AND_RELEASE => Self::SetAndRelease,
+            _ => Self::Other(d),
+        }
+    }
+}
+
+impl From<TcTaprioCmd> for u8 {
+    fn from(v: TcTaprioCmd) -> u8 {
+        match v {
+            TcTaprioCmd::SetGates => TC_TAPRIO_CMD_SET_GATES,
+            TcTaprioCmd::SetAndHold => TC_TAPRIO_CMD_SET_AND_HOLD,
+            TcTaprioCmd::SetAndRelease => TC_TAPRIO_CMD_SET_AND_RELEASE,
+            TcTaprioCmd::Other(d) => d,
+        }
+    }
+}
+
+const TCA_TAPRIO_TC_ENTRY_INDEX: u16 = 1;
+const TCA_TAPRIO_TC_ENTRY_MAX_SDU: u16 = 2;
+const TCA_TAPRIO_TC_ENTRY_FP: u16 = 3;
+
+/// Per traffic class attributes nested in `TCA_TAPRIO_ATTR_TC_ENTRY`
+#[derive(Debug, PartialEq, Eq, Clone)]
+#[non_exhaustive]
+pub enum TcTaprioTcEntry {
+    /// Index of traffic class
+    Index(u32),
+    /// Maximum SDU in bytes, 0 for no limit
+    MaxSdu(u32),
+    /// Frame preemption status
+    Fp(TcFpAdminStatus),
+    Other(DefaultNla),
+}
+
+impl Nla for TcTaprioTcEntry {
+    fn value_len(&self) -> usize {
+        match self {
+            Self::Index(_) | Self::MaxSdu(_) | Self::Fp(_) => 4,
+            Self::Other(attr) => attr.value_len(),
+        }
+    }
+
+    fn emit_value(&self, buffer: &mut [u8]) {
+        match self {
+            Self::Index(d) | Self::MaxSdu(d) => {
+                NativeEndian::write_u32(buffer, *d)
+            }
+            Self::Fp(v) => NativeEndian::write_u32(buffer, (*v).into()),
+            Self::Other(attr) => attr.emit_value(buffer),
+        }
+    }
+
+    fn kind(&self) -> u16 {
+        match self {
+            Self::Index(_) => TCA_TAPRIO_TC_ENTRY_INDEX,
+            Self::MaxSdu(_) => TCA_TAPRIO_TC_ENTRY_MAX_SDU,
+            Self::Fp(_) => TCA_TAPRIO_TC_ENTRY_FP,
+            Self::Other(attr) => attr.kind(),
+        }
+    }
+}
+
+impl<'a, T: AsRef<[u8]> + ?Sized> Parseable<NlaBuffer<&'a T>>
+    for TcTaprioTcEntry
+{
+    fn parse(buf: &NlaBuffer<&'a T>) -> Result<Self, DecodeError> {
+        let payload = buf.value();
+        Ok(match buf.kind() {
+            TCA_TAPRIO_TC_ENTRY_INDEX => Self::Index(
+                parse_u32(payload)
+                    .context("failed to parse TCA_TAPRIO_TC_ENTRY_INDEX")?,
+            ),
+            TCA_TAPRIO_TC_ENTRY_MAX_SDU => Self::MaxSdu(
+                parse_u32(payload)
+                    .context("failed to parse TCA_TAPRIO_TC_ENTRY_MAX_SDU")?,
+            ),
+            TCA_TAPRIO_TC_ENTRY_FP => Self::Fp(
+                parse_u32(payload)
+                    .context("failed to parse TCA_TAPRIO_TC_ENTRY_FP")?
+                    .into(),
+            ),
+            _ => Self::Other(
+                DefaultNla::parse(buf)
+                    .context("failed to parse taprio tc entry nla")?,
+            ),
+        })
+    }
+}
+
+const TCA_TAPRIO_ATTR_FLAG_TXTIME_ASSIST: u32 = 1 << 0;
+const TCA_TAPRIO_ATTR_FLAG_FULL_OFFLOAD: u32 = 1 << 1;
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+#[non_exhaustive]
+pub enum TcTaprioFlag {
+    TxtimeAssist,
+    FullOffload,
+    Other(u32),
+}
+
+impl From<TcTaprioFlag> for u32 {
+    fn from(v: TcTaprioFlag) -> u32 {
+        match v {
+            TcTaprioFlag::TxtimeAssist => TCA_TAPRIO_ATTR_FLAG_TXTIME_ASSIST,
+            TcTaprioFlag::FullOffload => TCA_TAPRIO_ATTR_FLAG_FULL_OFFLOAD,
+            TcTaprioFlag::Other(i) => i,
+        }
+    }
+}
+
+const ALL_TAPRIO_FLAGS: [TcTaprioFlag; 2] =
+    [TcTaprioFlag::TxtimeAssist, TcTaprioFlag::FullOffload];
+
+#[derive(Clone, Eq, PartialEq, Debug)]
+struct VecTcTaprioFlag(Vec<TcTaprioFlag>);
+
+impl From<u32> for VecTcTaprioFlag {
+    fn from(d: u32) -> Self {
+        let mut got: u32 = 0;
+        let mut ret = Vec::new();
+        for flag in ALL_TAPRIO_FLAGS {
+            if (d & (u32::from(flag))) > 0 {
+                ret.push(flag);
+                got += u32::from(flag);
+            }
+        }
+        if got != d {
+            ret.push(TcTaprioFlag::Other(d - got));
+        }
+        Self(ret)
+    }
+}
+
+impl From<&VecTcTaprioFlag> for u32 {
+    fn from(v: &VecTcTaprioFlag) -> u32 {
+        let mut d: u32 = 0;
+        for flag in &v.0 {
+            d += u32::from(*flag);
+        }
+        d
+    }
+}
+
+// const TCA_TAPRIO_OFFLOAD_STATS_PAD: u16 = 1;
+const TCA_TAPRIO_OFFLOAD_STATS_WINDOW_DROPS: u16 = 2;
+const TCA_TAPRIO_OFFLOAD_STATS_TX_OVERRUNS: u16 = 3;
+
+/// Offload statistics of `taprio` qdisc and its classes
+#[derive(Debug, PartialEq, Eq, Clone)]
+#[non_exhaustive]
+pub enum TcTaprioXstats {
+    /// Frames dropped as they do not fit into the transmission window
+    WindowDrops(u64),
+    /// Frames still transmitting when their gate closed
+    TxOverruns(u64),
+    Other(DefaultNla),
+}
+
+impl Nla for TcTaprioXstats {
+    fn value_len(&self) -> usize {
+        match self {
+            Self::WindowDrops(_) | Self::TxOverruns(_) => 8,
+            Self::Other(attr) => attr.value_len(),
+        }
+    }
+
+    fn emit_value(&self, buffer: &mut [u8]) {
+        match self {
+            Self::WindowDrops(d) | Self::TxOverruns(d) => {
+                NativeEndian::write_u64(buffer, *d)
+            }
+            Self::Other(attr) => attr.emit_value(buffer),
+        }
+    }
+
+    fn kind(&self) -> u16 {
+        match self {
+            Self::WindowDrops(_) => TCA_TAPRIO_OFFLOAD_STATS_WINDOW_DROPS,
+            Self::TxOverruns(_) => TCA_TAPRIO_OFFLOAD_STATS_TX_OVERRUNS,
+            Self::Other(attr) => attr.kind(),
+        }
+    }
+}
+
+impl<'a, T: AsRef<[u8]> + ?Sized> Parseable<NlaBuffer<&'a T>>
+    for TcTaprioXstats
+{
+    fn parse(buf: &NlaBuffer<&'a T>) -> Result<Self, DecodeError> {
+        let payload = buf.value();
+        Ok(match buf.kind() {
+            TCA_TAPRIO_OFFLOAD_STATS_WINDOW_DROPS => {
+                Self::WindowDrops(parse_u64(payload).context(
+                    "failed to parse TCA_TAPRIO_OFFLOAD_STATS_WINDOW_DROPS",
+                )?)
+            }
+            TCA_TAPRIO_OFFLOAD_STATS_TX_OVERRUNS => {
+                Self::TxOverruns(parse_u64(payload).context(
+                    "failed to parse TCA_TAPRIO_OFFLOAD_STATS_TX_OVERRUNS",
+                )?)
+            }
+            _ => Self::Other(
+                DefaultNla::parse(buf)
+                    .context("failed to parse taprio xstats nla")?,
+            ),
+        })
+    }
+}
+
+pub(crate) fn parse_taprio_xstats(
+    payload: &[u8],
+) -> Result<Vec<TcTaprioXstats>, DecodeError> {
+    let mut stats = vec![];
+    for nla in NlasIterator::new(payload) {
+        let nla = nla.context("invalid taprio xstats")?;
+        stats.push(
+            TcTaprioXstats::parse(&nla)
+                .context("failed to parse taprio xstats")?,
+        );
+    }
+    Ok(stats)
+}
@@ -0,0 +1,186 @@
+// SPDX-License-Identifier: MIT
+
+/// Token Bucket Filter
+///
+/// TBF shapes traffic to `rate` with bursts up to `buffer` bytes and
+/// optionally limits the burst speed to `peakrate`.
+use anyhow::Context;
+use byteorder::{ByteOrder, NativeEndian};
+use netlink_packet_utils::{
+    nla::{DefaultNla, Nla, NlaBuffer},
+    parsers::{parse_u32, parse_u64},
+    traits::{Emitable, Parseable},
+    DecodeError,
+};
+
+use crate::tc::{
+    ratespec::{emit_rate_table, parse_rate_table},
+    TcRateSpec, TcRateSpecBuffer,
+};
+
+#[derive(Debug, PartialEq, Eq, Clone)]
+#[non_exhaustive]
+pub struct TcQdiscTbf {}
+
+impl TcQdiscTbf {
+    pub(crate) const KIND: &'static str = "tbf";
+}
+
+const TCA_TBF_PARMS: u16 = 1;
+const TCA_TBF_RTAB: u16 = 2;
+const TCA_TBF_PTAB: u16 = 3;
+const TCA_TBF_RATE64: u16 = 4;
+const TCA_TBF_PRATE64: u16 = 5;
+const TCA_TBF_BURST: u16 = 6;
+const TCA_TBF_PBURST: u16 = 7;
+// const TCA_TBF_PAD: u16 = 8;
+
+#[derive(Debug, PartialEq, Eq, Clone)]
+#[non_exhaustive]
+pub enum TcQdiscTbfOption {
+    Parms(TcTbfQopt),
+    /// Rate table, could be generated by `TcPsched::calc_rtable()`
+    Rtab(Vec<u32>),
+    /// Peak rate table, could be generated by `TcPsched::calc_rtable()`
+    Ptab(Vec<u32>),
+    /// Rate in bytes per second when it does not fit into
+    /// `TcRateSpec::rate`
+    Rate64(u64),
+    /// Peak rate in bytes per second when it does not fit into
+    /// `TcRateSpec::rate`
+    Prate64(u64),
+    /// Bucket size in bytes
+    Burst(u32),
+    /// Peak rate bucket size in bytes
+    Pburst(u32),
+    Other(DefaultNla),
+}
+
+impl Nla for TcQdiscTbfOption {
+    fn value_len(&self) -> usize {
+        match self {
+            Self::Parms(v) => v.buffer_len(),
+            Self::Rtab(v) | Self::Ptab(v) => v.len() * 4,
+            Self::Rate64(_) | Self::Prate64(_) => 8,
+            Self::Burst(_) | Self::Pburst(_) => 4,
+            Self::Other(attr) => attr.value_len(),
+        }
+    }
+
+    fn emit_value(&self, buffer: &mut [u8]) {
+        match self {
+            Self::Parms(v) => v.emit(buffer),
+            Self::Rtab(v) | Self::Ptab(v) => emit_rate_table(v, buffer),
+            Self::Rate64(d) | Self::Prate64(d) => {
+                NativeEndian::write_u64(buffer, *d)
+            }
+            Self::Burst(d) | Self::Pburst(d) => {
+                NativeEndian::write_u32(buffer, *d)
+            }
+            Self::Other(attr) => attr.emit_value(buffer),
+        }
+    }
+
+    fn kind(&self) -> u16 {
+        match self {
+            Self::Parms(_) => TCA_TBF_PARMS,
+            Self::Rtab(_) => TCA_TBF_RTAB,
+            Self::Ptab(_) => TCA_TBF_PTAB,
+            Self::Rate64(_) => TCA_TBF_RATE64,
+            Self::Prate64(_) => TCA_TBF_PRATE64,
+            Self::Burst(_) => TCA_TBF_BURST,
+            Self::Pburst(_) => TCA_TBF_PBURST,
+            Self::Other(attr) => attr.kind(),
+        }
+    }
+}
+
+impl<'a, T: AsRef<[u8]> + ?Sized> Parseable<NlaBuffer<&'a T>>
+    for TcQdiscTbfOption
+{
+    fn parse(buf: &NlaBuffer<&'a T>) -> Result<Self, DecodeError> {
+        let payload = buf.value();
+        Ok(match buf.kind() {
+            TCA_TBF_PARMS => Self::Parms(
+                TcTbfQopt::parse(
+                    &TcTbfQoptBuffer::new_checked(payload)
+                        .context("invalid TCA_TBF_PARMS")?,
+                )
+                .context("failed to parse TCA_TBF_PARMS")?,
+            ),
+            TCA_TBF_RTAB => Self::Rtab(parse_rate_table(payload)),
+            TCA_TBF_PTAB => Self::Ptab(parse_rate_table(payload)),
+            TCA_TBF_RATE64 => Self::Rate64(
+                parse_u64(payload).context("failed to parse TCA_TBF_RATE64")?,
+            ),
+            TCA_TBF_PRATE64 => Self::Prate64(
+                parse_u64(payload)
+                    .context("failed to parse TCA_TBF_PRATE64")?,
+            ),
+            TCA_TBF_BURST => Self::Burst(
+                parse_u32(payload).context("failed to parse TCA_TBF_BURST")?,
+            ),
+            TCA_TBF_PBURST => Self::Pburst(
+                parse_u32(payload).context("failed to parse TCA_TBF_PBURST")?,
+            ),
+            _ => Self::Other(
+                DefaultNla::parse(buf).context("failed to parse tbf nla")?,
+            ),
+        })
+    }
+}
+
+const TC_TBF_QOPT_BUF_LEN: usize = TcRateSpec::BUF_LEN * 2 + 12;
+
+// kernel struct `tc_tbf_qopt`
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Default)]
+#[non_exhaustive]
+pub struct TcTbfQopt {
+    pub rate: TcRateSpec,
+    pub peakrate: TcRateSpec,
+    /// Queue limit in bytes
+    pub limit: u32,
+    /// Bucket size in scheduler ticks, could be calculated by
+    /// `TcPsched::calc_xmittime()`
+    pub buffer: u32,
+    /// Peak rate bucket size in scheduler ticks, could be calculated by
+    /// `TcPsched::calc_xmittime()`
+    pub mtu: u32,
+}
+
+buffer!(TcTbfQoptBuffer(TC_TBF_QOPT_BUF_LEN) {
+    rate: (slice, 0..12),
+    peakrate: (slice, 12..24),
+    limit: (u32, 24..28),
+    buffer: (u32, 28..32),
+    mtu: (u32, 32..TC_TBF_QOPT_BUF_LEN),
+});
+
+impl Emitable for TcTbfQopt {
+    fn buffer_len(&self) -> usize {
+        TC_TBF_QOPT_BUF_LEN
+    }
+
+    fn emit(&self, buffer: &mut [u8]) {
+        let mut packet = TcTbfQoptBuffer::new(buffer);
+        self.rate.emit(packet.rate_mut());
+        self.peakrate.emit(packet.peakrate_mut());
+        packet.set_limit(self.limit);
+        packet.set_buffer(self.buffer);
+        packet.set_mtu(self.mtu);
+    }
+}
+
+impl<T: AsRef<[u8]> + ?Sized> Parseable<TcTbfQoptBuffer<&T>> for TcTbfQopt {
+    fn parse(buf: &TcTbfQoptBuffer<&T>) -> Result<Self, DecodeError> {
+        Ok(Self {
+            rate: TcRateSpec::parse(&TcRateSpecBuffer::new(buf.rate()))?,
+            peakrate: TcRateSpec::parse(&TcRateSpecBuffer::new(
+                buf.peakrate(),
+            ))?,
+            limit: buf.limit(),
+            buffer: buf.buffer(),
+            mtu: buf.mtu(),
+        })
+    }
+}
@@ -0,0 +1,116 @@
+// SPDX-License-Identifier: MIT
+
+use netlink_packet_utils::{
+    traits::{Emitable, Parseable},
+    DecodeError,
+};
+
+const TC_RATESPEC_BUF_LEN: usize = 12;
+
+/// Rate specification shared by the rate limiting qdiscs and actions,
+/// kernel struct `tc_ratespec`.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Default)]
+#[non_exhaustive]
+pub struct TcRateSpec {
+    pub cell_log: u8,
+    pub linklayer: TcLinkLayer,
+    pub overhead: u16,
+    pub cell_align: i16,
+    pub mpu: u16,
+    /// Rate in bytes per second
+    pub rate: u32,
+}
+
+buffer!(TcRateSpecBuffer(TC_RATESPEC_BUF_LEN) {
+    cell_log: (u8, 0),
+    linklayer: (u8, 1),
+    overhead: (u16, 2..4),
+    cell_align: (i16, 4..6),
+    mpu: (u16, 6..8),
+    rate: (u32, 8..TC_RATESPEC_BUF_LEN),
+});
+
+impl TcRateSpec {
+    pub(crate) const BUF_LEN: usize = TC_RATESPEC_BUF_LEN;
+}
+
+impl Emitable for TcRateSpec {
+    fn buffer_len(&self) -> usize {
+        TC_RATESPEC_BUF_LEN
+    }
+
+    fn emit(&self, buffer: &mut [u8]) {
+        let mut packet = TcRateSpecBuffer::new(buffer);
+        packet.set_cell_log(self.cell_log);
+        packet.set_linklayer(self.linklayer.into());
+        packet.set_overhead(self.overhead);
+        packet.set_cell_align(self.cell_align);
+        packet.set_mpu(self.mpu);
+        packet.set_rate(self.rate);
+    }
+}
+
+impl<T: AsRef<[u8]>> Parseable<TcRateSpecBuffer<T>> for TcRateSpec {
+    fn parse(buf: &TcRateSpecBuffer<T>) -> Result<Self, DecodeError> {
+        Ok(Self {
+            cell_log: buf.cell_log(),
+            linklayer: buf.linklayer().into(),
+            overhead: buf.overhead(),
+            cell_align: buf.cell_align(),
+            mpu: buf.mpu(),
+            rate: buf.rate(),
+        })
+    }
+}
+
+const TC_LINKLAYER_UNAWARE: u8 = 0;
+const TC_LINKLAYER_ETHERNET: u8 = 1;
+const TC_LINKLAYER_ATM: u8 = 2;
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Default)]
+#[non_exhaustive]
+pub enum TcLinkLayer {
+    /// Indicate unaware old iproute2 util
+    #[default]
+    Unaware,
+    Ethernet,
+    Atm,
+    Other(u8),
+}
+
+impl From<u8> for TcLinkLayer {
+    fn from(d: u8) -> Self {
+        match d {
+            TC_LINKLAYER_UNAWARE => Self::Unaware,
+            TC_LINKLAYER_ETHERNET => Self::Ethernet,
+            TC_LINKLAYER_ATM => Self::Atm,
+            _ => Self::Other(d),
+        }
+    }
+}
+
+impl From<TcLinkLayer> for u8 {
+    fn from(v: TcLinkLayer) -> u8 {
+        match v {
+            TcLinkLayer::Unaware => TC_LINKLAYER_UNAWARE,
+            TcLinkLayer::Ethernet => TC_LINKLAYER_ETHERNET,
+            TcLinkLayer::Atm => TC_LINKLAYER_ATM,
+            TcLinkLayer::Other(d) => d,
+        }
+    }
+}
+
+// Rate tables (`TCA_*_RTAB`, `TCA_*_CTAB`, `TCA_*_PTAB`) are
+// `TC_RTAB_SIZE` bytes holding 256 native endian u32 transmission times.
+pub(crate) fn parse_rate_table(payload: &[u8]) -> Vec<u32> {
+    payload
+        .chunks_exact(4)
+        .map(|d| u32::from_ne_bytes([d[0], d[1], d[2], d[3]]))
+        .collect()
+}
+
+pub(crate) fn emit_rate_table(table: &[u32], buffer: &mut [u8]) {
+    for (d, chunk) in table.iter().zip(buffer.chunks_exact_mut(4)) {
+        chunk.copy_from_slice(&d.to_ne_bytes());
+    }
+}
@@ -59,6 +59,11 @@ impl Nla for TcStats2 {
             Self::Other(ref nla) => nla.kind(),
         }
     }
+
+    fn is_nested(&self) -> bool {
+        // The `taprio` qdisc is using nla_nest_start() for TCA_STATS_APP
+        matches!(self, Self::App(TcXstats::Taprio(_)))
+    }
 }
 
 impl<'a, T> ParseableParametrized<NlaBuffer<&'a T>, &str> for TcStats2
@@ -6,12 +6,36 @@ use netlink_packet_utils::{
     DecodeError,
 };
 
-use crate::tc::{TcFqCodelXstats, TcQdiscFqCodel};
+use crate::tc::{
+    qdiscs::{parse_cake_xstats, parse_taprio_xstats},
+    TcCakeXstats, TcChokeXstats, TcChokeXstatsBuffer, TcCodelXstats,
+    TcCodelXstatsBuffer, TcDrrStats, TcDrrStatsBuffer, TcFqCodelXstats,
+    TcFqPieXstats, TcFqPieXstatsBuffer, TcFqQdStats, TcHfscStats,
+    TcHfscStatsBuffer, TcHtbXstats, TcHtbXstatsBuffer, TcPieXstats,
+    TcPieXstatsBuffer, TcQdiscCake, TcQdiscChoke, TcQdiscCodel, TcQdiscDrr,
+    TcQdiscFq, TcQdiscFqCodel, TcQdiscFqPie, TcQdiscHfsc, TcQdiscHtb,
+    TcQdiscPie, TcQdiscQfq, TcQdiscRed, TcQdiscSfq, TcQdiscTaprio, TcQfqStats,
+    TcQfqStatsBuffer, TcRedXstats, TcRedXstatsBuffer, TcSfqXstats,
+    TcSfqXstatsBuffer, TcTaprioXstats,
+};
 
 #[derive(Debug, PartialEq, Eq, Clone)]
 #[non_exhaustive]
 pub enum TcXstats {
     FqCodel(TcFqCodelXstats),
+    Htb(TcHtbXstats),
+    Cake(Vec<TcCakeXstats>),
+    Fq(TcFqQdStats),
+    Taprio(Vec<TcTaprioXstats>),
+    Sfq(TcSfqXstats),
+    Red(TcRedXstats),
+    Choke(TcChokeXstats),
+    Hfsc(TcHfscStats),
+    Drr(TcDrrStats),
+    Qfq(TcQfqStats),
+    Codel(TcCodelXstats),
+    Pie(TcPieXstats),
+    FqPie(TcFqPieXstats),
     Other(Vec<u8>),
 }
 
@@ -19,6 +43,19 @@ impl Emitable for TcXstats {
     fn buffer_len(&self) -> usize {
         match self {
             Self::FqCodel(v) => v.buffer_len(),
+            Self::Htb(v) => v.buffer_len(),
+            Self::Cake(v) => v.as_slice().buffer_len(),
+            Self::Fq(v) => v.buffer_len(),
+            Self::Taprio(v) => v.as_slice().buffer_len(),
+            Self::Sfq(v) => v.buffer_len(),
+            Self::Red(v) => v.buffer_len(),
+            Self::Choke(v) => v.buffer_len(),
+            Self::Hfsc(v) => v.buffer_len(),
+            Self::Drr(v) => v.buffer_len(),
+            Self::Qfq(v) => v.buffer_len(),
+            Self::Codel(v) => v.buffer_len(),
+            Self::Pie(v) => v.buffer_len(),
+            Self::FqPie(v) => v.buffer_len(),
             Self::Other(v) => v.len(),
         }
     }
@@ -26,6 +63,19 @@ impl Emitable for TcXstats {
     fn emit(&self, buffer: &mut [u8]) {
         match self {
             Self::FqCodel(v) => v.emit(buffer),
+            Self::Htb(v) => v.emit(buffer),
+            Self::Cake(v) => v.as_slice().emit(buffer),
+            Self::Fq(v) => v.emit(buffer),
+            Self::Taprio(v) => v.as_slice().emit(buffer),
+            Self::Sfq(v) => v.emit(buffer),
+            Self::Red(v) => v.emit(buffer),
+            Self::Choke(v) => v.emit(buffer),
+            Self::Hfsc(v) => v.emit(buffer),
+            Self::Drr(v) => v.emit(buffer),
+            Self::Qfq(v) => v.emit(buffer),
+            Self::Codel(v) => v.emit(buffer),
+            Self::Pie(v) => v.emit(buffer),
+            Self::FqPie(v) => v.emit(buffer),
             Self::Other(v) => buffer.copy_from_slice(v.as_slice()),
         }
     }
@@ -43,7 +93,72 @@ where
             TcQdiscFqCodel::KIND => {
                 TcXstats::FqCodel(TcFqCodelXstats::parse(buf.value())?)
             }
+            TcQdiscHtb::KIND if has_len::<TcHtbXstats>(buf.value()) => {
+                TcXstats::Htb(TcHtbXstats::parse(
+                    &TcHtbXstatsBuffer::new_checked(buf.value())?,
+                )?)
+            }
+            TcQdiscCake::KIND => {
+                TcXstats::Cake(parse_cake_xstats(buf.value())?)
+            }
+            TcQdiscFq::KIND => {
+                TcXstats::Fq(TcFqQdStats::parse_with_padding(buf.value())?)
+            }
+            TcQdiscTaprio::KIND => {
+                TcXstats::Taprio(parse_taprio_xstats(buf.value())?)
+            }
+            TcQdiscSfq::KIND if has_len::<TcSfqXstats>(buf.value()) => {
+                TcXstats::Sfq(TcSfqXstats::parse(
+                    &TcSfqXstatsBuffer::new_checked(buf.value())?,
+                )?)
+            }
+            TcQdiscRed::KIND if has_len::<TcRedXstats>(buf.value()) => {
+                TcXstats::Red(TcRedXstats::parse(
+                    &TcRedXstatsBuffer::new_checked(buf.value())?,
+                )?)
+            }
+            TcQdiscChoke::KIND if has_len::<TcChokeXstats>(buf.value()) => {
+                TcXstats::Choke(TcChokeXstats::parse(
+                    &TcChokeXstatsBuffer::new_checked(buf.value())?,
+                )?)
+            }
+            TcQdiscHfsc::KIND if has_len::<TcHfscStats>(buf.value()) => {
+                TcXstats::Hfsc(TcHfscStats::parse(
+                    &TcHfscStatsBuffer::new_checked(buf.value())?,
+                )?)
+            }
+            TcQdiscDrr::KIND if has_len::<TcDrrStats>(buf.value()) => {
+                TcXstats::Drr(TcDrrStats::parse(
+                    &TcDrrStatsBuffer::new_checked(buf.value())?,
+                )?)
+            }
+            TcQdiscQfq::KIND if has_len::<TcQfqStats>(buf.value()) => {
+                TcXstats::Qfq(TcQfqStats::parse(
+                    &TcQfqStatsBuffer::new_checked(buf.value())?,
+                )?)
+            }
+            TcQdiscCodel::KIND if has_len::<TcCodelXstats>(buf.value()) => {
+                TcXstats::Codel(TcCodelXstats::parse(
+                    &TcCodelXstatsBuffer::new_checked(buf.value())?,
+                )?)
+            }
+            TcQdiscPie::KIND if has_len::<TcPieXstats>(buf.value()) => {
+                TcXstats::Pie(TcPieXstats::parse(
+                    &TcPieXstatsBuffer::new_checked(buf.value())?,
+                )?)
+            }
+            TcQdiscFqPie::KIND if has_len::<TcFqPieXstats>(buf.value()) => {
+                TcXstats::FqPie(TcFqPieXstats::parse(
+                    &TcFqPieXstatsBuffer::new_checked(buf.value())?,
+                )?)
+            }
+            // Unknown kinds, and fixed-size stats in a layout of another
+            // kernel version, are kept as raw bytes
             _ => TcXstats::Other(buf.value().to_vec()),
         })
     }
 }
+
+fn has_len<T: Emitable + Default>(payload: &[u8]) -> bool {
+    payload.len() == T::default().buffer_len()
+}
@@ -0,0 +1,294 @@
+// SPDX-License-Identifier: MIT
+
+use netlink_packet_utils::{Emitable, Parseable};
+
+use crate::{
+    tc::{
+        TcAction, TcActionAttribute, TcActionGactOption, TcActionGeneric,
+        TcActionOption, TcActionType, TcAttribute, TcFilterMatchAllOption,
+        TcGact, TcGactProb, TcGactProbType, TcHandle, TcHeader, TcMatchAllPcnt,
+        TcMessage, TcMessageBuffer, TcOption, Tcf,
+    },
+    AddressFamily,
+};
+
+// Capture nlmon of this command:
+//
+//      tc filter add dev lo ingress protocol ip pref 1 matchall \
+//          action gact goto chain 5 random determ pass 10 index 3
+//
+// Raw packet modification:
+//   * rtnetlink header removed.
+//   * NLA_F_NESTED flag removed from TCA_ACT_OPTIONS.
+#[test]
+fn test_new_filter_matchall_gact_goto_chain() {
+    let raw = vec![
+        0x00, // AF_UNSPEC
+        0x00, 0x00, 0x00, // padding
+        0x01, 0x00, 0x00, 0x00, // iface index: 1
+        0x00, 0x00, 0x00, 0x00, // handle 0:0
+        0xf2, 0xff, 0xff, 0xff, // parent ffff:fff2 (clsact ingress)
+        0x08, 0x00, 0x01, 0x00, // info: pref 1, protocol ip
+        0x0d, 0x00, // length 13
+        0x01, 0x00, // TCA_KIND
+        0x6d, 0x61, 0x74, 0x63, 0x68, 0x61, 0x6c, 0x6c, 0x00, 0x00, 0x00, 0x00,
+        // "matchall\0" and 3 bytes pad
+        0x40, 0x00, // length 64
+        0x02, 0x00, // TCA_OPTIONS for `matchall`
+        0x3c, 0x00, // length 60
+        0x02, 0x00, // TCA_MATCHALL_ACT
+        0x38, 0x00, // length 56
+        0x01, 0x00, // TCA_ACT_TAB
+        0x09, 0x00, // length 9
+        0x01, 0x00, // TCA_ACT_KIND
+        0x67, 0x61, 0x63, 0x74, 0x00, 0x00, 0x00, 0x00,
+        // "gact\0" and 3 bytes pad
+        0x28, 0x00, // length 40
+        0x02, 0x00, // TCA_ACT_OPTIONS
+        0x18, 0x00, // length 24
+        0x02, 0x00, // TCA_GACT_PARMS
+        0x03, 0x00, 0x00, 0x00, // index 3
+        0x00, 0x00, 0x00, 0x00, // capab 0
+        0x05, 0x00, 0x00, 0x20, // action TC_ACT_GOTO_CHAIN | 5
+        0x00, 0x00, 0x00, 0x00, // refcount 0
+        0x00, 0x00, 0x00, 0x00, // bindcnt 0
+        0x0c, 0x00, // length 12
+        0x03, 0x00, // TCA_GACT_PROB
+        0x02, 0x00, // ptype PGACT_DETERM
+        0x0a, 0x00, // pval 10
+        0x00, 0x00, 0x00, 0x00, // paction TC_ACT_OK
+    ];
+
+    let expected = TcMessage {
+        header: TcHeader {
+            family: AddressFamily::Unspec,
+            index: 1,
+            handle: TcHandle::UNSPEC,
+            parent: TcHandle::CLSACT_INGRESS,
+            info: 0x10008,
+        },
+        attributes: vec![
+            TcAttribute::Kind("matchall".to_string()),
+            TcAttribute::Options(vec![TcOption::MatchAll(
+                TcFilterMatchAllOption::Action(vec![TcAction {
+                    tab: 1,
+                    attributes: vec![
+                        TcActionAttribute::Kind("gact".to_string()),
+                        TcActionAttribute::Options(vec![
+                            TcActionOption::Gact(TcActionGactOption::Parms(
+                                TcGact {
+                                    generic: TcActionGeneric {
+                                        index: 3,
+                                        capab: 0,
+                                        action: TcActionType::GotoChain(5),
+                                        refcnt: 0,
+                                        bindcnt: 0,
+                                    },
+                                },
+                            )),
+                            TcActionOption::Gact(TcActionGactOption::Prob(
+                                TcGactProb {
+                                    ptype: TcGactProbType::Determ,
+                                    pval: 10,
+                                    paction: TcActionType::Ok,
+                                },
+                            )),
+                        ]),
+                    ],
+                }]),
+            )]),
+        ],
+    };
+
+    assert_eq!(
+        expected,
+        TcMessage::parse(&TcMessageBuffer::new(&raw)).unwrap()
+    );
+
+    let mut buf = vec![0; expected.buffer_len()];
+
+    expected.emit(&mut buf);
+
+    assert_eq!(buf, raw);
+}
+
+// Raw packet follows the kernel `tcf_gact_dump()` layout for
+// `tc filter show dev lo ingress` after
+// `tc filter add dev lo ingress protocol all pref 1 matchall \
+//      action gact pipe random netrand jump 1 4 action gact drop` with:
+//   * rtnetlink header removed.
+//   * TCA_ACT_STATS removed.
+#[test]
+fn test_get_filter_matchall_gact_jump() {
+    let raw = vec![
+        0x00, // AF_UNSPEC
+        0x00, 0x00, 0x00, // padding
+        0x01, 0x00, 0x00, 0x00, // iface index: 1
+        0x01, 0x00, 0x00, 0x00, // handle 0:1
+        0xf2, 0xff, 0xff, 0xff, // parent ffff:fff2 (clsact ingress)
+        0x00, 0x03, 0x01, 0x00, // info: pref 1, protocol all
+        0x0d, 0x00, // length 13
+        0x01, 0x00, // TCA_KIND
+        0x6d, 0x61, 0x74, 0x63, 0x68, 0x61, 0x6c, 0x6c, 0x00, 0x00, 0x00, 0x00,
+        // "matchall\0" and 3 bytes pad
+        0x08, 0x00, // length 8
+        0x0b, 0x00, // TCA_CHAIN
+        0x00, 0x00, 0x00, 0x00, // chain: 0
+        0xd8, 0x00, // length 216
+        0x02, 0x00, // TCA_OPTIONS for `matchall`
+        0x08, 0x00, // length 8
+        0x03, 0x00, // TCA_MATCHALL_FLAGS
+        0x08, 0x00, 0x00, 0x00, // TCA_CLS_FLAGS_NOT_IN_HW
+        0x0c, 0x00, // length 12
+        0x04, 0x00, // TCA_MATCHALL_PCNT
+        0x08, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, // rhit 8
+        0xc0, 0x00, // length 192
+        0x02, 0x00, // TCA_MATCHALL_ACT
+        0x64, 0x00, // length 100
+        0x01, 0x00, // TCA_ACT_TAB
+        0x09, 0x00, // length 9
+        0x01, 0x00, // TCA_ACT_KIND
+        0x67, 0x61, 0x63, 0x74, 0x00, 0x00, 0x00, 0x00,
+        // "gact\0" and 3 bytes pad
+        0x08, 0x00, // length 8
+        0x0a, 0x00, // TCA_ACT_IN_HW_COUNT
+        0x00, 0x00, 0x00, 0x00, // 0
+        0x4c, 0x00, // length 76
+        0x02, 0x00, // TCA_ACT_OPTIONS
+        0x18, 0x00, // length 24
+        0x02, 0x00, // TCA_GACT_PARMS
+        0x01, 0x00, 0x00, 0x00, // index 1
+        0x00, 0x00, 0x00, 0x00, // capab 0
+        0x03, 0x00, 0x00, 0x00, // action TC_ACT_PIPE
+        0x01, 0x00, 0x00, 0x00, // refcount 1
+        0x01, 0x00, 0x00, 0x00, // bindcnt 1
+        0x0c, 0x00, // length 12
+        0x03, 0x00, // TCA_GACT_PROB
+        0x01, 0x00, // ptype PGACT_NETRAND
+        0x04, 0x00, // pval 4
+        0x01, 0x00, 0x00, 0x10, // paction TC_ACT_JUMP | 1
+        0x24, 0x00, // length 36
+        0x01, 0x00, // TCA_GACT_TM
+        0x64, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, // install 100
+        0x0a, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, // lastuse 10
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, // expires 0
+        0x3c, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, // firstuse 60
+        0x58, 0x00, // length 88
+        0x02, 0x00, // TCA_ACT_TAB
+        0x09, 0x00, // length 9
+        0x01, 0x00, // TCA_ACT_KIND
+        0x67, 0x61, 0x63, 0x74, 0x00, 0x00, 0x00, 0x00,
+        // "gact\0" and 3 bytes pad
+        0x08, 0x00, // length 8
+        0x0a, 0x00, // TCA_ACT_IN_HW_COUNT
+        0x00, 0x00, 0x00, 0x00, // 0
+        0x40, 0x00, // length 64
+        0x02, 0x00, // TCA_ACT_OPTIONS
+        0x18, 0x00, // length 24
+        0x02, 0x00, // TCA_GACT_PARMS
+        0x02, 0x00, 0x00, 0x00, // index 2
+        0x00, 0x00, 0x00, 0x00, // capab 0
+        0x02, 0x00, 0x00, 0x00, // action TC_ACT_SHOT
+        0x01, 0x00, 0x00, 0x00, // refcount 1
+        0x01, 0x00, 0x00, 0x00, // bindcnt 1
+        0x24, 0x00, // length 36
+        0x01, 0x00, // TCA_GACT_TM
+        0x64, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, // install 100
+        0x0a, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, // lastuse 10
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, // expires 0
+        0x3c, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, // firstuse 60
+    ];
+
+    let tm = Tcf {
+        install: 100,
+        lastuse: 10,
+        expires: 0,
+        firstuse: 60,
+    };
+
+    let expected = TcMessage {
+        header: TcHeader {
+            family: AddressFamily::Unspec,
+            index: 1,
+            handle: TcHandle { major: 0, minor: 1 },
+            parent: TcHandle::CLSACT_INGRESS,
+            info: 0x10300,
+        },
+        attributes: vec![
+            TcAttribute::Kind("matchall".to_string()),
+            TcAttribute::Chain(0),
+            TcAttribute::Options(vec![
+                TcOption::MatchAll(TcFilterMatchAllOption::Flags(8)),
+                TcOption::MatchAll(TcFilterMatchAllOption::Pnct(
+                    TcMatchAllPcnt { rhit: 8 },
+                )),
+                TcOption::MatchAll(TcFilterMatchAllOption::Action(vec![
+                    TcAction {
+                        tab: 1,
+                        attributes: vec![
+                            TcActionAttribute::Kind("gact".to_string()),
+                            TcActionAttribute::InHwCount(0),
+                            TcActionAttribute::Options(vec![
+                                TcActionOption::Gact(
+                                    TcActionGactOption::Parms(TcGact {
+                                        generic: TcActionGeneric {
+                                            index: 1,
+                                            capab: 0,
+                                            action: TcActionType::Pipe,
+                                            refcnt: 1,
+                                            bindcnt: 1,
+                                        },
+                                    }),
+                                ),
+                                TcActionOption::Gact(TcActionGactOption::Prob(
+                                    TcGactProb {
+                                        ptype: TcGactProbType::Netrand,
+                                        pval: 4,
+                                        paction: TcActionType::Jump(1),
+                                    },
+                                )),
+                                TcActionOption::Gact(TcActionGactOption::Tm(
+                                    tm,
+                                )),
+                            ]),
+                        ],
+                    },
+                    TcAction {
+                        tab: 2,
+                        attributes: vec![
+                            TcActionAttribute::Kind("gact".to_string()),
+                            TcActionAttribute::InHwCount(0),
+                            TcActionAttribute::Options(vec![
+                                TcActionOption::Gact(
+                                    TcActionGactOption::Parms(TcGact {
+                                        generic: TcActionGeneric {
+                                            index: 2,
+                                            capab: 0,
+                                            action: TcActionType::Shot,
+                                            refcnt: 1,
+                                            bindcnt: 1,
+                                        },
+                                    }),
+                                ),
+                                TcActionOption::Gact(TcActionGactOption::Tm(
+                                    tm,
+                                )),
+                            ]),
+                        ],
+                    },
+                ])),
+            ]),
+        ],
+    };
+
+    assert_eq!(
+        expected,
+        TcMessage::parse(&TcMessageBuffer::new(&raw)).unwrap()
+    );
+
+    let mut buf = vec![0; expected.buffer_len()];
+
+    expected.emit(&mut buf);
+
+    assert_eq!(buf, raw);
+}
@@ -9,8 +9,8 @@ use crate::{
         TcAction, TcActionAttribute, TcActionGeneric, TcActionNatOption,
         TcActionOption, TcActionType, TcAttribute, TcFilterU32Option, TcHandle,
         TcHeader, TcMessage, TcMessageBuffer, TcNat, TcOption, TcStats2,
-        TcStatsBasic, TcStatsQueue, TcU32Key, TcU32OptionFlag, TcU32Selector,
-        TcU32SelectorFlag,
+        TcStatsBasic, TcStatsQueue, TcU32Key, TcU32OptionFlag, TcU32Pcnt,
+        TcU32Selector, TcU32SelectorFlag,
     },
     AddressFamily,
 };
@@ -143,10 +143,11 @@ fn test_get_filter_nat() {
                         ]),
                     ],
                 }])),
-                TcOption::U32(TcFilterU32Option::Pnct(vec![
-                    4, 0, 0, 0, 0, 0, 0, 0, 1, 0, 0, 0, 0, 0, 0, 0, 1, 0, 0, 0,
-                    0, 0, 0, 0,
-                ])),
+                TcOption::U32(TcFilterU32Option::Pnct(TcU32Pcnt {
+                    rcnt: 4,
+                    rhit: 1,
+                    kcnts: vec![1],
+                })),
             ]),
         ],
     };
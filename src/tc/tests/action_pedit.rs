@@ -0,0 +1,278 @@
+// SPDX-License-Identifier: MIT
+
+use std::net::{Ipv4Addr, Ipv6Addr};
+
+use netlink_packet_utils::{Emitable, Parseable};
+
+use crate::{
+    tc::{
+        TcAction, TcActionAttribute, TcActionGeneric, TcActionOption,
+        TcActionPedit, TcActionPeditOption, TcActionType, TcAttribute,
+        TcFilterMatchAllOption, TcHandle, TcHeader, TcMessage, TcMessageBuffer,
+        TcOption, TcPedit, TcPeditCmd, TcPeditEdit, TcPeditHeaderType,
+        TcPeditKey, TcPeditKeyEx,
+    },
+    AddressFamily,
+};
+
+// Capture nlmon of this command:
+//
+//      tc filter add dev lo ingress protocol ip pref 1 matchall \
+//          action pedit ex munge eth dst set 11:22:33:44:55:66 \
+//          munge ip ttl set 64 munge tcp dport set 8080 pipe
+//
+// Raw packet modification:
+//   * rtnetlink header removed.
+//   * NLA_F_NESTED flag removed from TCA_ACT_OPTIONS.
+#[test]
+fn test_new_filter_matchall_pedit() {
+    let raw = vec![
+        0x00, // AF_UNSPEC
+        0x00, 0x00, 0x00, // padding
+        0x01, 0x00, 0x00, 0x00, // iface index: 1
+        0x00, 0x00, 0x00, 0x00, // handle 0:0
+        0xf2, 0xff, 0xff, 0xff, // parent ffff:fff2 (clsact ingress)
+        0x08, 0x00, 0x01, 0x00, // info: pref 1, protocol ip
+        0x0d, 0x00, // length 13
+        0x01, 0x00, // TCA_KIND
+        0x6d, 0x61, 0x74, 0x63, 0x68, 0x61, 0x6c, 0x6c, 0x00, 0x00, 0x00, 0x00,
+        // "matchall\0" and 3 bytes pad
+        0xec, 0x00, // length 236
+        0x02, 0x00, // TCA_OPTIONS for `matchall`
+        0xe8, 0x00, // length 232
+        0x02, 0x00, // TCA_MATCHALL_ACT
+        0xe4, 0x00, // length 228
+        0x01, 0x00, // TCA_ACT_TAB
+        0x0a, 0x00, // length 10
+        0x01, 0x00, // TCA_ACT_KIND
+        0x70, 0x65, 0x64, 0x69, 0x74, 0x00, 0x00, 0x00,
+        // "pedit\0" and 2 bytes pad
+        0xd4, 0x00, // length 212
+        0x02, 0x00, // TCA_ACT_OPTIONS
+        0x7c, 0x00, // length 124
+        0x04, 0x00, // TCA_PEDIT_PARMS_EX
+        0x00, 0x00, 0x00, 0x00, // index 0
+        0x00, 0x00, 0x00, 0x00, // capab 0
+        0x03, 0x00, 0x00, 0x00, // action TC_ACT_PIPE
+        0x00, 0x00, 0x00, 0x00, // refcnt 0
+        0x00, 0x00, 0x00, 0x00, // bindcnt 0
+        0x04, // nkeys 4
+        0x00, // flags 0
+        0x00, 0x00, // padding
+        0x00, 0x00, 0x00, 0x00, // key 0 mask
+        0x11, 0x22, 0x33, 0x44, // key 0 val
+        0x00, 0x00, 0x00, 0x00, // key 0 off 0
+        0x00, 0x00, 0x00, 0x00, // key 0 at
+        0x00, 0x00, 0x00, 0x00, // key 0 offmask
+        0x00, 0x00, 0x00, 0x00, // key 0 shift
+        0x00, 0x00, 0xff, 0xff, // key 1 mask
+        0x55, 0x66, 0x00, 0x00, // key 1 val
+        0x04, 0x00, 0x00, 0x00, // key 1 off 4
+        0x00, 0x00, 0x00, 0x00, // key 1 at
+        0x00, 0x00, 0x00, 0x00, // key 1 offmask
+        0x00, 0x00, 0x00, 0x00, // key 1 shift
+        0x00, 0xff, 0xff, 0xff, // key 2 mask
+        0x40, 0x00, 0x00, 0x00, // key 2 val
+        0x08, 0x00, 0x00, 0x00, // key 2 off 8
+        0x00, 0x00, 0x00, 0x00, // key 2 at
+        0x00, 0x00, 0x00, 0x00, // key 2 offmask
+        0x00, 0x00, 0x00, 0x00, // key 2 shift
+        0xff, 0xff, 0x00, 0x00, // key 3 mask
+        0x00, 0x00, 0x1f, 0x90, // key 3 val
+        0x00, 0x00, 0x00, 0x00, // key 3 off 0
+        0x00, 0x00, 0x00, 0x00, // key 3 at
+        0x00, 0x00, 0x00, 0x00, // key 3 offmask
+        0x00, 0x00, 0x00, 0x00, // key 3 shift
+        0x54, 0x00, // length 84
+        0x05, 0x80, // TCA_PEDIT_KEYS_EX with NLA_F_NESTED
+        0x14, 0x00, // length 20
+        0x06, 0x80, // TCA_PEDIT_KEY_EX with NLA_F_NESTED
+        0x06, 0x00, // length 6
+        0x01, 0x00, // TCA_PEDIT_KEY_EX_HTYPE
+        0x01, 0x00, 0x00, 0x00, // TCA_PEDIT_KEY_EX_HDR_TYPE_ETH and pad
+        0x06, 0x00, // length 6
+        0x02, 0x00, // TCA_PEDIT_KEY_EX_CMD
+        0x00, 0x00, 0x00, 0x00, // TCA_PEDIT_KEY_EX_CMD_SET and pad
+        0x14, 0x00, // length 20
+        0x06, 0x80, // TCA_PEDIT_KEY_EX with NLA_F_NESTED
+        0x06, 0x00, // length 6
+        0x01, 0x00, // TCA_PEDIT_KEY_EX_HTYPE
+        0x01, 0x00, 0x00, 0x00, // TCA_PEDIT_KEY_EX_HDR_TYPE_ETH and pad
+        0x06, 0x00, // length 6
+        0x02, 0x00, // TCA_PEDIT_KEY_EX_CMD
+        0x00, 0x00, 0x00, 0x00, // TCA_PEDIT_KEY_EX_CMD_SET and pad
+        0x14, 0x00, // length 20
+        0x06, 0x80, // TCA_PEDIT_KEY_EX with NLA_F_NESTED
+        0x06, 0x00, // length 6
+        0x01, 0x00, // TCA_PEDIT_KEY_EX_HTYPE
+        0x02, 0x00, 0x00, 0x00, // TCA_PEDIT_KEY_EX_HDR_TYPE_IP4 and pad
+        0x06, 0x00, // length 6
+        0x02, 0x00, // TCA_PEDIT_KEY_EX_CMD
+        0x00, 0x00, 0x00, 0x00, // TCA_PEDIT_KEY_EX_CMD_SET and pad
+        0x14, 0x00, // length 20
+        0x06, 0x80, // TCA_PEDIT_KEY_EX with NLA_F_NESTED
+        0x06, 0x00, // length 6
+        0x01, 0x00, // TCA_PEDIT_KEY_EX_HTYPE
+        0x04, 0x00, 0x00, 0x00, // TCA_PEDIT_KEY_EX_HDR_TYPE_TCP and pad
+        0x06, 0x00, // length 6
+        0x02, 0x00, // TCA_PEDIT_KEY_EX_CMD
+        0x00, 0x00, 0x00, 0x00, // TCA_PEDIT_KEY_EX_CMD_SET and pad
+    ];
+
+    let generic = TcActionGeneric {
+        action: TcActionType::Pipe,
+        ..Default::default()
+    };
+
+    let options = vec![
+        TcActionOption::Pedit(TcActionPeditOption::ParmsEx(TcPedit {
+            generic,
+            flags: 0,
+            keys: vec![
+                TcPeditKey {
+                    mask: 0,
+                    val: u32::from_ne_bytes([0x11, 0x22, 0x33, 0x44]),
+                    off: 0,
+                    ..Default::default()
+                },
+                TcPeditKey {
+                    mask: u32::from_ne_bytes([0x00, 0x00, 0xff, 0xff]),
+                    val: u32::from_ne_bytes([0x55, 0x66, 0x00, 0x00]),
+                    off: 4,
+                    ..Default::default()
+                },
+                TcPeditKey {
+                    mask: u32::from_ne_bytes([0x00, 0xff, 0xff, 0xff]),
+                    val: u32::from_ne_bytes([64, 0x00, 0x00, 0x00]),
+                    off: 8,
+                    ..Default::default()
+                },
+                TcPeditKey {
+                    mask: u32::from_ne_bytes([0xff, 0xff, 0x00, 0x00]),
+                    val: u32::from_ne_bytes([0x00, 0x00, 0x1f, 0x90]),
+                    off: 0,
+                    ..Default::default()
+                },
+            ],
+        })),
+        TcActionOption::Pedit(TcActionPeditOption::KeysEx(vec![
+            TcPeditKeyEx {
+                htype: TcPeditHeaderType::Eth,
+                cmd: TcPeditCmd::Set,
+            },
+            TcPeditKeyEx {
+                htype: TcPeditHeaderType::Eth,
+                cmd: TcPeditCmd::Set,
+            },
+            TcPeditKeyEx {
+                htype: TcPeditHeaderType::Ip4,
+                cmd: TcPeditCmd::Set,
+            },
+            TcPeditKeyEx {
+                htype: TcPeditHeaderType::Tcp,
+                cmd: TcPeditCmd::Set,
+            },
+        ])),
+    ];
+
+    let mut edits = TcPeditEdit::eth_dst([0x11, 0x22, 0x33, 0x44, 0x55, 0x66]);
+    edits.push(TcPeditEdit::ipv4_ttl(64));
+    edits.push(TcPeditEdit::tcp_dport(8080));
+
+    assert_eq!(TcActionPedit::options(generic, &edits), options);
+
+    let expected = TcMessage {
+        header: TcHeader {
+            family: AddressFamily::Unspec,
+            index: 1,
+            handle: TcHandle::UNSPEC,
+            parent: TcHandle::CLSACT_INGRESS,
+            info: 0x10008,
+        },
+        attributes: vec![
+            TcAttribute::Kind("matchall".to_string()),
+            TcAttribute::Options(vec![TcOption::MatchAll(
+                TcFilterMatchAllOption::Action(vec![TcAction {
+                    tab: 1,
+                    attributes: vec![
+                        TcActionAttribute::Kind("pedit".to_string()),
+                        TcActionAttribute::Options(options),
+                    ],
+                }]),
+            )]),
+        ],
+    };
+
+    assert_eq!(
+        expected,
+        TcMessage::parse(&TcMessageBuffer::new(&raw)).unwrap()
+    );
+
+    let mut buf = vec![0; expected.buffer_len()];
+
+    expected.emit(&mut buf);
+
+    assert_eq!(buf, raw);
+}
+
+// Keys generated by iproute2 for:
+//
+//      tc filter add dev lo ingress protocol all pref 1 matchall \
+//          action pedit ex munge eth src set 11:22:33:44:55:66 \
+//          munge ip6 dst set 2001:db8::1 munge ip6 hoplimit set 9 \
+//          munge udp sport set 53 munge eth type set 0x86dd \
+//          munge ip tos set 0x10 munge ip src set 10.1.2.3 \
+//          munge ip ttl add 255 continue
+#[test]
+fn test_pedit_edit_helpers() {
+    fn edit(
+        htype: TcPeditHeaderType,
+        mask: [u8; 4],
+        val: [u8; 4],
+        off: u32,
+    ) -> TcPeditEdit {
+        TcPeditEdit {
+            key: TcPeditKey {
+                mask: u32::from_ne_bytes(mask),
+                val: u32::from_ne_bytes(val),
+                off,
+                ..Default::default()
+            },
+            htype,
+            cmd: TcPeditCmd::Set,
+        }
+    }
+    let eth = TcPeditHeaderType::Eth;
+    let ip4 = TcPeditHeaderType::Ip4;
+    let ip6 = TcPeditHeaderType::Ip6;
+    let udp = TcPeditHeaderType::Udp;
+
+    let expected = vec![
+        edit(eth, [0xff, 0xff, 0, 0], [0, 0, 0x11, 0x22], 4),
+        edit(eth, [0, 0, 0, 0], [0x33, 0x44, 0x55, 0x66], 8),
+        edit(ip6, [0, 0, 0, 0], [0x20, 0x01, 0x0d, 0xb8], 24),
+        edit(ip6, [0, 0, 0, 0], [0, 0, 0, 0], 28),
+        edit(ip6, [0, 0, 0, 0], [0, 0, 0, 0], 32),
+        edit(ip6, [0, 0, 0, 0], [0, 0, 0, 1], 36),
+        edit(ip6, [0xff, 0xff, 0xff, 0], [0, 0, 0, 9], 4),
+        edit(udp, [0, 0, 0xff, 0xff], [0, 0x35, 0, 0], 0),
+        edit(eth, [0, 0, 0xff, 0xff], [0x86, 0xdd, 0, 0], 12),
+        edit(ip4, [0xff, 0, 0xff, 0xff], [0, 0x10, 0, 0], 0),
+        edit(ip4, [0, 0, 0, 0], [10, 1, 2, 3], 12),
+        edit(ip4, [0, 0xff, 0xff, 0xff], [0xff, 0, 0, 0], 8)
+            .with_cmd(TcPeditCmd::Add),
+    ];
+
+    let mut edits = TcPeditEdit::eth_src([0x11, 0x22, 0x33, 0x44, 0x55, 0x66]);
+    edits.extend(TcPeditEdit::ipv6_dst(Ipv6Addr::new(
+        0x2001, 0xdb8, 0, 0, 0, 0, 0, 1,
+    )));
+    edits.push(TcPeditEdit::ipv6_hoplimit(9));
+    edits.push(TcPeditEdit::udp_sport(53));
+    edits.push(TcPeditEdit::eth_type(0x86dd));
+    edits.push(TcPeditEdit::ipv4_tos(0x10));
+    edits.push(TcPeditEdit::ipv4_src(Ipv4Addr::new(10, 1, 2, 3)));
+    edits.push(TcPeditEdit::ipv4_ttl(255).with_cmd(TcPeditCmd::Add));
+
+    assert_eq!(edits, expected);
+}
@@ -0,0 +1,269 @@
+// SPDX-License-Identifier: MIT
+
+use netlink_packet_utils::{Emitable, Parseable};
+
+use crate::{
+    tc::{
+        TcAction, TcActionAttribute, TcActionOption, TcActionPoliceOption,
+        TcActionType, TcAttribute, TcFilterMatchAllOption, TcHandle, TcHeader,
+        TcLinkLayer, TcMatchAllPcnt, TcMessage, TcMessageBuffer, TcOption,
+        TcPolice, TcRateSpec, Tcf,
+    },
+    AddressFamily,
+};
+
+// Capture nlmon of this command:
+//
+//      tc filter add dev lo ingress protocol all pref 1 matchall \
+//          action police pkts_rate 1000 pkts_burst 100 \
+//          conform-exceed drop/pipe index 2
+//
+// Raw packet modification:
+//   * rtnetlink header removed.
+//   * NLA_F_NESTED flag removed from TCA_ACT_OPTIONS.
+#[test]
+fn test_new_filter_matchall_police_pps() {
+    let raw = vec![
+        0x00, // AF_UNSPEC
+        0x00, 0x00, 0x00, // padding
+        0x01, 0x00, 0x00, 0x00, // iface index: 1
+        0x00, 0x00, 0x00, 0x00, // handle 0:0
+        0xf2, 0xff, 0xff, 0xff, // parent ffff:fff2 (clsact ingress)
+        0x00, 0x03, 0x01, 0x00, // info: pref 1, protocol all
+        0x0d, 0x00, // length 13
+        0x01, 0x00, // TCA_KIND
+        0x6d, 0x61, 0x74, 0x63, 0x68, 0x61, 0x6c, 0x6c, 0x00, 0x00, 0x00, 0x00,
+        // "matchall\0" and 3 bytes pad
+        0x78, 0x00, // length 120
+        0x02, 0x00, // TCA_OPTIONS for `matchall`
+        0x74, 0x00, // length 116
+        0x02, 0x00, // TCA_MATCHALL_ACT
+        0x70, 0x00, // length 112
+        0x01, 0x00, // TCA_ACT_TAB
+        0x0b, 0x00, // length 11
+        0x01, 0x00, // TCA_ACT_KIND
+        0x70, 0x6f, 0x6c, 0x69, 0x63, 0x65, 0x00, 0x00,
+        // "police\0" and 1 padding byte
+        0x60, 0x00, // length 96
+        0x02, 0x00, // TCA_ACT_OPTIONS
+        0x3c, 0x00, // length 60
+        0x01, 0x00, // TCA_POLICE_TBF
+        0x02, 0x00, 0x00, 0x00, // index 2
+        0x02, 0x00, 0x00, 0x00, // action TC_ACT_SHOT
+        0x00, 0x00, 0x00, 0x00, // limit 0
+        0x00, 0x00, 0x00, 0x00, // burst 0
+        0x00, 0x00, 0x00, 0x00, // mtu 0
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0x00, // rate
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0x00, // peakrate
+        0x00, 0x00, 0x00, 0x00, // refcnt 0
+        0x00, 0x00, 0x00, 0x00, // bindcnt 0
+        0x00, 0x00, 0x00, 0x00, // capab 0
+        0x08, 0x00, // length 8
+        0x05, 0x00, // TCA_POLICE_RESULT
+        0x03, 0x00, 0x00, 0x00, // TC_ACT_PIPE
+        0x0c, 0x00, // length 12
+        0x0a, 0x00, // TCA_POLICE_PKTRATE64
+        0xe8, 0x03, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, // 1000
+        0x0c, 0x00, // length 12
+        0x0b, 0x00, // TCA_POLICE_PKTBURST64
+        0x84, 0xd7, 0x17, 0x00, 0x00, 0x00, 0x00, 0x00, // 1562500
+    ];
+
+    let expected = TcMessage {
+        header: TcHeader {
+            family: AddressFamily::Unspec,
+            index: 1,
+            handle: TcHandle::UNSPEC,
+            parent: TcHandle::CLSACT_INGRESS,
+            info: 0x10300,
+        },
+        attributes: vec![
+            TcAttribute::Kind("matchall".to_string()),
+            TcAttribute::Options(vec![TcOption::MatchAll(
+                TcFilterMatchAllOption::Action(vec![TcAction {
+                    tab: 1,
+                    attributes: vec![
+                        TcActionAttribute::Kind("police".to_string()),
+                        TcActionAttribute::Options(vec![
+                            TcActionOption::Police(TcActionPoliceOption::Tbf(
+                                TcPolice {
+                                    index: 2,
+                                    action: TcActionType::Shot,
+                                    ..Default::default()
+                                },
+                            )),
+                            TcActionOption::Police(
+                                TcActionPoliceOption::Result(
+                                    TcActionType::Pipe,
+                                ),
+                            ),
+                            TcActionOption::Police(
+                                TcActionPoliceOption::PktRate64(1000),
+                            ),
+                            TcActionOption::Police(
+                                TcActionPoliceOption::PktBurst64(1562500),
+                            ),
+                        ]),
+                    ],
+                }]),
+            )]),
+        ],
+    };
+
+    assert_eq!(
+        expected,
+        TcMessage::parse(&TcMessageBuffer::new(&raw)).unwrap()
+    );
+
+    let mut buf = vec![0; expected.buffer_len()];
+
+    expected.emit(&mut buf);
+
+    assert_eq!(buf, raw);
+}
+
+// Raw packet follows the kernel `tcf_police_dump()` layout for
+// `tc filter show dev lo ingress` after
+// `tc filter add dev lo ingress protocol all pref 1 matchall \
+//      action police rate 50gbit burst 1m drop` with:
+//   * rtnetlink header removed.
+//   * TCA_ACT_STATS removed.
+#[test]
+fn test_get_filter_matchall_police_rate64() {
+    let raw = vec![
+        0x00, // AF_UNSPEC
+        0x00, 0x00, 0x00, // padding
+        0x01, 0x00, 0x00, 0x00, // iface index: 1
+        0x01, 0x00, 0x00, 0x00, // handle 0:1
+        0xf2, 0xff, 0xff, 0xff, // parent ffff:fff2 (clsact ingress)
+        0x00, 0x03, 0x01, 0x00, // info: pref 1, protocol all
+        0x0d, 0x00, // length 13
+        0x01, 0x00, // TCA_KIND
+        0x6d, 0x61, 0x74, 0x63, 0x68, 0x61, 0x6c, 0x6c, 0x00, 0x00, 0x00, 0x00,
+        // "matchall\0" and 3 bytes pad
+        0x08, 0x00, // length 8
+        0x0b, 0x00, // TCA_CHAIN
+        0x00, 0x00, 0x00, 0x00, // chain: 0
+        0xa4, 0x00, // length 164
+        0x02, 0x00, // TCA_OPTIONS for `matchall`
+        0x08, 0x00, // length 8
+        0x03, 0x00, // TCA_MATCHALL_FLAGS
+        0x08, 0x00, 0x00, 0x00, // TCA_CLS_FLAGS_NOT_IN_HW
+        0x0c, 0x00, // length 12
+        0x04, 0x00, // TCA_MATCHALL_PCNT
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, // rhit 0
+        0x8c, 0x00, // length 140
+        0x02, 0x00, // TCA_MATCHALL_ACT
+        0x88, 0x00, // length 136
+        0x01, 0x00, // TCA_ACT_TAB
+        0x0b, 0x00, // length 11
+        0x01, 0x00, // TCA_ACT_KIND
+        0x70, 0x6f, 0x6c, 0x69, 0x63, 0x65, 0x00, 0x00,
+        // "police\0" and 1 padding byte
+        0x08, 0x00, // length 8
+        0x0a, 0x00, // TCA_ACT_IN_HW_COUNT
+        0x00, 0x00, 0x00, 0x00, // 0
+        0x70, 0x00, // length 112
+        0x02, 0x00, // TCA_ACT_OPTIONS
+        0x3c, 0x00, // length 60
+        0x01, 0x00, // TCA_POLICE_TBF
+        0x01, 0x00, 0x00, 0x00, // index 1
+        0x02, 0x00, 0x00, 0x00, // action TC_ACT_SHOT
+        0x00, 0x00, 0x00, 0x00, // limit 0
+        0x3d, 0x0a, 0x00, 0x00, // burst 2621
+        0xf8, 0x07, 0x00, 0x00, // mtu 2040
+        0x00, // rate cell_log
+        0x01, // rate linklayer: TC_LINKLAYER_ETHERNET
+        0x00, 0x00, // rate overhead
+        0x00, 0x00, // rate cell_align
+        0x00, 0x00, // rate mpu
+        0xff, 0xff, 0xff, 0xff, // rate: saturated, see TCA_POLICE_RATE64
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0x00, // peakrate
+        0x01, 0x00, 0x00, 0x00, // refcnt 1
+        0x01, 0x00, 0x00, 0x00, // bindcnt 1
+        0x00, 0x00, 0x00, 0x00, // capab 0
+        0x0c, 0x00, // length 12
+        0x08, 0x00, // TCA_POLICE_RATE64
+        0x80, 0x6e, 0x87, 0x74, 0x01, 0x00, 0x00, 0x00, // 6250000000
+        0x24, 0x00, // length 36
+        0x06, 0x00, // TCA_POLICE_TM
+        0xc8, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, // install 200
+        0xc8, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, // lastuse 200
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, // expires 0
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, // firstuse 0
+    ];
+
+    let expected = TcMessage {
+        header: TcHeader {
+            family: AddressFamily::Unspec,
+            index: 1,
+            handle: TcHandle { major: 0, minor: 1 },
+            parent: TcHandle::CLSACT_INGRESS,
+            info: 0x10300,
+        },
+        attributes: vec![
+            TcAttribute::Kind("matchall".to_string()),
+            TcAttribute::Chain(0),
+            TcAttribute::Options(vec![
+                TcOption::MatchAll(TcFilterMatchAllOption::Flags(8)),
+                TcOption::MatchAll(TcFilterMatchAllOption::Pnct(
+                    TcMatchAllPcnt { rhit: 0 },
+                )),
+                TcOption::MatchAll(TcFilterMatchAllOption::Action(vec![
+                    TcAction {
+                        tab: 1,
+                        attributes: vec![
+                            TcActionAttribute::Kind("police".to_string()),
+                            TcActionAttribute::InHwCount(0),
+                            TcActionAttribute::Options(vec![
+                                TcActionOption::Police(
+                                    TcActionPoliceOption::Tbf(TcPolice {
+                                        index: 1,
+                                        action: TcActionType::Shot,
+                                        limit: 0,
+                                        burst: 2621,
+                                        mtu: 2040,
+                                        rate: TcRateSpec {
+                                            linklayer: TcLinkLayer::Ethernet,
+                                            rate: u32::MAX,
+                                            ..Default::default()
+                                        },
+                                        peakrate: TcRateSpec::default(),
+                                        refcnt: 1,
+                                        bindcnt: 1,
+                                        capab: 0,
+                                    }),
+                                ),
+                                TcActionOption::Police(
+                                    TcActionPoliceOption::Rate64(6250000000),
+                                ),
+                                TcActionOption::Police(
+                                    TcActionPoliceOption::Tm(Tcf {
+                                        install: 200,
+                                        lastuse: 200,
+                                        expires: 0,
+                                        firstuse: 0,
+                                    }),
+                                ),
+                            ]),
+                        ],
+                    },
+                ])),
+            ]),
+        ],
+    };
+
+    assert_eq!(
+        expected,
+        TcMessage::parse(&TcMessageBuffer::new(&raw)).unwrap()
+    );
+
+    let mut buf = vec![0; expected.buffer_len()];
+
+    expected.emit(&mut buf);
+
+    assert_eq!(buf, raw);
+}
@@ -0,0 +1,138 @@
+// SPDX-License-Identifier: MIT
+
+use netlink_packet_utils::{Emitable, Parseable};
+
+use crate::{
+    tc::{
+        TcAction, TcActionAttribute, TcActionGeneric, TcActionOption,
+        TcActionSkbeditOption, TcActionType, TcAttribute,
+        TcFilterMatchAllOption, TcHandle, TcHeader, TcMessage, TcMessageBuffer,
+        TcOption, TcSkbedit, TcSkbeditFlag, TcSkbeditPtype,
+    },
+    AddressFamily,
+};
+
+// Capture nlmon of this command:
+//
+//      tc filter add dev lo ingress protocol all pref 1 matchall \
+//          action skbedit priority 1:10 mark 0x10/0xff ptype host \
+//          inheritdsfield pipe
+//
+// Raw packet modification:
+//   * rtnetlink header removed.
+//   * NLA_F_NESTED flag removed from TCA_ACT_OPTIONS.
+#[test]
+fn test_new_filter_matchall_skbedit() {
+    let raw = vec![
+        0x00, // AF_UNSPEC
+        0x00, 0x00, 0x00, // padding
+        0x01, 0x00, 0x00, 0x00, // iface index: 1
+        0x00, 0x00, 0x00, 0x00, // handle 0:0
+        0xf2, 0xff, 0xff, 0xff, // parent ffff:fff2 (clsact ingress)
+        0x00, 0x03, 0x01, 0x00, // info: pref 1, protocol all
+        0x0d, 0x00, // length 13
+        0x01, 0x00, // TCA_KIND
+        0x6d, 0x61, 0x74, 0x63, 0x68, 0x61, 0x6c, 0x6c, 0x00, 0x00, 0x00, 0x00,
+        // "matchall\0" and 3 bytes pad
+        0x60, 0x00, // length 96
+        0x02, 0x00, // TCA_OPTIONS for `matchall`
+        0x5c, 0x00, // length 92
+        0x02, 0x00, // TCA_MATCHALL_ACT
+        0x58, 0x00, // length 88
+        0x01, 0x00, // TCA_ACT_TAB
+        0x0c, 0x00, // length 12
+        0x01, 0x00, // TCA_ACT_KIND
+        0x73, 0x6b, 0x62, 0x65, 0x64, 0x69, 0x74, 0x00, // "skbedit\0"
+        0x48, 0x00, // length 72
+        0x02, 0x00, // TCA_ACT_OPTIONS
+        0x18, 0x00, // length 24
+        0x02, 0x00, // TCA_SKBEDIT_PARMS
+        0x00, 0x00, 0x00, 0x00, // index 0
+        0x00, 0x00, 0x00, 0x00, // capab 0
+        0x03, 0x00, 0x00, 0x00, // action TC_ACT_PIPE
+        0x00, 0x00, 0x00, 0x00, // refcount 0
+        0x00, 0x00, 0x00, 0x00, // bindcnt 0
+        0x08, 0x00, // length 8
+        0x03, 0x00, // TCA_SKBEDIT_PRIORITY
+        0x10, 0x00, 0x01, 0x00, // 1:10
+        0x08, 0x00, // length 8
+        0x05, 0x00, // TCA_SKBEDIT_MARK
+        0x10, 0x00, 0x00, 0x00, // 0x10
+        0x08, 0x00, // length 8
+        0x08, 0x00, // TCA_SKBEDIT_MASK
+        0xff, 0x00, 0x00, 0x00, // 0xff
+        0x06, 0x00, // length 6
+        0x07, 0x00, // TCA_SKBEDIT_PTYPE
+        0x00, 0x00, 0x00, 0x00, // PACKET_HOST and 2 bytes pad
+        0x0c, 0x00, // length 12
+        0x09, 0x00, // TCA_SKBEDIT_FLAGS
+        0x20, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0x00, // SKBEDIT_F_INHERITDSFIELD
+    ];
+
+    let expected = TcMessage {
+        header: TcHeader {
+            family: AddressFamily::Unspec,
+            index: 1,
+            handle: TcHandle::UNSPEC,
+            parent: TcHandle::CLSACT_INGRESS,
+            info: 0x10300,
+        },
+        attributes: vec![
+            TcAttribute::Kind("matchall".to_string()),
+            TcAttribute::Options(vec![TcOption::MatchAll(
+                TcFilterMatchAllOption::Action(vec![TcAction {
+                    tab: 1,
+                    attributes: vec![
+                        TcActionAttribute::Kind("skbedit".to_string()),
+                        TcActionAttribute::Options(vec![
+                            TcActionOption::Skbedit(
+                                TcActionSkbeditOption::Parms(TcSkbedit {
+                                    generic: TcActionGeneric {
+                                        index: 0,
+                                        capab: 0,
+                                        action: TcActionType::Pipe,
+                                        refcnt: 0,
+                                        bindcnt: 0,
+                                    },
+                                }),
+                            ),
+                            TcActionOption::Skbedit(
+                                TcActionSkbeditOption::Priority(TcHandle::new(
+                                    1, 0x10,
+                                )),
+                            ),
+                            TcActionOption::Skbedit(
+                                TcActionSkbeditOption::Mark(0x10),
+                            ),
+                            TcActionOption::Skbedit(
+                                TcActionSkbeditOption::Mask(0xff),
+                            ),
+                            TcActionOption::Skbedit(
+                                TcActionSkbeditOption::Ptype(
+                                    TcSkbeditPtype::Host,
+                                ),
+                            ),
+                            TcActionOption::Skbedit(
+                                TcActionSkbeditOption::Flags(vec![
+                                    TcSkbeditFlag::InheritDsfield,
+                                ]),
+                            ),
+                        ]),
+                    ],
+                }]),
+            )]),
+        ],
+    };
+
+    assert_eq!(
+        expected,
+        TcMessage::parse(&TcMessageBuffer::new(&raw)).unwrap()
+    );
+
+    let mut buf = vec![0; expected.buffer_len()];
+
+    expected.emit(&mut buf);
+
+    assert_eq!(buf, raw);
+}
@@ -0,0 +1,131 @@
+// SPDX-License-Identifier: MIT
+
+use netlink_packet_utils::{Emitable, Parseable};
+
+use crate::{
+    tc::{
+        TcAction, TcActionAttribute, TcActionGeneric, TcActionOption,
+        TcActionSkbmodOption, TcActionType, TcAttribute,
+        TcFilterMatchAllOption, TcHandle, TcHeader, TcMessage, TcMessageBuffer,
+        TcOption, TcSkbmod, TcSkbmodFlag,
+    },
+    AddressFamily,
+};
+
+// Capture nlmon of this command:
+//
+//      tc filter add dev lo ingress protocol all pref 1 matchall \
+//          action skbmod set dmac 11:22:33:44:55:66 \
+//          set smac 66:55:44:33:22:11 set etype 0x0800 pipe
+//
+// Raw packet modification:
+//   * rtnetlink header removed.
+//   * NLA_F_NESTED flag removed from TCA_ACT_OPTIONS.
+#[test]
+fn test_new_filter_matchall_skbmod() {
+    let raw = vec![
+        0x00, // AF_UNSPEC
+        0x00, 0x00, 0x00, // padding
+        0x01, 0x00, 0x00, 0x00, // iface index: 1
+        0x00, 0x00, 0x00, 0x00, // handle 0:0
+        0xf2, 0xff, 0xff, 0xff, // parent ffff:fff2 (clsact ingress)
+        0x00, 0x03, 0x01, 0x00, // info: pref 1, protocol all
+        0x0d, 0x00, // length 13
+        0x01, 0x00, // TCA_KIND
+        0x6d, 0x61, 0x74, 0x63, 0x68, 0x61, 0x6c, 0x6c, 0x00, 0x00, 0x00, 0x00,
+        // "matchall\0" and 3 bytes pad
+        0x60, 0x00, // length 96
+        0x02, 0x00, // TCA_OPTIONS for `matchall`
+        0x5c, 0x00, // length 92
+        0x02, 0x00, // TCA_MATCHALL_ACT
+        0x58, 0x00, // length 88
+        0x01, 0x00, // TCA_ACT_TAB
+        0x0b, 0x00, // length 11
+        0x01, 0x00, // TCA_ACT_KIND
+        0x73, 0x6b, 0x62, 0x6d, 0x6f, 0x64, 0x00, 0x00,
+        // "skbmod\0" and 1 byte pad
+        0x48, 0x00, // length 72
+        0x02, 0x00, // TCA_ACT_OPTIONS
+        0x24, 0x00, // length 36
+        0x02, 0x00, // TCA_SKBMOD_PARMS
+        0x00, 0x00, 0x00, 0x00, // index 0
+        0x00, 0x00, 0x00, 0x00, // capab 0
+        0x03, 0x00, 0x00, 0x00, // action TC_ACT_PIPE
+        0x00, 0x00, 0x00, 0x00, // refcount 0
+        0x00, 0x00, 0x00, 0x00, // bindcnt 0
+        0x00, 0x00, 0x00, 0x00, // padding
+        0x07, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        // flags: SKBMOD_F_DMAC | SKBMOD_F_SMAC | SKBMOD_F_ETYPE
+        0x0a, 0x00, // length 10
+        0x03, 0x00, // TCA_SKBMOD_DMAC
+        0x11, 0x22, 0x33, 0x44, 0x55, 0x66, 0x00, 0x00,
+        // 11:22:33:44:55:66 and 2 bytes pad
+        0x06, 0x00, // length 6
+        0x05, 0x00, // TCA_SKBMOD_ETYPE
+        0x00, 0x08, 0x00, 0x00, // 0x0800 and 2 bytes pad
+        0x0a, 0x00, // length 10
+        0x04, 0x00, // TCA_SKBMOD_SMAC
+        0x66, 0x55, 0x44, 0x33, 0x22, 0x11, 0x00,
+        0x00,
+        // 66:55:44:33:22:11 and 2 bytes pad
+    ];
+
+    let expected = TcMessage {
+        header: TcHeader {
+            family: AddressFamily::Unspec,
+            index: 1,
+            handle: TcHandle::UNSPEC,
+            parent: TcHandle::CLSACT_INGRESS,
+            info: 0x10300,
+        },
+        attributes: vec![
+            TcAttribute::Kind("matchall".to_string()),
+            TcAttribute::Options(vec![TcOption::MatchAll(
+                TcFilterMatchAllOption::Action(vec![TcAction {
+                    tab: 1,
+                    attributes: vec![
+                        TcActionAttribute::Kind("skbmod".to_string()),
+                        TcActionAttribute::Options(vec![
+                            TcActionOption::Skbmod(
+                                TcActionSkbmodOption::Parms(TcSkbmod {
+                                    generic: TcActionGeneric {
+                                        index: 0,
+                                        capab: 0,
+                                        action: TcActionType::Pipe,
+                                        refcnt: 0,
+                                        bindcnt: 0,
+                                    },
+                                    flags: vec![
+                                        TcSkbmodFlag::Dmac,
+                                        TcSkbmodFlag::Smac,
+                                        TcSkbmodFlag::Etype,
+                                    ],
+                                }),
+                            ),
+                            TcActionOption::Skbmod(TcActionSkbmodOption::Dmac(
+                                [0x11, 0x22, 0x33, 0x44, 0x55, 0x66],
+                            )),
+                            TcActionOption::Skbmod(
+                                TcActionSkbmodOption::Etype(0x0800),
+                            ),
+                            TcActionOption::Skbmod(TcActionSkbmodOption::Smac(
+                                [0x66, 0x55, 0x44, 0x33, 0x22, 0x11],
+                            )),
+                        ]),
+                    ],
+                }]),
+            )]),
+        ],
+    };
+
+    assert_eq!(
+        expected,
+        TcMessage::parse(&TcMessageBuffer::new(&raw)).unwrap()
+    );
+
+    let mut buf = vec![0; expected.buffer_len()];
+
+    expected.emit(&mut buf);
+
+    assert_eq!(buf, raw);
+}
@@ -0,0 +1,684 @@
+// SPDX-License-Identifier: MIT
+
+use std::net::{Ipv4Addr, Ipv6Addr};
+
+use netlink_packet_utils::{Emitable, Parseable};
+
+use crate::{
+    tc::{
+        TcAction, TcActionAttribute, TcActionGeneric, TcActionOption,
+        TcActionTunnelKeyOption, TcActionType, TcAttribute,
+        TcFilterMatchAllOption, TcFlowerEncOpts, TcFlowerErspanOpt,
+        TcFlowerGeneveOpt, TcFlowerVxlanOpt, TcHandle, TcHeader,
+        TcMatchAllPcnt, TcMessage, TcMessageBuffer, TcOption, TcTunnelKey,
+        TcTunnelKeyAction, Tcf,
+    },
+    AddressFamily,
+};
+
+// Capture nlmon of this command:
+//
+//      tc filter add dev lo ingress protocol all pref 1 matchall \
+//          action tunnel_key set src_ip 10.0.0.1 dst_ip 10.0.0.2 id 100 \
+//          dst_port 6081 \
+//          geneve_opts 0102:80:00880022,0103:81:00990033 \
+//          tos 0x10 ttl 64 nocsum pipe
+//
+// Raw packet modification:
+//   * rtnetlink header removed.
+//   * NLA_F_NESTED flag removed from TCA_ACT_OPTIONS.
+#[test]
+fn test_new_filter_matchall_tunnel_key_geneve() {
+    let raw = vec![
+        0x00, // AF_UNSPEC
+        0x00, 0x00, 0x00, // padding
+        0x01, 0x00, 0x00, 0x00, // iface index: 1
+        0x00, 0x00, 0x00, 0x00, // handle 0:0
+        0xf2, 0xff, 0xff, 0xff, // parent ffff:fff2 (clsact ingress)
+        0x00, 0x03, 0x01, 0x00, // info: pref 1, protocol all
+        0x0d, 0x00, // length 13
+        0x01, 0x00, // TCA_KIND
+        0x6d, 0x61, 0x74, 0x63, 0x68, 0x61, 0x6c, 0x6c, 0x00, 0x00, 0x00, 0x00,
+        // "matchall\0" and 3 bytes pad
+        0xb0, 0x00, // length 176
+        0x02, 0x00, // TCA_OPTIONS for `matchall`
+        0xac, 0x00, // length 172
+        0x02, 0x00, // TCA_MATCHALL_ACT
+        0xa8, 0x00, // length 168
+        0x01, 0x00, // TCA_ACT_TAB
+        0x0f, 0x00, // length 15
+        0x01, 0x00, // TCA_ACT_KIND
+        0x74, 0x75, 0x6e, 0x6e, 0x65, 0x6c, 0x5f, 0x6b, 0x65, 0x79, 0x00, 0x00,
+        // "tunnel_key\0" and 1 byte pad
+        0x94, 0x00, // length 148
+        0x02, 0x00, // TCA_ACT_OPTIONS
+        0x08, 0x00, // length 8
+        0x03, 0x00, // TCA_TUNNEL_KEY_ENC_IPV4_SRC
+        0x0a, 0x00, 0x00, 0x01, // 10.0.0.1
+        0x08, 0x00, // length 8
+        0x04, 0x00, // TCA_TUNNEL_KEY_ENC_IPV4_DST
+        0x0a, 0x00, 0x00, 0x02, // 10.0.0.2
+        0x08, 0x00, // length 8
+        0x07, 0x00, // TCA_TUNNEL_KEY_ENC_KEY_ID
+        0x00, 0x00, 0x00, 0x64, // 100 in network order
+        0x06, 0x00, // length 6
+        0x09, 0x00, // TCA_TUNNEL_KEY_ENC_DST_PORT
+        0x17, 0xc1, 0x00, 0x00, // 6081 in network order and 2 bytes pad
+        0x3c, 0x00, // length 60
+        0x0b, 0x00, // TCA_TUNNEL_KEY_ENC_OPTS
+        0x1c, 0x00, // length 28
+        0x01, 0x00, // TCA_TUNNEL_KEY_ENC_OPTS_GENEVE
+        0x06, 0x00, // length 6
+        0x01, 0x00, // TCA_TUNNEL_KEY_ENC_OPT_GENEVE_CLASS
+        0x01, 0x02, 0x00, 0x00, // 0x0102 and 2 bytes pad
+        0x05, 0x00, // length 5
+        0x02, 0x00, // TCA_TUNNEL_KEY_ENC_OPT_GENEVE_TYPE
+        0x80, 0x00, 0x00, 0x00, // 0x80 and 3 bytes pad
+        0x08, 0x00, // length 8
+        0x03, 0x00, // TCA_TUNNEL_KEY_ENC_OPT_GENEVE_DATA
+        0x00, 0x88, 0x00, 0x22, // data
+        0x1c, 0x00, // length 28
+        0x01, 0x00, // TCA_TUNNEL_KEY_ENC_OPTS_GENEVE
+        0x06, 0x00, // length 6
+        0x01, 0x00, // TCA_TUNNEL_KEY_ENC_OPT_GENEVE_CLASS
+        0x01, 0x03, 0x00, 0x00, // 0x0103 and 2 bytes pad
+        0x05, 0x00, // length 5
+        0x02, 0x00, // TCA_TUNNEL_KEY_ENC_OPT_GENEVE_TYPE
+        0x81, 0x00, 0x00, 0x00, // 0x81 and 3 bytes pad
+        0x08, 0x00, // length 8
+        0x03, 0x00, // TCA_TUNNEL_KEY_ENC_OPT_GENEVE_DATA
+        0x00, 0x99, 0x00, 0x33, // data
+        0x05, 0x00, // length 5
+        0x0c, 0x00, // TCA_TUNNEL_KEY_ENC_TOS
+        0x10, 0x00, 0x00, 0x00, // 0x10 and 3 bytes pad
+        0x05, 0x00, // length 5
+        0x0d, 0x00, // TCA_TUNNEL_KEY_ENC_TTL
+        0x40, 0x00, 0x00, 0x00, // 64 and 3 bytes pad
+        0x05, 0x00, // length 5
+        0x0a, 0x00, // TCA_TUNNEL_KEY_NO_CSUM
+        0x01, 0x00, 0x00, 0x00, // 1 and 3 bytes pad
+        0x1c, 0x00, // length 28
+        0x02, 0x00, // TCA_TUNNEL_KEY_PARMS
+        0x00, 0x00, 0x00, 0x00, // index 0
+        0x00, 0x00, 0x00, 0x00, // capab 0
+        0x03, 0x00, 0x00, 0x00, // action TC_ACT_PIPE
+        0x00, 0x00, 0x00, 0x00, // refcount 0
+        0x00, 0x00, 0x00, 0x00, // bindcnt 0
+        0x01, 0x00, 0x00, 0x00, // t_action TCA_TUNNEL_KEY_ACT_SET
+    ];
+
+    let expected = TcMessage {
+        header: TcHeader {
+            family: AddressFamily::Unspec,
+            index: 1,
+            handle: TcHandle::UNSPEC,
+            parent: TcHandle::CLSACT_INGRESS,
+            info: 0x10300,
+        },
+        attributes: vec![
+            TcAttribute::Kind("matchall".to_string()),
+            TcAttribute::Options(vec![TcOption::MatchAll(
+                TcFilterMatchAllOption::Action(vec![TcAction {
+                    tab: 1,
+                    attributes: vec![
+                        TcActionAttribute::Kind("tunnel_key".to_string()),
+                        TcActionAttribute::Options(vec![
+                            TcActionOption::TunnelKey(
+                                TcActionTunnelKeyOption::EncIpv4Src(
+                                    Ipv4Addr::new(10, 0, 0, 1),
+                                ),
+                            ),
+                            TcActionOption::TunnelKey(
+                                TcActionTunnelKeyOption::EncIpv4Dst(
+                                    Ipv4Addr::new(10, 0, 0, 2),
+                                ),
+                            ),
+                            TcActionOption::TunnelKey(
+                                TcActionTunnelKeyOption::EncKeyId(100),
+                            ),
+                            TcActionOption::TunnelKey(
+                                TcActionTunnelKeyOption::EncDstPort(6081),
+                            ),
+                            TcActionOption::TunnelKey(
+                                TcActionTunnelKeyOption::EncOpts(vec![
+                                    TcFlowerEncOpts::Geneve(vec![
+                                        TcFlowerGeneveOpt::Class(0x0102),
+                                        TcFlowerGeneveOpt::Type(0x80),
+                                        TcFlowerGeneveOpt::Data(vec![
+                                            0x00, 0x88, 0x00, 0x22,
+                                        ]),
+                                    ]),
+                                    TcFlowerEncOpts::Geneve(vec![
+                                        TcFlowerGeneveOpt::Class(0x0103),
+                                        TcFlowerGeneveOpt::Type(0x81),
+                                        TcFlowerGeneveOpt::Data(vec![
+                                            0x00, 0x99, 0x00, 0x33,
+                                        ]),
+                                    ]),
+                                ]),
+                            ),
+                            TcActionOption::TunnelKey(
+                                TcActionTunnelKeyOption::EncTos(0x10),
+                            ),
+                            TcActionOption::TunnelKey(
+                                TcActionTunnelKeyOption::EncTtl(64),
+                            ),
+                            TcActionOption::TunnelKey(
+                                TcActionTunnelKeyOption::NoCsum(true),
+                            ),
+                            TcActionOption::TunnelKey(
+                                TcActionTunnelKeyOption::Parms(TcTunnelKey {
+                                    generic: TcActionGeneric {
+                                        index: 0,
+                                        capab: 0,
+                                        action: TcActionType::Pipe,
+                                        refcnt: 0,
+                                        bindcnt: 0,
+                                    },
+                                    t_action: TcTunnelKeyAction::Set,
+                                }),
+                            ),
+                        ]),
+                    ],
+                }]),
+            )]),
+        ],
+    };
+
+    assert_eq!(
+        expected,
+        TcMessage::parse(&TcMessageBuffer::new(&raw)).unwrap()
+    );
+
+    let mut buf = vec![0; expected.buffer_len()];
+
+    expected.emit(&mut buf);
+
+    assert_eq!(buf, raw);
+}
+
+// Capture nlmon of this command:
+//
+//      tc filter add dev lo ingress protocol all pref 1 matchall \
+//          action tunnel_key set src_ip 2001:db8::1 dst_ip 2001:db8::2 \
+//          id 42 dst_port 4789 vxlan_opts 1234 pipe
+//
+// Raw packet modification:
+//   * rtnetlink header removed.
+//   * NLA_F_NESTED flag removed from TCA_ACT_OPTIONS.
+#[test]
+fn test_new_filter_matchall_tunnel_key_vxlan() {
+    let raw = vec![
+        0x00, // AF_UNSPEC
+        0x00, 0x00, 0x00, // padding
+        0x01, 0x00, 0x00, 0x00, // iface index: 1
+        0x00, 0x00, 0x00, 0x00, // handle 0:0
+        0xf2, 0xff, 0xff, 0xff, // parent ffff:fff2 (clsact ingress)
+        0x00, 0x03, 0x01, 0x00, // info: pref 1, protocol all
+        0x0d, 0x00, // length 13
+        0x01, 0x00, // TCA_KIND
+        0x6d, 0x61, 0x74, 0x63, 0x68, 0x61, 0x6c, 0x6c, 0x00, 0x00, 0x00, 0x00,
+        // "matchall\0" and 3 bytes pad
+        0x8c, 0x00, // length 140
+        0x02, 0x00, // TCA_OPTIONS for `matchall`
+        0x88, 0x00, // length 136
+        0x02, 0x00, // TCA_MATCHALL_ACT
+        0x84, 0x00, // length 132
+        0x01, 0x00, // TCA_ACT_TAB
+        0x0f, 0x00, // length 15
+        0x01, 0x00, // TCA_ACT_KIND
+        0x74, 0x75, 0x6e, 0x6e, 0x65, 0x6c, 0x5f, 0x6b, 0x65, 0x79, 0x00, 0x00,
+        // "tunnel_key\0" and 1 byte pad
+        0x70, 0x00, // length 112
+        0x02, 0x00, // TCA_ACT_OPTIONS
+        0x14, 0x00, // length 20
+        0x05, 0x00, // TCA_TUNNEL_KEY_ENC_IPV6_SRC
+        0x20, 0x01, 0x0d, 0xb8, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0x00, 0x00, 0x00, 0x01, // 2001:db8::1
+        0x14, 0x00, // length 20
+        0x06, 0x00, // TCA_TUNNEL_KEY_ENC_IPV6_DST
+        0x20, 0x01, 0x0d, 0xb8, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0x00, 0x00, 0x00, 0x02, // 2001:db8::2
+        0x08, 0x00, // length 8
+        0x07, 0x00, // TCA_TUNNEL_KEY_ENC_KEY_ID
+        0x00, 0x00, 0x00, 0x2a, // 42 in network order
+        0x06, 0x00, // length 6
+        0x09, 0x00, // TCA_TUNNEL_KEY_ENC_DST_PORT
+        0x12, 0xb5, 0x00, 0x00, // 4789 in network order and 2 bytes pad
+        0x10, 0x00, // length 16
+        0x0b, 0x80, // TCA_TUNNEL_KEY_ENC_OPTS with NLA_F_NESTED
+        0x0c, 0x00, // length 12
+        0x02, 0x80, // TCA_TUNNEL_KEY_ENC_OPTS_VXLAN with NLA_F_NESTED
+        0x08, 0x00, // length 8
+        0x01, 0x00, // TCA_TUNNEL_KEY_ENC_OPT_VXLAN_GBP
+        0xd2, 0x04, 0x00, 0x00, // 1234
+        0x05, 0x00, // length 5
+        0x0a, 0x00, // TCA_TUNNEL_KEY_NO_CSUM
+        0x00, 0x00, 0x00, 0x00, // 0 and 3 bytes pad
+        0x1c, 0x00, // length 28
+        0x02, 0x00, // TCA_TUNNEL_KEY_PARMS
+        0x00, 0x00, 0x00, 0x00, // index 0
+        0x00, 0x00, 0x00, 0x00, // capab 0
+        0x03, 0x00, 0x00, 0x00, // action TC_ACT_PIPE
+        0x00, 0x00, 0x00, 0x00, // refcount 0
+        0x00, 0x00, 0x00, 0x00, // bindcnt 0
+        0x01, 0x00, 0x00, 0x00, // t_action TCA_TUNNEL_KEY_ACT_SET
+    ];
+
+    let expected = TcMessage {
+        header: TcHeader {
+            family: AddressFamily::Unspec,
+            index: 1,
+            handle: TcHandle::UNSPEC,
+            parent: TcHandle::CLSACT_INGRESS,
+            info: 0x10300,
+        },
+        attributes: vec![
+            TcAttribute::Kind("matchall".to_string()),
+            TcAttribute::Options(vec![TcOption::MatchAll(
+                TcFilterMatchAllOption::Action(vec![TcAction {
+                    tab: 1,
+                    attributes: vec![
+                        TcActionAttribute::Kind("tunnel_key".to_string()),
+                        TcActionAttribute::Options(vec![
+                            TcActionOption::TunnelKey(
+                                TcActionTunnelKeyOption::EncIpv6Src(
+                                    Ipv6Addr::new(
+                                        0x2001, 0xdb8, 0, 0, 0, 0, 0, 1,
+                                    ),
+                                ),
+                            ),
+                            TcActionOption::TunnelKey(
+                                TcActionTunnelKeyOption::EncIpv6Dst(
+                                    Ipv6Addr::new(
+                                        0x2001, 0xdb8, 0, 0, 0, 0, 0, 2,
+                                    ),
+                                ),
+                            ),
+                            TcActionOption::TunnelKey(
+                                TcActionTunnelKeyOption::EncKeyId(42),
+                            ),
+                            TcActionOption::TunnelKey(
+                                TcActionTunnelKeyOption::EncDstPort(4789),
+                            ),
+                            TcActionOption::TunnelKey(
+                                TcActionTunnelKeyOption::EncOpts(vec![
+                                    TcFlowerEncOpts::Vxlan(vec![
+                                        TcFlowerVxlanOpt::Gbp(1234),
+                                    ]),
+                                ]),
+                            ),
+                            TcActionOption::TunnelKey(
+                                TcActionTunnelKeyOption::NoCsum(false),
+                            ),
+                            TcActionOption::TunnelKey(
+                                TcActionTunnelKeyOption::Parms(TcTunnelKey {
+                                    generic: TcActionGeneric {
+                                        index: 0,
+                                        capab: 0,
+                                        action: TcActionType::Pipe,
+                                        refcnt: 0,
+                                        bindcnt: 0,
+                                    },
+                                    t_action: TcTunnelKeyAction::Set,
+                                }),
+                            ),
+                        ]),
+                    ],
+                }]),
+            )]),
+        ],
+    };
+
+    assert_eq!(
+        expected,
+        TcMessage::parse(&TcMessageBuffer::new(&raw)).unwrap()
+    );
+
+    let mut buf = vec![0; expected.buffer_len()];
+
+    expected.emit(&mut buf);
+
+    assert_eq!(buf, raw);
+}
+
+// Capture nlmon of this command:
+//
+//      tc filter add dev lo ingress protocol all pref 1 matchall \
+//          action tunnel_key set src_ip 10.0.0.1 dst_ip 10.0.0.2 id 7 \
+//          erspan_opts 2:0:1:17 pipe
+//
+// Raw packet modification:
+//   * rtnetlink header removed.
+//   * NLA_F_NESTED flag removed from TCA_ACT_OPTIONS.
+#[test]
+fn test_new_filter_matchall_tunnel_key_erspan() {
+    let raw = vec![
+        0x00, // AF_UNSPEC
+        0x00, 0x00, 0x00, // padding
+        0x01, 0x00, 0x00, 0x00, // iface index: 1
+        0x00, 0x00, 0x00, 0x00, // handle 0:0
+        0xf2, 0xff, 0xff, 0xff, // parent ffff:fff2 (clsact ingress)
+        0x00, 0x03, 0x01, 0x00, // info: pref 1, protocol all
+        0x0d, 0x00, // length 13
+        0x01, 0x00, // TCA_KIND
+        0x6d, 0x61, 0x74, 0x63, 0x68, 0x61, 0x6c, 0x6c, 0x00, 0x00, 0x00, 0x00,
+        // "matchall\0" and 3 bytes pad
+        0x84, 0x00, // length 132
+        0x02, 0x00, // TCA_OPTIONS for `matchall`
+        0x80, 0x00, // length 128
+        0x02, 0x00, // TCA_MATCHALL_ACT
+        0x7c, 0x00, // length 124
+        0x01, 0x00, // TCA_ACT_TAB
+        0x0f, 0x00, // length 15
+        0x01, 0x00, // TCA_ACT_KIND
+        0x74, 0x75, 0x6e, 0x6e, 0x65, 0x6c, 0x5f, 0x6b, 0x65, 0x79, 0x00, 0x00,
+        // "tunnel_key\0" and 1 byte pad
+        0x68, 0x00, // length 104
+        0x02, 0x00, // TCA_ACT_OPTIONS
+        0x08, 0x00, // length 8
+        0x03, 0x00, // TCA_TUNNEL_KEY_ENC_IPV4_SRC
+        0x0a, 0x00, 0x00, 0x01, // 10.0.0.1
+        0x08, 0x00, // length 8
+        0x04, 0x00, // TCA_TUNNEL_KEY_ENC_IPV4_DST
+        0x0a, 0x00, 0x00, 0x02, // 10.0.0.2
+        0x08, 0x00, // length 8
+        0x07, 0x00, // TCA_TUNNEL_KEY_ENC_KEY_ID
+        0x00, 0x00, 0x00, 0x07, // 7 in network order
+        0x28, 0x00, // length 40
+        0x0b, 0x80, // TCA_TUNNEL_KEY_ENC_OPTS with NLA_F_NESTED
+        0x24, 0x00, // length 36
+        0x03, 0x80, // TCA_TUNNEL_KEY_ENC_OPTS_ERSPAN with NLA_F_NESTED
+        0x05, 0x00, // length 5
+        0x01, 0x00, // TCA_TUNNEL_KEY_ENC_OPT_ERSPAN_VER
+        0x02, 0x00, 0x00, 0x00, // 2 and 3 bytes pad
+        0x08, 0x00, // length 8
+        0x02, 0x00, // TCA_TUNNEL_KEY_ENC_OPT_ERSPAN_INDEX
+        0x00, 0x00, 0x00, 0x00, // 0
+        0x05, 0x00, // length 5
+        0x03, 0x00, // TCA_TUNNEL_KEY_ENC_OPT_ERSPAN_DIR
+        0x01, 0x00, 0x00, 0x00, // 1 and 3 bytes pad
+        0x05, 0x00, // length 5
+        0x04, 0x00, // TCA_TUNNEL_KEY_ENC_OPT_ERSPAN_HWID
+        0x11, 0x00, 0x00, 0x00, // 17 and 3 bytes pad
+        0x05, 0x00, // length 5
+        0x0a, 0x00, // TCA_TUNNEL_KEY_NO_CSUM
+        0x00, 0x00, 0x00, 0x00, // 0 and 3 bytes pad
+        0x1c, 0x00, // length 28
+        0x02, 0x00, // TCA_TUNNEL_KEY_PARMS
+        0x00, 0x00, 0x00, 0x00, // index 0
+        0x00, 0x00, 0x00, 0x00, // capab 0
+        0x03, 0x00, 0x00, 0x00, // action TC_ACT_PIPE
+        0x00, 0x00, 0x00, 0x00, // refcount 0
+        0x00, 0x00, 0x00, 0x00, // bindcnt 0
+        0x01, 0x00, 0x00, 0x00, // t_action TCA_TUNNEL_KEY_ACT_SET
+    ];
+
+    let expected = TcMessage {
+        header: TcHeader {
+            family: AddressFamily::Unspec,
+            index: 1,
+            handle: TcHandle::UNSPEC,
+            parent: TcHandle::CLSACT_INGRESS,
+            info: 0x10300,
+        },
+        attributes: vec![
+            TcAttribute::Kind("matchall".to_string()),
+            TcAttribute::Options(vec![TcOption::MatchAll(
+                TcFilterMatchAllOption::Action(vec![TcAction {
+                    tab: 1,
+                    attributes: vec![
+                        TcActionAttribute::Kind("tunnel_key".to_string()),
+                        TcActionAttribute::Options(vec![
+                            TcActionOption::TunnelKey(
+                                TcActionTunnelKeyOption::EncIpv4Src(
+                                    Ipv4Addr::new(10, 0, 0, 1),
+                                ),
+                            ),
+                            TcActionOption::TunnelKey(
+                                TcActionTunnelKeyOption::EncIpv4Dst(
+                                    Ipv4Addr::new(10, 0, 0, 2),
+                                ),
+                            ),
+                            TcActionOption::TunnelKey(
+                                TcActionTunnelKeyOption::EncKeyId(7),
+                            ),
+                            TcActionOption::TunnelKey(
+                                TcActionTunnelKeyOption::EncOpts(vec![
+                                    TcFlowerEncOpts::Erspan(vec![
+                                        TcFlowerErspanOpt::Ver(2),
+                                        TcFlowerErspanOpt::Index(0),
+                                        TcFlowerErspanOpt::Dir(1),
+                                        TcFlowerErspanOpt::Hwid(17),
+                                    ]),
+                                ]),
+                            ),
+                            TcActionOption::TunnelKey(
+                                TcActionTunnelKeyOption::NoCsum(false),
+                            ),
+                            TcActionOption::TunnelKey(
+                                TcActionTunnelKeyOption::Parms(TcTunnelKey {
+                                    generic: TcActionGeneric {
+                                        index: 0,
+                                        capab: 0,
+                                        action: TcActionType::Pipe,
+                                        refcnt: 0,
+                                        bindcnt: 0,
+                                    },
+                                    t_action: TcTunnelKeyAction::Set,
+                                }),
+                            ),
+                        ]),
+                    ],
+                }]),
+            )]),
+        ],
+    };
+
+    assert_eq!(
+        expected,
+        TcMessage::parse(&TcMessageBuffer::new(&raw)).unwrap()
+    );
+
+    let mut buf = vec![0; expected.buffer_len()];
+
+    expected.emit(&mut buf);
+
+    assert_eq!(buf, raw);
+}
+
+// Raw packet follows the kernel `tunnel_key_dump()` layout for
+// `tc filter show dev lo ingress` after
+// `tc filter add dev lo ingress protocol all pref 1 matchall \
+//      action tunnel_key set src_ip 10.0.0.1 dst_ip 10.0.0.2 id 100 \
+//      dst_port 6081 geneve_opts 0102:80:00880022 tos 0x10 ttl 64 pipe`
+// with:
+//   * rtnetlink header removed.
+//   * TCA_ACT_STATS removed.
+#[test]
+fn test_get_filter_matchall_tunnel_key_geneve() {
+    let raw = vec![
+        0x00, // AF_UNSPEC
+        0x00, 0x00, 0x00, // padding
+        0x01, 0x00, 0x00, 0x00, // iface index: 1
+        0x01, 0x00, 0x00, 0x00, // handle 0:1
+        0xf2, 0xff, 0xff, 0xff, // parent ffff:fff2 (clsact ingress)
+        0x00, 0x03, 0x01, 0x00, // info: pref 1, protocol all
+        0x0d, 0x00, // length 13
+        0x01, 0x00, // TCA_KIND
+        0x6d, 0x61, 0x74, 0x63, 0x68, 0x61, 0x6c, 0x6c, 0x00, 0x00, 0x00, 0x00,
+        // "matchall\0" and 3 bytes pad
+        0x08, 0x00, // length 8
+        0x0b, 0x00, // TCA_CHAIN
+        0x00, 0x00, 0x00, 0x00, // chain: 0
+        0xd4, 0x00, // length 212
+        0x02, 0x00, // TCA_OPTIONS for `matchall`
+        0x08, 0x00, // length 8
+        0x03, 0x00, // TCA_MATCHALL_FLAGS
+        0x08, 0x00, 0x00, 0x00, // TCA_CLS_FLAGS_NOT_IN_HW
+        0x0c, 0x00, // length 12
+        0x04, 0x00, // TCA_MATCHALL_PCNT
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, // rhit 0
+        0xbc, 0x00, // length 188
+        0x02, 0x00, // TCA_MATCHALL_ACT
+        0xb8, 0x00, // length 184
+        0x01, 0x00, // TCA_ACT_TAB
+        0x0f, 0x00, // length 15
+        0x01, 0x00, // TCA_ACT_KIND
+        0x74, 0x75, 0x6e, 0x6e, 0x65, 0x6c, 0x5f, 0x6b, 0x65, 0x79, 0x00, 0x00,
+        // "tunnel_key\0" and 1 byte pad
+        0x08, 0x00, // length 8
+        0x0a, 0x00, // TCA_ACT_IN_HW_COUNT
+        0x00, 0x00, 0x00, 0x00, // 0
+        0x9c, 0x00, // length 156
+        0x02, 0x00, // TCA_ACT_OPTIONS
+        0x1c, 0x00, // length 28
+        0x02, 0x00, // TCA_TUNNEL_KEY_PARMS
+        0x01, 0x00, 0x00, 0x00, // index 1
+        0x00, 0x00, 0x00, 0x00, // capab 0
+        0x03, 0x00, 0x00, 0x00, // action TC_ACT_PIPE
+        0x01, 0x00, 0x00, 0x00, // refcount 1
+        0x01, 0x00, 0x00, 0x00, // bindcnt 1
+        0x01, 0x00, 0x00, 0x00, // t_action TCA_TUNNEL_KEY_ACT_SET
+        0x08, 0x00, // length 8
+        0x07, 0x00, // TCA_TUNNEL_KEY_ENC_KEY_ID
+        0x00, 0x00, 0x00, 0x64, // 100 in network order
+        0x08, 0x00, // length 8
+        0x03, 0x00, // TCA_TUNNEL_KEY_ENC_IPV4_SRC
+        0x0a, 0x00, 0x00, 0x01, // 10.0.0.1
+        0x08, 0x00, // length 8
+        0x04, 0x00, // TCA_TUNNEL_KEY_ENC_IPV4_DST
+        0x0a, 0x00, 0x00, 0x02, // 10.0.0.2
+        0x06, 0x00, // length 6
+        0x09, 0x00, // TCA_TUNNEL_KEY_ENC_DST_PORT
+        0x17, 0xc1, 0x00, 0x00, // 6081 in network order and 2 bytes pad
+        0x05, 0x00, // length 5
+        0x0a, 0x00, // TCA_TUNNEL_KEY_NO_CSUM
+        0x00, 0x00, 0x00, 0x00, // 0 and 3 bytes pad
+        0x20, 0x00, // length 32
+        0x0b, 0x00, // TCA_TUNNEL_KEY_ENC_OPTS
+        0x1c, 0x00, // length 28
+        0x01, 0x00, // TCA_TUNNEL_KEY_ENC_OPTS_GENEVE
+        0x06, 0x00, // length 6
+        0x01, 0x00, // TCA_TUNNEL_KEY_ENC_OPT_GENEVE_CLASS
+        0x01, 0x02, 0x00, 0x00, // 0x0102 and 2 bytes pad
+        0x05, 0x00, // length 5
+        0x02, 0x00, // TCA_TUNNEL_KEY_ENC_OPT_GENEVE_TYPE
+        0x80, 0x00, 0x00, 0x00, // 0x80 and 3 bytes pad
+        0x08, 0x00, // length 8
+        0x03, 0x00, // TCA_TUNNEL_KEY_ENC_OPT_GENEVE_DATA
+        0x00, 0x88, 0x00, 0x22, // data
+        0x05, 0x00, // length 5
+        0x0c, 0x00, // TCA_TUNNEL_KEY_ENC_TOS
+        0x10, 0x00, 0x00, 0x00, // 0x10 and 3 bytes pad
+        0x05, 0x00, // length 5
+        0x0d, 0x00, // TCA_TUNNEL_KEY_ENC_TTL
+        0x40, 0x00, 0x00, 0x00, // 64 and 3 bytes pad
+        0x24, 0x00, // length 36
+        0x01, 0x00, // TCA_TUNNEL_KEY_TM
+        0x64, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, // install 100
+        0x0a, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, // lastuse 10
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, // expires 0
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, // firstuse 0
+    ];
+
+    let expected = TcMessage {
+        header: TcHeader {
+            family: AddressFamily::Unspec,
+            index: 1,
+            handle: TcHandle { major: 0, minor: 1 },
+            parent: TcHandle::CLSACT_INGRESS,
+            info: 0x10300,
+        },
+        attributes: vec![
+            TcAttribute::Kind("matchall".to_string()),
+            TcAttribute::Chain(0),
+            TcAttribute::Options(vec![
+                TcOption::MatchAll(TcFilterMatchAllOption::Flags(8)),
+                TcOption::MatchAll(TcFilterMatchAllOption::Pnct(
+                    TcMatchAllPcnt { rhit: 0 },
+                )),
+                TcOption::MatchAll(TcFilterMatchAllOption::Action(vec![
+                    TcAction {
+                        tab: 1,
+                        attributes: vec![
+                            TcActionAttribute::Kind("tunnel_key".to_string()),
+                            TcActionAttribute::InHwCount(0),
+                            TcActionAttribute::Options(vec![
+                                TcActionOption::TunnelKey(
+                                    TcActionTunnelKeyOption::Parms(
+                                        TcTunnelKey {
+                                            generic: TcActionGeneric {
+                                                index: 1,
+                                                capab: 0,
+                                                action: TcActionType::Pipe,
+                                                refcnt: 1,
+                                                bindcnt: 1,
+                                            },
+                                            t_action: TcTunnelKeyAction::Set,
+                                        },
+                                    ),
+                                ),
+                                TcActionOption::TunnelKey(
+                                    TcActionTunnelKeyOption::EncKeyId(100),
+                                ),
+                                TcActionOption::TunnelKey(
+                                    TcActionTunnelKeyOption::EncIpv4Src(
+                                        Ipv4Addr::new(10, 0, 0, 1),
+                                    ),
+                                ),
+                                TcActionOption::TunnelKey(
+                                    TcActionTunnelKeyOption::EncIpv4Dst(
+                                        Ipv4Addr::new(10, 0, 0, 2),
+                                    ),
+                                ),
+                                TcActionOption::TunnelKey(
+                                    TcActionTunnelKeyOption::EncDstPort(6081),
+                                ),
+                                TcActionOption::TunnelKey(
+                                    TcActionTunnelKeyOption::NoCsum(false),
+                                ),
+                                TcActionOption::TunnelKey(
+                                    TcActionTunnelKeyOption::EncOpts(vec![
+                                        TcFlowerEncOpts::Geneve(vec![
+                                            TcFlowerGeneveOpt::Class(0x0102),
+                                            TcFlowerGeneveOpt::Type(0x80),
+                                            TcFlowerGeneveOpt::Data(vec![
+                                                0x00, 0x88, 0x00, 0x22,
+                                            ]),
+                                        ]),
+                                    ]),
+                                ),
+                                TcActionOption::TunnelKey(
+                                    TcActionTunnelKeyOption::EncTos(0x10),
+                                ),
+                                TcActionOption::TunnelKey(
+                                    TcActionTunnelKeyOption::EncTtl(64),
+                                ),
+                                TcActionOption::TunnelKey(
+                                    TcActionTunnelKeyOption::Tm(Tcf {
+                                        install: 100,
+                                        lastuse: 10,
+                                        expires: 0,
+                                        firstuse: 0,
+                                    }),
+                                ),
+                            ]),
+                        ],
+                    },
+                ])),
+            ]),
+        ],
+    };
+
+    assert_eq!(
+        expected,
+        TcMessage::parse(&TcMessageBuffer::new(&raw)).unwrap()
+    );
+
+    let mut buf = vec![0; expected.buffer_len()];
+
+    expected.emit(&mut buf);
+
+    assert_eq!(buf, raw);
+}
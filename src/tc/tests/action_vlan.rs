@@ -0,0 +1,232 @@
+// SPDX-License-Identifier: MIT
+
+use netlink_packet_utils::{Emitable, Parseable};
+
+use crate::{
+    tc::{
+        TcAction, TcActionAttribute, TcActionGeneric, TcActionOption,
+        TcActionType, TcActionVlanOption, TcAttribute, TcFilterMatchAllOption,
+        TcHandle, TcHeader, TcMessage, TcMessageBuffer, TcOption, TcVlan,
+        TcVlanAction,
+    },
+    AddressFamily,
+};
+
+// Capture nlmon of this command:
+//
+//      tc filter add dev lo ingress protocol all pref 1 matchall \
+//          action vlan push id 100 protocol 802.1ad priority 3 pipe
+//
+// Raw packet modification:
+//   * rtnetlink header removed.
+//   * NLA_F_NESTED flag removed from TCA_ACT_OPTIONS.
+#[test]
+fn test_new_filter_matchall_vlan_push() {
+    let raw = vec![
+        0x00, // AF_UNSPEC
+        0x00, 0x00, 0x00, // padding
+        0x01, 0x00, 0x00, 0x00, // iface index: 1
+        0x00, 0x00, 0x00, 0x00, // handle 0:0
+        0xf2, 0xff, 0xff, 0xff, // parent ffff:fff2 (clsact ingress)
+        0x00, 0x03, 0x01, 0x00, // info: pref 1, protocol all
+        0x0d, 0x00, // length 13
+        0x01, 0x00, // TCA_KIND
+        0x6d, 0x61, 0x74, 0x63, 0x68, 0x61, 0x6c, 0x6c, 0x00, 0x00, 0x00, 0x00,
+        // "matchall\0" and 3 bytes pad
+        0x50, 0x00, // length 80
+        0x02, 0x00, // TCA_OPTIONS for `matchall`
+        0x4c, 0x00, // length 76
+        0x02, 0x00, // TCA_MATCHALL_ACT
+        0x48, 0x00, // length 72
+        0x01, 0x00, // TCA_ACT_TAB
+        0x09, 0x00, // length 9
+        0x01, 0x00, // TCA_ACT_KIND
+        0x76, 0x6c, 0x61, 0x6e, 0x00, 0x00, 0x00, 0x00,
+        // "vlan\0" and 3 bytes pad
+        0x38, 0x00, // length 56
+        0x02, 0x00, // TCA_ACT_OPTIONS
+        0x1c, 0x00, // length 28
+        0x02, 0x00, // TCA_VLAN_PARMS
+        0x00, 0x00, 0x00, 0x00, // index 0
+        0x00, 0x00, 0x00, 0x00, // capab 0
+        0x03, 0x00, 0x00, 0x00, // action TC_ACT_PIPE
+        0x00, 0x00, 0x00, 0x00, // refcount 0
+        0x00, 0x00, 0x00, 0x00, // bindcnt 0
+        0x02, 0x00, 0x00, 0x00, // v_action TCA_VLAN_ACT_PUSH
+        0x06, 0x00, // length 6
+        0x03, 0x00, // TCA_VLAN_PUSH_VLAN_ID
+        0x64, 0x00, 0x00, 0x00, // 100 and 2 bytes pad
+        0x06, 0x00, // length 6
+        0x04, 0x00, // TCA_VLAN_PUSH_VLAN_PROTOCOL
+        0x88, 0xa8, 0x00,
+        0x00, // ETH_P_8021AD in network order and 2 bytes pad
+        0x05, 0x00, // length 5
+        0x06, 0x00, // TCA_VLAN_PUSH_VLAN_PRIORITY
+        0x03, 0x00, 0x00, 0x00, // 3 and 3 bytes pad
+    ];
+
+    let expected = TcMessage {
+        header: TcHeader {
+            family: AddressFamily::Unspec,
+            index: 1,
+            handle: TcHandle::UNSPEC,
+            parent: TcHandle::CLSACT_INGRESS,
+            info: 0x10300,
+        },
+        attributes: vec![
+            TcAttribute::Kind("matchall".to_string()),
+            TcAttribute::Options(vec![TcOption::MatchAll(
+                TcFilterMatchAllOption::Action(vec![TcAction {
+                    tab: 1,
+                    attributes: vec![
+                        TcActionAttribute::Kind("vlan".to_string()),
+                        TcActionAttribute::Options(vec![
+                            TcActionOption::Vlan(TcActionVlanOption::Parms(
+                                TcVlan {
+                                    generic: TcActionGeneric {
+                                        index: 0,
+                                        capab: 0,
+                                        action: TcActionType::Pipe,
+                                        refcnt: 0,
+                                        bindcnt: 0,
+                                    },
+                                    v_action: TcVlanAction::Push,
+                                },
+                            )),
+                            TcActionOption::Vlan(
+                                TcActionVlanOption::PushVlanId(100),
+                            ),
+                            TcActionOption::Vlan(
+                                TcActionVlanOption::PushVlanProtocol(0x88a8),
+                            ),
+                            TcActionOption::Vlan(
+                                TcActionVlanOption::PushVlanPriority(3),
+                            ),
+                        ]),
+                    ],
+                }]),
+            )]),
+        ],
+    };
+
+    assert_eq!(
+        expected,
+        TcMessage::parse(&TcMessageBuffer::new(&raw)).unwrap()
+    );
+
+    let mut buf = vec![0; expected.buffer_len()];
+
+    expected.emit(&mut buf);
+
+    assert_eq!(buf, raw);
+}
+
+// Capture nlmon of this command:
+//
+//      tc filter add dev lo ingress protocol all pref 1 matchall \
+//          action vlan push_eth dst_mac 11:22:33:44:55:66 \
+//          src_mac 66:55:44:33:22:11 pipe
+//
+// Raw packet modification:
+//   * rtnetlink header removed.
+//   * NLA_F_NESTED flag removed from TCA_ACT_OPTIONS.
+#[test]
+fn test_new_filter_matchall_vlan_push_eth() {
+    let raw = vec![
+        0x00, // AF_UNSPEC
+        0x00, 0x00, 0x00, // padding
+        0x01, 0x00, 0x00, 0x00, // iface index: 1
+        0x00, 0x00, 0x00, 0x00, // handle 0:0
+        0xf2, 0xff, 0xff, 0xff, // parent ffff:fff2 (clsact ingress)
+        0x00, 0x03, 0x01, 0x00, // info: pref 1, protocol all
+        0x0d, 0x00, // length 13
+        0x01, 0x00, // TCA_KIND
+        0x6d, 0x61, 0x74, 0x63, 0x68, 0x61, 0x6c, 0x6c, 0x00, 0x00, 0x00, 0x00,
+        // "matchall\0" and 3 bytes pad
+        0x50, 0x00, // length 80
+        0x02, 0x00, // TCA_OPTIONS for `matchall`
+        0x4c, 0x00, // length 76
+        0x02, 0x00, // TCA_MATCHALL_ACT
+        0x48, 0x00, // length 72
+        0x01, 0x00, // TCA_ACT_TAB
+        0x09, 0x00, // length 9
+        0x01, 0x00, // TCA_ACT_KIND
+        0x76, 0x6c, 0x61, 0x6e, 0x00, 0x00, 0x00, 0x00,
+        // "vlan\0" and 3 bytes pad
+        0x38, 0x00, // length 56
+        0x02, 0x00, // TCA_ACT_OPTIONS
+        0x1c, 0x00, // length 28
+        0x02, 0x00, // TCA_VLAN_PARMS
+        0x00, 0x00, 0x00, 0x00, // index 0
+        0x00, 0x00, 0x00, 0x00, // capab 0
+        0x03, 0x00, 0x00, 0x00, // action TC_ACT_PIPE
+        0x00, 0x00, 0x00, 0x00, // refcount 0
+        0x00, 0x00, 0x00, 0x00, // bindcnt 0
+        0x05, 0x00, 0x00, 0x00, // v_action TCA_VLAN_ACT_PUSH_ETH
+        0x0a, 0x00, // length 10
+        0x07, 0x00, // TCA_VLAN_PUSH_ETH_DST
+        0x11, 0x22, 0x33, 0x44, 0x55, 0x66, 0x00, 0x00,
+        // 11:22:33:44:55:66 and 2 bytes pad
+        0x0a, 0x00, // length 10
+        0x08, 0x00, // TCA_VLAN_PUSH_ETH_SRC
+        0x66, 0x55, 0x44, 0x33, 0x22, 0x11, 0x00,
+        0x00,
+        // 66:55:44:33:22:11 and 2 bytes pad
+    ];
+
+    let expected = TcMessage {
+        header: TcHeader {
+            family: AddressFamily::Unspec,
+            index: 1,
+            handle: TcHandle::UNSPEC,
+            parent: TcHandle::CLSACT_INGRESS,
+            info: 0x10300,
+        },
+        attributes: vec![
+            TcAttribute::Kind("matchall".to_string()),
+            TcAttribute::Options(vec![TcOption::MatchAll(
+                TcFilterMatchAllOption::Action(vec![TcAction {
+                    tab: 1,
+                    attributes: vec![
+                        TcActionAttribute::Kind("vlan".to_string()),
+                        TcActionAttribute::Options(vec![
+                            TcActionOption::Vlan(TcActionVlanOption::Parms(
+                                TcVlan {
+                                    generic: TcActionGeneric {
+                                        index: 0,
+                                        capab: 0,
+                                        action: TcActionType::Pipe,
+                                        refcnt: 0,
+                                        bindcnt: 0,
+                                    },
+                                    v_action: TcVlanAction::PushEth,
+                                },
+                            )),
+                            TcActionOption::Vlan(
+                                TcActionVlanOption::PushEthDst([
+                                    0x11, 0x22, 0x33, 0x44, 0x55, 0x66,
+                                ]),
+                            ),
+                            TcActionOption::Vlan(
+                                TcActionVlanOption::PushEthSrc([
+                                    0x66, 0x55, 0x44, 0x33, 0x22, 0x11,
+                                ]),
+                            ),
+                        ]),
+                    ],
+                }]),
+            )]),
+        ],
+    };
+
+    assert_eq!(
+        expected,
+        TcMessage::parse(&TcMessageBuffer::new(&raw)).unwrap()
+    );
+
+    let mut buf = vec![0; expected.buffer_len()];
+
+    expected.emit(&mut buf);
+
+    assert_eq!(buf, raw);
+}
@@ -0,0 +1,533 @@
+// SPDX-License-Identifier: MIT
+
+use netlink_packet_utils::{Emitable, Parseable};
+
+use crate::{
+    tc::{
+        TcAttribute, TcBasicPcnt, TcEmatchCmp, TcEmatchCmpAlign, TcEmatchExpr,
+        TcEmatchIpset, TcEmatchLayer, TcEmatchMetaHeader, TcEmatchMetaId,
+        TcEmatchMetaOption, TcEmatchMetaType, TcEmatchMetaValue, TcEmatchNbyte,
+        TcEmatchOperand, TcEmatchOther, TcEmatchRelation, TcEmatchTerm,
+        TcEmatchTree, TcFilterBasicOption, TcHandle, TcHeader, TcMessage,
+        TcMessageBuffer, TcOption, TcU32Key,
+    },
+    AddressFamily,
+};
+
+// Capture nlmon of this command:
+//
+//      tc filter add dev lo parent 1: protocol ip pref 1 basic match \
+//          'cmp(u16 at 2 layer network eq 0x1234) and (meta(nf_mark gt 10)
+//          or not u32(u32 0x0a000000 0xff000000 at 12))' classid 1:1
+//
+// Raw packet modification:
+//   * rtnetlink header removed.
+#[test]
+fn test_new_filter_basic_ematch_tree() {
+    let raw = vec![
+        0x00, // AF_UNSPEC
+        0x00, 0x00, 0x00, // padding
+        0x01, 0x00, 0x00, 0x00, // iface index: 1
+        0x00, 0x00, 0x00, 0x00, // handle 0:0
+        0x00, 0x00, 0x01, 0x00, // parent 1:0
+        0x08, 0x00, 0x01, 0x00, // info: pref 1, protocol ip
+        0x0a, 0x00, // length 10
+        0x01, 0x00, // TCA_KIND
+        0x62, 0x61, 0x73, 0x69, 0x63, 0x00, 0x00, 0x00,
+        // "basic\0" and 2 padding bytes
+        0x88, 0x00, // length 136
+        0x02, 0x00, // TCA_OPTIONS for `basic`
+        0x7c, 0x00, // length 124
+        0x02, 0x00, // TCA_BASIC_EMATCHES
+        0x08, 0x00, // length 8
+        0x01, 0x00, // TCA_EMATCH_TREE_HDR
+        0x04, 0x00, // nmatches: 4
+        0x00, 0x00, // progid: 0
+        0x70, 0x00, // length 112
+        0x02, 0x00, // TCA_EMATCH_TREE_LIST
+        0x18, 0x00, // length 24
+        0x01, 0x00, // ematch 0
+        0x00, 0x00, // matchid: 0
+        0x01, 0x00, // kind: TCF_EM_CMP
+        0x01, 0x00, // flags: TCF_EM_REL_AND
+        0x00, 0x00, // pad
+        0x34, 0x12, 0x00, 0x00, // val: 0x1234
+        0x00, 0x00, 0x00, 0x00, // mask: 0
+        0x02, 0x00, // off: 2
+        0x02, // align: TCF_EM_ALIGN_U16, flags: 0
+        0x01, // layer: TCF_LAYER_NETWORK, opnd: TCF_EM_OPND_EQ
+        0x10, 0x00, // length 16
+        0x02, 0x00, // ematch 1
+        0x00, 0x00, // matchid: 0
+        0x00, 0x00, // kind: TCF_EM_CONTAINER
+        0x00, 0x00, // flags: TCF_EM_REL_END
+        0x00, 0x00, // pad
+        0x02, 0x00, 0x00, 0x00, // reference to ematch 2
+        0x28, 0x00, // length 40
+        0x03, 0x00, // ematch 2
+        0x00, 0x00, // matchid: 0
+        0x04, 0x00, // kind: TCF_EM_META
+        0x02, 0x00, // flags: TCF_EM_REL_OR
+        0x00, 0x00, // pad
+        0x0c, 0x00, // length 12
+        0x01, 0x00, // TCA_EM_META_HDR
+        0x0c, 0x10, // left kind: TCF_META_TYPE_INT, TCF_META_ID_NFMARK
+        0x00, // left shift: 0
+        0x01, // left op: TCF_EM_OPND_GT
+        0x00, 0x10, // right kind: TCF_META_TYPE_INT, TCF_META_ID_VALUE
+        0x00, // right shift: 0
+        0x00, // right op: TCF_EM_OPND_EQ
+        0x08, 0x00, // length 8
+        0x02, 0x00, // TCA_EM_META_LVALUE
+        0x00, 0x00, 0x00, 0x00, // 0
+        0x08, 0x00, // length 8
+        0x03, 0x00, // TCA_EM_META_RVALUE
+        0x0a, 0x00, 0x00, 0x00, // 10
+        0x1c, 0x00, // length 28
+        0x04, 0x00, // ematch 3
+        0x00, 0x00, // matchid: 0
+        0x03, 0x00, // kind: TCF_EM_U32
+        0x04, 0x00, // flags: TCF_EM_INVERT | TCF_EM_REL_END
+        0x00, 0x00, // pad
+        0xff, 0x00, 0x00, 0x00, // mask: 255.0.0.0
+        0x0a, 0x00, 0x00, 0x00, // val: 10.0.0.0
+        0x0c, 0x00, 0x00, 0x00, // off: 12
+        0x00, 0x00, 0x00, 0x00, // offmask: 0
+        0x08, 0x00, // length 8
+        0x01, 0x00, // TCA_BASIC_CLASSID
+        0x01, 0x00, 0x01, 0x00, // 1:1
+    ];
+
+    let expected = TcMessage {
+        header: TcHeader {
+            family: AddressFamily::Unspec,
+            index: 1,
+            handle: TcHandle::UNSPEC,
+            parent: TcHandle { major: 1, minor: 0 },
+            info: 0x10008,
+        },
+        attributes: vec![
+            TcAttribute::Kind("basic".to_string()),
+            TcAttribute::Options(vec![
+                TcOption::Basic(TcFilterBasicOption::Ematches(TcEmatchTree {
+                    progid: 0,
+                    terms: vec![
+                        TcEmatchTerm {
+                            matchid: 0,
+                            relation: TcEmatchRelation::And,
+                            invert: false,
+                            flags: 0,
+                            expr: TcEmatchExpr::Cmp(TcEmatchCmp {
+                                val: 0x1234,
+                                mask: 0,
+                                off: 2,
+                                align: TcEmatchCmpAlign::U16,
+                                flags: 0,
+                                layer: TcEmatchLayer::Network,
+                                opnd: TcEmatchOperand::Eq,
+                            }),
+                        },
+                        TcEmatchTerm {
+                            matchid: 0,
+                            relation: TcEmatchRelation::End,
+                            invert: false,
+                            flags: 0,
+                            expr: TcEmatchExpr::Group(vec![
+                                TcEmatchTerm {
+                                    matchid: 0,
+                                    relation: TcEmatchRelation::Or,
+                                    invert: false,
+                                    flags: 0,
+                                    expr: TcEmatchExpr::Meta(vec![
+                                        TcEmatchMetaOption::Header(
+                                            TcEmatchMetaHeader {
+                                                left: TcEmatchMetaValue {
+                                                    value_type:
+                                                        TcEmatchMetaType::Int,
+                                                    id: TcEmatchMetaId::NfMark,
+                                                    shift: 0,
+                                                    op: TcEmatchOperand::Gt,
+                                                },
+                                                right: TcEmatchMetaValue {
+                                                    value_type:
+                                                        TcEmatchMetaType::Int,
+                                                    id: TcEmatchMetaId::Value,
+                                                    shift: 0,
+                                                    op: TcEmatchOperand::Eq,
+                                                },
+                                            },
+                                        ),
+                                        TcEmatchMetaOption::Lvalue(vec![
+                                            0, 0, 0, 0,
+                                        ]),
+                                        TcEmatchMetaOption::Rvalue(vec![
+                                            10, 0, 0, 0,
+                                        ]),
+                                    ]),
+                                },
+                                TcEmatchTerm {
+                                    matchid: 0,
+                                    relation: TcEmatchRelation::End,
+                                    invert: true,
+                                    flags: 0,
+                                    expr: TcEmatchExpr::U32(TcU32Key {
+                                        mask: 0xff,
+                                        val: 0x0a,
+                                        off: 12,
+                                        offmask: 0,
+                                    }),
+                                },
+                            ]),
+                        },
+                    ],
+                })),
+                TcOption::Basic(TcFilterBasicOption::ClassId(TcHandle {
+                    major: 1,
+                    minor: 1,
+                })),
+            ]),
+        ],
+    };
+
+    assert_eq!(
+        expected,
+        TcMessage::parse(&TcMessageBuffer::new(&raw)).unwrap()
+    );
+
+    let mut buf = vec![0; expected.buffer_len()];
+
+    expected.emit(&mut buf);
+
+    assert_eq!(buf, raw);
+}
+
+// Capture nlmon of this command:
+//
+//      tc filter add dev lo parent 1: protocol ip pref 2 basic match \
+//          'nbyte("abcd" at 4 layer transport)' classid 1:2
+//
+// Raw packet modification:
+//   * rtnetlink header removed.
+#[test]
+fn test_new_filter_basic_nbyte() {
+    let raw = vec![
+        0x00, // AF_UNSPEC
+        0x00, 0x00, 0x00, // padding
+        0x01, 0x00, 0x00, 0x00, // iface index: 1
+        0x00, 0x00, 0x00, 0x00, // handle 0:0
+        0x00, 0x00, 0x01, 0x00, // parent 1:0
+        0x08, 0x00, 0x02, 0x00, // info: pref 2, protocol ip
+        0x0a, 0x00, // length 10
+        0x01, 0x00, // TCA_KIND
+        0x62, 0x61, 0x73, 0x69, 0x63, 0x00, 0x00, 0x00,
+        // "basic\0" and 2 padding bytes
+        0x30, 0x00, // length 48
+        0x02, 0x00, // TCA_OPTIONS for `basic`
+        0x24, 0x00, // length 36
+        0x02, 0x00, // TCA_BASIC_EMATCHES
+        0x08, 0x00, // length 8
+        0x01, 0x00, // TCA_EMATCH_TREE_HDR
+        0x01, 0x00, // nmatches: 1
+        0x00, 0x00, // progid: 0
+        0x18, 0x00, // length 24
+        0x02, 0x00, // TCA_EMATCH_TREE_LIST
+        0x14, 0x00, // length 20
+        0x01, 0x00, // ematch 0
+        0x00, 0x00, // matchid: 0
+        0x02, 0x00, // kind: TCF_EM_NBYTE
+        0x00, 0x00, // flags: TCF_EM_REL_END
+        0x00, 0x00, // pad
+        0x04, 0x00, // off: 4
+        0x04, 0x20, // len: 4, layer: TCF_LAYER_TRANSPORT
+        0x61, 0x62, 0x63, 0x64, // "abcd"
+        0x08, 0x00, // length 8
+        0x01, 0x00, // TCA_BASIC_CLASSID
+        0x02, 0x00, 0x01, 0x00, // 1:2
+    ];
+
+    let expected = TcMessage {
+        header: TcHeader {
+            family: AddressFamily::Unspec,
+            index: 1,
+            handle: TcHandle::UNSPEC,
+            parent: TcHandle { major: 1, minor: 0 },
+            info: 0x20008,
+        },
+        attributes: vec![
+            TcAttribute::Kind("basic".to_string()),
+            TcAttribute::Options(vec![
+                TcOption::Basic(TcFilterBasicOption::Ematches(TcEmatchTree {
+                    progid: 0,
+                    terms: vec![TcEmatchTerm {
+                        matchid: 0,
+                        relation: TcEmatchRelation::End,
+                        invert: false,
+                        flags: 0,
+                        expr: TcEmatchExpr::Nbyte(TcEmatchNbyte {
+                            off: 4,
+                            layer: TcEmatchLayer::Transport,
+                            needle: b"abcd".to_vec(),
+                        }),
+                    }],
+                })),
+                TcOption::Basic(TcFilterBasicOption::ClassId(TcHandle {
+                    major: 1,
+                    minor: 2,
+                })),
+            ]),
+        ],
+    };
+
+    assert_eq!(
+        expected,
+        TcMessage::parse(&TcMessageBuffer::new(&raw)).unwrap()
+    );
+
+    let mut buf = vec![0; expected.buffer_len()];
+
+    expected.emit(&mut buf);
+
+    assert_eq!(buf, raw);
+}
+
+// Raw packet follows the kernel `basic_dump()` layout for
+// `tc filter show dev lo` after
+// `tc filter add dev lo parent 1: protocol ip pref 3 basic \
+//      match 'ipset(foo src,dst)' classid 1:3`, once the filter looked up
+// 3 packets and matched 2 of them, with:
+//   * rtnetlink header removed.
+#[test]
+fn test_get_filter_basic_ipset() {
+    let raw = vec![
+        0x00, // AF_UNSPEC
+        0x00, 0x00, 0x00, // padding
+        0x01, 0x00, 0x00, 0x00, // iface index: 1
+        0x01, 0x00, 0x00, 0x00, // handle 0:1
+        0x00, 0x00, 0x01, 0x00, // parent 1:0
+        0x08, 0x00, 0x03, 0x00, // info: pref 3, protocol ip
+        0x0a, 0x00, // length 10
+        0x01, 0x00, // TCA_KIND
+        0x62, 0x61, 0x73, 0x69, 0x63, 0x00, 0x00, 0x00,
+        // "basic\0" and 2 padding bytes
+        0x08, 0x00, // length 8
+        0x0b, 0x00, // TCA_CHAIN
+        0x00, 0x00, 0x00, 0x00, // 0
+        0x40, 0x00, // length 64
+        0x02, 0x00, // TCA_OPTIONS for `basic`
+        0x08, 0x00, // length 8
+        0x01, 0x00, // TCA_BASIC_CLASSID
+        0x03, 0x00, 0x01, 0x00, // 1:3
+        0x14, 0x00, // length 20
+        0x05, 0x00, // TCA_BASIC_PCNT
+        0x03, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, // rcnt: 3
+        0x02, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, // rhit: 2
+        0x20, 0x00, // length 32
+        0x02, 0x00, // TCA_BASIC_EMATCHES
+        0x08, 0x00, // length 8
+        0x01, 0x00, // TCA_EMATCH_TREE_HDR
+        0x01, 0x00, // nmatches: 1
+        0x00, 0x00, // progid: 0
+        0x14, 0x00, // length 20
+        0x02, 0x00, // TCA_EMATCH_TREE_LIST
+        0x10, 0x00, // length 16
+        0x01, 0x00, // ematch 0
+        0x00, 0x00, // matchid: 0
+        0x08, 0x00, // kind: TCF_EM_IPSET
+        0x00, 0x00, // flags: TCF_EM_REL_END
+        0x00, 0x00, // pad
+        0x00, 0x00, // index: 0
+        0x02, // dim: 2
+        0x02, // flags: IPSET_DIM_ONE_SRC
+    ];
+
+    let expected = TcMessage {
+        header: TcHeader {
+            family: AddressFamily::Unspec,
+            index: 1,
+            handle: TcHandle { major: 0, minor: 1 },
+            parent: TcHandle { major: 1, minor: 0 },
+            info: 0x30008,
+        },
+        attributes: vec![
+            TcAttribute::Kind("basic".to_string()),
+            TcAttribute::Chain(0),
+            TcAttribute::Options(vec![
+                TcOption::Basic(TcFilterBasicOption::ClassId(TcHandle {
+                    major: 1,
+                    minor: 3,
+                })),
+                TcOption::Basic(TcFilterBasicOption::Pnct(TcBasicPcnt {
+                    rcnt: 3,
+                    rhit: 2,
+                })),
+                TcOption::Basic(TcFilterBasicOption::Ematches(TcEmatchTree {
+                    progid: 0,
+                    terms: vec![TcEmatchTerm {
+                        matchid: 0,
+                        relation: TcEmatchRelation::End,
+                        invert: false,
+                        flags: 0,
+                        expr: TcEmatchExpr::Ipset(TcEmatchIpset {
+                            index: 0,
+                            dim: 2,
+                            flags: 0x02,
+                        }),
+                    }],
+                })),
+            ]),
+        ],
+    };
+
+    assert_eq!(
+        expected,
+        TcMessage::parse(&TcMessageBuffer::new(&raw)).unwrap()
+    );
+
+    let mut buf = vec![0; expected.buffer_len()];
+
+    expected.emit(&mut buf);
+
+    assert_eq!(buf, raw);
+}
+
+// Raw packet follows the kernel `tcf_em_tree_dump()` layout for a basic
+// filter holding a single `TCF_EM_SIMPLE` ematch of kind not supported
+// by this crate, with:
+//   * rtnetlink header removed.
+#[test]
+fn test_filter_basic_ematch_simple() {
+    let raw = vec![
+        0x00, // AF_UNSPEC
+        0x00, 0x00, 0x00, // padding
+        0x01, 0x00, 0x00, 0x00, // iface index: 1
+        0x01, 0x00, 0x00, 0x00, // handle 0:1
+        0x00, 0x00, 0x01, 0x00, // parent 1:0
+        0x08, 0x00, 0x04, 0x00, // info: pref 4, protocol ip
+        0x0a, 0x00, // length 10
+        0x01, 0x00, // TCA_KIND
+        0x62, 0x61, 0x73, 0x69, 0x63, 0x00, 0x00, 0x00,
+        // "basic\0" and 2 padding bytes
+        0x2c, 0x00, // length 44
+        0x02, 0x00, // TCA_OPTIONS for `basic`
+        0x08, 0x00, // length 8
+        0x01, 0x00, // TCA_BASIC_CLASSID
+        0x04, 0x00, 0x01, 0x00, // 1:4
+        0x20, 0x00, // length 32
+        0x02, 0x00, // TCA_BASIC_EMATCHES
+        0x08, 0x00, // length 8
+        0x01, 0x00, // TCA_EMATCH_TREE_HDR
+        0x01, 0x00, // nmatches: 1
+        0x00, 0x00, // progid: 0
+        0x14, 0x00, // length 20
+        0x02, 0x00, // TCA_EMATCH_TREE_LIST
+        0x10, 0x00, // length 16
+        0x01, 0x00, // ematch 0
+        0x00, 0x00, // matchid: 0
+        0x06, 0x00, // kind: TCF_EM_VLAN
+        0x0c,
+        0x00, // flags: TCF_EM_SIMPLE | TCF_EM_INVERT | TCF_EM_REL_END
+        0x00, 0x00, // pad
+        0x64, 0x00, 0x00, 0x00, // simple value: 100
+    ];
+
+    let expected = TcMessage {
+        header: TcHeader {
+            family: AddressFamily::Unspec,
+            index: 1,
+            handle: TcHandle { major: 0, minor: 1 },
+            parent: TcHandle { major: 1, minor: 0 },
+            info: 0x40008,
+        },
+        attributes: vec![
+            TcAttribute::Kind("basic".to_string()),
+            TcAttribute::Options(vec![
+                TcOption::Basic(TcFilterBasicOption::ClassId(TcHandle {
+                    major: 1,
+                    minor: 4,
+                })),
+                TcOption::Basic(TcFilterBasicOption::Ematches(TcEmatchTree {
+                    progid: 0,
+                    terms: vec![TcEmatchTerm {
+                        matchid: 0,
+                        relation: TcEmatchRelation::End,
+                        invert: true,
+                        flags: 0x08,
+                        expr: TcEmatchExpr::Other(TcEmatchOther {
+                            kind: 6,
+                            data: vec![0x64, 0x00, 0x00, 0x00],
+                        }),
+                    }],
+                })),
+            ]),
+        ],
+    };
+
+    assert_eq!(
+        expected,
+        TcMessage::parse(&TcMessageBuffer::new(&raw)).unwrap()
+    );
+
+    let mut buf = vec![0; expected.buffer_len()];
+
+    expected.emit(&mut buf);
+
+    assert_eq!(buf, raw);
+}
+
+// Raw packet follows the kernel `tcf_em_tree_dump()` layout for a basic
+// filter whose two containers reference the same ematch sequence, which
+// is accepted by kernel but cannot be represented as `TcEmatchTree`, with:
+//   * rtnetlink header removed.
+#[test]
+fn test_filter_basic_ematch_shared_container() {
+    let raw = vec![
+        0x00, // AF_UNSPEC
+        0x00, 0x00, 0x00, // padding
+        0x01, 0x00, 0x00, 0x00, // iface index: 1
+        0x01, 0x00, 0x00, 0x00, // handle 0:1
+        0x00, 0x00, 0x01, 0x00, // parent 1:0
+        0x08, 0x00, 0x05, 0x00, // info: pref 5, protocol ip
+        0x0a, 0x00, // length 10
+        0x01, 0x00, // TCA_KIND
+        0x62, 0x61, 0x73, 0x69, 0x63, 0x00, 0x00, 0x00,
+        // "basic\0" and 2 padding bytes
+        0x4c, 0x00, // length 76
+        0x02, 0x00, // TCA_OPTIONS for `basic`
+        0x08, 0x00, // length 8
+        0x01, 0x00, // TCA_BASIC_CLASSID
+        0x05, 0x00, 0x01, 0x00, // 1:5
+        0x40, 0x00, // length 64
+        0x02, 0x00, // TCA_BASIC_EMATCHES
+        0x08, 0x00, // length 8
+        0x01, 0x00, // TCA_EMATCH_TREE_HDR
+        0x03, 0x00, // nmatches: 3
+        0x00, 0x00, // progid: 0
+        0x34, 0x00, // length 52
+        0x02, 0x00, // TCA_EMATCH_TREE_LIST
+        0x10, 0x00, // length 16
+        0x01, 0x00, // ematch 0
+        0x00, 0x00, // matchid: 0
+        0x00, 0x00, // kind: TCF_EM_CONTAINER
+        0x01, 0x00, // flags: TCF_EM_REL_AND
+        0x00, 0x00, // pad
+        0x02, 0x00, 0x00, 0x00, // reference: 2
+        0x10, 0x00, // length 16
+        0x02, 0x00, // ematch 1
+        0x00, 0x00, // matchid: 0
+        0x00, 0x00, // kind: TCF_EM_CONTAINER
+        0x00, 0x00, // flags: TCF_EM_REL_END
+        0x00, 0x00, // pad
+        0x02, 0x00, 0x00, 0x00, // reference: 2
+        0x10, 0x00, // length 16
+        0x03, 0x00, // ematch 2
+        0x00, 0x00, // matchid: 0
+        0x08, 0x00, // kind: TCF_EM_IPSET
+        0x00, 0x00, // flags: TCF_EM_REL_END
+        0x00, 0x00, // pad
+        0x00, 0x00, // index: 0
+        0x02, // dim: 2
+        0x02, // flags: IPSET_DIM_ONE_SRC
+    ];
+
+    assert!(TcMessage::parse(&TcMessageBuffer::new(&raw)).is_err());
+}
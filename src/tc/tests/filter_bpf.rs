@@ -0,0 +1,164 @@
+// SPDX-License-Identifier: MIT
+
+use netlink_packet_utils::{Emitable, Parseable};
+
+use crate::{
+    tc::{
+        TcAttribute, TcBpfFlag, TcBpfSockFilter, TcFilterBpfOption, TcHandle,
+        TcHeader, TcMessage, TcMessageBuffer, TcOption, TcU32OptionFlag,
+    },
+    AddressFamily,
+};
+
+// Capture nlmon of this command:
+//
+//      tc filter add dev lo ingress pref 10 protocol all bpf \
+//          bytecode '1,6 0 0 4294967295,' classid 1:1
+//
+// Raw packet modification:
+//   * rtnetlink header removed.
+#[test]
+fn test_new_filter_bpf_classic() {
+    let raw = vec![
+        0x00, // AF_UNSPEC
+        0x00, 0x00, 0x00, // padding
+        0x01, 0x00, 0x00, 0x00, // iface index: 1
+        0x00, 0x00, 0x00, 0x00, // handle 0:0
+        0xf2, 0xff, 0xff, 0xff, // parent ffff:fff2 (clsact ingress)
+        0x00, 0x03, 0x0a, 0x00, // info: pref 10, protocol all
+        0x08, 0x00, // length 8
+        0x01, 0x00, // TCA_KIND
+        0x62, 0x70, 0x66, 0x00, // "bpf\0"
+        0x20, 0x00, // length 32
+        0x02, 0x00, // TCA_OPTIONS for `bpf`
+        0x08, 0x00, // length 8
+        0x03, 0x00, // TCA_BPF_CLASSID
+        0x01, 0x00, 0x01, 0x00, // 1:1
+        0x06, 0x00, // length 6
+        0x04, 0x00, // TCA_BPF_OPS_LEN
+        0x01, 0x00, 0x00, 0x00, // 1 and 2 padding bytes
+        0x0c, 0x00, // length 12
+        0x05, 0x00, // TCA_BPF_OPS
+        0x06, 0x00, // code: 6 (BPF_RET | BPF_K)
+        0x00, // jt: 0
+        0x00, // jf: 0
+        0xff, 0xff, 0xff, 0xff, // k: 4294967295
+    ];
+
+    let expected = TcMessage {
+        header: TcHeader {
+            family: AddressFamily::Unspec,
+            index: 1,
+            handle: TcHandle::UNSPEC,
+            parent: TcHandle::CLSACT_INGRESS,
+            info: 0xa0300,
+        },
+        attributes: vec![
+            TcAttribute::Kind("bpf".to_string()),
+            TcAttribute::Options(vec![
+                TcOption::Bpf(TcFilterBpfOption::ClassId(TcHandle {
+                    major: 1,
+                    minor: 1,
+                })),
+                TcOption::Bpf(TcFilterBpfOption::OpsLen(1)),
+                TcOption::Bpf(TcFilterBpfOption::Ops(vec![TcBpfSockFilter {
+                    code: 6,
+                    jt: 0,
+                    jf: 0,
+                    k: u32::MAX,
+                }])),
+            ]),
+        ],
+    };
+
+    assert_eq!(
+        expected,
+        TcMessage::parse(&TcMessageBuffer::new(&raw)).unwrap()
+    );
+
+    let mut buf = vec![0; expected.buffer_len()];
+
+    expected.emit(&mut buf);
+
+    assert_eq!(buf, raw);
+}
+
+// Raw packet follows the kernel `cls_bpf_dump()` layout for
+// `tc filter show dev lo ingress` after
+// `tc filter add dev lo ingress bpf da obj bpf.o sec tc` with:
+//   * rtnetlink header removed.
+#[test]
+fn test_get_filter_bpf_direct_action() {
+    let raw = vec![
+        0x00, // AF_UNSPEC
+        0x00, 0x00, 0x00, // padding
+        0x01, 0x00, 0x00, 0x00, // iface index: 1
+        0x01, 0x00, 0x00, 0x00, // handle 0:1
+        0xf2, 0xff, 0xff, 0xff, // parent ffff:fff2 (clsact ingress)
+        0x00, 0x03, 0x00, 0xc0, // info: pref 49152, protocol all
+        0x08, 0x00, // length 8
+        0x01, 0x00, // TCA_KIND
+        0x62, 0x70, 0x66, 0x00, // "bpf\0"
+        0x08, 0x00, // length 8
+        0x0b, 0x00, // TCA_CHAIN
+        0x00, 0x00, 0x00, 0x00, // 0
+        0x38, 0x00, // length 56
+        0x02, 0x00, // TCA_OPTIONS for `bpf`
+        0x0f, 0x00, // length 15
+        0x07, 0x00, // TCA_BPF_NAME
+        0x62, 0x70, 0x66, 0x2e, 0x6f, 0x3a, 0x5b, 0x74, 0x63, 0x5d, 0x00,
+        0x00, // "bpf.o:[tc]\0" and 1 padding byte
+        0x08, 0x00, // length 8
+        0x0b, 0x00, // TCA_BPF_ID
+        0x2a, 0x00, 0x00, 0x00, // 42
+        0x0c, 0x00, // length 12
+        0x0a, 0x00, // TCA_BPF_TAG
+        0xa0, 0x4f, 0x5e, 0xef, 0x06, 0xa7, 0xf5, 0x55, // tag
+        0x08, 0x00, // length 8
+        0x08, 0x00, // TCA_BPF_FLAGS
+        0x01, 0x00, 0x00, 0x00, // TCA_BPF_FLAG_ACT_DIRECT
+        0x08, 0x00, // length 8
+        0x09, 0x00, // TCA_BPF_FLAGS_GEN
+        0x08, 0x00, 0x00, 0x00, // TCA_CLS_FLAGS_NOT_IN_HW
+    ];
+
+    let expected = TcMessage {
+        header: TcHeader {
+            family: AddressFamily::Unspec,
+            index: 1,
+            handle: TcHandle { major: 0, minor: 1 },
+            parent: TcHandle::CLSACT_INGRESS,
+            info: 0xc0000300,
+        },
+        attributes: vec![
+            TcAttribute::Kind("bpf".to_string()),
+            TcAttribute::Chain(0),
+            TcAttribute::Options(vec![
+                TcOption::Bpf(TcFilterBpfOption::Name(
+                    "bpf.o:[tc]".to_string(),
+                )),
+                TcOption::Bpf(TcFilterBpfOption::Id(42)),
+                TcOption::Bpf(TcFilterBpfOption::Tag([
+                    0xa0, 0x4f, 0x5e, 0xef, 0x06, 0xa7, 0xf5, 0x55,
+                ])),
+                TcOption::Bpf(TcFilterBpfOption::Flags(vec![
+                    TcBpfFlag::ActDirect,
+                ])),
+                TcOption::Bpf(TcFilterBpfOption::FlagsGen(vec![
+                    TcU32OptionFlag::NotInHw,
+                ])),
+            ]),
+        ],
+    };
+
+    assert_eq!(
+        expected,
+        TcMessage::parse(&TcMessageBuffer::new(&raw)).unwrap()
+    );
+
+    let mut buf = vec![0; expected.buffer_len()];
+
+    expected.emit(&mut buf);
+
+    assert_eq!(buf, raw);
+}
@@ -0,0 +1,105 @@
+// SPDX-License-Identifier: MIT
+
+use netlink_packet_utils::{Emitable, Parseable};
+
+use crate::{
+    tc::{
+        TcAction, TcActionAttribute, TcActionGeneric, TcActionMirrorOption,
+        TcActionOption, TcActionType, TcAttribute, TcFilterCgroupOption,
+        TcHandle, TcHeader, TcMessage, TcMessageBuffer, TcMirror,
+        TcMirrorActionType, TcOption,
+    },
+    AddressFamily,
+};
+
+// Capture nlmon of this command:
+//
+//      tc filter add dev lo parent 1: protocol all pref 5 handle 1 \
+//          cgroup action mirred egress redirect dev lo
+//
+// Raw packet modification:
+//   * rtnetlink header removed.
+//   * NLA_F_NESTED flag removed from TCA_ACT_OPTIONS.
+#[test]
+fn test_new_filter_cgroup() {
+    let raw = vec![
+        0x00, // AF_UNSPEC
+        0x00, 0x00, 0x00, // padding
+        0x01, 0x00, 0x00, 0x00, // iface index: 1
+        0x01, 0x00, 0x00, 0x00, // handle 0:1
+        0x00, 0x00, 0x01, 0x00, // parent 1:0
+        0x00, 0x03, 0x05, 0x00, // info: pref 5, protocol all
+        0x0b, 0x00, // length 11
+        0x01, 0x00, // TCA_KIND
+        0x63, 0x67, 0x72, 0x6f, 0x75, 0x70, 0x00, 0x00,
+        // "cgroup\0" and 1 padding byte
+        0x3c, 0x00, // length 60
+        0x02, 0x00, // TCA_OPTIONS for `cgroup`
+        0x38, 0x00, // length 56
+        0x01, 0x00, // TCA_CGROUP_ACT
+        0x34, 0x00, // length 52
+        0x01, 0x00, // TCA_ACT_TAB
+        0x0b, 0x00, // length 11
+        0x01, 0x00, // TCA_ACT_KIND
+        0x6d, 0x69, 0x72, 0x72, 0x65, 0x64, 0x00, 0x00,
+        // "mirred\0" and 1 padding byte
+        0x24, 0x00, // length 36
+        0x02, 0x00, // TCA_ACT_OPTIONS
+        0x20, 0x00, // length 32
+        0x02, 0x00, // TCA_MIRRED_PARMS
+        0x00, 0x00, 0x00, 0x00, // index 0
+        0x00, 0x00, 0x00, 0x00, // capab 0
+        0x04, 0x00, 0x00, 0x00, // action 4 (TC_ACT_STOLEN)
+        0x00, 0x00, 0x00, 0x00, // refcount 0
+        0x00, 0x00, 0x00, 0x00, // bindcnt 0
+        0x01, 0x00, 0x00, 0x00, // eaction 1 (TCA_EGRESS_REDIR)
+        0x01, 0x00, 0x00, 0x00, // ifindex 1
+    ];
+
+    let expected = TcMessage {
+        header: TcHeader {
+            family: AddressFamily::Unspec,
+            index: 1,
+            handle: TcHandle { major: 0, minor: 1 },
+            parent: TcHandle { major: 1, minor: 0 },
+            info: 0x50300,
+        },
+        attributes: vec![
+            TcAttribute::Kind("cgroup".to_string()),
+            TcAttribute::Options(vec![TcOption::Cgroup(
+                TcFilterCgroupOption::Action(vec![TcAction {
+                    tab: 1,
+                    attributes: vec![
+                        TcActionAttribute::Kind("mirred".to_string()),
+                        TcActionAttribute::Options(vec![
+                            TcActionOption::Mirror(
+                                TcActionMirrorOption::Parms(TcMirror {
+                                    generic: TcActionGeneric {
+                                        index: 0,
+                                        capab: 0,
+                                        action: TcActionType::Stolen,
+                                        refcnt: 0,
+                                        bindcnt: 0,
+                                    },
+                                    eaction: TcMirrorActionType::EgressRedir,
+                                    ifindex: 1,
+                                }),
+                            ),
+                        ]),
+                    ],
+                }]),
+            )]),
+        ],
+    };
+
+    assert_eq!(
+        expected,
+        TcMessage::parse(&TcMessageBuffer::new(&raw)).unwrap()
+    );
+
+    let mut buf = vec![0; expected.buffer_len()];
+
+    expected.emit(&mut buf);
+
+    assert_eq!(buf, raw);
+}
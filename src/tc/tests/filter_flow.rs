@@ -0,0 +1,173 @@
+// SPDX-License-Identifier: MIT
+
+use netlink_packet_utils::{Emitable, Parseable};
+
+use crate::{
+    tc::{
+        TcAttribute, TcFilterFlowOption, TcFlowKey, TcFlowMode, TcHandle,
+        TcHeader, TcMessage, TcMessageBuffer, TcOption,
+    },
+    AddressFamily,
+};
+
+// Capture nlmon of this command:
+//
+//      tc filter add dev lo parent 1: protocol ip pref 3 handle 1 \
+//          flow hash keys src,dst,proto-dst divisor 1024 perturb 10 \
+//          baseclass 1:1
+//
+// Raw packet modification:
+//   * rtnetlink header removed.
+#[test]
+fn test_new_filter_flow_hash() {
+    let raw = vec![
+        0x00, // AF_UNSPEC
+        0x00, 0x00, 0x00, // padding
+        0x01, 0x00, 0x00, 0x00, // iface index: 1
+        0x01, 0x00, 0x00, 0x00, // handle 0:1
+        0x00, 0x00, 0x01, 0x00, // parent 1:0
+        0x08, 0x00, 0x03, 0x00, // info: pref 3, protocol ip
+        0x09, 0x00, // length 9
+        0x01, 0x00, // TCA_KIND
+        0x66, 0x6c, 0x6f, 0x77, 0x00, 0x00, 0x00, 0x00,
+        // "flow\0" and 3 padding bytes
+        0x2c, 0x00, // length 44
+        0x02, 0x00, // TCA_OPTIONS for `flow`
+        0x08, 0x00, // length 8
+        0x01, 0x00, // TCA_FLOW_KEYS
+        0x13, 0x00, 0x00, 0x00, // src, dst and proto-dst
+        0x08, 0x00, // length 8
+        0x08, 0x00, // TCA_FLOW_DIVISOR
+        0x00, 0x04, 0x00, 0x00, // 1024
+        0x08, 0x00, // length 8
+        0x0c, 0x00, // TCA_FLOW_PERTURB
+        0x0a, 0x00, 0x00, 0x00, // 10
+        0x08, 0x00, // length 8
+        0x03, 0x00, // TCA_FLOW_BASECLASS
+        0x01, 0x00, 0x01, 0x00, // 1:1
+        0x08, 0x00, // length 8
+        0x02, 0x00, // TCA_FLOW_MODE
+        0x01, 0x00, 0x00, 0x00, // FLOW_MODE_HASH
+    ];
+
+    let expected = TcMessage {
+        header: TcHeader {
+            family: AddressFamily::Unspec,
+            index: 1,
+            handle: TcHandle { major: 0, minor: 1 },
+            parent: TcHandle { major: 1, minor: 0 },
+            info: 0x30008,
+        },
+        attributes: vec![
+            TcAttribute::Kind("flow".to_string()),
+            TcAttribute::Options(vec![
+                TcOption::Flow(TcFilterFlowOption::Keys(vec![
+                    TcFlowKey::Src,
+                    TcFlowKey::Dst,
+                    TcFlowKey::ProtoDst,
+                ])),
+                TcOption::Flow(TcFilterFlowOption::Divisor(1024)),
+                TcOption::Flow(TcFilterFlowOption::Perturb(10)),
+                TcOption::Flow(TcFilterFlowOption::BaseClass(TcHandle {
+                    major: 1,
+                    minor: 1,
+                })),
+                TcOption::Flow(TcFilterFlowOption::Mode(TcFlowMode::Hash)),
+            ]),
+        ],
+    };
+
+    assert_eq!(
+        expected,
+        TcMessage::parse(&TcMessageBuffer::new(&raw)).unwrap()
+    );
+
+    let mut buf = vec![0; expected.buffer_len()];
+
+    expected.emit(&mut buf);
+
+    assert_eq!(buf, raw);
+}
+
+// Capture nlmon of this command:
+//
+//      tc filter add dev lo parent 1: protocol ip pref 4 handle 2 \
+//          flow map key mark and 0xff xor 1 rshift 2 addend 3 \
+//          baseclass 1:1
+//
+// Raw packet modification:
+//   * rtnetlink header removed.
+#[test]
+fn test_new_filter_flow_map() {
+    let raw = vec![
+        0x00, // AF_UNSPEC
+        0x00, 0x00, 0x00, // padding
+        0x01, 0x00, 0x00, 0x00, // iface index: 1
+        0x02, 0x00, 0x00, 0x00, // handle 0:2
+        0x00, 0x00, 0x01, 0x00, // parent 1:0
+        0x08, 0x00, 0x04, 0x00, // info: pref 4, protocol ip
+        0x09, 0x00, // length 9
+        0x01, 0x00, // TCA_KIND
+        0x66, 0x6c, 0x6f, 0x77, 0x00, 0x00, 0x00, 0x00,
+        // "flow\0" and 3 padding bytes
+        0x3c, 0x00, // length 60
+        0x02, 0x00, // TCA_OPTIONS for `flow`
+        0x08, 0x00, // length 8
+        0x01, 0x00, // TCA_FLOW_KEYS
+        0x80, 0x00, 0x00, 0x00, // mark
+        0x08, 0x00, // length 8
+        0x04, 0x00, // TCA_FLOW_RSHIFT
+        0x02, 0x00, 0x00, 0x00, // 2
+        0x08, 0x00, // length 8
+        0x05, 0x00, // TCA_FLOW_ADDEND
+        0x03, 0x00, 0x00, 0x00, // 3
+        0x08, 0x00, // length 8
+        0x03, 0x00, // TCA_FLOW_BASECLASS
+        0x01, 0x00, 0x01, 0x00, // 1:1
+        0x08, 0x00, // length 8
+        0x02, 0x00, // TCA_FLOW_MODE
+        0x00, 0x00, 0x00, 0x00, // FLOW_MODE_MAP
+        0x08, 0x00, // length 8
+        0x06, 0x00, // TCA_FLOW_MASK
+        0xff, 0x00, 0x00, 0x00, // 0xff
+        0x08, 0x00, // length 8
+        0x07, 0x00, // TCA_FLOW_XOR
+        0x01, 0x00, 0x00, 0x00, // 1
+    ];
+
+    let expected = TcMessage {
+        header: TcHeader {
+            family: AddressFamily::Unspec,
+            index: 1,
+            handle: TcHandle { major: 0, minor: 2 },
+            parent: TcHandle { major: 1, minor: 0 },
+            info: 0x40008,
+        },
+        attributes: vec![
+            TcAttribute::Kind("flow".to_string()),
+            TcAttribute::Options(vec![
+                TcOption::Flow(TcFilterFlowOption::Keys(vec![TcFlowKey::Mark])),
+                TcOption::Flow(TcFilterFlowOption::Rshift(2)),
+                TcOption::Flow(TcFilterFlowOption::Addend(3)),
+                TcOption::Flow(TcFilterFlowOption::BaseClass(TcHandle {
+                    major: 1,
+                    minor: 1,
+                })),
+                TcOption::Flow(TcFilterFlowOption::Mode(TcFlowMode::Map)),
+                TcOption::Flow(TcFilterFlowOption::Mask(0xff)),
+                TcOption::Flow(TcFilterFlowOption::Xor(1)),
+            ]),
+        ],
+    };
+
+    assert_eq!(
+        expected,
+        TcMessage::parse(&TcMessageBuffer::new(&raw)).unwrap()
+    );
+
+    let mut buf = vec![0; expected.buffer_len()];
+
+    expected.emit(&mut buf);
+
+    assert_eq!(buf, raw);
+}
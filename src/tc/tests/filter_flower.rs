@@ -0,0 +1,749 @@
+// SPDX-License-Identifier: MIT
+
+use std::net::Ipv4Addr;
+
+use netlink_packet_utils::{Emitable, Parseable};
+
+use crate::{
+    tc::{
+        TcAction, TcActionAttribute, TcActionGactOption, TcActionGeneric,
+        TcActionMirrorOption, TcActionOption, TcActionType, TcAttribute,
+        TcFilterFlowerOption, TcFlowerCtFlag, TcFlowerEncOpts,
+        TcFlowerGeneveOpt, TcFlowerKeyFlag, TcFlowerMplsLseOpt,
+        TcFlowerVxlanOpt, TcGact, TcHandle, TcHeader, TcMessage,
+        TcMessageBuffer, TcMirror, TcMirrorActionType, TcOption,
+        TcU32OptionFlag,
+    },
+    AddressFamily, IpProtocol,
+};
+
+// Capture nlmon of this command:
+//
+//      tc filter add dev lo ingress protocol ip pref 1 flower skip_hw \
+//          dst_mac 02:00:00:00:00:01 ip_proto tcp src_ip 192.168.1.0/24 \
+//          dst_port 80 tcp_flags 0x02/0x12 \
+//          action mirred egress redirect dev lo
+//
+// Raw packet modification:
+//   * rtnetlink header removed.
+//   * NLA_F_NESTED flag removed from TCA_ACT_OPTIONS.
+#[test]
+fn test_new_filter_flower() {
+    let raw = vec![
+        0x00, // AF_UNSPEC
+        0x00, 0x00, 0x00, // padding
+        0x01, 0x00, 0x00, 0x00, // iface index: 1
+        0x00, 0x00, 0x00, 0x00, // handle 0:0
+        0xf2, 0xff, 0xff, 0xff, // parent ffff:fff2 (clsact ingress)
+        0x08, 0x00, 0x01, 0x00, // info: pref 1, protocol ip
+        0x0b, 0x00, // length 11
+        0x01, 0x00, // TCA_KIND
+        0x66, 0x6c, 0x6f, 0x77, 0x65, 0x72, 0x00, 0x00,
+        // "flower\0" and 1 padding byte
+        0x94, 0x00, // length 148
+        0x02, 0x00, // TCA_OPTIONS for `flower`
+        0x0a, 0x00, // length 10
+        0x04, 0x00, // TCA_FLOWER_KEY_ETH_DST
+        0x02, 0x00, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00,
+        // 02:00:00:00:00:01 and 2 padding bytes
+        0x0a, 0x00, // length 10
+        0x05, 0x00, // TCA_FLOWER_KEY_ETH_DST_MASK
+        0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0x00, 0x00,
+        // ff:ff:ff:ff:ff:ff and 2 padding bytes
+        0x05, 0x00, // length 5
+        0x09, 0x00, // TCA_FLOWER_KEY_IP_PROTO
+        0x06, 0x00, 0x00, 0x00, // IPPROTO_TCP and 3 padding bytes
+        0x08, 0x00, // length 8
+        0x0a, 0x00, // TCA_FLOWER_KEY_IPV4_SRC
+        0xc0, 0xa8, 0x01, 0x00, // 192.168.1.0
+        0x08, 0x00, // length 8
+        0x0b, 0x00, // TCA_FLOWER_KEY_IPV4_SRC_MASK
+        0xff, 0xff, 0xff, 0x00, // 255.255.255.0
+        0x06, 0x00, // length 6
+        0x13, 0x00, // TCA_FLOWER_KEY_TCP_DST
+        0x00, 0x50, 0x00, 0x00, // 80 in big endian and 2 padding bytes
+        0x06, 0x00, // length 6
+        0x47, 0x00, // TCA_FLOWER_KEY_TCP_FLAGS
+        0x00, 0x02, 0x00, 0x00, // SYN in big endian and 2 padding bytes
+        0x06, 0x00, // length 6
+        0x48, 0x00, // TCA_FLOWER_KEY_TCP_FLAGS_MASK
+        0x00, 0x12, 0x00,
+        0x00, // SYN|ACK in big endian and 2 padding bytes
+        0x38, 0x00, // length 56
+        0x03, 0x00, // TCA_FLOWER_ACT
+        0x34, 0x00, // length 52
+        0x01, 0x00, // TCA_ACT_TAB
+        0x0b, 0x00, // length 11
+        0x01, 0x00, // TCA_ACT_KIND
+        0x6d, 0x69, 0x72, 0x72, 0x65, 0x64, 0x00, 0x00,
+        // "mirred\0" and 1 padding byte
+        0x24, 0x00, // length 36
+        0x02, 0x00, // TCA_ACT_OPTIONS
+        0x20, 0x00, // length 32
+        0x02, 0x00, // TCA_MIRRED_PARMS
+        0x00, 0x00, 0x00, 0x00, // index 0
+        0x00, 0x00, 0x00, 0x00, // capab 0
+        0x04, 0x00, 0x00, 0x00, // action 4 (TC_ACT_STOLEN)
+        0x00, 0x00, 0x00, 0x00, // refcount 0
+        0x00, 0x00, 0x00, 0x00, // bindcnt 0
+        0x01, 0x00, 0x00, 0x00, // eaction 1 (TCA_EGRESS_REDIR)
+        0x01, 0x00, 0x00, 0x00, // ifindex 1
+        0x08, 0x00, // length 8
+        0x16, 0x00, // TCA_FLOWER_FLAGS
+        0x01, 0x00, 0x00, 0x00, // TCA_CLS_FLAGS_SKIP_HW
+        0x06, 0x00, // length 6
+        0x08, 0x00, // TCA_FLOWER_KEY_ETH_TYPE
+        0x08, 0x00, 0x00,
+        0x00, // 0x0800 in big endian and 2 padding bytes
+    ];
+
+    let expected = TcMessage {
+        header: TcHeader {
+            family: AddressFamily::Unspec,
+            index: 1,
+            handle: TcHandle::UNSPEC,
+            parent: TcHandle::CLSACT_INGRESS,
+            info: 0x10008,
+        },
+        attributes: vec![
+            TcAttribute::Kind("flower".to_string()),
+            TcAttribute::Options(vec![
+                TcOption::Flower(TcFilterFlowerOption::KeyEthDst([
+                    0x02, 0, 0, 0, 0, 0x01,
+                ])),
+                TcOption::Flower(TcFilterFlowerOption::KeyEthDstMask(
+                    [0xff; 6],
+                )),
+                TcOption::Flower(TcFilterFlowerOption::KeyIpProto(
+                    IpProtocol::Tcp,
+                )),
+                TcOption::Flower(TcFilterFlowerOption::KeyIpv4Src(
+                    Ipv4Addr::new(192, 168, 1, 0),
+                )),
+                TcOption::Flower(TcFilterFlowerOption::KeyIpv4SrcMask(
+                    Ipv4Addr::new(255, 255, 255, 0),
+                )),
+                TcOption::Flower(TcFilterFlowerOption::KeyTcpDst(80)),
+                TcOption::Flower(TcFilterFlowerOption::KeyTcpFlags(0x02)),
+                TcOption::Flower(TcFilterFlowerOption::KeyTcpFlagsMask(0x12)),
+                TcOption::Flower(TcFilterFlowerOption::Action(vec![
+                    TcAction {
+                        tab: 1,
+                        attributes: vec![
+                            TcActionAttribute::Kind("mirred".to_string()),
+                            TcActionAttribute::Options(vec![
+                                TcActionOption::Mirror(
+                                    TcActionMirrorOption::Parms(TcMirror {
+                                        generic: TcActionGeneric {
+                                            index: 0,
+                                            capab: 0,
+                                            action: TcActionType::Stolen,
+                                            refcnt: 0,
+                                            bindcnt: 0,
+                                        },
+                                        eaction:
+                                            TcMirrorActionType::EgressRedir,
+                                        ifindex: 1,
+                                    }),
+                                ),
+                            ]),
+                        ],
+                    },
+                ])),
+                TcOption::Flower(TcFilterFlowerOption::Flags(vec![
+                    TcU32OptionFlag::SkipHw,
+                ])),
+                TcOption::Flower(TcFilterFlowerOption::KeyEthType(0x0800)),
+            ]),
+        ],
+    };
+
+    assert_eq!(
+        expected,
+        TcMessage::parse(&TcMessageBuffer::new(&raw)).unwrap()
+    );
+
+    let mut buf = vec![0; expected.buffer_len()];
+
+    expected.emit(&mut buf);
+
+    assert_eq!(buf, raw);
+}
+
+// Capture nlmon of this command:
+//
+//      tc filter add dev lo ingress protocol ip pref 1 flower \
+//          enc_key_id 100 enc_dst_ip 10.0.0.1 enc_src_ip 10.0.0.2 \
+//          enc_dst_port 6081 enc_tos 0x10/0xff \
+//          geneve_opts 0102:80:00112233/ffff:ff:ffffffff
+//
+// Raw packet modification:
+//   * rtnetlink header removed.
+#[test]
+fn test_new_filter_flower_enc_opts() {
+    let raw = vec![
+        0x00, // AF_UNSPEC
+        0x00, 0x00, 0x00, // padding
+        0x01, 0x00, 0x00, 0x00, // iface index: 1
+        0x00, 0x00, 0x00, 0x00, // handle 0:0
+        0xf2, 0xff, 0xff, 0xff, // parent ffff:fff2 (clsact ingress)
+        0x08, 0x00, 0x01, 0x00, // info: pref 1, protocol ip
+        0x0b, 0x00, // length 11
+        0x01, 0x00, // TCA_KIND
+        0x66, 0x6c, 0x6f, 0x77, 0x65, 0x72, 0x00, 0x00,
+        // "flower\0" and 1 padding byte
+        0x94, 0x00, // length 148
+        0x02, 0x00, // TCA_OPTIONS for `flower`
+        0x08, 0x00, // length 8
+        0x1a, 0x00, // TCA_FLOWER_KEY_ENC_KEY_ID
+        0x00, 0x00, 0x00, 0x64, // 100 in big endian
+        0x08, 0x00, // length 8
+        0x1d, 0x00, // TCA_FLOWER_KEY_ENC_IPV4_DST
+        0x0a, 0x00, 0x00, 0x01, // 10.0.0.1
+        0x08, 0x00, // length 8
+        0x1e, 0x00, // TCA_FLOWER_KEY_ENC_IPV4_DST_MASK
+        0xff, 0xff, 0xff, 0xff, // 255.255.255.255
+        0x08, 0x00, // length 8
+        0x1b, 0x00, // TCA_FLOWER_KEY_ENC_IPV4_SRC
+        0x0a, 0x00, 0x00, 0x02, // 10.0.0.2
+        0x08, 0x00, // length 8
+        0x1c, 0x00, // TCA_FLOWER_KEY_ENC_IPV4_SRC_MASK
+        0xff, 0xff, 0xff, 0xff, // 255.255.255.255
+        0x06, 0x00, // length 6
+        0x2d, 0x00, // TCA_FLOWER_KEY_ENC_UDP_DST_PORT
+        0x17, 0xc1, 0x00, 0x00, // 6081 in big endian and 2 padding bytes
+        0x05, 0x00, // length 5
+        0x50, 0x00, // TCA_FLOWER_KEY_ENC_IP_TOS
+        0x10, 0x00, 0x00, 0x00, // 0x10 and 3 padding bytes
+        0x05, 0x00, // length 5
+        0x51, 0x00, // TCA_FLOWER_KEY_ENC_IP_TOS_MASK
+        0xff, 0x00, 0x00, 0x00, // 0xff and 3 padding bytes
+        0x20, 0x00, // length 32
+        0x54, 0x00, // TCA_FLOWER_KEY_ENC_OPTS
+        0x1c, 0x00, // length 28
+        0x01, 0x00, // TCA_FLOWER_KEY_ENC_OPTS_GENEVE
+        0x06, 0x00, // length 6
+        0x01, 0x00, // TCA_FLOWER_KEY_ENC_OPT_GENEVE_CLASS
+        0x01, 0x02, 0x00,
+        0x00, // 0x0102 in big endian and 2 padding bytes
+        0x05, 0x00, // length 5
+        0x02, 0x00, // TCA_FLOWER_KEY_ENC_OPT_GENEVE_TYPE
+        0x80, 0x00, 0x00, 0x00, // 0x80 and 3 padding bytes
+        0x08, 0x00, // length 8
+        0x03, 0x00, // TCA_FLOWER_KEY_ENC_OPT_GENEVE_DATA
+        0x00, 0x11, 0x22, 0x33, // 00112233
+        0x20, 0x00, // length 32
+        0x55, 0x00, // TCA_FLOWER_KEY_ENC_OPTS_MASK
+        0x1c, 0x00, // length 28
+        0x01, 0x00, // TCA_FLOWER_KEY_ENC_OPTS_GENEVE
+        0x06, 0x00, // length 6
+        0x01, 0x00, // TCA_FLOWER_KEY_ENC_OPT_GENEVE_CLASS
+        0xff, 0xff, 0x00, 0x00, // 0xffff and 2 padding bytes
+        0x05, 0x00, // length 5
+        0x02, 0x00, // TCA_FLOWER_KEY_ENC_OPT_GENEVE_TYPE
+        0xff, 0x00, 0x00, 0x00, // 0xff and 3 padding bytes
+        0x08, 0x00, // length 8
+        0x03, 0x00, // TCA_FLOWER_KEY_ENC_OPT_GENEVE_DATA
+        0xff, 0xff, 0xff, 0xff, // ffffffff
+        0x08, 0x00, // length 8
+        0x16, 0x00, // TCA_FLOWER_FLAGS
+        0x00, 0x00, 0x00, 0x00, // none
+        0x06, 0x00, // length 6
+        0x08, 0x00, // TCA_FLOWER_KEY_ETH_TYPE
+        0x08, 0x00, 0x00,
+        0x00, // 0x0800 in big endian and 2 padding bytes
+    ];
+
+    let expected = TcMessage {
+        header: TcHeader {
+            family: AddressFamily::Unspec,
+            index: 1,
+            handle: TcHandle::UNSPEC,
+            parent: TcHandle::CLSACT_INGRESS,
+            info: 0x10008,
+        },
+        attributes: vec![
+            TcAttribute::Kind("flower".to_string()),
+            TcAttribute::Options(vec![
+                TcOption::Flower(TcFilterFlowerOption::KeyEncKeyId(100)),
+                TcOption::Flower(TcFilterFlowerOption::KeyEncIpv4Dst(
+                    Ipv4Addr::new(10, 0, 0, 1),
+                )),
+                TcOption::Flower(TcFilterFlowerOption::KeyEncIpv4DstMask(
+                    Ipv4Addr::BROADCAST,
+                )),
+                TcOption::Flower(TcFilterFlowerOption::KeyEncIpv4Src(
+                    Ipv4Addr::new(10, 0, 0, 2),
+                )),
+                TcOption::Flower(TcFilterFlowerOption::KeyEncIpv4SrcMask(
+                    Ipv4Addr::BROADCAST,
+                )),
+                TcOption::Flower(TcFilterFlowerOption::KeyEncUdpDstPort(6081)),
+                TcOption::Flower(TcFilterFlowerOption::KeyEncIpTos(0x10)),
+                TcOption::Flower(TcFilterFlowerOption::KeyEncIpTosMask(0xff)),
+                TcOption::Flower(TcFilterFlowerOption::KeyEncOpts(vec![
+                    TcFlowerEncOpts::Geneve(vec![
+                        TcFlowerGeneveOpt::Class(0x0102),
+                        TcFlowerGeneveOpt::Type(0x80),
+                        TcFlowerGeneveOpt::Data(vec![0x00, 0x11, 0x22, 0x33]),
+                    ]),
+                ])),
+                TcOption::Flower(TcFilterFlowerOption::KeyEncOptsMask(vec![
+                    TcFlowerEncOpts::Geneve(vec![
+                        TcFlowerGeneveOpt::Class(0xffff),
+                        TcFlowerGeneveOpt::Type(0xff),
+                        TcFlowerGeneveOpt::Data(vec![0xff; 4]),
+                    ]),
+                ])),
+                TcOption::Flower(TcFilterFlowerOption::Flags(vec![])),
+                TcOption::Flower(TcFilterFlowerOption::KeyEthType(0x0800)),
+            ]),
+        ],
+    };
+
+    assert_eq!(
+        expected,
+        TcMessage::parse(&TcMessageBuffer::new(&raw)).unwrap()
+    );
+
+    let mut buf = vec![0; expected.buffer_len()];
+
+    expected.emit(&mut buf);
+
+    assert_eq!(buf, raw);
+}
+
+// Capture nlmon of this command:
+//
+//      tc filter add dev lo ingress protocol ip pref 1 flower \
+//          enc_key_id 42 vxlan_opts 1234/0xffff
+//
+// Raw packet modification:
+//   * rtnetlink header removed.
+#[test]
+fn test_new_filter_flower_enc_opts_vxlan() {
+    let raw = vec![
+        0x00, // AF_UNSPEC
+        0x00, 0x00, 0x00, // padding
+        0x01, 0x00, 0x00, 0x00, // iface index: 1
+        0x00, 0x00, 0x00, 0x00, // handle 0:0
+        0xf2, 0xff, 0xff, 0xff, // parent ffff:fff2 (clsact ingress)
+        0x08, 0x00, 0x01, 0x00, // info: pref 1, protocol ip
+        0x0b, 0x00, // length 11
+        0x01, 0x00, // TCA_KIND
+        0x66, 0x6c, 0x6f, 0x77, 0x65, 0x72, 0x00, 0x00,
+        // "flower\0" and 1 padding byte
+        0x3c, 0x00, // length 60
+        0x02, 0x00, // TCA_OPTIONS for `flower`
+        0x08, 0x00, // length 8
+        0x1a, 0x00, // TCA_FLOWER_KEY_ENC_KEY_ID
+        0x00, 0x00, 0x00, 0x2a, // 42 in big endian
+        0x10, 0x00, // length 16
+        0x54, 0x80, // TCA_FLOWER_KEY_ENC_OPTS with NLA_F_NESTED
+        0x0c, 0x00, // length 12
+        0x02, 0x80, // TCA_FLOWER_KEY_ENC_OPTS_VXLAN with NLA_F_NESTED
+        0x08, 0x00, // length 8
+        0x01, 0x00, // TCA_FLOWER_KEY_ENC_OPT_VXLAN_GBP
+        0xd2, 0x04, 0x00, 0x00, // 1234
+        0x10, 0x00, // length 16
+        0x55, 0x80, // TCA_FLOWER_KEY_ENC_OPTS_MASK with NLA_F_NESTED
+        0x0c, 0x00, // length 12
+        0x02, 0x80, // TCA_FLOWER_KEY_ENC_OPTS_VXLAN with NLA_F_NESTED
+        0x08, 0x00, // length 8
+        0x01, 0x00, // TCA_FLOWER_KEY_ENC_OPT_VXLAN_GBP
+        0xff, 0xff, 0x00, 0x00, // 0xffff
+        0x08, 0x00, // length 8
+        0x16, 0x00, // TCA_FLOWER_FLAGS
+        0x00, 0x00, 0x00, 0x00, // none
+        0x06, 0x00, // length 6
+        0x08, 0x00, // TCA_FLOWER_KEY_ETH_TYPE
+        0x08, 0x00, 0x00,
+        0x00, // 0x0800 in big endian and 2 padding bytes
+    ];
+
+    let expected = TcMessage {
+        header: TcHeader {
+            family: AddressFamily::Unspec,
+            index: 1,
+            handle: TcHandle::UNSPEC,
+            parent: TcHandle::CLSACT_INGRESS,
+            info: 0x10008,
+        },
+        attributes: vec![
+            TcAttribute::Kind("flower".to_string()),
+            TcAttribute::Options(vec![
+                TcOption::Flower(TcFilterFlowerOption::KeyEncKeyId(42)),
+                TcOption::Flower(TcFilterFlowerOption::KeyEncOpts(vec![
+                    TcFlowerEncOpts::Vxlan(vec![TcFlowerVxlanOpt::Gbp(1234)]),
+                ])),
+                TcOption::Flower(TcFilterFlowerOption::KeyEncOptsMask(vec![
+                    TcFlowerEncOpts::Vxlan(vec![TcFlowerVxlanOpt::Gbp(0xffff)]),
+                ])),
+                TcOption::Flower(TcFilterFlowerOption::Flags(vec![])),
+                TcOption::Flower(TcFilterFlowerOption::KeyEthType(0x0800)),
+            ]),
+        ],
+    };
+
+    assert_eq!(
+        expected,
+        TcMessage::parse(&TcMessageBuffer::new(&raw)).unwrap()
+    );
+
+    let mut buf = vec![0; expected.buffer_len()];
+
+    expected.emit(&mut buf);
+
+    assert_eq!(buf, raw);
+}
+
+// Capture nlmon of this command:
+//
+//      tc filter add dev lo ingress protocol mpls_uc pref 1 flower \
+//          mpls lse depth 1 label 100 tc 3 lse depth 2 ttl 64 bos 1
+//
+// Raw packet modification:
+//   * rtnetlink header removed.
+#[test]
+fn test_new_filter_flower_mpls_opts() {
+    let raw = vec![
+        0x00, // AF_UNSPEC
+        0x00, 0x00, 0x00, // padding
+        0x01, 0x00, 0x00, 0x00, // iface index: 1
+        0x00, 0x00, 0x00, 0x00, // handle 0:0
+        0xf2, 0xff, 0xff, 0xff, // parent ffff:fff2 (clsact ingress)
+        0x88, 0x47, 0x01, 0x00, // info: pref 1, protocol mpls_uc
+        0x0b, 0x00, // length 11
+        0x01, 0x00, // TCA_KIND
+        0x66, 0x6c, 0x6f, 0x77, 0x65, 0x72, 0x00, 0x00,
+        // "flower\0" and 1 padding byte
+        0x50, 0x00, // length 80
+        0x02, 0x00, // TCA_OPTIONS for `flower`
+        0x3c, 0x00, // length 60
+        0x63, 0x80, // TCA_FLOWER_KEY_MPLS_OPTS with NLA_F_NESTED
+        0x1c, 0x00, // length 28
+        0x01, 0x80, // TCA_FLOWER_KEY_MPLS_OPTS_LSE with NLA_F_NESTED
+        0x05, 0x00, // length 5
+        0x01, 0x00, // TCA_FLOWER_KEY_MPLS_OPT_LSE_DEPTH
+        0x01, 0x00, 0x00, 0x00, // 1 and 3 padding bytes
+        0x08, 0x00, // length 8
+        0x05, 0x00, // TCA_FLOWER_KEY_MPLS_OPT_LSE_LABEL
+        0x64, 0x00, 0x00, 0x00, // 100
+        0x05, 0x00, // length 5
+        0x04, 0x00, // TCA_FLOWER_KEY_MPLS_OPT_LSE_TC
+        0x03, 0x00, 0x00, 0x00, // 3 and 3 padding bytes
+        0x1c, 0x00, // length 28
+        0x01, 0x80, // TCA_FLOWER_KEY_MPLS_OPTS_LSE with NLA_F_NESTED
+        0x05, 0x00, // length 5
+        0x01, 0x00, // TCA_FLOWER_KEY_MPLS_OPT_LSE_DEPTH
+        0x02, 0x00, 0x00, 0x00, // 2 and 3 padding bytes
+        0x05, 0x00, // length 5
+        0x02, 0x00, // TCA_FLOWER_KEY_MPLS_OPT_LSE_TTL
+        0x40, 0x00, 0x00, 0x00, // 64 and 3 padding bytes
+        0x05, 0x00, // length 5
+        0x03, 0x00, // TCA_FLOWER_KEY_MPLS_OPT_LSE_BOS
+        0x01, 0x00, 0x00, 0x00, // 1 and 3 padding bytes
+        0x08, 0x00, // length 8
+        0x16, 0x00, // TCA_FLOWER_FLAGS
+        0x00, 0x00, 0x00, 0x00, // none
+        0x06, 0x00, // length 6
+        0x08, 0x00, // TCA_FLOWER_KEY_ETH_TYPE
+        0x88, 0x47, 0x00,
+        0x00, // 0x8847 in big endian and 2 padding bytes
+    ];
+
+    let expected = TcMessage {
+        header: TcHeader {
+            family: AddressFamily::Unspec,
+            index: 1,
+            handle: TcHandle::UNSPEC,
+            parent: TcHandle::CLSACT_INGRESS,
+            info: 0x14788,
+        },
+        attributes: vec![
+            TcAttribute::Kind("flower".to_string()),
+            TcAttribute::Options(vec![
+                TcOption::Flower(TcFilterFlowerOption::KeyMplsOpts(vec![
+                    vec![
+                        TcFlowerMplsLseOpt::Depth(1),
+                        TcFlowerMplsLseOpt::Label(100),
+                        TcFlowerMplsLseOpt::Tc(3),
+                    ],
+                    vec![
+                        TcFlowerMplsLseOpt::Depth(2),
+                        TcFlowerMplsLseOpt::Ttl(64),
+                        TcFlowerMplsLseOpt::Bos(1),
+                    ],
+                ])),
+                TcOption::Flower(TcFilterFlowerOption::Flags(vec![])),
+                TcOption::Flower(TcFilterFlowerOption::KeyEthType(0x8847)),
+            ]),
+        ],
+    };
+
+    assert_eq!(
+        expected,
+        TcMessage::parse(&TcMessageBuffer::new(&raw)).unwrap()
+    );
+
+    let mut buf = vec![0; expected.buffer_len()];
+
+    expected.emit(&mut buf);
+
+    assert_eq!(buf, raw);
+}
+
+// Capture nlmon of this command:
+//
+//      tc filter add dev lo ingress protocol ip pref 1 flower indev lo \
+//          ct_state +trk+est-rpl ct_zone 5 ct_mark 0x10/0xff \
+//          ct_label 01000000000000000000000000000002/\
+//              ff0000000000000000000000000000ff \
+//          ip_flags frag/firstfrag
+//
+// Raw packet modification:
+//   * rtnetlink header removed.
+#[test]
+fn test_new_filter_flower_ct() {
+    let raw = vec![
+        0x00, // AF_UNSPEC
+        0x00, 0x00, 0x00, // padding
+        0x01, 0x00, 0x00, 0x00, // iface index: 1
+        0x00, 0x00, 0x00, 0x00, // handle 0:0
+        0xf2, 0xff, 0xff, 0xff, // parent ffff:fff2 (clsact ingress)
+        0x08, 0x00, 0x01, 0x00, // info: pref 1, protocol ip
+        0x0b, 0x00, // length 11
+        0x01, 0x00, // TCA_KIND
+        0x66, 0x6c, 0x6f, 0x77, 0x65, 0x72, 0x00, 0x00,
+        // "flower\0" and 1 padding byte
+        0x84, 0x00, // length 132
+        0x02, 0x00, // TCA_OPTIONS for `flower`
+        0x07, 0x00, // length 7
+        0x02, 0x00, // TCA_FLOWER_INDEV
+        0x6c, 0x6f, 0x00, 0x00, // "lo\0" and 1 padding byte
+        0x06, 0x00, // length 6
+        0x5b, 0x00, // TCA_FLOWER_KEY_CT_STATE
+        0x0a, 0x00, 0x00, 0x00, // established|tracked and 2 padding bytes
+        0x06, 0x00, // length 6
+        0x5c, 0x00, // TCA_FLOWER_KEY_CT_STATE_MASK
+        0x2a, 0x00, 0x00, 0x00,
+        // established|tracked|reply and 2 padding bytes
+        0x06, 0x00, // length 6
+        0x5d, 0x00, // TCA_FLOWER_KEY_CT_ZONE
+        0x05, 0x00, 0x00, 0x00, // 5 and 2 padding bytes
+        0x06, 0x00, // length 6
+        0x5e, 0x00, // TCA_FLOWER_KEY_CT_ZONE_MASK
+        0xff, 0xff, 0x00, 0x00, // 0xffff and 2 padding bytes
+        0x08, 0x00, // length 8
+        0x5f, 0x00, // TCA_FLOWER_KEY_CT_MARK
+        0x10, 0x00, 0x00, 0x00, // 0x10
+        0x08, 0x00, // length 8
+        0x60, 0x00, // TCA_FLOWER_KEY_CT_MARK_MASK
+        0xff, 0x00, 0x00, 0x00, // 0xff
+        0x14, 0x00, // length 20
+        0x61, 0x00, // TCA_FLOWER_KEY_CT_LABELS
+        0x01, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0x00, 0x00, 0x00, 0x02, // 01000000000000000000000000000002
+        0x14, 0x00, // length 20
+        0x62, 0x00, // TCA_FLOWER_KEY_CT_LABELS_MASK
+        0xff, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0x00, 0x00, 0x00, 0xff, // ff0000000000000000000000000000ff
+        0x08, 0x00, // length 8
+        0x16, 0x00, // TCA_FLOWER_FLAGS
+        0x00, 0x00, 0x00, 0x00, // none
+        0x08, 0x00, // length 8
+        0x2f, 0x00, // TCA_FLOWER_KEY_FLAGS
+        0x00, 0x00, 0x00, 0x03, // frag|firstfrag in big endian
+        0x08, 0x00, // length 8
+        0x30, 0x00, // TCA_FLOWER_KEY_FLAGS_MASK
+        0x00, 0x00, 0x00, 0x03, // frag|firstfrag in big endian
+        0x06, 0x00, // length 6
+        0x08, 0x00, // TCA_FLOWER_KEY_ETH_TYPE
+        0x08, 0x00, 0x00,
+        0x00, // 0x0800 in big endian and 2 padding bytes
+    ];
+
+    let mut labels = [0u8; 16];
+    labels[0] = 0x01;
+    labels[15] = 0x02;
+    let mut labels_mask = [0u8; 16];
+    labels_mask[0] = 0xff;
+    labels_mask[15] = 0xff;
+
+    let expected = TcMessage {
+        header: TcHeader {
+            family: AddressFamily::Unspec,
+            index: 1,
+            handle: TcHandle::UNSPEC,
+            parent: TcHandle::CLSACT_INGRESS,
+            info: 0x10008,
+        },
+        attributes: vec![
+            TcAttribute::Kind("flower".to_string()),
+            TcAttribute::Options(vec![
+                TcOption::Flower(TcFilterFlowerOption::Indev("lo".to_string())),
+                TcOption::Flower(TcFilterFlowerOption::KeyCtState(vec![
+                    TcFlowerCtFlag::Established,
+                    TcFlowerCtFlag::Tracked,
+                ])),
+                TcOption::Flower(TcFilterFlowerOption::KeyCtStateMask(vec![
+                    TcFlowerCtFlag::Established,
+                    TcFlowerCtFlag::Tracked,
+                    TcFlowerCtFlag::Reply,
+                ])),
+                TcOption::Flower(TcFilterFlowerOption::KeyCtZone(5)),
+                TcOption::Flower(TcFilterFlowerOption::KeyCtZoneMask(0xffff)),
+                TcOption::Flower(TcFilterFlowerOption::KeyCtMark(0x10)),
+                TcOption::Flower(TcFilterFlowerOption::KeyCtMarkMask(0xff)),
+                TcOption::Flower(TcFilterFlowerOption::KeyCtLabels(labels)),
+                TcOption::Flower(TcFilterFlowerOption::KeyCtLabelsMask(
+                    labels_mask,
+                )),
+                TcOption::Flower(TcFilterFlowerOption::Flags(vec![])),
+                TcOption::Flower(TcFilterFlowerOption::KeyFlags(vec![
+                    TcFlowerKeyFlag::IsFragment,
+                    TcFlowerKeyFlag::FragIsFirst,
+                ])),
+                TcOption::Flower(TcFilterFlowerOption::KeyFlagsMask(vec![
+                    TcFlowerKeyFlag::IsFragment,
+                    TcFlowerKeyFlag::FragIsFirst,
+                ])),
+                TcOption::Flower(TcFilterFlowerOption::KeyEthType(0x0800)),
+            ]),
+        ],
+    };
+
+    assert_eq!(
+        expected,
+        TcMessage::parse(&TcMessageBuffer::new(&raw)).unwrap()
+    );
+
+    let mut buf = vec![0; expected.buffer_len()];
+
+    expected.emit(&mut buf);
+
+    assert_eq!(buf, raw);
+}
+
+// Capture nlmon of this command:
+//
+//      tc filter add dev lo ingress protocol 802.1ad pref 1 flower \
+//          vlan_id 100 vlan_prio 3 vlan_ethtype 802.1q \
+//          cvlan_id 200 cvlan_prio 5 cvlan_ethtype ip action drop
+//
+// Raw packet modification:
+//   * rtnetlink header removed.
+//   * NLA_F_NESTED flag removed from TCA_ACT_OPTIONS.
+#[test]
+fn test_new_filter_flower_vlan() {
+    let raw = vec![
+        0x00, // AF_UNSPEC
+        0x00, 0x00, 0x00, // padding
+        0x01, 0x00, 0x00, 0x00, // iface index: 1
+        0x00, 0x00, 0x00, 0x00, // handle 0:0
+        0xf2, 0xff, 0xff, 0xff, // parent ffff:fff2 (clsact ingress)
+        0x88, 0xa8, 0x01, 0x00, // info: pref 1, protocol 802.1ad
+        0x0b, 0x00, // length 11
+        0x01, 0x00, // TCA_KIND
+        0x66, 0x6c, 0x6f, 0x77, 0x65, 0x72, 0x00, 0x00,
+        // "flower\0" and 1 byte pad
+        0x74, 0x00, // length 116
+        0x02, 0x00, // TCA_OPTIONS for `flower`
+        0x06, 0x00, // length 6
+        0x17, 0x00, // TCA_FLOWER_KEY_VLAN_ID
+        0x64, 0x00, 0x00, 0x00, // 100 in host order and 2 bytes pad
+        0x05, 0x00, // length 5
+        0x18, 0x00, // TCA_FLOWER_KEY_VLAN_PRIO
+        0x03, 0x00, 0x00, 0x00, // 3 and 3 bytes pad
+        0x06, 0x00, // length 6
+        0x19, 0x00, // TCA_FLOWER_KEY_VLAN_ETH_TYPE
+        0x81, 0x00, 0x00, 0x00, // ETH_P_8021Q and 2 bytes pad
+        0x06, 0x00, // length 6
+        0x4d, 0x00, // TCA_FLOWER_KEY_CVLAN_ID
+        0xc8, 0x00, 0x00, 0x00, // 200 in host order and 2 bytes pad
+        0x05, 0x00, // length 5
+        0x4e, 0x00, // TCA_FLOWER_KEY_CVLAN_PRIO
+        0x05, 0x00, 0x00, 0x00, // 5 and 3 bytes pad
+        0x06, 0x00, // length 6
+        0x4f, 0x00, // TCA_FLOWER_KEY_CVLAN_ETH_TYPE
+        0x08, 0x00, 0x00, 0x00, // ETH_P_IP and 2 bytes pad
+        0x30, 0x00, // length 48
+        0x03, 0x00, // TCA_FLOWER_ACT
+        0x2c, 0x00, // length 44
+        0x01, 0x00, // TCA_ACT_TAB
+        0x09, 0x00, // length 9
+        0x01, 0x00, // TCA_ACT_KIND
+        0x67, 0x61, 0x63, 0x74, 0x00, 0x00, 0x00, 0x00,
+        // "gact\0" and 3 bytes pad
+        0x1c, 0x00, // length 28
+        0x02, 0x00, // TCA_ACT_OPTIONS
+        0x18, 0x00, // length 24
+        0x02, 0x00, // TCA_GACT_PARMS
+        0x00, 0x00, 0x00, 0x00, // index 0
+        0x00, 0x00, 0x00, 0x00, // capab 0
+        0x02, 0x00, 0x00, 0x00, // action TC_ACT_SHOT
+        0x00, 0x00, 0x00, 0x00, // refcount 0
+        0x00, 0x00, 0x00, 0x00, // bindcnt 0
+        0x08, 0x00, // length 8
+        0x16, 0x00, // TCA_FLOWER_FLAGS
+        0x00, 0x00, 0x00, 0x00, // 0
+        0x06, 0x00, // length 6
+        0x08, 0x00, // TCA_FLOWER_KEY_ETH_TYPE
+        0x88, 0xa8, 0x00, 0x00, // ETH_P_8021AD and 2 bytes pad
+    ];
+
+    let expected = TcMessage {
+        header: TcHeader {
+            family: AddressFamily::Unspec,
+            index: 1,
+            handle: TcHandle::UNSPEC,
+            parent: TcHandle::CLSACT_INGRESS,
+            info: 0x1a888,
+        },
+        attributes: vec![
+            TcAttribute::Kind("flower".to_string()),
+            TcAttribute::Options(vec![
+                TcOption::Flower(TcFilterFlowerOption::KeyVlanId(100)),
+                TcOption::Flower(TcFilterFlowerOption::KeyVlanPrio(3)),
+                TcOption::Flower(TcFilterFlowerOption::KeyVlanEthType(0x8100)),
+                TcOption::Flower(TcFilterFlowerOption::KeyCvlanId(200)),
+                TcOption::Flower(TcFilterFlowerOption::KeyCvlanPrio(5)),
+                TcOption::Flower(TcFilterFlowerOption::KeyCvlanEthType(0x0800)),
+                TcOption::Flower(TcFilterFlowerOption::Action(vec![
+                    TcAction {
+                        tab: 1,
+                        attributes: vec![
+                            TcActionAttribute::Kind("gact".to_string()),
+                            TcActionAttribute::Options(vec![
+                                TcActionOption::Gact(
+                                    TcActionGactOption::Parms(TcGact {
+                                        generic: TcActionGeneric {
+                                            index: 0,
+                                            capab: 0,
+                                            action: TcActionType::Shot,
+                                            refcnt: 0,
+                                            bindcnt: 0,
+                                        },
+                                    }),
+                                ),
+                            ]),
+                        ],
+                    },
+                ])),
+                TcOption::Flower(TcFilterFlowerOption::Flags(vec![])),
+                TcOption::Flower(TcFilterFlowerOption::KeyEthType(0x88a8)),
+            ]),
+        ],
+    };
+
+    assert_eq!(
+        expected,
+        TcMessage::parse(&TcMessageBuffer::new(&raw)).unwrap()
+    );
+
+    let mut buf = vec![0; expected.buffer_len()];
+
+    expected.emit(&mut buf);
+
+    assert_eq!(buf, raw);
+}
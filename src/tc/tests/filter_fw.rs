@@ -0,0 +1,79 @@
+// SPDX-License-Identifier: MIT
+
+use netlink_packet_utils::{Emitable, Parseable};
+
+use crate::{
+    tc::{
+        TcAttribute, TcFilterFwOption, TcHandle, TcHeader, TcMessage,
+        TcMessageBuffer, TcOption,
+    },
+    AddressFamily,
+};
+
+// Capture nlmon of this command:
+//
+//      tc filter add dev lo parent 1: protocol ip pref 1 \
+//          handle 0x10/0xff fw classid 1:10 indev lo
+//
+// Raw packet modification:
+//   * rtnetlink header removed.
+#[test]
+fn test_new_filter_fw() {
+    let raw = vec![
+        0x00, // AF_UNSPEC
+        0x00, 0x00, 0x00, // padding
+        0x01, 0x00, 0x00, 0x00, // iface index: 1
+        0x10, 0x00, 0x00, 0x00, // handle 0:10
+        0x00, 0x00, 0x01, 0x00, // parent 1:0
+        0x08, 0x00, 0x01, 0x00, // info: pref 1, protocol ip
+        0x07, 0x00, // length 7
+        0x01, 0x00, // TCA_KIND
+        0x66, 0x77, 0x00, 0x00, // "fw\0" and 1 padding byte
+        0x1c, 0x00, // length 28
+        0x02, 0x00, // TCA_OPTIONS for `fw`
+        0x08, 0x00, // length 8
+        0x05, 0x00, // TCA_FW_MASK
+        0xff, 0x00, 0x00, 0x00, // 0xff
+        0x08, 0x00, // length 8
+        0x01, 0x00, // TCA_FW_CLASSID
+        0x10, 0x00, 0x01, 0x00, // 1:10
+        0x07, 0x00, // length 7
+        0x03, 0x00, // TCA_FW_INDEV
+        0x6c, 0x6f, 0x00, 0x00, // "lo\0" and 1 padding byte
+    ];
+
+    let expected = TcMessage {
+        header: TcHeader {
+            family: AddressFamily::Unspec,
+            index: 1,
+            handle: TcHandle {
+                major: 0,
+                minor: 0x10,
+            },
+            parent: TcHandle { major: 1, minor: 0 },
+            info: 0x10008,
+        },
+        attributes: vec![
+            TcAttribute::Kind("fw".to_string()),
+            TcAttribute::Options(vec![
+                TcOption::Fw(TcFilterFwOption::Mask(0xff)),
+                TcOption::Fw(TcFilterFwOption::ClassId(TcHandle {
+                    major: 1,
+                    minor: 0x10,
+                })),
+                TcOption::Fw(TcFilterFwOption::Indev("lo".to_string())),
+            ]),
+        ],
+    };
+
+    assert_eq!(
+        expected,
+        TcMessage::parse(&TcMessageBuffer::new(&raw)).unwrap()
+    );
+
+    let mut buf = vec![0; expected.buffer_len()];
+
+    expected.emit(&mut buf);
+
+    assert_eq!(buf, raw);
+}
@@ -6,8 +6,9 @@ use crate::{
     tc::{
         TcAction, TcActionAttribute, TcActionGeneric, TcActionMirrorOption,
         TcActionOption, TcActionType, TcAttribute, TcFilterMatchAllOption,
-        TcHandle, TcHeader, TcMessage, TcMessageBuffer, TcMirror,
-        TcMirrorActionType, TcOption, TcStats2, TcStatsBasic, TcStatsQueue,
+        TcHandle, TcHeader, TcMatchAllPcnt, TcMessage, TcMessageBuffer,
+        TcMirror, TcMirrorActionType, TcOption, TcStats2, TcStatsBasic,
+        TcStatsQueue,
     },
     AddressFamily,
 };
@@ -50,7 +51,7 @@ fn test_get_filter_matchall() {
         0x08, 0x00, 0x00, 0x00, // flags: 8
         0x0c, 0x00, // length 12
         0x04, 0x00, // TCA_MATCHALL_PCNT
-        0x01, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, // TODO
+        0x01, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, // rhit 1
         0xa8, 0x00, // length 168
         0x02, 0x00, // TCA_MATCHALL_ACT
         0xa4, 0x00, // length 164
@@ -113,9 +114,9 @@ fn test_get_filter_matchall() {
             TcAttribute::Chain(0),
             TcAttribute::Options(vec![
                 TcOption::MatchAll(TcFilterMatchAllOption::Flags(8)),
-                TcOption::MatchAll(TcFilterMatchAllOption::Pnct(vec![
-                    1, 0, 0, 0, 0, 0, 0, 0, // TODO(Gris Ge)
-                ])),
+                TcOption::MatchAll(TcFilterMatchAllOption::Pnct(
+                    TcMatchAllPcnt { rhit: 1 },
+                )),
                 TcOption::MatchAll(TcFilterMatchAllOption::Action(vec![
                     TcAction {
                         tab: 1,
@@ -0,0 +1,80 @@
+// SPDX-License-Identifier: MIT
+
+use netlink_packet_utils::{Emitable, Parseable};
+
+use crate::{
+    tc::{
+        TcAttribute, TcFilterRouteOption, TcHandle, TcHeader, TcMessage,
+        TcMessageBuffer, TcOption,
+    },
+    AddressFamily,
+};
+
+// Capture nlmon of this command:
+//
+//      tc filter add dev lo parent 1: protocol ip pref 2 \
+//          route to 10 from 20 classid 1:20
+//
+// Raw packet modification:
+//   * rtnetlink header removed.
+#[test]
+fn test_new_filter_route() {
+    let raw = vec![
+        0x00, // AF_UNSPEC
+        0x00, 0x00, 0x00, // padding
+        0x01, 0x00, 0x00, 0x00, // iface index: 1
+        0x0a, 0x00, 0x14, 0x00, // handle 14:a
+        0x00, 0x00, 0x01, 0x00, // parent 1:0
+        0x08, 0x00, 0x02, 0x00, // info: pref 2, protocol ip
+        0x0a, 0x00, // length 10
+        0x01, 0x00, // TCA_KIND
+        0x72, 0x6f, 0x75, 0x74, 0x65, 0x00, 0x00, 0x00,
+        // "route\0" and 2 padding bytes
+        0x1c, 0x00, // length 28
+        0x02, 0x00, // TCA_OPTIONS for `route`
+        0x08, 0x00, // length 8
+        0x02, 0x00, // TCA_ROUTE4_TO
+        0x0a, 0x00, 0x00, 0x00, // 10
+        0x08, 0x00, // length 8
+        0x03, 0x00, // TCA_ROUTE4_FROM
+        0x14, 0x00, 0x00, 0x00, // 20
+        0x08, 0x00, // length 8
+        0x01, 0x00, // TCA_ROUTE4_CLASSID
+        0x20, 0x00, 0x01, 0x00, // 1:20
+    ];
+
+    let expected = TcMessage {
+        header: TcHeader {
+            family: AddressFamily::Unspec,
+            index: 1,
+            handle: TcHandle {
+                major: 0x14,
+                minor: 0x0a,
+            },
+            parent: TcHandle { major: 1, minor: 0 },
+            info: 0x20008,
+        },
+        attributes: vec![
+            TcAttribute::Kind("route".to_string()),
+            TcAttribute::Options(vec![
+                TcOption::Route(TcFilterRouteOption::To(10)),
+                TcOption::Route(TcFilterRouteOption::From(20)),
+                TcOption::Route(TcFilterRouteOption::ClassId(TcHandle {
+                    major: 1,
+                    minor: 0x20,
+                })),
+            ]),
+        ],
+    };
+
+    assert_eq!(
+        expected,
+        TcMessage::parse(&TcMessageBuffer::new(&raw)).unwrap()
+    );
+
+    let mut buf = vec![0; expected.buffer_len()];
+
+    expected.emit(&mut buf);
+
+    assert_eq!(buf, raw);
+}
@@ -6,9 +6,10 @@ use netlink_packet_utils::{Emitable, Parseable};
 
 use crate::{
     tc::{
-        TcAttribute, TcFilterU32Option, TcHandle, TcHeader, TcMessage,
-        TcMessageBuffer, TcOption, TcU32Key, TcU32OptionFlag, TcU32Selector,
-        TcU32SelectorFlag,
+        TcActionPoliceOption, TcActionType, TcAttribute, TcFilterU32Option,
+        TcHandle, TcHeader, TcLinkLayer, TcMessage, TcMessageBuffer, TcOption,
+        TcPolice, TcRateSpec, TcU32Key, TcU32Mark, TcU32OptionFlag, TcU32Pcnt,
+        TcU32Selector, TcU32SelectorFlag,
     },
     AddressFamily,
 };
@@ -96,7 +97,181 @@ fn test_get_filter_u32() {
                 TcOption::U32(TcFilterU32Option::Flags(vec![
                     TcU32OptionFlag::NotInHw,
                 ])),
-                TcOption::U32(TcFilterU32Option::Pnct(vec![0; 32])), // TODO
+                TcOption::U32(TcFilterU32Option::Pnct(TcU32Pcnt {
+                    rcnt: 0,
+                    rhit: 0,
+                    kcnts: vec![0, 0],
+                })),
+            ]),
+        ],
+    };
+
+    assert_eq!(
+        expected,
+        TcMessage::parse(&TcMessageBuffer::new(&raw)).unwrap()
+    );
+
+    let mut buf = vec![0; expected.buffer_len()];
+
+    expected.emit(&mut buf);
+
+    assert_eq!(buf, raw);
+}
+
+// Raw packet follows the kernel `u32_dump()` layout for
+// `tc -s filter show dev lo` after
+// `tc filter add dev lo parent 1: protocol ip pref 1 u32 \
+//      match ip dst 10.0.0.1/32 match mark 0x10 0xff indev lo \
+//      police rate 1mbit burst 10k drop flowid 1:1` with:
+//   * rtnetlink header removed.
+//   * TCA_POLICE_TM removed.
+//   * TCA_STATS and TCA_STATS2 removed.
+#[test]
+fn test_get_filter_u32_police_mark_indev() {
+    let raw = vec![
+        0x00, // AF_UNSPEC
+        0x00, 0x00, 0x00, // padding
+        0x01, 0x00, 0x00, 0x00, // iface index: 1
+        0x00, 0x08, 0x00, 0x80, // handle 800::800
+        0x00, 0x00, 0x01, 0x00, // parent 1:0
+        0x08, 0x00, 0x01, 0x00, // info: pref 1, protocol ip
+        0x08, 0x00, // length 8
+        0x01, 0x00, // TCA_KIND
+        0x75, 0x33, 0x32, 0x00, // "u32\0"
+        0x08, 0x00, // length 8
+        0x0b, 0x00, // TCA_CHAIN
+        0x00, 0x00, 0x00, 0x00, // 0
+        0xb4, 0x00, // length 180
+        0x02, 0x00, // TCA_OPTIONS for `u32`
+        0x24, 0x00, // length 36
+        0x05, 0x00, // TCA_U32_SEL
+        0x01, // flags: TC_U32_TERMINAL
+        0x00, // offshift
+        0x01, // nkeys
+        0x00, // padding
+        0x00, 0x00, // offmask
+        0x00, 0x00, // off
+        0x00, 0x00, // offoff
+        0x00, 0x00, // hoff
+        0x00, 0x00, 0x00, 0x00, // hmask
+        0xff, 0xff, 0xff, 0xff, // key mask
+        0x0a, 0x00, 0x00, 0x01, // key val: 10.0.0.1
+        0x10, 0x00, 0x00, 0x00, // key off: 16
+        0x00, 0x00, 0x00, 0x00, // key offmask
+        0x08, 0x00, // length 8
+        0x02, 0x00, // TCA_U32_HASH
+        0x00, 0x00, 0x00, 0x80, // 0x80000000
+        0x08, 0x00, // length 8
+        0x01, 0x00, // TCA_U32_CLASSID
+        0x01, 0x00, 0x01, 0x00, // 1:1
+        0x08, 0x00, // length 8
+        0x0b, 0x00, // TCA_U32_FLAGS
+        0x08, 0x00, 0x00, 0x00, // TCA_CLS_FLAGS_NOT_IN_HW
+        0x10, 0x00, // length 16
+        0x0a, 0x00, // TCA_U32_MARK
+        0x10, 0x00, 0x00, 0x00, // val 0x10
+        0xff, 0x00, 0x00, 0x00, // mask 0xff
+        0x05, 0x00, 0x00, 0x00, // success 5
+        0x40, 0x00, // length 64
+        0x06, 0x00, // TCA_U32_POLICE
+        0x3c, 0x00, // length 60
+        0x01, 0x00, // TCA_POLICE_TBF
+        0x01, 0x00, 0x00, 0x00, // index 1
+        0x02, 0x00, 0x00, 0x00, // action TC_ACT_SHOT
+        0x00, 0x00, 0x00, 0x00, // limit 0
+        0x00, 0x88, 0x13, 0x00, // burst 1280000
+        0xf8, 0x07, 0x00, 0x00, // mtu 2040
+        0x00, // rate cell_log
+        0x01, // rate linklayer: TC_LINKLAYER_ETHERNET
+        0x00, 0x00, // rate overhead
+        0x00, 0x00, // rate cell_align
+        0x00, 0x00, // rate mpu
+        0x48, 0xe8, 0x01, 0x00, // rate 125000
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0x00, // peakrate
+        0x01, 0x00, 0x00, 0x00, // refcnt 1
+        0x01, 0x00, 0x00, 0x00, // bindcnt 1
+        0x00, 0x00, 0x00, 0x00, // capab 0
+        0x07, 0x00, // length 7
+        0x08, 0x00, // TCA_U32_INDEV
+        0x6c, 0x6f, 0x00, 0x00, // "lo\0" and 1 padding byte
+        0x1c, 0x00, // length 28
+        0x09, 0x00, // TCA_U32_PCNT
+        0x0a, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, // rcnt 10
+        0x05, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, // rhit 5
+        0x05, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, // kcnts[0] 5
+    ];
+
+    let expected = TcMessage {
+        header: TcHeader {
+            family: AddressFamily::Unspec,
+            index: 1,
+            handle: TcHandle {
+                major: 0x8000,
+                minor: 0x800,
+            },
+            parent: TcHandle { major: 1, minor: 0 },
+            info: 0x10008,
+        },
+        attributes: vec![
+            TcAttribute::Kind("u32".to_string()),
+            TcAttribute::Chain(0),
+            TcAttribute::Options(vec![
+                TcOption::U32(TcFilterU32Option::Selector(TcU32Selector {
+                    flags: vec![TcU32SelectorFlag::Terminal],
+                    offshift: 0,
+                    nkeys: 1,
+                    offmask: 0,
+                    off: 0,
+                    offoff: 0,
+                    hoff: 0,
+                    hmask: 0,
+                    keys: vec![TcU32Key {
+                        mask: 0xffffffff,
+                        val: u32::from_ne_bytes(
+                            Ipv4Addr::new(10, 0, 0, 1).octets(),
+                        ),
+                        off: 16,
+                        offmask: 0,
+                    }],
+                })),
+                TcOption::U32(TcFilterU32Option::Hash(0x80000000)),
+                TcOption::U32(TcFilterU32Option::ClassId(TcHandle {
+                    major: 1,
+                    minor: 1,
+                })),
+                TcOption::U32(TcFilterU32Option::Flags(vec![
+                    TcU32OptionFlag::NotInHw,
+                ])),
+                TcOption::U32(TcFilterU32Option::Mark(TcU32Mark {
+                    val: 0x10,
+                    mask: 0xff,
+                    success: 5,
+                })),
+                TcOption::U32(TcFilterU32Option::Police(vec![
+                    TcActionPoliceOption::Tbf(TcPolice {
+                        index: 1,
+                        action: TcActionType::Shot,
+                        limit: 0,
+                        burst: 1280000,
+                        mtu: 2040,
+                        rate: TcRateSpec {
+                            linklayer: TcLinkLayer::Ethernet,
+                            rate: 125000,
+                            ..Default::default()
+                        },
+                        peakrate: TcRateSpec::default(),
+                        refcnt: 1,
+                        bindcnt: 1,
+                        capab: 0,
+                    }),
+                ])),
+                TcOption::U32(TcFilterU32Option::Indev("lo".to_string())),
+                TcOption::U32(TcFilterU32Option::Pnct(TcU32Pcnt {
+                    rcnt: 10,
+                    rhit: 5,
+                    kcnts: vec![5],
+                })),
             ]),
         ],
     };
@@ -1,12 +1,78 @@
 // SPDX-License-Identifier: MIT
 
+#[cfg(test)]
+mod action_gact;
 #[cfg(test)]
 mod action_nat;
 #[cfg(test)]
+mod action_pedit;
+#[cfg(test)]
+mod action_police;
+#[cfg(test)]
+mod action_skbedit;
+#[cfg(test)]
+mod action_skbmod;
+#[cfg(test)]
+mod action_tunnel_key;
+#[cfg(test)]
+mod action_vlan;
+#[cfg(test)]
+mod filter_basic;
+#[cfg(test)]
+mod filter_bpf;
+#[cfg(test)]
+mod filter_cgroup;
+#[cfg(test)]
+mod filter_flow;
+#[cfg(test)]
+mod filter_flower;
+#[cfg(test)]
+mod filter_fw;
+#[cfg(test)]
 mod filter_matchall;
 #[cfg(test)]
+mod filter_route;
+#[cfg(test)]
 mod filter_u32;
 #[cfg(test)]
+mod qdisc_cake;
+#[cfg(test)]
+mod qdisc_cbs;
+#[cfg(test)]
+mod qdisc_clsact;
+#[cfg(test)]
+mod qdisc_codel;
+#[cfg(test)]
+mod qdisc_drr;
+#[cfg(test)]
+mod qdisc_etf;
+#[cfg(test)]
+mod qdisc_ets;
+#[cfg(test)]
+mod qdisc_fifo;
+#[cfg(test)]
+mod qdisc_fq;
+#[cfg(test)]
 mod qdisc_fq_codel;
 #[cfg(test)]
+mod qdisc_hfsc;
+#[cfg(test)]
+mod qdisc_htb;
+#[cfg(test)]
 mod qdisc_ingress;
+#[cfg(test)]
+mod qdisc_mqprio;
+#[cfg(test)]
+mod qdisc_netem;
+#[cfg(test)]
+mod qdisc_pie;
+#[cfg(test)]
+mod qdisc_prio;
+#[cfg(test)]
+mod qdisc_red;
+#[cfg(test)]
+mod qdisc_sfq;
+#[cfg(test)]
+mod qdisc_taprio;
+#[cfg(test)]
+mod qdisc_tbf;
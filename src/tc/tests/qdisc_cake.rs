@@ -0,0 +1,307 @@
+// SPDX-License-Identifier: MIT
+
+use netlink_packet_utils::{Emitable, Parseable};
+
+use crate::{
+    tc::{
+        TcAttribute, TcCakeAckFilter, TcCakeAtmMode, TcCakeDiffservMode,
+        TcCakeFlowMode, TcCakeTinStats, TcCakeXstats, TcHandle, TcHeader,
+        TcMessage, TcMessageBuffer, TcOption, TcQdiscCakeOption, TcStats2,
+        TcXstats,
+    },
+    AddressFamily,
+};
+
+// Capture nlmon of this command:
+//
+//      tc qdisc add dev lo root cake bandwidth 100mbit diffserv4 \
+//          dual-dsthost nat wash ack-filter split-gso rtt 50ms \
+//          memlimit 32mb ingress overhead 18 mpu 64 ptm fwmark 0xff
+//
+// Raw packet modification:
+//   * rtnetlink header removed.
+#[test]
+fn test_new_qdisc_cake() {
+    let raw = vec![
+        0x00, // AF_UNSPEC
+        0x00, 0x00, 0x00, // padding
+        0x01, 0x00, 0x00, 0x00, // iface index: 1
+        0x00, 0x00, 0x00, 0x00, // handle 0:0 (TC_H_UNSPEC)
+        0xff, 0xff, 0xff, 0xff, // parent u32::MAX (TC_H_ROOT)
+        0x00, 0x00, 0x00, 0x00, // info: 0
+        0x09, 0x00, // length 9
+        0x01, 0x00, // TCA_KIND
+        0x63, 0x61, 0x6b, 0x65, 0x00, 0x00, 0x00, 0x00,
+        // "cake\0" and 3 bytes pad
+        0x88, 0x00, // length 136
+        0x02, 0x00, // TCA_OPTIONS for `cake`
+        0x0c, 0x00, // length 12
+        0x02, 0x00, // TCA_CAKE_BASE_RATE64
+        0x20, 0xbc, 0xbe, 0x00, 0x00, 0x00, 0x00, 0x00, // 12500000
+        0x08, 0x00, // length 8
+        0x03, 0x00, // TCA_CAKE_DIFFSERV_MODE
+        0x01, 0x00, 0x00, 0x00, // CAKE_DIFFSERV_DIFFSERV4
+        0x08, 0x00, // length 8
+        0x04, 0x00, // TCA_CAKE_ATM
+        0x02, 0x00, 0x00, 0x00, // CAKE_ATM_PTM
+        0x08, 0x00, // length 8
+        0x05, 0x00, // TCA_CAKE_FLOW_MODE
+        0x06, 0x00, 0x00, 0x00, // CAKE_FLOW_DUAL_DST
+        0x08, 0x00, // length 8
+        0x06, 0x00, // TCA_CAKE_OVERHEAD
+        0x12, 0x00, 0x00, 0x00, // 18
+        0x08, 0x00, // length 8
+        0x0e, 0x00, // TCA_CAKE_MPU
+        0x40, 0x00, 0x00, 0x00, // 64
+        0x08, 0x00, // length 8
+        0x07, 0x00, // TCA_CAKE_RTT
+        0x50, 0xc3, 0x00, 0x00, // 50000
+        0x08, 0x00, // length 8
+        0x08, 0x00, // TCA_CAKE_TARGET
+        0xc4, 0x09, 0x00, 0x00, // 2500
+        0x08, 0x00, // length 8
+        0x09, 0x00, // TCA_CAKE_AUTORATE
+        0x00, 0x00, 0x00, 0x00, // 0
+        0x08, 0x00, // length 8
+        0x0a, 0x00, // TCA_CAKE_MEMORY
+        0x00, 0x00, 0x00, 0x02, // 33554432
+        0x08, 0x00, // length 8
+        0x12, 0x00, // TCA_CAKE_FWMARK
+        0xff, 0x00, 0x00, 0x00, // 0xff
+        0x08, 0x00, // length 8
+        0x0b, 0x00, // TCA_CAKE_NAT
+        0x01, 0x00, 0x00, 0x00, // 1
+        0x08, 0x00, // length 8
+        0x0d, 0x00, // TCA_CAKE_WASH
+        0x01, 0x00, 0x00, 0x00, // 1
+        0x08, 0x00, // length 8
+        0x11, 0x00, // TCA_CAKE_SPLIT_GSO
+        0x01, 0x00, 0x00, 0x00, // 1
+        0x08, 0x00, // length 8
+        0x0f, 0x00, // TCA_CAKE_INGRESS
+        0x01, 0x00, 0x00, 0x00, // 1
+        0x08, 0x00, // length 8
+        0x10, 0x00, // TCA_CAKE_ACK_FILTER
+        0x01, 0x00, 0x00, 0x00, // CAKE_ACK_FILTER
+    ];
+
+    let expected = TcMessage {
+        header: TcHeader {
+            family: AddressFamily::Unspec,
+            index: 1,
+            handle: TcHandle::UNSPEC,
+            parent: TcHandle::ROOT,
+            info: 0,
+        },
+        attributes: vec![
+            TcAttribute::Kind("cake".to_string()),
+            TcAttribute::Options(vec![
+                TcOption::Cake(TcQdiscCakeOption::BaseRate64(12500000)),
+                TcOption::Cake(TcQdiscCakeOption::DiffservMode(
+                    TcCakeDiffservMode::Diffserv4,
+                )),
+                TcOption::Cake(TcQdiscCakeOption::Atm(TcCakeAtmMode::Ptm)),
+                TcOption::Cake(TcQdiscCakeOption::FlowMode(
+                    TcCakeFlowMode::DualDst,
+                )),
+                TcOption::Cake(TcQdiscCakeOption::Overhead(18)),
+                TcOption::Cake(TcQdiscCakeOption::Mpu(64)),
+                TcOption::Cake(TcQdiscCakeOption::Rtt(50000)),
+                TcOption::Cake(TcQdiscCakeOption::Target(2500)),
+                TcOption::Cake(TcQdiscCakeOption::AutoRate(0)),
+                TcOption::Cake(TcQdiscCakeOption::Memory(33554432)),
+                TcOption::Cake(TcQdiscCakeOption::FwMark(0xff)),
+                TcOption::Cake(TcQdiscCakeOption::Nat(1)),
+                TcOption::Cake(TcQdiscCakeOption::Wash(1)),
+                TcOption::Cake(TcQdiscCakeOption::SplitGso(1)),
+                TcOption::Cake(TcQdiscCakeOption::Ingress(1)),
+                TcOption::Cake(TcQdiscCakeOption::AckFilter(
+                    TcCakeAckFilter::Filter,
+                )),
+            ]),
+        ],
+    };
+
+    assert_eq!(
+        expected,
+        TcMessage::parse(&TcMessageBuffer::new(&raw)).unwrap()
+    );
+
+    let mut buf = vec![0; expected.buffer_len()];
+
+    expected.emit(&mut buf);
+
+    assert_eq!(buf, raw);
+}
+
+// Setup:
+//      tc qdisc add dev lo root handle 1: cake bandwidth 100mbit besteffort
+//
+// Raw packet follows the kernel `cake_dump_stats()` layout for
+// `tc -s qdisc show dev lo` with:
+//   * rtnetlink header removed.
+//   * TCA_OPTIONS, TCA_STATS and all TCA_STATS2 attributes except
+//     TCA_STATS_APP removed.
+#[test]
+fn test_get_qdisc_cake_xstats() {
+    let raw = vec![
+        0x00, // AF_UNSPEC
+        0x00, 0x00, 0x00, // padding
+        0x01, 0x00, 0x00, 0x00, // iface index: 1
+        0x00, 0x00, 0x01, 0x00, // handle 1:0
+        0xff, 0xff, 0xff, 0xff, // parent u32::MAX (TC_H_ROOT)
+        0x02, 0x00, 0x00, 0x00, // info(refcount): 2
+        0x09, 0x00, // length 9
+        0x01, 0x00, // TCA_KIND
+        0x63, 0x61, 0x6b, 0x65, 0x00, 0x00, 0x00, 0x00,
+        // "cake\0" and 3 bytes pad
+        0xfc, 0x00, // length 252
+        0x07, 0x00, // TCA_STATS2
+        0xf8, 0x00, // length 248
+        0x04, 0x00, // TCA_STATS_APP
+        0x0c, 0x00, // length 12
+        0x02, 0x00, // TCA_CAKE_STATS_CAPACITY_ESTIMATE64
+        0x20, 0xbc, 0xbe, 0x00, 0x00, 0x00, 0x00, 0x00, // 12500000
+        0x08, 0x00, // length 8
+        0x03, 0x00, // TCA_CAKE_STATS_MEMORY_LIMIT
+        0x00, 0x00, 0x40, 0x00, // 4194304
+        0x08, 0x00, // length 8
+        0x04, 0x00, // TCA_CAKE_STATS_MEMORY_USED
+        0x00, 0x06, 0x00, 0x00, // 1536
+        0x08, 0x00, // length 8
+        0x05, 0x00, // TCA_CAKE_STATS_AVG_NETOFF
+        0x0e, 0x00, 0x00, 0x00, // 14
+        0x08, 0x00, // length 8
+        0x07, 0x00, // TCA_CAKE_STATS_MAX_NETLEN
+        0x62, 0x00, 0x00, 0x00, // 98
+        0x08, 0x00, // length 8
+        0x09, 0x00, // TCA_CAKE_STATS_MAX_ADJLEN
+        0x62, 0x00, 0x00, 0x00, // 98
+        0x08, 0x00, // length 8
+        0x06, 0x00, // TCA_CAKE_STATS_MIN_NETLEN
+        0x2a, 0x00, 0x00, 0x00, // 42
+        0x08, 0x00, // length 8
+        0x08, 0x00, // TCA_CAKE_STATS_MIN_ADJLEN
+        0x2a, 0x00, 0x00, 0x00, // 42
+        0xb0, 0x00, // length 176
+        0x0a, 0x00, // TCA_CAKE_STATS_TIN_STATS
+        0xac, 0x00, // length 172
+        0x01, 0x00, // tin 0
+        0x0c, 0x00, // length 12
+        0x0c, 0x00, // TCA_CAKE_TIN_STATS_THRESHOLD_RATE64
+        0x20, 0xbc, 0xbe, 0x00, 0x00, 0x00, 0x00, 0x00, // 12500000
+        0x0c, 0x00, // length 12
+        0x03, 0x00, // TCA_CAKE_TIN_STATS_SENT_BYTES64
+        0x7c, 0x0b, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, // 2940
+        0x08, 0x00, // length 8
+        0x0b, 0x00, // TCA_CAKE_TIN_STATS_BACKLOG_BYTES
+        0x00, 0x00, 0x00, 0x00, // 0
+        0x08, 0x00, // length 8
+        0x0d, 0x00, // TCA_CAKE_TIN_STATS_TARGET_US
+        0x88, 0x13, 0x00, 0x00, // 5000
+        0x08, 0x00, // length 8
+        0x0e, 0x00, // TCA_CAKE_TIN_STATS_INTERVAL_US
+        0xa0, 0x86, 0x01, 0x00, // 100000
+        0x08, 0x00, // length 8
+        0x02, 0x00, // TCA_CAKE_TIN_STATS_SENT_PACKETS
+        0x1e, 0x00, 0x00, 0x00, // 30
+        0x08, 0x00, // length 8
+        0x04, 0x00, // TCA_CAKE_TIN_STATS_DROPPED_PACKETS
+        0x02, 0x00, 0x00, 0x00, // 2
+        0x08, 0x00, // length 8
+        0x08, 0x00, // TCA_CAKE_TIN_STATS_ECN_MARKED_PACKETS
+        0x01, 0x00, 0x00, 0x00, // 1
+        0x08, 0x00, // length 8
+        0x06, 0x00, // TCA_CAKE_TIN_STATS_ACKS_DROPPED_PACKETS
+        0x00, 0x00, 0x00, 0x00, // 0
+        0x08, 0x00, // length 8
+        0x12, 0x00, // TCA_CAKE_TIN_STATS_PEAK_DELAY_US
+        0x34, 0x00, 0x00, 0x00, // 52
+        0x08, 0x00, // length 8
+        0x13, 0x00, // TCA_CAKE_TIN_STATS_AVG_DELAY_US
+        0x08, 0x00, 0x00, 0x00, // 8
+        0x08, 0x00, // length 8
+        0x14, 0x00, // TCA_CAKE_TIN_STATS_BASE_DELAY_US
+        0x03, 0x00, 0x00, 0x00, // 3
+        0x08, 0x00, // length 8
+        0x0f, 0x00, // TCA_CAKE_TIN_STATS_WAY_INDIRECT_HITS
+        0x00, 0x00, 0x00, 0x00, // 0
+        0x08, 0x00, // length 8
+        0x10, 0x00, // TCA_CAKE_TIN_STATS_WAY_MISSES
+        0x03, 0x00, 0x00, 0x00, // 3
+        0x08, 0x00, // length 8
+        0x11, 0x00, // TCA_CAKE_TIN_STATS_WAY_COLLISIONS
+        0x01, 0x00, 0x00, 0x00, // 1
+        0x08, 0x00, // length 8
+        0x15, 0x00, // TCA_CAKE_TIN_STATS_SPARSE_FLOWS
+        0x01, 0x00, 0x00, 0x00, // 1
+        0x08, 0x00, // length 8
+        0x16, 0x00, // TCA_CAKE_TIN_STATS_BULK_FLOWS
+        0x00, 0x00, 0x00, 0x00, // 0
+        0x08, 0x00, // length 8
+        0x17, 0x00, // TCA_CAKE_TIN_STATS_UNRESPONSIVE_FLOWS
+        0x00, 0x00, 0x00, 0x00, // 0
+        0x08, 0x00, // length 8
+        0x18, 0x00, // TCA_CAKE_TIN_STATS_MAX_SKBLEN
+        0x62, 0x00, 0x00, 0x00, // 98
+        0x08, 0x00, // length 8
+        0x19, 0x00, // TCA_CAKE_TIN_STATS_FLOW_QUANTUM
+        0xea, 0x05, 0x00, 0x00, // 1514
+    ];
+
+    let expected = TcMessage {
+        header: TcHeader {
+            family: AddressFamily::Unspec,
+            index: 1,
+            handle: TcHandle { major: 1, minor: 0 },
+            parent: TcHandle::ROOT,
+            info: 2,
+        },
+        attributes: vec![
+            TcAttribute::Kind("cake".to_string()),
+            TcAttribute::Stats2(vec![TcStats2::App(TcXstats::Cake(vec![
+                TcCakeXstats::CapacityEstimate64(12500000),
+                TcCakeXstats::MemoryLimit(4194304),
+                TcCakeXstats::MemoryUsed(1536),
+                TcCakeXstats::AvgNetoff(14),
+                TcCakeXstats::MaxNetlen(98),
+                TcCakeXstats::MaxAdjlen(98),
+                TcCakeXstats::MinNetlen(42),
+                TcCakeXstats::MinAdjlen(42),
+                TcCakeXstats::TinStats(vec![vec![
+                    TcCakeTinStats::ThresholdRate64(12500000),
+                    TcCakeTinStats::SentBytes64(2940),
+                    TcCakeTinStats::BacklogBytes(0),
+                    TcCakeTinStats::TargetUs(5000),
+                    TcCakeTinStats::IntervalUs(100000),
+                    TcCakeTinStats::SentPackets(30),
+                    TcCakeTinStats::DroppedPackets(2),
+                    TcCakeTinStats::EcnMarkedPackets(1),
+                    TcCakeTinStats::AcksDroppedPackets(0),
+                    TcCakeTinStats::PeakDelayUs(52),
+                    TcCakeTinStats::AvgDelayUs(8),
+                    TcCakeTinStats::BaseDelayUs(3),
+                    TcCakeTinStats::WayIndirectHits(0),
+                    TcCakeTinStats::WayMisses(3),
+                    TcCakeTinStats::WayCollisions(1),
+                    TcCakeTinStats::SparseFlows(1),
+                    TcCakeTinStats::BulkFlows(0),
+                    TcCakeTinStats::UnresponsiveFlows(0),
+                    TcCakeTinStats::MaxSkblen(98),
+                    TcCakeTinStats::FlowQuantum(1514),
+                ]]),
+            ]))]),
+        ],
+    };
+
+    assert_eq!(
+        expected,
+        TcMessage::parse(&TcMessageBuffer::new(&raw)).unwrap()
+    );
+
+    let mut buf = vec![0; expected.buffer_len()];
+
+    expected.emit(&mut buf);
+
+    assert_eq!(buf, raw);
+}
@@ -0,0 +1,79 @@
+// SPDX-License-Identifier: MIT
+
+use netlink_packet_utils::{Emitable, Parseable};
+
+use crate::{
+    tc::{
+        TcAttribute, TcCbsQopt, TcHandle, TcHeader, TcMessage, TcMessageBuffer,
+        TcOption, TcQdiscCbsOption,
+    },
+    AddressFamily,
+};
+
+// Capture nlmon of this command:
+//
+//      tc qdisc add dev lo parent 1:1 handle 10: cbs idleslope 20000 \
+//          sendslope -980000 hicredit 30 locredit -1470 offload 1
+//
+// Raw packet modification:
+//   * rtnetlink header removed.
+#[test]
+fn test_new_qdisc_cbs() {
+    let raw = vec![
+        0x00, // AF_UNSPEC
+        0x00, 0x00, 0x00, // padding
+        0x01, 0x00, 0x00, 0x00, // iface index: 1
+        0x00, 0x00, 0x10, 0x00, // handle 10:0
+        0x01, 0x00, 0x01, 0x00, // parent 1:1
+        0x00, 0x00, 0x00, 0x00, // info: 0
+        0x08, 0x00, // length 8
+        0x01, 0x00, // TCA_KIND
+        0x63, 0x62, 0x73, 0x00, // "cbs\0"
+        0x1c, 0x00, // length 28
+        0x02, 0x00, // TCA_OPTIONS for `cbs`
+        0x18, 0x00, // length 24
+        0x01, 0x00, // TCA_CBS_PARMS
+        0x01, // offload: 1
+        0x00, 0x00, 0x00, // padding
+        0x1e, 0x00, 0x00, 0x00, // hicredit: 30
+        0x42, 0xfa, 0xff, 0xff, // locredit: -1470
+        0x20, 0x4e, 0x00, 0x00, // idleslope: 20000
+        0xe0, 0x0b, 0xf1, 0xff, // sendslope: -980000
+    ];
+
+    let expected = TcMessage {
+        header: TcHeader {
+            family: AddressFamily::Unspec,
+            index: 1,
+            handle: TcHandle {
+                major: 0x10,
+                minor: 0,
+            },
+            parent: TcHandle { major: 1, minor: 1 },
+            info: 0,
+        },
+        attributes: vec![
+            TcAttribute::Kind("cbs".to_string()),
+            TcAttribute::Options(vec![TcOption::Cbs(TcQdiscCbsOption::Parms(
+                TcCbsQopt {
+                    offload: 1,
+                    hicredit: 30,
+                    locredit: -1470,
+                    idleslope: 20000,
+                    sendslope: -980000,
+                },
+            ))]),
+        ],
+    };
+
+    assert_eq!(
+        expected,
+        TcMessage::parse(&TcMessageBuffer::new(&raw)).unwrap()
+    );
+
+    let mut buf = vec![0; expected.buffer_len()];
+
+    expected.emit(&mut buf);
+
+    assert_eq!(buf, raw);
+}
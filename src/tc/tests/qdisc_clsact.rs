@@ -0,0 +1,94 @@
+// SPDX-License-Identifier: MIT
+
+use netlink_packet_utils::{Emitable, Parseable};
+
+use crate::{
+    tc::{
+        TcAttribute, TcHandle, TcHeader, TcMessage, TcMessageBuffer, TcStats,
+        TcStats2, TcStatsBasic, TcStatsQueue,
+    },
+    AddressFamily,
+};
+
+// Setup:
+//      tc qdisc add dev lo clsact
+//
+// Capture nlmon of this command:
+//
+//      tc -s qdisc show dev lo
+//
+// Raw packet modification:
+//   * rtnetlink header removed.
+#[test]
+fn test_get_qdisc_clsact() {
+    let raw = vec![
+        0x00, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x00, 0x00, 0x00, 0xff, 0xff,
+        0xf1, 0xff, 0xff, 0xff, 0x01, 0x00, 0x00, 0x00, 0x0b, 0x00, 0x01, 0x00,
+        0x63, 0x6c, 0x73, 0x61, 0x63, 0x74, 0x00, 0x00, 0x04, 0x00, 0x02, 0x00,
+        0x05, 0x00, 0x0c, 0x00, 0x00, 0x00, 0x00, 0x00, 0x30, 0x00, 0x07, 0x00,
+        0x14, 0x00, 0x01, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x18, 0x00, 0x03, 0x00,
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x2c, 0x00, 0x03, 0x00,
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0x00, 0x00, 0x00, 0x00,
+    ];
+
+    let expected = TcMessage {
+        header: TcHeader {
+            family: AddressFamily::Unspec,
+            index: 1,
+            handle: TcHandle::new(0xffff, 0),
+            parent: TcHandle::CLSACT,
+            info: 1,
+        },
+        attributes: vec![
+            TcAttribute::Kind("clsact".to_string()),
+            TcAttribute::Options(vec![]),
+            TcAttribute::HwOffload(0),
+            TcAttribute::Stats2(vec![
+                TcStats2::Basic(TcStatsBasic {
+                    bytes: 0,
+                    packets: 0,
+                }),
+                TcStats2::Queue(TcStatsQueue {
+                    qlen: 0,
+                    backlog: 0,
+                    drops: 0,
+                    requeues: 0,
+                    overlimits: 0,
+                }),
+            ]),
+            TcAttribute::Stats(TcStats {
+                bytes: 0,
+                packets: 0,
+                drops: 0,
+                overlimits: 0,
+                bps: 0,
+                pps: 0,
+                qlen: 0,
+                backlog: 0,
+            }),
+        ],
+    };
+
+    assert_eq!(
+        expected,
+        TcMessage::parse(&TcMessageBuffer::new(&raw)).unwrap()
+    );
+
+    let mut buf = vec![0; expected.buffer_len()];
+
+    expected.emit(&mut buf);
+
+    assert_eq!(buf, raw);
+}
+
+#[test]
+fn test_clsact_filter_parents() {
+    assert_eq!(u32::from(TcHandle::CLSACT_INGRESS), 0xfffffff2);
+    assert_eq!(u32::from(TcHandle::CLSACT_EGRESS), 0xfffffff3);
+    assert_eq!(TcHandle::from(0xfffffff1), TcHandle::CLSACT);
+}
@@ -0,0 +1,148 @@
+// SPDX-License-Identifier: MIT
+
+use netlink_packet_utils::{Emitable, Parseable};
+
+use crate::{
+    tc::{
+        TcAttribute, TcCodelXstats, TcHandle, TcHeader, TcMessage,
+        TcMessageBuffer, TcOption, TcQdiscCodelOption, TcXstats,
+    },
+    AddressFamily,
+};
+
+// Capture nlmon of this command:
+//
+//      tc qdisc add dev lo root handle 1: codel limit 1000 target 5ms \
+//          interval 100ms ecn ce_threshold 1ms
+//
+// Raw packet modification:
+//   * rtnetlink header removed.
+#[test]
+fn test_new_qdisc_codel() {
+    let raw = vec![
+        0x00, // AF_UNSPEC
+        0x00, 0x00, 0x00, // padding
+        0x01, 0x00, 0x00, 0x00, // iface index: 1
+        0x00, 0x00, 0x01, 0x00, // handle 1:0
+        0xff, 0xff, 0xff, 0xff, // parent u32::MAX (TC_H_ROOT)
+        0x00, 0x00, 0x00, 0x00, // info: 0
+        0x0a, 0x00, // length 10
+        0x01, 0x00, // TCA_KIND
+        0x63, 0x6f, 0x64, 0x65, 0x6c, 0x00, // "codel\0"
+        0x00, 0x00, // padding
+        0x2c, 0x00, // length 44
+        0x02, 0x00, // TCA_OPTIONS for `codel`
+        0x08, 0x00, // length 8
+        0x02, 0x00, // TCA_CODEL_LIMIT
+        0xe8, 0x03, 0x00, 0x00, // 1000
+        0x08, 0x00, // length 8
+        0x03, 0x00, // TCA_CODEL_INTERVAL
+        0xa0, 0x86, 0x01, 0x00, // 100000
+        0x08, 0x00, // length 8
+        0x01, 0x00, // TCA_CODEL_TARGET
+        0x88, 0x13, 0x00, 0x00, // 5000
+        0x08, 0x00, // length 8
+        0x04, 0x00, // TCA_CODEL_ECN
+        0x01, 0x00, 0x00, 0x00, // 1
+        0x08, 0x00, // length 8
+        0x05, 0x00, // TCA_CODEL_CE_THRESHOLD
+        0xe8, 0x03, 0x00, 0x00, // 1000
+    ];
+
+    let expected = TcMessage {
+        header: TcHeader {
+            family: AddressFamily::Unspec,
+            index: 1,
+            handle: TcHandle { major: 1, minor: 0 },
+            parent: TcHandle::ROOT,
+            info: 0,
+        },
+        attributes: vec![
+            TcAttribute::Kind("codel".to_string()),
+            TcAttribute::Options(vec![
+                TcOption::Codel(TcQdiscCodelOption::Limit(1000)),
+                TcOption::Codel(TcQdiscCodelOption::Interval(100000)),
+                TcOption::Codel(TcQdiscCodelOption::Target(5000)),
+                TcOption::Codel(TcQdiscCodelOption::Ecn(1)),
+                TcOption::Codel(TcQdiscCodelOption::CeThreshold(1000)),
+            ]),
+        ],
+    };
+
+    assert_eq!(
+        expected,
+        TcMessage::parse(&TcMessageBuffer::new(&raw)).unwrap()
+    );
+
+    let mut buf = vec![0; expected.buffer_len()];
+
+    expected.emit(&mut buf);
+
+    assert_eq!(buf, raw);
+}
+
+// Raw packet follows the kernel `codel_dump_stats()` layout for
+// `tc -s qdisc show dev lo` with:
+//   * rtnetlink header removed.
+//   * TCA_OPTIONS, TCA_STATS and TCA_STATS2 removed.
+#[test]
+fn test_get_qdisc_codel_xstats() {
+    let raw = vec![
+        0x00, // AF_UNSPEC
+        0x00, 0x00, 0x00, // padding
+        0x01, 0x00, 0x00, 0x00, // iface index: 1
+        0x00, 0x00, 0x01, 0x00, // handle 1:0
+        0xff, 0xff, 0xff, 0xff, // parent u32::MAX (TC_H_ROOT)
+        0x00, 0x00, 0x00, 0x00, // info: 0
+        0x0a, 0x00, // length 10
+        0x01, 0x00, // TCA_KIND
+        0x63, 0x6f, 0x64, 0x65, 0x6c, 0x00, // "codel\0"
+        0x00, 0x00, // padding
+        0x28, 0x00, // length 40
+        0x04, 0x00, // TCA_XSTATS
+        0xea, 0x05, 0x00, 0x00, // maxpacket: 1514
+        0x03, 0x00, 0x00, 0x00, // count: 3
+        0x02, 0x00, 0x00, 0x00, // lastcount: 2
+        0x0c, 0x00, 0x00, 0x00, // ldelay: 12
+        0x00, 0xfb, 0xff, 0xff, // drop_next: -1280
+        0x00, 0x00, 0x00, 0x00, // drop_overlimit: 0
+        0x11, 0x00, 0x00, 0x00, // ecn_mark: 17
+        0x01, 0x00, 0x00, 0x00, // dropping: 1
+        0x05, 0x00, 0x00, 0x00, // ce_mark: 5
+    ];
+
+    let expected = TcMessage {
+        header: TcHeader {
+            family: AddressFamily::Unspec,
+            index: 1,
+            handle: TcHandle { major: 1, minor: 0 },
+            parent: TcHandle::ROOT,
+            info: 0,
+        },
+        attributes: vec![
+            TcAttribute::Kind("codel".to_string()),
+            TcAttribute::Xstats(TcXstats::Codel(TcCodelXstats {
+                maxpacket: 1514,
+                count: 3,
+                lastcount: 2,
+                ldelay: 12,
+                drop_next: -1280,
+                drop_overlimit: 0,
+                ecn_mark: 17,
+                dropping: 1,
+                ce_mark: 5,
+            })),
+        ],
+    };
+
+    assert_eq!(
+        expected,
+        TcMessage::parse(&TcMessageBuffer::new(&raw)).unwrap()
+    );
+
+    let mut buf = vec![0; expected.buffer_len()];
+
+    expected.emit(&mut buf);
+
+    assert_eq!(buf, raw);
+}
@@ -0,0 +1,229 @@
+// SPDX-License-Identifier: MIT
+
+use netlink_packet_utils::{Emitable, Parseable};
+
+use crate::{
+    tc::{
+        TcAttribute, TcDrrStats, TcHandle, TcHeader, TcMessage,
+        TcMessageBuffer, TcOption, TcQdiscDrrOption, TcQdiscQfqOption,
+        TcQfqStats, TcXstats,
+    },
+    AddressFamily,
+};
+
+// Capture nlmon of this command:
+//
+//      tc class add dev lo parent 1: classid 1:1 drr quantum 1514
+//
+// Raw packet modification:
+//   * rtnetlink header removed.
+#[test]
+fn test_new_class_drr() {
+    let raw = vec![
+        0x00, // AF_UNSPEC
+        0x00, 0x00, 0x00, // padding
+        0x01, 0x00, 0x00, 0x00, // iface index: 1
+        0x01, 0x00, 0x01, 0x00, // handle 1:1
+        0x00, 0x00, 0x01, 0x00, // parent 1:0
+        0x00, 0x00, 0x00, 0x00, // info: 0
+        0x08, 0x00, // length 8
+        0x01, 0x00, // TCA_KIND
+        0x64, 0x72, 0x72, 0x00, // "drr\0"
+        0x0c, 0x00, // length 12
+        0x02, 0x00, // TCA_OPTIONS for `drr`
+        0x08, 0x00, // length 8
+        0x01, 0x00, // TCA_DRR_QUANTUM
+        0xea, 0x05, 0x00, 0x00, // 1514
+    ];
+
+    let expected = TcMessage {
+        header: TcHeader {
+            family: AddressFamily::Unspec,
+            index: 1,
+            handle: TcHandle { major: 1, minor: 1 },
+            parent: TcHandle { major: 1, minor: 0 },
+            info: 0,
+        },
+        attributes: vec![
+            TcAttribute::Kind("drr".to_string()),
+            TcAttribute::Options(vec![TcOption::Drr(
+                TcQdiscDrrOption::Quantum(1514),
+            )]),
+        ],
+    };
+
+    assert_eq!(
+        expected,
+        TcMessage::parse(&TcMessageBuffer::new(&raw)).unwrap()
+    );
+
+    let mut buf = vec![0; expected.buffer_len()];
+
+    expected.emit(&mut buf);
+
+    assert_eq!(buf, raw);
+}
+
+// Raw packet follows the kernel `drr_dump_class()` and
+// `drr_dump_class_stats()` layout for `tc -s class show dev lo` with:
+//   * rtnetlink header removed.
+//   * TCA_STATS and TCA_STATS2 removed.
+#[test]
+fn test_get_class_drr_xstats() {
+    let raw = vec![
+        0x00, // AF_UNSPEC
+        0x00, 0x00, 0x00, // padding
+        0x01, 0x00, 0x00, 0x00, // iface index: 1
+        0x01, 0x00, 0x01, 0x00, // handle 1:1
+        0xff, 0xff, 0xff, 0xff, // parent u32::MAX (TC_H_ROOT)
+        0x00, 0x00, 0x00, 0x00, // info: 0
+        0x08, 0x00, // length 8
+        0x01, 0x00, // TCA_KIND
+        0x64, 0x72, 0x72, 0x00, // "drr\0"
+        0x0c, 0x00, // length 12
+        0x02, 0x00, // TCA_OPTIONS for `drr`
+        0x08, 0x00, // length 8
+        0x01, 0x00, // TCA_DRR_QUANTUM
+        0xea, 0x05, 0x00, 0x00, // 1514
+        0x08, 0x00, // length 8
+        0x04, 0x00, // TCA_XSTATS
+        0x8e, 0x05, 0x00, 0x00, // deficit: 1422
+    ];
+
+    let expected = TcMessage {
+        header: TcHeader {
+            family: AddressFamily::Unspec,
+            index: 1,
+            handle: TcHandle { major: 1, minor: 1 },
+            parent: TcHandle::ROOT,
+            info: 0,
+        },
+        attributes: vec![
+            TcAttribute::Kind("drr".to_string()),
+            TcAttribute::Options(vec![TcOption::Drr(
+                TcQdiscDrrOption::Quantum(1514),
+            )]),
+            TcAttribute::Xstats(TcXstats::Drr(TcDrrStats { deficit: 1422 })),
+        ],
+    };
+
+    assert_eq!(
+        expected,
+        TcMessage::parse(&TcMessageBuffer::new(&raw)).unwrap()
+    );
+
+    let mut buf = vec![0; expected.buffer_len()];
+
+    expected.emit(&mut buf);
+
+    assert_eq!(buf, raw);
+}
+
+// Capture nlmon of this command:
+//
+//      tc class add dev lo parent 1: classid 1:1 qfq weight 10 maxpkt 2048
+//
+// Raw packet modification:
+//   * rtnetlink header removed.
+#[test]
+fn test_new_class_qfq() {
+    let raw = vec![
+        0x00, // AF_UNSPEC
+        0x00, 0x00, 0x00, // padding
+        0x01, 0x00, 0x00, 0x00, // iface index: 1
+        0x01, 0x00, 0x01, 0x00, // handle 1:1
+        0x00, 0x00, 0x01, 0x00, // parent 1:0
+        0x00, 0x00, 0x00, 0x00, // info: 0
+        0x08, 0x00, // length 8
+        0x01, 0x00, // TCA_KIND
+        0x71, 0x66, 0x71, 0x00, // "qfq\0"
+        0x14, 0x00, // length 20
+        0x02, 0x00, // TCA_OPTIONS for `qfq`
+        0x08, 0x00, // length 8
+        0x01, 0x00, // TCA_QFQ_WEIGHT
+        0x0a, 0x00, 0x00, 0x00, // 10
+        0x08, 0x00, // length 8
+        0x02, 0x00, // TCA_QFQ_LMAX
+        0x00, 0x08, 0x00, 0x00, // 2048
+    ];
+
+    let expected = TcMessage {
+        header: TcHeader {
+            family: AddressFamily::Unspec,
+            index: 1,
+            handle: TcHandle { major: 1, minor: 1 },
+            parent: TcHandle { major: 1, minor: 0 },
+            info: 0,
+        },
+        attributes: vec![
+            TcAttribute::Kind("qfq".to_string()),
+            TcAttribute::Options(vec![
+                TcOption::Qfq(TcQdiscQfqOption::Weight(10)),
+                TcOption::Qfq(TcQdiscQfqOption::Lmax(2048)),
+            ]),
+        ],
+    };
+
+    assert_eq!(
+        expected,
+        TcMessage::parse(&TcMessageBuffer::new(&raw)).unwrap()
+    );
+
+    let mut buf = vec![0; expected.buffer_len()];
+
+    expected.emit(&mut buf);
+
+    assert_eq!(buf, raw);
+}
+
+// Raw packet follows the kernel `qfq_dump_class_stats()` layout for
+// `tc -s class show dev lo` after creating the class of
+// `test_new_class_qfq()` with:
+//   * rtnetlink header removed.
+//   * TCA_OPTIONS, TCA_STATS and TCA_STATS2 removed.
+#[test]
+fn test_get_class_qfq_xstats() {
+    let raw = vec![
+        0x00, // AF_UNSPEC
+        0x00, 0x00, 0x00, // padding
+        0x01, 0x00, 0x00, 0x00, // iface index: 1
+        0x01, 0x00, 0x01, 0x00, // handle 1:1
+        0xff, 0xff, 0xff, 0xff, // parent u32::MAX (TC_H_ROOT)
+        0x00, 0x00, 0x00, 0x00, // info: 0
+        0x08, 0x00, // length 8
+        0x01, 0x00, // TCA_KIND
+        0x71, 0x66, 0x71, 0x00, // "qfq\0"
+        0x0c, 0x00, // length 12
+        0x04, 0x00, // TCA_XSTATS
+        0x0a, 0x00, 0x00, 0x00, // weight: 10
+        0x00, 0x08, 0x00, 0x00, // lmax: 2048
+    ];
+
+    let expected = TcMessage {
+        header: TcHeader {
+            family: AddressFamily::Unspec,
+            index: 1,
+            handle: TcHandle { major: 1, minor: 1 },
+            parent: TcHandle::ROOT,
+            info: 0,
+        },
+        attributes: vec![
+            TcAttribute::Kind("qfq".to_string()),
+            TcAttribute::Xstats(TcXstats::Qfq(TcQfqStats {
+                weight: 10,
+                lmax: 2048,
+            })),
+        ],
+    };
+
+    assert_eq!(
+        expected,
+        TcMessage::parse(&TcMessageBuffer::new(&raw)).unwrap()
+    );
+
+    let mut buf = vec![0; expected.buffer_len()];
+
+    expected.emit(&mut buf);
+
+    assert_eq!(buf, raw);
+}
@@ -0,0 +1,76 @@
+// SPDX-License-Identifier: MIT
+
+use netlink_packet_utils::{Emitable, Parseable};
+
+use crate::{
+    tc::{
+        TcAttribute, TcEtfFlag, TcEtfQopt, TcHandle, TcHeader, TcMessage,
+        TcMessageBuffer, TcOption, TcQdiscEtfOption,
+    },
+    AddressFamily,
+};
+
+// Capture nlmon of this command:
+//
+//      tc qdisc add dev lo parent 1:1 etf clockid CLOCK_TAI delta 300000 \
+//          offload deadline_mode skip_sock_check
+//
+// Raw packet modification:
+//   * rtnetlink header removed.
+#[test]
+fn test_new_qdisc_etf() {
+    let raw = vec![
+        0x00, // AF_UNSPEC
+        0x00, 0x00, 0x00, // padding
+        0x01, 0x00, 0x00, 0x00, // iface index: 1
+        0x00, 0x00, 0x00, 0x00, // handle 0:0
+        0x01, 0x00, 0x01, 0x00, // parent 1:1
+        0x00, 0x00, 0x00, 0x00, // info: 0
+        0x08, 0x00, // length 8
+        0x01, 0x00, // TCA_KIND
+        0x65, 0x74, 0x66, 0x00, // "etf\0"
+        0x14, 0x00, // length 20
+        0x02, 0x00, // TCA_OPTIONS for `etf`
+        0x10, 0x00, // length 16
+        0x01, 0x00, // TCA_ETF_PARMS
+        0xe0, 0x93, 0x04, 0x00, // delta: 300000
+        0x0b, 0x00, 0x00, 0x00, // clockid: 11 (CLOCK_TAI)
+        0x07, 0x00, 0x00,
+        0x00, // flags: deadline_mode|offload|skip_sock_check
+    ];
+
+    let expected = TcMessage {
+        header: TcHeader {
+            family: AddressFamily::Unspec,
+            index: 1,
+            handle: TcHandle::UNSPEC,
+            parent: TcHandle { major: 1, minor: 1 },
+            info: 0,
+        },
+        attributes: vec![
+            TcAttribute::Kind("etf".to_string()),
+            TcAttribute::Options(vec![TcOption::Etf(TcQdiscEtfOption::Parms(
+                TcEtfQopt {
+                    delta: 300000,
+                    clockid: 11,
+                    flags: vec![
+                        TcEtfFlag::DeadlineMode,
+                        TcEtfFlag::Offload,
+                        TcEtfFlag::SkipSockCheck,
+                    ],
+                },
+            ))]),
+        ],
+    };
+
+    assert_eq!(
+        expected,
+        TcMessage::parse(&TcMessageBuffer::new(&raw)).unwrap()
+    );
+
+    let mut buf = vec![0; expected.buffer_len()];
+
+    expected.emit(&mut buf);
+
+    assert_eq!(buf, raw);
+}
@@ -0,0 +1,149 @@
+// SPDX-License-Identifier: MIT
+
+use netlink_packet_utils::{Emitable, Parseable};
+
+use crate::{
+    tc::{
+        TcAttribute, TcHandle, TcHeader, TcMessage, TcMessageBuffer, TcOption,
+        TcQdiscEtsOption,
+    },
+    AddressFamily,
+};
+
+// Capture nlmon of this command:
+//
+//      tc qdisc add dev lo root handle 1: ets bands 4 strict 1 \
+//          quanta 3000 2000 1000 priomap 3 2 1 0
+//
+// Raw packet modification:
+//   * rtnetlink header removed.
+#[test]
+fn test_new_qdisc_ets() {
+    let raw = vec![
+        0x00, // AF_UNSPEC
+        0x00, 0x00, 0x00, // padding
+        0x01, 0x00, 0x00, 0x00, // iface index: 1
+        0x00, 0x00, 0x01, 0x00, // handle 1:0
+        0xff, 0xff, 0xff, 0xff, // parent u32::MAX (TC_H_ROOT)
+        0x00, 0x00, 0x00, 0x00, // info: 0
+        0x08, 0x00, // length 8
+        0x01, 0x00, // TCA_KIND
+        0x65, 0x74, 0x73, 0x00, // "ets\0"
+        0x54, 0x00, // length 84
+        0x02, 0x80, // TCA_OPTIONS for `ets` with NLA_F_NESTED
+        0x05, 0x00, // length 5
+        0x01, 0x00, // TCA_ETS_NBANDS
+        0x04, 0x00, 0x00, 0x00, // 4 with padding
+        0x05, 0x00, // length 5
+        0x02, 0x00, // TCA_ETS_NSTRICT
+        0x01, 0x00, 0x00, 0x00, // 1 with padding
+        0x1c, 0x00, // length 28
+        0x03, 0x80, // TCA_ETS_QUANTA with NLA_F_NESTED
+        0x08, 0x00, // length 8
+        0x04, 0x00, // TCA_ETS_QUANTA_BAND
+        0xb8, 0x0b, 0x00, 0x00, // 3000
+        0x08, 0x00, // length 8
+        0x04, 0x00, // TCA_ETS_QUANTA_BAND
+        0xd0, 0x07, 0x00, 0x00, // 2000
+        0x08, 0x00, // length 8
+        0x04, 0x00, // TCA_ETS_QUANTA_BAND
+        0xe8, 0x03, 0x00, 0x00, // 1000
+        0x24, 0x00, // length 36
+        0x05, 0x80, // TCA_ETS_PRIOMAP with NLA_F_NESTED
+        0x05, 0x00, // length 5
+        0x06, 0x00, // TCA_ETS_PRIOMAP_BAND
+        0x03, 0x00, 0x00, 0x00, // 3 with padding
+        0x05, 0x00, // length 5
+        0x06, 0x00, // TCA_ETS_PRIOMAP_BAND
+        0x02, 0x00, 0x00, 0x00, // 2 with padding
+        0x05, 0x00, // length 5
+        0x06, 0x00, // TCA_ETS_PRIOMAP_BAND
+        0x01, 0x00, 0x00, 0x00, // 1 with padding
+        0x05, 0x00, // length 5
+        0x06, 0x00, // TCA_ETS_PRIOMAP_BAND
+        0x00, 0x00, 0x00, 0x00, // 0 with padding
+    ];
+
+    let expected = TcMessage {
+        header: TcHeader {
+            family: AddressFamily::Unspec,
+            index: 1,
+            handle: TcHandle { major: 1, minor: 0 },
+            parent: TcHandle::ROOT,
+            info: 0,
+        },
+        attributes: vec![
+            TcAttribute::Kind("ets".to_string()),
+            TcAttribute::Options(vec![
+                TcOption::Ets(TcQdiscEtsOption::Nbands(4)),
+                TcOption::Ets(TcQdiscEtsOption::Nstrict(1)),
+                TcOption::Ets(TcQdiscEtsOption::Quanta(vec![3000, 2000, 1000])),
+                TcOption::Ets(TcQdiscEtsOption::Priomap(vec![3, 2, 1, 0])),
+            ]),
+        ],
+    };
+
+    assert_eq!(
+        expected,
+        TcMessage::parse(&TcMessageBuffer::new(&raw)).unwrap()
+    );
+
+    let mut buf = vec![0; expected.buffer_len()];
+
+    expected.emit(&mut buf);
+
+    assert_eq!(buf, raw);
+}
+
+// Capture nlmon of this command:
+//
+//      tc class change dev lo classid 1:3 ets quantum 1500
+//
+// Raw packet modification:
+//   * rtnetlink header removed.
+#[test]
+fn test_change_class_ets() {
+    let raw = vec![
+        0x00, // AF_UNSPEC
+        0x00, 0x00, 0x00, // padding
+        0x01, 0x00, 0x00, 0x00, // iface index: 1
+        0x03, 0x00, 0x01, 0x00, // handle 1:3
+        0x00, 0x00, 0x00, 0x00, // parent 0:0 (TC_H_UNSPEC)
+        0x00, 0x00, 0x00, 0x00, // info: 0
+        0x08, 0x00, // length 8
+        0x01, 0x00, // TCA_KIND
+        0x65, 0x74, 0x73, 0x00, // "ets\0"
+        0x0c, 0x00, // length 12
+        0x02, 0x80, // TCA_OPTIONS for `ets` with NLA_F_NESTED
+        0x08, 0x00, // length 8
+        0x04, 0x00, // TCA_ETS_QUANTA_BAND
+        0xdc, 0x05, 0x00, 0x00, // 1500
+    ];
+
+    let expected = TcMessage {
+        header: TcHeader {
+            family: AddressFamily::Unspec,
+            index: 1,
+            handle: TcHandle { major: 1, minor: 3 },
+            parent: TcHandle::UNSPEC,
+            info: 0,
+        },
+        attributes: vec![
+            TcAttribute::Kind("ets".to_string()),
+            TcAttribute::Options(vec![TcOption::Ets(
+                TcQdiscEtsOption::QuantaBand(1500),
+            )]),
+        ],
+    };
+
+    assert_eq!(
+        expected,
+        TcMessage::parse(&TcMessageBuffer::new(&raw)).unwrap()
+    );
+
+    let mut buf = vec![0; expected.buffer_len()];
+
+    expected.emit(&mut buf);
+
+    assert_eq!(buf, raw);
+}
@@ -0,0 +1,117 @@
+// SPDX-License-Identifier: MIT
+
+use netlink_packet_utils::{Emitable, Parseable};
+
+use crate::{
+    tc::{
+        TcAttribute, TcFifoQopt, TcHandle, TcHeader, TcMessage,
+        TcMessageBuffer, TcMultiqQopt, TcOption, TcQdiscFifoOption,
+        TcQdiscMultiqOption,
+    },
+    AddressFamily,
+};
+
+// Capture nlmon of this command:
+//
+//      tc qdisc add dev lo root handle 1: bfifo limit 10000
+//
+// Raw packet modification:
+//   * rtnetlink header removed.
+#[test]
+fn test_new_qdisc_bfifo() {
+    let raw = vec![
+        0x00, // AF_UNSPEC
+        0x00, 0x00, 0x00, // padding
+        0x01, 0x00, 0x00, 0x00, // iface index: 1
+        0x00, 0x00, 0x01, 0x00, // handle 1:0
+        0xff, 0xff, 0xff, 0xff, // parent u32::MAX (TC_H_ROOT)
+        0x00, 0x00, 0x00, 0x00, // info: 0
+        0x0a, 0x00, // length 10
+        0x01, 0x00, // TCA_KIND
+        0x62, 0x66, 0x69, 0x66, 0x6f, 0x00, // "bfifo\0"
+        0x00, 0x00, // padding
+        0x08, 0x00, // length 8
+        0x02, 0x00, // TCA_OPTIONS for `bfifo`
+        0x10, 0x27, 0x00, 0x00, // limit: 10000
+    ];
+
+    let expected = TcMessage {
+        header: TcHeader {
+            family: AddressFamily::Unspec,
+            index: 1,
+            handle: TcHandle { major: 1, minor: 0 },
+            parent: TcHandle::ROOT,
+            info: 0,
+        },
+        attributes: vec![
+            TcAttribute::Kind("bfifo".to_string()),
+            TcAttribute::Options(vec![TcOption::Bfifo(
+                TcQdiscFifoOption::Qopt(TcFifoQopt { limit: 10000 }),
+            )]),
+        ],
+    };
+
+    assert_eq!(
+        expected,
+        TcMessage::parse(&TcMessageBuffer::new(&raw)).unwrap()
+    );
+
+    let mut buf = vec![0; expected.buffer_len()];
+
+    expected.emit(&mut buf);
+
+    assert_eq!(buf, raw);
+}
+
+// Capture nlmon of this command:
+//
+//      tc qdisc add dev lo root handle 1: multiq
+//
+// Raw packet modification:
+//   * rtnetlink header removed.
+#[test]
+fn test_new_qdisc_multiq() {
+    let raw = vec![
+        0x00, // AF_UNSPEC
+        0x00, 0x00, 0x00, // padding
+        0x01, 0x00, 0x00, 0x00, // iface index: 1
+        0x00, 0x00, 0x01, 0x00, // handle 1:0
+        0xff, 0xff, 0xff, 0xff, // parent u32::MAX (TC_H_ROOT)
+        0x00, 0x00, 0x00, 0x00, // info: 0
+        0x0b, 0x00, // length 11
+        0x01, 0x00, // TCA_KIND
+        0x6d, 0x75, 0x6c, 0x74, 0x69, 0x71, 0x00, // "multiq\0"
+        0x00, // padding
+        0x08, 0x00, // length 8
+        0x02, 0x00, // TCA_OPTIONS for `multiq`
+        0x00, 0x00, // bands: 0
+        0x00, 0x00, // max_bands: 0
+    ];
+
+    let expected = TcMessage {
+        header: TcHeader {
+            family: AddressFamily::Unspec,
+            index: 1,
+            handle: TcHandle { major: 1, minor: 0 },
+            parent: TcHandle::ROOT,
+            info: 0,
+        },
+        attributes: vec![
+            TcAttribute::Kind("multiq".to_string()),
+            TcAttribute::Options(vec![TcOption::Multiq(
+                TcQdiscMultiqOption::Qopt(TcMultiqQopt::default()),
+            )]),
+        ],
+    };
+
+    assert_eq!(
+        expected,
+        TcMessage::parse(&TcMessageBuffer::new(&raw)).unwrap()
+    );
+
+    let mut buf = vec![0; expected.buffer_len()];
+
+    expected.emit(&mut buf);
+
+    assert_eq!(buf, raw);
+}
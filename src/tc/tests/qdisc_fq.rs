@@ -0,0 +1,246 @@
+// SPDX-License-Identifier: MIT
+
+use netlink_packet_utils::{Emitable, Parseable};
+
+use crate::{
+    tc::{
+        TcAttribute, TcFqQdStats, TcHandle, TcHeader, TcMessage,
+        TcMessageBuffer, TcOption, TcPrioQopt, TcQdiscFqOption, TcStats2,
+        TcXstats,
+    },
+    AddressFamily,
+};
+
+// Capture nlmon of this command:
+//
+//      tc qdisc add dev lo root fq limit 10000 flow_limit 100 \
+//          quantum 3028 initial_quantum 15140 maxrate 1gbit buckets 1024 \
+//          refill_delay 40ms orphan_mask 1023 low_rate_threshold 550kbit \
+//          ce_threshold 4ms timer_slack 10us horizon 10s horizon_drop pacing
+//
+// Raw packet modification:
+//   * rtnetlink header removed.
+#[test]
+fn test_new_qdisc_fq() {
+    let raw = vec![
+        0x00, // AF_UNSPEC
+        0x00, 0x00, 0x00, // padding
+        0x01, 0x00, 0x00, 0x00, // iface index: 1
+        0x00, 0x00, 0x00, 0x00, // handle 0:0 (TC_H_UNSPEC)
+        0xff, 0xff, 0xff, 0xff, // parent u32::MAX (TC_H_ROOT)
+        0x00, 0x00, 0x00, 0x00, // info: 0
+        0x07, 0x00, // length 7
+        0x01, 0x00, // TCA_KIND
+        0x66, 0x71, 0x00, 0x00, // "fq\0" and 1 byte pad
+        0x74, 0x00, // length 116
+        0x02, 0x00, // TCA_OPTIONS for `fq`
+        0x08, 0x00, // length 8
+        0x08, 0x00, // TCA_FQ_BUCKETS_LOG
+        0x0a, 0x00, 0x00, 0x00, // 10
+        0x08, 0x00, // length 8
+        0x01, 0x00, // TCA_FQ_PLIMIT
+        0x10, 0x27, 0x00, 0x00, // 10000
+        0x08, 0x00, // length 8
+        0x02, 0x00, // TCA_FQ_FLOW_PLIMIT
+        0x64, 0x00, 0x00, 0x00, // 100
+        0x08, 0x00, // length 8
+        0x03, 0x00, // TCA_FQ_QUANTUM
+        0xd4, 0x0b, 0x00, 0x00, // 3028
+        0x08, 0x00, // length 8
+        0x04, 0x00, // TCA_FQ_INITIAL_QUANTUM
+        0x24, 0x3b, 0x00, 0x00, // 15140
+        0x08, 0x00, // length 8
+        0x05, 0x00, // TCA_FQ_RATE_ENABLE
+        0x01, 0x00, 0x00, 0x00, // 1
+        0x08, 0x00, // length 8
+        0x07, 0x00, // TCA_FQ_FLOW_MAX_RATE
+        0x40, 0x59, 0x73, 0x07, // 125000000
+        0x08, 0x00, // length 8
+        0x0b, 0x00, // TCA_FQ_LOW_RATE_THRESHOLD
+        0x8e, 0x0c, 0x01, 0x00, // 68750
+        0x08, 0x00, // length 8
+        0x09, 0x00, // TCA_FQ_FLOW_REFILL_DELAY
+        0x40, 0x9c, 0x00, 0x00, // 40000
+        0x08, 0x00, // length 8
+        0x0a, 0x00, // TCA_FQ_ORPHAN_MASK
+        0xff, 0x03, 0x00, 0x00, // 1023
+        0x08, 0x00, // length 8
+        0x0c, 0x00, // TCA_FQ_CE_THRESHOLD
+        0xa0, 0x0f, 0x00, 0x00, // 4000
+        0x08, 0x00, // length 8
+        0x0d, 0x00, // TCA_FQ_TIMER_SLACK
+        0x10, 0x27, 0x00, 0x00, // 10000
+        0x08, 0x00, // length 8
+        0x0e, 0x00, // TCA_FQ_HORIZON
+        0x80, 0x96, 0x98, 0x00, // 10000000
+        0x05, 0x00, // length 5
+        0x0f, 0x00, // TCA_FQ_HORIZON_DROP
+        0x01, 0x00, 0x00, 0x00, // 1 and 3 bytes pad
+    ];
+
+    let expected = TcMessage {
+        header: TcHeader {
+            family: AddressFamily::Unspec,
+            index: 1,
+            handle: TcHandle::UNSPEC,
+            parent: TcHandle::ROOT,
+            info: 0,
+        },
+        attributes: vec![
+            TcAttribute::Kind("fq".to_string()),
+            TcAttribute::Options(vec![
+                TcOption::Fq(TcQdiscFqOption::BucketsLog(10)),
+                TcOption::Fq(TcQdiscFqOption::Plimit(10000)),
+                TcOption::Fq(TcQdiscFqOption::FlowPlimit(100)),
+                TcOption::Fq(TcQdiscFqOption::Quantum(3028)),
+                TcOption::Fq(TcQdiscFqOption::InitialQuantum(15140)),
+                TcOption::Fq(TcQdiscFqOption::RateEnable(1)),
+                TcOption::Fq(TcQdiscFqOption::FlowMaxRate(125000000)),
+                TcOption::Fq(TcQdiscFqOption::LowRateThreshold(68750)),
+                TcOption::Fq(TcQdiscFqOption::FlowRefillDelay(40000)),
+                TcOption::Fq(TcQdiscFqOption::OrphanMask(1023)),
+                TcOption::Fq(TcQdiscFqOption::CeThreshold(4000)),
+                TcOption::Fq(TcQdiscFqOption::TimerSlack(10000)),
+                TcOption::Fq(TcQdiscFqOption::Horizon(10000000)),
+                TcOption::Fq(TcQdiscFqOption::HorizonDrop(1)),
+            ]),
+        ],
+    };
+
+    assert_eq!(
+        expected,
+        TcMessage::parse(&TcMessageBuffer::new(&raw)).unwrap()
+    );
+
+    let mut buf = vec![0; expected.buffer_len()];
+
+    expected.emit(&mut buf);
+
+    assert_eq!(buf, raw);
+}
+
+// Setup:
+//      tc qdisc add dev lo root handle 1: fq
+//
+// Raw packet follows the Linux 6.7 kernel `fq_dump()` and
+// `fq_dump_stats()` layout for `tc -s qdisc show dev lo` with:
+//   * rtnetlink header removed.
+//   * TCA_OPTIONS except TCA_FQ_PRIOMAP and TCA_FQ_WEIGHTS removed.
+//   * TCA_STATS and all TCA_STATS2 attributes except TCA_STATS_APP
+//     removed.
+#[test]
+fn test_get_qdisc_fq_xstats() {
+    let raw = vec![
+        0x00, // AF_UNSPEC
+        0x00, 0x00, 0x00, // padding
+        0x01, 0x00, 0x00, 0x00, // iface index: 1
+        0x00, 0x00, 0x01, 0x00, // handle 1:0
+        0xff, 0xff, 0xff, 0xff, // parent u32::MAX (TC_H_ROOT)
+        0x02, 0x00, 0x00, 0x00, // info(refcount): 2
+        0x07, 0x00, // length 7
+        0x01, 0x00, // TCA_KIND
+        0x66, 0x71, 0x00, 0x00, // "fq\0" and 1 byte pad
+        0x2c, 0x00, // length 44
+        0x02, 0x00, // TCA_OPTIONS for `fq`
+        0x18, 0x00, // length 24
+        0x10, 0x00, // TCA_FQ_PRIOMAP
+        0x03, 0x00, 0x00, 0x00, // bands: 3
+        0x01, 0x02, 0x02, 0x02, 0x01, 0x02, 0x00, 0x00, // priomap[0..8]
+        0x01, 0x01, 0x01, 0x01, 0x01, 0x01, 0x01, 0x01, // priomap[8..16]
+        0x10, 0x00, // length 16
+        0x11, 0x00, // TCA_FQ_WEIGHTS
+        0x00, 0x00, 0x09, 0x00, // 589824
+        0x00, 0x00, 0x03, 0x00, // 196608
+        0x00, 0x00, 0x01, 0x00, // 65536
+        0xa0, 0x00, // length 160
+        0x07, 0x00, // TCA_STATS2
+        0x9c, 0x00, // length 156
+        0x04, 0x00, // TCA_STATS_APP
+        0x03, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, // gc_flows: 3
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0x00, // highprio_packets: 0
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, // tcp_retrans: 0
+        0x0c, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, // throttled: 12
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, // flows_plimit: 0
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0x00, // pkts_too_long: 0
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0x00, // allocation_errors: 0
+        // time_next_delayed_flow: 9000
+        0x28, 0x23, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x04, 0x00, 0x00,
+        0x00, // flows: 4
+        0x02, 0x00, 0x00, 0x00, // inactive_flows: 2
+        0x01, 0x00, 0x00, 0x00, // throttled_flows: 1
+        0x2c, 0x0a, 0x00, 0x00, // unthrottle_latency_ns: 2604
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, // ce_mark: 0
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0x00, // horizon_drops: 0
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, // horizon_caps: 0
+        // fastpath_packets: 1025
+        0x01, 0x04, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0x00, 0x00, 0x00, 0x00, // band_drops[0]: 0
+        0x05, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0x00, // band_drops[1]: 5
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0x00, // band_drops[2]: 0
+        0x02, 0x00, 0x00, 0x00, // band_pkt_count[0]: 2
+        0x00, 0x00, 0x00, 0x00, // band_pkt_count[1]: 0
+        0x01, 0x00, 0x00, 0x00, // band_pkt_count[2]: 1
+        0x00, 0x00, 0x00, 0x00, // pad
+    ];
+
+    let expected = TcMessage {
+        header: TcHeader {
+            family: AddressFamily::Unspec,
+            index: 1,
+            handle: TcHandle { major: 1, minor: 0 },
+            parent: TcHandle::ROOT,
+            info: 2,
+        },
+        attributes: vec![
+            TcAttribute::Kind("fq".to_string()),
+            TcAttribute::Options(vec![
+                TcOption::Fq(TcQdiscFqOption::Priomap(TcPrioQopt {
+                    bands: 3,
+                    priomap: [1, 2, 2, 2, 1, 2, 0, 0, 1, 1, 1, 1, 1, 1, 1, 1],
+                })),
+                TcOption::Fq(TcQdiscFqOption::Weights(vec![
+                    589824, 196608, 65536,
+                ])),
+            ]),
+            TcAttribute::Stats2(vec![TcStats2::App(TcXstats::Fq(
+                TcFqQdStats {
+                    gc_flows: 3,
+                    highprio_packets: 0,
+                    tcp_retrans: 0,
+                    throttled: 12,
+                    flows_plimit: 0,
+                    pkts_too_long: 0,
+                    allocation_errors: 0,
+                    time_next_delayed_flow: 9000,
+                    flows: 4,
+                    inactive_flows: 2,
+                    throttled_flows: 1,
+                    unthrottle_latency_ns: 2604,
+                    ce_mark: 0,
+                    horizon_drops: 0,
+                    horizon_caps: 0,
+                    fastpath_packets: 1025,
+                    band_drops: [0, 5, 0],
+                    band_pkt_count: [2, 0, 1],
+                },
+            ))]),
+        ],
+    };
+
+    assert_eq!(
+        expected,
+        TcMessage::parse(&TcMessageBuffer::new(&raw)).unwrap()
+    );
+
+    let mut buf = vec![0; expected.buffer_len()];
+
+    expected.emit(&mut buf);
+
+    assert_eq!(buf, raw);
+}
@@ -0,0 +1,270 @@
+// SPDX-License-Identifier: MIT
+
+use netlink_packet_utils::{Emitable, Parseable};
+
+use crate::{
+    tc::{
+        TcAttribute, TcHandle, TcHeader, TcHfscQopt, TcHfscStats, TcMessage,
+        TcMessageBuffer, TcOption, TcQdiscHfscOption, TcServiceCurve, TcStats2,
+        TcXstats,
+    },
+    AddressFamily,
+};
+
+// Capture nlmon of this command:
+//
+//      tc qdisc add dev lo root handle 1: hfsc default 10
+//
+// Raw packet modification:
+//   * rtnetlink header removed.
+#[test]
+fn test_new_qdisc_hfsc() {
+    let raw = vec![
+        0x00, // AF_UNSPEC
+        0x00, 0x00, 0x00, // padding
+        0x01, 0x00, 0x00, 0x00, // iface index: 1
+        0x00, 0x00, 0x01, 0x00, // handle 1:0
+        0xff, 0xff, 0xff, 0xff, // parent u32::MAX (TC_H_ROOT)
+        0x00, 0x00, 0x00, 0x00, // info: 0
+        0x09, 0x00, // length 9
+        0x01, 0x00, // TCA_KIND
+        0x68, 0x66, 0x73, 0x63, 0x00, 0x00, 0x00,
+        0x00, // "hfsc\0" with padding
+        0x06, 0x00, // length 6
+        0x02, 0x00, // TCA_OPTIONS for `hfsc`
+        0x10, 0x00, // defcls: 16
+        0x00, 0x00, // padding
+    ];
+
+    let expected = TcMessage {
+        header: TcHeader {
+            family: AddressFamily::Unspec,
+            index: 1,
+            handle: TcHandle { major: 1, minor: 0 },
+            parent: TcHandle::ROOT,
+            info: 0,
+        },
+        attributes: vec![
+            TcAttribute::Kind("hfsc".to_string()),
+            TcAttribute::Options(vec![TcOption::Hfsc(
+                TcQdiscHfscOption::Qopt(TcHfscQopt { defcls: 0x10 }),
+            )]),
+        ],
+    };
+
+    assert_eq!(
+        expected,
+        TcMessage::parse(&TcMessageBuffer::new(&raw)).unwrap()
+    );
+
+    let mut buf = vec![0; expected.buffer_len()];
+
+    expected.emit(&mut buf);
+
+    assert_eq!(buf, raw);
+}
+
+// Capture nlmon of this command:
+//
+//      tc class add dev lo parent 1: classid 1:10 hfsc \
+//          rt m1 2mbit d 10ms m2 1mbit ls m2 5mbit ul m2 10mbit
+//
+// Raw packet modification:
+//   * rtnetlink header removed.
+#[test]
+fn test_new_class_hfsc() {
+    let raw = vec![
+        0x00, // AF_UNSPEC
+        0x00, 0x00, 0x00, // padding
+        0x01, 0x00, 0x00, 0x00, // iface index: 1
+        0x10, 0x00, 0x01, 0x00, // handle 1:10
+        0x00, 0x00, 0x01, 0x00, // parent 1:0
+        0x00, 0x00, 0x00, 0x00, // info: 0
+        0x09, 0x00, // length 9
+        0x01, 0x00, // TCA_KIND
+        0x68, 0x66, 0x73, 0x63, 0x00, 0x00, 0x00,
+        0x00, // "hfsc\0" with padding
+        0x34, 0x00, // length 52
+        0x02, 0x00, // TCA_OPTIONS for `hfsc`
+        0x10, 0x00, // length 16
+        0x01, 0x00, // TCA_HFSC_RSC
+        0x90, 0xd0, 0x03, 0x00, // m1: 250000
+        0x10, 0x27, 0x00, 0x00, // d: 10000
+        0x48, 0xe8, 0x01, 0x00, // m2: 125000
+        0x10, 0x00, // length 16
+        0x02, 0x00, // TCA_HFSC_FSC
+        0x00, 0x00, 0x00, 0x00, // m1: 0
+        0x00, 0x00, 0x00, 0x00, // d: 0
+        0x68, 0x89, 0x09, 0x00, // m2: 625000
+        0x10, 0x00, // length 16
+        0x03, 0x00, // TCA_HFSC_USC
+        0x00, 0x00, 0x00, 0x00, // m1: 0
+        0x00, 0x00, 0x00, 0x00, // d: 0
+        0xd0, 0x12, 0x13, 0x00, // m2: 1250000
+    ];
+
+    let expected = TcMessage {
+        header: TcHeader {
+            family: AddressFamily::Unspec,
+            index: 1,
+            handle: TcHandle {
+                major: 1,
+                minor: 0x10,
+            },
+            parent: TcHandle { major: 1, minor: 0 },
+            info: 0,
+        },
+        attributes: vec![
+            TcAttribute::Kind("hfsc".to_string()),
+            TcAttribute::Options(vec![
+                TcOption::Hfsc(TcQdiscHfscOption::Rsc(TcServiceCurve {
+                    m1: 250000,
+                    d: 10000,
+                    m2: 125000,
+                })),
+                TcOption::Hfsc(TcQdiscHfscOption::Fsc(TcServiceCurve {
+                    m1: 0,
+                    d: 0,
+                    m2: 625000,
+                })),
+                TcOption::Hfsc(TcQdiscHfscOption::Usc(TcServiceCurve {
+                    m1: 0,
+                    d: 0,
+                    m2: 1250000,
+                })),
+            ]),
+        ],
+    };
+
+    assert_eq!(
+        expected,
+        TcMessage::parse(&TcMessageBuffer::new(&raw)).unwrap()
+    );
+
+    let mut buf = vec![0; expected.buffer_len()];
+
+    expected.emit(&mut buf);
+
+    assert_eq!(buf, raw);
+}
+
+// Raw packet follows the kernel `hfsc_dump_class_stats()` layout for
+// `tc -s class show dev lo` after the class of `test_new_class_hfsc()`
+// transmitted 2 packets with:
+//   * rtnetlink header removed.
+//   * TCA_OPTIONS removed.
+//   * TCA_STATS, TCA_STATS_BASIC and TCA_STATS_QUEUE removed.
+#[test]
+fn test_get_class_hfsc_xstats() {
+    let raw = vec![
+        0x00, // AF_UNSPEC
+        0x00, 0x00, 0x00, // padding
+        0x01, 0x00, 0x00, 0x00, // iface index: 1
+        0x10, 0x00, 0x01, 0x00, // handle 1:10
+        0x00, 0x00, 0x01, 0x00, // parent 1:0
+        0x00, 0x00, 0x00, 0x00, // info: 0
+        0x09, 0x00, // length 9
+        0x01, 0x00, // TCA_KIND
+        0x68, 0x66, 0x73, 0x63, 0x00, 0x00, 0x00,
+        0x00, // "hfsc\0" with padding
+        0x20, 0x00, // length 32
+        0x07, 0x00, // TCA_STATS2
+        0x1c, 0x00, // length 28
+        0x04, 0x00, // TCA_STATS_APP
+        0xc4, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, // work: 196
+        0xc4, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, // rtwork: 196
+        0x01, 0x00, 0x00, 0x00, // period: 1
+        0x01, 0x00, 0x00, 0x00, // level: 1
+        0x1c, 0x00, // length 28
+        0x04, 0x00, // TCA_XSTATS
+        0xc4, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, // work: 196
+        0xc4, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, // rtwork: 196
+        0x01, 0x00, 0x00, 0x00, // period: 1
+        0x01, 0x00, 0x00, 0x00, // level: 1
+    ];
+
+    let stats = TcHfscStats {
+        work: 196,
+        rtwork: 196,
+        period: 1,
+        level: 1,
+    };
+
+    let expected = TcMessage {
+        header: TcHeader {
+            family: AddressFamily::Unspec,
+            index: 1,
+            handle: TcHandle {
+                major: 1,
+                minor: 0x10,
+            },
+            parent: TcHandle { major: 1, minor: 0 },
+            info: 0,
+        },
+        attributes: vec![
+            TcAttribute::Kind("hfsc".to_string()),
+            TcAttribute::Stats2(vec![TcStats2::App(TcXstats::Hfsc(stats))]),
+            TcAttribute::Xstats(TcXstats::Hfsc(stats)),
+        ],
+    };
+
+    assert_eq!(
+        expected,
+        TcMessage::parse(&TcMessageBuffer::new(&raw)).unwrap()
+    );
+
+    let mut buf = vec![0; expected.buffer_len()];
+
+    expected.emit(&mut buf);
+
+    assert_eq!(buf, raw);
+}
+
+// Raw packet follows the kernel `hfsc_dump_class()` layout for
+// `tc class show dev lo` after
+// `tc qdisc add dev lo root handle 1: hfsc default 10` and
+// `tc class add dev lo parent 1: classid 1:10 hfsc sc rate 1mbit` with:
+//   * rtnetlink header removed.
+//   * TCA_STATS, TCA_STATS2 and TCA_XSTATS removed.
+#[test]
+fn test_get_root_class_hfsc() {
+    let raw = vec![
+        0x00, // AF_UNSPEC
+        0x00, 0x00, 0x00, // padding
+        0x01, 0x00, 0x00, 0x00, // iface index: 1
+        0x00, 0x00, 0x01, 0x00, // handle 1:0
+        0xff, 0xff, 0xff, 0xff, // parent ffff:ffff (root)
+        0x00, 0x00, 0x00, 0x00, // info: 0
+        0x09, 0x00, // length 9
+        0x01, 0x00, // TCA_KIND
+        0x68, 0x66, 0x73, 0x63, 0x00, 0x00, 0x00,
+        0x00, // "hfsc\0" with padding
+        0x04, 0x00, // length 4
+        0x02, 0x00, // TCA_OPTIONS without any service curve
+    ];
+
+    let expected = TcMessage {
+        header: TcHeader {
+            family: AddressFamily::Unspec,
+            index: 1,
+            handle: TcHandle { major: 1, minor: 0 },
+            parent: TcHandle::ROOT,
+            info: 0,
+        },
+        attributes: vec![
+            TcAttribute::Kind("hfsc".to_string()),
+            TcAttribute::Options(vec![]),
+        ],
+    };
+
+    assert_eq!(
+        expected,
+        TcMessage::parse(&TcMessageBuffer::new(&raw)).unwrap()
+    );
+
+    let mut buf = vec![0; expected.buffer_len()];
+
+    expected.emit(&mut buf);
+
+    assert_eq!(buf, raw);
+}
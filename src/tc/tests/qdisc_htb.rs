@@ -0,0 +1,399 @@
+// SPDX-License-Identifier: MIT
+
+use netlink_packet_utils::{Emitable, Parseable};
+
+use crate::{
+    tc::{
+        TcAttribute, TcHandle, TcHeader, TcHtbGlob, TcHtbOpt, TcHtbXstats,
+        TcLinkLayer, TcMessage, TcMessageBuffer, TcOption, TcQdiscHtbOption,
+        TcRateSpec, TcStats, TcStats2, TcStatsBasic, TcStatsQueue, TcXstats,
+    },
+    AddressFamily,
+};
+
+// Setup:
+//      tc qdisc add dev lo root handle 1: htb default 10 r2q 10
+//
+// Capture nlmon of this command:
+//
+//      tc -s qdisc show dev lo
+//
+// Raw packet modification:
+//   * rtnetlink header removed.
+#[test]
+fn test_get_qdisc_htb() {
+    let raw = vec![
+        0x00, // AF_UNSPEC
+        0x00, 0x00, 0x00, // padding
+        0x01, 0x00, 0x00, 0x00, // iface index: 1
+        0x00, 0x00, 0x01, 0x00, // handle 1:0
+        0xff, 0xff, 0xff, 0xff, // parent u32::MAX (TC_H_ROOT)
+        0x02, 0x00, 0x00, 0x00, // info(refcount): 2
+        0x08, 0x00, // length 8
+        0x01, 0x00, // TCA_KIND
+        0x68, 0x74, 0x62, 0x00, // "htb\0"
+        0x24, 0x00, // length 36
+        0x02, 0x00, // TCA_OPTIONS for `htb`
+        0x18, 0x00, // length 24
+        0x02, 0x00, // TCA_HTB_INIT
+        0x11, 0x00, 0x03, 0x00, // version: 3.17
+        0x0a, 0x00, 0x00, 0x00, // rate2quantum: 10
+        0x10, 0x00, 0x00, 0x00, // defcls: 0x10
+        0x00, 0x00, 0x00, 0x00, // debug: 0
+        0x00, 0x00, 0x00, 0x00, // direct_pkts: 0
+        0x08, 0x00, // length 8
+        0x05, 0x00, // TCA_HTB_DIRECT_QLEN
+        0xe8, 0x03, 0x00, 0x00, // 1000
+        0x05, 0x00, // length 5
+        0x0c, 0x00, // TCA_HW_OFFLOAD
+        0x00, 0x00, 0x00, 0x00, // 0 with padding
+        0x30, 0x00, // length 48
+        0x07, 0x00, // TCA_STATS2
+        0x14, 0x00, // length 20
+        0x01, 0x00, // TCA_STATS_BASIC
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, // bytes: 0
+        0x00, 0x00, 0x00, 0x00, // packets: 0
+        0x00, 0x00, 0x00, 0x00, // padding
+        0x18, 0x00, // length 24
+        0x03, 0x00, // TCA_STATS_QUEUE
+        0x00, 0x00, 0x00, 0x00, // qlen: 0
+        0x00, 0x00, 0x00, 0x00, // backlog: 0
+        0x00, 0x00, 0x00, 0x00, // drops: 0
+        0x00, 0x00, 0x00, 0x00, // requeues: 0
+        0x00, 0x00, 0x00, 0x00, // overlimits: 0
+        0x2c, 0x00, // length 44
+        0x03, 0x00, // TCA_STATS
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, // bytes: 0
+        0x00, 0x00, 0x00, 0x00, // packets: 0
+        0x00, 0x00, 0x00, 0x00, // drops: 0
+        0x00, 0x00, 0x00, 0x00, // overlimits: 0
+        0x00, 0x00, 0x00, 0x00, // bps: 0
+        0x00, 0x00, 0x00, 0x00, // pps: 0
+        0x00, 0x00, 0x00, 0x00, // qlen: 0
+        0x00, 0x00, 0x00, 0x00, // backlog: 0
+        0x00, 0x00, 0x00, 0x00, // padding
+    ];
+
+    let expected = TcMessage {
+        header: TcHeader {
+            family: AddressFamily::Unspec,
+            index: 1,
+            handle: TcHandle { major: 1, minor: 0 },
+            parent: TcHandle::ROOT,
+            info: 2,
+        },
+        attributes: vec![
+            TcAttribute::Kind("htb".to_string()),
+            TcAttribute::Options(vec![
+                TcOption::Htb(TcQdiscHtbOption::Init(TcHtbGlob {
+                    version: 0x30011,
+                    rate2quantum: 10,
+                    defcls: 0x10,
+                    debug: 0,
+                    direct_pkts: 0,
+                })),
+                TcOption::Htb(TcQdiscHtbOption::DirectQlen(1000)),
+            ]),
+            TcAttribute::HwOffload(0),
+            TcAttribute::Stats2(vec![
+                TcStats2::Basic(TcStatsBasic {
+                    bytes: 0,
+                    packets: 0,
+                }),
+                TcStats2::Queue(TcStatsQueue {
+                    qlen: 0,
+                    backlog: 0,
+                    drops: 0,
+                    requeues: 0,
+                    overlimits: 0,
+                }),
+            ]),
+            TcAttribute::Stats(TcStats {
+                bytes: 0,
+                packets: 0,
+                drops: 0,
+                overlimits: 0,
+                bps: 0,
+                pps: 0,
+                qlen: 0,
+                backlog: 0,
+            }),
+        ],
+    };
+
+    assert_eq!(
+        expected,
+        TcMessage::parse(&TcMessageBuffer::new(&raw)).unwrap()
+    );
+
+    let mut buf = vec![0; expected.buffer_len()];
+
+    expected.emit(&mut buf);
+
+    assert_eq!(buf, raw);
+}
+
+// Setup:
+//      tc qdisc add dev lo root handle 1: htb default 10 r2q 10
+//      tc class add dev lo parent 1: classid 1:10 htb \
+//          rate 1mbit ceil 2mbit prio 1
+//
+// Capture nlmon of this command:
+//
+//      tc -s class show dev lo
+//
+// Raw packet modification:
+//   * rtnetlink header removed.
+#[test]
+fn test_get_class_htb() {
+    let raw = vec![
+        0x00, // AF_UNSPEC
+        0x00, 0x00, 0x00, // padding
+        0x01, 0x00, 0x00, 0x00, // iface index: 1
+        0x10, 0x00, 0x01, 0x00, // handle 1:10
+        0xff, 0xff, 0xff, 0xff, // parent u32::MAX (TC_H_ROOT)
+        0x00, 0x00, 0x00, 0x00, // info: 0
+        0x08, 0x00, // length 8
+        0x01, 0x00, // TCA_KIND
+        0x68, 0x74, 0x62, 0x00, // "htb\0"
+        0x34, 0x00, // length 52
+        0x02, 0x00, // TCA_OPTIONS for `htb`
+        0x30, 0x00, // length 48
+        0x01, 0x00, // TCA_HTB_PARMS
+        0x00, // rate.cell_log: 0
+        0x01, // rate.linklayer: TC_LINKLAYER_ETHERNET
+        0x00, 0x00, // rate.overhead: 0
+        0x00, 0x00, // rate.cell_align: 0
+        0x00, 0x00, // rate.mpu: 0
+        0x48, 0xe8, 0x01, 0x00, // rate.rate: 125000
+        0x00, // ceil.cell_log: 0
+        0x01, // ceil.linklayer: TC_LINKLAYER_ETHERNET
+        0x00, 0x00, // ceil.overhead: 0
+        0x00, 0x00, // ceil.cell_align: 0
+        0x00, 0x00, // ceil.mpu: 0
+        0x90, 0xd0, 0x03, 0x00, // ceil.rate: 250000
+        0x40, 0x0d, 0x03, 0x00, // buffer: 200000
+        0xa0, 0x86, 0x01, 0x00, // cbuffer: 100000
+        0xd4, 0x30, 0x00, 0x00, // quantum: 12500
+        0x00, 0x00, 0x00, 0x00, // level: 0
+        0x01, 0x00, 0x00, 0x00, // prio: 1
+        0x48, 0x00, // length 72
+        0x07, 0x00, // TCA_STATS2
+        0x14, 0x00, // length 20
+        0x01, 0x00, // TCA_STATS_BASIC
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, // bytes: 0
+        0x00, 0x00, 0x00, 0x00, // packets: 0
+        0x00, 0x00, 0x00, 0x00, // padding
+        0x18, 0x00, // length 24
+        0x03, 0x00, // TCA_STATS_QUEUE
+        0x00, 0x00, 0x00, 0x00, // qlen: 0
+        0x00, 0x00, 0x00, 0x00, // backlog: 0
+        0x00, 0x00, 0x00, 0x00, // drops: 0
+        0x00, 0x00, 0x00, 0x00, // requeues: 0
+        0x00, 0x00, 0x00, 0x00, // overlimits: 0
+        0x18, 0x00, // length 24
+        0x04, 0x00, // TCA_STATS_APP
+        0x00, 0x00, 0x00, 0x00, // lends: 0
+        0x00, 0x00, 0x00, 0x00, // borrows: 0
+        0x00, 0x00, 0x00, 0x00, // giants: 0
+        0x40, 0x0d, 0x03, 0x00, // tokens: 200000
+        0xa0, 0x86, 0x01, 0x00, // ctokens: 100000
+        0x2c, 0x00, // length 44
+        0x03, 0x00, // TCA_STATS
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, // bytes: 0
+        0x00, 0x00, 0x00, 0x00, // packets: 0
+        0x00, 0x00, 0x00, 0x00, // drops: 0
+        0x00, 0x00, 0x00, 0x00, // overlimits: 0
+        0x00, 0x00, 0x00, 0x00, // bps: 0
+        0x00, 0x00, 0x00, 0x00, // pps: 0
+        0x00, 0x00, 0x00, 0x00, // qlen: 0
+        0x00, 0x00, 0x00, 0x00, // backlog: 0
+        0x00, 0x00, 0x00, 0x00, // padding
+        0x18, 0x00, // length 24
+        0x04, 0x00, // TCA_XSTATS
+        0x00, 0x00, 0x00, 0x00, // lends: 0
+        0x00, 0x00, 0x00, 0x00, // borrows: 0
+        0x00, 0x00, 0x00, 0x00, // giants: 0
+        0x40, 0x0d, 0x03, 0x00, // tokens: 200000
+        0xa0, 0x86, 0x01, 0x00, // ctokens: 100000
+    ];
+
+    let rate = TcRateSpec {
+        cell_log: 0,
+        linklayer: TcLinkLayer::Ethernet,
+        overhead: 0,
+        cell_align: 0,
+        mpu: 0,
+        rate: 125000,
+    };
+    let ceil = TcRateSpec {
+        rate: 250000,
+        ..rate
+    };
+
+    let xstats = TcXstats::Htb(TcHtbXstats {
+        lends: 0,
+        borrows: 0,
+        giants: 0,
+        tokens: 200000,
+        ctokens: 100000,
+    });
+
+    let expected = TcMessage {
+        header: TcHeader {
+            family: AddressFamily::Unspec,
+            index: 1,
+            handle: TcHandle {
+                major: 1,
+                minor: 0x10,
+            },
+            parent: TcHandle::ROOT,
+            info: 0,
+        },
+        attributes: vec![
+            TcAttribute::Kind("htb".to_string()),
+            TcAttribute::Options(vec![TcOption::Htb(TcQdiscHtbOption::Parms(
+                TcHtbOpt {
+                    rate,
+                    ceil,
+                    buffer: 200000,
+                    cbuffer: 100000,
+                    quantum: 12500,
+                    level: 0,
+                    prio: 1,
+                },
+            ))]),
+            TcAttribute::Stats2(vec![
+                TcStats2::Basic(TcStatsBasic {
+                    bytes: 0,
+                    packets: 0,
+                }),
+                TcStats2::Queue(TcStatsQueue {
+                    qlen: 0,
+                    backlog: 0,
+                    drops: 0,
+                    requeues: 0,
+                    overlimits: 0,
+                }),
+                TcStats2::App(xstats.clone()),
+            ]),
+            TcAttribute::Stats(TcStats {
+                bytes: 0,
+                packets: 0,
+                drops: 0,
+                overlimits: 0,
+                bps: 0,
+                pps: 0,
+                qlen: 0,
+                backlog: 0,
+            }),
+            TcAttribute::Xstats(xstats),
+        ],
+    };
+
+    assert_eq!(
+        expected,
+        TcMessage::parse(&TcMessageBuffer::new(&raw)).unwrap()
+    );
+
+    let mut buf = vec![0; expected.buffer_len()];
+
+    expected.emit(&mut buf);
+
+    assert_eq!(buf, raw);
+}
+
+// Setup:
+//      tc qdisc add dev lo root handle 1: htb default 10 r2q 10
+//      tc class add dev lo parent 1: classid 1:20 htb \
+//          rate 50gbit ceil 50gbit
+//
+// Capture nlmon of this command:
+//
+//      tc class show dev lo classid 1:20
+//
+// Raw packet modification:
+//   * rtnetlink header removed.
+//   * TCA_STATS2, TCA_STATS and TCA_XSTATS removed.
+#[test]
+fn test_get_class_htb_rate64() {
+    let raw = vec![
+        0x00, // AF_UNSPEC
+        0x00, 0x00, 0x00, // padding
+        0x01, 0x00, 0x00, 0x00, // iface index: 1
+        0x20, 0x00, 0x01, 0x00, // handle 1:20
+        0xff, 0xff, 0xff, 0xff, // parent u32::MAX (TC_H_ROOT)
+        0x00, 0x00, 0x00, 0x00, // info: 0
+        0x08, 0x00, // length 8
+        0x01, 0x00, // TCA_KIND
+        0x68, 0x74, 0x62, 0x00, // "htb\0"
+        0x4c, 0x00, // length 76
+        0x02, 0x00, // TCA_OPTIONS for `htb`
+        0x30, 0x00, // length 48
+        0x01, 0x00, // TCA_HTB_PARMS
+        0x00, 0x01, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, // rate
+        0xff, 0xff, 0xff, 0xff, // rate.rate: u32::MAX
+        0x00, 0x01, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, // ceil
+        0xff, 0xff, 0xff, 0xff, // ceil.rate: u32::MAX
+        0x00, 0x00, 0x00, 0x00, // buffer: 0
+        0x00, 0x00, 0x00, 0x00, // cbuffer: 0
+        0x40, 0x0d, 0x03, 0x00, // quantum: 200000
+        0x00, 0x00, 0x00, 0x00, // level: 0
+        0x00, 0x00, 0x00, 0x00, // prio: 0
+        0x0c, 0x00, // length 12
+        0x06, 0x00, // TCA_HTB_RATE64
+        0x80, 0x6e, 0x87, 0x74, 0x01, 0x00, 0x00, 0x00, // 6250000000
+        0x0c, 0x00, // length 12
+        0x07, 0x00, // TCA_HTB_CEIL64
+        0x80, 0x6e, 0x87, 0x74, 0x01, 0x00, 0x00, 0x00, // 6250000000
+    ];
+
+    let rate = TcRateSpec {
+        cell_log: 0,
+        linklayer: TcLinkLayer::Ethernet,
+        overhead: 0,
+        cell_align: 0,
+        mpu: 0,
+        rate: u32::MAX,
+    };
+
+    let expected = TcMessage {
+        header: TcHeader {
+            family: AddressFamily::Unspec,
+            index: 1,
+            handle: TcHandle {
+                major: 1,
+                minor: 0x20,
+            },
+            parent: TcHandle::ROOT,
+            info: 0,
+        },
+        attributes: vec![
+            TcAttribute::Kind("htb".to_string()),
+            TcAttribute::Options(vec![
+                TcOption::Htb(TcQdiscHtbOption::Parms(TcHtbOpt {
+                    rate,
+                    ceil: rate,
+                    buffer: 0,
+                    cbuffer: 0,
+                    quantum: 200000,
+                    level: 0,
+                    prio: 0,
+                })),
+                TcOption::Htb(TcQdiscHtbOption::Rate64(6250000000)),
+                TcOption::Htb(TcQdiscHtbOption::Ceil64(6250000000)),
+            ]),
+        ],
+    };
+
+    assert_eq!(
+        expected,
+        TcMessage::parse(&TcMessageBuffer::new(&raw)).unwrap()
+    );
+
+    let mut buf = vec![0; expected.buffer_len()];
+
+    expected.emit(&mut buf);
+
+    assert_eq!(buf, raw);
+}
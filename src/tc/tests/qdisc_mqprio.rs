@@ -0,0 +1,147 @@
+// SPDX-License-Identifier: MIT
+
+use netlink_packet_utils::{Emitable, Parseable};
+
+use crate::{
+    tc::{
+        TcAttribute, TcFpAdminStatus, TcHandle, TcHeader, TcMessage,
+        TcMessageBuffer, TcMqprioMode, TcMqprioQopt, TcMqprioShaper,
+        TcMqprioTcEntry, TcOption, TcQdiscMqprioOption,
+    },
+    AddressFamily,
+};
+
+// Raw packet follows the kernel `mqprio_dump()` layout for qdisc created by
+//
+//      tc qdisc add dev eth1 root handle 1: mqprio num_tc 2 \
+//          map 0 0 0 0 1 1 1 1 queues 1@0 1@1 hw 0 mode channel \
+//          shaper bw_rlimit min_rate 1mbit 2mbit max_rate 10mbit 20mbit
+//
+// with:
+//   * rtnetlink header removed.
+//   * Only first 2 of 16 TCA_MQPRIO_TC_ENTRY included.
+//   * TCA_STATS, TCA_STATS2 and TCA_HW_OFFLOAD removed.
+#[test]
+fn test_get_qdisc_mqprio() {
+    let raw = vec![
+        0x00, // AF_UNSPEC
+        0x00, 0x00, 0x00, // padding
+        0x02, 0x00, 0x00, 0x00, // iface index: 2
+        0x00, 0x00, 0x01, 0x00, // handle 1:0
+        0xff, 0xff, 0xff, 0xff, // parent u32::MAX (TC_H_ROOT)
+        0x01, 0x00, 0x00, 0x00, // info(refcount): 1
+        0x0b, 0x00, // length 11
+        0x01, 0x00, // TCA_KIND
+        0x6d, 0x71, 0x70, 0x72, 0x69, 0x6f, 0x00, // "mqprio\0"
+        0x00, // padding
+        0xc8, 0x00, // length 200
+        0x02, 0x00, // TCA_OPTIONS for `mqprio`
+        0x02, // num_tc: 2
+        0x00, 0x00, 0x00, 0x00, 0x01, 0x01, 0x01, 0x01, // prio_tc_map
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, // prio_tc_map
+        0x00, // hw: 0
+        0x01, 0x00, 0x01, 0x00, 0x00, 0x00, 0x00, 0x00, // count
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, // count
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, // count
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, // count
+        0x00, 0x00, 0x01, 0x00, 0x00, 0x00, 0x00, 0x00, // offset
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, // offset
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, // offset
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, // offset
+        0x00, 0x00, // padding
+        0x06, 0x00, // length 6
+        0x01, 0x00, // TCA_MQPRIO_MODE
+        0x01, 0x00, 0x00, 0x00, // TC_MQPRIO_MODE_CHANNEL and padding
+        0x06, 0x00, // length 6
+        0x02, 0x00, // TCA_MQPRIO_SHAPER
+        0x01, 0x00, 0x00, 0x00, // TC_MQPRIO_SHAPER_BW_RATE and padding
+        0x1c, 0x00, // length 28
+        0x03, 0x00, // TCA_MQPRIO_MIN_RATE64
+        0x0c, 0x00, // length 12
+        0x03, 0x00, // TCA_MQPRIO_MIN_RATE64
+        0x48, 0xe8, 0x01, 0x00, 0x00, 0x00, 0x00, 0x00, // 125000
+        0x0c, 0x00, // length 12
+        0x03, 0x00, // TCA_MQPRIO_MIN_RATE64
+        0x90, 0xd0, 0x03, 0x00, 0x00, 0x00, 0x00, 0x00, // 250000
+        0x1c, 0x00, // length 28
+        0x04, 0x00, // TCA_MQPRIO_MAX_RATE64
+        0x0c, 0x00, // length 12
+        0x04, 0x00, // TCA_MQPRIO_MAX_RATE64
+        0xd0, 0x12, 0x13, 0x00, 0x00, 0x00, 0x00, 0x00, // 1250000
+        0x0c, 0x00, // length 12
+        0x04, 0x00, // TCA_MQPRIO_MAX_RATE64
+        0xa0, 0x25, 0x26, 0x00, 0x00, 0x00, 0x00, 0x00, // 2500000
+        0x14, 0x00, // length 20
+        0x05, 0x80, // TCA_MQPRIO_TC_ENTRY with NLA_F_NESTED
+        0x08, 0x00, // length 8
+        0x01, 0x00, // TCA_MQPRIO_TC_ENTRY_INDEX
+        0x00, 0x00, 0x00, 0x00, // 0
+        0x08, 0x00, // length 8
+        0x02, 0x00, // TCA_MQPRIO_TC_ENTRY_FP
+        0x01, 0x00, 0x00, 0x00, // TC_FP_EXPRESS
+        0x14, 0x00, // length 20
+        0x05, 0x80, // TCA_MQPRIO_TC_ENTRY with NLA_F_NESTED
+        0x08, 0x00, // length 8
+        0x01, 0x00, // TCA_MQPRIO_TC_ENTRY_INDEX
+        0x01, 0x00, 0x00, 0x00, // 1
+        0x08, 0x00, // length 8
+        0x02, 0x00, // TCA_MQPRIO_TC_ENTRY_FP
+        0x01, 0x00, 0x00, 0x00, // TC_FP_EXPRESS
+    ];
+
+    let mut qopt = TcMqprioQopt {
+        num_tc: 2,
+        prio_tc_map: [0, 0, 0, 0, 1, 1, 1, 1, 0, 0, 0, 0, 0, 0, 0, 0],
+        hw: 0,
+        ..Default::default()
+    };
+    qopt.count[..2].copy_from_slice(&[1, 1]);
+    qopt.offset[..2].copy_from_slice(&[0, 1]);
+
+    let expected = TcMessage {
+        header: TcHeader {
+            family: AddressFamily::Unspec,
+            index: 2,
+            handle: TcHandle { major: 1, minor: 0 },
+            parent: TcHandle::ROOT,
+            info: 1,
+        },
+        attributes: vec![
+            TcAttribute::Kind("mqprio".to_string()),
+            TcAttribute::Options(vec![
+                TcOption::Mqprio(TcQdiscMqprioOption::Qopt(qopt)),
+                TcOption::Mqprio(TcQdiscMqprioOption::Mode(
+                    TcMqprioMode::Channel,
+                )),
+                TcOption::Mqprio(TcQdiscMqprioOption::Shaper(
+                    TcMqprioShaper::BwRate,
+                )),
+                TcOption::Mqprio(TcQdiscMqprioOption::MinRate64(vec![
+                    125000, 250000,
+                ])),
+                TcOption::Mqprio(TcQdiscMqprioOption::MaxRate64(vec![
+                    1250000, 2500000,
+                ])),
+                TcOption::Mqprio(TcQdiscMqprioOption::TcEntry(vec![
+                    TcMqprioTcEntry::Index(0),
+                    TcMqprioTcEntry::Fp(TcFpAdminStatus::Express),
+                ])),
+                TcOption::Mqprio(TcQdiscMqprioOption::TcEntry(vec![
+                    TcMqprioTcEntry::Index(1),
+                    TcMqprioTcEntry::Fp(TcFpAdminStatus::Express),
+                ])),
+            ]),
+        ],
+    };
+
+    assert_eq!(
+        expected,
+        TcMessage::parse(&TcMessageBuffer::new(&raw)).unwrap()
+    );
+
+    let mut buf = vec![0; expected.buffer_len()];
+
+    expected.emit(&mut buf);
+
+    assert_eq!(buf, raw);
+}
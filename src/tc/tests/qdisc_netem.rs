@@ -0,0 +1,155 @@
+// SPDX-License-Identifier: MIT
+
+use netlink_packet_utils::{Emitable, Parseable};
+
+use crate::{
+    tc::{
+        TcAttribute, TcHandle, TcHeader, TcMessage, TcMessageBuffer,
+        TcNetemCorr, TcNetemCorrupt, TcNetemGeModel, TcNetemLoss, TcNetemQopt,
+        TcNetemRate, TcNetemReorder, TcNetemSlot, TcOption, TcQdiscNetemOption,
+    },
+    AddressFamily,
+};
+
+// Capture nlmon of this command:
+//
+//      tc qdisc add dev lo root netem limit 1000 \
+//          delay 100ms 10ms 25% \
+//          loss gemodel 1% 10% 70% 0.1% \
+//          duplicate 1% corrupt 0.1% reorder 5% 50% gap 5 \
+//          rate 1mbit 20 100 -4 \
+//          slot 800us 1ms packets 32 ecn
+//
+// Raw packet modification:
+//   * rtnetlink header removed.
+#[test]
+fn test_new_qdisc_netem() {
+    let raw = vec![
+        0x00, // AF_UNSPEC
+        0x00, 0x00, 0x00, // padding
+        0x01, 0x00, 0x00, 0x00, // iface index: 1
+        0x00, 0x00, 0x00, 0x00, // handle 0:0 (TC_H_UNSPEC)
+        0xff, 0xff, 0xff, 0xff, // parent u32::MAX (TC_H_ROOT)
+        0x00, 0x00, 0x00, 0x00, // info: 0
+        0x0a, 0x00, // length 10
+        0x01, 0x00, // TCA_KIND
+        0x6e, 0x65, 0x74, 0x65, 0x6d, 0x00, 0x00, 0x00,
+        // "netem\0" and 2 bytes pad
+        0xa4, 0x00, // length 164
+        0x02, 0x00, // TCA_OPTIONS for `netem`
+        0x84, 0xd7, 0x17, 0x00, // tc_netem_qopt.latency: 1562500 ticks
+        0xe8, 0x03, 0x00, 0x00, // tc_netem_qopt.limit: 1000
+        0x00, 0x00, 0x00, 0x00, // tc_netem_qopt.loss: 0
+        0x05, 0x00, 0x00, 0x00, // tc_netem_qopt.gap: 5
+        0x29, 0x5c, 0x8f, 0x02, // tc_netem_qopt.duplicate: 1%
+        0x5a, 0x62, 0x02, 0x00, // tc_netem_qopt.jitter: 156250 ticks
+        0x10, 0x00, // length 16
+        0x01, 0x00, // TCA_NETEM_CORR
+        0x00, 0x00, 0x00, 0x40, // delay_corr: 25%
+        0x00, 0x00, 0x00, 0x00, // loss_corr: 0
+        0x00, 0x00, 0x00, 0x00, // dup_corr: 0
+        0x0c, 0x00, // length 12
+        0x03, 0x00, // TCA_NETEM_REORDER
+        0xcd, 0xcc, 0xcc, 0x0c, // probability: 5%
+        0x00, 0x00, 0x00, 0x80, // correlation: 50%
+        0x08, 0x00, // length 8
+        0x07, 0x00, // TCA_NETEM_ECN
+        0x01, 0x00, 0x00, 0x00, // 1
+        0x0c, 0x00, // length 12
+        0x04, 0x00, // TCA_NETEM_CORRUPT
+        0x37, 0x89, 0x41, 0x00, // probability: 0.1%
+        0x00, 0x00, 0x00, 0x00, // correlation: 0
+        0x2c, 0x00, // length 44
+        0x0c, 0x00, // TCA_NETEM_SLOT
+        0x00, 0x35, 0x0c, 0x00, 0x00, 0x00, 0x00, 0x00, // min_delay 800us
+        0x40, 0x42, 0x0f, 0x00, 0x00, 0x00, 0x00, 0x00, // max_delay 1ms
+        0x20, 0x00, 0x00, 0x00, // max_packets: 32
+        0x00, 0x00, 0x00, 0x00, // max_bytes: 0
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, // dist_delay: 0
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, // dist_jitter: 0
+        0x18, 0x00, // length 24
+        0x05, 0x80, // TCA_NETEM_LOSS with NLA_F_NESTED
+        0x14, 0x00, // length 20
+        0x02, 0x00, // NETEM_LOSS_GE
+        0x29, 0x5c, 0x8f, 0x02, // p: 1%
+        0x9a, 0x99, 0x99, 0x19, // r: 10%
+        0xcd, 0xcc, 0xcc, 0x4c, // h: 30%
+        0x37, 0x89, 0x41, 0x00, // k1: 0.1%
+        0x14, 0x00, // length 20
+        0x06, 0x00, // TCA_NETEM_RATE
+        0x48, 0xe8, 0x01, 0x00, // rate: 125000
+        0x14, 0x00, 0x00, 0x00, // packet_overhead: 20
+        0x64, 0x00, 0x00, 0x00, // cell_size: 100
+        0xfc, 0xff, 0xff, 0xff, // cell_overhead: -4
+    ];
+
+    let expected = TcMessage {
+        header: TcHeader {
+            family: AddressFamily::Unspec,
+            index: 1,
+            handle: TcHandle::UNSPEC,
+            parent: TcHandle::ROOT,
+            info: 0,
+        },
+        attributes: vec![
+            TcAttribute::Kind("netem".to_string()),
+            TcAttribute::Options(vec![
+                TcOption::Netem(TcQdiscNetemOption::Qopt(TcNetemQopt {
+                    latency: 1562500,
+                    limit: 1000,
+                    loss: 0,
+                    gap: 5,
+                    duplicate: 42949673,
+                    jitter: 156250,
+                })),
+                TcOption::Netem(TcQdiscNetemOption::Corr(TcNetemCorr {
+                    delay_corr: 1073741824,
+                    loss_corr: 0,
+                    dup_corr: 0,
+                })),
+                TcOption::Netem(TcQdiscNetemOption::Reorder(TcNetemReorder {
+                    probability: 214748365,
+                    correlation: 2147483648,
+                })),
+                TcOption::Netem(TcQdiscNetemOption::Ecn(1)),
+                TcOption::Netem(TcQdiscNetemOption::Corrupt(TcNetemCorrupt {
+                    probability: 4294967,
+                    correlation: 0,
+                })),
+                TcOption::Netem(TcQdiscNetemOption::Slot(TcNetemSlot {
+                    min_delay: 800000,
+                    max_delay: 1000000,
+                    max_packets: 32,
+                    max_bytes: 0,
+                    dist_delay: 0,
+                    dist_jitter: 0,
+                })),
+                TcOption::Netem(TcQdiscNetemOption::Loss(vec![
+                    TcNetemLoss::Ge(TcNetemGeModel {
+                        p: 42949673,
+                        r: 429496730,
+                        h: 1288490189,
+                        k1: 4294967,
+                    }),
+                ])),
+                TcOption::Netem(TcQdiscNetemOption::Rate(TcNetemRate {
+                    rate: 125000,
+                    packet_overhead: 20,
+                    cell_size: 100,
+                    cell_overhead: -4,
+                })),
+            ]),
+        ],
+    };
+
+    assert_eq!(
+        expected,
+        TcMessage::parse(&TcMessageBuffer::new(&raw)).unwrap()
+    );
+
+    let mut buf = vec![0; expected.buffer_len()];
+
+    expected.emit(&mut buf);
+
+    assert_eq!(buf, raw);
+}
@@ -0,0 +1,380 @@
+// SPDX-License-Identifier: MIT
+
+use netlink_packet_utils::{Emitable, Parseable};
+
+use crate::{
+    tc::{
+        TcAttribute, TcFqPieXstats, TcHandle, TcHeader, TcMessage,
+        TcMessageBuffer, TcOption, TcPieXstats, TcQdiscFqPieOption,
+        TcQdiscPieOption, TcXstats,
+    },
+    AddressFamily,
+};
+
+// Capture nlmon of this command:
+//
+//      tc qdisc add dev lo root handle 1: pie limit 1000 target 15ms \
+//          tupdate 15ms alpha 2 beta 20 ecn bytemode dq_rate_estimator
+//
+// Raw packet modification:
+//   * rtnetlink header removed.
+#[test]
+fn test_new_qdisc_pie() {
+    let raw = vec![
+        0x00, // AF_UNSPEC
+        0x00, 0x00, 0x00, // padding
+        0x01, 0x00, 0x00, 0x00, // iface index: 1
+        0x00, 0x00, 0x01, 0x00, // handle 1:0
+        0xff, 0xff, 0xff, 0xff, // parent u32::MAX (TC_H_ROOT)
+        0x00, 0x00, 0x00, 0x00, // info: 0
+        0x08, 0x00, // length 8
+        0x01, 0x00, // TCA_KIND
+        0x70, 0x69, 0x65, 0x00, // "pie\0"
+        0x44, 0x00, // length 68
+        0x02, 0x00, // TCA_OPTIONS for `pie`
+        0x08, 0x00, // length 8
+        0x02, 0x00, // TCA_PIE_LIMIT
+        0xe8, 0x03, 0x00, 0x00, // 1000
+        0x08, 0x00, // length 8
+        0x03, 0x00, // TCA_PIE_TUPDATE
+        0x98, 0x3a, 0x00, 0x00, // 15000
+        0x08, 0x00, // length 8
+        0x01, 0x00, // TCA_PIE_TARGET
+        0x98, 0x3a, 0x00, 0x00, // 15000
+        0x08, 0x00, // length 8
+        0x04, 0x00, // TCA_PIE_ALPHA
+        0x02, 0x00, 0x00, 0x00, // 2
+        0x08, 0x00, // length 8
+        0x05, 0x00, // TCA_PIE_BETA
+        0x14, 0x00, 0x00, 0x00, // 20
+        0x08, 0x00, // length 8
+        0x06, 0x00, // TCA_PIE_ECN
+        0x01, 0x00, 0x00, 0x00, // 1
+        0x08, 0x00, // length 8
+        0x07, 0x00, // TCA_PIE_BYTEMODE
+        0x01, 0x00, 0x00, 0x00, // 1
+        0x08, 0x00, // length 8
+        0x08, 0x00, // TCA_PIE_DQ_RATE_ESTIMATOR
+        0x01, 0x00, 0x00, 0x00, // 1
+    ];
+
+    let expected = TcMessage {
+        header: TcHeader {
+            family: AddressFamily::Unspec,
+            index: 1,
+            handle: TcHandle { major: 1, minor: 0 },
+            parent: TcHandle::ROOT,
+            info: 0,
+        },
+        attributes: vec![
+            TcAttribute::Kind("pie".to_string()),
+            TcAttribute::Options(vec![
+                TcOption::Pie(TcQdiscPieOption::Limit(1000)),
+                TcOption::Pie(TcQdiscPieOption::Tupdate(15000)),
+                TcOption::Pie(TcQdiscPieOption::Target(15000)),
+                TcOption::Pie(TcQdiscPieOption::Alpha(2)),
+                TcOption::Pie(TcQdiscPieOption::Beta(20)),
+                TcOption::Pie(TcQdiscPieOption::Ecn(1)),
+                TcOption::Pie(TcQdiscPieOption::Bytemode(1)),
+                TcOption::Pie(TcQdiscPieOption::DqRateEstimator(1)),
+            ]),
+        ],
+    };
+
+    assert_eq!(
+        expected,
+        TcMessage::parse(&TcMessageBuffer::new(&raw)).unwrap()
+    );
+
+    let mut buf = vec![0; expected.buffer_len()];
+
+    expected.emit(&mut buf);
+
+    assert_eq!(buf, raw);
+}
+
+// Raw packet follows the kernel `pie_dump_stats()` layout for
+// `tc -s qdisc show dev lo` with:
+//   * rtnetlink header removed.
+//   * TCA_OPTIONS, TCA_STATS and TCA_STATS2 removed.
+#[test]
+fn test_get_qdisc_pie_xstats() {
+    let raw = vec![
+        0x00, // AF_UNSPEC
+        0x00, 0x00, 0x00, // padding
+        0x01, 0x00, 0x00, 0x00, // iface index: 1
+        0x00, 0x00, 0x01, 0x00, // handle 1:0
+        0xff, 0xff, 0xff, 0xff, // parent u32::MAX (TC_H_ROOT)
+        0x00, 0x00, 0x00, 0x00, // info: 0
+        0x08, 0x00, // length 8
+        0x01, 0x00, // TCA_KIND
+        0x70, 0x69, 0x65, 0x00, // "pie\0"
+        0x2c, 0x00, // length 44
+        0x04, 0x00, // TCA_XSTATS
+        0x8f, 0xc2, 0xf5, 0x28, 0x5c, 0x8f, 0x02, 0x00, // prob: 1%
+        0x03, 0x00, 0x00, 0x00, // delay: 3
+        0x00, 0x00, 0x00, 0x00, // avg_dq_rate: 0
+        0x01, 0x00, 0x00, 0x00, // dq_rate_estimating: 1
+        0x0d, 0x2f, 0x00, 0x00, // packets_in: 12045
+        0x25, 0x00, 0x00, 0x00, // dropped: 37
+        0x00, 0x00, 0x00, 0x00, // overlimit: 0
+        0x56, 0x00, 0x00, 0x00, // maxq: 86
+        0x04, 0x00, 0x00, 0x00, // ecn_mark: 4
+    ];
+
+    let expected = TcMessage {
+        header: TcHeader {
+            family: AddressFamily::Unspec,
+            index: 1,
+            handle: TcHandle { major: 1, minor: 0 },
+            parent: TcHandle::ROOT,
+            info: 0,
+        },
+        attributes: vec![
+            TcAttribute::Kind("pie".to_string()),
+            TcAttribute::Xstats(TcXstats::Pie(TcPieXstats {
+                prob: 720575940379279,
+                delay: 3,
+                avg_dq_rate: 0,
+                dq_rate_estimating: 1,
+                packets_in: 12045,
+                dropped: 37,
+                overlimit: 0,
+                maxq: 86,
+                ecn_mark: 4,
+            })),
+        ],
+    };
+
+    assert_eq!(
+        expected,
+        TcMessage::parse(&TcMessageBuffer::new(&raw)).unwrap()
+    );
+
+    let mut buf = vec![0; expected.buffer_len()];
+
+    expected.emit(&mut buf);
+
+    assert_eq!(buf, raw);
+}
+
+// Raw packet follows the pre-5.0 kernel `pie_dump_stats()` layout, with a
+// u32 `prob` and no `dq_rate_estimating`, for
+// `tc -s qdisc show dev lo` with:
+//   * rtnetlink header removed.
+//   * TCA_OPTIONS, TCA_STATS and TCA_STATS2 removed.
+#[test]
+fn test_get_qdisc_pie_legacy_xstats() {
+    let raw = vec![
+        0x00, // AF_UNSPEC
+        0x00, 0x00, 0x00, // padding
+        0x01, 0x00, 0x00, 0x00, // iface index: 1
+        0x00, 0x00, 0x01, 0x00, // handle 1:0
+        0xff, 0xff, 0xff, 0xff, // parent u32::MAX (TC_H_ROOT)
+        0x00, 0x00, 0x00, 0x00, // info: 0
+        0x08, 0x00, // length 8
+        0x01, 0x00, // TCA_KIND
+        0x70, 0x69, 0x65, 0x00, // "pie\0"
+        0x24, 0x00, // length 36
+        0x04, 0x00, // TCA_XSTATS
+        0x5c, 0x8f, 0x02, 0x00, // prob: 1%
+        0x03, 0x00, 0x00, 0x00, // delay: 3
+        0x00, 0x00, 0x00, 0x00, // avg_dq_rate: 0
+        0x0d, 0x2f, 0x00, 0x00, // packets_in: 12045
+        0x25, 0x00, 0x00, 0x00, // dropped: 37
+        0x00, 0x00, 0x00, 0x00, // overlimit: 0
+        0x56, 0x00, 0x00, 0x00, // maxq: 86
+        0x04, 0x00, 0x00, 0x00, // ecn_mark: 4
+    ];
+
+    let expected = TcMessage {
+        header: TcHeader {
+            family: AddressFamily::Unspec,
+            index: 1,
+            handle: TcHandle { major: 1, minor: 0 },
+            parent: TcHandle::ROOT,
+            info: 0,
+        },
+        attributes: vec![
+            TcAttribute::Kind("pie".to_string()),
+            TcAttribute::Xstats(TcXstats::Other(raw[32..].to_vec())),
+        ],
+    };
+
+    assert_eq!(
+        expected,
+        TcMessage::parse(&TcMessageBuffer::new(&raw)).unwrap()
+    );
+
+    let mut buf = vec![0; expected.buffer_len()];
+
+    expected.emit(&mut buf);
+
+    assert_eq!(buf, raw);
+}
+
+// Capture nlmon of this command:
+//
+//      tc qdisc add dev lo root handle 1: fq_pie limit 10240 flows 1024 \
+//          target 15ms tupdate 15ms alpha 2 beta 20 quantum 1514 \
+//          memory_limit 32Mb ecn_prob 10 ecn bytemode dq_rate_estimator
+//
+// Raw packet modification:
+//   * rtnetlink header removed.
+#[test]
+fn test_new_qdisc_fq_pie() {
+    let raw = vec![
+        0x00, // AF_UNSPEC
+        0x00, 0x00, 0x00, // padding
+        0x01, 0x00, 0x00, 0x00, // iface index: 1
+        0x00, 0x00, 0x01, 0x00, // handle 1:0
+        0xff, 0xff, 0xff, 0xff, // parent u32::MAX (TC_H_ROOT)
+        0x00, 0x00, 0x00, 0x00, // info: 0
+        0x0b, 0x00, // length 11
+        0x01, 0x00, // TCA_KIND
+        0x66, 0x71, 0x5f, 0x70, 0x69, 0x65, 0x00, // "fq_pie\0"
+        0x00, // padding
+        0x64, 0x00, // length 100
+        0x02, 0x80, // TCA_OPTIONS for `fq_pie` with NLA_F_NESTED
+        0x08, 0x00, // length 8
+        0x01, 0x00, // TCA_FQ_PIE_LIMIT
+        0x00, 0x28, 0x00, 0x00, // 10240
+        0x08, 0x00, // length 8
+        0x02, 0x00, // TCA_FQ_PIE_FLOWS
+        0x00, 0x04, 0x00, 0x00, // 1024
+        0x08, 0x00, // length 8
+        0x03, 0x00, // TCA_FQ_PIE_TARGET
+        0x98, 0x3a, 0x00, 0x00, // 15000
+        0x08, 0x00, // length 8
+        0x04, 0x00, // TCA_FQ_PIE_TUPDATE
+        0x98, 0x3a, 0x00, 0x00, // 15000
+        0x08, 0x00, // length 8
+        0x05, 0x00, // TCA_FQ_PIE_ALPHA
+        0x02, 0x00, 0x00, 0x00, // 2
+        0x08, 0x00, // length 8
+        0x06, 0x00, // TCA_FQ_PIE_BETA
+        0x14, 0x00, 0x00, 0x00, // 20
+        0x08, 0x00, // length 8
+        0x07, 0x00, // TCA_FQ_PIE_QUANTUM
+        0xea, 0x05, 0x00, 0x00, // 1514
+        0x08, 0x00, // length 8
+        0x08, 0x00, // TCA_FQ_PIE_MEMORY_LIMIT
+        0x00, 0x00, 0x00, 0x02, // 33554432
+        0x08, 0x00, // length 8
+        0x09, 0x00, // TCA_FQ_PIE_ECN_PROB
+        0x0a, 0x00, 0x00, 0x00, // 10
+        0x08, 0x00, // length 8
+        0x0a, 0x00, // TCA_FQ_PIE_ECN
+        0x01, 0x00, 0x00, 0x00, // 1
+        0x08, 0x00, // length 8
+        0x0b, 0x00, // TCA_FQ_PIE_BYTEMODE
+        0x01, 0x00, 0x00, 0x00, // 1
+        0x08, 0x00, // length 8
+        0x0c, 0x00, // TCA_FQ_PIE_DQ_RATE_ESTIMATOR
+        0x01, 0x00, 0x00, 0x00, // 1
+    ];
+
+    let expected = TcMessage {
+        header: TcHeader {
+            family: AddressFamily::Unspec,
+            index: 1,
+            handle: TcHandle { major: 1, minor: 0 },
+            parent: TcHandle::ROOT,
+            info: 0,
+        },
+        attributes: vec![
+            TcAttribute::Kind("fq_pie".to_string()),
+            TcAttribute::Options(vec![
+                TcOption::FqPie(TcQdiscFqPieOption::Limit(10240)),
+                TcOption::FqPie(TcQdiscFqPieOption::Flows(1024)),
+                TcOption::FqPie(TcQdiscFqPieOption::Target(15000)),
+                TcOption::FqPie(TcQdiscFqPieOption::Tupdate(15000)),
+                TcOption::FqPie(TcQdiscFqPieOption::Alpha(2)),
+                TcOption::FqPie(TcQdiscFqPieOption::Beta(20)),
+                TcOption::FqPie(TcQdiscFqPieOption::Quantum(1514)),
+                TcOption::FqPie(TcQdiscFqPieOption::MemoryLimit(33554432)),
+                TcOption::FqPie(TcQdiscFqPieOption::EcnProb(10)),
+                TcOption::FqPie(TcQdiscFqPieOption::Ecn(1)),
+                TcOption::FqPie(TcQdiscFqPieOption::Bytemode(1)),
+                TcOption::FqPie(TcQdiscFqPieOption::DqRateEstimator(1)),
+            ]),
+        ],
+    };
+
+    assert_eq!(
+        expected,
+        TcMessage::parse(&TcMessageBuffer::new(&raw)).unwrap()
+    );
+
+    let mut buf = vec![0; expected.buffer_len()];
+
+    expected.emit(&mut buf);
+
+    assert_eq!(buf, raw);
+}
+
+// Raw packet follows the kernel `fq_pie_dump_stats()` layout for
+// `tc -s qdisc show dev lo` with:
+//   * rtnetlink header removed.
+//   * TCA_OPTIONS, TCA_STATS and TCA_STATS2 removed.
+#[test]
+fn test_get_qdisc_fq_pie_xstats() {
+    let raw = vec![
+        0x00, // AF_UNSPEC
+        0x00, 0x00, 0x00, // padding
+        0x01, 0x00, 0x00, 0x00, // iface index: 1
+        0x00, 0x00, 0x01, 0x00, // handle 1:0
+        0xff, 0xff, 0xff, 0xff, // parent u32::MAX (TC_H_ROOT)
+        0x00, 0x00, 0x00, 0x00, // info: 0
+        0x0b, 0x00, // length 11
+        0x01, 0x00, // TCA_KIND
+        0x66, 0x71, 0x5f, 0x70, 0x69, 0x65, 0x00, // "fq_pie\0"
+        0x00, // padding
+        0x28, 0x00, // length 40
+        0x04, 0x00, // TCA_XSTATS
+        0x0d, 0x2f, 0x00, 0x00, // packets_in: 12045
+        0x25, 0x00, 0x00, 0x00, // dropped: 37
+        0x00, 0x00, 0x00, 0x00, // overlimit: 0
+        0x00, 0x00, 0x00, 0x00, // overmemory: 0
+        0x04, 0x00, 0x00, 0x00, // ecn_mark: 4
+        0x09, 0x00, 0x00, 0x00, // new_flow_count: 9
+        0x00, 0x00, 0x00, 0x00, // new_flows_len: 0
+        0x02, 0x00, 0x00, 0x00, // old_flows_len: 2
+        0x00, 0x00, 0x00, 0x00, // memory_usage: 0
+    ];
+
+    let expected = TcMessage {
+        header: TcHeader {
+            family: AddressFamily::Unspec,
+            index: 1,
+            handle: TcHandle { major: 1, minor: 0 },
+            parent: TcHandle::ROOT,
+            info: 0,
+        },
+        attributes: vec![
+            TcAttribute::Kind("fq_pie".to_string()),
+            TcAttribute::Xstats(TcXstats::FqPie(TcFqPieXstats {
+                packets_in: 12045,
+                dropped: 37,
+                overlimit: 0,
+                overmemory: 0,
+                ecn_mark: 4,
+                new_flow_count: 9,
+                new_flows_len: 0,
+                old_flows_len: 2,
+                memory_usage: 0,
+            })),
+        ],
+    };
+
+    assert_eq!(
+        expected,
+        TcMessage::parse(&TcMessageBuffer::new(&raw)).unwrap()
+    );
+
+    let mut buf = vec![0; expected.buffer_len()];
+
+    expected.emit(&mut buf);
+
+    assert_eq!(buf, raw);
+}
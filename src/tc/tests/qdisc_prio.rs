@@ -0,0 +1,186 @@
+// SPDX-License-Identifier: MIT
+
+use netlink_packet_utils::{nla::DefaultNla, Emitable, Parseable};
+
+use crate::{
+    tc::{
+        TcAttribute, TcHandle, TcHeader, TcMessage, TcMessageBuffer, TcOption,
+        TcPrioQopt, TcQdiscPrioOption, TcStats, TcStats2, TcStatsBasic,
+        TcStatsQueue,
+    },
+    AddressFamily,
+};
+
+// Capture nlmon of this command:
+//
+//      tc qdisc add dev lo root handle 1: prio bands 4 \
+//          priomap 1 2 2 2 1 2 0 0 1 1 1 1 1 1 1 3
+//
+// Raw packet modification:
+//   * rtnetlink header removed.
+#[test]
+fn test_new_qdisc_prio() {
+    let raw = vec![
+        0x00, // AF_UNSPEC
+        0x00, 0x00, 0x00, // padding
+        0x01, 0x00, 0x00, 0x00, // iface index: 1
+        0x00, 0x00, 0x01, 0x00, // handle 1:0
+        0xff, 0xff, 0xff, 0xff, // parent u32::MAX (TC_H_ROOT)
+        0x00, 0x00, 0x00, 0x00, // info: 0
+        0x09, 0x00, // length 9
+        0x01, 0x00, // TCA_KIND
+        0x70, 0x72, 0x69, 0x6f, 0x00, // "prio\0"
+        0x00, 0x00, 0x00, // padding
+        0x1c, 0x00, // length 28
+        0x02, 0x00, // TCA_OPTIONS for `prio`
+        0x04, 0x00, 0x00, 0x00, // bands: 4
+        0x01, 0x02, 0x02, 0x02, 0x01, 0x02, 0x00, 0x00, // priomap
+        0x01, 0x01, 0x01, 0x01, 0x01, 0x01, 0x01, 0x03, // priomap
+        0x04, 0x00, // length 4
+        0x02, 0x00, // empty nested TCA_OPTIONS
+    ];
+
+    let expected = TcMessage {
+        header: TcHeader {
+            family: AddressFamily::Unspec,
+            index: 1,
+            handle: TcHandle { major: 1, minor: 0 },
+            parent: TcHandle::ROOT,
+            info: 0,
+        },
+        attributes: vec![
+            TcAttribute::Kind("prio".to_string()),
+            TcAttribute::Options(vec![
+                TcOption::Prio(TcQdiscPrioOption::Qopt(TcPrioQopt {
+                    bands: 4,
+                    priomap: [1, 2, 2, 2, 1, 2, 0, 0, 1, 1, 1, 1, 1, 1, 1, 3],
+                })),
+                TcOption::Prio(TcQdiscPrioOption::Other(DefaultNla::new(
+                    2,
+                    vec![],
+                ))),
+            ]),
+        ],
+    };
+
+    assert_eq!(
+        expected,
+        TcMessage::parse(&TcMessageBuffer::new(&raw)).unwrap()
+    );
+
+    let mut buf = vec![0; expected.buffer_len()];
+
+    expected.emit(&mut buf);
+
+    assert_eq!(buf, raw);
+}
+
+// Setup:
+//      tc qdisc add dev lo root handle 1: pfifo_fast
+//
+// Capture nlmon of this command:
+//
+//      tc -s qdisc show dev lo
+//
+// Raw packet modification:
+//   * rtnetlink header removed.
+#[test]
+fn test_get_qdisc_pfifo_fast() {
+    let raw = vec![
+        0x00, // AF_UNSPEC
+        0x00, 0x00, 0x00, // padding
+        0x01, 0x00, 0x00, 0x00, // iface index: 1
+        0x00, 0x00, 0x01, 0x00, // handle 1:0
+        0xff, 0xff, 0xff, 0xff, // parent u32::MAX (TC_H_ROOT)
+        0x02, 0x00, 0x00, 0x00, // info(refcount): 2
+        0x0f, 0x00, // length 15
+        0x01, 0x00, // TCA_KIND
+        0x70, 0x66, 0x69, 0x66, 0x6f, 0x5f, 0x66, 0x61, 0x73, 0x74,
+        0x00, // "pfifo_fast\0"
+        0x00, // padding
+        0x18, 0x00, // length 24
+        0x02, 0x00, // TCA_OPTIONS for `pfifo_fast`
+        0x03, 0x00, 0x00, 0x00, // bands: 3
+        0x01, 0x02, 0x02, 0x02, 0x01, 0x02, 0x00, 0x00, // priomap
+        0x01, 0x01, 0x01, 0x01, 0x01, 0x01, 0x01, 0x01, // priomap
+        0x05, 0x00, // length 5
+        0x0c, 0x00, // TCA_HW_OFFLOAD
+        0x00, 0x00, 0x00, 0x00, // 0 with padding
+        0x30, 0x00, // length 48
+        0x07, 0x00, // TCA_STATS2
+        0x14, 0x00, // length 20
+        0x01, 0x00, // TCA_STATS_BASIC
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, // bytes: 0
+        0x00, 0x00, 0x00, 0x00, // packets: 0
+        0x00, 0x00, 0x00, 0x00, // padding
+        0x18, 0x00, // length 24
+        0x03, 0x00, // TCA_STATS_QUEUE
+        0x00, 0x00, 0x00, 0x00, // qlen: 0
+        0x00, 0x00, 0x00, 0x00, // backlog: 0
+        0x00, 0x00, 0x00, 0x00, // drops: 0
+        0x00, 0x00, 0x00, 0x00, // requeues: 0
+        0x00, 0x00, 0x00, 0x00, // overlimits: 0
+        0x2c, 0x00, // length 44
+        0x03, 0x00, // TCA_STATS
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, // bytes: 0
+        0x00, 0x00, 0x00, 0x00, // packets: 0
+        0x00, 0x00, 0x00, 0x00, // drops: 0
+        0x00, 0x00, 0x00, 0x00, // overlimits: 0
+        0x00, 0x00, 0x00, 0x00, // bps: 0
+        0x00, 0x00, 0x00, 0x00, // pps: 0
+        0x00, 0x00, 0x00, 0x00, // qlen: 0
+        0x00, 0x00, 0x00, 0x00, // backlog: 0
+        0x00, 0x00, 0x00, 0x00, // padding
+    ];
+
+    let expected = TcMessage {
+        header: TcHeader {
+            family: AddressFamily::Unspec,
+            index: 1,
+            handle: TcHandle { major: 1, minor: 0 },
+            parent: TcHandle::ROOT,
+            info: 2,
+        },
+        attributes: vec![
+            TcAttribute::Kind("pfifo_fast".to_string()),
+            TcAttribute::Options(vec![TcOption::PfifoFast(
+                TcQdiscPrioOption::Qopt(TcPrioQopt::default()),
+            )]),
+            TcAttribute::HwOffload(0),
+            TcAttribute::Stats2(vec![
+                TcStats2::Basic(TcStatsBasic {
+                    bytes: 0,
+                    packets: 0,
+                }),
+                TcStats2::Queue(TcStatsQueue {
+                    qlen: 0,
+                    backlog: 0,
+                    drops: 0,
+                    requeues: 0,
+                    overlimits: 0,
+                }),
+            ]),
+            TcAttribute::Stats(TcStats {
+                bytes: 0,
+                packets: 0,
+                drops: 0,
+                overlimits: 0,
+                bps: 0,
+                pps: 0,
+                qlen: 0,
+                backlog: 0,
+            }),
+        ],
+    };
+
+    assert_eq!(
+        expected,
+        TcMessage::parse(&TcMessageBuffer::new(&raw)).unwrap()
+    );
+
+    let mut buf = vec![0; expected.buffer_len()];
+
+    expected.emit(&mut buf);
+
+    assert_eq!(buf, raw);
+}
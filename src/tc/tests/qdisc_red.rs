@@ -0,0 +1,510 @@
+// SPDX-License-Identifier: MIT
+
+use netlink_packet_utils::{Emitable, Parseable};
+
+use crate::{
+    tc::{
+        TcAttribute, TcChokeXstats, TcGredQopt, TcGredSopt, TcGredVqEntry,
+        TcHandle, TcHeader, TcMessage, TcMessageBuffer, TcOption, TcPsched,
+        TcQdiscChokeOption, TcQdiscGredOption, TcQdiscRedOption, TcRedFlag,
+        TcRedFlags, TcRedQopt, TcRedXstats, TcXstats,
+    },
+    AddressFamily,
+};
+
+// Capture nlmon of this command:
+//
+//      tc qdisc add dev lo root handle 1: red limit 400000 min 30000 \
+//          max 90000 avpkt 1000 burst 55 ecn adaptive bandwidth 10Mbit
+//
+// Raw packet modification:
+//   * rtnetlink header removed.
+#[test]
+fn test_new_qdisc_red() {
+    let raw = vec![
+        0x00, // AF_UNSPEC
+        0x00, 0x00, 0x00, // padding
+        0x01, 0x00, 0x00, 0x00, // iface index: 1
+        0x00, 0x00, 0x01, 0x00, // handle 1:0
+        0xff, 0xff, 0xff, 0xff, // parent u32::MAX (TC_H_ROOT)
+        0x00, 0x00, 0x00, 0x00, // info: 0
+        0x08, 0x00, // length 8
+        0x01, 0x00, // TCA_KIND
+        0x72, 0x65, 0x64, 0x00, // "red\0"
+        0x30, 0x01, // length 304
+        0x02, 0x00, // TCA_OPTIONS for `red`
+        0x14, 0x00, // length 20
+        0x01, 0x00, // TCA_RED_PARMS
+        0x80, 0x1a, 0x06, 0x00, // limit: 400000
+        0x30, 0x75, 0x00, 0x00, // qth_min: 30000
+        0x90, 0x5f, 0x01, 0x00, // qth_max: 90000
+        0x05, // wlog: 5
+        0x16, // plog: 22
+        0x0f, // scell_log: 15
+        0x00, // flags: 0
+        0x04, 0x01, // length 260
+        0x02, 0x00, // TCA_RED_STAB
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0x00, // idle damping table
+        0x00, 0x01, 0x01, 0x01, 0x01, 0x01, 0x01, 0x01, 0x01, 0x01, 0x01, 0x01,
+        0x01, 0x02, 0x02, 0x02, 0x02, 0x02, 0x02, 0x02, 0x02, 0x02, 0x02, 0x02,
+        0x02, 0x03, 0x03, 0x03, 0x03, 0x03, 0x03, 0x03, 0x03, 0x03, 0x03, 0x03,
+        0x03, 0x04, 0x04, 0x04, 0x04, 0x04, 0x04, 0x04, 0x04, 0x04, 0x04, 0x04,
+        0x04, 0x05, 0x05, 0x05, 0x05, 0x05, 0x05, 0x05, 0x05, 0x05, 0x05, 0x05,
+        0x05, 0x06, 0x06, 0x06, 0x06, 0x06, 0x06, 0x06, 0x06, 0x06, 0x06, 0x06,
+        0x06, 0x07, 0x07, 0x07, 0x07, 0x07, 0x07, 0x07, 0x07, 0x07, 0x07, 0x07,
+        0x07, 0x08, 0x08, 0x08, 0x08, 0x08, 0x08, 0x08, 0x08, 0x08, 0x08, 0x08,
+        0x08, 0x09, 0x09, 0x09, 0x09, 0x09, 0x09, 0x09, 0x09, 0x09, 0x09, 0x09,
+        0x09, 0x0a, 0x0a, 0x0a, 0x0a, 0x0a, 0x0a, 0x0a, 0x0a, 0x0a, 0x0a, 0x0a,
+        0x0a, 0x0b, 0x0b, 0x0b, 0x0b, 0x0b, 0x0b, 0x0b, 0x0b, 0x0b, 0x0b, 0x0b,
+        0x0b, 0x0c, 0x0c, 0x0c, 0x0c, 0x0c, 0x0c, 0x0c, 0x0c, 0x0c, 0x0c, 0x0c,
+        0x0c, 0x0d, 0x0d, 0x0d, 0x0d, 0x0d, 0x0d, 0x0d, 0x0d, 0x0d, 0x0d, 0x0d,
+        0x0d, 0x0e, 0x0e, 0x0e, 0x0e, 0x0e, 0x0e, 0x0e, 0x0e, 0x0e, 0x0e, 0x0e,
+        0x0e, 0x0f, 0x0f, 0x0f, 0x0f, 0x0f, 0x0f, 0x0f, 0x0f, 0x0f, 0x0f, 0x0f,
+        0x0f, 0x10, 0x10, 0x10, 0x10, 0x10, 0x10, 0x10, 0x10, 0x10, 0x10, 0x10,
+        0x10, 0x11, 0x11, 0x11, 0x11, 0x11, 0x11, 0x11, 0x11, 0x11, 0x11, 0x11,
+        0x11, 0x12, 0x12, 0x12, 0x12, 0x12, 0x12, 0x12, 0x12, 0x12, 0x12, 0x12,
+        0x12, 0x13, 0x13, 0x13, 0x13, 0x13, 0x13, 0x13, 0x13, 0x13, 0x13, 0x13,
+        0x13, 0x14, 0x14, 0x14, 0x14, 0x14, 0x14, 0x14, 0x14, 0x14, 0x14, 0x14,
+        0x14, 0x15, 0x15, 0x1f, 0x08, 0x00, // length 8
+        0x03, 0x00, // TCA_RED_MAX_P
+        0x51, 0xb8, 0x1e, 0x05, // 85899345
+        0x0c, 0x00, // length 12
+        0x04, 0x00, // TCA_RED_FLAGS
+        0x05, 0x00, 0x00, 0x00, // value: TC_RED_ECN | TC_RED_ADAPTATIVE
+        0x0f, 0x00, 0x00, 0x00, // selector: all 4 flags
+    ];
+
+    let (scell_log, stab) =
+        TcPsched::default().calc_red_stab(5, 1000, 1250000).unwrap();
+    assert_eq!(scell_log, 15);
+
+    let expected = TcMessage {
+        header: TcHeader {
+            family: AddressFamily::Unspec,
+            index: 1,
+            handle: TcHandle { major: 1, minor: 0 },
+            parent: TcHandle::ROOT,
+            info: 0,
+        },
+        attributes: vec![
+            TcAttribute::Kind("red".to_string()),
+            TcAttribute::Options(vec![
+                TcOption::Red(TcQdiscRedOption::Parms(TcRedQopt {
+                    limit: 400000,
+                    qth_min: 30000,
+                    qth_max: 90000,
+                    wlog: 5,
+                    plog: 22,
+                    scell_log,
+                    flags: vec![],
+                })),
+                TcOption::Red(TcQdiscRedOption::Stab(stab)),
+                TcOption::Red(TcQdiscRedOption::MaxP(85899345)),
+                TcOption::Red(TcQdiscRedOption::Flags(TcRedFlags {
+                    value: vec![TcRedFlag::Ecn, TcRedFlag::Adaptative],
+                    selector: vec![
+                        TcRedFlag::Ecn,
+                        TcRedFlag::HardDrop,
+                        TcRedFlag::Adaptative,
+                        TcRedFlag::NoDrop,
+                    ],
+                })),
+            ]),
+        ],
+    };
+
+    assert_eq!(
+        expected,
+        TcMessage::parse(&TcMessageBuffer::new(&raw)).unwrap()
+    );
+
+    let mut buf = vec![0; expected.buffer_len()];
+
+    expected.emit(&mut buf);
+
+    assert_eq!(buf, raw);
+}
+
+// Raw packet follows the kernel `red_dump()` and `red_dump_stats()` layout
+// for `tc -s qdisc show dev lo` after creating the `red` qdisc of
+// `test_new_qdisc_red()` with:
+//   * rtnetlink header removed.
+//   * TCA_STATS, TCA_STATS2 and TCA_HW_OFFLOAD removed.
+#[test]
+fn test_get_qdisc_red_xstats() {
+    let raw = vec![
+        0x00, // AF_UNSPEC
+        0x00, 0x00, 0x00, // padding
+        0x01, 0x00, 0x00, 0x00, // iface index: 1
+        0x00, 0x00, 0x01, 0x00, // handle 1:0
+        0xff, 0xff, 0xff, 0xff, // parent u32::MAX (TC_H_ROOT)
+        0x02, 0x00, 0x00, 0x00, // info(refcount): 2
+        0x08, 0x00, // length 8
+        0x01, 0x00, // TCA_KIND
+        0x72, 0x65, 0x64, 0x00, // "red\0"
+        0x3c, 0x00, // length 60
+        0x02, 0x00, // TCA_OPTIONS for `red`
+        0x14, 0x00, // length 20
+        0x01, 0x00, // TCA_RED_PARMS
+        0x80, 0x1a, 0x06, 0x00, // limit: 400000
+        0x30, 0x75, 0x00, 0x00, // qth_min: 30000
+        0x90, 0x5f, 0x01, 0x00, // qth_max: 90000
+        0x05, // wlog: 5
+        0x16, // plog: 22
+        0x0f, // scell_log: 15
+        0x05, // flags: TC_RED_ECN | TC_RED_ADAPTATIVE
+        0x08, 0x00, // length 8
+        0x03, 0x00, // TCA_RED_MAX_P
+        0x51, 0xb8, 0x1e, 0x05, // 85899345
+        0x0c, 0x00, // length 12
+        0x04, 0x00, // TCA_RED_FLAGS
+        0x05, 0x00, 0x00, 0x00, // value: TC_RED_ECN | TC_RED_ADAPTATIVE
+        0x0f, 0x00, 0x00, 0x00, // selector: all 4 flags
+        0x08, 0x00, // length 8
+        0x05, 0x00, // TCA_RED_EARLY_DROP_BLOCK
+        0x00, 0x00, 0x00, 0x00, // 0
+        0x08, 0x00, // length 8
+        0x06, 0x00, // TCA_RED_MARK_BLOCK
+        0x00, 0x00, 0x00, 0x00, // 0
+        0x14, 0x00, // length 20
+        0x04, 0x00, // TCA_XSTATS
+        0x03, 0x00, 0x00, 0x00, // early: 3
+        0x01, 0x00, 0x00, 0x00, // pdrop: 1
+        0x00, 0x00, 0x00, 0x00, // other: 0
+        0x07, 0x00, 0x00, 0x00, // marked: 7
+    ];
+
+    let expected = TcMessage {
+        header: TcHeader {
+            family: AddressFamily::Unspec,
+            index: 1,
+            handle: TcHandle { major: 1, minor: 0 },
+            parent: TcHandle::ROOT,
+            info: 2,
+        },
+        attributes: vec![
+            TcAttribute::Kind("red".to_string()),
+            TcAttribute::Options(vec![
+                TcOption::Red(TcQdiscRedOption::Parms(TcRedQopt {
+                    limit: 400000,
+                    qth_min: 30000,
+                    qth_max: 90000,
+                    wlog: 5,
+                    plog: 22,
+                    scell_log: 15,
+                    flags: vec![TcRedFlag::Ecn, TcRedFlag::Adaptative],
+                })),
+                TcOption::Red(TcQdiscRedOption::MaxP(85899345)),
+                TcOption::Red(TcQdiscRedOption::Flags(TcRedFlags {
+                    value: vec![TcRedFlag::Ecn, TcRedFlag::Adaptative],
+                    selector: vec![
+                        TcRedFlag::Ecn,
+                        TcRedFlag::HardDrop,
+                        TcRedFlag::Adaptative,
+                        TcRedFlag::NoDrop,
+                    ],
+                })),
+                TcOption::Red(TcQdiscRedOption::EarlyDropBlock(0)),
+                TcOption::Red(TcQdiscRedOption::MarkBlock(0)),
+            ]),
+            TcAttribute::Xstats(TcXstats::Red(TcRedXstats {
+                early: 3,
+                pdrop: 1,
+                other: 0,
+                marked: 7,
+            })),
+        ],
+    };
+
+    assert_eq!(
+        expected,
+        TcMessage::parse(&TcMessageBuffer::new(&raw)).unwrap()
+    );
+
+    let mut buf = vec![0; expected.buffer_len()];
+
+    expected.emit(&mut buf);
+
+    assert_eq!(buf, raw);
+}
+
+// Capture nlmon of this command:
+//
+//      tc qdisc add dev lo root handle 1: gred setup vqs 4 default 0 grio
+//
+// Raw packet modification:
+//   * rtnetlink header removed.
+#[test]
+fn test_new_qdisc_gred_setup() {
+    let raw = vec![
+        0x00, // AF_UNSPEC
+        0x00, 0x00, 0x00, // padding
+        0x01, 0x00, 0x00, 0x00, // iface index: 1
+        0x00, 0x00, 0x01, 0x00, // handle 1:0
+        0xff, 0xff, 0xff, 0xff, // parent u32::MAX (TC_H_ROOT)
+        0x00, 0x00, 0x00, 0x00, // info: 0
+        0x09, 0x00, // length 9
+        0x01, 0x00, // TCA_KIND
+        0x67, 0x72, 0x65, 0x64, 0x00, 0x00, 0x00,
+        0x00, // "gred\0" with padding
+        0x14, 0x00, // length 20
+        0x02, 0x00, // TCA_OPTIONS for `gred`
+        0x10, 0x00, // length 16
+        0x03, 0x00, // TCA_GRED_DPS
+        0x04, 0x00, 0x00, 0x00, // DPs: 4
+        0x00, 0x00, 0x00, 0x00, // def_DP: 0
+        0x01, // grio: 1
+        0x00, // flags: 0
+        0x00, 0x00, // padding
+    ];
+
+    let expected = TcMessage {
+        header: TcHeader {
+            family: AddressFamily::Unspec,
+            index: 1,
+            handle: TcHandle { major: 1, minor: 0 },
+            parent: TcHandle::ROOT,
+            info: 0,
+        },
+        attributes: vec![
+            TcAttribute::Kind("gred".to_string()),
+            TcAttribute::Options(vec![TcOption::Gred(TcQdiscGredOption::Dps(
+                TcGredSopt {
+                    dps: 4,
+                    def_dp: 0,
+                    grio: 1,
+                    flags: vec![],
+                },
+            ))]),
+        ],
+    };
+
+    assert_eq!(
+        expected,
+        TcMessage::parse(&TcMessageBuffer::new(&raw)).unwrap()
+    );
+
+    let mut buf = vec![0; expected.buffer_len()];
+
+    expected.emit(&mut buf);
+
+    assert_eq!(buf, raw);
+}
+
+// Capture nlmon of this command:
+//
+//      tc qdisc add dev lo root handle 1: gred limit 60000 min 15000 \
+//          max 45000 avpkt 1000 burst 20 bandwidth 10Mbit DP 1 \
+//          probability 0.02 prio 2 ecn
+//
+// Raw packet modification:
+//   * rtnetlink header removed.
+#[test]
+fn test_new_qdisc_gred_vq() {
+    let raw = vec![
+        0x00, // AF_UNSPEC
+        0x00, 0x00, 0x00, // padding
+        0x01, 0x00, 0x00, 0x00, // iface index: 1
+        0x00, 0x00, 0x01, 0x00, // handle 1:0
+        0xff, 0xff, 0xff, 0xff, // parent u32::MAX (TC_H_ROOT)
+        0x00, 0x00, 0x00, 0x00, // info: 0
+        0x09, 0x00, // length 9
+        0x01, 0x00, // TCA_KIND
+        0x67, 0x72, 0x65, 0x64, 0x00, 0x00, 0x00,
+        0x00, // "gred\0" with padding
+        0x60, 0x01, // length 352
+        0x02, 0x00, // TCA_OPTIONS for `gred`
+        0x38, 0x00, // length 56
+        0x01, 0x00, // TCA_GRED_PARMS
+        0x60, 0xea, 0x00, 0x00, // limit: 60000
+        0x98, 0x3a, 0x00, 0x00, // qth_min: 15000
+        0xc8, 0xaf, 0x00, 0x00, // qth_max: 45000
+        0x01, 0x00, 0x00, 0x00, // DP: 1
+        0x00, 0x00, 0x00, 0x00, // backlog: 0
+        0x00, 0x00, 0x00, 0x00, // qave: 0
+        0x00, 0x00, 0x00, 0x00, // forced: 0
+        0x00, 0x00, 0x00, 0x00, // early: 0
+        0x00, 0x00, 0x00, 0x00, // other: 0
+        0x00, 0x00, 0x00, 0x00, // pdrop: 0
+        0x03, // wlog: 3
+        0x15, // plog: 21
+        0x0d, // scell_log: 13
+        0x02, // prio: 2
+        0x00, 0x00, 0x00, 0x00, // packets: 0
+        0x00, 0x00, 0x00, 0x00, // bytesin: 0
+        0x04, 0x01, // length 260
+        0x02, 0x00, // TCA_GRED_STAB
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0x00, // idle damping table
+        0x01, 0x01, 0x01, 0x01, 0x01, 0x01, 0x01, 0x01, 0x01, 0x01, 0x01, 0x02,
+        0x02, 0x02, 0x02, 0x02, 0x02, 0x02, 0x02, 0x02, 0x02, 0x02, 0x02, 0x03,
+        0x03, 0x03, 0x03, 0x03, 0x03, 0x03, 0x03, 0x03, 0x03, 0x03, 0x04, 0x04,
+        0x04, 0x04, 0x04, 0x04, 0x04, 0x04, 0x04, 0x04, 0x04, 0x04, 0x05, 0x05,
+        0x05, 0x05, 0x05, 0x05, 0x05, 0x05, 0x05, 0x05, 0x05, 0x06, 0x06, 0x06,
+        0x06, 0x06, 0x06, 0x06, 0x06, 0x06, 0x06, 0x06, 0x07, 0x07, 0x07, 0x07,
+        0x07, 0x07, 0x07, 0x07, 0x07, 0x07, 0x07, 0x07, 0x08, 0x08, 0x08, 0x08,
+        0x08, 0x08, 0x08, 0x08, 0x08, 0x08, 0x08, 0x09, 0x09, 0x09, 0x09, 0x09,
+        0x09, 0x09, 0x09, 0x09, 0x09, 0x09, 0x09, 0x0a, 0x0a, 0x0a, 0x0a, 0x0a,
+        0x0a, 0x0a, 0x0a, 0x0a, 0x0a, 0x0a, 0x0b, 0x0b, 0x0b, 0x0b, 0x0b, 0x0b,
+        0x0b, 0x0b, 0x0b, 0x0b, 0x0b, 0x0b, 0x0c, 0x0c, 0x0c, 0x0c, 0x0c, 0x0c,
+        0x0c, 0x0c, 0x0c, 0x0c, 0x0c, 0x0d, 0x0d, 0x0d, 0x0d, 0x0d, 0x0d, 0x0d,
+        0x0d, 0x0d, 0x0d, 0x0d, 0x0e, 0x0e, 0x0e, 0x0e, 0x0e, 0x0e, 0x0e, 0x0e,
+        0x0e, 0x0e, 0x0e, 0x0e, 0x0f, 0x0f, 0x0f, 0x0f, 0x0f, 0x0f, 0x0f, 0x0f,
+        0x0f, 0x0f, 0x0f, 0x10, 0x10, 0x10, 0x10, 0x10, 0x10, 0x10, 0x10, 0x10,
+        0x10, 0x10, 0x10, 0x11, 0x11, 0x11, 0x11, 0x11, 0x11, 0x11, 0x11, 0x11,
+        0x11, 0x11, 0x12, 0x12, 0x12, 0x12, 0x12, 0x12, 0x12, 0x12, 0x12, 0x12,
+        0x12, 0x12, 0x13, 0x13, 0x13, 0x13, 0x13, 0x13, 0x13, 0x13, 0x13, 0x13,
+        0x13, 0x14, 0x14, 0x14, 0x14, 0x14, 0x14, 0x14, 0x14, 0x14, 0x14, 0x14,
+        0x15, 0x15, 0x15, 0x15, 0x15, 0x15, 0x15, 0x15, 0x15, 0x15, 0x15, 0x15,
+        0x16, 0x16, 0x16, 0x1f, 0x08, 0x00, // length 8
+        0x04, 0x00, // TCA_GRED_MAX_P
+        0x51, 0xb8, 0x1e, 0x05, // 85899345
+        0x18, 0x00, // length 24
+        0x06, 0x00, // TCA_GRED_VQ_LIST
+        0x14, 0x00, // length 20
+        0x01, 0x00, // TCA_GRED_VQ_ENTRY
+        0x08, 0x00, // length 8
+        0x02, 0x00, // TCA_GRED_VQ_DP
+        0x01, 0x00, 0x00, 0x00, // 1
+        0x08, 0x00, // length 8
+        0x0c, 0x00, // TCA_GRED_VQ_FLAGS
+        0x01, 0x00, 0x00, 0x00, // TC_RED_ECN
+    ];
+
+    let (scell_log, stab) =
+        TcPsched::default().calc_red_stab(3, 1000, 1250000).unwrap();
+    assert_eq!(scell_log, 13);
+
+    let expected = TcMessage {
+        header: TcHeader {
+            family: AddressFamily::Unspec,
+            index: 1,
+            handle: TcHandle { major: 1, minor: 0 },
+            parent: TcHandle::ROOT,
+            info: 0,
+        },
+        attributes: vec![
+            TcAttribute::Kind("gred".to_string()),
+            TcAttribute::Options(vec![
+                TcOption::Gred(TcQdiscGredOption::Parms(vec![TcGredQopt {
+                    limit: 60000,
+                    qth_min: 15000,
+                    qth_max: 45000,
+                    dp: 1,
+                    wlog: 3,
+                    plog: 21,
+                    scell_log,
+                    prio: 2,
+                    ..Default::default()
+                }])),
+                TcOption::Gred(TcQdiscGredOption::Stab(stab)),
+                TcOption::Gred(TcQdiscGredOption::MaxP(vec![85899345])),
+                TcOption::Gred(TcQdiscGredOption::VqList(vec![vec![
+                    TcGredVqEntry::Dp(1),
+                    TcGredVqEntry::Flags(vec![TcRedFlag::Ecn]),
+                ]])),
+            ]),
+        ],
+    };
+
+    assert_eq!(
+        expected,
+        TcMessage::parse(&TcMessageBuffer::new(&raw)).unwrap()
+    );
+
+    let mut buf = vec![0; expected.buffer_len()];
+
+    expected.emit(&mut buf);
+
+    assert_eq!(buf, raw);
+}
+
+// Raw packet follows the kernel `choke_dump()` and `choke_dump_stats()`
+// layout for `tc -s qdisc show dev lo` after:
+//
+//      tc qdisc add dev lo root handle 1: choke limit 1000 \
+//          bandwidth 10Mbit min 100 max 300 avpkt 1000 ecn
+//
+// with:
+//   * rtnetlink header removed.
+//   * TCA_STATS, TCA_STATS2 and TCA_HW_OFFLOAD removed.
+#[test]
+fn test_get_qdisc_choke_xstats() {
+    let raw = vec![
+        0x00, // AF_UNSPEC
+        0x00, 0x00, 0x00, // padding
+        0x01, 0x00, 0x00, 0x00, // iface index: 1
+        0x00, 0x00, 0x01, 0x00, // handle 1:0
+        0xff, 0xff, 0xff, 0xff, // parent u32::MAX (TC_H_ROOT)
+        0x02, 0x00, 0x00, 0x00, // info(refcount): 2
+        0x0a, 0x00, // length 10
+        0x01, 0x00, // TCA_KIND
+        0x63, 0x68, 0x6f, 0x6b, 0x65, 0x00, 0x00,
+        0x00, // "choke\0" with padding
+        0x20, 0x00, // length 32
+        0x02, 0x00, // TCA_OPTIONS for `choke`
+        0x14, 0x00, // length 20
+        0x01, 0x00, // TCA_CHOKE_PARMS
+        0xe8, 0x03, 0x00, 0x00, // limit: 1000
+        0x64, 0x00, 0x00, 0x00, // qth_min: 100
+        0x2c, 0x01, 0x00, 0x00, // qth_max: 300
+        0x07, // wlog: 7
+        0x18, // plog: 24
+        0x11, // scell_log: 17
+        0x01, // flags: TC_RED_ECN
+        0x08, 0x00, // length 8
+        0x03, 0x00, // TCA_CHOKE_MAX_P
+        0x51, 0xb8, 0x1e, 0x05, // 85899345
+        0x18, 0x00, // length 24
+        0x04, 0x00, // TCA_XSTATS
+        0x02, 0x00, 0x00, 0x00, // early: 2
+        0x00, 0x00, 0x00, 0x00, // pdrop: 0
+        0x00, 0x00, 0x00, 0x00, // other: 0
+        0x05, 0x00, 0x00, 0x00, // marked: 5
+        0x09, 0x00, 0x00, 0x00, // matched: 9
+    ];
+
+    let expected = TcMessage {
+        header: TcHeader {
+            family: AddressFamily::Unspec,
+            index: 1,
+            handle: TcHandle { major: 1, minor: 0 },
+            parent: TcHandle::ROOT,
+            info: 2,
+        },
+        attributes: vec![
+            TcAttribute::Kind("choke".to_string()),
+            TcAttribute::Options(vec![
+                TcOption::Choke(TcQdiscChokeOption::Parms(TcRedQopt {
+                    limit: 1000,
+                    qth_min: 100,
+                    qth_max: 300,
+                    wlog: 7,
+                    plog: 24,
+                    scell_log: 17,
+                    flags: vec![TcRedFlag::Ecn],
+                })),
+                TcOption::Choke(TcQdiscChokeOption::MaxP(85899345)),
+            ]),
+            TcAttribute::Xstats(TcXstats::Choke(TcChokeXstats {
+                early: 2,
+                pdrop: 0,
+                other: 0,
+                marked: 5,
+                matched: 9,
+            })),
+        ],
+    };
+
+    assert_eq!(
+        expected,
+        TcMessage::parse(&TcMessageBuffer::new(&raw)).unwrap()
+    );
+
+    let mut buf = vec![0; expected.buffer_len()];
+
+    expected.emit(&mut buf);
+
+    assert_eq!(buf, raw);
+}
@@ -0,0 +1,227 @@
+// SPDX-License-Identifier: MIT
+
+use netlink_packet_utils::{Emitable, Parseable};
+
+use crate::{
+    tc::{
+        TcAttribute, TcHandle, TcHeader, TcMessage, TcMessageBuffer, TcOption,
+        TcQdiscSfqOption, TcRedFlag, TcRedQopt, TcSfqQopt, TcSfqQoptV1,
+        TcSfqXstats, TcStats2, TcXstats,
+    },
+    AddressFamily,
+};
+
+// Capture nlmon of this command:
+//
+//      tc qdisc add dev lo root handle 1: sfq limit 3000 depth 127 \
+//          headdrop divisor 1024 redflowlimit 100000 min 8000 max 60000 \
+//          probability 0.20 ecn
+//
+// Raw packet modification:
+//   * rtnetlink header removed.
+#[test]
+fn test_new_qdisc_sfq_v1() {
+    let raw = vec![
+        0x00, // AF_UNSPEC
+        0x00, 0x00, 0x00, // padding
+        0x01, 0x00, 0x00, 0x00, // iface index: 1
+        0x00, 0x00, 0x01, 0x00, // handle 1:0
+        0xff, 0xff, 0xff, 0xff, // parent u32::MAX (TC_H_ROOT)
+        0x00, 0x00, 0x00, 0x00, // info: 0
+        0x08, 0x00, // length 8
+        0x01, 0x00, // TCA_KIND
+        0x73, 0x66, 0x71, 0x00, // "sfq\0"
+        0x4c, 0x00, // length 76
+        0x02, 0x00, // TCA_OPTIONS for `sfq`
+        0x00, 0x00, 0x00, 0x00, // v0.quantum: 0
+        0x00, 0x00, 0x00, 0x00, // v0.perturb_period: 0
+        0xb8, 0x0b, 0x00, 0x00, // v0.limit: 3000
+        0x00, 0x04, 0x00, 0x00, // v0.divisor: 1024
+        0x00, 0x00, 0x00, 0x00, // v0.flows: 0
+        0x7f, 0x00, 0x00, 0x00, // depth: 127
+        0x01, 0x00, 0x00, 0x00, // headdrop: 1
+        0xa0, 0x86, 0x01, 0x00, // red.limit: 100000
+        0x40, 0x1f, 0x00, 0x00, // red.qth_min: 8000
+        0x60, 0xea, 0x00, 0x00, // red.qth_max: 60000
+        0x06, // red.wlog: 6
+        0x12, // red.plog: 18
+        0x00, // red.scell_log: 0
+        0x01, // red.flags: TC_RED_ECN
+        0x33, 0x33, 0x33, 0x33, // max_p: 858993459
+        0x00, 0x00, 0x00, 0x00, // stats.prob_drop: 0
+        0x00, 0x00, 0x00, 0x00, // stats.forced_drop: 0
+        0x00, 0x00, 0x00, 0x00, // stats.prob_mark: 0
+        0x00, 0x00, 0x00, 0x00, // stats.forced_mark: 0
+        0x00, 0x00, 0x00, 0x00, // stats.prob_mark_head: 0
+        0x00, 0x00, 0x00, 0x00, // stats.forced_mark_head: 0
+    ];
+
+    let expected = TcMessage {
+        header: TcHeader {
+            family: AddressFamily::Unspec,
+            index: 1,
+            handle: TcHandle { major: 1, minor: 0 },
+            parent: TcHandle::ROOT,
+            info: 0,
+        },
+        attributes: vec![
+            TcAttribute::Kind("sfq".to_string()),
+            TcAttribute::Options(vec![TcOption::Sfq(
+                TcQdiscSfqOption::QoptV1(TcSfqQoptV1 {
+                    v0: TcSfqQopt {
+                        quantum: 0,
+                        perturb_period: 0,
+                        limit: 3000,
+                        divisor: 1024,
+                        flows: 0,
+                    },
+                    depth: 127,
+                    headdrop: 1,
+                    red: TcRedQopt {
+                        limit: 100000,
+                        qth_min: 8000,
+                        qth_max: 60000,
+                        wlog: 6,
+                        plog: 18,
+                        scell_log: 0,
+                        flags: vec![TcRedFlag::Ecn],
+                    },
+                    max_p: 858993459,
+                    stats: Default::default(),
+                }),
+            )]),
+        ],
+    };
+
+    assert_eq!(
+        expected,
+        TcMessage::parse(&TcMessageBuffer::new(&raw)).unwrap()
+    );
+
+    let mut buf = vec![0; expected.buffer_len()];
+
+    expected.emit(&mut buf);
+
+    assert_eq!(buf, raw);
+}
+
+// Raw packet follows the kernel `sfq_change()` handling of the original
+// `tc_sfq_qopt` sent by iproute2 before 3.3 for:
+//
+//      tc qdisc add dev lo root handle 1: sfq perturb 10
+//
+// with:
+//   * rtnetlink header removed.
+#[test]
+fn test_new_qdisc_sfq_v0() {
+    let raw = vec![
+        0x00, // AF_UNSPEC
+        0x00, 0x00, 0x00, // padding
+        0x01, 0x00, 0x00, 0x00, // iface index: 1
+        0x00, 0x00, 0x01, 0x00, // handle 1:0
+        0xff, 0xff, 0xff, 0xff, // parent u32::MAX (TC_H_ROOT)
+        0x00, 0x00, 0x00, 0x00, // info: 0
+        0x08, 0x00, // length 8
+        0x01, 0x00, // TCA_KIND
+        0x73, 0x66, 0x71, 0x00, // "sfq\0"
+        0x18, 0x00, // length 24
+        0x02, 0x00, // TCA_OPTIONS for `sfq`
+        0x00, 0x00, 0x00, 0x00, // quantum: 0
+        0x0a, 0x00, 0x00, 0x00, // perturb_period: 10
+        0x00, 0x00, 0x00, 0x00, // limit: 0
+        0x00, 0x00, 0x00, 0x00, // divisor: 0
+        0x00, 0x00, 0x00, 0x00, // flows: 0
+    ];
+
+    let expected = TcMessage {
+        header: TcHeader {
+            family: AddressFamily::Unspec,
+            index: 1,
+            handle: TcHandle { major: 1, minor: 0 },
+            parent: TcHandle::ROOT,
+            info: 0,
+        },
+        attributes: vec![
+            TcAttribute::Kind("sfq".to_string()),
+            TcAttribute::Options(vec![TcOption::Sfq(TcQdiscSfqOption::Qopt(
+                TcSfqQopt {
+                    perturb_period: 10,
+                    ..Default::default()
+                },
+            ))]),
+        ],
+    };
+
+    assert_eq!(
+        expected,
+        TcMessage::parse(&TcMessageBuffer::new(&raw)).unwrap()
+    );
+
+    let mut buf = vec![0; expected.buffer_len()];
+
+    expected.emit(&mut buf);
+
+    assert_eq!(buf, raw);
+}
+
+// Setup:
+//      tc qdisc add dev lo root handle 1: sfq
+//      ping -c 1 127.0.0.1
+//
+// Raw packet follows the kernel `sfq_dump_class_stats()` layout for
+// `tc -s class show dev lo` with:
+//   * rtnetlink header removed.
+//   * TCA_STATS and TCA_STATS_QUEUE of TCA_STATS2 removed.
+#[test]
+fn test_get_class_sfq_xstats() {
+    let raw = vec![
+        0x00, // AF_UNSPEC
+        0x00, 0x00, 0x00, // padding
+        0x01, 0x00, 0x00, 0x00, // iface index: 1
+        0xb2, 0x01, 0x01, 0x00, // handle 1:1b2
+        0x00, 0x00, 0x01, 0x00, // parent 1:0
+        0x00, 0x00, 0x00, 0x00, // info: 0
+        0x08, 0x00, // length 8
+        0x01, 0x00, // TCA_KIND
+        0x73, 0x66, 0x71, 0x00, // "sfq\0"
+        0x0c, 0x00, // length 12
+        0x07, 0x00, // TCA_STATS2
+        0x08, 0x00, // length 8
+        0x04, 0x00, // TCA_STATS_APP
+        0xc0, 0xfb, 0xff, 0xff, // allot: -1088
+        0x08, 0x00, // length 8
+        0x04, 0x00, // TCA_XSTATS
+        0xc0, 0xfb, 0xff, 0xff, // allot: -1088
+    ];
+
+    let expected = TcMessage {
+        header: TcHeader {
+            family: AddressFamily::Unspec,
+            index: 1,
+            handle: TcHandle {
+                major: 1,
+                minor: 0x1b2,
+            },
+            parent: TcHandle { major: 1, minor: 0 },
+            info: 0,
+        },
+        attributes: vec![
+            TcAttribute::Kind("sfq".to_string()),
+            TcAttribute::Stats2(vec![TcStats2::App(TcXstats::Sfq(
+                TcSfqXstats { allot: -1088 },
+            ))]),
+            TcAttribute::Xstats(TcXstats::Sfq(TcSfqXstats { allot: -1088 })),
+        ],
+    };
+
+    assert_eq!(
+        expected,
+        TcMessage::parse(&TcMessageBuffer::new(&raw)).unwrap()
+    );
+
+    let mut buf = vec![0; expected.buffer_len()];
+
+    expected.emit(&mut buf);
+
+    assert_eq!(buf, raw);
+}
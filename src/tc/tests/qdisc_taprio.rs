@@ -0,0 +1,200 @@
+// SPDX-License-Identifier: MIT
+
+use netlink_packet_utils::{Emitable, Parseable};
+
+use crate::{
+    tc::{
+        TcAttribute, TcHandle, TcHeader, TcMessage, TcMessageBuffer,
+        TcMqprioQopt, TcOption, TcQdiscTaprioOption, TcStats2, TcTaprioCmd,
+        TcTaprioSchedEntry, TcTaprioXstats, TcXstats,
+    },
+    AddressFamily,
+};
+
+// Capture nlmon of this command:
+//
+//      tc qdisc replace dev lo parent root handle 100 taprio num_tc 2 \
+//          map 0 0 0 0 1 1 1 1 queues 1@0 1@1 base-time 1000000000 \
+//          sched-entry S 01 300000 sched-entry S 02 200000 \
+//          clockid CLOCK_TAI
+//
+// Raw packet modification:
+//   * rtnetlink header removed.
+#[test]
+fn test_new_qdisc_taprio() {
+    let raw = vec![
+        0x00, // AF_UNSPEC
+        0x00, 0x00, 0x00, // padding
+        0x01, 0x00, 0x00, 0x00, // iface index: 1
+        0x00, 0x00, 0x00, 0x01, // handle 100:0
+        0xff, 0xff, 0xff, 0xff, // parent u32::MAX (TC_H_ROOT)
+        0x00, 0x00, 0x00, 0x00, // info: 0
+        0x0b, 0x00, // length 11
+        0x01, 0x00, // TCA_KIND
+        0x74, 0x61, 0x70, 0x72, 0x69, 0x6f, 0x00, // "taprio\0"
+        0x00, // padding
+        0xac, 0x00, // length 172
+        0x02, 0x00, // TCA_OPTIONS for `taprio`
+        0x08, 0x00, // length 8
+        0x05, 0x00, // TCA_TAPRIO_ATTR_SCHED_CLOCKID
+        0x0b, 0x00, 0x00, 0x00, // CLOCK_TAI
+        0x56, 0x00, // length 86
+        0x01, 0x00, // TCA_TAPRIO_ATTR_PRIOMAP
+        0x02, // num_tc: 2
+        0x00, 0x00, 0x00, 0x00, 0x01, 0x01, 0x01, 0x01, // prio_tc_map
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, // prio_tc_map
+        0x00, // hw: 0
+        0x01, 0x00, 0x01, 0x00, 0x00, 0x00, 0x00, 0x00, // count
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, // count
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, // count
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, // count
+        0x00, 0x00, 0x01, 0x00, 0x00, 0x00, 0x00, 0x00, // offset
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, // offset
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, // offset
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, // offset
+        0x00, 0x00, // padding
+        0x0c, 0x00, // length 12
+        0x03, 0x00, // TCA_TAPRIO_ATTR_SCHED_BASE_TIME
+        0x00, 0xca, 0x9a, 0x3b, 0x00, 0x00, 0x00, 0x00, // 1000000000
+        0x3c, 0x00, // length 60
+        0x02, 0x80, // TCA_TAPRIO_ATTR_SCHED_ENTRY_LIST with NLA_F_NESTED
+        0x1c, 0x00, // length 28
+        0x01, 0x00, // TCA_TAPRIO_SCHED_ENTRY
+        0x05, 0x00, // length 5
+        0x02, 0x00, // TCA_TAPRIO_SCHED_ENTRY_CMD
+        0x00, 0x00, 0x00, 0x00, // TC_TAPRIO_CMD_SET_GATES and padding
+        0x08, 0x00, // length 8
+        0x03, 0x00, // TCA_TAPRIO_SCHED_ENTRY_GATE_MASK
+        0x01, 0x00, 0x00, 0x00, // 0x01
+        0x08, 0x00, // length 8
+        0x04, 0x00, // TCA_TAPRIO_SCHED_ENTRY_INTERVAL
+        0xe0, 0x93, 0x04, 0x00, // 300000
+        0x1c, 0x00, // length 28
+        0x01, 0x00, // TCA_TAPRIO_SCHED_ENTRY
+        0x05, 0x00, // length 5
+        0x02, 0x00, // TCA_TAPRIO_SCHED_ENTRY_CMD
+        0x00, 0x00, 0x00, 0x00, // TC_TAPRIO_CMD_SET_GATES and padding
+        0x08, 0x00, // length 8
+        0x03, 0x00, // TCA_TAPRIO_SCHED_ENTRY_GATE_MASK
+        0x02, 0x00, 0x00, 0x00, // 0x02
+        0x08, 0x00, // length 8
+        0x04, 0x00, // TCA_TAPRIO_SCHED_ENTRY_INTERVAL
+        0x40, 0x0d, 0x03, 0x00, // 200000
+    ];
+
+    let mut priomap = TcMqprioQopt {
+        num_tc: 2,
+        prio_tc_map: [0, 0, 0, 0, 1, 1, 1, 1, 0, 0, 0, 0, 0, 0, 0, 0],
+        ..Default::default()
+    };
+    priomap.count[..2].copy_from_slice(&[1, 1]);
+    priomap.offset[..2].copy_from_slice(&[0, 1]);
+
+    let expected = TcMessage {
+        header: TcHeader {
+            family: AddressFamily::Unspec,
+            index: 1,
+            handle: TcHandle {
+                major: 0x100,
+                minor: 0,
+            },
+            parent: TcHandle::ROOT,
+            info: 0,
+        },
+        attributes: vec![
+            TcAttribute::Kind("taprio".to_string()),
+            TcAttribute::Options(vec![
+                TcOption::Taprio(TcQdiscTaprioOption::SchedClockid(11)),
+                TcOption::Taprio(TcQdiscTaprioOption::Priomap(priomap)),
+                TcOption::Taprio(TcQdiscTaprioOption::SchedBaseTime(
+                    1000000000,
+                )),
+                TcOption::Taprio(TcQdiscTaprioOption::SchedEntryList(vec![
+                    vec![
+                        TcTaprioSchedEntry::Cmd(TcTaprioCmd::SetGates),
+                        TcTaprioSchedEntry::GateMask(0x01),
+                        TcTaprioSchedEntry::Interval(300000),
+                    ],
+                    vec![
+                        TcTaprioSchedEntry::Cmd(TcTaprioCmd::SetGates),
+                        TcTaprioSchedEntry::GateMask(0x02),
+                        TcTaprioSchedEntry::Interval(200000),
+                    ],
+                ])),
+            ]),
+        ],
+    };
+
+    assert_eq!(
+        expected,
+        TcMessage::parse(&TcMessageBuffer::new(&raw)).unwrap()
+    );
+
+    let mut buf = vec![0; expected.buffer_len()];
+
+    expected.emit(&mut buf);
+
+    assert_eq!(buf, raw);
+}
+
+// Raw packet follows the kernel `taprio_dump_stats()` layout for
+// `tc -s qdisc show dev eth1` of offloaded taprio qdisc with:
+//   * rtnetlink header removed.
+//   * TCA_OPTIONS, TCA_STATS and all TCA_STATS2 attributes except
+//     TCA_STATS_APP removed.
+#[test]
+fn test_get_qdisc_taprio_xstats() {
+    let raw = vec![
+        0x00, // AF_UNSPEC
+        0x00, 0x00, 0x00, // padding
+        0x02, 0x00, 0x00, 0x00, // iface index: 2
+        0x00, 0x00, 0x00, 0x01, // handle 100:0
+        0xff, 0xff, 0xff, 0xff, // parent u32::MAX (TC_H_ROOT)
+        0x01, 0x00, 0x00, 0x00, // info(refcount): 1
+        0x0b, 0x00, // length 11
+        0x01, 0x00, // TCA_KIND
+        0x74, 0x61, 0x70, 0x72, 0x69, 0x6f, 0x00, // "taprio\0"
+        0x00, // padding
+        0x20, 0x00, // length 32
+        0x07, 0x00, // TCA_STATS2
+        0x1c, 0x00, // length 28
+        0x04, 0x80, // TCA_STATS_APP with NLA_F_NESTED
+        0x0c, 0x00, // length 12
+        0x02, 0x00, // TCA_TAPRIO_OFFLOAD_STATS_WINDOW_DROPS
+        0x07, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, // 7
+        0x0c, 0x00, // length 12
+        0x03, 0x00, // TCA_TAPRIO_OFFLOAD_STATS_TX_OVERRUNS
+        0x02, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, // 2
+    ];
+
+    let expected = TcMessage {
+        header: TcHeader {
+            family: AddressFamily::Unspec,
+            index: 2,
+            handle: TcHandle {
+                major: 0x100,
+                minor: 0,
+            },
+            parent: TcHandle::ROOT,
+            info: 1,
+        },
+        attributes: vec![
+            TcAttribute::Kind("taprio".to_string()),
+            TcAttribute::Stats2(vec![TcStats2::App(TcXstats::Taprio(vec![
+                TcTaprioXstats::WindowDrops(7),
+                TcTaprioXstats::TxOverruns(2),
+            ]))]),
+        ],
+    };
+
+    assert_eq!(
+        expected,
+        TcMessage::parse(&TcMessageBuffer::new(&raw)).unwrap()
+    );
+
+    let mut buf = vec![0; expected.buffer_len()];
+
+    expected.emit(&mut buf);
+
+    assert_eq!(buf, raw);
+}
@@ -0,0 +1,206 @@
+// SPDX-License-Identifier: MIT
+
+use netlink_packet_utils::{Emitable, Parseable};
+
+use crate::{
+    tc::{
+        TcAttribute, TcHandle, TcHeader, TcLinkLayer, TcMessage,
+        TcMessageBuffer, TcOption, TcPsched, TcQdiscTbfOption, TcRateSpec,
+        TcStats, TcStats2, TcStatsBasic, TcStatsQueue, TcTbfQopt,
+    },
+    AddressFamily,
+};
+
+// Setup:
+//      tc qdisc add dev lo root handle 1: tbf rate 1mbit burst 32kbit \
+//          latency 50ms peakrate 2mbit mtu 1540
+//
+// Capture nlmon of this command:
+//
+//      tc -s qdisc show dev lo
+//
+// Raw packet modification:
+//   * rtnetlink header removed.
+#[test]
+fn test_get_qdisc_tbf() {
+    let raw = vec![
+        0x00, // AF_UNSPEC
+        0x00, 0x00, 0x00, // padding
+        0x01, 0x00, 0x00, 0x00, // iface index: 1
+        0x00, 0x00, 0x01, 0x00, // handle 1:0
+        0xff, 0xff, 0xff, 0xff, // parent u32::MAX (TC_H_ROOT)
+        0x02, 0x00, 0x00, 0x00, // info(refcount): 2
+        0x08, 0x00, // length 8
+        0x01, 0x00, // TCA_KIND
+        0x74, 0x62, 0x66, 0x00, // "tbf\0"
+        0x2c, 0x00, // length 44
+        0x02, 0x00, // TCA_OPTIONS for `tbf`
+        0x28, 0x00, // length 40
+        0x01, 0x00, // TCA_TBF_PARMS
+        0x00, // rate.cell_log: 0
+        0x01, // rate.linklayer: TC_LINKLAYER_ETHERNET
+        0x00, 0x00, // rate.overhead: 0
+        0x00, 0x00, // rate.cell_align: 0
+        0x00, 0x00, // rate.mpu: 0
+        0x48, 0xe8, 0x01, 0x00, // rate.rate: 125000
+        0x00, // peakrate.cell_log: 0
+        0x01, // peakrate.linklayer: TC_LINKLAYER_ETHERNET
+        0x00, 0x00, // peakrate.overhead: 0
+        0x00, 0x00, // peakrate.cell_align: 0
+        0x00, 0x00, // peakrate.mpu: 0
+        0x90, 0xd0, 0x03, 0x00, // peakrate.rate: 250000
+        0x6a, 0x28, 0x00, 0x00, // limit: 10346
+        0x00, 0xd0, 0x07, 0x00, // buffer: 512000
+        0xfa, 0x77, 0x01, 0x00, // mtu: 96250
+        0x05, 0x00, // length 5
+        0x0c, 0x00, // TCA_HW_OFFLOAD
+        0x00, 0x00, 0x00, 0x00, // 0 with padding
+        0x30, 0x00, // length 48
+        0x07, 0x00, // TCA_STATS2
+        0x14, 0x00, // length 20
+        0x01, 0x00, // TCA_STATS_BASIC
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, // bytes: 0
+        0x00, 0x00, 0x00, 0x00, // packets: 0
+        0x00, 0x00, 0x00, 0x00, // padding
+        0x18, 0x00, // length 24
+        0x03, 0x00, // TCA_STATS_QUEUE
+        0x00, 0x00, 0x00, 0x00, // qlen: 0
+        0x00, 0x00, 0x00, 0x00, // backlog: 0
+        0x00, 0x00, 0x00, 0x00, // drops: 0
+        0x00, 0x00, 0x00, 0x00, // requeues: 0
+        0x00, 0x00, 0x00, 0x00, // overlimits: 0
+        0x2c, 0x00, // length 44
+        0x03, 0x00, // TCA_STATS
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, // bytes: 0
+        0x00, 0x00, 0x00, 0x00, // packets: 0
+        0x00, 0x00, 0x00, 0x00, // drops: 0
+        0x00, 0x00, 0x00, 0x00, // overlimits: 0
+        0x00, 0x00, 0x00, 0x00, // bps: 0
+        0x00, 0x00, 0x00, 0x00, // pps: 0
+        0x00, 0x00, 0x00, 0x00, // qlen: 0
+        0x00, 0x00, 0x00, 0x00, // backlog: 0
+        0x00, 0x00, 0x00, 0x00, // padding
+    ];
+
+    let expected = TcMessage {
+        header: TcHeader {
+            family: AddressFamily::Unspec,
+            index: 1,
+            handle: TcHandle { major: 1, minor: 0 },
+            parent: TcHandle::ROOT,
+            info: 2,
+        },
+        attributes: vec![
+            TcAttribute::Kind("tbf".to_string()),
+            TcAttribute::Options(vec![TcOption::Tbf(TcQdiscTbfOption::Parms(
+                TcTbfQopt {
+                    rate: TcRateSpec {
+                        cell_log: 0,
+                        linklayer: TcLinkLayer::Ethernet,
+                        overhead: 0,
+                        cell_align: 0,
+                        mpu: 0,
+                        rate: 125000,
+                    },
+                    peakrate: TcRateSpec {
+                        cell_log: 0,
+                        linklayer: TcLinkLayer::Ethernet,
+                        overhead: 0,
+                        cell_align: 0,
+                        mpu: 0,
+                        rate: 250000,
+                    },
+                    limit: 10346,
+                    buffer: 512000,
+                    mtu: 96250,
+                },
+            ))]),
+            TcAttribute::HwOffload(0),
+            TcAttribute::Stats2(vec![
+                TcStats2::Basic(TcStatsBasic {
+                    bytes: 0,
+                    packets: 0,
+                }),
+                TcStats2::Queue(TcStatsQueue {
+                    qlen: 0,
+                    backlog: 0,
+                    drops: 0,
+                    requeues: 0,
+                    overlimits: 0,
+                }),
+            ]),
+            TcAttribute::Stats(TcStats {
+                bytes: 0,
+                packets: 0,
+                drops: 0,
+                overlimits: 0,
+                bps: 0,
+                pps: 0,
+                qlen: 0,
+                backlog: 0,
+            }),
+        ],
+    };
+
+    assert_eq!(
+        expected,
+        TcMessage::parse(&TcMessageBuffer::new(&raw)).unwrap()
+    );
+
+    let mut buf = vec![0; expected.buffer_len()];
+
+    expected.emit(&mut buf);
+
+    assert_eq!(buf, raw);
+}
+
+// The TCA_TBF_PARMS, TCA_TBF_RTAB and TCA_TBF_PTAB sent by iproute2 6.1 for
+//
+//      tc qdisc add dev lo root handle 1: tbf rate 1mbit burst 32kbit \
+//          latency 50ms peakrate 2mbit mtu 1540
+//
+// on kernel with `/proc/net/psched` holding
+// `000003e8 00000040 000f4240 3b9aca00`.
+#[test]
+fn test_tbf_psched_calc() {
+    let psched: TcPsched =
+        "000003e8 00000040 000f4240 3b9aca00\n".parse().unwrap();
+    assert_eq!(psched, TcPsched::default());
+
+    let mut rate = TcRateSpec {
+        linklayer: TcLinkLayer::Ethernet,
+        rate: 125000,
+        ..Default::default()
+    };
+    let rtab = psched.calc_rtable(&mut rate, None, 1540);
+    assert_eq!(rate.cell_log, 3);
+    assert_eq!(rate.cell_align, -1);
+    let mut expected_rtab: Vec<u32> = (1..=256).map(|i| i * 1000).collect();
+    // Floating point truncation of iproute2 `tc_calc_xmittime()`
+    expected_rtab[248] = 248984;
+    expected_rtab[250] = 250984;
+    assert_eq!(rtab, expected_rtab);
+
+    let mut peakrate = TcRateSpec {
+        linklayer: TcLinkLayer::Ethernet,
+        rate: 250000,
+        ..Default::default()
+    };
+    let ptab = psched.calc_rtable(&mut peakrate, None, 1540);
+    assert_eq!(peakrate.cell_log, 3);
+    let mut expected_ptab: Vec<u32> = (1..=256).map(|i| i * 500).collect();
+    expected_ptab[248] = 124484;
+    expected_ptab[250] = 125484;
+    assert_eq!(ptab, expected_ptab);
+
+    // burst 32kbit
+    assert_eq!(psched.calc_xmittime(125000, 4096), 512000);
+    assert_eq!(psched.calc_xmitsize(125000, 512000), 4096);
+    // mtu 1540
+    assert_eq!(psched.calc_xmittime(250000, 1540), 96250);
+    // latency 50ms
+    assert_eq!(
+        psched.calc_xmitsize(125000, psched.time2tick(50000)) + 4096,
+        10346
+    );
+}